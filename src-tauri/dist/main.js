@@ -10,10 +10,12 @@ const state = {
   completed: [],
   completedSeq: 0,
   loraMetaRequestSeq: 0,
+  loraPreviewObjectUrl: null,
   currentLoraMetaId: null,
   loraMetaCache: new Map(),
   busyDownloads: 0,
   activeDownloadKind: null,
+  creatorQueue: [],
   comfyInstallBusy: false,
   comfySage3Eligible: false,
   comfyPreflightOk: null,
@@ -21,8 +23,12 @@ const state = {
   comfyRuntimeRunning: false,
   comfyRuntimeStarting: false,
   comfyRuntimeTarget: "",
+  comfyRuntimeInstances: [],
+  comfyGpuCount: 0,
   comfyAttentionBusy: false,
   comfyComponentBusy: false,
+  comfyComponentQueue: [],
+  mutationSessionActive: false,
   comfyMode: "install",
   updateAvailable: false,
   updateVersion: null,
@@ -33,6 +39,8 @@ const state = {
   comfyUpdateAvailable: false,
   comfyUpdateChecked: false,
   comfyUpdateBusy: false,
+  comfyRollback: null,
+  comfyPinnedRef: null,
   comfyLatestVersion: null,
   comfyLastUpdateDetailLogKey: "",
   comfyTorchProfileLocked: false,
@@ -40,8 +48,32 @@ const state = {
   comfyTorchRecommendedBase: "Recommended 'Torch 2.8.0 + cu128' for your GPU",
   sharedModelsRootDefault: "",
   sharedModelsUseDefault: false,
+  remoteAgentUrl: "",
+  remoteEventSeq: 0,
+  operationTargets: { install: "local", runtime: "local", download: "local" },
 };
 
+// Commands that follow the per-area target picker when a remote agent is paired.
+const REMOTE_OPERATIONS = {
+  install: [
+    "run_comfyui_preflight",
+    "plan_comfyui_install",
+    "start_comfyui_install",
+    "resume_comfyui_install",
+    "cancel_comfyui_install",
+    "get_install_log_tail",
+  ],
+  runtime: ["get_comfyui_runtime_status", "start_comfyui_root", "stop_comfyui_root", "get_comfyui_log_tail"],
+  download: [
+    "download_model_assets",
+    "download_lora_asset",
+    "download_workflow_asset",
+    "cancel_active_download",
+    "resolve_superseded_artifacts",
+  ],
+};
+const appEventHandlers = new Map();
+
 let progressSmoothTimer = null;
 
 const ramOptions = [
@@ -61,6 +93,10 @@ const comfyTorchProfiles = [
   { value: "torch271_cu128", label: "Torch 2.7.1 + cu128" },
   { value: "torch280_cu128", label: "Torch 2.8.0 + cu128" },
   { value: "torch291_cu130", label: "Torch 2.9.1 + cu130" },
+  { value: "torch280_rocm64", label: "Torch 2.8.0 + ROCm 6.4 (AMD)" },
+  { value: "torch291_rocm64", label: "Torch 2.9.1 + ROCm 6.4 (AMD)" },
+  { value: "torch_cpu", label: "Torch 2.8.0 CPU only (no GPU)" },
+  { value: "torch_nightly_cu130", label: "Torch nightly + cu130 (pre-release)" },
 ];
 
 const el = {
@@ -68,6 +104,7 @@ const el = {
   updateStatus: document.getElementById("update-status"),
   statusLog: document.getElementById("status-log"),
   clearStatusLog: document.getElementById("clear-status-log"),
+  hostStatsList: document.getElementById("host-stats-list"),
   progressLine: document.getElementById("download-progress"),
   overallProgress: document.getElementById("overall-progress"),
   overallProgressFill: document.getElementById("overall-progress-fill"),
@@ -81,10 +118,12 @@ const el = {
   tabModels: document.getElementById("tab-models"),
   tabLoras: document.getElementById("tab-loras"),
   tabWorkflows: document.getElementById("tab-workflows"),
+  tabAssets: document.getElementById("tab-assets"),
   contentComfyui: document.getElementById("tab-content-comfyui"),
   contentModels: document.getElementById("tab-content-models"),
   contentLoras: document.getElementById("tab-content-loras"),
   contentWorkflows: document.getElementById("tab-content-workflows"),
+  contentAssets: document.getElementById("tab-content-assets"),
   downloadsStatusPanel: document.getElementById("downloads-status-panel"),
 
   comfyTorchProfile: document.getElementById("comfy-torch-profile"),
@@ -93,6 +132,42 @@ const el = {
   comfyModeHelp: document.getElementById("comfy-mode-help"),
   comfyExistingInstall: document.getElementById("comfy-existing-install"),
   updateSelectedInstall: document.getElementById("update-selected-install"),
+  rollbackSelectedInstall: document.getElementById("rollback-selected-install"),
+  unpinSelectedInstall: document.getElementById("unpin-selected-install"),
+  repairSelectedInstall: document.getElementById("repair-selected-install"),
+  rebuildSelectedVenv: document.getElementById("rebuild-selected-venv"),
+  duplicateSelectedInstall: document.getElementById("duplicate-selected-install"),
+  relocateSelectedInstall: document.getElementById("relocate-selected-install"),
+  diffSelectedInstall: document.getElementById("diff-selected-install"),
+  exportInstallReport: document.getElementById("export-install-report"),
+  applyComfySettingsTemplate: document.getElementById("apply-comfy-settings-template"),
+  cancelComponentToggle: document.getElementById("cancel-component-toggle"),
+  mutationSession: document.getElementById("mutation-session"),
+  customNodeUrlRow: document.getElementById("custom-node-url-row"),
+  customNodeUrl: document.getElementById("custom-node-url"),
+  installCustomNodeUrl: document.getElementById("install-custom-node-url"),
+  updateAllCustomNodes: document.getElementById("update-all-custom-nodes"),
+  envSnapshotRow: document.getElementById("env-snapshot-row"),
+  envSnapshotSelect: document.getElementById("env-snapshot-select"),
+  refreshEnvSnapshots: document.getElementById("refresh-env-snapshots"),
+  restoreEnvSnapshot: document.getElementById("restore-env-snapshot"),
+  modelWarmupRow: document.getElementById("model-warmup-row"),
+  launchEnvRow: document.getElementById("launch-env-row"),
+  gpuSelectRow: document.getElementById("gpu-select-row"),
+  comfyGpuSelect: document.getElementById("comfy-gpu-select"),
+  vramModeRow: document.getElementById("vram-mode-row"),
+  comfyVramMode: document.getElementById("comfy-vram-mode"),
+  comfyCacheNone: document.getElementById("comfy-cache-none"),
+  managerSecurityLevel: document.getElementById("manager-security-level"),
+  managerNetworkMode: document.getElementById("manager-network-mode"),
+  managerChannelUrl: document.getElementById("manager-channel-url"),
+  saveManagerConfig: document.getElementById("save-manager-config"),
+  launchEnvVars: document.getElementById("launch-env-vars"),
+  saveLaunchEnv: document.getElementById("save-launch-env"),
+  comfyAutoRestart: document.getElementById("comfy-auto-restart"),
+  comfyAutoRestartAttempts: document.getElementById("comfy-auto-restart-attempts"),
+  warmupModelFile: document.getElementById("warmup-model-file"),
+  warmModelCache: document.getElementById("warm-model-cache"),
   useExistingInstall: document.getElementById("use-existing-install"),
   comfyInstallRoot: document.getElementById("comfy-install-root"),
   chooseInstallRoot: document.getElementById("choose-install-root"),
@@ -100,30 +175,76 @@ const el = {
   comfyExtraModelRow: document.getElementById("comfy-extra-model-row"),
   comfyExtraModelRoot: document.getElementById("comfy-extra-model-root"),
   chooseExtraModelRoot: document.getElementById("choose-extra-model-root"),
+  comfyOutputDir: document.getElementById("comfy-output-dir"),
+  comfyInputDir: document.getElementById("comfy-input-dir"),
+  comfyUserDir: document.getElementById("comfy-user-dir"),
+  comfyRepoUrl: document.getElementById("comfy-repo-url"),
+  comfyRef: document.getElementById("comfy-ref"),
+  comfyFrontendVersion: document.getElementById("comfy-frontend-version"),
+  pinComfyFrontend: document.getElementById("pin-comfy-frontend"),
+  comfyOfflineCache: document.getElementById("comfy-offline-cache"),
+  buildOfflineCache: document.getElementById("build-offline-cache"),
+  comfyOfflineInstall: document.getElementById("comfy-offline-install"),
   comfyExtraModelDefault: document.getElementById("comfy-extra-model-default"),
   clearExtraModelRoot: document.getElementById("clear-extra-model-root"),
+  syncSharedModels: document.getElementById("sync-shared-models"),
+  diagnoseModelsFolders: document.getElementById("diagnose-models-folders"),
+  migrateSourceRoot: document.getElementById("migrate-source-root"),
+  migrateDestRoot: document.getElementById("migrate-dest-root"),
+  migrateMode: document.getElementById("migrate-mode"),
+  migrateCollision: document.getElementById("migrate-collision"),
+  migrateModels: document.getElementById("migrate-models"),
   comfyResumeBanner: document.getElementById("comfy-resume-banner"),
   comfyResumeText: document.getElementById("comfy-resume-text"),
   comfyResumeBtn: document.getElementById("comfy-resume-btn"),
+  remoteAgentHost: document.getElementById("remote-agent-host"),
+  remoteAgentToken: document.getElementById("remote-agent-token"),
+  pairRemoteAgent: document.getElementById("pair-remote-agent"),
+  unpairRemoteAgent: document.getElementById("unpair-remote-agent"),
+  remoteAgentStatus: document.getElementById("remote-agent-status"),
+  operationTargets: document.querySelectorAll(".agent-target-row select"),
   comfyFreshBtn: document.getElementById("comfy-fresh-btn"),
   installComfyui: document.getElementById("install-comfyui"),
   comfyInstallSpinner: document.getElementById("comfy-install-spinner"),
   comfyQuickActions: document.getElementById("comfy-quick-actions"),
   comfyLastInstallPath: document.getElementById("comfy-last-install-path"),
   comfyOpenInstallFolder: document.getElementById("comfy-open-install-folder"),
+  comfyOpenVenvShell: document.getElementById("comfy-open-venv-shell"),
+  comfyLoginService: document.getElementById("comfy-login-service"),
+  comfyDesktopEntry: document.getElementById("comfy-desktop-entry"),
   comfyStartInstalled: document.getElementById("comfy-start-installed"),
   comfyInstallLog: document.getElementById("comfy-install-log"),
+  comfyInstallProgress: document.getElementById("comfy-install-progress"),
+  comfyInstallProgressFill: document.getElementById("comfy-install-progress-fill"),
+  comfyInstallProgressMeta: document.getElementById("comfy-install-progress-meta"),
   comfyClearInstallLog: document.getElementById("comfy-clear-install-log"),
+  comfyInstallConsoleWrap: document.getElementById("comfy-install-console-wrap"),
+  comfyInstallConsole: document.getElementById("comfy-install-console"),
+  comfyRuntimeConsoleWrap: document.getElementById("comfy-runtime-console-wrap"),
+  comfyRuntimeConsole: document.getElementById("comfy-runtime-console"),
   runPreflight: document.getElementById("run-preflight"),
+  reviewInstallPlan: document.getElementById("review-install-plan"),
+  checkRuntimeCache: document.getElementById("check-runtime-cache"),
+  cleanRuntimeCache: document.getElementById("clean-runtime-cache"),
+  runtimeCacheSummary: document.getElementById("runtime-cache-summary"),
   preflightSummary: document.getElementById("preflight-summary"),
   preflightList: document.getElementById("preflight-list"),
+  showInstallReport: document.getElementById("show-install-report"),
+  installReportSummary: document.getElementById("install-report-summary"),
+  installReportList: document.getElementById("install-report-list"),
   addonSageAttention: document.getElementById("addon-sageattention"),
   addonSageAttention3: document.getElementById("addon-sageattention3"),
   addonFlashAttention: document.getElementById("addon-flashattention"),
   addonInsightFace: document.getElementById("addon-insightface"),
+  addonXformers: document.getElementById("addon-xformers"),
+  comfyInstallBackend: document.getElementById("comfy-install-backend"),
+  comfyServerMode: document.getElementById("comfy-server-mode"),
+  comfyServerPort: document.getElementById("comfy-server-port"),
+  comfyServerAuth: document.getElementById("comfy-server-auth"),
   addonNunchaku: document.getElementById("addon-nunchaku"),
   addonTrellis2: document.getElementById("addon-trellis2"),
   addonPinnedMemory: document.getElementById("addon-pinned-memory"),
+  addonAttentionActive: document.getElementById("addon-attention-active"),
   nodeComfyuiManager: document.getElementById("node-comfyui-manager"),
   nodeComfyuiEasyUse: document.getElementById("node-comfyui-easy-use"),
   nodeRgthreeComfy: document.getElementById("node-rgthree-comfy"),
@@ -146,17 +267,32 @@ const el = {
   vramTier: document.getElementById("vram-tier"),
   ramTier: document.getElementById("ram-tier"),
   variantId: document.getElementById("variant-id"),
+  modelArtifactPicker: document.getElementById("model-artifact-picker"),
+  modelArtifactOptions: document.getElementById("model-artifact-options"),
   downloadModel: document.getElementById("download-model"),
   enableHfXet: document.getElementById("enable-hf-xet"),
+  civitaiDownloadExamples: document.getElementById("civitai-download-examples"),
 
   loraFamily: document.getElementById("lora-family"),
   loraId: document.getElementById("lora-id"),
+  toggleLoraFavorite: document.getElementById("toggle-lora-favorite"),
   civitaiToken: document.getElementById("civitai-token"),
   saveToken: document.getElementById("save-token"),
   downloadLora: document.getElementById("download-lora"),
+  followCreatorName: document.getElementById("follow-creator-name"),
+  followCreator: document.getElementById("follow-creator"),
+  checkFollowedCreators: document.getElementById("check-followed-creators"),
+  clearCreatorUpdates: document.getElementById("clear-creator-updates"),
+  followedCreatorList: document.getElementById("followed-creator-list"),
+  creatorUpdateList: document.getElementById("creator-update-list"),
   workflowFamily: document.getElementById("workflow-family"),
   workflowId: document.getElementById("workflow-id"),
   downloadWorkflow: document.getElementById("download-workflow"),
+  assetClass: document.getElementById("asset-class"),
+  assetId: document.getElementById("asset-id"),
+  downloadAsset: document.getElementById("download-asset"),
+  assetDestination: document.getElementById("asset-destination"),
+  assetNote: document.getElementById("asset-note"),
 
   metaCreator: document.getElementById("meta-creator"),
   metaCreatorLink: document.getElementById("meta-creator-link"),
@@ -196,6 +332,67 @@ function logComfyLine(text) {
   el.comfyInstallLog.textContent = `[${stamp}] ${text}\n` + el.comfyInstallLog.textContent;
 }
 
+const INSTALL_CONSOLE_MAX_LINES = 2000;
+
+function appendInstallConsole(lines) {
+  if (!el.comfyInstallConsole || !lines.length) return;
+  const existing = el.comfyInstallConsole.textContent ? el.comfyInstallConsole.textContent.split("\n") : [];
+  const merged = existing.concat(lines).slice(-INSTALL_CONSOLE_MAX_LINES);
+  el.comfyInstallConsole.textContent = merged.join("\n");
+  el.comfyInstallConsole.scrollTop = el.comfyInstallConsole.scrollHeight;
+}
+
+async function loadInstallConsoleTail() {
+  const installRoot =
+    String(el.comfyExistingInstall?.value || "").trim() || state.comfyResumeState?.install_dir || "";
+  if (!installRoot || !el.comfyInstallConsole || el.comfyInstallConsole.textContent) return;
+  try {
+    const lines = await invokeTarget("get_install_log_tail", { installRoot, lines: 500 });
+    appendInstallConsole(lines || []);
+  } catch (err) {
+    logComfyLine(`Could not read install.log: ${err}`);
+  }
+}
+
+function appendRuntimeConsole(lines) {
+  if (!el.comfyRuntimeConsole || !lines.length) return;
+  const existing = el.comfyRuntimeConsole.textContent ? el.comfyRuntimeConsole.textContent.split("\n") : [];
+  const merged = existing.concat(lines).slice(-INSTALL_CONSOLE_MAX_LINES);
+  el.comfyRuntimeConsole.textContent = merged.join("\n");
+  el.comfyRuntimeConsole.scrollTop = el.comfyRuntimeConsole.scrollHeight;
+}
+
+async function loadRuntimeConsoleTail() {
+  const comfyuiRoot = runtimeRootForUi();
+  if (!comfyuiRoot || !el.comfyRuntimeConsole) return;
+  try {
+    const lines = await invokeTarget("get_comfyui_log_tail", { comfyuiRoot, lines: 500 });
+    el.comfyRuntimeConsole.textContent = "";
+    appendRuntimeConsole(lines || []);
+  } catch (err) {
+    logComfyLine(`Could not read the ComfyUI output log: ${err}`);
+  }
+}
+
+function resetComfyInstallProgress() {
+  el.comfyInstallProgress?.classList.add("hidden");
+  el.comfyInstallProgressMeta?.classList.add("hidden");
+  if (el.comfyInstallProgressFill) el.comfyInstallProgressFill.style.width = "0%";
+}
+
+function updateComfyInstallProgress(p) {
+  if (!el.comfyInstallProgress || !el.comfyInstallProgressFill) return;
+  const percent = p.phase === "finished" ? 100 : p.percent;
+  if (typeof percent !== "number") return;
+  el.comfyInstallProgress.classList.remove("hidden");
+  el.comfyInstallProgressFill.style.width = `${percent}%`;
+  if (el.comfyInstallProgressMeta) {
+    const stepText = p.step_index && p.total_steps ? `Step ${p.step_index} of ${p.total_steps}${DOT_SEP}` : "";
+    el.comfyInstallProgressMeta.textContent = `${stepText}${percent}%`;
+    el.comfyInstallProgressMeta.classList.remove("hidden");
+  }
+}
+
 function setStartupStatus(text) {
   if (!el.startupStatus) return;
   el.startupStatus.textContent = String(text || "Preparing workspace...");
@@ -363,6 +560,17 @@ function updateComfyUpdateButton() {
   const btn = el.updateSelectedInstall;
   if (!btn) return;
   const hasSelection = Boolean(String(el.comfyExistingInstall?.value || "").trim());
+  if (el.rollbackSelectedInstall) {
+    el.rollbackSelectedInstall.classList.toggle("hidden", !hasSelection || !state.comfyRollback);
+    el.rollbackSelectedInstall.disabled = state.comfyUpdateBusy;
+  }
+  if (el.unpinSelectedInstall) {
+    el.unpinSelectedInstall.classList.toggle("hidden", !hasSelection || !state.comfyPinnedRef);
+    el.unpinSelectedInstall.disabled = state.comfyUpdateBusy;
+    el.unpinSelectedInstall.title = state.comfyPinnedRef
+      ? `Pinned to ${state.comfyPinnedRef}. Unpin so updates follow ComfyUI release tags.`
+      : "";
+  }
   btn.classList.toggle("hidden", !hasSelection);
   btn.classList.remove("update-available");
   if (!hasSelection) return;
@@ -478,6 +686,9 @@ function setComfyQuickActions(installDir, comfyRoot) {
   if (el.comfyOpenInstallFolder) {
     el.comfyOpenInstallFolder.dataset.path = finalRoot;
   }
+  if (el.comfyOpenVenvShell) {
+    el.comfyOpenVenvShell.dataset.path = finalRoot;
+  }
   if (el.comfyStartInstalled) {
     el.comfyStartInstalled.dataset.path = finalRoot;
   }
@@ -495,6 +706,8 @@ function buildComfyInstallRequest() {
     includeFlashAttention: Boolean(el.addonFlashAttention.checked),
     includeInsightFace: Boolean(el.addonInsightFace.checked),
     includeNunchaku: Boolean(el.addonNunchaku.checked),
+    attentionBackend: el.addonAttentionActive?.value || null,
+    includeXformers: Boolean(el.addonXformers?.checked),
     includeTrellis2: Boolean(el.addonTrellis2?.checked),
     includePinnedMemory: Boolean(el.addonPinnedMemory?.checked ?? true),
     nodeComfyuiManager: Boolean(el.nodeComfyuiManager.checked),
@@ -503,6 +716,23 @@ function buildComfyInstallRequest() {
     nodeComfyuiGguf: Boolean(el.nodeComfyuiGguf.checked),
     nodeComfyuiKjnodes: Boolean(el.nodeComfyuiKjnodes.checked),
     nodeComfyuiCrystools: Boolean(el.nodeComfyuiCrystools?.checked),
+    outputDir: String(el.comfyOutputDir?.value || "").trim() || null,
+    inputDir: String(el.comfyInputDir?.value || "").trim() || null,
+    userDir: String(el.comfyUserDir?.value || "").trim() || null,
+    comfyuiRepoUrl: String(el.comfyRepoUrl?.value || "").trim() || null,
+    comfyuiRef: String(el.comfyRef?.value || "").trim() || null,
+    frontendVersion: String(el.comfyFrontendVersion?.value || "").trim() || null,
+    offlineCache: el.comfyOfflineInstall?.checked
+      ? String(el.comfyOfflineCache?.value || "").trim() || null
+      : null,
+    containerEngine: el.comfyInstallBackend?.value || null,
+    manager: el.nodeComfyuiManager.checked ? managerOptionsFromForm() : null,
+    server: el.comfyServerMode?.checked
+      ? {
+        port: Number(el.comfyServerPort?.value) || 8188,
+        auth: Boolean(el.comfyServerAuth?.checked),
+      }
+      : null,
   };
 }
 
@@ -512,8 +742,10 @@ function resetComfySelectionsToDefaults() {
   if (el.addonFlashAttention) el.addonFlashAttention.checked = false;
   if (el.addonNunchaku) el.addonNunchaku.checked = false;
   if (el.addonInsightFace) el.addonInsightFace.checked = false;
+  if (el.addonXformers) el.addonXformers.checked = false;
   if (el.addonTrellis2) el.addonTrellis2.checked = false;
   if (el.addonPinnedMemory) el.addonPinnedMemory.checked = true;
+  if (el.addonAttentionActive) el.addonAttentionActive.value = "none";
 
   if (el.nodeComfyuiManager) el.nodeComfyuiManager.checked = false;
   if (el.nodeComfyuiEasyUse) el.nodeComfyuiEasyUse.checked = false;
@@ -528,6 +760,12 @@ async function loadInstalledAddonState(comfyuiRoot) {
   const root = String(comfyuiRoot || el.comfyRoot.value || "").trim();
   if (!root) return;
   const loadSeq = ++state.comfyAddonLoadSeq;
+  loadEnvSnapshots(root).catch(() => {});
+  refreshMutationSession(root).catch(() => {});
+  loadLaunchEnvVars(root).catch(() => {});
+  loadGpuSelection(root).catch(() => {});
+  loadVramMode(root).catch(() => {});
+  loadManagerConfig(root).catch(() => {});
   try {
     const installed = await invoke("get_comfyui_addon_state", { comfyuiRoot: root });
     if (loadSeq !== state.comfyAddonLoadSeq) return;
@@ -545,7 +783,10 @@ async function loadInstalledAddonState(comfyuiRoot) {
     if (el.addonFlashAttention) el.addonFlashAttention.checked = Boolean(installed?.flash_attention);
     if (el.addonNunchaku) el.addonNunchaku.checked = Boolean(installed?.nunchaku);
     if (el.addonInsightFace) el.addonInsightFace.checked = Boolean(installed?.insight_face);
+    if (el.addonXformers) el.addonXformers.checked = Boolean(installed?.xformers);
     if (el.addonTrellis2) el.addonTrellis2.checked = Boolean(installed?.trellis2);
+    if (el.addonPinnedMemory) el.addonPinnedMemory.checked = installed?.pinned_memory !== false;
+    if (el.addonAttentionActive) el.addonAttentionActive.value = installed?.attention_backend || "none";
 
     if (el.nodeComfyuiManager) el.nodeComfyuiManager.checked = Boolean(installed?.node_comfyui_manager);
     if (el.nodeComfyuiEasyUse) el.nodeComfyuiEasyUse.checked = Boolean(installed?.node_comfyui_easy_use);
@@ -584,6 +825,10 @@ function updateComfyRuntimeButton() {
   }
   el.comfyStartInstalled.textContent = running ? "Stop ComfyUI" : "Start ComfyUI";
   el.comfyStartInstalled.disabled = false;
+  const others = (state.comfyRuntimeInstances || [])
+    .filter((item) => normalizeSlashes(item.root) !== normalizeSlashes(runtimeRootForUi()))
+    .map((item) => `${item.name} (port ${item.port})`);
+  el.comfyStartInstalled.title = others.length ? `Also running: ${others.join(", ")}` : "";
   el.comfyStartInstalled.classList.toggle("stop-state", running);
   el.comfyStartInstalled.classList.remove("starting-state");
 }
@@ -605,17 +850,30 @@ function checkedAttentionEntries(exceptBox = null) {
   return attentionAddonEntries().filter((entry) => entry.box !== exceptBox && entry.box.checked);
 }
 
-function enforceExclusiveAttentionSelectionLocal(changedBox) {
-  if (!changedBox?.checked) return;
-  checkedAttentionEntries(changedBox).forEach((entry) => {
-    entry.box.checked = false;
+function syncActiveAttentionOptions() {
+  const select = el.addonAttentionActive;
+  if (!select) return;
+  const checked = checkedAttentionEntries().map((entry) => entry.backend);
+  Array.from(select.options).forEach((option) => {
+    option.disabled = option.value !== "none" && !checked.includes(option.value);
   });
+  if (select.selectedOptions[0]?.disabled) {
+    select.value = checked[0] || "none";
+  }
+}
+
+function compatReasonOf(err) {
+  const match = /^\[([a-z_]+)\]/.exec(String(err || ""));
+  return match ? match[1] : null;
 }
 
 async function applyAttentionBackendFromToggle(changedBox) {
   if (!changedBox) return;
   if (state.comfyMode !== "manage") {
-    enforceExclusiveAttentionSelectionLocal(changedBox);
+    if (changedBox.checked && el.addonAttentionActive?.value === "none") {
+      el.addonAttentionActive.value = attentionEntryForBox(changedBox)?.backend || "none";
+    }
+    syncActiveAttentionOptions();
     return;
   }
   if (state.comfyAttentionBusy) return;
@@ -629,20 +887,15 @@ async function applyAttentionBackendFromToggle(changedBox) {
 
   const changed = attentionEntryForBox(changedBox);
   if (!changed) return;
+  const installing = Boolean(changedBox.checked);
   const others = checkedAttentionEntries(changedBox);
-  let targetBackend = "none";
-  let confirmMessage = "";
-
-  if (changedBox.checked) {
-    targetBackend = changed.backend;
-    if (others.length > 0) {
-      confirmMessage = `Are you sure you want to install '${changed.label}'?\nInstalling '${changed.label}' will automatically remove '${others[0].label}'.`;
-    }
-  } else {
-    confirmMessage = `Are you sure you want to remove '${changed.label}'?`;
-  }
+  const confirmMessage = installing
+    ? others.length > 0
+      ? `Are you sure you want to install '${changed.label}'?\nIt is installed next to ${others.map((entry) => `'${entry.label}'`).join(", ")} and becomes the backend ComfyUI launches with.`
+      : `Are you sure you want to install '${changed.label}'?`
+    : `Are you sure you want to remove '${changed.label}'?`;
 
-  if (confirmMessage && !(await showConfirmDialog(confirmMessage))) {
+  if (!(await showConfirmDialog(confirmMessage))) {
     changedBox.checked = !changedBox.checked;
     return;
   }
@@ -652,13 +905,25 @@ async function applyAttentionBackendFromToggle(changedBox) {
   updateComfyRuntimeButton();
   setToggleBusy(changedBox, true);
   try {
-    const result = await invoke("apply_attention_backend_change", {
-      request: {
-        comfyuiRoot: root,
-        targetBackend,
-        torchProfile: el.comfyTorchProfile?.value || null,
-      },
-    });
+    const request = {
+      comfyuiRoot: root,
+      targetBackend: changed.backend,
+      torchProfile: el.comfyTorchProfile?.value || null,
+      keepOthers: true,
+    };
+    let result;
+    try {
+      result = installing
+        ? await invoke("apply_attention_backend_change", { request })
+        : await invoke("remove_attention_backend", { comfyuiRoot: root, backend: changed.backend });
+    } catch (err) {
+      if (!compatReasonOf(err)) throw err;
+      const ok = await showConfirmDialog(`${String(err).replace(/^\[[a-z_]+\]\s*/, "")}\n\nInstall '${changed.label}' anyway?`);
+      if (!ok) throw err;
+      result = await invoke("apply_attention_backend_change", {
+        request: { ...request, allowIncompatible: true },
+      });
+    }
     if (result) {
       logComfyLine(String(result));
     }
@@ -673,8 +938,25 @@ async function applyAttentionBackendFromToggle(changedBox) {
   }
 }
 
+async function selectActiveAttentionBackend() {
+  syncActiveAttentionOptions();
+  if (state.comfyMode !== "manage") return;
+  const root = String(el.comfyRoot.value || "").trim();
+  if (!root) return;
+  try {
+    const result = await invoke("select_attention_backend", {
+      comfyuiRoot: root,
+      backend: el.addonAttentionActive.value,
+    });
+    logComfyLine(String(result));
+  } catch (err) {
+    logComfyLine(`Selecting attention backend failed: ${err}`);
+    await loadInstalledAddonState(root);
+  }
+}
+
 async function applyComponentToggleFromCheckbox(changedBox, component, label) {
-  if (!changedBox || state.comfyComponentBusy) return;
+  if (!changedBox) return;
   if (state.comfyMode !== "manage") {
     return;
   }
@@ -692,11 +974,19 @@ async function applyComponentToggleFromCheckbox(changedBox, component, label) {
     changedBox.checked = !changedBox.checked;
     return;
   }
+  if (state.comfyComponentBusy) {
+    state.comfyComponentQueue = state.comfyComponentQueue.filter((entry) => entry.component !== component);
+    state.comfyComponentQueue.push({ box: changedBox, component, label, enabled: enabling });
+    setToggleBusy(changedBox, true);
+    logComfyLine(`Queued: ${action} '${label}' (runs after the current change).`);
+    return;
+  }
 
   await waitForNextPaint();
   state.comfyComponentBusy = true;
   updateComfyRuntimeButton();
   setToggleBusy(changedBox, true);
+  el.cancelComponentToggle?.classList.remove("hidden");
   try {
     const result = await invoke("apply_comfyui_component_toggle", {
       request: {
@@ -713,21 +1003,56 @@ async function applyComponentToggleFromCheckbox(changedBox, component, label) {
     logComfyLine(`Component change failed: ${err}`);
   } finally {
     await loadInstalledAddonState(root);
-    if (component === "addon_pinned_memory" && el.addonPinnedMemory) {
-      try {
-        const settings = await invoke("get_settings");
-        el.addonPinnedMemory.checked = settings?.comfyui_pinned_memory_enabled !== false;
-      } catch (_) {}
-    }
+    el.cancelComponentToggle?.classList.add("hidden");
     state.comfyComponentBusy = false;
     updateComfyRuntimeButton();
     setToggleBusy(changedBox, false);
   }
+  await flushComponentToggleQueue(root);
+}
+
+async function flushComponentToggleQueue(root) {
+  const queued = state.comfyComponentQueue.splice(0);
+  if (!queued.length) return;
+  state.comfyComponentBusy = true;
+  updateComfyRuntimeButton();
+  el.cancelComponentToggle?.classList.remove("hidden");
+  logComfyLine(`Applying ${queued.length} queued component change(s)...`);
+  try {
+    const items = await invoke("apply_comfyui_component_toggles", {
+      request: {
+        comfyuiRoot: root,
+        changes: queued.map(({ component, enabled }) => ({ component, enabled })),
+      },
+    });
+    for (const item of items || []) {
+      logComfyLine(`  ${item.name}: ${item.status}${DOT_SEP}${item.detail}`);
+    }
+  } catch (err) {
+    logComfyLine(`Queued component changes failed: ${err}`);
+  } finally {
+    await loadInstalledAddonState(root);
+    el.cancelComponentToggle?.classList.add("hidden");
+    state.comfyComponentBusy = false;
+    updateComfyRuntimeButton();
+    for (const entry of queued) {
+      setToggleBusy(entry.box, false);
+    }
+  }
+  await flushComponentToggleQueue(root);
 }
 
 let runtimeStatusPollTimer = null;
 let runtimeStatusPollInFlight = false;
 
+// Install the runtime button starts and stops; other instances keep running on their own ports.
+function runtimeRootForUi() {
+  const preferredManageRoot = state.comfyMode === "manage"
+    ? String(el.comfyExistingInstall?.value || "").trim()
+    : "";
+  return String(preferredManageRoot || el.comfyStartInstalled?.dataset.path || "").trim();
+}
+
 async function refreshComfyRuntimeStatus() {
   if (runtimeStatusPollInFlight || !invoke) return;
 
@@ -739,8 +1064,9 @@ async function refreshComfyRuntimeStatus() {
   runtimeStatusPollInFlight = true;
   const wasStarting = Boolean(state.comfyRuntimeStarting);
   try {
-    const result = await invoke("get_comfyui_runtime_status");
+    const result = await invokeTarget("get_comfyui_runtime_status", { comfyuiRoot: runtimeRootForUi() || null });
     state.comfyRuntimeRunning = Boolean(result?.running);
+    state.comfyRuntimeInstances = Array.isArray(result?.instances) ? result.instances : [];
   } catch (_) {
     state.comfyRuntimeRunning = false;
   } finally {
@@ -770,11 +1096,12 @@ function scheduleRuntimeStatusPoll(delayMs = null) {
 
 async function openComfyWhenReady(timeoutMs = 45000) {
   const startedAt = Date.now();
+  const comfyuiRoot = runtimeRootForUi() || null;
   while ((Date.now() - startedAt) < timeoutMs) {
     try {
-      const status = await invoke("get_comfyui_runtime_status");
+      const status = await invokeTarget("get_comfyui_runtime_status", { comfyuiRoot });
       if (status?.running) {
-        await invoke("open_external_url", { url: "http://127.0.0.1:8188" });
+        await invoke("open_external_url", { url: `http://127.0.0.1:${status.port || 8188}` });
         return true;
       }
     } catch (_) {}
@@ -790,7 +1117,18 @@ function updateComfyModeUi() {
   el.installComfyui?.classList.toggle("hidden", !installMode);
   el.comfyResumeBanner?.classList.toggle("hidden", !installMode || !state.comfyResumeState?.found);
   el.comfyOpenInstallFolder?.classList.toggle("hidden", !canShowManageActions);
+  el.comfyOpenVenvShell?.classList.toggle("hidden", !canShowManageActions);
   el.comfyStartInstalled?.classList.toggle("hidden", !canShowManageActions);
+  el.comfyLoginService?.classList.toggle("hidden", !canShowManageActions);
+  el.comfyDesktopEntry?.classList.toggle("hidden", !canShowManageActions);
+  el.customNodeUrlRow?.classList.toggle("hidden", !canShowManageActions);
+  el.envSnapshotRow?.classList.toggle("hidden", !canShowManageActions);
+  el.modelWarmupRow?.classList.toggle("hidden", !canShowManageActions);
+  el.launchEnvRow?.classList.toggle("hidden", !canShowManageActions);
+  el.gpuSelectRow?.classList.toggle("hidden", !canShowManageActions || state.comfyGpuCount < 2);
+  el.vramModeRow?.classList.toggle("hidden", !canShowManageActions);
+  el.saveManagerConfig?.classList.toggle("hidden", !canShowManageActions);
+  el.pinComfyFrontend?.classList.toggle("hidden", !canShowManageActions);
   updateComfyUpdateButton();
   if (el.comfyModeHelp) {
     el.comfyModeHelp.textContent = installMode
@@ -879,6 +1217,9 @@ async function refreshExistingInstallations(basePath, preferredRoot = null) {
     if (el.comfyOpenInstallFolder) {
       el.comfyOpenInstallFolder.dataset.path = "";
     }
+    if (el.comfyOpenVenvShell) {
+      el.comfyOpenVenvShell.dataset.path = "";
+    }
     if (el.comfyRoot) el.comfyRoot.value = "";
     if (el.comfyRootLora) el.comfyRootLora.value = "";
     invoke("set_comfyui_root", { comfyuiRoot: "" }).catch(() => {});
@@ -937,14 +1278,18 @@ async function refreshComfyUiUpdateStatus(rootPath = null) {
   state.comfyUpdateAvailable = false;
   state.comfyLatestVersion = null;
   state.selectedComfyVersion = null;
+  state.comfyRollback = null;
+  state.comfyPinnedRef = null;
   updateComfyUpdateButton();
   renderTitleMeta();
   if (!root) return;
   try {
+    state.comfyRollback = await invoke("get_comfyui_rollback", { comfyuiRoot: root }).catch(() => null);
     const status = await invoke("get_comfyui_update_status", { comfyuiRoot: root });
     state.comfyUpdateChecked = Boolean(status?.checked);
     state.comfyUpdateAvailable = Boolean(status?.update_available);
     state.comfyLatestVersion = status?.latest_version || null;
+    state.comfyPinnedRef = status?.pinned_ref || null;
     const detailTextRaw = String(status?.detail || "");
     const headMatchesTag = Boolean(status?.head_matches_latest_tag);
     state.selectedComfyVersion = headMatchesTag
@@ -1014,7 +1359,7 @@ async function syncComfyInstallSelection(
       }
       await refreshComfyRuntimeStatus();
       if (emitDetectionLog && state.comfyRuntimeRunning) {
-        logComfyLine("Detected running ComfyUI server. If you want to start a different one, stop this server first.");
+        logComfyLine("Detected running ComfyUI server for this install.");
       }
       return;
     }
@@ -1092,10 +1437,66 @@ function renderPreflight(result) {
   el.preflightSummary.textContent = result?.summary || (state.comfyPreflightOk ? "Preflight passed." : "Preflight has issues.");
 }
 
+const installReportStatus = { ok: "pass", failed: "fail", skipped: "warn" };
+
+function installReportRow(item, root) {
+  const status = installReportStatus[item.status] || "warn";
+  const row = document.createElement("div");
+  row.className = `preflight-item ${status}`;
+  const badge = document.createElement("div");
+  badge.className = "status";
+  badge.textContent = String(item.status || "unknown").toUpperCase();
+  const text = document.createElement("div");
+  text.textContent = `${item.name}: ${item.detail}`;
+  row.appendChild(badge);
+  row.appendChild(text);
+  if (item.retry_component) {
+    row.classList.add("retryable");
+    const retry = document.createElement("button");
+    retry.textContent = "Retry";
+    retry.addEventListener("click", async () => {
+      retry.disabled = true;
+      try {
+        const result = await invoke("apply_comfyui_component_toggle", {
+          request: { comfyuiRoot: root, component: item.retry_component, enabled: true },
+        });
+        if (result) logComfyLine(String(result));
+      } catch (err) {
+        logComfyLine(`Retrying ${item.name} failed: ${err}`);
+      }
+      await loadInstallReport(root).catch(() => {});
+    });
+    row.appendChild(retry);
+  }
+  return row;
+}
+
+async function loadInstallReport(comfyuiRoot) {
+  if (!el.installReportList || !el.installReportSummary) return;
+  const root = String(comfyuiRoot || el.comfyRoot.value || "").trim();
+  el.installReportList.innerHTML = "";
+  let report;
+  try {
+    report = await invoke("get_install_summary", { installRoot: root || null });
+  } catch (err) {
+    el.installReportSummary.textContent = String(err);
+    return;
+  }
+  const items = [...(report.items || []), ...(report.validation || []).map((item) => ({
+    ...item,
+    name: `Check: ${item.name}`,
+  }))];
+  items.forEach((item) => el.installReportList.appendChild(installReportRow(item, report.comfyui_root)));
+  const failed = items.filter((item) => item.status === "failed").length;
+  el.installReportSummary.textContent = failed
+    ? `${failed} item(s) need attention in ${report.comfyui_root}.`
+    : `All recorded components are fine in ${report.comfyui_root}.`;
+}
+
 async function runComfyPreflight() {
   try {
     const request = buildComfyInstallRequest();
-    const result = await invoke("run_comfyui_preflight", { request });
+    const result = await invokeTarget("run_comfyui_preflight", { request });
     renderPreflight(result);
     return result;
   } catch (err) {
@@ -1108,6 +1509,26 @@ async function runComfyPreflight() {
   }
 }
 
+async function reviewComfyInstallPlan() {
+  const request = buildComfyInstallRequest();
+  const plan = await invokeTarget("plan_comfyui_install", { request });
+  const mode = plan.reuses_existing ? "existing install" : "new install";
+  logComfyLine(`Install plan for ${plan.install_dir} (${mode}, ${plan.torch_profile}):`);
+  plan.steps.forEach((step, idx) => {
+    const size = step.estimated_bytes ? `${DOT_SEP}~${formatBytes(step.estimated_bytes)}` : "";
+    logComfyLine(`  ${idx + 1}. ${step.title}${size}`);
+    if (step.detail) logComfyLine(`     ${step.detail}`);
+    if (step.packages?.length) logComfyLine(`     packages: ${step.packages.join(", ")}`);
+    for (const source of step.sources || []) {
+      logComfyLine(`     ${source}`);
+    }
+  });
+  logComfyLine(`Estimated download: ~${formatBytes(plan.estimated_download_bytes || 0)}`);
+  for (const warning of plan.warnings || []) {
+    logComfyLine(`Warning: ${warning}`);
+  }
+}
+
 async function refreshComfyResumeState() {
   try {
     const installBase = String(el.comfyInstallRoot.value || "").trim() || null;
@@ -1130,9 +1551,31 @@ async function refreshComfyResumeState() {
   }
 }
 
+async function logComfyInstallHistory() {
+  const installDir = state.comfyResumeState?.install_dir;
+  if (!installDir) return;
+  const detail = await invoke("get_install_state_detail", { installDir });
+  const steps = detail?.steps || [];
+  if (!steps.length) return;
+  const failure = detail.failure;
+  if (failure) {
+    const when = new Date((failure.finished_at || failure.started_at) * 1000).toLocaleString();
+    logComfyLine(`Install stopped at '${failure.step}' (${failure.status}) on ${when}: ${failure.error || "no error recorded"}`);
+  }
+  logComfyLine(`Install steps (${steps.filter((s) => s.status === "ok").length}/${steps.length} done):`);
+  for (const entry of steps) {
+    const elapsed = entry.finished_at && entry.started_at
+      ? `${DOT_SEP}${Math.max(0, entry.finished_at - entry.started_at)}s`
+      : "";
+    const retries = entry.retries ? `${DOT_SEP}${entry.retries} retr${entry.retries === 1 ? "y" : "ies"}` : "";
+    const error = entry.error ? `${DOT_SEP}${entry.error}` : "";
+    logComfyLine(`  ${entry.step}: ${entry.status}${elapsed}${retries}${error}`);
+  }
+}
+
 async function startComfyInstall(forceFresh) {
   if (state.comfyInstallBusy) {
-    const cancelled = await invoke("cancel_comfyui_install");
+    const cancelled = await invokeTarget("cancel_comfyui_install");
     if (cancelled) {
       logComfyLine("ComfyUI installation cancellation requested.");
     } else {
@@ -1150,7 +1593,7 @@ async function startComfyInstall(forceFresh) {
   if (state.comfyRuntimeRunning) {
     logComfyLine("Detected running ComfyUI server. Stopping it before install...");
     try {
-      await invoke("stop_comfyui_root");
+      await invokeTarget("stop_comfyui_root");
     } catch (err) {
       logComfyLine(`Failed to stop running ComfyUI before install: ${err}`);
       return;
@@ -1164,17 +1607,28 @@ async function startComfyInstall(forceFresh) {
   }
 
   const preflight = await runComfyPreflight();
-  if (!preflight || !preflight.ok) {
+  const failures = (preflight?.items || []).filter((item) => item.status === "fail");
+  let allowIncompatible = false;
+  if (preflight && !preflight.ok && failures.length && failures.every((item) => item.reason)) {
+    const details = failures.map((item) => item.detail).join("\n");
+    allowIncompatible = await showConfirmDialog(`${details}\n\nInstall anyway?`);
+    if (!allowIncompatible) {
+      logComfyLine("Install cancelled: selected add-ons do not fit this hardware.");
+      return;
+    }
+  } else if (!preflight || !preflight.ok) {
     logComfyLine("Preflight has blocking issues. Resolve them before install.");
     return;
   }
   state.comfyInstallBusy = true;
   updateComfyInstallButton();
+  resetComfyInstallProgress();
   logComfyLine(forceFresh ? "Starting fresh ComfyUI installation..." : "Starting ComfyUI installation...");
   try {
     const request = buildComfyInstallRequest();
     request.forceFresh = Boolean(forceFresh);
-    await invoke("start_comfyui_install", { request });
+    request.allowIncompatible = allowIncompatible;
+    await invokeTarget("start_comfyui_install", { request });
     logComfyLine("ComfyUI installation started.");
   } catch (err) {
     state.comfyInstallBusy = false;
@@ -1183,6 +1637,53 @@ async function startComfyInstall(forceFresh) {
   }
 }
 
+async function resumeComfyInstall() {
+  const installDir = state.comfyResumeState?.install_dir;
+  if (!installDir) {
+    await startComfyInstall(false);
+    return;
+  }
+  if (state.comfyInstallBusy) {
+    logComfyLine("Wait for the running installation to finish first.");
+    return;
+  }
+  state.comfyInstallBusy = true;
+  updateComfyInstallButton();
+  resetComfyInstallProgress();
+  logComfyLine(`Resuming ComfyUI installation in ${installDir}...`);
+  try {
+    await invokeTarget("resume_comfyui_install", { installDir });
+  } catch (err) {
+    state.comfyInstallBusy = false;
+    updateComfyInstallButton();
+    logComfyLine(`Cannot resume: ${err}`);
+  }
+}
+
+async function buildOfflineInstallCache() {
+  if (state.comfyInstallBusy) {
+    logComfyLine("Wait for the running installation to finish first.");
+    return;
+  }
+  const cacheDir = String(el.comfyOfflineCache?.value || "").trim();
+  if (!cacheDir) {
+    logComfyLine("Enter an offline cache folder first.");
+    return;
+  }
+  state.comfyInstallBusy = true;
+  updateComfyInstallButton();
+  resetComfyInstallProgress();
+  try {
+    const request = buildComfyInstallRequest();
+    request.offlineCache = null;
+    await invoke("build_offline_install_cache", { request, cacheDir });
+  } catch (err) {
+    state.comfyInstallBusy = false;
+    updateComfyInstallButton();
+    logComfyLine(`Offline cache build failed to start: ${err}`);
+  }
+}
+
 function applyComfyAddonRules() {
   if (el.addonSageAttention3) {
     const wasChecked = el.addonSageAttention3.checked;
@@ -1202,6 +1703,18 @@ function applyComfyAddonRules() {
     }
   }
 
+  if (el.addonXformers) {
+    const profile = String(el.comfyTorchProfile?.value || "").trim();
+    const xformersAllowed = ["torch271_cu128", "torch280_cu128", "torch291_cu130"].includes(profile);
+    const wasChecked = el.addonXformers.checked;
+    el.addonXformers.disabled = !xformersAllowed;
+    if (!xformersAllowed && wasChecked) {
+      el.addonXformers.checked = false;
+    }
+  }
+
+  syncActiveAttentionOptions();
+
   if (el.addonNunchaku && el.addonInsightFace) {
     const nunchakuSelected = Boolean(el.addonNunchaku.checked);
     if (nunchakuSelected) {
@@ -1419,6 +1932,8 @@ function beginBusyDownload(label) {
       state.activeDownloadKind = "lora";
     } else if (state.activeTab === "workflows") {
       state.activeDownloadKind = "workflow";
+    } else if (state.activeTab === "assets") {
+      state.activeDownloadKind = el.assetClass.value;
     } else {
       state.activeDownloadKind = "model";
     }
@@ -1445,17 +1960,19 @@ function updateDownloadButtons() {
     el.downloadModel.textContent = "Cancel Download";
     el.downloadLora.textContent = "Cancel Download";
     el.downloadWorkflow.textContent = "Cancel Download";
+    el.downloadAsset.textContent = "Cancel Download";
   } else {
     el.downloadModel.textContent = "Download Model Assets";
     el.downloadLora.textContent = "Download LoRA";
     el.downloadWorkflow.textContent = "Download Workflow";
+    el.downloadAsset.textContent = "Download";
   }
 }
 
 async function requestCancelDownload() {
   try {
     setProgress("Cancelling download...");
-    const cancelled = await invoke("cancel_active_download");
+    const cancelled = await invokeTarget("cancel_active_download");
     if (cancelled) {
       logLine("Cancellation requested.");
       setProgress("Cancellation requested...");
@@ -1509,6 +2026,161 @@ function renderActiveTransfers() {
   }
 }
 
+function renderHostStats(entries) {
+  if (!el.hostStatsList) return;
+  el.hostStatsList.innerHTML = "";
+  if (!entries.length) {
+    const msg = document.createElement("div");
+    msg.className = "empty-msg";
+    msg.textContent = "No download hosts used yet.";
+    el.hostStatsList.appendChild(msg);
+    return;
+  }
+  for (const entry of entries) {
+    const row = document.createElement("div");
+    row.className = "transfer-item";
+    const title = document.createElement("div");
+    title.className = "transfer-title";
+    title.textContent = entry.flagged_reason ? `\u26a0 ${entry.host}` : entry.host;
+    const sub = document.createElement("div");
+    sub.className = "transfer-sub";
+    const parts = [`${entry.attempts} downloads`, `${Math.round(entry.failure_rate * 100)}% failed`];
+    if (entry.bytes_per_sec) parts.push(`${formatBytes(entry.bytes_per_sec)}/s avg`);
+    if (entry.blacklisted) parts.push("blacklisted");
+    else if (entry.flagged_reason) parts.push(`flagged: ${entry.flagged_reason}`);
+    sub.textContent = parts.join(DOT_SEP);
+    if (entry.last_error) sub.title = entry.last_error;
+    const button = document.createElement("button");
+    button.setAttribute("type", "button");
+    button.textContent = entry.blacklisted ? "Allow Host" : "Blacklist Host";
+    button.addEventListener("click", async () => {
+      try {
+        const updated = await invoke("set_host_blacklisted", {
+          host: entry.host,
+          blacklisted: !entry.blacklisted,
+        });
+        renderHostStats(updated || []);
+      } catch (err) {
+        logLine(`Host blacklist update failed: ${err}`);
+      }
+    });
+    row.appendChild(title);
+    row.appendChild(sub);
+    row.appendChild(button);
+    el.hostStatsList.appendChild(row);
+  }
+}
+
+function renderFollowedCreators(report) {
+  if (!el.followedCreatorList || !el.creatorUpdateList) return;
+  el.followedCreatorList.innerHTML = "";
+  el.creatorUpdateList.innerHTML = "";
+  const creators = report?.creators || [];
+  const updates = report?.updates || [];
+  if (!creators.length) {
+    const msg = document.createElement("div");
+    msg.className = "empty-msg";
+    msg.textContent = "Not following any Civitai creators.";
+    el.followedCreatorList.appendChild(msg);
+  }
+  for (const creator of creators) {
+    const row = document.createElement("div");
+    row.className = "transfer-item";
+    const title = document.createElement("div");
+    title.className = "transfer-title";
+    title.textContent = creator.username;
+    const sub = document.createElement("div");
+    sub.className = "transfer-sub";
+    sub.textContent = creator.last_checked_at
+      ? `Last checked ${new Date(creator.last_checked_at * 1000).toLocaleString()}`
+      : "Not checked yet";
+    const button = document.createElement("button");
+    button.setAttribute("type", "button");
+    button.textContent = "Unfollow";
+    button.addEventListener("click", async () => {
+      try {
+        renderFollowedCreators(await invoke("unfollow_civitai_creator", { username: creator.username }));
+      } catch (err) {
+        logLine(`Unfollow failed: ${err}`);
+      }
+    });
+    row.appendChild(title);
+    row.appendChild(sub);
+    row.appendChild(button);
+    el.followedCreatorList.appendChild(row);
+  }
+  if (!updates.length) {
+    const msg = document.createElement("div");
+    msg.className = "empty-msg";
+    msg.textContent = "No new LoRAs from followed creators.";
+    el.creatorUpdateList.appendChild(msg);
+    return;
+  }
+  for (const update of updates) {
+    const row = document.createElement("div");
+    row.className = "transfer-item";
+    const title = document.createElement("div");
+    title.className = "transfer-title";
+    title.textContent = `${update.model_name} (${update.version_name})`;
+    const sub = document.createElement("div");
+    sub.className = "transfer-sub";
+    const parts = [update.creator, update.base_model || "unknown base model"];
+    parts.push(update.compatible ? "matches an installed model" : "no matching model installed");
+    sub.textContent = parts.join(DOT_SEP);
+    const button = document.createElement("button");
+    button.setAttribute("type", "button");
+    button.textContent = "Add to Queue";
+    button.disabled = !update.compatible;
+    button.addEventListener("click", () => queueCreatorUpdate(update));
+    row.appendChild(title);
+    row.appendChild(sub);
+    row.appendChild(button);
+    el.creatorUpdateList.appendChild(row);
+  }
+}
+
+async function refreshFollowedCreators() {
+  try {
+    renderFollowedCreators(await invoke("get_followed_creator_updates"));
+  } catch (err) {
+    logLine(`Followed creators unavailable: ${err}`);
+  }
+}
+
+function queueCreatorUpdate(update) {
+  if (state.creatorQueue.some((item) => item.version_id === update.version_id)) return;
+  state.creatorQueue.push(update);
+  logLine(`Queued ${update.model_name} (${update.version_name}).`);
+  startNextCreatorDownload();
+}
+
+// Runs queued creator LoRAs one at a time; the backend allows a single active download.
+async function startNextCreatorDownload() {
+  if (state.busyDownloads > 0 || !state.creatorQueue.length) return;
+  const next = state.creatorQueue.shift();
+  if (!state.activeDownloadKind) state.activeDownloadKind = "lora";
+  beginBusyDownload(`Starting ${next.model_name}...`);
+  try {
+    await invoke("download_creator_update", {
+      versionId: next.version_id,
+      token: el.civitaiToken.value?.trim() || null,
+      comfyuiRoot: el.comfyRootLora.value,
+    });
+  } catch (err) {
+    logLine(`${next.model_name}: ${err}`);
+    endBusyDownload();
+    startNextCreatorDownload();
+  }
+}
+
+async function refreshHostStats() {
+  try {
+    renderHostStats((await invoke("get_host_stats")) || []);
+  } catch (err) {
+    logLine(`Host statistics unavailable: ${err}`);
+  }
+}
+
 function renderCompletedTransfers() {
   el.completedList.innerHTML = "";
   if (!state.completed.length) {
@@ -1595,14 +2267,17 @@ function switchTab(tab) {
   const models = tab === "models";
   const loras = tab === "loras";
   const workflows = tab === "workflows";
+  const assets = tab === "assets";
   el.tabComfyui.classList.toggle("active", comfyui);
   el.tabModels.classList.toggle("active", models);
   el.tabLoras.classList.toggle("active", loras);
   el.tabWorkflows.classList.toggle("active", workflows);
+  el.tabAssets.classList.toggle("active", assets);
   el.contentComfyui.classList.toggle("hidden", !comfyui);
   el.contentModels.classList.toggle("hidden", !models);
   el.contentLoras.classList.toggle("hidden", !loras);
   el.contentWorkflows.classList.toggle("hidden", !workflows);
+  el.contentAssets.classList.toggle("hidden", !assets);
   el.downloadsStatusPanel.classList.toggle("hidden", comfyui);
 }
 
@@ -1631,22 +2306,123 @@ function refreshModelSelectors() {
 
   const selectedModel = state.catalog.models.find((m) => m.id === el.modelId.value);
   const tier = el.vramTier.value;
+  // Audio and 3D variants built for a weaker GPU also fit stronger ones.
+  const acceptsWeaker = ["audio", "3d"].includes(selectedModel?.family_type);
+  const tierRank = (id) => vramOptions.findIndex((v) => v.id === id);
   const variants = (selectedModel?.variants || [])
-    .filter((v) => v.tier === tier)
+    .filter((v) => v.tier === tier || (acceptsWeaker && tierRank(v.tier) > tierRank(tier)))
     .map((v) => ({
       value: v.id,
       label: [v.model_size, v.quantization, v.note, v.tier?.toUpperCase?.()].filter(Boolean).join(DOT_SEP),
     }));
 
   setOptions(el.variantId, variants.length ? variants : [{ value: "", label: "No variant for selected VRAM tier" }]);
+  refreshModelArtifactOptions().catch(() => {});
+}
+
+// Selects the saved model and variant once their option lists exist; ids the catalog
+// no longer has fall back to the first entry as before.
+function restoreModelSelection(last) {
+  refreshModelSelectors();
+  if (!last?.model_id) return;
+  if ([...el.modelId.options].some((opt) => opt.value === last.model_id)) {
+    el.modelId.value = last.model_id;
+    refreshModelSelectors();
+  }
+  if (last.variant_id && [...el.variantId.options].some((opt) => opt.value === last.variant_id)) {
+    el.variantId.value = last.variant_id;
+    refreshModelArtifactOptions().catch(() => {});
+  }
+}
+
+function saveLastSelection() {
+  invoke("set_last_selection", {
+    selection: {
+      family: el.modelFamily.value || null,
+      model_id: el.modelId.value || null,
+      variant_id: el.variantId.value || null,
+      vram_tier: el.vramTier.value || null,
+      ram_tier: el.ramTier.value || null,
+    },
+  }).catch(() => {});
+}
+
+async function refreshModelArtifactOptions() {
+  if (!el.modelArtifactOptions) return;
+  el.modelArtifactOptions.innerHTML = "";
+  if (!el.modelArtifactPicker.open || !el.modelId.value || !el.variantId.value) return;
+  let options = [];
+  try {
+    options = await invoke("get_variant_artifact_options", {
+      modelId: el.modelId.value,
+      variantId: el.variantId.value,
+      ramTier: el.ramTier.value,
+    });
+  } catch (err) {
+    logLine(`Could not list variant files: ${err}`);
+    return;
+  }
+  for (const option of options || []) {
+    const label = document.createElement("label");
+    label.className = "transfer-item";
+    const box = document.createElement("input");
+    box.type = "checkbox";
+    box.value = option.path;
+    box.checked = !!option.recommended;
+    const parts = [option.file_name, option.quantization, option.category, option.group];
+    if (option.size_bytes) parts.push(formatBytes(option.size_bytes));
+    label.appendChild(box);
+    label.appendChild(document.createTextNode(` ${parts.filter(Boolean).join(DOT_SEP)}`));
+    el.modelArtifactOptions.appendChild(label);
+  }
+}
+
+function selectedModelArtifactPaths() {
+  if (!el.modelArtifactPicker?.open) return null;
+  return [...el.modelArtifactOptions.querySelectorAll("input[type=checkbox]:checked")].map((box) => box.value);
 }
 
 function refreshLoraSelectors() {
   if (!state.catalog) return;
   const family = el.loraFamily.value || "all";
   const filtered = state.catalog.loras.filter((l) => family === "all" || l.family === family);
-  const options = filtered.map((l) => ({ value: l.id, label: l.display_name }));
+  const options = filtered.map((l) => ({ value: l.id, label: l.favorite ? `\u2605 ${l.display_name}` : l.display_name }));
   setOptions(el.loraId, options);
+  const favorites = filtered.filter((l) => l.favorite).length;
+  if (favorites > 0 && favorites < filtered.length) {
+    const group = document.createElement("optgroup");
+    group.label = "Favorites";
+    [...el.loraId.options].slice(0, favorites).forEach((opt) => group.appendChild(opt));
+    el.loraId.insertBefore(group, el.loraId.firstChild);
+  }
+  refreshLoraFavoriteButton();
+}
+
+function refreshLoraFavoriteButton() {
+  const lora = state.catalog?.loras.find((l) => l.id === el.loraId.value);
+  el.toggleLoraFavorite.disabled = !lora;
+  el.toggleLoraFavorite.textContent = lora?.favorite ? "\u2605 Unfavorite" : "\u2606 Favorite";
+}
+
+const ASSET_CLASSES = {
+  upscaler: { key: "upscalers", subdir: "models/upscale_models" },
+  controlnet: { key: "controlnets", subdir: "models/controlnet" },
+  embedding: { key: "embeddings", subdir: "models/embeddings" },
+  clip_vision: { key: "clip_vision", subdir: "models/clip_vision" },
+};
+
+function refreshAssetSelectors() {
+  if (!state.catalog) return;
+  const info = ASSET_CLASSES[el.assetClass.value] || ASSET_CLASSES.upscaler;
+  const assets = state.catalog[info.key] || [];
+  const options = assets.map((a) => ({
+    value: a.id,
+    label: a.family ? `${a.display_name}${DOT_SEP}${a.family}` : a.display_name,
+  }));
+  setOptions(el.assetId, options.length ? options : [{ value: "", label: "No entries in catalog" }]);
+  const selected = assets.find((a) => a.id === el.assetId.value);
+  el.assetDestination.textContent = info.subdir;
+  el.assetNote.textContent = selected?.note || "-";
 }
 
 function refreshWorkflowSelectors() {
@@ -1709,6 +2485,26 @@ function loadWorkflowPreview() {
   }
 }
 
+// Cached previews are played from the backend's copy; anything else streams from its
+// URL while the backend caches it for next time.
+async function showLoraPreviewMedia(loraId, meta, requestSeq) {
+  const token = el.civitaiToken.value?.trim() || null;
+  if (meta.preview_url && meta.preview_cached) {
+    try {
+      const bytes = await invoke("get_lora_preview_media", { loraId, token });
+      if (requestSeq !== state.loraMetaRequestSeq || loraId !== el.loraId.value) return;
+      if (state.loraPreviewObjectUrl) URL.revokeObjectURL(state.loraPreviewObjectUrl);
+      state.loraPreviewObjectUrl = URL.createObjectURL(new Blob([bytes]));
+      applyLoraPreview(state.loraPreviewObjectUrl, meta.preview_kind);
+      return;
+    } catch (_) {}
+  }
+  applyLoraPreview(meta.preview_url, meta.preview_kind);
+  if (meta.preview_url) {
+    invoke("get_lora_preview_media", { loraId, token, prefetch: true }).catch(() => {});
+  }
+}
+
 async function loadLoraMetadata() {
   const loraId = el.loraId.value;
   if (!loraId) return;
@@ -1719,6 +2515,14 @@ async function loadLoraMetadata() {
     applyLoraPreview(cachedMeta.preview_url, cachedMeta.preview_kind);
   }
 
+  // Civitai calls are rate limited in the backend; show the queue instead of a stall.
+  const queueTimer = setInterval(async () => {
+    const position = await invoke("get_lora_metadata_queue_position", { loraId }).catch(() => null);
+    if (!position || requestSeq !== state.loraMetaRequestSeq) return;
+    el.metaDescription.textContent = position > 1
+      ? `Fetching (queued, ${position - 1} ahead)...`
+      : "Fetching (queued)...";
+  }, 400);
   try {
     const rawMeta = await invoke("get_lora_metadata", {
       loraId,
@@ -1753,20 +2557,66 @@ async function loadLoraMetadata() {
     el.metaDescription.textContent = trimDescription(meta.description || "-");
     state.currentLoraMetaId = loraId;
 
-    applyLoraPreview(meta.preview_url, meta.preview_kind);
+    await showLoraPreviewMedia(loraId, meta, requestSeq);
   } catch (err) {
     if (cachedMeta) {
       return;
     }
     logLine(`Metadata error: ${err}`);
+  } finally {
+    clearInterval(queueTimer);
   }
 }
 
-async function bootstrap() {
-  if (!invoke) {
-    logLine("Tauri invoke bridge unavailable.");
+let loraMetaDebounce = null;
+
+// Scrolling through the LoRA list fires a change per row; only the one it stops on loads.
+function scheduleLoraMetadata() {
+  clearTimeout(loraMetaDebounce);
+  loraMetaDebounce = setTimeout(() => {
+    loadLoraMetadata().catch((err) => logLine(String(err)));
+  }, 250);
+}
+
+async function unlockEncryptedSettings() {
+  const status = await invoke("get_settings_encryption_status").catch(() => null);
+  if (!status?.enabled || status.unlocked) return;
+  for (let attempt = 0; attempt < 3; attempt += 1) {
+    const passphrase = window.prompt("Settings are encrypted. Enter your passphrase to unlock API tokens and hook commands:");
+    if (passphrase == null) break;
+    try {
+      await invoke("unlock_settings", { passphrase });
+      return;
+    } catch (err) {
+      logLine(`Unlock failed: ${err}`);
+    }
+  }
+  logLine("Encrypted settings remain locked; API tokens and hook commands are unavailable this session.");
+}
+
+async function preselectDetectedVramTier(keepCurrent = false) {
+  const initial = el.vramTier.value;
+  const detected = await invoke("detect_vram_tier");
+  if (!detected?.tier) return;
+  // Keep a restored tier, or one the user picked while nvidia-smi was still running.
+  const selected = !keepCurrent && el.vramTier.value === initial ? detected.tier : el.vramTier.value;
+  setOptions(
+    el.vramTier,
+    vramOptions.map((v) => ({
+      value: v.id,
+      label: v.id === detected.tier ? `${v.label} (auto-detected)` : v.label,
+    })),
+    selected,
+  );
+  refreshModelSelectors();
+}
+
+async function bootstrap() {
+  if (!invoke) {
+    logLine("Tauri invoke bridge unavailable.");
     return;
   }
+  await unlockEncryptedSettings();
   setStartupStatus("Loading settings and catalog...");
   const [settings, catalog] = await Promise.all([
     invoke("get_settings"),
@@ -1825,12 +2675,25 @@ async function bootstrap() {
     el.comfyMode.value = state.comfyMode;
   }
   el.civitaiToken.value = settings.civitai_token || "";
+  state.remoteAgentUrl = settings.remote_agent_url || "";
+  if (el.remoteAgentHost) el.remoteAgentHost.value = state.remoteAgentUrl;
+  updateRemoteAgentUi();
+  if (state.remoteAgentUrl) pollRemoteAgentEvents(false).catch(() => {});
   if (el.addonPinnedMemory) {
     el.addonPinnedMemory.checked = settings.comfyui_pinned_memory_enabled !== false;
   }
   if (el.enableHfXet) {
     el.enableHfXet.checked = settings.hf_xet_enabled === true;
   }
+  if (el.civitaiDownloadExamples) {
+    el.civitaiDownloadExamples.checked = settings.civitai_download_examples === true;
+  }
+  if (el.comfyAutoRestart) {
+    el.comfyAutoRestart.checked = settings.comfyui_auto_restart?.enabled === true;
+  }
+  if (el.comfyAutoRestartAttempts) {
+    el.comfyAutoRestartAttempts.value = String(settings.comfyui_auto_restart?.policy?.attempts ?? 3);
+  }
   setComfyQuickActions(settings.comfyui_last_install_dir || "", settings.comfyui_root || "");
   setOptions(el.comfyTorchProfile, comfyTorchProfiles);
   const savedTorchProfile = String(settings.comfyui_torch_profile || "").trim();
@@ -1910,6 +2773,7 @@ async function bootstrap() {
     await refreshExistingInstallations("", null);
   }
   await refreshComfyResumeState();
+  await refreshMutationSession(el.comfyRoot.value || "").catch(() => {});
   setStartupStatus("Checking ComfyUI runtime status...");
   await refreshComfyRuntimeStatus();
   updateComfyModeUi();
@@ -1917,10 +2781,12 @@ async function bootstrap() {
     loadInstalledAddonState(el.comfyRoot.value || "").catch(() => {});
   }, 0);
 
-  setOptions(el.modelFamily, familyOptions(catalog.models));
-  setOptions(el.vramTier, vramOptions.map((v) => ({ value: v.id, label: v.label })), "tier_s");
-  setOptions(el.ramTier, ramOptions.map((r) => ({ value: r.id, label: r.label })), "tier_a");
-  refreshModelSelectors();
+  const last = await invoke("get_last_selection").catch(() => ({}));
+  setOptions(el.modelFamily, familyOptions(catalog.models), last?.family || null);
+  setOptions(el.vramTier, vramOptions.map((v) => ({ value: v.id, label: v.label })), last?.vram_tier || "tier_s");
+  setOptions(el.ramTier, ramOptions.map((r) => ({ value: r.id, label: r.label })), last?.ram_tier || "tier_a");
+  restoreModelSelection(last);
+  preselectDetectedVramTier(Boolean(last?.vram_tier)).catch(() => {});
 
   setOptions(el.loraFamily, loraFamilyOptions(catalog.loras));
   refreshLoraSelectors();
@@ -1930,6 +2796,7 @@ async function bootstrap() {
 
   setOptions(el.workflowFamily, workflowFamilyOptions(catalog.workflows || []));
   refreshWorkflowSelectors();
+  refreshAssetSelectors();
 
   logLine(`Loaded ${catalog.models?.length || 0} models, ${catalog.loras?.length || 0} LoRAs, and ${catalog.workflows?.length || 0} workflows.`);
   try {
@@ -1946,17 +2813,40 @@ el.tabComfyui.addEventListener("click", () => switchTab("comfyui"));
 el.tabModels.addEventListener("click", () => switchTab("models"));
 el.tabLoras.addEventListener("click", () => switchTab("loras"));
 el.tabWorkflows.addEventListener("click", () => switchTab("workflows"));
+el.tabAssets.addEventListener("click", () => switchTab("assets"));
+el.assetClass.addEventListener("change", refreshAssetSelectors);
+el.assetId.addEventListener("change", refreshAssetSelectors);
 
 el.modelFamily.addEventListener("change", refreshModelSelectors);
 el.modelId.addEventListener("change", refreshModelSelectors);
 el.vramTier.addEventListener("change", refreshModelSelectors);
+el.variantId.addEventListener("change", () => refreshModelArtifactOptions().catch(() => {}));
+el.ramTier.addEventListener("change", () => refreshModelArtifactOptions().catch(() => {}));
+el.modelArtifactPicker.addEventListener("toggle", () => refreshModelArtifactOptions().catch(() => {}));
+for (const select of [el.modelFamily, el.modelId, el.vramTier, el.variantId, el.ramTier]) {
+  select.addEventListener("change", saveLastSelection);
+}
 
 el.loraFamily.addEventListener("change", () => {
   refreshLoraSelectors();
-  loadLoraMetadata().catch((err) => logLine(String(err)));
+  scheduleLoraMetadata();
 });
 el.loraId.addEventListener("change", () => {
-  loadLoraMetadata().catch((err) => logLine(String(err)));
+  refreshLoraFavoriteButton();
+  scheduleLoraMetadata();
+});
+el.toggleLoraFavorite.addEventListener("click", async () => {
+  const loraId = el.loraId.value;
+  if (!loraId) return;
+  try {
+    await invoke("toggle_lora_favorite", { loraId });
+    state.catalog = await invoke("get_catalog");
+    refreshLoraSelectors();
+    el.loraId.value = loraId;
+    refreshLoraFavoriteButton();
+  } catch (err) {
+    logLine(`Favorite update failed: ${err}`);
+  }
 });
 el.workflowFamily.addEventListener("change", refreshWorkflowSelectors);
 el.workflowId.addEventListener("change", loadWorkflowPreview);
@@ -2132,6 +3022,82 @@ el.clearExtraModelRoot?.addEventListener("click", async () => {
   logComfyLine("Optional extra models folder cleared.");
 });
 
+el.syncSharedModels?.addEventListener("click", async () => {
+  const sharedModelsRoot = String(el.comfyExtraModelRoot?.value || "").trim();
+  if (!sharedModelsRoot) {
+    logComfyLine("Choose a shared models folder first.");
+    return;
+  }
+  el.syncSharedModels.disabled = true;
+  try {
+    const report = await invoke("sync_shared_models_dir", {
+      sharedModelsRoot,
+      useAsDefault: Boolean(el.comfyExtraModelDefault?.checked),
+    });
+    state.sharedModelsRootDefault = report.shared_models_root;
+    state.sharedModelsUseDefault = report.use_as_default;
+    if (!report.installs?.length) {
+      logComfyLine("Shared models folder saved; no ComfyUI installs were detected to update.");
+    }
+    for (const item of report.installs || []) {
+      logComfyLine(`${item.name}: ${item.status}${DOT_SEP}${item.detail}`);
+    }
+  } catch (err) {
+    logComfyLine(`Applying shared models folder failed: ${err}`);
+  } finally {
+    el.syncSharedModels.disabled = false;
+  }
+});
+
+el.diagnoseModelsFolders?.addEventListener("click", async () => {
+  const root = String(el.comfyRoot.value || "").trim();
+  el.diagnoseModelsFolders.disabled = true;
+  try {
+    const report = await invoke("diagnose_models_folders", { comfyuiRoot: root || null });
+    const flagged = (report.folders || []).filter((item) => item.status !== "pass");
+    logComfyLine(
+      `Models folders in ${report.comfyui_root}: ${flagged.length ? `${flagged.length} issue(s)` : "no issues found"}.`,
+    );
+    for (const item of flagged) {
+      logComfyLine(`[${item.status.toUpperCase()}] ${item.path}${DOT_SEP}${item.detail}`);
+    }
+  } catch (err) {
+    logComfyLine(`Models folder check failed: ${err}`);
+  } finally {
+    el.diagnoseModelsFolders.disabled = false;
+  }
+});
+
+el.migrateModels?.addEventListener("click", async () => {
+  const sourceRoot = String(el.migrateSourceRoot?.value || "").trim();
+  const destRoot = String(el.migrateDestRoot?.value || "").trim();
+  if (!sourceRoot || !destRoot) {
+    logComfyLine("Enter both the install to migrate from and the one to migrate into.");
+    return;
+  }
+  const moveOrLink = el.migrateMode?.value || "move";
+  const verb = moveOrLink === "link" ? "Symlink" : "Move";
+  if (!window.confirm(`${verb} every file in ${sourceRoot}/models into ${destRoot}/models?`)) return;
+  el.migrateModels.disabled = true;
+  try {
+    const report = await invoke("migrate_models", {
+      sourceRoot,
+      destRoot,
+      moveOrLink,
+      onCollision: el.migrateCollision?.value || "skip",
+    });
+    for (const item of report.collisions || []) {
+      logComfyLine(`${item.name}: ${item.status}${DOT_SEP}${item.detail}`);
+    }
+    for (const failure of report.failed || []) logComfyLine(`Migration failed: ${failure}`);
+    logComfyLine(`Migrated ${report.migrated} file(s) (${formatBytes(report.bytes)}) into ${report.dest_models}.`);
+  } catch (err) {
+    logComfyLine(`Model migration failed: ${err}`);
+  } finally {
+    el.migrateModels.disabled = false;
+  }
+});
+
 el.comfyExtraModelDefault?.addEventListener("change", async () => {
   const hasRoot = Boolean(String(el.comfyExtraModelRoot?.value || "").trim());
   if (!hasRoot && el.comfyExtraModelDefault?.checked) {
@@ -2213,24 +3179,9 @@ el.comfyExistingInstall?.addEventListener("change", async () => {
     refreshComfyUiUpdateStatus("").catch(() => {});
     return;
   }
-  const previousRoot = String(el.comfyRoot?.value || "").trim();
-  const switchingInstall = previousRoot
-    && normalizeSlashes(previousRoot) !== normalizeSlashes(selectedRoot);
   try {
-    if (state.comfyMode === "manage" && switchingInstall) {
-      await refreshComfyRuntimeStatus().catch(() => {});
-      if (state.comfyRuntimeRunning) {
-        logComfyLine("ComfyUI server is running. Stopping it before switching managed install...");
-        await invoke("stop_comfyui_root");
-        await refreshComfyRuntimeStatus().catch(() => {});
-        if (state.comfyRuntimeRunning) {
-          logComfyLine("ComfyUI is still running. Stop it first, then switch install.");
-          return;
-        }
-        logComfyLine("ComfyUI server stopped.");
-      }
-    }
     await applySelectedExistingInstallation(selectedRoot);
+    refreshComfyRuntimeStatus().catch(() => {});
     if (state.comfyMode === "manage") {
       logComfyLine(`Now managing: ${selectedRoot}`);
     }
@@ -2271,204 +3222,943 @@ el.updateSelectedInstall?.addEventListener("click", async () => {
   }
 });
 
-el.installComfyui.addEventListener("click", async () => {
-  await startComfyInstall(false);
-});
-
-el.addonSageAttention?.addEventListener("change", () => {
-  applyAttentionBackendFromToggle(el.addonSageAttention).catch((err) => logComfyLine(String(err)));
-});
-el.addonSageAttention3?.addEventListener("change", () => {
-  applyAttentionBackendFromToggle(el.addonSageAttention3).catch((err) => logComfyLine(String(err)));
-});
-el.addonFlashAttention?.addEventListener("change", () => {
-  applyAttentionBackendFromToggle(el.addonFlashAttention).catch((err) => logComfyLine(String(err)));
-});
-el.addonNunchaku?.addEventListener("change", () => {
-  applyComfyAddonRules();
-  applyAttentionBackendFromToggle(el.addonNunchaku).catch((err) => logComfyLine(String(err)));
-});
-el.addonInsightFace?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.addonInsightFace, "addon_insightface", "InsightFace")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.addonTrellis2?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.addonTrellis2, "addon_trellis2", "Trellis2")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.addonPinnedMemory?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.addonPinnedMemory, "addon_pinned_memory", "Pinned Memory")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.nodeComfyuiManager?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.nodeComfyuiManager, "node_comfyui_manager", "comfyui-manager")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.nodeComfyuiEasyUse?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.nodeComfyuiEasyUse, "node_comfyui_easy_use", "ComfyUI-Easy-Use")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.nodeRgthreeComfy?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.nodeRgthreeComfy, "node_rgthree_comfy", "rgthree-comfy")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.nodeComfyuiGguf?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.nodeComfyuiGguf, "node_comfyui_gguf", "ComfyUI-GGUF")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.nodeComfyuiKjnodes?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.nodeComfyuiKjnodes, "node_comfyui_kjnodes", "comfyui-kjnodes")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.nodeComfyuiCrystools?.addEventListener("change", () => {
-  applyComponentToggleFromCheckbox(el.nodeComfyuiCrystools, "node_comfyui_crystools", "comfyui-crystools")
-    .catch((err) => logComfyLine(String(err)));
-});
-el.comfyTorchProfile?.addEventListener("change", () => {
-  state.comfyTorchProfileLocked = true;
-  applyComfyAddonRules();
-});
-el.runPreflight?.addEventListener("click", () => {
-  runComfyPreflight().then((result) => {
-    if (!result) return;
-    logComfyLine(result.summary || "Preflight completed.");
-  });
-});
-el.comfyResumeBtn?.addEventListener("click", async () => {
-  await startComfyInstall(false);
-});
-el.comfyFreshBtn?.addEventListener("click", async () => {
-  await startComfyInstall(true);
-});
-
-el.comfyClearInstallLog?.addEventListener("click", () => {
-  if (el.comfyInstallLog) el.comfyInstallLog.textContent = "Ready";
-});
-
-el.clearStatusLog?.addEventListener("click", () => {
-  if (el.statusLog) el.statusLog.textContent = "Ready";
-});
-
-el.comfyOpenInstallFolder?.addEventListener("click", async () => {
-  const path = String(el.comfyOpenInstallFolder.dataset.path || "").trim();
-  if (!path) return;
+el.rollbackSelectedInstall?.addEventListener("click", async () => {
+  const selectedRoot = String(el.comfyExistingInstall?.value || "").trim();
+  const rollback = state.comfyRollback;
+  if (!selectedRoot || !rollback || state.comfyUpdateBusy) return;
+  const target = String(rollback.comfyui_commit || "").slice(0, 7);
+  const ok = await showConfirmDialog(`Roll ComfyUI back to ${target} and restore the packages it had before the update?`);
+  if (!ok) return;
   try {
-    await invoke("open_folder", { path });
+    state.comfyUpdateBusy = true;
+    updateComfyUpdateButton();
+    const result = await invoke("rollback_comfyui_update", { comfyuiRoot: selectedRoot });
+    if (result) {
+      logComfyLine(String(result));
+    }
+    await loadInstalledAddonState(selectedRoot);
   } catch (err) {
-    logComfyLine(`Open install folder failed: ${err}`);
+    logComfyLine(`ComfyUI rollback failed: ${err}`);
+  } finally {
+    state.comfyUpdateBusy = false;
+    await refreshComfyUiUpdateStatus(selectedRoot);
   }
 });
 
-el.comfyStartInstalled?.addEventListener("click", async () => {
-  const preferredManageRoot = state.comfyMode === "manage"
-    ? String(el.comfyExistingInstall?.value || "").trim()
-    : "";
-  const path = String(preferredManageRoot || el.comfyStartInstalled.dataset.path || "").trim();
-  if (!path) return;
+el.unpinSelectedInstall?.addEventListener("click", async () => {
+  const selectedRoot = String(el.comfyExistingInstall?.value || "").trim();
+  if (!selectedRoot || !state.comfyPinnedRef || state.comfyUpdateBusy) return;
   try {
-    if (state.comfyRuntimeRunning) {
-      state.comfyRuntimeStarting = false;
-      state.comfyRuntimeTarget = "";
-      updateComfyRuntimeButton();
-      const stopped = await invoke("stop_comfyui_root");
-      logComfyLine(stopped ? "ComfyUI stop requested." : "ComfyUI was not running.");
-      await refreshComfyRuntimeStatus();
-    } else {
-      state.comfyRuntimeTarget = comfyInstallNameFromRoot(path);
-      state.comfyRuntimeStarting = true;
-      state.comfyRuntimeRunning = false;
-      updateComfyRuntimeButton();
-      await invoke("start_comfyui_root", { comfyuiRoot: path });
-      logComfyLine("ComfyUI launch requested.");
+    const result = await invoke("unpin_comfyui_ref", { comfyuiRoot: selectedRoot });
+    if (result) {
+      logComfyLine(String(result));
     }
   } catch (err) {
-    state.comfyRuntimeStarting = false;
-    state.comfyRuntimeRunning = false;
-    state.comfyRuntimeTarget = "";
-    updateComfyRuntimeButton();
-    logComfyLine(`ComfyUI runtime action failed: ${err}`);
+    logComfyLine(`Unpinning ComfyUI failed: ${err}`);
+  } finally {
+    await refreshComfyUiUpdateStatus(selectedRoot);
   }
 });
 
-el.saveToken.addEventListener("click", async () => {
-  try {
-    await invoke("save_civitai_token", { token: el.civitaiToken.value });
-    const original = el.saveToken.textContent;
-    el.saveToken.textContent = "Saved";
-    el.saveToken.disabled = true;
-    window.setTimeout(() => {
-      el.saveToken.textContent = original || "Save Token";
-      el.saveToken.disabled = false;
-    }, 900);
-    await loadLoraMetadata();
-  } catch (err) {
-    logLine(`Save token failed: ${err}`);
+el.repairSelectedInstall?.addEventListener("click", async () => {
+  const selectedRoot = String(el.comfyExistingInstall?.value || "").trim();
+  if (!selectedRoot) {
+    logComfyLine("No existing ComfyUI installation selected.");
+    return;
   }
-});
-
-el.checkUpdates.addEventListener("click", async () => {
-  if (state.updateInstalling) return;
-  if (state.updateAvailable) {
-    try {
-      state.updateInstalling = true;
-      updateUpdateButton();
-      el.updateStatus.textContent = state.updateVersion
-        ? `Installing v${state.updateVersion}...`
-        : "Installing update...";
-      showBlockingOverlay("App updating... It will close now. Please launch it again after update.");
-      await invoke("auto_update_startup");
-    } catch (err) {
-      state.updateInstalling = false;
-      updateUpdateButton();
-      el.updateStatus.textContent = "Error";
-      logLine(String(err));
-      hideStartupOverlay();
-    }
+  if (!window.confirm(`Repair ${selectedRoot}? ComfyUI will be stopped while packages are checked and reinstalled.`)) {
     return;
   }
+  el.repairSelectedInstall.disabled = true;
   try {
-    const result = await invoke("check_updates_now");
-    if (result.available) {
-      state.updateAvailable = true;
-      state.updateVersion = result.version || null;
-      el.updateStatus.textContent = "New update available";
-      updateUpdateButton();
-      logLine(`Update available: v${result.version}`);
-    } else {
-      state.updateAvailable = false;
-      state.updateVersion = null;
-      el.updateStatus.textContent = "Up to date";
-      updateUpdateButton();
-      logLine("No updates available.");
+    const report = await invoke("repair_comfyui_installation", { comfyuiRoot: selectedRoot });
+    const failed = (report?.stages || []).filter((stage) => stage.status === "failed");
+    for (const stage of failed) {
+      logComfyLine(`Repair needs attention - ${stage.label}: ${stage.detail}`);
     }
+    if (report?.report_path) logComfyLine(`Repair report saved to ${report.report_path}`);
+    await loadInstalledAddonState(selectedRoot);
   } catch (err) {
-    state.updateAvailable = false;
-    state.updateVersion = null;
-    state.updateInstalling = false;
-    updateUpdateButton();
-    el.updateStatus.textContent = "Error";
-    logLine(String(err));
+    logComfyLine(`Repair failed: ${err}`);
+  } finally {
+    el.repairSelectedInstall.disabled = false;
   }
 });
 
-el.metaCreatorLink.addEventListener("click", async (event) => {
-  const href = el.metaCreatorLink.getAttribute("href") || "";
-  if (!href || href === "#") {
-    event.preventDefault();
+el.rebuildSelectedVenv?.addEventListener("click", async () => {
+  const selectedRoot = String(el.comfyExistingInstall?.value || "").trim();
+  if (!selectedRoot) {
+    logComfyLine("No existing ComfyUI installation selected.");
     return;
   }
-  event.preventDefault();
+  const ok = await showConfirmDialog(
+    "Rebuild the Python environment from scratch? This reinstalls Torch, ComfyUI and custom node requirements and can take a while.",
+  );
+  if (!ok) return;
+  el.rebuildSelectedVenv.disabled = true;
   try {
-    await invoke("open_external_url", { url: href });
+    await invoke("repair_comfyui_install", { comfyuiRoot: selectedRoot });
+    await loadInstalledAddonState(selectedRoot);
   } catch (err) {
-    logLine(`Open owner link failed: ${err}`);
+    logComfyLine(`Venv rebuild failed: ${err}`);
+  } finally {
+    el.rebuildSelectedVenv.disabled = false;
   }
 });
 
-el.workflowYoutubeLink?.addEventListener("click", async (event) => {
-  const href = el.workflowYoutubeLink.getAttribute("href") || "";
-  if (!href || href === "#") {
-    event.preventDefault();
+el.duplicateSelectedInstall?.addEventListener("click", async () => {
+  const selectedRoot = String(el.comfyExistingInstall?.value || "").trim();
+  if (!selectedRoot) {
+    logComfyLine("No existing ComfyUI installation selected.");
+    return;
+  }
+  const ok = await showConfirmDialog(
+    "Duplicate this install into a new ComfyUI-XX folder? The copy gets its own venv with the same Torch profile and custom nodes.",
+  );
+  if (!ok) return;
+  const linkModels = await showConfirmDialog(
+    "Share the models folder with the copy (symlink) instead of copying every model?",
+  );
+  el.duplicateSelectedInstall.disabled = true;
+  try {
+    const report = await invoke("duplicate_comfyui_install", { comfyuiRoot: selectedRoot, linkModels });
+    for (const failure of report.node_failures || []) {
+      logComfyLine(`Custom node requirements failed in the copy: ${failure}`);
+    }
+    await refreshExistingInstallations(el.comfyInstallRoot?.value || "", selectedRoot).catch(() => []);
+  } catch (err) {
+    logComfyLine(`Duplicate failed: ${err}`);
+  } finally {
+    el.duplicateSelectedInstall.disabled = false;
+  }
+});
+
+el.relocateSelectedInstall?.addEventListener("click", async () => {
+  const selectedRoot = String(el.comfyExistingInstall?.value || "").trim();
+  if (!selectedRoot) {
+    logComfyLine("No existing ComfyUI installation selected.");
+    return;
+  }
+  let destination = null;
+  try {
+    destination = await invoke("pick_folder");
+  } catch (err) {
+    logComfyLine(String(err));
+  }
+  if (!destination) return;
+  const ok = await showConfirmDialog(
+    `Move this install into ${destination}? ComfyUI stays unavailable until the move finishes.`,
+  );
+  if (!ok) return;
+  el.relocateSelectedInstall.disabled = true;
+  try {
+    const report = await invoke("relocate_installation", { oldRoot: selectedRoot, newPath: destination });
+    if (report.venv_rebuilt) logComfyLine("The venv was rebuilt at the new location.");
+    for (const link of report.relinked || []) logComfyLine(`Re-pointed shared models link: ${link}`);
+    if (report.settings_updated?.length) {
+      logComfyLine(`Updated saved paths: ${report.settings_updated.join(", ")}.`);
+    }
+    for (const input of [el.comfyRoot, el.comfyRootLora, el.comfyRootWorkflow]) {
+      if (input && input.value.trim() === selectedRoot) input.value = report.comfyui_root;
+    }
+    await refreshExistingInstallations(el.comfyInstallRoot?.value || "", report.comfyui_root).catch(() => []);
+  } catch (err) {
+    logComfyLine(`Move failed: ${err}`);
+  } finally {
+    el.relocateSelectedInstall.disabled = false;
+  }
+});
+
+async function refreshMutationSession(root) {
+  const session = await invoke("get_mutation_session", { comfyuiRoot: root || null });
+  state.mutationSessionActive = Boolean(session?.active);
+  updateMutationSessionButton();
+}
+
+function updateMutationSessionButton() {
+  if (!el.mutationSession) return;
+  el.mutationSession.textContent = state.mutationSessionActive ? "Apply Changes" : "Batch Changes";
+}
+
+el.mutationSession?.addEventListener("click", async () => {
+  el.mutationSession.disabled = true;
+  try {
+    if (state.mutationSessionActive) {
+      logComfyLine("Applying batched changes...");
+      await invoke("commit_mutation", { comfyuiRoot: String(el.comfyRoot.value || "").trim() || null });
+      state.mutationSessionActive = false;
+      logComfyLine("Batched changes applied.");
+      await refreshComfyRuntimeStatus().catch(() => {});
+    } else {
+      const root = String(el.comfyRoot.value || "").trim();
+      if (!root) {
+        logComfyLine("Set ComfyUI folder first.");
+        return;
+      }
+      await invoke("begin_mutation", { comfyuiRoot: root });
+      state.mutationSessionActive = true;
+      logComfyLine("Batching changes: ComfyUI stays stopped until you click Apply Changes.");
+    }
+  } catch (err) {
+    logComfyLine(`Batch changes failed: ${err}`);
+  } finally {
+    el.mutationSession.disabled = false;
+    updateMutationSessionButton();
+  }
+});
+
+el.cancelComponentToggle?.addEventListener("click", async () => {
+  try {
+    const cancelled = await invoke("cancel_comfyui_component_toggle");
+    logComfyLine(cancelled ? "Component change cancellation requested." : "No component change is running.");
+  } catch (err) {
+    logComfyLine(`Cancel failed: ${err}`);
+  }
+});
+
+el.installCustomNodeUrl?.addEventListener("click", async () => {
+  const root = String(el.comfyRoot.value || "").trim();
+  const repoUrl = String(el.customNodeUrl?.value || "").trim();
+  if (!root) {
+    logComfyLine("Set ComfyUI folder first.");
+    return;
+  }
+  if (!repoUrl) {
+    logComfyLine("Enter a custom node GitHub URL first.");
+    return;
+  }
+  el.installCustomNodeUrl.disabled = true;
+  try {
+    const item = await invoke("install_custom_node_by_url", { comfyuiRoot: root, repoUrl });
+    if (item?.status === "ok") {
+      logComfyLine(`Custom node ${item.name} installed.`);
+      el.customNodeUrl.value = "";
+    } else {
+      logComfyLine(`Custom node ${item?.name || repoUrl} failed: ${item?.detail || "unknown error"}`);
+    }
+    await loadInstalledAddonState(root);
+  } catch (err) {
+    logComfyLine(`Custom node install failed: ${err}`);
+  } finally {
+    el.installCustomNodeUrl.disabled = false;
+  }
+});
+
+el.updateAllCustomNodes?.addEventListener("click", async () => {
+  const root = String(el.comfyRoot.value || "").trim();
+  if (!root) {
+    logComfyLine("Set ComfyUI folder first.");
+    return;
+  }
+  const ok = await showConfirmDialog("Update all git custom nodes and reinstall their requirements?");
+  if (!ok) return;
+  el.updateAllCustomNodes.disabled = true;
+  try {
+    const items = await invoke("update_all_custom_nodes", { comfyuiRoot: root });
+    if (!items?.length) {
+      logComfyLine("No git custom nodes found to update.");
+    }
+    for (const item of items || []) {
+      logComfyLine(`  ${item.name}: ${item.status}${DOT_SEP}${item.detail}`);
+    }
+    await loadInstalledAddonState(root);
+  } catch (err) {
+    logComfyLine(`Custom node update failed: ${err}`);
+  } finally {
+    el.updateAllCustomNodes.disabled = false;
+  }
+});
+
+function managerOptionsFromForm() {
+  return {
+    securityLevel: el.managerSecurityLevel?.value || null,
+    networkMode: el.managerNetworkMode?.value || null,
+    channelUrl: String(el.managerChannelUrl?.value || "").trim() || null,
+  };
+}
+
+async function loadManagerConfig(root) {
+  if (!el.managerSecurityLevel) return;
+  const config = await invoke("get_comfyui_manager_config", { comfyuiRoot: root });
+  el.managerSecurityLevel.value = config?.securityLevel || "normal";
+  el.managerNetworkMode.value = config?.networkMode || "public";
+  el.managerChannelUrl.value = config?.channelUrl || "";
+}
+
+async function loadLaunchEnvVars(root) {
+  if (!el.launchEnvVars) return;
+  const vars = await invoke("get_install_env_vars", { comfyuiRoot: root });
+  el.launchEnvVars.value = Object.entries(vars || {})
+    .map(([name, value]) => `${name}=${value}`)
+    .join("\n");
+}
+
+async function loadGpuSelection(root) {
+  if (!el.comfyGpuSelect) return;
+  const selection = await invoke("get_comfyui_gpus", { comfyuiRoot: root });
+  const gpus = Array.isArray(selection?.gpus) ? selection.gpus : [];
+  state.comfyGpuCount = gpus.length;
+  const options = [{ value: "", label: "All GPUs" }].concat(
+    gpus.map((gpu) => {
+      const vram = gpu.vram_mb ? ` (${Math.round(gpu.vram_mb / 1024)} GB)` : "";
+      return { value: String(gpu.index), label: `GPU ${gpu.index}: ${gpu.name || "NVIDIA GPU"}${vram}` };
+    }),
+  );
+  const selected = String(selection?.cuda_devices || "");
+  if (selected && !options.some((option) => option.value === selected)) {
+    options.push({ value: selected, label: `GPUs ${selected}` });
+  }
+  el.comfyGpuSelect.innerHTML = "";
+  for (const option of options) {
+    const node = document.createElement("option");
+    node.value = option.value;
+    node.textContent = option.label;
+    el.comfyGpuSelect.appendChild(node);
+  }
+  el.comfyGpuSelect.value = selected;
+  updateComfyModeUi();
+}
+
+function describeVramFlags(mode) {
+  const flags = Array.isArray(mode?.flags) && mode.flags.length ? mode.flags.join(" ") : "no memory flags";
+  const tier = mode?.detected_tier ? ` (detected ${mode.detected_tier})` : "";
+  return `${flags}${tier}`;
+}
+
+async function loadVramMode(root) {
+  if (!el.comfyVramMode) return;
+  const mode = await invoke("get_comfyui_vram_mode", { comfyuiRoot: root });
+  el.comfyVramMode.value = mode?.mode || "auto";
+  if (el.comfyCacheNone) el.comfyCacheNone.checked = Boolean(mode?.cache_none);
+  el.vramModeRow?.setAttribute("title", `Next start uses ${describeVramFlags(mode)}.`);
+}
+
+async function saveVramMode() {
+  const root = String(el.comfyExistingInstall?.value || "").trim();
+  if (!root || !el.comfyVramMode) return;
+  el.comfyVramMode.disabled = true;
+  if (el.comfyCacheNone) el.comfyCacheNone.disabled = true;
+  try {
+    const saved = await invoke("set_comfyui_vram_mode", {
+      comfyuiRoot: root,
+      mode: String(el.comfyVramMode.value || "auto"),
+      cacheNone: Boolean(el.comfyCacheNone?.checked),
+    });
+    el.vramModeRow?.setAttribute("title", `Next start uses ${describeVramFlags(saved)}.`);
+    logComfyLine(`VRAM mode saved: next start uses ${describeVramFlags(saved)}.`);
+  } catch (err) {
+    logComfyLine(`Saving the VRAM mode failed: ${err}`);
+  } finally {
+    el.comfyVramMode.disabled = false;
+    if (el.comfyCacheNone) el.comfyCacheNone.disabled = false;
+  }
+}
+
+function parseLaunchEnvVars(text) {
+  const vars = {};
+  String(text || "").split("\n").forEach((line) => {
+    const trimmed = line.trim();
+    if (!trimmed || trimmed.startsWith("#")) return;
+    const eq = trimmed.indexOf("=");
+    if (eq <= 0) throw new Error(`Expected NAME=value, got '${trimmed}'.`);
+    vars[trimmed.slice(0, eq).trim()] = trimmed.slice(eq + 1);
+  });
+  return vars;
+}
+
+async function loadEnvSnapshots(root) {
+  if (!el.envSnapshotSelect) return;
+  el.envSnapshotSelect.innerHTML = "";
+  let snapshots = [];
+  if (root) {
+    try {
+      snapshots = await invoke("list_env_snapshots", { comfyuiRoot: root });
+    } catch (_) {
+      snapshots = [];
+    }
+  }
+  if (!snapshots?.length) {
+    const opt = document.createElement("option");
+    opt.value = "";
+    opt.textContent = "No environment snapshots";
+    el.envSnapshotSelect.appendChild(opt);
+    return;
+  }
+  for (const snapshot of snapshots) {
+    const opt = document.createElement("option");
+    opt.value = snapshot.id;
+    const when = new Date(snapshot.created_at * 1000).toLocaleString();
+    const commit = snapshot.comfyui_commit ? `${DOT_SEP}${snapshot.comfyui_commit.slice(0, 7)}` : "";
+    opt.textContent = `${when}${DOT_SEP}${snapshot.reason}${commit}${DOT_SEP}${snapshot.package_count} packages`;
+    el.envSnapshotSelect.appendChild(opt);
+  }
+}
+
+el.refreshEnvSnapshots?.addEventListener("click", async () => {
+  await loadEnvSnapshots(String(el.comfyRoot.value || "").trim());
+});
+
+el.restoreEnvSnapshot?.addEventListener("click", async () => {
+  const root = String(el.comfyRoot.value || "").trim();
+  const snapshotId = String(el.envSnapshotSelect?.value || "").trim();
+  if (!root) {
+    logComfyLine("Set ComfyUI folder first.");
+    return;
+  }
+  if (!snapshotId) {
+    logComfyLine("Select an environment snapshot first.");
+    return;
+  }
+  const ok = await showConfirmDialog(
+    "Restore this snapshot? ComfyUI will be stopped and its packages and commit rolled back.",
+  );
+  if (!ok) return;
+  el.restoreEnvSnapshot.disabled = true;
+  try {
+    const message = await invoke("restore_env_snapshot", { comfyuiRoot: root, snapshotId });
+    logComfyLine(message);
+    await loadInstalledAddonState(root);
+  } catch (err) {
+    logComfyLine(`Snapshot restore failed: ${err}`);
+  } finally {
+    el.restoreEnvSnapshot.disabled = false;
+    await loadEnvSnapshots(root);
+  }
+});
+
+el.pinComfyFrontend?.addEventListener("click", async () => {
+  const root = String(el.comfyRoot.value || "").trim();
+  if (!root) {
+    logComfyLine("Set ComfyUI folder first.");
+    return;
+  }
+  const version = String(el.comfyFrontendVersion?.value || "").trim();
+  el.pinComfyFrontend.disabled = true;
+  try {
+    const result = await invoke("set_comfyui_frontend_version", { comfyuiRoot: root, version: version || null });
+    logComfyLine(String(result));
+  } catch (err) {
+    logComfyLine(`Changing the frontend version failed: ${err}`);
+  } finally {
+    el.pinComfyFrontend.disabled = false;
+  }
+});
+
+el.saveManagerConfig?.addEventListener("click", async () => {
+  const root = String(el.comfyRoot.value || "").trim();
+  if (!root) {
+    logComfyLine("Set ComfyUI folder first.");
+    return;
+  }
+  try {
+    await invoke("set_comfyui_manager_config", { comfyuiRoot: root, options: managerOptionsFromForm() });
+    logComfyLine("ComfyUI-Manager config saved; it applies the next time ComfyUI starts.");
+  } catch (err) {
+    logComfyLine(`Saving ComfyUI-Manager config failed: ${err}`);
+  }
+});
+
+el.comfyGpuSelect?.addEventListener("change", async () => {
+  const root = String(el.comfyExistingInstall?.value || "").trim();
+  if (!root) return;
+  const cudaDevices = String(el.comfyGpuSelect.value || "");
+  el.comfyGpuSelect.disabled = true;
+  try {
+    const saved = await invoke("set_comfyui_gpu", { comfyuiRoot: root, cudaDevices: cudaDevices || null });
+    logComfyLine(saved ? `This install will launch on GPU ${saved}.` : "This install will launch on all GPUs.");
+  } catch (err) {
+    logComfyLine(`Saving the GPU choice failed: ${err}`);
+  } finally {
+    el.comfyGpuSelect.disabled = false;
+  }
+});
+
+el.comfyVramMode?.addEventListener("change", saveVramMode);
+el.comfyCacheNone?.addEventListener("change", saveVramMode);
+
+el.saveLaunchEnv?.addEventListener("click", async () => {
+  const root = String(el.comfyRoot.value || "").trim();
+  if (!root) {
+    logComfyLine("Set ComfyUI folder first.");
+    return;
+  }
+  try {
+    const saved = await invoke("set_install_env_vars", {
+      comfyuiRoot: root,
+      vars: parseLaunchEnvVars(el.launchEnvVars.value),
+    });
+    const count = Object.keys(saved || {}).length;
+    logComfyLine(`Saved ${count} launch environment variable(s); they apply the next time ComfyUI starts.`);
+  } catch (err) {
+    logComfyLine(`Saving launch environment failed: ${err}`);
+  }
+});
+
+el.warmModelCache?.addEventListener("click", async () => {
+  const modelFile = String(el.warmupModelFile?.value || "").trim();
+  if (!modelFile) {
+    logComfyLine("Enter a checkpoint file name to warm up.");
+    return;
+  }
+  const root = String(el.comfyExistingInstall?.value || "").trim() || null;
+  el.warmModelCache.disabled = true;
+  logComfyLine(`Loading ${modelFile} into ComfyUI...`);
+  try {
+    const result = await invoke("warm_model_cache", { comfyuiRoot: root, modelFile });
+    const seconds = (result.load_ms / 1000).toFixed(1);
+    const vram = result.vram_used_bytes != null
+      ? `${DOT_SEP}VRAM ${formatBytes(result.vram_used_bytes)} of ${formatBytes(result.vram_total_bytes || 0)}`
+      : "";
+    const delta = result.vram_delta_bytes ? ` (+${formatBytes(result.vram_delta_bytes)})` : "";
+    logComfyLine(`Warmed ${result.model_file} in ${seconds}s${vram}${delta}`);
+  } catch (err) {
+    logComfyLine(`Model warm-up failed: ${err}`);
+  } finally {
+    el.warmModelCache.disabled = false;
+  }
+});
+
+el.diffSelectedInstall?.addEventListener("click", async () => {
+  const selectedRoot = String(el.comfyExistingInstall?.value || "").trim();
+  const activeRoot = String(el.comfyRoot.value || "").trim();
+  if (!selectedRoot || !activeRoot) {
+    logComfyLine("Select an installation and set a ComfyUI root folder to compare against.");
+    return;
+  }
+  if (normalizeSlashes(selectedRoot) === normalizeSlashes(activeRoot)) {
+    logComfyLine("The selected installation is the active ComfyUI root; pick a different one to compare.");
+    return;
+  }
+  el.diffSelectedInstall.disabled = true;
+  logComfyLine(`Comparing ${activeRoot} with ${selectedRoot}...`);
+  try {
+    const diff = await invoke("diff_installs", { rootA: activeRoot, rootB: selectedRoot });
+    if (diff.identical) {
+      logComfyLine("No differences found.");
+      return;
+    }
+    for (const entry of diff.differences || []) {
+      logComfyLine(`[${entry.category}] ${entry.key}: ${entry.a ?? "(missing)"} -> ${entry.b ?? "(missing)"}`);
+    }
+  } catch (err) {
+    logComfyLine(`Install comparison failed: ${err}`);
+  } finally {
+    el.diffSelectedInstall.disabled = false;
+  }
+});
+
+el.exportInstallReport?.addEventListener("click", async () => {
+  const root = String(el.comfyExistingInstall?.value || "").trim();
+  if (!root) {
+    logComfyLine("Select an installation to report on.");
+    return;
+  }
+  el.exportInstallReport.disabled = true;
+  logComfyLine(`Collecting install report for ${root}...`);
+  try {
+    const path = await invoke("export_install_report", { comfyuiRoot: root, format: "markdown" });
+    logComfyLine(`Install report written to ${path}`);
+    await invoke("open_folder", { path: root });
+  } catch (err) {
+    logComfyLine(`Install report failed: ${err}`);
+  } finally {
+    el.exportInstallReport.disabled = false;
+  }
+});
+
+el.applyComfySettingsTemplate?.addEventListener("click", async () => {
+  const root = String(el.comfyExistingInstall?.value || "").trim();
+  if (!root) {
+    logComfyLine("Select an installation to apply the settings template to.");
+    return;
+  }
+  try {
+    const path = await invoke("apply_comfy_settings_template", { comfyuiRoot: root });
+    logComfyLine(`ComfyUI settings template applied to ${path}. Reload the ComfyUI page to see it.`);
+  } catch (err) {
+    logComfyLine(`Applying settings template failed: ${err}`);
+  }
+});
+
+el.installComfyui.addEventListener("click", async () => {
+  await startComfyInstall(false);
+});
+
+el.addonSageAttention?.addEventListener("change", () => {
+  applyAttentionBackendFromToggle(el.addonSageAttention).catch((err) => logComfyLine(String(err)));
+});
+el.addonSageAttention3?.addEventListener("change", () => {
+  applyAttentionBackendFromToggle(el.addonSageAttention3).catch((err) => logComfyLine(String(err)));
+});
+el.addonFlashAttention?.addEventListener("change", () => {
+  applyAttentionBackendFromToggle(el.addonFlashAttention).catch((err) => logComfyLine(String(err)));
+});
+el.addonNunchaku?.addEventListener("change", () => {
+  applyComfyAddonRules();
+  applyAttentionBackendFromToggle(el.addonNunchaku).catch((err) => logComfyLine(String(err)));
+});
+el.addonAttentionActive?.addEventListener("change", () => {
+  selectActiveAttentionBackend().catch((err) => logComfyLine(String(err)));
+});
+el.addonInsightFace?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.addonInsightFace, "addon_insightface", "InsightFace")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.addonXformers?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.addonXformers, "addon_xformers", "xformers")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.addonTrellis2?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.addonTrellis2, "addon_trellis2", "Trellis2")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.addonPinnedMemory?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.addonPinnedMemory, "addon_pinned_memory", "Pinned Memory")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.nodeComfyuiManager?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.nodeComfyuiManager, "node_comfyui_manager", "comfyui-manager")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.nodeComfyuiEasyUse?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.nodeComfyuiEasyUse, "node_comfyui_easy_use", "ComfyUI-Easy-Use")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.nodeRgthreeComfy?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.nodeRgthreeComfy, "node_rgthree_comfy", "rgthree-comfy")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.nodeComfyuiGguf?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.nodeComfyuiGguf, "node_comfyui_gguf", "ComfyUI-GGUF")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.nodeComfyuiKjnodes?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.nodeComfyuiKjnodes, "node_comfyui_kjnodes", "comfyui-kjnodes")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.nodeComfyuiCrystools?.addEventListener("change", () => {
+  applyComponentToggleFromCheckbox(el.nodeComfyuiCrystools, "node_comfyui_crystools", "comfyui-crystools")
+    .catch((err) => logComfyLine(String(err)));
+});
+el.comfyTorchProfile?.addEventListener("change", () => {
+  state.comfyTorchProfileLocked = true;
+  if (el.comfyTorchProfile.value === "torch_nightly_cu130") {
+    logComfyLine("Torch nightly builds change daily and have no prebuilt attention or Nunchaku wheels.");
+  }
+  applyComfyAddonRules();
+});
+el.comfyInstallBackend?.addEventListener("change", () => {
+  if (el.comfyInstallBackend.value) {
+    logComfyLine("Container installs include ComfyUI and Torch only; add-ons and custom nodes need a host install.");
+  }
+});
+el.runPreflight?.addEventListener("click", () => {
+  runComfyPreflight().then((result) => {
+    if (!result) return;
+    logComfyLine(result.summary || "Preflight completed.");
+  });
+});
+el.showInstallReport?.addEventListener("click", () => {
+  loadInstallReport().catch((err) => logComfyLine(`Install report failed: ${err}`));
+});
+el.buildOfflineCache?.addEventListener("click", () => {
+  buildOfflineInstallCache().catch((err) => logComfyLine(String(err)));
+});
+el.reviewInstallPlan?.addEventListener("click", () => {
+  reviewComfyInstallPlan().catch((err) => logComfyLine(`Install plan failed: ${err}`));
+});
+async function refreshRuntimeCacheInfo() {
+  if (el.runtimeCacheSummary) el.runtimeCacheSummary.textContent = "Measuring...";
+  const info = await invoke("get_runtime_cache_info");
+  const unused = (info.toolchains || []).filter((toolchain) => !toolchain.in_use).length;
+  const parts = [
+    `Total ${formatBytes(info.total_bytes || 0)}`,
+    `uv cache ${formatBytes(info.uv_cache_bytes || 0)}`,
+    `runtime ${formatBytes(info.runtime_bytes || 0)}`,
+  ];
+  if (unused) parts.push(`${unused} unused Python toolchain(s), ${formatBytes(info.reclaimable_bytes || 0)}`);
+  if (el.runtimeCacheSummary) el.runtimeCacheSummary.textContent = parts.join(DOT_SEP);
+  return info;
+}
+
+el.checkRuntimeCache?.addEventListener("click", () => {
+  refreshRuntimeCacheInfo().catch((err) => logComfyLine(`Cache check failed: ${err}`));
+});
+el.cleanRuntimeCache?.addEventListener("click", async () => {
+  el.cleanRuntimeCache.disabled = true;
+  try {
+    const report = await invoke("clean_runtime_cache", {});
+    for (const name of report.removed_toolchains || []) logComfyLine(`Removed unused Python toolchain ${name}.`);
+    for (const message of report.messages || []) logComfyLine(message);
+    await refreshRuntimeCacheInfo();
+  } catch (err) {
+    logComfyLine(`Cache cleanup failed: ${err}`);
+  } finally {
+    el.cleanRuntimeCache.disabled = false;
+  }
+});
+el.comfyResumeBtn?.addEventListener("click", async () => {
+  await resumeComfyInstall();
+});
+el.comfyFreshBtn?.addEventListener("click", async () => {
+  await startComfyInstall(true);
+});
+
+el.comfyClearInstallLog?.addEventListener("click", () => {
+  if (el.comfyInstallLog) el.comfyInstallLog.textContent = "Ready";
+  if (el.comfyInstallConsole) el.comfyInstallConsole.textContent = "";
+  if (el.comfyRuntimeConsole) el.comfyRuntimeConsole.textContent = "";
+});
+
+el.comfyRuntimeConsoleWrap?.addEventListener("toggle", () => {
+  if (el.comfyRuntimeConsoleWrap.open) loadRuntimeConsoleTail().catch(() => {});
+});
+
+el.comfyInstallConsoleWrap?.addEventListener("toggle", () => {
+  if (el.comfyInstallConsoleWrap.open) loadInstallConsoleTail();
+});
+
+el.clearStatusLog?.addEventListener("click", () => {
+  if (el.statusLog) el.statusLog.textContent = "Ready";
+});
+
+el.comfyOpenInstallFolder?.addEventListener("click", async () => {
+  const path = String(el.comfyOpenInstallFolder.dataset.path || "").trim();
+  if (!path) return;
+  try {
+    await invoke("open_folder", { path });
+  } catch (err) {
+    logComfyLine(`Open install folder failed: ${err}`);
+  }
+});
+
+el.comfyOpenVenvShell?.addEventListener("click", async () => {
+  const preferredManageRoot = state.comfyMode === "manage"
+    ? String(el.comfyExistingInstall?.value || "").trim()
+    : "";
+  const path = String(preferredManageRoot || el.comfyOpenVenvShell.dataset.path || "").trim();
+  if (!path) return;
+  try {
+    const terminal = await invoke("open_venv_shell", { comfyuiRoot: path });
+    logComfyLine(`Opened venv shell in ${terminal}.`);
+  } catch (err) {
+    logComfyLine(`Open terminal failed: ${err}`);
+  }
+});
+
+el.comfyLoginService?.addEventListener("click", async () => {
+  const installRoot = String(el.comfyExistingInstall?.value || "").trim();
+  if (!installRoot) return;
+  try {
+    const current = await invoke("get_comfyui_service_status", { installRoot });
+    if (current?.installed) {
+      if (!(await showConfirmDialog(`Remove ${current.unit}? ComfyUI will no longer start at login.`))) return;
+      await invoke("remove_comfyui_service", { installRoot });
+      logComfyLine(`Removed ${current.unit}.`);
+      return;
+    }
+    const status = await invoke("create_comfyui_service", { installRoot });
+    logComfyLine(`ComfyUI will start at login (${status.unit}, ${status.unit_path}).`);
+  } catch (err) {
+    logComfyLine(`Run at login failed: ${err}`);
+  }
+});
+
+el.comfyDesktopEntry?.addEventListener("click", async () => {
+  const installRoot = String(el.comfyExistingInstall?.value || "").trim();
+  if (!installRoot) return;
+  try {
+    const path = await invoke("create_comfyui_desktop_entry", { installRoot });
+    logComfyLine(`App menu launcher written to ${path}.`);
+  } catch (err) {
+    logComfyLine(`Add to app menu failed: ${err}`);
+  }
+});
+
+el.comfyStartInstalled?.addEventListener("click", async () => {
+  const path = runtimeRootForUi();
+  if (!path) return;
+  try {
+    if (state.comfyRuntimeRunning) {
+      state.comfyRuntimeStarting = false;
+      state.comfyRuntimeTarget = "";
+      updateComfyRuntimeButton();
+      const stopped = await invokeTarget("stop_comfyui_root", { comfyuiRoot: path });
+      logComfyLine(stopped ? "ComfyUI stop requested." : "ComfyUI was not running.");
+      await refreshComfyRuntimeStatus();
+    } else {
+      state.comfyRuntimeTarget = comfyInstallNameFromRoot(path);
+      state.comfyRuntimeStarting = true;
+      state.comfyRuntimeRunning = false;
+      updateComfyRuntimeButton();
+      let result = await invokeTarget("start_comfyui_root", { comfyuiRoot: path });
+      if (result?.status === "conflict" && result.conflict) {
+        const conflict = result.conflict;
+        const choices = Array.isArray(conflict.choices) ? conflict.choices : [];
+        let choice = "abort";
+        if (choices.includes("adopt") && window.confirm(`${conflict.detail}\n\nUse the running instance instead of starting a new one?`)) {
+          choice = "adopt";
+        } else if (
+          choices.includes("other_port")
+          && window.confirm(`${conflict.detail}\n\nStart on port ${conflict.suggested_port} instead?`)
+        ) {
+          choice = "other_port";
+        }
+        result = await invokeTarget("start_comfyui_root", { comfyuiRoot: path, onConflict: choice });
+      }
+      if (result?.status === "busy") {
+        state.comfyRuntimeStarting = false;
+        state.comfyRuntimeTarget = "";
+        updateComfyRuntimeButton();
+        const step = result.maintenance?.step ? ` (step: ${result.maintenance.step})` : "";
+        logComfyLine(`ComfyUI cannot start yet: busy ${result.maintenance?.operation || "with changes"}${step}.`);
+      } else if (result?.status === "aborted") {
+        state.comfyRuntimeStarting = false;
+        state.comfyRuntimeTarget = "";
+        updateComfyRuntimeButton();
+        logComfyLine("ComfyUI launch cancelled: port already in use.");
+      } else if (result?.status === "adopted") {
+        logComfyLine(`Using ComfyUI already running on port ${result.port}.`);
+        await refreshComfyRuntimeStatus();
+      } else {
+        logComfyLine(`ComfyUI launch requested on port ${result?.port || 8188}.`);
+      }
+    }
+  } catch (err) {
+    state.comfyRuntimeStarting = false;
+    state.comfyRuntimeRunning = false;
+    state.comfyRuntimeTarget = "";
+    updateComfyRuntimeButton();
+    logComfyLine(`ComfyUI runtime action failed: ${err}`);
+  }
+});
+
+el.pairRemoteAgent?.addEventListener("click", async () => {
+  const host = String(el.remoteAgentHost?.value || "").trim();
+  const token = String(el.remoteAgentToken?.value || "").trim();
+  if (!host || !token) {
+    logComfyLine("Enter the agent host and its pairing token.");
+    return;
+  }
+  el.pairRemoteAgent.disabled = true;
+  try {
+    const result = await invoke("pair_remote_agent", { host, token });
+    state.remoteAgentUrl = result.url;
+    state.remoteEventSeq = 0;
+    if (el.remoteAgentToken) el.remoteAgentToken.value = "";
+    // Skip whatever the agent buffered before pairing.
+    await pollRemoteAgentEvents(false).catch(() => {});
+    const gpu = result.snapshot?.nvidia_gpu_name || "no NVIDIA GPU detected";
+    logComfyLine(`Paired with agent ${result.url} (${gpu}).`);
+  } catch (err) {
+    logComfyLine(`Pairing failed: ${err}`);
+  } finally {
+    el.pairRemoteAgent.disabled = false;
+    updateRemoteAgentUi();
+  }
+});
+
+el.unpairRemoteAgent?.addEventListener("click", async () => {
+  try {
+    await invoke("unpair_remote_agent");
+    state.remoteAgentUrl = "";
+    updateRemoteAgentUi();
+    logComfyLine("Remote agent unpaired.");
+  } catch (err) {
+    logComfyLine(`Unpair failed: ${err}`);
+  }
+});
+
+for (const select of el.operationTargets) {
+  select.addEventListener("change", () => {
+    state.operationTargets[select.dataset.area] = select.value;
+    if (select.dataset.area === "runtime") refreshComfyRuntimeStatus().catch(() => {});
+  });
+}
+
+setInterval(() => {
+  if (remoteTargetActive()) pollRemoteAgentEvents().catch(() => {});
+}, 1000);
+
+el.saveToken.addEventListener("click", async () => {
+  try {
+    await invoke("save_civitai_token", { token: el.civitaiToken.value });
+    const original = el.saveToken.textContent;
+    el.saveToken.textContent = "Saved";
+    el.saveToken.disabled = true;
+    window.setTimeout(() => {
+      el.saveToken.textContent = original || "Save Token";
+      el.saveToken.disabled = false;
+    }, 900);
+    await loadLoraMetadata();
+  } catch (err) {
+    logLine(`Save token failed: ${err}`);
+  }
+});
+
+el.checkUpdates.addEventListener("click", async () => {
+  if (state.updateInstalling) return;
+  if (state.updateAvailable) {
+    try {
+      state.updateInstalling = true;
+      updateUpdateButton();
+      el.updateStatus.textContent = state.updateVersion
+        ? `Installing v${state.updateVersion}...`
+        : "Installing update...";
+      showBlockingOverlay("App updating... It will close now. Please launch it again after update.");
+      await invoke("auto_update_startup");
+    } catch (err) {
+      state.updateInstalling = false;
+      updateUpdateButton();
+      el.updateStatus.textContent = "Error";
+      logLine(String(err));
+      hideStartupOverlay();
+    }
+    return;
+  }
+  try {
+    const result = await invoke("check_updates_now");
+    if (result.available) {
+      state.updateAvailable = true;
+      state.updateVersion = result.version || null;
+      el.updateStatus.textContent = "New update available";
+      updateUpdateButton();
+      logLine(`Update available: v${result.version}`);
+    } else {
+      state.updateAvailable = false;
+      state.updateVersion = null;
+      el.updateStatus.textContent = "Up to date";
+      updateUpdateButton();
+      logLine("No updates available.");
+    }
+  } catch (err) {
+    state.updateAvailable = false;
+    state.updateVersion = null;
+    state.updateInstalling = false;
+    updateUpdateButton();
+    el.updateStatus.textContent = "Error";
+    logLine(String(err));
+  }
+});
+
+el.metaCreatorLink.addEventListener("click", async (event) => {
+  const href = el.metaCreatorLink.getAttribute("href") || "";
+  if (!href || href === "#") {
+    event.preventDefault();
+    return;
+  }
+  event.preventDefault();
+  try {
+    await invoke("open_external_url", { url: href });
+  } catch (err) {
+    logLine(`Open owner link failed: ${err}`);
+  }
+});
+
+el.workflowYoutubeLink?.addEventListener("click", async (event) => {
+  const href = el.workflowYoutubeLink.getAttribute("href") || "";
+  if (!href || href === "#") {
+    event.preventDefault();
     return;
   }
   event.preventDefault();
@@ -2491,19 +4181,68 @@ document.querySelectorAll(".footer-link[data-url]").forEach((button) => {
   });
 });
 
+async function onAppEvent(channel, handler) {
+  appEventHandlers.set(channel, handler);
+  await listen(channel, handler);
+}
+
+function invokeTarget(cmd, args) {
+  const area = Object.keys(REMOTE_OPERATIONS).find((key) => REMOTE_OPERATIONS[key].includes(cmd));
+  if (!area || state.operationTargets[area] !== "remote") {
+    return invoke(cmd, args);
+  }
+  return invoke("invoke_remote_agent", { method: cmd, params: args || {} });
+}
+
+function remoteTargetActive() {
+  return Boolean(state.remoteAgentUrl) && Object.values(state.operationTargets).includes("remote");
+}
+
+// Replays the agent's buffered events through the same handlers local events use.
+async function pollRemoteAgentEvents(dispatch = true) {
+  const events = await invoke("invoke_remote_agent", {
+    method: "agent_events",
+    params: { since: state.remoteEventSeq },
+  });
+  for (const event of events || []) {
+    state.remoteEventSeq = Math.max(state.remoteEventSeq, Number(event.seq) || 0);
+    if (dispatch) appEventHandlers.get(event.channel)?.({ payload: event.payload });
+  }
+}
+
+function updateRemoteAgentUi() {
+  const paired = Boolean(state.remoteAgentUrl);
+  for (const select of el.operationTargets) {
+    const remoteOption = select.querySelector('option[value="remote"]');
+    if (remoteOption) remoteOption.disabled = !paired;
+    if (!paired) {
+      select.value = "local";
+      state.operationTargets[select.dataset.area] = "local";
+    }
+  }
+  if (el.unpairRemoteAgent) el.unpairRemoteAgent.disabled = !paired;
+  if (el.remoteAgentStatus) {
+    el.remoteAgentStatus.textContent = paired ? `Paired with ${state.remoteAgentUrl}` : "No agent paired.";
+  }
+}
+
 async function initEventListeners() {
   if (!listen) {
     logLine("Tauri event bridge unavailable.");
     return;
   }
   try {
-    await listen("download-progress", (event) => {
+    await onAppEvent("download-progress", (event) => {
     const p = event.payload || {};
     if (p.phase === "cancelled") {
       logLine(`[${p.kind}] cancelled.`);
       setProgress(`[${p.kind}] cancelled`);
       state.transfers.clear();
       renderTransfers();
+      if (state.creatorQueue.length) {
+        logLine(`Dropped ${state.creatorQueue.length} queued creator LoRA(s).`);
+        state.creatorQueue = [];
+      }
       endBusyDownload();
       return;
     }
@@ -2514,6 +4253,12 @@ async function initEventListeners() {
       setProgress("Idle");
       renderTransfers();
       endBusyDownload();
+      refreshHostStats();
+      startNextCreatorDownload();
+      return;
+    }
+    if (p.phase === "safe_to_eject" || p.phase === "eject_unsafe") {
+      logLine(p.message || `[${p.kind}] drive status: ${p.phase}`);
       return;
     }
     if (p.phase === "batch_failed") {
@@ -2521,6 +4266,8 @@ async function initEventListeners() {
       setProgress(`[${p.kind}] failed`);
       renderTransfers();
       endBusyDownload();
+      refreshHostStats();
+      startNextCreatorDownload();
       return;
     }
 
@@ -2595,15 +4342,55 @@ async function initEventListeners() {
     }
     });
 
-    await listen("comfyui-install-progress", (event) => {
+    await onAppEvent("model-superseded", (event) => {
+      promptSupersededArtifacts(event.payload || {});
+    });
+    await onAppEvent("creator-updates", (event) => {
+      const fresh = event.payload || [];
+      for (const update of fresh) {
+        logLine(`New from ${update.creator}: ${update.model_name} (${update.version_name}).`);
+      }
+      refreshFollowedCreators();
+    });
+    await onAppEvent("models-migration", (event) => {
+      const p = event.payload || {};
+      if (p.phase === "file") {
+        const done = p.total_bytes ? Math.round((p.bytes_done / p.total_bytes) * 100) : 0;
+        setProgress(`Migrating models ${p.index}/${p.total}${DOT_SEP}${done}%${DOT_SEP}${p.file}`);
+        return;
+      }
+      // Collisions are listed from the command's report once it returns.
+      if (p.phase === "collision") return;
+      if (p.phase === "finished") setProgress("Idle");
+      if (p.message) logComfyLine(p.message);
+    });
+    await onAppEvent("comfyui-install-log", (event) => {
+      const line = event.payload?.line;
+      if (typeof line === "string") appendInstallConsole([line]);
+    });
+    await onAppEvent("comfyui-log", (event) => {
+      const p = event.payload || {};
+      if (!Array.isArray(p.lines)) return;
+      if (normalizeSlashes(p.root) !== normalizeSlashes(runtimeRootForUi())) return;
+      appendRuntimeConsole(p.lines.map(String));
+    });
+    await onAppEvent("comfyui-install-progress", (event) => {
       const p = event.payload || {};
+      if (p.kind === "component_toggle" && p.phase !== "toggle_started" && p.phase !== "toggle_step") {
+        // The toggle's own result is logged by the caller once the invoke settles.
+        return;
+      }
       const message = String(p.message || "").trim();
       if (message) {
         logComfyLine(message);
       }
+      if (p.kind === "comfyui_install") {
+        updateComfyInstallProgress(p);
+      }
       if (p.phase === "failed") {
         state.comfyInstallBusy = false;
         updateComfyInstallButton();
+        refreshComfyResumeState().then(logComfyInstallHistory).catch(() => {});
         return;
       }
       if (p.phase === "finished") {
@@ -2624,6 +4411,7 @@ async function initEventListeners() {
             await refreshComfyUiUpdateStatus(installedRoot).catch(() => {});
             setComfyQuickActions(installBase, installedRoot);
             await refreshComfyRuntimeStatus().catch(() => {});
+            await loadInstallReport(installedRoot).catch(() => {});
             updateComfyModeUi();
           };
 
@@ -2636,7 +4424,7 @@ async function initEventListeners() {
       }
     });
 
-    await listen("comfyui-runtime", (event) => {
+    await onAppEvent("comfyui-runtime", (event) => {
       const p = event.payload || {};
       const phase = String(p.phase || "").trim();
       const msg = String(p.message || "").trim();
@@ -2644,13 +4432,24 @@ async function initEventListeners() {
         logComfyLine(msg);
         logLine(msg);
       }
-      if (phase === "starting") {
+      // Events about another install only change that instance, not this button.
+      const eventRoot = String(p.root || "").trim();
+      if (eventRoot && normalizeSlashes(eventRoot) !== normalizeSlashes(runtimeRootForUi())) {
+        refreshComfyRuntimeStatus().catch(() => {});
+        return;
+      }
+      if (phase === "mutation_session_expired") {
+        state.mutationSessionActive = false;
+        updateMutationSessionButton();
+        return;
+      }
+      if (phase === "starting" || phase === "crashed") {
         state.comfyRuntimeStarting = true;
         state.comfyRuntimeRunning = false;
         updateComfyRuntimeButton();
         return;
       }
-    if (phase === "started") {
+    if (phase === "started" || phase === "restarted") {
       state.comfyRuntimeTarget = "";
       state.comfyRuntimeStarting = false;
       state.comfyRuntimeRunning = true;
@@ -2662,7 +4461,13 @@ async function initEventListeners() {
       openComfyWhenReady().catch(() => {});
       return;
     }
-    if (phase === "stopped" || phase === "start_failed" || phase === "stop_failed") {
+    if (
+      phase === "stopped"
+      || phase === "start_failed"
+      || phase === "start_blocked"
+      || phase === "stop_failed"
+      || phase === "restart_gave_up"
+    ) {
       state.comfyRuntimeTarget = "";
       state.comfyRuntimeStarting = false;
       state.comfyRuntimeRunning = false;
@@ -2675,6 +4480,66 @@ async function initEventListeners() {
   }
 }
 
+function acknowledgeVariantNotices(details) {
+  const notices = details?.notices || [];
+  for (const notice of notices) {
+    if (notice.level !== "warning") logLine(`Note: ${notice.message}`);
+  }
+  const warnings = notices.filter((notice) => notice.level === "warning");
+  if (!warnings.length || details?.warnings_acknowledged) return true;
+  const lines = warnings.map((notice) => `- ${notice.message}`);
+  return window.confirm(`This variant comes with warnings:\n\n${lines.join("\n")}\n\nDownload anyway?`);
+}
+
+// Offered after a higher quantization lands: delete or archive the lower ones it replaces.
+async function promptSupersededArtifacts(report) {
+  const artifacts = report.artifacts || [];
+  if (!artifacts.length) return;
+  const lines = artifacts.map((a) => `- ${a.file_name} (${a.quantization || a.variant}, ${formatBytes(a.size_bytes)})`);
+  const header = `${report.replacement} replaces ${artifacts.length} lower-quality file(s) of the same model ` +
+    `(${formatBytes(report.reclaimable_bytes)}):\n\n${lines.join("\n")}`;
+  let action = null;
+  if (window.confirm(`${header}\n\nDelete them now? Choose Cancel to archive or keep them.`)) {
+    action = "delete";
+  } else if (window.confirm(`Move them to models/.arctic-archive instead, so ComfyUI no longer lists them?`)) {
+    action = "archive";
+  }
+  if (!action) {
+    logLine(`Kept ${artifacts.length} superseded file(s) of ${report.model_id}.`);
+    return;
+  }
+  try {
+    const result = await invokeTarget("resolve_superseded_artifacts", {
+      modelId: report.model_id,
+      variantId: report.variant_id,
+      comfyuiRoot: report.comfyui_root,
+      paths: artifacts.map((a) => a.path),
+      action,
+    });
+    if (result.deleted?.length) {
+      logLine(`Deleted ${result.deleted.length} superseded file(s), freed ${formatBytes(result.freed_bytes)}.`);
+    }
+    if (result.archived?.length) logLine(`Archived ${result.archived.length} superseded file(s).`);
+    for (const failure of result.failed || []) logLine(`Superseded cleanup: ${failure}`);
+    refreshHostStats();
+  } catch (err) {
+    logLine(`Superseded cleanup failed: ${err}`);
+  }
+}
+
+function confirmModelDownloadPlan(plan) {
+  const lines = (plan.artifacts || []).map((a) => {
+    const size = a.size_bytes ? formatBytes(a.size_bytes) : "size unknown";
+    return `${a.exists ? "[on disk] " : ""}${a.file_name} (${size})`;
+  });
+  const unknown = plan.unknown_size_count ? ` + ${plan.unknown_size_count} file(s) of unknown size` : "";
+  let summary = `Download ${formatBytes(plan.download_bytes)}${unknown} into ${plan.destination_root}?`;
+  if (plan.existing_count) summary += `\n${plan.existing_count} file(s) already on disk will be skipped.`;
+  if (plan.free_space_bytes != null) summary += `\nFree space: ${formatBytes(plan.free_space_bytes)}.`;
+  if (plan.enough_space === false) summary += "\nWARNING: not enough free disk space for this download.";
+  return window.confirm(`${summary}\n\n${lines.join("\n")}`);
+}
+
 el.downloadModel.addEventListener("click", async () => {
   if (state.busyDownloads > 0) {
     await requestCancelDownload();
@@ -2684,14 +4549,62 @@ el.downloadModel.addEventListener("click", async () => {
     logLine("Select a model and variant first.");
     return;
   }
-  beginBusyDownload("Starting model download...");
+  const artifactPaths = selectedModelArtifactPaths();
+  if (artifactPaths && !artifactPaths.length) {
+    logLine("Select at least one file, or close the manual file picker.");
+    return;
+  }
+  const request = {
+    modelId: el.modelId.value,
+    variantId: el.variantId.value,
+    ramTier: el.ramTier.value,
+    comfyuiRoot: el.comfyRoot.value,
+    artifactPaths,
+  };
+  setProgress("Estimating download size...");
   try {
-    await invoke("download_model_assets", {
-      modelId: el.modelId.value,
-      variantId: el.variantId.value,
-      ramTier: el.ramTier.value,
-      comfyuiRoot: el.comfyRoot.value,
+    const details = await invoke("get_variant_details", {
+      modelId: request.modelId,
+      variantId: request.variantId,
+      ramTier: request.ramTier,
     });
+    if (!acknowledgeVariantNotices(details)) {
+      setProgress("Idle");
+      logLine("Model download cancelled before start.");
+      return;
+    }
+    if (!details?.warnings_acknowledged) {
+      await invoke("acknowledge_variant_warnings", {
+        modelId: request.modelId,
+        variantId: request.variantId,
+      });
+    }
+    const plan = await invoke("plan_model_download", request);
+    if (plan.compat) {
+      const ok = await showConfirmDialog(`${plan.compat.message}\n\nDownload this variant anyway?`);
+      if (!ok) {
+        setProgress("Idle");
+        logLine("Model download cancelled: the variant does not fit this machine's memory.");
+        return;
+      }
+      await invoke("override_compat_block", {
+        modelId: request.modelId,
+        variantId: request.variantId,
+      });
+    }
+    if (!confirmModelDownloadPlan(plan)) {
+      setProgress("Idle");
+      logLine("Model download cancelled before start.");
+      return;
+    }
+  } catch (err) {
+    setProgress("Idle");
+    logLine(String(err));
+    return;
+  }
+  beginBusyDownload("Starting model download...");
+  try {
+    await invokeTarget("download_model_assets", request);
     logLine("Model download started.");
   } catch (err) {
     logLine(String(err));
@@ -2699,6 +4612,17 @@ el.downloadModel.addEventListener("click", async () => {
   }
 });
 
+el.civitaiDownloadExamples?.addEventListener("change", async () => {
+  const enabled = !!el.civitaiDownloadExamples.checked;
+  try {
+    state.settings = await invoke("set_civitai_examples_enabled", { enabled });
+    logLine(enabled ? "Civitai examples will be saved next to downloaded LoRAs." : "Civitai example download disabled.");
+  } catch (err) {
+    logLine(`Saving example download setting failed: ${err}`);
+    el.civitaiDownloadExamples.checked = !enabled;
+  }
+});
+
 if (el.enableHfXet) {
   el.enableHfXet.addEventListener("change", async () => {
     const enabled = !!el.enableHfXet.checked;
@@ -2725,6 +4649,38 @@ if (el.enableHfXet) {
   });
 }
 
+async function saveComfyAutoRestart() {
+  const attempts = Math.round(Number(el.comfyAutoRestartAttempts?.value || 3));
+  const autoRestart = {
+    enabled: Boolean(el.comfyAutoRestart?.checked),
+    policy: {
+      ...(state.settings?.comfyui_auto_restart?.policy || { initial_delay_secs: 5, max_delay_secs: 60 }),
+      attempts,
+    },
+  };
+  try {
+    const updated = await invoke("set_comfyui_auto_restart", { autoRestart });
+    state.settings = updated;
+    logComfyLine(
+      autoRestart.enabled
+        ? `ComfyUI will be restarted after a crash (up to ${attempts} attempts).`
+        : "ComfyUI auto-restart disabled.",
+    );
+  } catch (err) {
+    logComfyLine(`Saving auto-restart failed: ${err}`);
+    if (el.comfyAutoRestart) {
+      el.comfyAutoRestart.checked = state.settings?.comfyui_auto_restart?.enabled === true;
+    }
+  }
+}
+
+el.comfyAutoRestart?.addEventListener("change", () => {
+  saveComfyAutoRestart().catch(() => {});
+});
+el.comfyAutoRestartAttempts?.addEventListener("change", () => {
+  saveComfyAutoRestart().catch(() => {});
+});
+
 el.downloadLora.addEventListener("click", async () => {
   if (state.busyDownloads > 0) {
     await requestCancelDownload();
@@ -2736,7 +4692,7 @@ el.downloadLora.addEventListener("click", async () => {
   }
   beginBusyDownload("Starting LoRA download...");
   try {
-    await invoke("download_lora_asset", {
+    await invokeTarget("download_lora_asset", {
       loraId: el.loraId.value,
       token: el.civitaiToken.value?.trim() || null,
       comfyuiRoot: el.comfyRootLora.value,
@@ -2747,6 +4703,42 @@ el.downloadLora.addEventListener("click", async () => {
   }
 });
 
+el.followCreator?.addEventListener("click", async () => {
+  const username = String(el.followCreatorName?.value || "").trim();
+  if (!username) {
+    logLine("Enter a Civitai username to follow.");
+    return;
+  }
+  try {
+    renderFollowedCreators(await invoke("follow_civitai_creator", { username }));
+    el.followCreatorName.value = "";
+    logLine("Following creator. Their existing LoRAs are skipped; only new releases are reported.");
+  } catch (err) {
+    logLine(`Follow failed: ${err}`);
+  }
+});
+
+el.checkFollowedCreators?.addEventListener("click", async () => {
+  el.checkFollowedCreators.disabled = true;
+  try {
+    const report = await invoke("check_followed_creators");
+    for (const failure of report.errors || []) logLine(`Creator check failed: ${failure}`);
+    renderFollowedCreators(report);
+  } catch (err) {
+    logLine(String(err));
+  } finally {
+    el.checkFollowedCreators.disabled = false;
+  }
+});
+
+el.clearCreatorUpdates?.addEventListener("click", async () => {
+  try {
+    renderFollowedCreators(await invoke("dismiss_creator_updates", { versionIds: null }));
+  } catch (err) {
+    logLine(`Clearing creator updates failed: ${err}`);
+  }
+});
+
 el.downloadWorkflow?.addEventListener("click", async () => {
   if (state.busyDownloads > 0) {
     await requestCancelDownload();
@@ -2758,7 +4750,7 @@ el.downloadWorkflow?.addEventListener("click", async () => {
   }
   beginBusyDownload("Starting workflow download...");
   try {
-    await invoke("download_workflow_asset", {
+    await invokeTarget("download_workflow_asset", {
       workflowId: el.workflowId.value,
       comfyuiRoot: el.comfyRootWorkflow?.value || el.comfyRoot.value,
     });
@@ -2769,6 +4761,29 @@ el.downloadWorkflow?.addEventListener("click", async () => {
   }
 });
 
+el.downloadAsset?.addEventListener("click", async () => {
+  if (state.busyDownloads > 0) {
+    await requestCancelDownload();
+    return;
+  }
+  if (!el.assetId.value) {
+    logLine("Select an entry first.");
+    return;
+  }
+  beginBusyDownload("Starting download...");
+  try {
+    await invoke("download_catalog_asset", {
+      class: el.assetClass.value,
+      assetId: el.assetId.value,
+      comfyuiRoot: el.comfyRoot.value,
+    });
+    logLine("Download started.");
+  } catch (err) {
+    logLine(String(err));
+    endBusyDownload();
+  }
+});
+
 switchTab("comfyui");
 updateDownloadButtons();
 updateComfyInstallButton();
@@ -2784,6 +4799,8 @@ renderTransfers();
     setStartupStatus("Preparing workspace...");
     await bootstrap();
     hideStartupOverlay();
+    refreshHostStats();
+    refreshFollowedCreators();
     setTimeout(() => {
       invoke("check_updates_now")
         .then((startup) => {
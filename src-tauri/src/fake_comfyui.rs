@@ -0,0 +1,267 @@
+//! Stand-ins for a ComfyUI server and the process that launches it, so the runtime
+//! code can be exercised end-to-end without a GPU or a ComfyUI checkout.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    process::{Child, Command},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+
+/// What the fake server reports and what it has been sent.
+#[derive(Debug)]
+pub struct FakeComfyState {
+    pub vram_total: u64,
+    pub vram_free: u64,
+    /// Bytes of VRAM a queued prompt takes, as if it loaded a model.
+    pub prompt_vram_bytes: u64,
+    pub queue_running: usize,
+    pub queue_pending: usize,
+    /// When set, every prompt finishes with this execution error.
+    pub prompt_error: Option<String>,
+    pub prompts: Vec<Value>,
+    /// `METHOD /path` of every request, in order.
+    pub requests: Vec<String>,
+}
+
+impl Default for FakeComfyState {
+    fn default() -> Self {
+        Self {
+            vram_total: 24 * 1024 * 1024 * 1024,
+            vram_free: 23 * 1024 * 1024 * 1024,
+            prompt_vram_bytes: 0,
+            queue_running: 0,
+            queue_pending: 0,
+            prompt_error: None,
+            prompts: Vec::new(),
+            requests: Vec::new(),
+        }
+    }
+}
+
+/// HTTP server answering `/system_stats`, `/queue`, `/prompt` and `/history/<id>`
+/// the way ComfyUI does. Stops when dropped.
+pub struct FakeComfyServer {
+    port: u16,
+    state: Arc<Mutex<FakeComfyState>>,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl FakeComfyServer {
+    pub fn start() -> Self {
+        Self::start_on(free_port())
+    }
+
+    pub fn start_on(port: u16) -> Self {
+        let listener = TcpListener::bind(("127.0.0.1", port)).expect("bind fake ComfyUI port");
+        let state = Arc::new(Mutex::new(FakeComfyState::default()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let state = state.clone();
+            let shutdown = shutdown.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        handle_connection(stream, &state);
+                    }
+                }
+            })
+        };
+        Self {
+            port,
+            state,
+            shutdown,
+            handle: Some(handle),
+        }
+    }
+
+    /// Starts the server on `port` after `delay`, like a ComfyUI that is still loading.
+    pub fn start_later(port: u16, delay: Duration) -> JoinHandle<Self> {
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            Self::start_on(port)
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub fn with_state<R>(&self, f: impl FnOnce(&mut FakeComfyState) -> R) -> R {
+        f(&mut self.state.lock().expect("fake ComfyUI state"))
+    }
+}
+
+impl Drop for FakeComfyServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the blocking accept so the thread sees the flag.
+        let _ = TcpStream::connect(("127.0.0.1", self.port));
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+pub fn free_port() -> u16 {
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .expect("reserve a free port")
+}
+
+/// How a fake ComfyUI process behaves once launched.
+pub enum FakeLaunch {
+    /// Keeps running until killed, like a healthy server.
+    Runs,
+    /// Exits right away with this code, like a crash on import.
+    Exits(i32),
+}
+
+/// Launches a process in place of `python main.py`.
+pub fn spawn_fake_comfyui(launch: FakeLaunch) -> Child {
+    let mut cmd = match launch {
+        FakeLaunch::Runs => {
+            let mut cmd = Command::new("sleep");
+            cmd.arg("60");
+            cmd
+        }
+        FakeLaunch::Exits(code) => {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(format!("exit {code}"));
+            cmd
+        }
+    };
+    cmd.spawn().expect("spawn fake ComfyUI process")
+}
+
+fn handle_connection(stream: TcpStream, state: &Mutex<FakeComfyState>) {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() || request_line.trim().is_empty() {
+        return;
+    }
+    let mut content_length = 0usize;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0u8; content_length];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let (status, response) = {
+        let mut state = state.lock().expect("fake ComfyUI state");
+        state.requests.push(format!("{method} {path}"));
+        route(&mut state, &method, &path, &body)
+    };
+
+    let payload = response.to_string();
+    let mut writer = &stream;
+    let _ = write!(
+        writer,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{payload}",
+        payload.len()
+    );
+}
+
+fn route(
+    state: &mut FakeComfyState,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> (&'static str, Value) {
+    match (method, path) {
+        ("GET", "/system_stats") => (
+            "200 OK",
+            json!({
+                "system": { "os": "posix", "comfyui_version": "0.0.0-fake", "python_version": "3.12.10" },
+                "devices": [{
+                    "name": "cuda:0 Fake GPU",
+                    "type": "cuda",
+                    "index": 0,
+                    "vram_total": state.vram_total,
+                    "vram_free": state.vram_free,
+                }],
+            }),
+        ),
+        ("GET", "/queue") => {
+            let entries = |count: usize| -> Vec<Value> {
+                (0..count)
+                    .map(|n| json!([n, format!("queued-{n}"), {}, {}, []]))
+                    .collect()
+            };
+            (
+                "200 OK",
+                json!({
+                    "queue_running": entries(state.queue_running),
+                    "queue_pending": entries(state.queue_pending),
+                }),
+            )
+        }
+        ("POST", "/prompt") => match serde_json::from_slice::<Value>(body) {
+            Ok(prompt) if prompt.get("prompt").is_some() => {
+                state.prompts.push(prompt);
+                state.vram_free = state.vram_free.saturating_sub(state.prompt_vram_bytes);
+                let number = state.prompts.len() - 1;
+                (
+                    "200 OK",
+                    json!({ "prompt_id": format!("fake-{number}"), "number": number, "node_errors": {} }),
+                )
+            }
+            _ => (
+                "400 Bad Request",
+                json!({ "error": { "type": "invalid_prompt", "message": "Invalid prompt" } }),
+            ),
+        },
+        ("GET", history) if history.starts_with("/history/") => {
+            let prompt_id = &history["/history/".len()..];
+            let known = prompt_id
+                .strip_prefix("fake-")
+                .and_then(|n| n.parse::<usize>().ok())
+                .is_some_and(|n| n < state.prompts.len());
+            if !known {
+                return ("200 OK", json!({}));
+            }
+            let status = match &state.prompt_error {
+                Some(message) => json!({
+                    "status_str": "error",
+                    "completed": false,
+                    "messages": [["execution_error", { "exception_message": message }]],
+                }),
+                None => json!({ "status_str": "success", "completed": true, "messages": [] }),
+            };
+            (
+                "200 OK",
+                json!({ prompt_id: { "status": status, "outputs": {} } }),
+            )
+        }
+        _ => ("404 Not Found", json!({})),
+    }
+}
@@ -1,20 +1,46 @@
 use arctic_downloader::{
+    agent::{self, AgentServer, DEFAULT_AGENT_PORT},
+    api_access::{issue_token, ApiScope, ApiToken},
     app::{build_context, AppContext},
-    config::AppSettings,
-    download::{CivitaiPreview, DownloadSignal, DownloadStatus},
-    env_flags::auto_update_enabled,
-    model::{LoraDefinition, ModelCatalog, WorkflowDefinition},
+    catalog::OFFICIAL_SOURCE_NAME,
+    compat::{self, CompatBlock, MachineProfile},
+    config::{
+        AppSettings, AutoRestartSettings, CatalogSource, ComfySettingsTemplate,
+        EventExportSettings, EventExportTarget, FollowedCreator, HookSettings, LastSelection,
+        PackageIndexSettings, RetryPolicy, RetrySettings,
+    },
+    download::{
+        artifact_destination_dir, lora_destination, CivitaiCreatorRelease, CivitaiPreview,
+        DownloadSignal, DownloadStatus, PlannedArtifact,
+    },
+    env_flags::{auto_update_enabled, subprocess_idle_timeout},
+    faults,
+    hooks::{run_hook, HookEvent},
+    host_stats::{is_blacklisted, HostStats},
+    inventory::{find_orphaned_models, remove_model_file},
+    model::{
+        quantization_from_file_name, ArtifactOption, AssetClass, CatalogChanges, CatalogConflict,
+        CatalogIssue, LoraDefinition, MasterModel, ModelArtifact, ModelCatalog, ModelVariant,
+        RamTierThresholds, ResolvedModel, ResolvedRamTierThresholds, VariantNotice,
+        WorkflowDefinition,
+    },
+    perf::{self, PerfProfile},
     ram::{detect_ram_profile, RamTier},
+    removable::{removable_mount, sync_filesystem},
+    vram::VramTier,
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    io::IsTerminal,
-    net::{TcpStream, ToSocketAddrs},
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    io::{BufRead, BufReader, IsTerminal, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    os::unix::process::CommandExt,
     path::{Path, PathBuf},
     process::Stdio,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Mutex, OnceLock,
+        Arc, Mutex, OnceLock,
     },
     time::{Duration, Instant},
 };
@@ -22,7 +48,7 @@ use tauri::{
     image::Image,
     menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager, State, WindowEvent,
+    AppHandle, Emitter, Listener, Manager, State, WindowEvent,
 };
 use tauri_plugin_notification::NotificationExt;
 use tokio_util::sync::CancellationToken;
@@ -32,7 +58,12 @@ struct AppState {
     active_cancel: Mutex<Option<CancellationToken>>,
     active_abort: Mutex<Option<tokio::task::AbortHandle>>,
     install_cancel: Mutex<Option<CancellationToken>>,
-    comfyui_process: Mutex<Option<std::process::Child>>,
+    toggle_cancel: Mutex<Option<CancellationToken>>,
+    /// Open change sessions, keyed by canonical install root.
+    mutation_sessions: Mutex<BTreeMap<PathBuf, MutationSession>>,
+    maintenance: Mutex<BTreeMap<PathBuf, MaintenanceStatus>>,
+    /// ComfyUI instances started or adopted by the app, keyed by canonical install root.
+    comfyui_instances: Mutex<BTreeMap<PathBuf, Arc<ComfyInstance>>>,
     quitting: Mutex<bool>,
 }
 
@@ -73,6 +104,8 @@ struct LoraMetadataResponse {
     description: String,
     preview_url: Option<String>,
     preview_kind: String,
+    /// A copy of the preview is on disk; `get_lora_preview_media` serves it.
+    preview_cached: bool,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -97,7 +130,7 @@ struct ComfyInstallRecommendation {
     reason: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ComfyInstallRequest {
     install_root: String,
@@ -111,8 +144,17 @@ struct ComfyInstallRequest {
     include_flash_attention: bool,
     include_insight_face: bool,
     include_nunchaku: bool,
+    /// Which of the selected attention add-ons launches use; the others stay installed.
+    #[serde(default)]
+    attention_backend: Option<String>,
+    #[serde(default)]
+    include_xformers: bool,
     #[serde(default)]
     include_trellis2: bool,
+    /// Triton override, saved to settings so later repairs keep it. See
+    /// `normalize_triton_version`.
+    #[serde(default)]
+    triton_version: Option<String>,
     #[serde(default = "default_true")]
     include_pinned_memory: bool,
     node_comfyui_manager: bool,
@@ -124,6 +166,37 @@ struct ComfyInstallRequest {
     node_comfyui_crystools: bool,
     #[serde(default)]
     force_fresh: bool,
+    #[serde(default)]
+    output_dir: Option<String>,
+    #[serde(default)]
+    input_dir: Option<String>,
+    #[serde(default)]
+    user_dir: Option<String>,
+    #[serde(default)]
+    comfyui_ref: Option<String>,
+    #[serde(default)]
+    comfyui_repo_url: Option<String>,
+    /// `comfyui-frontend-package` version to install over the one requirements.txt pins.
+    #[serde(default)]
+    frontend_version: Option<String>,
+    /// Cache folder from `build_offline_install_cache`; the install then uses nothing else.
+    #[serde(default)]
+    offline_cache: Option<String>,
+    /// Install add-ons even when the hardware compatibility gate blocks them.
+    #[serde(default)]
+    allow_incompatible: bool,
+    /// `podman`, `docker` or `auto` to build a container image instead of a host venv.
+    #[serde(default)]
+    container_engine: Option<String>,
+    /// Headless server profile; `None` for a desktop install.
+    #[serde(default)]
+    server: Option<ComfyServerOptions>,
+    /// ComfyUI-Manager `config.ini` values; unset ones get the installer defaults.
+    #[serde(default)]
+    manager: Option<ComfyManagerOptions>,
+    /// Step an interrupted install stopped at; set only by `resume_comfyui_install`.
+    #[serde(skip)]
+    resume_from: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -131,6 +204,35 @@ struct PreflightItem {
     status: String, // pass | warn | fail
     title: String,
     detail: String,
+    /// `CompatReason` code when the item comes from the hardware compatibility gate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComfyServerOptions {
+    #[serde(default = "default_server_listen")]
+    listen: String,
+    #[serde(default = "default_server_port")]
+    port: u16,
+    /// Keep ComfyUI on loopback and write reverse-proxy configs that require a token.
+    #[serde(default)]
+    auth: bool,
+    #[serde(default = "default_server_proxy_port")]
+    proxy_port: u16,
+}
+
+fn default_server_listen() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_server_port() -> u16 {
+    COMFYUI_DEFAULT_PORT
+}
+
+fn default_server_proxy_port() -> u16 {
+    8080
 }
 
 #[derive(Debug, Serialize)]
@@ -143,6 +245,11 @@ struct ComfyPreflightResponse {
 #[derive(Clone, Debug)]
 struct LinuxPrereqScan {
     distro: String,
+    /// Running inside Flatpak: packages were probed on the host and must be installed there.
+    flatpak: bool,
+    /// `rpm-ostree` or `nixos`: nothing can be installed, so tools are looked up on PATH
+    /// and the missing lists hold binary names rather than packages.
+    immutable: Option<&'static str>,
     missing_required: Vec<String>,
     missing_optional: Vec<String>,
 }
@@ -175,12 +282,64 @@ struct ComfyUiUpdateStatus {
     update_available: bool,
     checked: bool,
     detail: String,
+    /// Ref the install was pinned to at install time; updates follow it, not tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pinned_ref: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct InstallState {
     status: String, // in_progress | completed
     step: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comfyui_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<InstallStepRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstallStepRecord {
+    step: String,
+    status: String, // running | ok | failed | cancelled
+    started_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    finished_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Earlier failed or cancelled attempts at this step.
+    #[serde(default)]
+    retries: u32,
+}
+
+/// One planned or attempted step with its latest attempt, for the resume UI.
+#[derive(Debug, Serialize)]
+struct InstallStepDetail {
+    step: String,
+    status: String, // pending | running | ok | failed | cancelled
+    started_at: Option<u64>,
+    finished_at: Option<u64>,
+    error: Option<String>,
+    retries: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct InstallStateDetail {
+    install_dir: String,
+    status: String,
+    current_step: String,
+    comfyui_ref: Option<String>,
+    steps: Vec<InstallStepDetail>,
+    /// The attempt that stopped the install, when it failed or was cancelled.
+    failure: Option<InstallStepRecord>,
+}
+
+const INSTALL_HISTORY_LIMIT: usize = 200;
+
+fn unix_now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -191,6 +350,7 @@ struct InstallSummaryItem {
 }
 
 const UV_PYTHON_VERSION: &str = "3.12.10";
+const COMFYUI_DEFAULT_PORT: u16 = 8188;
 fn default_true() -> bool {
     true
 }
@@ -200,10 +360,13 @@ fn get_app_snapshot(state: State<'_, AppState>) -> AppSnapshot {
     let catalog = state.context.catalog.catalog_snapshot();
     let (nvidia_gpu_name, nvidia_gpu_vram_mb) = detect_nvidia_gpu();
     let ram_profile = state.context.ram_profile.or_else(detect_ram_profile);
+    let thresholds = ResolvedRamTierThresholds::default()
+        .with_user_overrides(state.context.config.settings().ram_tier_thresholds.as_ref());
     AppSnapshot {
         version: state.context.display_version.clone(),
         total_ram_gb: ram_profile.map(|profile| profile.total_gb),
-        ram_tier: ram_profile.map(|profile| profile.tier.label().to_string()),
+        ram_tier: ram_profile
+            .map(|profile| thresholds.tier_for(profile.total_gb).label().to_string()),
         nvidia_gpu_name,
         nvidia_gpu_vram_mb,
         model_count: catalog.models.len(),
@@ -211,12 +374,32 @@ fn get_app_snapshot(state: State<'_, AppState>) -> AppSnapshot {
     }
 }
 
+#[derive(Debug, Serialize)]
+struct VramTierDetection {
+    tier: Option<String>,
+    label: Option<String>,
+    gpu_name: Option<String>,
+    vram_mb: Option<u64>,
+}
+
+#[tauri::command]
+fn detect_vram_tier() -> VramTierDetection {
+    let (gpu_name, vram_mb) = detect_nvidia_gpu();
+    let tier = vram_mb.map(|mb| VramTier::from_vram_gb(mb as f64 / 1024.0));
+    VramTierDetection {
+        tier: tier.map(|tier| tier.identifier().to_string()),
+        label: tier.map(|tier| tier.description().to_string()),
+        gpu_name,
+        vram_mb,
+    }
+}
+
 fn detect_nvidia_gpu() -> (Option<String>, Option<u64>) {
     let detailed = detect_nvidia_gpu_details();
     (detailed.name, detailed.vram_mb)
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize)]
 struct NvidiaGpuDetails {
     name: Option<String>,
     vram_mb: Option<u64>,
@@ -224,10 +407,22 @@ struct NvidiaGpuDetails {
     compute_capability: Option<String>,
 }
 
+#[derive(Clone, Debug, Default)]
+struct AmdRocmDetails {
+    name: Option<String>,
+    rocm_version: Option<String>,
+    gfx_target: Option<String>,
+}
+
 static GPU_DETAILS_CACHE: OnceLock<Mutex<Option<NvidiaGpuDetails>>> = OnceLock::new();
+static AMD_ROCM_CACHE: OnceLock<Option<AmdRocmDetails>> = OnceLock::new();
 static GPU_DETAILS_PROBE_STARTED: AtomicBool = AtomicBool::new(false);
 static TRAY_MENU_ITEMS: OnceLock<Mutex<Option<TrayMenuItems>>> = OnceLock::new();
 static LINUX_PREREQ_CACHE: OnceLock<Mutex<Option<LinuxPrereqScan>>> = OnceLock::new();
+/// The app handle, set once during setup, for code that runs deep inside blocking
+/// work with no handle of its own: the subprocess watchdog, install and ComfyUI log
+/// forwarding, and the settings lookups (retry, package index, Triton override).
+static GLOBAL_APP: OnceLock<AppHandle> = OnceLock::new();
 
 struct TrayMenuItems {
     start: MenuItem<tauri::Wry>,
@@ -246,8 +441,37 @@ fn linux_prereq_cache() -> &'static Mutex<Option<LinuxPrereqScan>> {
     LINUX_PREREQ_CACHE.get_or_init(|| Mutex::new(None))
 }
 
+fn running_in_flatpak() -> bool {
+    Path::new("/.flatpak-info").exists()
+}
+
+/// Flatpak mounts the host's os-release at /run/host; /etc/os-release is the runtime's.
+fn host_os_release_path() -> &'static Path {
+    let host = Path::new("/run/host/os-release");
+    if running_in_flatpak() && host.exists() {
+        host
+    } else {
+        Path::new("/etc/os-release")
+    }
+}
+
+/// Runs a probe on the host rather than in the Flatpak sandbox. Needs the
+/// `org.freedesktop.Flatpak` talk permission; without it every probe fails.
+fn run_host_command_capture(program: &str, args: &[&str]) -> Result<(String, String), String> {
+    if !running_in_flatpak() {
+        return run_command_capture(program, args, None);
+    }
+    let mut host_args = vec!["--host", program];
+    host_args.extend_from_slice(args);
+    run_command_capture("flatpak-spawn", &host_args, None)
+}
+
+fn flatpak_host_access() -> bool {
+    command_available("flatpak-spawn", &["--host", "true"])
+}
+
 fn detect_linux_distro_family() -> String {
-    let os_release = std::fs::read_to_string("/etc/os-release").unwrap_or_default();
+    let os_release = std::fs::read_to_string(host_os_release_path()).unwrap_or_default();
     let mut id = String::new();
     let mut id_like = String::new();
     for line in os_release.lines() {
@@ -265,11 +489,70 @@ fn detect_linux_distro_family() -> String {
     } else if haystack.contains("fedora") || haystack.contains("rhel") || haystack.contains("centos")
     {
         "fedora".to_string()
+    } else if haystack.contains("suse") {
+        "suse".to_string()
     } else {
         "unknown".to_string()
     }
 }
 
+fn detect_immutable_os() -> Option<&'static str> {
+    let os_release = std::fs::read_to_string(host_os_release_path()).unwrap_or_default();
+    if Path::new("/etc/NIXOS").exists()
+        || os_release
+            .lines()
+            .any(|line| line.trim() == "ID=nixos" || line.trim() == "ID=\"nixos\"")
+    {
+        Some("nixos")
+    } else if Path::new("/run/ostree-booted").exists() {
+        Some("rpm-ostree")
+    } else {
+        None
+    }
+}
+
+/// Build tools are optional here: the prebuilt wheels cover the common install.
+fn immutable_binary_sets() -> (Vec<&'static str>, Vec<&'static str>) {
+    (
+        vec!["git", "curl", "python3"],
+        vec!["wget", "gcc", "g++", "make", "cmake", "ninja"],
+    )
+}
+
+fn binary_on_path(binary: &str) -> bool {
+    run_host_command_capture("sh", &["-c", &format!("command -v {binary}")]).is_ok()
+}
+
+fn immutable_package_name<'a>(system: &str, binary: &'a str) -> &'a str {
+    match (system, binary) {
+        ("nixos", "g++") => "gcc",
+        ("nixos", "make") => "gnumake",
+        ("nixos", other) => other,
+        (_, "g++") => "gcc-c++",
+        (_, "ninja") => "ninja-build",
+        (_, other) => other,
+    }
+}
+
+/// How to provide missing tools on a system the installer cannot add packages to.
+fn immutable_os_guidance(system: &str, binaries: &[String]) -> String {
+    let mut packages: Vec<&str> = binaries
+        .iter()
+        .map(|binary| immutable_package_name(system, binary))
+        .collect();
+    packages.dedup();
+    let packages = packages.join(" ");
+    if system == "nixos" {
+        format!(
+            "add them to environment.systemPackages, or start the app from `nix-shell -p {packages}`"
+        )
+    } else {
+        format!(
+            "run the app inside a toolbox or distrobox container and `sudo dnf install {packages}` there, or layer them with `rpm-ostree install {packages}` and reboot"
+        )
+    }
+}
+
 fn linux_package_sets(distro: &str) -> (Vec<&'static str>, Vec<&'static str>) {
     match distro {
         "arch" => (
@@ -310,18 +593,24 @@ fn linux_package_sets(distro: &str) -> (Vec<&'static str>, Vec<&'static str>) {
             ],
             vec!["mesa-libGL"],
         ),
+        "suse" => (
+            vec![
+                "git", "curl", "wget", "python3", "gcc", "gcc-c++", "make", "cmake", "ninja",
+            ],
+            vec!["Mesa-libGL1"],
+        ),
         _ => (vec!["git", "curl", "wget", "python3"], Vec::new()),
     }
 }
 
 fn linux_package_installed(distro: &str, package: &str) -> bool {
-    if package == "wget" && command_available("wget", &["--version"]) {
+    if package == "wget" && run_host_command_capture("wget", &["--version"]).is_ok() {
         return true;
     }
     let probe = match distro {
-        "arch" => run_command_capture("pacman", &["-Q", package], None),
-        "debian" => run_command_capture("dpkg", &["-s", package], None),
-        "fedora" => run_command_capture("rpm", &["-q", package], None),
+        "arch" => run_host_command_capture("pacman", &["-Q", package]),
+        "debian" => run_host_command_capture("dpkg", &["-s", package]),
+        "fedora" | "suse" => run_host_command_capture("rpm", &["-q", package]),
         _ => return true,
     };
     probe.is_ok()
@@ -329,6 +618,23 @@ fn linux_package_installed(distro: &str, package: &str) -> bool {
 
 fn scan_linux_prereqs() -> Result<LinuxPrereqScan, String> {
     let distro = detect_linux_distro_family();
+    if let Some(system) = detect_immutable_os() {
+        let (required, optional) = immutable_binary_sets();
+        let missing = |binaries: Vec<&str>| {
+            binaries
+                .into_iter()
+                .filter(|binary| !binary_on_path(binary))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        return Ok(LinuxPrereqScan {
+            distro,
+            flatpak: running_in_flatpak(),
+            immutable: Some(system),
+            missing_required: missing(required),
+            missing_optional: missing(optional),
+        });
+    }
     let (required, optional) = linux_package_sets(&distro);
     let missing_required = required
         .into_iter()
@@ -342,6 +648,8 @@ fn scan_linux_prereqs() -> Result<LinuxPrereqScan, String> {
         .collect::<Vec<_>>();
     Ok(LinuxPrereqScan {
         distro,
+        flatpak: running_in_flatpak(),
+        immutable: None,
         missing_required,
         missing_optional,
     })
@@ -370,82 +678,119 @@ fn warm_linux_prereq_cache_background() {
     });
 }
 
-fn install_missing_linux_prereqs(scan: &LinuxPrereqScan) -> Result<(), String> {
-    if scan.missing_required.is_empty() {
-        return Ok(());
-    }
+fn linux_prereq_install_commands(scan: &LinuxPrereqScan) -> Result<Vec<Vec<&str>>, String> {
     let mut package_args: Vec<&str> = scan.missing_required.iter().map(String::as_str).collect();
     match scan.distro.as_str() {
         "arch" => {
-            run_privileged_command("pacman", &["-Sy"], None)?;
-            let mut args = vec!["-S", "--needed", "--noconfirm"];
+            let mut args = vec!["pacman", "-Sy", "--needed", "--noconfirm"];
             args.append(&mut package_args);
-            run_privileged_command("pacman", &args, None)?;
+            Ok(vec![args])
         }
         "debian" => {
-            run_privileged_command("apt", &["update"], None)?;
-            let mut args = vec!["install", "-y"];
+            // sudo and pkexec drop the environment, so pass it through env(1).
+            let mut args = vec![
+                "env",
+                "DEBIAN_FRONTEND=noninteractive",
+                "apt-get",
+                "install",
+                "-y",
+            ];
             args.append(&mut package_args);
-            run_privileged_command("apt", &args, None)?;
+            Ok(vec![vec!["apt-get", "update"], args])
         }
         "fedora" => {
-            run_privileged_command("dnf", &["makecache"], None)?;
-            let mut args = vec!["install", "-y"];
+            let mut args = vec!["dnf", "install", "-y"];
             args.append(&mut package_args);
-            run_privileged_command("dnf", &args, None)?;
+            Ok(vec![vec!["dnf", "makecache"], args])
         }
-        _ => {
-            return Err(
-                "Unsupported Linux distribution for automatic package install. Install required packages manually."
-                    .to_string(),
-            );
+        "suse" => {
+            let mut args = vec!["zypper", "--non-interactive", "install", "--no-recommends"];
+            args.append(&mut package_args);
+            Ok(vec![vec!["zypper", "--non-interactive", "refresh"], args])
         }
+        _ => Err(
+            "Unsupported Linux distribution for automatic package install. Install required packages manually."
+                .to_string(),
+        ),
+    }
+}
+
+/// Copy-pasteable command for a host terminal, for when the app cannot install itself.
+fn linux_prereq_manual_command(scan: &LinuxPrereqScan) -> Option<String> {
+    let commands = linux_prereq_install_commands(scan).ok()?;
+    Some(format!(
+        "sudo sh -c {}",
+        shell_quote(&privileged_script(&commands))
+    ))
+}
+
+fn install_missing_linux_prereqs(scan: &LinuxPrereqScan) -> Result<(), String> {
+    if scan.missing_required.is_empty() {
+        return Ok(());
+    }
+    let commands = linux_prereq_install_commands(scan)?;
+    if scan.flatpak {
+        run_host_privileged_commands(&commands)
+    } else {
+        run_privileged_commands(&commands)
     }
-    Ok(())
 }
 
 fn query_nvidia_gpu_details_blocking() -> NvidiaGpuDetails {
-    let (stdout, _) = match run_command_capture(
+    query_nvidia_gpus_blocking()
+        .into_iter()
+        .next()
+        .map(|gpu| gpu.details)
+        .unwrap_or_default()
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct NvidiaGpuEntry {
+    /// nvidia-smi index, which is what `CUDA_VISIBLE_DEVICES` takes.
+    index: u32,
+    #[serde(flatten)]
+    details: NvidiaGpuDetails,
+}
+
+/// Every GPU nvidia-smi lists, in index order.
+fn query_nvidia_gpus_blocking() -> Vec<NvidiaGpuEntry> {
+    let Ok((stdout, _)) = run_command_capture(
         "nvidia-smi",
         &[
-            "--query-gpu=name,memory.total,driver_version,compute_cap",
+            "--query-gpu=index,name,memory.total,driver_version,compute_cap",
             "--format=csv,noheader,nounits",
         ],
         None,
-    ) {
-        Ok(out) => out,
-        Err(_) => return NvidiaGpuDetails::default(),
+    ) else {
+        return Vec::new();
     };
-    let first = stdout
+    let field = |value: Option<&str>| {
+        value
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+    };
+    stdout
         .lines()
         .map(str::trim)
-        .find(|line| !line.is_empty())
-        .unwrap_or_default();
-    if first.is_empty() {
-        return NvidiaGpuDetails::default();
-    }
-
-    let mut parts = first.split(',').map(str::trim);
-    let name = parts
-        .next()
-        .filter(|value| !value.is_empty())
-        .map(ToOwned::to_owned);
-    let vram_mb = parts.next().and_then(|value| value.parse::<u64>().ok());
-    let driver_version = parts
-        .next()
-        .filter(|value| !value.is_empty())
-        .map(ToOwned::to_owned);
-    let compute_capability = parts
-        .next()
-        .filter(|value| !value.is_empty())
-        .map(ToOwned::to_owned);
-
-    NvidiaGpuDetails {
-        name,
-        vram_mb,
-        driver_version,
-        compute_capability,
-    }
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let mut parts = line.split(',').map(str::trim);
+            let index = parts.next()?.parse::<u32>().ok()?;
+            let name = field(parts.next());
+            let vram_mb = parts.next().and_then(|value| value.parse::<u64>().ok());
+            let driver_version = field(parts.next());
+            let compute_capability = field(parts.next());
+            Some(NvidiaGpuEntry {
+                index,
+                details: NvidiaGpuDetails {
+                    name,
+                    vram_mb,
+                    driver_version,
+                    compute_capability,
+                },
+            })
+        })
+        .collect()
 }
 
 fn is_nvidia_hopper_sm90() -> bool {
@@ -469,6 +814,32 @@ fn is_nvidia_hopper_sm90() -> bool {
         .unwrap_or(false)
 }
 
+/// GPU architecture a prebuilt accelerator wheel is compiled for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WheelArch {
+    Generic,
+    /// Hopper (H100/H200).
+    Sm90,
+    /// Consumer Blackwell (RTX 50-series, RTX PRO 6000), compute capability 12.x.
+    Sm120,
+}
+
+fn detect_wheel_arch() -> WheelArch {
+    if is_nvidia_hopper_sm90() {
+        return WheelArch::Sm90;
+    }
+    let blackwell = detect_nvidia_gpu_details()
+        .compute_capability
+        .as_deref()
+        .and_then(compat::parse_compute_capability)
+        .is_some_and(|(major, _)| major == 12);
+    if blackwell {
+        WheelArch::Sm120
+    } else {
+        WheelArch::Generic
+    }
+}
+
 fn detect_nvidia_gpu_details() -> NvidiaGpuDetails {
     if let Ok(guard) = gpu_details_cache().lock() {
         if let Some(details) = guard.clone() {
@@ -478,7 +849,9 @@ fn detect_nvidia_gpu_details() -> NvidiaGpuDetails {
 
     if !GPU_DETAILS_PROBE_STARTED.swap(true, Ordering::SeqCst) {
         std::thread::spawn(|| {
+            let started = Instant::now();
             let details = query_nvidia_gpu_details_blocking();
+            perf::record_startup("first_gpu_probe", started);
             let has_data = details.name.is_some()
                 || details.vram_mb.is_some()
                 || details.driver_version.is_some();
@@ -496,6 +869,87 @@ fn detect_nvidia_gpu_details() -> NvidiaGpuDetails {
     NvidiaGpuDetails::default()
 }
 
+const ROCM_ROOT: &str = "/opt/rocm";
+/// gfx targets with official PyTorch ROCm wheels (RDNA3/RDNA4 desktop, CDNA2/3).
+const ROCM_SUPPORTED_GFX: &[&str] = &[
+    "gfx1100", "gfx1101", "gfx1102", "gfx1200", "gfx1201", "gfx90a", "gfx942",
+];
+
+fn query_amd_rocm_details_blocking() -> Option<AmdRocmDetails> {
+    let rocm_root = Path::new(ROCM_ROOT);
+    let smi_out = run_command_capture("rocm-smi", &["--showproductname", "--csv"], None)
+        .ok()
+        .map(|(stdout, _)| stdout);
+    if smi_out.is_none() && !rocm_root.exists() {
+        return None;
+    }
+
+    // CSV header looks like `device,Card Series,Card Model,Card Vendor,Card SKU,...`.
+    let name = smi_out.as_deref().and_then(|csv| {
+        let mut lines = csv.lines().map(str::trim).filter(|line| !line.is_empty());
+        let header: Vec<&str> = lines.next()?.split(',').collect();
+        let column = header
+            .iter()
+            .position(|col| col.eq_ignore_ascii_case("card series"))?;
+        lines
+            .next()?
+            .split(',')
+            .nth(column)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+    });
+    let rocm_version = std::fs::read_to_string(rocm_root.join(".info").join("version"))
+        .ok()
+        .map(|raw| raw.trim().split('-').next().unwrap_or_default().to_string())
+        .filter(|value| !value.is_empty());
+    let gfx_target = run_command_capture("rocminfo", &[], None)
+        .ok()
+        .and_then(|(stdout, _)| {
+            stdout.lines().find_map(|line| {
+                line.trim()
+                    .strip_prefix("Name:")
+                    .map(str::trim)
+                    .filter(|value| value.starts_with("gfx"))
+                    .map(ToOwned::to_owned)
+            })
+        });
+
+    Some(AmdRocmDetails {
+        name,
+        rocm_version,
+        gfx_target,
+    })
+}
+
+/// AMD GPU / ROCm runtime details, probed once per process. `None` when neither
+/// `rocm-smi` nor `/opt/rocm` is present.
+fn detect_amd_rocm_details() -> Option<AmdRocmDetails> {
+    AMD_ROCM_CACHE
+        .get_or_init(query_amd_rocm_details_blocking)
+        .clone()
+}
+
+fn is_rocm_torch_profile(profile: &str) -> bool {
+    profile.contains("_rocm")
+}
+
+fn is_cpu_torch_profile(profile: &str) -> bool {
+    profile == "torch_cpu"
+}
+
+/// Opt-in profile tracking PyTorch nightlies, for GPUs newer than any stable profile.
+/// Never recommended automatically and has no prebuilt accelerator wheels.
+const TORCH_NIGHTLY_PROFILE: &str = "torch_nightly_cu130";
+
+fn is_nightly_torch_profile(profile: &str) -> bool {
+    profile.starts_with("torch_nightly")
+}
+
+fn torch_profile_uses_cuda(profile: &str) -> bool {
+    !is_rocm_torch_profile(profile) && !is_cpu_torch_profile(profile)
+}
+
 #[tauri::command]
 fn get_comfyui_install_recommendation() -> ComfyInstallRecommendation {
     let gpu = detect_nvidia_gpu_details();
@@ -547,6 +1001,29 @@ fn get_comfyui_install_recommendation() -> ComfyInstallRecommendation {
         };
     }
 
+    if gpu.name.is_none() {
+        if let Some(amd) = detect_amd_rocm_details() {
+            return ComfyInstallRecommendation {
+                gpu_name: amd.name.or(amd.gfx_target),
+                driver_version: amd.rocm_version.map(|v| format!("ROCm {v}")),
+                torch_profile: "torch280_rocm64".to_string(),
+                torch_label: "Torch 2.8.0 + ROCm 6.4".to_string(),
+                reason: "Detected AMD GPU with ROCm runtime.".to_string(),
+            };
+        }
+        // The nvidia-smi probe runs in the background; only fall back to CPU when the
+        // NVIDIA driver is absent altogether.
+        if !Path::new("/dev/nvidiactl").exists() {
+            return ComfyInstallRecommendation {
+                gpu_name: None,
+                driver_version: None,
+                torch_profile: "torch_cpu".to_string(),
+                torch_label: "Torch 2.8.0 CPU only".to_string(),
+                reason: "No supported GPU detected; ComfyUI will run on the CPU.".to_string(),
+            };
+        }
+    }
+
     ComfyInstallRecommendation {
         gpu_name: gpu.name,
         driver_version: gpu.driver_version,
@@ -601,27 +1078,22 @@ fn write_extra_model_paths_yaml(
 
     let target = comfy_dir.join("extra_model_paths.yaml");
     let example = comfy_dir.join("extra_model_paths.yaml.example");
-    if !target.exists() {
-        if example.exists() {
-            std::fs::rename(&example, &target).map_err(|err| {
-                format!(
-                    "failed to rename '{}' to '{}': {err}",
-                    example.display(),
-                    target.display()
-                )
-            })?;
-        } else {
-            return Err(
-                "extra_model_paths.yaml.example was not found in ComfyUI install folder."
-                    .to_string(),
-            );
-        }
+    // Older checkouts and installs made by other tools may not ship the example; the
+    // file is fully rewritten below either way.
+    if !target.exists() && example.exists() {
+        std::fs::rename(&example, &target).map_err(|err| {
+            format!(
+                "failed to rename '{}' to '{}': {err}",
+                example.display(),
+                target.display()
+            )
+        })?;
     }
 
     let base = yaml_single_quote(&normalize_canonical_path(base_path).to_string_lossy());
     let default_value = if is_default { "true" } else { "false" };
     let yaml = [
-        "# Managed by Arctic ComfyUI Helper.".to_string(),
+        MANAGED_EXTRA_MODEL_PATHS_HEADER.to_string(),
         "comfyui:".to_string(),
         format!("  base_path: {base}"),
         format!("  is_default: {default_value}"),
@@ -655,53 +1127,676 @@ fn write_extra_model_paths_yaml(
     Ok(target)
 }
 
-fn is_forbidden_install_path(path: &Path) -> bool {
-    let _ = path;
-    false
+/// Header `write_extra_model_paths_yaml` puts on the files it owns.
+const MANAGED_EXTRA_MODEL_PATHS_HEADER: &str = "# Managed by Arctic ComfyUI Helper.";
+
+/// Folders the generated `extra_model_paths.yaml` points at, relative to the shared root.
+const SHARED_MODEL_SUBDIRS: &[&str] = &[
+    "checkpoints",
+    "text_encoders",
+    "clip",
+    "clip_vision",
+    "configs",
+    "controlnet",
+    "diffusion_models",
+    "unet",
+    "embeddings",
+    "loras",
+    "upscale_models",
+    "vae",
+    "audio_encoders",
+    "model_patches",
+];
+
+#[derive(Debug, Serialize)]
+struct SharedModelsSyncReport {
+    shared_models_root: String,
+    use_as_default: bool,
+    installs: Vec<InstallSummaryItem>,
 }
 
-fn find_in_progress_install(base_root: &Path) -> Option<(PathBuf, InstallState)> {
-    if let Ok(entries) = std::fs::read_dir(base_root) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if !path.is_dir() {
-                continue;
-            }
-            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
-                continue;
-            };
-            if !(name == "ComfyUI"
-                || (name.starts_with("ComfyUI-") && name.len() == "ComfyUI-00".len()))
-            {
-                continue;
-            }
-            let state_path = path.join(".arctic_install_state.json");
-            if !state_path.exists() {
+/// Every ComfyUI checkout the app knows about: the install base and its children, the
+/// selected root and the last install, each with or without a `ComfyUI/` level.
+fn known_comfyui_roots(state: &AppState) -> Vec<PathBuf> {
+    let settings = state.context.config.settings();
+    let mut candidates: Vec<PathBuf> = [settings.comfyui_root, settings.comfyui_last_install_dir]
+        .into_iter()
+        .flatten()
+        .collect();
+    if let Some(base) = settings.comfyui_install_base {
+        if let Ok(entries) = std::fs::read_dir(&base) {
+            candidates.extend(entries.flatten().map(|entry| entry.path()));
+        }
+        candidates.push(base);
+    }
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        for root in [candidate.join("ComfyUI"), candidate] {
+            if !root.join("main.py").is_file() {
                 continue;
             }
-            let data = match std::fs::read(&state_path) {
-                Ok(d) => d,
-                Err(_) => continue,
-            };
-            let parsed: InstallState = match serde_json::from_slice(&data) {
-                Ok(v) => v,
-                Err(_) => continue,
-            };
-            if parsed.status == "in_progress" {
-                return Some((path, parsed));
+            let root = std::fs::canonicalize(&root).unwrap_or(root);
+            if !roots.contains(&root) {
+                roots.push(root);
             }
         }
     }
-    None
+    roots
 }
 
-fn choose_install_folder(base_root: &Path, force_fresh: bool) -> PathBuf {
-    if !force_fresh {
-        if let Some((existing, _)) = find_in_progress_install(base_root) {
-            return existing;
-        }
+/// Points every known install at one shared models folder by (re)writing its
+/// `extra_model_paths.yaml`. A hand-written file is kept as `.bak` before the first
+/// overwrite.
+#[tauri::command]
+fn sync_shared_models_dir(
+    state: State<'_, AppState>,
+    shared_models_root: Option<String>,
+    use_as_default: Option<bool>,
+) -> Result<SharedModelsSyncReport, String> {
+    let settings = state.context.config.settings();
+    let shared_root = normalize_optional_path(shared_models_root.as_deref())?
+        .or(settings.shared_models_root)
+        .ok_or_else(|| "Choose a shared models folder first.".to_string())?;
+    let use_as_default = use_as_default.unwrap_or(settings.shared_models_use_default);
+
+    for subdir in SHARED_MODEL_SUBDIRS {
+        let dir = shared_root.join("models").join(subdir);
+        std::fs::create_dir_all(&dir)
+            .map_err(|err| format!("failed to create '{}': {err}", dir.display()))?;
     }
-
+    let stored_root = shared_root.clone();
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings.shared_models_root = Some(stored_root);
+            settings.shared_models_use_default = use_as_default;
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut installs = Vec::new();
+    for root in known_comfyui_roots(&state) {
+        let name = root.to_string_lossy().to_string();
+        let yaml_path = root.join("extra_model_paths.yaml");
+        let hand_written = std::fs::read_to_string(&yaml_path)
+            .map(|content| !content.starts_with(MANAGED_EXTRA_MODEL_PATHS_HEADER))
+            .unwrap_or(false);
+        let backup = root.join("extra_model_paths.yaml.bak");
+        if hand_written && !backup.exists() {
+            if let Err(err) = std::fs::copy(&yaml_path, &backup) {
+                installs.push(InstallSummaryItem {
+                    name,
+                    status: "failed".to_string(),
+                    detail: format!("could not back up the existing config: {err}"),
+                });
+                continue;
+            }
+        }
+        installs.push(
+            match write_extra_model_paths_yaml(&root, &shared_root, use_as_default) {
+                Ok(path) => InstallSummaryItem {
+                    name,
+                    status: "ok".to_string(),
+                    detail: if hand_written {
+                        format!("updated {} (previous copy in .bak)", path.display())
+                    } else {
+                        format!("wrote {}", path.display())
+                    },
+                },
+                Err(err) => InstallSummaryItem {
+                    name,
+                    status: "failed".to_string(),
+                    detail: err,
+                },
+            },
+        );
+    }
+
+    Ok(SharedModelsSyncReport {
+        shared_models_root: shared_root.to_string_lossy().to_string(),
+        use_as_default,
+        installs,
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct ModelsFolderCheck {
+    /// `models` for the checkout's own folder, `extra` for the `extra_model_paths.yaml` base.
+    location: String,
+    folder: String,
+    path: String,
+    status: String, // pass | warn | fail
+    exists: bool,
+    writable: bool,
+    /// Where the folder (or one of its parents) resolves to when it goes through a symlink.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved: Option<String>,
+    cross_filesystem: bool,
+    /// Entries inside the folder whose symlink target no longer exists.
+    broken_links: Vec<String>,
+    detail: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ModelsFoldersDiagnosis {
+    comfyui_root: String,
+    ok: bool,
+    folders: Vec<ModelsFolderCheck>,
+}
+
+/// Creates and removes a probe file; mode bits alone miss ACLs and read-only mounts.
+fn dir_accepts_writes(dir: &Path) -> bool {
+    let probe = dir.join(format!(".arctic-write-probe-{}", std::process::id()));
+    let ok = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    ok
+}
+
+fn check_models_folder(
+    location: &str,
+    folder: &str,
+    path: &Path,
+    root_dev: Option<u64>,
+) -> ModelsFolderCheck {
+    use std::os::unix::fs::MetadataExt;
+
+    let mut check = ModelsFolderCheck {
+        location: location.to_string(),
+        folder: folder.to_string(),
+        path: path.to_string_lossy().to_string(),
+        status: "pass".to_string(),
+        exists: false,
+        writable: false,
+        resolved: None,
+        cross_filesystem: false,
+        broken_links: Vec::new(),
+        detail: String::new(),
+    };
+    let mut problems: Vec<String> = Vec::new();
+
+    let is_link = std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink());
+    let Ok(meta) = std::fs::metadata(path) else {
+        if is_link {
+            check.status = "fail".to_string();
+            let target = std::fs::read_link(path)
+                .map(|target| target.to_string_lossy().to_string())
+                .unwrap_or_default();
+            check.detail = format!("symlink points to missing {target}");
+        } else {
+            check.status = "warn".to_string();
+            check.detail = "missing; created on the first download into it".to_string();
+        }
+        return check;
+    };
+    if !meta.is_dir() {
+        check.status = "fail".to_string();
+        check.detail = "exists but is not a folder".to_string();
+        return check;
+    }
+    check.exists = true;
+
+    if let Ok(canonical) = std::fs::canonicalize(path) {
+        if canonical != path {
+            check.resolved = Some(canonical.to_string_lossy().to_string());
+            check.cross_filesystem = root_dev.is_some_and(|dev| dev != meta.dev());
+        }
+    }
+    if check.cross_filesystem {
+        problems.push(format!(
+            "links to another filesystem ({}); downloads are copied instead of moved and containers may not see it",
+            check.resolved.as_deref().unwrap_or_default()
+        ));
+    }
+
+    check.writable = dir_accepts_writes(path);
+    if !check.writable {
+        problems.push("not writable by this user".to_string());
+    }
+
+    if let Ok(entries) = std::fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            let dangling = entry.file_type().is_ok_and(|kind| kind.is_symlink())
+                && std::fs::metadata(&entry_path).is_err();
+            if dangling {
+                check
+                    .broken_links
+                    .push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        check.broken_links.sort();
+    }
+    if !check.broken_links.is_empty() {
+        problems.push(format!(
+            "broken symlinks: {}",
+            check.broken_links.join(", ")
+        ));
+    }
+
+    if !check.writable {
+        check.status = "fail".to_string();
+    } else if !problems.is_empty() {
+        check.status = "warn".to_string();
+    }
+    check.detail = if problems.is_empty() {
+        "ok".to_string()
+    } else {
+        problems.join("; ")
+    };
+    check
+}
+
+/// Checks every models subfolder ComfyUI reads, in the checkout and under the
+/// `extra_model_paths.yaml` base, for the problems behind "downloaded but not listed".
+#[tauri::command]
+fn diagnose_models_folders(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<ModelsFoldersDiagnosis, String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let root_dev = std::fs::metadata(&root).ok().map(|meta| meta.dev());
+
+    let mut locations = vec![("models", root.join("models"))];
+    if let Some(config) = comfy_extra_model_config(&root) {
+        locations.push(("extra", config.base_path.join("models")));
+    }
+
+    let mut folders = Vec::new();
+    for (location, models_dir) in locations {
+        let parent = check_models_folder(location, "models", &models_dir, root_dev);
+        let parent_exists = parent.exists;
+        folders.push(parent);
+        if !parent_exists {
+            continue;
+        }
+        for subdir in SHARED_MODEL_SUBDIRS {
+            folders.push(check_models_folder(
+                location,
+                subdir,
+                &models_dir.join(subdir),
+                root_dev,
+            ));
+        }
+    }
+
+    Ok(ModelsFoldersDiagnosis {
+        comfyui_root: root.to_string_lossy().to_string(),
+        ok: folders.iter().all(|check| check.status != "fail"),
+        folders,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ModelMigrationEvent {
+    phase: String,
+    index: Option<usize>,
+    total: Option<usize>,
+    file: Option<String>,
+    bytes_done: u64,
+    total_bytes: u64,
+    message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ModelMigrationReport {
+    source_models: String,
+    dest_models: String,
+    mode: String,
+    migrated: usize,
+    bytes: u64,
+    /// One entry per file that already existed in the destination.
+    collisions: Vec<InstallSummaryItem>,
+    failed: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MigrationCollision {
+    Skip,
+    Rename,
+    Replace,
+}
+
+/// Files and symlinks under `models`, as paths relative to it with their size.
+fn migration_entries(models: &Path) -> Vec<(PathBuf, u64)> {
+    let mut entries = Vec::new();
+    let mut stack = vec![models.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let size = std::fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+            if let Ok(relative) = path.strip_prefix(models) {
+                entries.push((relative.to_path_buf(), size));
+            }
+        }
+    }
+    entries.sort();
+    entries
+}
+
+/// `name (ComfyUI-01).ext`, numbered further if that is taken too.
+fn migration_rename_target(target: &Path, source_label: &str) -> PathBuf {
+    let stem = target
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = target
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut attempt = 1;
+    loop {
+        let suffix = if attempt == 1 {
+            format!(" ({source_label})")
+        } else {
+            format!(" ({source_label} {attempt})")
+        };
+        let candidate = target.with_file_name(format!("{stem}{suffix}{ext}"));
+        if std::fs::symlink_metadata(&candidate).is_err() {
+            return candidate;
+        }
+        attempt += 1;
+    }
+}
+
+/// Renames when both sides share a filesystem, otherwise copies and removes the source.
+fn move_model_file(source: &Path, target: &Path) -> Result<(), String> {
+    if std::fs::rename(source, target).is_ok() {
+        return Ok(());
+    }
+    if std::fs::symlink_metadata(source).is_ok_and(|meta| meta.file_type().is_symlink()) {
+        let link = std::fs::read_link(source)
+            .map_err(|err| format!("failed to read link {}: {err}", source.display()))?;
+        std::os::unix::fs::symlink(&link, target)
+            .map_err(|err| format!("failed to create link {}: {err}", target.display()))?;
+    } else {
+        std::fs::copy(source, target).map_err(|err| {
+            let _ = std::fs::remove_file(target);
+            format!("failed to copy to {}: {err}", target.display())
+        })?;
+    }
+    std::fs::remove_file(source)
+        .map_err(|err| format!("copied, but failed to remove {}: {err}", source.display()))
+}
+
+fn migrate_model_entry(source: &Path, target: &Path, link: bool) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+    }
+    if link {
+        let resolved = std::fs::canonicalize(source)
+            .map_err(|err| format!("failed to resolve {}: {err}", source.display()))?;
+        std::os::unix::fs::symlink(&resolved, target)
+            .map_err(|err| format!("failed to link {}: {err}", target.display()))
+    } else {
+        move_model_file(source, target)
+    }
+}
+
+/// Moves (or symlinks) every file under `<source_root>/models` into
+/// `<dest_root>/models`, keeping the folder layout. Files already in the destination
+/// are skipped, stored under a suffixed name, or replaced, depending on
+/// `on_collision`. Progress goes out as `models-migration` events.
+#[tauri::command]
+async fn migrate_models(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    source_root: String,
+    dest_root: String,
+    move_or_link: String,
+    on_collision: Option<String>,
+) -> Result<ModelMigrationReport, String> {
+    let link = match move_or_link.trim() {
+        "move" => false,
+        "link" => true,
+        other => return Err(format!("Unknown mode '{other}'; use move or link.")),
+    };
+    let collision = match on_collision.as_deref().map(str::trim).unwrap_or("skip") {
+        "skip" => MigrationCollision::Skip,
+        "rename" => MigrationCollision::Rename,
+        "replace" => MigrationCollision::Replace,
+        other => {
+            return Err(format!(
+                "Unknown collision policy '{other}'; use skip, rename or replace."
+            ))
+        }
+    };
+    ensure_no_active_download(&state)?;
+    if state
+        .install_cancel
+        .lock()
+        .map(|active| active.is_some())
+        .unwrap_or(true)
+    {
+        return Err("A ComfyUI install is running. Migrate models after it finishes.".to_string());
+    }
+    if comfyui_runtime_running(&state) {
+        return Err("Stop ComfyUI before migrating models.".to_string());
+    }
+
+    let source_root = normalize_optional_path(Some(&source_root))?
+        .ok_or_else(|| "Choose the install to migrate models from.".to_string())?;
+    let dest_root = normalize_optional_path(Some(&dest_root))?
+        .ok_or_else(|| "Choose the install to migrate models into.".to_string())?;
+    let source_models = std::fs::canonicalize(source_root.join("models"))
+        .map_err(|_| format!("{} has no models folder.", source_root.display()))?;
+    if !dest_root.is_dir() {
+        return Err(format!("{} does not exist.", dest_root.display()));
+    }
+    let dest_models = dest_root.join("models");
+    std::fs::create_dir_all(&dest_models)
+        .map_err(|err| format!("failed to create {}: {err}", dest_models.display()))?;
+    let dest_models = std::fs::canonicalize(&dest_models).unwrap_or(dest_models);
+    if source_models.starts_with(&dest_models) || dest_models.starts_with(&source_models) {
+        return Err("Source and destination models folders overlap.".to_string());
+    }
+    let source_label = source_root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .filter(|name| name != "ComfyUI")
+        .or_else(|| {
+            source_root
+                .parent()
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "migrated".to_string());
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = migration_entries(&source_models);
+        let total = entries.len();
+        let total_bytes: u64 = entries.iter().map(|(_, size)| size).sum();
+        let emit = |phase: &str, index: Option<usize>, file: Option<String>, bytes_done: u64, message: Option<String>| {
+            let _ = app.emit(
+                "models-migration",
+                ModelMigrationEvent {
+                    phase: phase.to_string(),
+                    index,
+                    total: Some(total),
+                    file,
+                    bytes_done,
+                    total_bytes,
+                    message,
+                },
+            );
+        };
+        emit(
+            "started",
+            None,
+            None,
+            0,
+            Some(format!(
+                "{} {total} file(s) from {} to {}.",
+                if link { "Linking" } else { "Moving" },
+                source_models.display(),
+                dest_models.display()
+            )),
+        );
+
+        let mut report = ModelMigrationReport {
+            source_models: source_models.to_string_lossy().to_string(),
+            dest_models: dest_models.to_string_lossy().to_string(),
+            mode: if link { "link" } else { "move" }.to_string(),
+            migrated: 0,
+            bytes: 0,
+            collisions: Vec::new(),
+            failed: Vec::new(),
+        };
+        let mut bytes_done = 0u64;
+        for (index, (relative, size)) in entries.into_iter().enumerate() {
+            let source = source_models.join(&relative);
+            let mut target = dest_models.join(&relative);
+            let name = relative.to_string_lossy().to_string();
+            emit("file", Some(index + 1), Some(name.clone()), bytes_done, None);
+
+            if let Ok(existing) = std::fs::metadata(&target) {
+                let already_linked = std::fs::canonicalize(&target).ok()
+                    == std::fs::canonicalize(&source).ok();
+                let same_size = existing.len() == size;
+                let (status, detail) = if already_linked {
+                    ("identical", "destination already points at this file".to_string())
+                } else {
+                    match collision {
+                        MigrationCollision::Skip => (
+                            "skipped",
+                            if same_size {
+                                "same name and size in the destination; left in place".to_string()
+                            } else {
+                                "a different file with this name is in the destination; left in place".to_string()
+                            },
+                        ),
+                        MigrationCollision::Rename => {
+                            target = migration_rename_target(&target, &source_label);
+                            (
+                                "renamed",
+                                format!(
+                                    "stored as {}",
+                                    target.file_name().unwrap_or_default().to_string_lossy()
+                                ),
+                            )
+                        }
+                        MigrationCollision::Replace => match std::fs::remove_file(&target) {
+                            Ok(()) => ("replaced", "destination file replaced".to_string()),
+                            Err(err) => ("failed", format!("could not remove the destination file: {err}")),
+                        },
+                    }
+                };
+                emit(
+                    "collision",
+                    Some(index + 1),
+                    Some(name.clone()),
+                    bytes_done,
+                    Some(format!("{status}: {detail}")),
+                );
+                report.collisions.push(InstallSummaryItem {
+                    name: name.clone(),
+                    status: status.to_string(),
+                    detail,
+                });
+                if matches!(status, "identical" | "skipped" | "failed") {
+                    bytes_done += size;
+                    continue;
+                }
+            }
+
+            match migrate_model_entry(&source, &target, link) {
+                Ok(()) => {
+                    report.migrated += 1;
+                    report.bytes += size;
+                }
+                Err(err) => {
+                    log::warn!("Model migration failed for {name}: {err}");
+                    report.failed.push(format!("{name}: {err}"));
+                }
+            }
+            bytes_done += size;
+        }
+
+        emit(
+            "finished",
+            None,
+            None,
+            bytes_done,
+            Some(format!(
+                "{} {} file(s), {} collision(s), {} failure(s).",
+                if link { "Linked" } else { "Moved" },
+                report.migrated,
+                report.collisions.len(),
+                report.failed.len()
+            )),
+        );
+        log::info!(
+            "Migrated {} model file(s) from {} to {} ({})",
+            report.migrated,
+            report.source_models,
+            report.dest_models,
+            report.mode
+        );
+        Ok(report)
+    })
+    .await
+    .map_err(|err| format!("model migration task failed: {err}"))?
+}
+
+fn is_forbidden_install_path(path: &Path) -> bool {
+    let _ = path;
+    false
+}
+
+fn find_in_progress_install(base_root: &Path) -> Option<(PathBuf, InstallState)> {
+    if let Ok(entries) = std::fs::read_dir(base_root) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !(name == "ComfyUI"
+                || (name.starts_with("ComfyUI-") && name.len() == "ComfyUI-00".len()))
+            {
+                continue;
+            }
+            let state_path = path.join(".arctic_install_state.json");
+            if !state_path.exists() {
+                continue;
+            }
+            let data = match std::fs::read(&state_path) {
+                Ok(d) => d,
+                Err(_) => continue,
+            };
+            let parsed: InstallState = match serde_json::from_slice(&data) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            if parsed.status == "in_progress" {
+                return Some((path, parsed));
+            }
+        }
+    }
+    None
+}
+
+fn choose_install_folder(base_root: &Path, force_fresh: bool) -> PathBuf {
+    if !force_fresh {
+        if let Some((existing, _)) = find_in_progress_install(base_root) {
+            return existing;
+        }
+    }
+
     for index in 1..=99u32 {
         let candidate = base_root.join(format!("ComfyUI-{index:02}"));
         if !candidate.exists() {
@@ -780,28 +1875,485 @@ fn normalize_canonical_path(path: &Path) -> PathBuf {
     path.to_path_buf()
 }
 
+/// The request an install was started with, kept so `resume_comfyui_install` can
+/// repeat it without the frontend having to remember every option.
+const INSTALL_REQUEST_FILE: &str = ".arctic_install_request.json";
+
+fn write_install_request(install_root: &Path, request: &ComfyInstallRequest) {
+    match serde_json::to_vec_pretty(request) {
+        Ok(data) => {
+            if let Err(err) = std::fs::write(install_root.join(INSTALL_REQUEST_FILE), data) {
+                log::warn!("Failed to save install request: {err}");
+            }
+        }
+        Err(err) => log::warn!("Failed to serialize install request: {err}"),
+    }
+}
+
+fn read_install_request(install_root: &Path) -> Option<ComfyInstallRequest> {
+    let data = std::fs::read(install_root.join(INSTALL_REQUEST_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+fn read_install_state(install_root: &Path) -> Option<InstallState> {
+    let data = std::fs::read(install_root.join(".arctic_install_state.json")).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
 fn write_install_state(install_root: &Path, status: &str, step: &str) {
+    arm_forced_step_failure((status == "in_progress").then_some(step));
+    advance_install_progress(status, step);
+    // Keep the pinned ref across step updates; only the clone step sets it.
+    let comfyui_ref = read_install_state(install_root).and_then(|state| state.comfyui_ref);
+    write_install_state_with_ref(install_root, status, step, comfyui_ref);
+}
+
+fn write_install_state_with_ref(
+    install_root: &Path,
+    status: &str,
+    step: &str,
+    comfyui_ref: Option<String>,
+) {
+    let now = unix_now_secs();
+    let mut history = read_install_state(install_root)
+        .map(|state| state.history)
+        .unwrap_or_default();
+    let same_step_running = history
+        .last()
+        .is_some_and(|last| last.status == "running" && last.step == step);
+    if !same_step_running {
+        if let Some(last) = history.last_mut().filter(|last| last.status == "running") {
+            last.status = "ok".to_string();
+            last.finished_at = Some(now);
+        }
+        let completed = status == "completed";
+        let retries = history
+            .iter()
+            .filter(|record| {
+                record.step == step && matches!(record.status.as_str(), "failed" | "cancelled")
+            })
+            .count() as u32;
+        history.push(InstallStepRecord {
+            step: step.to_string(),
+            status: if completed { "ok" } else { "running" }.to_string(),
+            started_at: now,
+            finished_at: completed.then_some(now),
+            error: None,
+            retries,
+        });
+    }
+    if history.len() > INSTALL_HISTORY_LIMIT {
+        history.drain(..history.len() - INSTALL_HISTORY_LIMIT);
+    }
+
     let path = install_root.join(".arctic_install_state.json");
     let payload = InstallState {
         status: status.to_string(),
         step: step.to_string(),
+        comfyui_ref,
+        history,
     };
     if let Ok(data) = serde_json::to_vec_pretty(&payload) {
         let _ = std::fs::write(path, data);
     }
 }
 
-fn push_preflight(
-    items: &mut Vec<PreflightItem>,
-    status: &str,
-    title: &str,
-    detail: impl Into<String>,
-) {
-    items.push(PreflightItem {
-        status: status.to_string(),
-        title: title.to_string(),
-        detail: detail.into(),
-    });
+fn record_install_step_failure(install_root: &Path, error: &str, cancelled: bool) {
+    let Some(mut state) = read_install_state(install_root) else {
+        return;
+    };
+    let Some(last) = state
+        .history
+        .last_mut()
+        .filter(|last| last.status == "running")
+    else {
+        return;
+    };
+    last.status = if cancelled { "cancelled" } else { "failed" }.to_string();
+    last.finished_at = Some(unix_now_secs());
+    last.error = Some(error.to_string());
+    if let Ok(data) = serde_json::to_vec_pretty(&state) {
+        let _ = std::fs::write(install_root.join(".arctic_install_state.json"), data);
+    }
+}
+
+fn last_install_failure(state: &InstallState) -> Option<&InstallStepRecord> {
+    state
+        .history
+        .last()
+        .filter(|last| matches!(last.status.as_str(), "failed" | "cancelled"))
+}
+
+/// Latest attempt per step in the order steps were first reached, followed by the
+/// saved request's remaining steps as `pending`.
+fn install_state_detail(
+    install_root: &Path,
+    apply_settings_template: bool,
+) -> Option<InstallStateDetail> {
+    let state = read_install_state(install_root)?;
+    let mut steps: Vec<InstallStepDetail> = Vec::new();
+    for record in &state.history {
+        let detail = InstallStepDetail {
+            step: record.step.clone(),
+            status: record.status.clone(),
+            started_at: Some(record.started_at),
+            finished_at: record.finished_at,
+            error: record.error.clone(),
+            retries: record.retries,
+        };
+        match steps
+            .iter_mut()
+            .find(|existing| existing.step == record.step)
+        {
+            Some(existing) => *existing = detail,
+            None => steps.push(detail),
+        }
+    }
+    if let Some(request) = read_install_request(install_root) {
+        let planned = planned_install_steps(
+            &request,
+            !install_root.join("main.py").exists(),
+            apply_settings_template,
+        );
+        for step in planned {
+            if !steps.iter().any(|existing| existing.step == step) {
+                steps.push(InstallStepDetail {
+                    step: step.to_string(),
+                    status: "pending".to_string(),
+                    started_at: None,
+                    finished_at: None,
+                    error: None,
+                    retries: 0,
+                });
+            }
+        }
+    }
+    Some(InstallStateDetail {
+        install_dir: install_root.to_string_lossy().to_string(),
+        failure: last_install_failure(&state).cloned(),
+        status: state.status,
+        current_step: state.step,
+        comfyui_ref: state.comfyui_ref,
+        steps,
+    })
+}
+
+fn pinned_comfyui_ref(install_root: &Path) -> Option<String> {
+    read_install_state(install_root)
+        .and_then(|state| state.comfyui_ref)
+        .and_then(|value| requested_comfyui_ref(Some(&value)).ok().flatten())
+}
+
+const COMFYUI_REPO_URL: &str = "https://github.com/comfyanonymous/ComfyUI.git";
+
+/// Accepts only `https://host/owner/name` remotes so a fork, mirror or custom node
+/// cannot smuggle git options, local paths or credential-bearing URLs into a clone.
+fn validate_repo_url(raw: &str, label: &str) -> Result<reqwest::Url, String> {
+    let trimmed = raw.trim();
+    let url = reqwest::Url::parse(trimmed)
+        .map_err(|err| format!("Invalid {label} URL '{trimmed}': {err}"))?;
+    if url.scheme() != "https" {
+        return Err(format!("{label} URL must use https:// (got '{trimmed}')."));
+    }
+    if url.host_str().unwrap_or_default().is_empty() {
+        return Err(format!("{label} URL has no host: '{trimmed}'."));
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(format!(
+            "{label} URL must not embed credentials; configure a git credential helper instead."
+        ));
+    }
+    let path = url.path().trim_matches('/');
+    if path.split('/').filter(|part| !part.is_empty()).count() < 2 {
+        return Err(format!(
+            "{label} URL must point at a repository (owner/name): '{trimmed}'."
+        ));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(format!(
+            "{label} URL must not contain a query or fragment: '{trimmed}'."
+        ));
+    }
+    Ok(url)
+}
+
+fn validate_comfyui_repo_url(raw: &str) -> Result<String, String> {
+    validate_repo_url(raw, "ComfyUI repository")?;
+    Ok(raw.trim().to_string())
+}
+
+/// Custom nodes are GitHub-only; returns the clone URL and the `custom_nodes/` folder name.
+fn custom_node_repo_from_url(raw: &str) -> Result<(String, String), String> {
+    let url = validate_repo_url(raw, "Custom node repository")?;
+    if !url
+        .host_str()
+        .is_some_and(|host| host.eq_ignore_ascii_case("github.com"))
+    {
+        return Err(format!(
+            "Custom node repository must be hosted on github.com (got '{}').",
+            raw.trim()
+        ));
+    }
+    let mut parts = url.path().trim_matches('/').split('/');
+    let owner = parts.next().unwrap_or_default();
+    let name = parts
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(".git")
+        .to_string();
+    if parts.next().is_some() {
+        return Err(format!(
+            "Custom node URL must be the repository root, e.g. https://github.com/owner/name (got '{}').",
+            raw.trim()
+        ));
+    }
+    let valid_name = |value: &str| {
+        !value.is_empty()
+            && !value.starts_with('.')
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    if !valid_name(owner) || !valid_name(&name) {
+        return Err(format!(
+            "Custom node URL has an invalid owner or repository name: '{}'.",
+            raw.trim()
+        ));
+    }
+    Ok((format!("https://github.com/{owner}/{name}"), name))
+}
+
+/// Abbreviated or full commit hash, safe to pass to git as a revision.
+fn is_git_sha(value: &str) -> bool {
+    (7..=40).contains(&value.len()) && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The trimmed ComfyUI ref from an install request, if one was given. Refs reach
+/// `git fetch` and `git checkout`, so anything git could read as an option or that is
+/// neither a commit id nor a valid ref name is refused.
+fn requested_comfyui_ref(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(git_ref) = raw.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    if git_ref.starts_with('-') {
+        return Err(format!("ComfyUI ref '{git_ref}' must not start with '-'."));
+    }
+    if is_git_sha(git_ref) {
+        return Ok(Some(git_ref.to_string()));
+    }
+    let valid = match std::process::Command::new("git")
+        .args(["check-ref-format", "--allow-onelevel", git_ref])
+        .output()
+    {
+        Ok(output) => output.status.success(),
+        // git is installed with the other prerequisites later on; until then only
+        // plain ref names are let through.
+        Err(_) => {
+            !git_ref.contains("..")
+                && git_ref
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'))
+        }
+    };
+    if !valid {
+        return Err(format!(
+            "ComfyUI ref '{git_ref}' is not a valid branch, tag or commit."
+        ));
+    }
+    Ok(Some(git_ref.to_string()))
+}
+
+fn checkout_comfyui_ref(comfy_dir: &Path, git_ref: &str) -> Result<(), String> {
+    let git_ref =
+        requested_comfyui_ref(Some(git_ref))?.ok_or_else(|| "ComfyUI ref is empty.".to_string())?;
+    let git_ref = git_ref.as_str();
+    // Commits that are not reachable from a branch or tag head are not part of
+    // the default clone, so fetch the ref explicitly before checking it out.
+    if git_commit_for_ref(comfy_dir, &format!("{git_ref}^{{commit}}")).is_none() {
+        run_command_with_retry(
+            "git",
+            &["fetch", "--end-of-options", "origin", git_ref],
+            Some(comfy_dir),
+            retry_settings().git,
+        )?;
+    }
+    let remote_branch = format!("origin/{git_ref}");
+    if git_commit_for_ref(comfy_dir, &format!("refs/remotes/{remote_branch}")).is_some() {
+        run_command_with_retry(
+            "git",
+            &[
+                "checkout",
+                "-B",
+                git_ref,
+                "--end-of-options",
+                &remote_branch,
+            ],
+            Some(comfy_dir),
+            RetryPolicy::once(),
+        )
+    } else {
+        let target = if git_commit_for_ref(comfy_dir, &format!("{git_ref}^{{commit}}")).is_some() {
+            git_ref
+        } else {
+            "FETCH_HEAD"
+        };
+        run_command_with_retry(
+            "git",
+            &["checkout", "-B", "master", "--end-of-options", target],
+            Some(comfy_dir),
+            RetryPolicy::once(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ComfyDataDirs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output_dir: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    input_dir: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_dir: Option<PathBuf>,
+}
+
+impl ComfyDataDirs {
+    fn from_raw(
+        output_dir: Option<&str>,
+        input_dir: Option<&str>,
+        user_dir: Option<&str>,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            output_dir: normalize_optional_path(output_dir)?,
+            input_dir: normalize_optional_path(input_dir)?,
+            user_dir: normalize_optional_path(user_dir)?,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.output_dir.is_none() && self.input_dir.is_none() && self.user_dir.is_none()
+    }
+
+    fn entries(&self) -> Vec<(&'static str, &Path)> {
+        [
+            ("--output-directory", self.output_dir.as_deref()),
+            ("--input-directory", self.input_dir.as_deref()),
+            ("--user-directory", self.user_dir.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(flag, path)| path.map(|p| (flag, p)))
+        .collect()
+    }
+}
+
+/// Where data-dir overrides lived before they moved into `InstallConfig`.
+const LEGACY_DATA_DIRS_FILE: &str = ".arctic_data_dirs.json";
+
+fn read_legacy_data_dirs(comfy_root: &Path) -> Option<ComfyDataDirs> {
+    let data = std::fs::read(comfy_root.join(LEGACY_DATA_DIRS_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// The install's data-dir overrides. An install without an install config yet still
+/// reads them from the legacy sidecar, which the first config write takes over.
+fn read_comfy_data_dirs(comfy_root: &Path) -> ComfyDataDirs {
+    match read_install_config(comfy_root) {
+        Some(config) => config.data_dirs,
+        None => read_legacy_data_dirs(comfy_root).unwrap_or_default(),
+    }
+}
+
+fn write_comfy_data_dirs(
+    settings: &AppSettings,
+    comfy_root: &Path,
+    dirs: &ComfyDataDirs,
+) -> Result<(), String> {
+    for (_, dir) in dirs.entries() {
+        std::fs::create_dir_all(dir)
+            .map_err(|err| format!("failed to prepare data folder '{}': {err}", dir.display()))?;
+    }
+    update_install_config(settings, comfy_root, |config| {
+        config.data_dirs = dirs.clone();
+    })
+}
+
+/// Merges `values` into the install's default-user `comfy.settings.json`, keeping any
+/// setting the template does not mention.
+fn apply_comfy_settings_values(
+    comfy_root: &Path,
+    values: &BTreeMap<String, serde_json::Value>,
+) -> Result<PathBuf, String> {
+    let user_dir = read_comfy_data_dirs(comfy_root)
+        .user_dir
+        .unwrap_or_else(|| comfy_root.join("user"));
+    let path = user_dir.join("default").join("comfy.settings.json");
+    let mut current = match std::fs::read(&path) {
+        Ok(data) => serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(&data)
+            .map_err(|err| format!("Refusing to overwrite unreadable {}: {err}", path.display()))?,
+        Err(_) => serde_json::Map::new(),
+    };
+    for (key, value) in values {
+        current.insert(key.clone(), value.clone());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to prepare '{}': {err}", parent.display()))?;
+    }
+    let data = serde_json::to_vec_pretty(&current).map_err(|err| err.to_string())?;
+    std::fs::write(&path, data)
+        .map_err(|err| format!("failed to write '{}': {err}", path.display()))?;
+    Ok(path)
+}
+
+fn push_preflight(
+    items: &mut Vec<PreflightItem>,
+    status: &str,
+    title: &str,
+    detail: impl Into<String>,
+) {
+    items.push(PreflightItem {
+        status: status.to_string(),
+        title: title.to_string(),
+        detail: detail.into(),
+        reason: None,
+    });
+}
+
+fn machine_profile() -> MachineProfile {
+    let gpu = detect_nvidia_gpu_details();
+    MachineProfile {
+        ram_gb: detect_ram_profile().map(|profile| profile.total_gb),
+        vram_gb: gpu.vram_mb.map(|mb| mb as f64 / 1024.0),
+        compute_capability: gpu
+            .compute_capability
+            .as_deref()
+            .and_then(compat::parse_compute_capability),
+    }
+}
+
+/// Gate results for the torch profile and attention add-ons an install request selects.
+fn install_compat_blocks(request: &ComfyInstallRequest, profile: &str) -> Vec<CompatBlock> {
+    let machine = machine_profile();
+    let profile_block = compat::check_torch_profile(&machine, profile);
+    let addon_blocks = [
+        (request.include_sage_attention, "sage", "SageAttention"),
+        (request.include_sage_attention3, "sage3", "SageAttention3"),
+        (request.include_flash_attention, "flash", "FlashAttention"),
+        (request.include_nunchaku, "nunchaku", "Nunchaku"),
+    ]
+    .into_iter()
+    .filter(|(selected, _, _)| *selected)
+    .filter_map(|(_, addon, label)| compat::check_addon(&machine, addon, label));
+    profile_block.into_iter().chain(addon_blocks).collect()
+}
+
+/// Gate result for a planned model download, judged by its largest file.
+fn model_compat_block(artifacts: &[ModelArtifact]) -> Option<CompatBlock> {
+    let largest = artifacts.iter().filter_map(|a| a.size_bytes).max()?;
+    compat::check_model_size(&machine_profile(), largest)
+}
+
+fn compat_override_key(model_id: &str, variant_id: &str) -> String {
+    format!("{model_id}/{variant_id}")
 }
 
 fn command_available(program: &str, args: &[&str]) -> bool {
@@ -815,17 +2367,38 @@ fn apply_background_command_flags(_cmd: &mut std::process::Command) {
     let _ = _cmd;
 }
 
+/// Gives the command its own process group so cancelling or the watchdog also reaches
+/// whatever uv/pip spawned. sudo/pkexec may prompt on the terminal, which a background
+/// group cannot read, so they stay in ours.
+fn isolate_process_group(cmd: &mut std::process::Command, program: &str) {
+    if !matches!(program, "sudo" | "pkexec") {
+        cmd.process_group(0);
+    }
+}
+
 fn build_command(
     program: &str,
     args: &[&str],
     working_dir: Option<&Path>,
     envs: &[(&str, &str)],
 ) -> Result<std::process::Command, String> {
+    if let Some(failure) = FORCED_STEP_FAILURE.with(|slot| slot.borrow_mut().take()) {
+        return Err(failure);
+    }
     let mut cmd = std::process::Command::new(program);
     cmd.args(args);
+    if subprocess_cancel().is_some() {
+        isolate_process_group(&mut cmd, program);
+    }
     if let Some(dir) = working_dir {
         cmd.current_dir(dir);
     }
+    if let Some(cache) = offline_cache() {
+        cmd.env("UV_CACHE_DIR", cache.dir.join(OFFLINE_UV_CACHE_DIR));
+        if cache.consuming() {
+            cmd.env("UV_OFFLINE", "1");
+        }
+    }
     for (key, value) in envs {
         cmd.env(key, value);
     }
@@ -1005,6 +2578,207 @@ fn set_hf_xet_enabled(
         .map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+fn set_ram_tier_thresholds(
+    state: State<'_, AppState>,
+    thresholds: Option<RamTierThresholds>,
+) -> Result<AppSettings, String> {
+    let thresholds = thresholds.filter(|thresholds| !thresholds.is_empty());
+    if let Some(thresholds) = &thresholds {
+        thresholds.validate().map_err(|err| err.to_string())?;
+    }
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.ram_tier_thresholds = thresholds)
+        .map_err(|err| err.to_string())
+}
+
+#[derive(Debug, Serialize)]
+struct ModelDetailResponse {
+    #[serde(flatten)]
+    model: MasterModel,
+    vram_tiers: Vec<String>,
+    license_urls: Vec<String>,
+}
+
+#[tauri::command]
+fn get_model_details(
+    state: State<'_, AppState>,
+    model_id: String,
+) -> Result<ModelDetailResponse, String> {
+    let catalog = state.context.catalog.catalog_snapshot();
+    let model = catalog
+        .find_model(&model_id)
+        .cloned()
+        .ok_or_else(|| "Selected model was not found in catalog.".to_string())?;
+
+    let mut vram_tiers: Vec<String> = Vec::new();
+    for variant in &model.variants {
+        let tier = variant.tier.identifier().to_string();
+        if !vram_tiers.contains(&tier) {
+            vram_tiers.push(tier);
+        }
+    }
+    let mut license_urls: Vec<String> = Vec::new();
+    let artifacts = model
+        .variants
+        .iter()
+        .flat_map(|variant| variant.artifacts.iter())
+        .chain(model.always.iter().flat_map(|group| group.artifacts.iter()));
+    for artifact in artifacts {
+        if let Some(url) = artifact.license_url.as_ref().filter(|url| !url.is_empty()) {
+            if !license_urls.contains(url) {
+                license_urls.push(url.clone());
+            }
+        }
+    }
+
+    Ok(ModelDetailResponse {
+        model,
+        vram_tiers,
+        license_urls,
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct RamTierRange {
+    tier: String,
+    label: String,
+    range: String,
+}
+
+#[derive(Debug, Serialize)]
+struct VariantDetailResponse {
+    model_id: String,
+    variant_id: String,
+    label: String,
+    ram_tier: Option<String>,
+    ram_tier_ranges: Vec<RamTierRange>,
+    thresholds_overridden: bool,
+    threshold_note: Option<String>,
+    notices: Vec<VariantNotice>,
+    warnings_acknowledged: bool,
+    artifacts: Vec<ModelArtifact>,
+}
+
+#[tauri::command]
+fn get_variant_details(
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+    ram_tier: Option<String>,
+) -> Result<VariantDetailResponse, String> {
+    let resolved = state
+        .context
+        .catalog
+        .resolve_variant(&model_id, &variant_id)
+        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+    let overrides = state
+        .context
+        .config
+        .settings()
+        .ram_tier_thresholds
+        .filter(|thresholds| !thresholds.is_empty());
+    let thresholds = effective_ram_thresholds(&state, &resolved.master);
+    let tier = ram_tier
+        .as_deref()
+        .and_then(parse_ram_tier)
+        .or_else(|| detected_ram_tier(&state, &resolved.master));
+    let threshold_note = overrides.as_ref().map(|overrides| {
+        let changed: Vec<String> = RamTier::all()
+            .iter()
+            .filter(|tier| overrides.min_for(**tier).is_some())
+            .map(|tier| format!("{} {}", tier.label(), thresholds.range_label(*tier)))
+            .collect();
+        format!(
+            "RAM tier thresholds overridden in settings: {}.",
+            changed.join(", ")
+        )
+    });
+
+    Ok(VariantDetailResponse {
+        model_id: resolved.master.id.clone(),
+        variant_id: resolved.variant.id.clone(),
+        label: resolved.variant.selection_label(),
+        ram_tier: tier.map(|tier| tier.identifier().to_string()),
+        ram_tier_ranges: RamTier::all()
+            .iter()
+            .map(|tier| RamTierRange {
+                tier: tier.identifier().to_string(),
+                label: tier.label().to_string(),
+                range: thresholds.range_label(*tier),
+            })
+            .collect(),
+        thresholds_overridden: overrides.is_some(),
+        threshold_note,
+        notices: resolved.variant.notices.clone(),
+        warnings_acknowledged: variant_warnings_acknowledged(&state, &resolved),
+        artifacts: resolved.artifacts_for_download(tier),
+    })
+}
+
+fn variant_warnings_acknowledged(state: &AppState, resolved: &ResolvedModel) -> bool {
+    match resolved.variant.warnings_fingerprint(&resolved.master.id) {
+        Some(fingerprint) => state
+            .context
+            .config
+            .settings()
+            .acknowledged_variant_warnings
+            .contains(&fingerprint),
+        None => true,
+    }
+}
+
+#[tauri::command]
+fn acknowledge_variant_warnings(
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+) -> Result<(), String> {
+    let resolved = state
+        .context
+        .catalog
+        .resolve_variant(&model_id, &variant_id)
+        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+    let Some(fingerprint) = resolved.variant.warnings_fingerprint(&resolved.master.id) else {
+        return Ok(());
+    };
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            if !settings
+                .acknowledged_variant_warnings
+                .contains(&fingerprint)
+            {
+                settings.acknowledged_variant_warnings.push(fingerprint);
+            }
+        })
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+/// Lets a variant the compatibility gate blocks download anyway.
+#[tauri::command]
+fn override_compat_block(
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+) -> Result<(), String> {
+    let key = compat_override_key(&model_id, &variant_id);
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            if !settings.compat_overrides.contains(&key) {
+                settings.compat_overrides.push(key);
+            }
+        })
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 fn run_comfyui_preflight(
     state: State<'_, AppState>,
@@ -1127,7 +2901,49 @@ fn run_comfyui_preflight(
         }
     }
 
-    if command_available("git", &["--version"]) {
+    match ComfyDataDirs::from_raw(
+        request.output_dir.as_deref(),
+        request.input_dir.as_deref(),
+        request.user_dir.as_deref(),
+    ) {
+        Ok(dirs) if dirs.is_empty() => {}
+        Ok(dirs) => {
+            let mut problems = Vec::new();
+            for (flag, dir) in dirs.entries() {
+                let writable = std::fs::create_dir_all(dir).is_ok() && {
+                    let probe = dir.join(".arctic-write-test");
+                    let ok = std::fs::write(&probe, b"ok").is_ok();
+                    let _ = std::fs::remove_file(&probe);
+                    ok
+                };
+                if !writable {
+                    problems.push(format!("{flag} {}", dir.display()));
+                }
+            }
+            if problems.is_empty() {
+                push_preflight(
+                    &mut items,
+                    "pass",
+                    "Data folders",
+                    "Custom output/input/user folders are writable.",
+                );
+            } else {
+                ok = false;
+                push_preflight(
+                    &mut items,
+                    "fail",
+                    "Data folders",
+                    format!("Cannot write to: {}", problems.join(", ")),
+                );
+            }
+        }
+        Err(err) => {
+            ok = false;
+            push_preflight(&mut items, "fail", "Data folders", err);
+        }
+    }
+
+    if command_available("git", &["--version"]) {
         push_preflight(&mut items, "pass", "Git", "Git is available.");
     } else {
         ok = false;
@@ -1139,8 +2955,29 @@ fn run_comfyui_preflight(
         );
     }
 
-    let dns_ok = has_dns("github.com", 443) && has_dns("pypi.org", 443);
-    if dns_ok {
+    let offline_dir = request
+        .offline_cache
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    if let Some(raw) = offline_dir {
+        match normalize_path(raw).and_then(|dir| read_offline_cache_manifest(&dir)) {
+            Ok(manifest) => push_preflight(
+                &mut items,
+                "pass",
+                "Offline cache",
+                format!(
+                    "Cache built for {} with {} custom node(s); no network needed.",
+                    manifest.torch_profile,
+                    manifest.custom_nodes.len()
+                ),
+            ),
+            Err(err) => {
+                ok = false;
+                push_preflight(&mut items, "fail", "Offline cache", err);
+            }
+        }
+    } else if has_dns("github.com", 443) && has_dns("pypi.org", 443) {
         push_preflight(
             &mut items,
             "pass",
@@ -1196,15 +3033,91 @@ fn run_comfyui_preflight(
         );
     }
 
+    if let Some(preference) = request
+        .container_engine
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        match resolve_container_engine(preference) {
+            Ok(engine) => {
+                push_preflight(
+                    &mut items,
+                    "pass",
+                    "Container engine",
+                    format!("ComfyUI will run in a {engine} container."),
+                );
+                let profile = request
+                    .torch_profile
+                    .clone()
+                    .unwrap_or_else(|| get_comfyui_install_recommendation().torch_profile);
+                match container_gpu_args(engine, &profile) {
+                    Ok(_) => push_preflight(
+                        &mut items,
+                        "pass",
+                        "Container GPU access",
+                        format!("{engine} can pass the GPU through for {profile}."),
+                    ),
+                    Err(err) => {
+                        ok = false;
+                        push_preflight(&mut items, "fail", "Container GPU access", err);
+                    }
+                }
+            }
+            Err(err) => {
+                ok = false;
+                push_preflight(&mut items, "fail", "Container engine", err);
+            }
+        }
+    }
+
     match get_linux_prereq_cache_or_scan() {
         Ok(scan) => {
-            if scan.missing_required.is_empty() {
+            if let Some(system) = scan.immutable {
+                let missing: Vec<String> = scan
+                    .missing_required
+                    .iter()
+                    .chain(scan.missing_optional.iter())
+                    .cloned()
+                    .collect();
+                if missing.is_empty() {
+                    push_preflight(
+                        &mut items,
+                        "pass",
+                        "Linux system packages",
+                        format!("{system} detected; required tools are on PATH."),
+                    );
+                } else {
+                    push_preflight(
+                        &mut items,
+                        "warn",
+                        "Linux system packages",
+                        format!(
+                            "{system} detected, so packages are not installed automatically. Not on PATH: {}. To provide them, {}.",
+                            missing.join(", "),
+                            immutable_os_guidance(system, &missing)
+                        ),
+                    );
+                }
+            } else if scan.missing_required.is_empty() {
                 push_preflight(
                     &mut items,
                     "pass",
                     "Linux system packages",
                     format!("{} prerequisites are installed.", scan.distro),
                 );
+            } else if scan.flatpak && !flatpak_host_access() {
+                push_preflight(
+                    &mut items,
+                    "fail",
+                    "Linux system packages",
+                    format!(
+                        "Missing required packages for {}: {}. The Flatpak sandbox cannot install host packages; run this in a host terminal: {}",
+                        scan.distro,
+                        scan.missing_required.join(", "),
+                        linux_prereq_manual_command(&scan).unwrap_or_default()
+                    ),
+                );
             } else {
                 push_preflight(
                     &mut items,
@@ -1217,7 +3130,11 @@ fn run_comfyui_preflight(
                     ),
                 );
             }
-            if !scan.missing_optional.is_empty() {
+            // The optional packages are GL libraries a headless server does not need.
+            if scan.immutable.is_none()
+                && request.server.is_none()
+                && !scan.missing_optional.is_empty()
+            {
                 push_preflight(
                     &mut items,
                     "warn",
@@ -1239,6 +3156,151 @@ fn run_comfyui_preflight(
         }
     }
 
+    let selected_profile = request
+        .torch_profile
+        .clone()
+        .unwrap_or_else(|| get_comfyui_install_recommendation().torch_profile);
+    if is_rocm_torch_profile(&selected_profile) {
+        match detect_amd_rocm_details() {
+            None => {
+                ok = false;
+                push_preflight(
+                    &mut items,
+                    "fail",
+                    "ROCm runtime",
+                    "ROCm profile selected but neither rocm-smi nor /opt/rocm was found. Install ROCm 6.4 from your distro or AMD.",
+                );
+            }
+            Some(amd) => {
+                let gpu = amd
+                    .name
+                    .clone()
+                    .or_else(|| amd.gfx_target.clone())
+                    .unwrap_or_else(|| "AMD GPU".to_string());
+                let version = amd
+                    .rocm_version
+                    .as_deref()
+                    .map(|v| format!("ROCm {v}"))
+                    .unwrap_or_else(|| "ROCm version unknown".to_string());
+                push_preflight(
+                    &mut items,
+                    "pass",
+                    "ROCm runtime",
+                    format!("{gpu} ({version})."),
+                );
+                match amd.gfx_target.as_deref() {
+                    Some(gfx) if ROCM_SUPPORTED_GFX.contains(&gfx) => {}
+                    Some(gfx) => push_preflight(
+                        &mut items,
+                        "warn",
+                        "ROCm GPU target",
+                        format!(
+                            "{gfx} has no official PyTorch ROCm wheels; it may need HSA_OVERRIDE_GFX_VERSION to run."
+                        ),
+                    ),
+                    None => push_preflight(
+                        &mut items,
+                        "warn",
+                        "ROCm GPU target",
+                        "Could not read the GPU gfx target (rocminfo missing?).",
+                    ),
+                }
+            }
+        }
+        let kfd_ok = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/kfd")
+            .is_ok();
+        if !kfd_ok {
+            push_preflight(
+                &mut items,
+                "warn",
+                "ROCm device access",
+                "/dev/kfd is not accessible. Add your user to the 'render' and 'video' groups and log in again.",
+            );
+        }
+    } else if is_cpu_torch_profile(&selected_profile) {
+        push_preflight(
+            &mut items,
+            "warn",
+            "GPU backend",
+            "CPU-only Torch selected. ComfyUI will run with --cpu and generation will be very slow.",
+        );
+    } else if detect_nvidia_gpu_details().name.is_none() && detect_amd_rocm_details().is_some() {
+        push_preflight(
+            &mut items,
+            "warn",
+            "GPU backend",
+            "AMD GPU with ROCm detected but a CUDA Torch profile is selected. Choose a ROCm profile.",
+        );
+    }
+    let machine = machine_profile();
+    if let (Some((major, minor)), Some((_, (max_major, max_minor)))) = (
+        machine.compute_capability,
+        compat::torch_profile_arch_range(&selected_profile),
+    ) {
+        if (major, minor) > (max_major, max_minor) {
+            push_preflight(
+                &mut items,
+                "warn",
+                "GPU compute capability",
+                format!(
+                    "Compute capability {major}.{minor} is newer than {selected_profile} ships kernels for (up to {max_major}.{max_minor}). Torch falls back to JIT-compiled PTX, which is slow on first use; prefer a cu130 profile."
+                ),
+            );
+        } else if compat::check_torch_profile(&machine, &selected_profile).is_none() {
+            push_preflight(
+                &mut items,
+                "pass",
+                "GPU compute capability",
+                format!("Compute capability {major}.{minor} is supported by {selected_profile}."),
+            );
+        }
+    }
+    if is_nightly_torch_profile(&selected_profile) {
+        push_preflight(
+            &mut items,
+            "warn",
+            "Torch nightly",
+            "Torch nightly builds change daily; custom nodes may break without notice.",
+        );
+    }
+    if !torch_profile_uses_cuda(&selected_profile) || is_nightly_torch_profile(&selected_profile) {
+        let cuda_only: Vec<&str> = [
+            (request.include_sage_attention, "SageAttention"),
+            (request.include_sage_attention3, "SageAttention3"),
+            (request.include_flash_attention, "FlashAttention"),
+            (request.include_nunchaku, "Nunchaku"),
+            (request.include_xformers, "xformers"),
+            (request.include_insight_face, "InsightFace"),
+            (request.include_trellis2, "Trellis2"),
+        ]
+        .into_iter()
+        .filter(|(selected, _)| *selected)
+        .map(|(_, name)| name)
+        .collect();
+        if !cuda_only.is_empty() {
+            ok = false;
+            push_preflight(
+                &mut items,
+                "fail",
+                "Add-on compatibility",
+                if is_nightly_torch_profile(&selected_profile) {
+                    format!(
+                        "These add-ons have no prebuilt wheels for {selected_profile}: {}.",
+                        cuda_only.join(", ")
+                    )
+                } else {
+                    format!(
+                        "These add-ons ship CUDA-only wheels and cannot be installed with {selected_profile}: {}.",
+                        cuda_only.join(", ")
+                    )
+                },
+            );
+        }
+    }
+
     let selected_attention = [
         request.include_sage_attention,
         request.include_sage_attention3,
@@ -1249,12 +3311,14 @@ fn run_comfyui_preflight(
     .filter(|v| *v)
     .count();
     if selected_attention > 1 {
-        ok = false;
         push_preflight(
             &mut items,
-            "fail",
+            "pass",
             "Attention add-on selection",
-            "Select only one of SageAttention / SageAttention3 / FlashAttention / Nunchaku.",
+            format!(
+                "All selected backends are installed side by side; launches use {} until another is picked in Manage mode.",
+                selected_attention_backend(&request)
+            ),
         );
     } else {
         push_preflight(
@@ -1266,12 +3330,15 @@ fn run_comfyui_preflight(
     }
 
     if request.include_sage_attention3 {
-        let gpu = detect_nvidia_gpu_details();
-        let allowed = gpu
-            .name
-            .as_deref()
-            .map(|n| n.to_ascii_lowercase().contains("rtx 50"))
-            .unwrap_or(false);
+        // Compute capability is authoritative when known; older drivers only give a name.
+        let allowed = match machine.compute_capability {
+            Some(cc) => compat::min_compute_capability("sage3").is_some_and(|min| cc >= min),
+            None => detect_nvidia_gpu_details()
+                .name
+                .as_deref()
+                .map(|n| n.to_ascii_lowercase().contains("rtx 50"))
+                .unwrap_or(false),
+        };
         if allowed {
             push_preflight(
                 &mut items,
@@ -1290,12 +3357,68 @@ fn run_comfyui_preflight(
         }
     }
 
+    for block in install_compat_blocks(&request, &selected_profile) {
+        if !request.allow_incompatible {
+            ok = false;
+        }
+        items.push(PreflightItem {
+            status: if request.allow_incompatible {
+                "warn"
+            } else {
+                "fail"
+            }
+            .to_string(),
+            title: "Hardware compatibility".to_string(),
+            detail: block.message,
+            reason: Some(block.reason.code()),
+        });
+    }
+
+    if let Err(err) = normalize_frontend_version(request.frontend_version.as_deref()) {
+        ok = false;
+        push_preflight(&mut items, "fail", "Frontend version", err);
+    }
+    if let Some(Err(err)) = request
+        .manager
+        .as_ref()
+        .map(ComfyManagerOptions::ini_values)
+    {
+        ok = false;
+        push_preflight(&mut items, "fail", "ComfyUI-Manager config", err);
+    }
+
+    let wheel_kinds = requested_wheel_kinds(&request);
+    let host_wheels = offline_dir.is_none()
+        && request
+            .container_engine
+            .as_deref()
+            .map_or(true, |engine| engine.trim().is_empty());
+    if host_wheels
+        && !wheel_kinds.is_empty()
+        && torch_profile_uses_cuda(&selected_profile)
+        && !is_nightly_torch_profile(&selected_profile)
+    {
+        let failures =
+            wheel_availability_failures(&selected_profile, detect_wheel_arch(), &wheel_kinds);
+        if failures.is_empty() {
+            push_preflight(
+                &mut items,
+                "pass",
+                "Add-on wheels",
+                format!(
+                    "{} prebuilt wheel(s) for {selected_profile} are available.",
+                    wheel_kinds.len()
+                ),
+            );
+        } else {
+            ok = false;
+            for failure in failures {
+                push_preflight(&mut items, "fail", "Add-on wheels", failure);
+            }
+        }
+    }
+
     if request.include_trellis2 {
-        let recommendation = get_comfyui_install_recommendation();
-        let selected_profile = request
-            .torch_profile
-            .clone()
-            .unwrap_or(recommendation.torch_profile);
         let trellis_supported = matches!(selected_profile.as_str(), "torch280_cu128");
         if trellis_supported {
             push_preflight(
@@ -1359,14 +3482,19 @@ fn get_comfyui_resume_state(
     }
 
     if let Some((dir, install_state)) = find_in_progress_install(&base) {
+        let reason = last_install_failure(&install_state)
+            .and_then(|failed| failed.error.as_deref())
+            .map(|err| format!(" Last error: {err}"))
+            .unwrap_or_default();
         return Ok(ComfyResumeStateResponse {
             found: true,
             install_dir: Some(dir.to_string_lossy().to_string()),
             step: Some(install_state.step.clone()),
             summary: format!(
-                "Interrupted install found in {} at step '{}'.",
+                "Interrupted install found in {} at step '{}'.{}",
                 dir.display(),
-                install_state.step
+                install_state.step,
+                reason
             ),
         });
     }
@@ -1379,6 +3507,54 @@ fn get_comfyui_resume_state(
     })
 }
 
+/// Last `lines` lines of an install's `install.log`, for the console after a reload.
+#[tauri::command]
+fn get_install_log_tail(install_root: String, lines: Option<usize>) -> Result<Vec<String>, String> {
+    let root = normalize_path(install_root.trim())?;
+    let path = root.join(INSTALL_LOG_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let limit = lines.unwrap_or(200).clamp(1, 5000);
+    let file = std::fs::File::open(&path)
+        .map_err(|err| format!("Failed to open {}: {err}", path.display()))?;
+    let mut tail = std::collections::VecDeque::with_capacity(limit);
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if tail.len() == limit {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+    Ok(tail.into())
+}
+
+#[tauri::command]
+fn get_install_history(state: State<'_, AppState>, root: String) -> Result<InstallState, String> {
+    let root = resolve_root_path(&state.context, Some(root))?;
+    read_install_state(&root).ok_or_else(|| {
+        format!(
+            "No install state recorded in {}.",
+            root.join(".arctic_install_state.json").display()
+        )
+    })
+}
+
+#[tauri::command]
+fn get_install_state_detail(
+    state: State<'_, AppState>,
+    install_dir: String,
+) -> Result<InstallStateDetail, String> {
+    let install_dir = normalize_path(install_dir.trim())?;
+    let template = state.context.config.settings().comfy_settings_template;
+    let apply_settings_template = template.apply_after_install && !template.values.is_empty();
+    install_state_detail(&install_dir, apply_settings_template).ok_or_else(|| {
+        format!(
+            "No install state recorded in {}.",
+            install_dir.join(".arctic_install_state.json").display()
+        )
+    })
+}
+
 fn download_http_file(url: &str, out_file: &Path) -> Result<(), String> {
     if let Some(parent) = out_file.parent() {
         std::fs::create_dir_all(parent).map_err(|err| {
@@ -1391,26 +3567,47 @@ fn download_http_file(url: &str, out_file: &Path) -> Result<(), String> {
 
     let tmp_file = out_file.with_extension("download");
     let user_agent = "ArcticComfyUIHelper/0.3.4";
+    let policy = retry_settings().network;
+    if let Some(fault) = faults::roll_request_fault() {
+        std::thread::sleep(fault.stall());
+        return Err(format!("Failed to download {url}: {fault}"));
+    }
 
-    let curl_output = std::process::Command::new("curl")
-        .arg("-fL")
+    let mut curl = std::process::Command::new("curl");
+    if let Some(rate) = faults::settings().bandwidth_bytes_per_sec {
+        curl.arg("--limit-rate").arg(rate.to_string());
+    }
+    curl.arg("-fL")
         .arg("--retry")
-        .arg("3")
+        .arg((policy.attempts() - 1).to_string())
+        .arg("--retry-delay")
+        .arg(policy.initial_delay_secs.to_string())
         .arg("--connect-timeout")
         .arg("20")
         .arg("-A")
         .arg(user_agent)
         .arg("-o")
         .arg(&tmp_file)
-        .arg(url)
-        .output();
+        .arg(url);
+    let curl_output = match subprocess_cancel() {
+        Some(cancel) => output_with_cancel(curl, "curl", &cancel),
+        None => curl.output().map_err(|err| err.to_string()),
+    };
+    if curl_output
+        .as_ref()
+        .is_err_and(|err| err == SUBPROCESS_CANCELLED)
+    {
+        let _ = std::fs::remove_file(&tmp_file);
+        return Err(SUBPROCESS_CANCELLED.to_string());
+    }
 
     let downloaded = match curl_output {
         Ok(output) if output.status.success() => true,
         Ok(output) => {
             let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
             let wget_output = std::process::Command::new("wget")
-                .arg("--tries=3")
+                .arg(format!("--tries={}", policy.attempts()))
+                .arg(format!("--waitretry={}", policy.max_delay_secs))
                 .arg("--timeout=20")
                 .arg("--user-agent")
                 .arg(user_agent)
@@ -1435,7 +3632,8 @@ fn download_http_file(url: &str, out_file: &Path) -> Result<(), String> {
         }
         Err(_) => {
             let wget_output = std::process::Command::new("wget")
-                .arg("--tries=3")
+                .arg(format!("--tries={}", policy.attempts()))
+                .arg(format!("--waitretry={}", policy.max_delay_secs))
                 .arg("--timeout=20")
                 .arg("--user-agent")
                 .arg(user_agent)
@@ -1475,50 +3673,189 @@ fn download_http_file(url: &str, out_file: &Path) -> Result<(), String> {
 
 
 fn run_command(program: &str, args: &[&str], working_dir: Option<&Path>) -> Result<(), String> {
+    match run_command_exit_code(program, args, working_dir)? {
+        Some(0) => Ok(()),
+        _ => Err(format!("Command failed: {} {}", program, args.join(" "))),
+    }
+}
+
+/// `run_command` that hands back the exit code (`None` when a signal ended the
+/// process) instead of turning a non-zero exit into an error.
+fn run_command_exit_code(
+    program: &str,
+    args: &[&str],
+    working_dir: Option<&Path>,
+) -> Result<Option<i32>, String> {
     log::debug!("run_command: {} {}", program, args.join(" "));
+    let cancel = subprocess_cancel();
+    if install_log().is_some() || cancel.is_some() {
+        log_command_line(program, args);
+        return match run_watched_command(program, args, working_dir, &[], None, cancel.as_ref()) {
+            Ok(status) => Ok(status.code()),
+            Err(WatchedRunError::Failed(err)) => Err(err),
+            Err(WatchedRunError::Cancelled) => Err(SUBPROCESS_CANCELLED.to_string()),
+            Err(WatchedRunError::Hung(_)) => {
+                Err(format!("Command failed: {} {}", program, args.join(" ")))
+            }
+        };
+    }
     let mut cmd = build_command(program, args, working_dir, &[])?;
     let status = cmd
         .status()
         .map_err(|err| format!("Failed to run {program}: {err}"))?;
-    if !status.success() {
-        return Err(format!("Command failed: {} {}", program, args.join(" ")));
-    }
-    Ok(())
+    Ok(status.code())
 }
 
 fn can_use_interactive_sudo() -> bool {
     std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
 }
 
-fn run_privileged_command(
-    program: &str,
-    args: &[&str],
-    working_dir: Option<&Path>,
-) -> Result<(), String> {
-    let mut sudo_non_interactive: Vec<&str> = vec!["-n", program];
-    sudo_non_interactive.extend_from_slice(args);
-    if run_command("sudo", &sudo_non_interactive, working_dir).is_ok() {
-        return Ok(());
+/// How root is obtained for system package installs, in the order they are tried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PrivilegeMethod {
+    Root,
+    /// sudo with cached credentials or NOPASSWD; `-n` never prompts.
+    SudoCached,
+    /// polkit prompt from the desktop session's authentication agent.
+    Pkexec,
+    /// sudo prompting on the terminal the app was started from.
+    SudoTerminal,
+}
+
+impl PrivilegeMethod {
+    fn label(self) -> &'static str {
+        match self {
+            PrivilegeMethod::Root => "root",
+            PrivilegeMethod::SudoCached => "sudo -n",
+            PrivilegeMethod::Pkexec => "pkexec",
+            PrivilegeMethod::SudoTerminal => "sudo",
+        }
     }
+}
 
-    let mut pkexec_args: Vec<&str> = vec![program];
-    pkexec_args.extend_from_slice(args);
-    if run_command("pkexec", &pkexec_args, working_dir).is_ok() {
-        return Ok(());
+/// Only mechanisms that can actually reach the user are offered: plain `sudo` from a
+/// desktop launcher would wait forever on a password prompt nobody sees.
+fn privilege_methods() -> Vec<PrivilegeMethod> {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    if unsafe { libc::geteuid() } == 0 {
+        return vec![PrivilegeMethod::Root];
+    }
+    if run_command_capture("sudo", &["-n", "true"], None).is_ok() {
+        return vec![PrivilegeMethod::SudoCached];
+    }
+    let mut methods = Vec::new();
+    let graphical =
+        std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some();
+    if graphical && command_available("pkexec", &["--version"]) {
+        methods.push(PrivilegeMethod::Pkexec);
+    }
+    if can_use_interactive_sudo() && command_available("sudo", &["--version"]) {
+        methods.push(PrivilegeMethod::SudoTerminal);
+    }
+    methods
+}
+
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=+:".contains(c))
+    {
+        return value.to_string();
     }
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
 
-    if can_use_interactive_sudo() {
-        let mut sudo_args: Vec<&str> = vec![program];
-        sudo_args.extend_from_slice(args);
-        if run_command("sudo", &sudo_args, working_dir).is_ok() {
-            return Ok(());
-        }
+fn privileged_script(commands: &[Vec<&str>]) -> String {
+    commands
+        .iter()
+        .map(|command| {
+            command
+                .iter()
+                .map(|arg| shell_quote(arg))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+/// The Flatpak sandbox has neither sudo nor the host's package manager, so the host's
+/// pkexec is reached through `flatpak-spawn --host`. Without host access the user gets
+/// the command to run in a host terminal instead.
+fn run_host_privileged_commands(commands: &[Vec<&str>]) -> Result<(), String> {
+    let script = privileged_script(commands);
+    let manual = format!("sudo sh -c {}", shell_quote(&script));
+    if !flatpak_host_access() {
+        return Err(format!(
+            "Running inside Flatpak without host access (--talk-name=org.freedesktop.Flatpak), so system packages cannot be installed from here. Run this in a host terminal, then retry: {manual}"
+        ));
     }
+    run_command(
+        "flatpak-spawn",
+        &["--host", "pkexec", "sh", "-c", &script],
+        None,
+    )
+    .map_err(|err| {
+        if err == SUBPROCESS_CANCELLED {
+            err
+        } else {
+            format!("Host package install through pkexec failed ({err}). Run this in a host terminal, then retry: {manual}")
+        }
+    })
+}
 
+/// Runs `commands` as root in one shell so the user authenticates once. A command
+/// failing under cached sudo, as root or after pkexec authenticated is a real failure;
+/// only a pkexec prompt that was dismissed or refused falls back to the terminal.
+fn run_privileged_commands(commands: &[Vec<&str>]) -> Result<(), String> {
+    let script = privileged_script(commands);
+    let methods = privilege_methods();
+    if methods.is_empty() {
+        return Err(format!(
+            "Cannot get administrator rights: pkexec needs a desktop session with a PolicyKit agent, and sudo needs a terminal. Run this manually, then retry: sudo sh -c {}",
+            shell_quote(&script)
+        ));
+    }
+    let mut failures = Vec::new();
+    for method in methods {
+        let mut last = matches!(method, PrivilegeMethod::Root | PrivilegeMethod::SudoCached);
+        let result = match method {
+            PrivilegeMethod::Root => run_command("sh", &["-c", &script], None),
+            PrivilegeMethod::SudoCached => run_command("sudo", &["-n", "sh", "-c", &script], None),
+            PrivilegeMethod::Pkexec => {
+                match run_command_exit_code("pkexec", &["sh", "-c", &script], None) {
+                    Ok(Some(0)) => Ok(()),
+                    // pkexec exits 126 when the prompt is dismissed and 127 when the user is
+                    // not authorized; the script has not run, so sudo may still get a turn.
+                    Ok(Some(code @ (126 | 127))) => {
+                        Err(format!("not authorized (exit code {code})"))
+                    }
+                    Ok(code) => {
+                        last = true;
+                        Err(match code {
+                            Some(code) => format!("exit code {code}"),
+                            None => "terminated by a signal".to_string(),
+                        })
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            PrivilegeMethod::SudoTerminal => run_command("sudo", &["sh", "-c", &script], None),
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(err) if err == SUBPROCESS_CANCELLED => return Err(err),
+            Err(err) => failures.push(format!("{}: {err}", method.label())),
+        }
+        if last {
+            break;
+        }
+    }
     Err(format!(
-        "Privilege escalation failed for {} {}. If running from desktop GUI, ensure a PolicyKit agent is active; otherwise run with --nerdstats from a terminal so sudo can prompt.",
-        program,
-        args.join(" ")
+        "Privileged package install failed ({}). If the authentication prompt was dismissed, retry; otherwise run manually: sudo sh -c {}",
+        failures.join("; "),
+        shell_quote(&script)
     ))
 }
 
@@ -1528,12 +3865,17 @@ fn run_command_capture(
     working_dir: Option<&Path>,
 ) -> Result<(String, String), String> {
     log::debug!("run_command_capture: {} {}", program, args.join(" "));
+    log_command_line(program, args);
     let mut cmd = build_command(program, args, working_dir, &[])?;
-    let output = cmd
-        .output()
-        .map_err(|err| format!("Failed to run {program}: {err}"))?;
+    let output = match subprocess_cancel() {
+        Some(cancel) => output_with_cancel(cmd, program, &cancel)?,
+        None => cmd
+            .output()
+            .map_err(|err| format!("Failed to run {program}: {err}"))?,
+    };
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    log_captured_output(&stdout, &stderr);
     if !output.status.success() {
         let tail = if stderr.trim().is_empty() {
             stdout
@@ -1566,26 +3908,389 @@ fn run_command_capture(
     Ok((stdout, stderr))
 }
 
+/// Current retry policies from settings; defaults before the app is set up.
+fn retry_settings() -> RetrySettings {
+    GLOBAL_APP
+        .get()
+        .map(|app| app.state::<AppState>().context.config.settings().retry)
+        .unwrap_or_default()
+}
+
+fn with_retry<T>(
+    policy: RetryPolicy,
+    mut op: impl FnMut() -> Result<T, String>,
+) -> Result<T, String> {
+    let attempts = policy.attempts();
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt >= attempts => return Err(err),
+            Err(err) if subprocess_cancel().is_some_and(|cancel| cancel.is_cancelled()) => {
+                return Err(err)
+            }
+            Err(err) => {
+                let delay = policy.delay_after(attempt);
+                log::warn!(
+                    "Attempt {attempt} of {attempts} failed, retrying in {}s: {err}",
+                    delay.as_secs()
+                );
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+        }
+    }
+}
+
 fn run_command_with_retry(
     program: &str,
     args: &[&str],
     working_dir: Option<&Path>,
-    retries: usize,
+    policy: RetryPolicy,
 ) -> Result<(), String> {
-    let attempts = retries.max(1);
-    let mut last_err = String::new();
-    for attempt in 1..=attempts {
-        match run_command_capture(program, args, working_dir) {
-            Ok(_) => return Ok(()),
-            Err(err) => {
-                last_err = err;
-                if attempt < attempts {
-                    std::thread::sleep(std::time::Duration::from_secs(2));
+    with_retry(policy, || {
+        run_command_capture(program, args, working_dir).map(|_| ())
+    })
+}
+
+thread_local! {
+    static SUBPROCESS_CANCEL: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Runs `op` with `cancel` watched by every subprocess it starts on this thread, so
+/// cancelling kills the running uv/pip child instead of waiting for it to finish.
+fn with_subprocess_cancel<T>(cancel: &CancellationToken, op: impl FnOnce() -> T) -> T {
+    let previous = SUBPROCESS_CANCEL.with(|slot| slot.replace(Some(cancel.clone())));
+    let result = op();
+    SUBPROCESS_CANCEL.with(|slot| *slot.borrow_mut() = previous);
+    result
+}
+
+fn subprocess_cancel() -> Option<CancellationToken> {
+    SUBPROCESS_CANCEL.with(|slot| slot.borrow().clone())
+}
+
+thread_local! {
+    /// Failure armed by `ARCTIC_FAULT_FAIL_STEPS` for the step this thread just entered;
+    /// the step's first subprocess reports it instead of running.
+    static FORCED_STEP_FAILURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn arm_forced_step_failure(step: Option<&str>) {
+    let failure = step
+        .filter(|step| faults::settings().fails_step(step))
+        .map(|step| format!("injected fault: step '{step}' forced to fail"));
+    FORCED_STEP_FAILURE.with(|slot| *slot.borrow_mut() = failure);
+}
+
+const INSTALL_LOG_FILE: &str = "install.log";
+/// A log past this size is moved to `install.log.1` when the next install starts.
+const INSTALL_LOG_ROTATE_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+struct InstallLogLine {
+    line: String,
+}
+
+/// `install.log` of the install running on this thread; writes are shared with the
+/// reader threads that drain a child's stdout and stderr.
+#[derive(Clone)]
+struct InstallLog {
+    file: Arc<Mutex<std::fs::File>>,
+}
+
+impl InstallLog {
+    fn open(install_root: &Path) -> Option<Self> {
+        let path = install_root.join(INSTALL_LOG_FILE);
+        if std::fs::metadata(&path).is_ok_and(|meta| meta.len() > INSTALL_LOG_ROTATE_BYTES) {
+            let _ = std::fs::rename(&path, install_root.join(format!("{INSTALL_LOG_FILE}.1")));
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map(|file| Self {
+                file: Arc::new(Mutex::new(file)),
+            })
+            .map_err(|err| log::warn!("Failed to open {}: {err}", path.display()))
+            .ok()
+    }
+
+    fn write_line(&self, line: &str) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{line}");
+        }
+        if let Some(app) = GLOBAL_APP.get() {
+            let _ = app.emit(
+                "comfyui-install-log",
+                InstallLogLine {
+                    line: line.to_string(),
+                },
+            );
+        }
+    }
+}
+
+thread_local! {
+    static INSTALL_LOG: RefCell<Option<InstallLog>> = const { RefCell::new(None) };
+}
+
+/// Routes command output on this thread into the install's `install.log` until dropped.
+struct InstallLogGuard {
+    previous: Option<InstallLog>,
+}
+
+impl InstallLogGuard {
+    fn start(install_root: &Path) -> Self {
+        let log = InstallLog::open(install_root);
+        if let Some(log) = log.as_ref() {
+            log.write_line(&format!(
+                "===== install started at {} =====",
+                unix_now_secs()
+            ));
+        }
+        Self::attach(log)
+    }
+
+    /// Shares an already open log with a helper thread.
+    fn attach(log: Option<InstallLog>) -> Self {
+        Self {
+            previous: INSTALL_LOG.with(|slot| slot.replace(log)),
+        }
+    }
+}
+
+impl Drop for InstallLogGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        INSTALL_LOG.with(|slot| *slot.borrow_mut() = previous);
+    }
+}
+
+fn install_log() -> Option<InstallLog> {
+    INSTALL_LOG.with(|slot| slot.borrow().clone())
+}
+
+fn log_command_line(program: &str, args: &[&str]) {
+    if let Some(log) = install_log() {
+        log.write_line(&format!("$ {} {}", program, args.join(" ")));
+    }
+}
+
+fn log_captured_output(stdout: &str, stderr: &str) {
+    if let Some(log) = install_log() {
+        for line in stdout.lines().chain(stderr.lines()) {
+            log.write_line(line);
+        }
+    }
+}
+
+/// How many times a hung subprocess is killed and started again before giving up.
+const WATCHDOG_MAX_RESTARTS: usize = 2;
+const WATCHDOG_POLL: Duration = Duration::from_millis(500);
+const SUBPROCESS_CANCELLED: &str = "Cancelled by user.";
+
+enum WatchedRunError {
+    Failed(String),
+    Hung(Duration),
+    Cancelled,
+}
+
+/// User + system CPU ticks of `pid` and its reaped children, from `/proc/<pid>/stat`.
+/// Downloads burn CPU on TLS even when they print nothing, so this separates a
+/// quiet-but-working process from one stuck on a dead connection.
+fn proc_cpu_ticks(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let (_, after_comm) = stat.rsplit_once(')')?;
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    // Fields after the command name start at `state` (field 3); utime..cstime are 14..17.
+    fields
+        .get(11..15)?
+        .iter()
+        .map(|value| value.parse::<u64>().ok())
+        .sum()
+}
+
+/// How long a cancelled process group gets to exit on SIGTERM before it is killed.
+const PROCESS_GROUP_GRACE: Duration = Duration::from_secs(3);
+/// Short enough that the many quick captures an install makes are not slowed down.
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+/// How long output readers get to reach EOF once their process has exited or been
+/// killed; a grandchild that inherited the pipe can otherwise hold it open for good.
+const READER_JOIN_GRACE: Duration = Duration::from_secs(5);
+
+/// Stops `child` and the rest of its process group: SIGTERM first so pip can clean
+/// up its temp files, then SIGKILL for anything still running.
+fn terminate_process_tree(child: &mut std::process::Child) {
+    let pgid = child.id() as libc::pid_t;
+    // SAFETY: plain signal syscalls. A child that was not given its own group has no
+    // group with its pid, so these fail with ESRCH and the direct kill below applies.
+    unsafe { libc::killpg(pgid, libc::SIGTERM) };
+    let deadline = Instant::now() + PROCESS_GROUP_GRACE;
+    while Instant::now() < deadline {
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            break;
+        }
+        std::thread::sleep(CANCEL_POLL);
+    }
+    // SAFETY: as above; grandchildren can outlive the leader, so the group is signalled
+    // even when the child itself has already exited.
+    unsafe { libc::killpg(pgid, libc::SIGKILL) };
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Joins the output reader threads, leaving any still blocked after
+/// [`READER_JOIN_GRACE`] to finish on their own.
+fn join_readers(readers: Vec<std::thread::JoinHandle<()>>) {
+    let deadline = Instant::now() + READER_JOIN_GRACE;
+    for reader in readers {
+        while !reader.is_finished() && Instant::now() < deadline {
+            std::thread::sleep(CANCEL_POLL);
+        }
+        if reader.is_finished() {
+            let _ = reader.join();
+        }
+    }
+}
+
+fn drain_pipe<R: Read + Send + 'static>(
+    pipe: Option<R>,
+) -> Option<std::thread::JoinHandle<Vec<u8>>> {
+    pipe.map(|mut pipe| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            buf
+        })
+    })
+}
+
+/// `Command::output` that kills the process group and gives up once `cancel` fires.
+fn output_with_cancel(
+    mut cmd: std::process::Command,
+    program: &str,
+    cancel: &CancellationToken,
+) -> Result<std::process::Output, String> {
+    if cancel.is_cancelled() {
+        return Err(SUBPROCESS_CANCELLED.to_string());
+    }
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| format!("Failed to run {program}: {err}"))?;
+    let stdout = drain_pipe(child.stdout.take());
+    let stderr = drain_pipe(child.stderr.take());
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(err) => return Err(format!("Failed to wait for {program}: {err}")),
+        }
+        if cancel.is_cancelled() {
+            terminate_process_tree(&mut child);
+            return Err(SUBPROCESS_CANCELLED.to_string());
+        }
+        std::thread::sleep(CANCEL_POLL);
+    };
+    let collect = |handle: Option<std::thread::JoinHandle<Vec<u8>>>| {
+        handle
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+    Ok(std::process::Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+fn run_watched_command(
+    program: &str,
+    args: &[&str],
+    working_dir: Option<&Path>,
+    envs: &[(&str, &str)],
+    idle_timeout: Option<Duration>,
+    cancel: Option<&CancellationToken>,
+) -> Result<std::process::ExitStatus, WatchedRunError> {
+    let mut cmd =
+        build_command(program, args, working_dir, envs).map_err(WatchedRunError::Failed)?;
+    // The watchdog kills through the group, so watched runs always get one.
+    isolate_process_group(&mut cmd, program);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| WatchedRunError::Failed(format!("Failed to run {program}: {err}")))?;
+
+    let last_output = Arc::new(Mutex::new(Instant::now()));
+    let log = install_log();
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let last_output = Arc::clone(&last_output);
+        let log = log.clone();
+        readers.push(std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                println!("{line}");
+                if let Some(log) = log.as_ref() {
+                    log.write_line(&line);
+                }
+                if let Ok(mut at) = last_output.lock() {
+                    *at = Instant::now();
                 }
             }
-        }
+        }));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let last_output = Arc::clone(&last_output);
+        readers.push(std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                eprintln!("{line}");
+                if let Some(log) = log.as_ref() {
+                    log.write_line(&line);
+                }
+                if let Ok(mut at) = last_output.lock() {
+                    *at = Instant::now();
+                }
+            }
+        }));
     }
-    Err(last_err)
+
+    let pid = child.id();
+    let mut cpu_ticks = proc_cpu_ticks(pid);
+    let mut last_activity = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(err) => {
+                return Err(WatchedRunError::Failed(format!(
+                    "Failed to wait for {program}: {err}"
+                )))
+            }
+        }
+        std::thread::sleep(WATCHDOG_POLL);
+        let ticks = proc_cpu_ticks(pid);
+        if ticks != cpu_ticks {
+            cpu_ticks = ticks;
+            last_activity = Instant::now();
+        }
+        if let Ok(at) = last_output.lock() {
+            last_activity = last_activity.max(*at);
+        }
+        let idle = last_activity.elapsed();
+        let cancelled = cancel.is_some_and(CancellationToken::is_cancelled);
+        if cancelled || idle_timeout.is_some_and(|limit| idle >= limit) {
+            terminate_process_tree(&mut child);
+            join_readers(readers);
+            return Err(if cancelled {
+                WatchedRunError::Cancelled
+            } else {
+                WatchedRunError::Hung(idle)
+            });
+        }
+    };
+    join_readers(readers);
+    Ok(status)
 }
 
 fn run_command_env(
@@ -1595,14 +4300,60 @@ fn run_command_env(
     envs: &[(&str, &str)],
 ) -> Result<(), String> {
     log::debug!("run_command_env: {} {}", program, args.join(" "));
-    let mut cmd = build_command(program, args, working_dir, envs)?;
-    let status = cmd
-        .status()
-        .map_err(|err| format!("Failed to run {program}: {err}"))?;
-    if !status.success() {
-        return Err(format!("Command failed: {} {}", program, args.join(" ")));
+    log_command_line(program, args);
+    let cancel = subprocess_cancel();
+    if cancel.as_ref().is_some_and(CancellationToken::is_cancelled) {
+        return Err(SUBPROCESS_CANCELLED.to_string());
+    }
+    let idle_timeout = subprocess_idle_timeout();
+    // Output has to be piped to reach install.log, which only the watched path does.
+    if idle_timeout.is_none() && cancel.is_none() && install_log().is_none() {
+        let mut cmd = build_command(program, args, working_dir, envs)?;
+        let status = cmd
+            .status()
+            .map_err(|err| format!("Failed to run {program}: {err}"))?;
+        if !status.success() {
+            return Err(format!("Command failed: {} {}", program, args.join(" ")));
+        }
+        return Ok(());
+    }
+
+    let command_line = format!("{} {}", program, args.join(" "));
+    let mut restart = 0;
+    loop {
+        match run_watched_command(
+            program,
+            args,
+            working_dir,
+            envs,
+            idle_timeout,
+            cancel.as_ref(),
+        ) {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(_) => return Err(format!("Command failed: {command_line}")),
+            Err(WatchedRunError::Failed(err)) => return Err(err),
+            Err(WatchedRunError::Cancelled) => return Err(SUBPROCESS_CANCELLED.to_string()),
+            Err(WatchedRunError::Hung(idle)) if restart < WATCHDOG_MAX_RESTARTS => {
+                restart += 1;
+                let message = format!(
+                    "No activity from `{command_line}` for {} min; killed and restarting (attempt {} of {}).",
+                    idle.as_secs() / 60,
+                    restart + 1,
+                    WATCHDOG_MAX_RESTARTS + 1
+                );
+                log::warn!("{message}");
+                if let Some(app) = GLOBAL_APP.get() {
+                    emit_install_event(app, "step_restarted", &message);
+                }
+            }
+            Err(WatchedRunError::Hung(idle)) => {
+                return Err(format!(
+                    "`{command_line}` hung with no activity for {} min after {WATCHDOG_MAX_RESTARTS} restarts.",
+                    idle.as_secs() / 60
+                ));
+            }
+        }
     }
-    Ok(())
 }
 
 fn pip_uninstall_best_effort(root: &Path, py_path: &str, packages: &[&str]) {
@@ -1748,51 +4499,134 @@ fn insightface_present(root: &Path) -> bool {
     pip_has_package(root, "insightface") || python_module_importable(root, "insightface")
 }
 
-fn linux_wheel_url(profile: &str, wheel_kind: &str, hopper_sm90: bool) -> Option<&'static str> {
-    match (profile, wheel_kind, hopper_sm90) {
-        ("torch271_cu128", "flash", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "insightface", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "nunchaku", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.7-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "sage", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "sage3", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "flash", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "insightface", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "nunchaku", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.8-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "sage", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "sage3", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "flash", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "insightface", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "nunchaku", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/nunchaku-1.3.0.dev20260215%2Bcu13.0torch2.9-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "sage", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "sage3", true) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "flash", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "insightface", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "nunchaku", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.7-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "sage", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch271_cu128", "sage3", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "flash", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "insightface", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "nunchaku", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.8-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "sage", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch280_cu128", "sage3", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "flash", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "insightface", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "nunchaku", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/nunchaku-1.3.0.dev20260215%2Bcu13.0torch2.9-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "sage", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
-        ("torch291_cu130", "sage3", false) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
+fn linux_wheel_url(profile: &str, wheel_kind: &str, wheel_arch: WheelArch) -> Option<&'static str> {
+    // Blackwell builds exist only for the kernels that benefit; the rest use the generic set.
+    let wheel_arch = match (wheel_arch, wheel_kind) {
+        (WheelArch::Sm120, "insightface" | "sage3") => WheelArch::Generic,
+        _ => wheel_arch,
+    };
+    match (profile, wheel_kind, wheel_arch) {
+        ("torch271_cu128", "flash", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "insightface", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "nunchaku", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.7-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "sage", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "sage3", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm90/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "flash", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "insightface", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "nunchaku", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.8-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "sage", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "sage3", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm90/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "flash", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "insightface", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "nunchaku", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/nunchaku-1.3.0.dev20260215%2Bcu13.0torch2.9-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "sage", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "sage3", WheelArch::Sm90) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm90/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "flash", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "insightface", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "nunchaku", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.7-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "sage", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "sage3", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "flash", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "insightface", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "nunchaku", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.8-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "sage", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "sage3", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "flash", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "insightface", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/insightface-0.7.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "nunchaku", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/nunchaku-1.3.0.dev20260215%2Bcu13.0torch2.9-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "sage", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "sage3", WheelArch::Generic) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312/sageattn3-1.0.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "flash", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm120/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "nunchaku", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm120/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.7-cp312-cp312-linux_x86_64.whl"),
+        ("torch271_cu128", "sage", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch271-py312-sm120/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "flash", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm120/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "nunchaku", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm120/nunchaku-1.3.0.dev20260215%2Bcu12.8torch2.8-cp312-cp312-linux_x86_64.whl"),
+        ("torch280_cu128", "sage", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu128-torch280-py312-sm120/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "flash", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm120/flash_attn-2.8.3-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "nunchaku", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm120/nunchaku-1.3.0.dev20260215%2Bcu13.0torch2.9-cp312-cp312-linux_x86_64.whl"),
+        ("torch291_cu130", "sage", WheelArch::Sm120) => Some("https://huggingface.co/arcticlatent/accelerator/resolve/main/cu130-torch291-py312-sm120/sageattention-2.2.0-cp312-cp312-linux_x86_64.whl"),
         _ => None,
     }
 }
 
+/// Prebuilt wheel kinds the request's add-ons install, without repeats.
+fn requested_wheel_kinds(request: &ComfyInstallRequest) -> Vec<&'static str> {
+    let selected: [(bool, &[&'static str]); 5] = [
+        (request.include_sage_attention, &["sage"]),
+        (request.include_sage_attention3, &["sage3", "sage"]),
+        (request.include_flash_attention, &["flash"]),
+        (request.include_nunchaku, &["nunchaku", "insightface"]),
+        (request.include_insight_face, &["insightface"]),
+    ];
+    let mut kinds: Vec<&'static str> = Vec::new();
+    for (_, wheel_kinds) in selected.iter().filter(|(included, _)| *included) {
+        for kind in wheel_kinds.iter() {
+            if !kinds.contains(kind) {
+                kinds.push(kind);
+            }
+        }
+    }
+    kinds
+}
+
+fn probe_wheel_url(client: &reqwest::blocking::Client, url: &str) -> Result<(), String> {
+    let response = client
+        .head(url)
+        .send()
+        .map_err(|err| format!("unreachable ({err})"))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status().as_u16()))
+    }
+}
+
+/// One message per wheel in `kinds` that has no mapping or does not answer a HEAD
+/// request, so a missing wheel fails preflight instead of the install's last steps.
+fn wheel_availability_failures(
+    profile: &str,
+    wheel_arch: WheelArch,
+    kinds: &[&str],
+) -> Vec<String> {
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("ArcticComfyUIHelper/0.3.4")
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => return vec![format!("Could not check the wheel host: {err}")],
+    };
+    let client = &client;
+    std::thread::scope(|scope| {
+        let probes: Vec<_> = kinds
+            .iter()
+            .map(|kind| {
+                scope.spawn(move || match linux_wheel_url(profile, kind, wheel_arch) {
+                    None => Some(format!(
+                        "No prebuilt {kind} wheel exists for {profile} on this GPU."
+                    )),
+                    Some(url) => probe_wheel_url(client, url).err().map(|err| {
+                        format!("The {kind} wheel for {profile} is not available ({err}): {url}")
+                    }),
+                })
+            })
+            .collect();
+        probes
+            .into_iter()
+            .filter_map(|probe| probe.join().ok().flatten())
+            .collect()
+    })
+}
+
 fn install_linux_wheel_for_profile(
     root: &Path,
     py_path: &str,
     profile: &str,
     wheel_kind: &str,
-    hopper_sm90: bool,
+    wheel_arch: WheelArch,
     force_reinstall: bool,
 ) -> Result<(), String> {
-    let wheel = linux_wheel_url(profile, wheel_kind, hopper_sm90).ok_or_else(|| {
+    let wheel = linux_wheel_url(profile, wheel_kind, wheel_arch).ok_or_else(|| {
         format!("No Linux wheel mapping for profile '{profile}' and wheel '{wheel_kind}'.")
     })?;
     let uv_bin = discover_uv_binary().ok_or_else(|| {
@@ -1813,18 +4647,18 @@ fn install_sageattention_linux(
     root: &Path,
     py_path: &str,
     profile: &str,
-    hopper_sm90: bool,
+    wheel_arch: WheelArch,
 ) -> Result<(), String> {
-    install_linux_wheel_for_profile(root, py_path, profile, "sage", hopper_sm90, true)
+    install_linux_wheel_for_profile(root, py_path, profile, "sage", wheel_arch, true)
 }
 
 fn install_flashattention_linux(
     root: &Path,
     py_path: &str,
     profile: &str,
-    hopper_sm90: bool,
+    wheel_arch: WheelArch,
 ) -> Result<(), String> {
-    install_linux_wheel_for_profile(root, py_path, profile, "flash", hopper_sm90, true)
+    install_linux_wheel_for_profile(root, py_path, profile, "flash", wheel_arch, true)
 }
 
 fn install_nunchaku_node_requirements(
@@ -1863,6 +4697,9 @@ fn install_nunchaku_node_requirements(
 }
 
 fn clone_or_update_repo(root: &Path, target_dir: &Path, repo_url: &str) -> Result<(), String> {
+    if !target_dir.exists() && restore_offline_node(target_dir)? {
+        return Ok(());
+    }
     if target_dir.join(".git").exists() {
         run_command(
             "git",
@@ -1895,6 +4732,8 @@ fn run_uv_pip_strict(
     working_dir: Option<&Path>,
     envs: &[(&str, &str)],
 ) -> Result<(), String> {
+    // An offline cache has to see every download, so cache bypasses are dropped.
+    let keep_cache = offline_cache().is_some();
     let mut uv_compatible_args: Vec<String> = Vec::new();
     let mut index = 0usize;
     while index < pip_args.len() {
@@ -1909,6 +4748,7 @@ fn run_uv_pip_strict(
         }
         match arg {
             "--force-reinstall" => uv_compatible_args.push("--reinstall".to_string()),
+            "--no-cache-dir" | "--no-cache" if keep_cache => {}
             "--no-cache-dir" => uv_compatible_args.push("--no-cache".to_string()),
             _ => uv_compatible_args.push(arg.to_string()),
         }
@@ -1929,17 +4769,25 @@ fn run_uv_pip_strict(
     }
 
     let args: Vec<&str> = args_owned.iter().map(String::as_str).collect();
-    let mut merged_envs: Vec<(&str, &str)> = Vec::with_capacity(envs.len() + 1);
+    let pypi_mirror = package_index_settings().pypi_index_url;
+    let mut merged_envs: Vec<(&str, &str)> = Vec::with_capacity(envs.len() + 2);
     merged_envs.push(("UV_LINK_MODE", "copy"));
+    // An explicit `--index-url` argument (the torch stack) still takes precedence.
+    if let Some(mirror) = pypi_mirror.as_deref() {
+        merged_envs.push(("UV_INDEX_URL", mirror));
+    }
     merged_envs.extend_from_slice(envs);
-    run_command_env(uv_bin, &args, working_dir, &merged_envs)
+    with_retry(retry_settings().pip, || {
+        run_command_env(uv_bin, &args, working_dir, &merged_envs)
+    })
 }
 fn profile_from_torch_env(root: &Path) -> Result<String, String> {
     let mut cmd = python_for_root(root);
     cmd.arg("-c").arg(
         "import torch; \
          v = getattr(torch, '__version__', ''); \
-         c = getattr(torch.version, 'cuda', '') or ''; \
+         h = getattr(torch.version, 'hip', '') or ''; \
+         c = getattr(torch.version, 'cuda', '') or ('rocm' + h if h else ''); \
          print(v); print(c)",
     );
     cmd.current_dir(root);
@@ -1971,7 +4819,130 @@ fn write_install_summary(install_root: &Path, items: &[InstallSummaryItem]) {
     }
 }
 
+/// Adds or replaces (by name) one entry of an existing install summary.
+fn record_install_summary_item(install_root: &Path, item: InstallSummaryItem) {
+    let mut items: Vec<InstallSummaryItem> =
+        std::fs::read(install_root.join("install-summary.json"))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+    items.retain(|existing| existing.name != item.name);
+    items.push(item);
+    write_install_summary(install_root, &items);
+}
+
+#[derive(Debug, Serialize)]
+struct InstallReportItem {
+    #[serde(flatten)]
+    item: InstallSummaryItem,
+    /// `apply_comfyui_component_toggle` component that retries a failed item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_component: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct InstallReport {
+    comfyui_root: String,
+    items: Vec<InstallReportItem>,
+    /// Checks of the install as it is now, next to what the installer recorded.
+    validation: Vec<InstallSummaryItem>,
+}
+
+fn validation_item(name: &str, result: Result<String, String>) -> InstallSummaryItem {
+    let (status, detail) = match result {
+        Ok(detail) => ("ok", detail),
+        Err(detail) => ("failed", detail),
+    };
+    InstallSummaryItem {
+        name: name.to_string(),
+        status: status.to_string(),
+        detail,
+    }
+}
+
+fn validate_install(settings: &AppSettings, root: &Path) -> Vec<InstallSummaryItem> {
+    let mut checks = Vec::new();
+    if read_container_install(root).is_some() {
+        return checks;
+    }
+    checks.push(validation_item(
+        "ComfyUI core",
+        if root.join("main.py").is_file() {
+            Ok("main.py is present.".to_string())
+        } else {
+            Err(format!("main.py not found in {}.", root.display()))
+        },
+    ));
+    checks.push(validation_item(
+        "Python environment",
+        python_exe_for_root(root).map(|python| format!("Using {}.", python.display())),
+    ));
+    let config = install_config(settings, root);
+    checks.push(validation_item(
+        "Torch profile",
+        profile_from_torch_env(root).and_then(|installed| match config.torch_profile {
+            Some(expected) if expected != installed => Err(format!(
+                "{installed} is installed but the install expects {expected}."
+            )),
+            _ => Ok(format!("{installed} is installed.")),
+        }),
+    ));
+    let active = active_attention_backend(settings, root);
+    if active != "none" {
+        checks.push(validation_item(
+            "Attention backend",
+            if installed_attention_backends(root).contains(&active.as_str()) {
+                Ok(format!("{active} is importable."))
+            } else {
+                Err(format!("{active} is selected but not importable."))
+            },
+        ));
+    }
+    checks
+}
+
+#[tauri::command]
+fn get_install_summary(
+    state: State<'_, AppState>,
+    install_root: Option<String>,
+) -> Result<InstallReport, String> {
+    let root = resolve_root_path(&state.context, install_root)?;
+    let path = root.join("install-summary.json");
+    let data = std::fs::read(&path)
+        .map_err(|err| format!("No install summary at {}: {err}", path.display()))?;
+    let summary: Vec<InstallSummaryItem> = serde_json::from_slice(&data)
+        .map_err(|err| format!("Install summary {} is not valid: {err}", path.display()))?;
+    let items = summary
+        .into_iter()
+        .map(|item| {
+            let retry_component = (item.status == "failed")
+                .then(|| {
+                    BUNDLED_CUSTOM_NODES
+                        .iter()
+                        .find(|(_, name, _)| name.eq_ignore_ascii_case(&item.name))
+                        .map(|(component, _, _)| *component)
+                })
+                .flatten();
+            InstallReportItem {
+                item,
+                retry_component,
+            }
+        })
+        .collect();
+    Ok(InstallReport {
+        comfyui_root: root.to_string_lossy().to_string(),
+        items,
+        validation: validate_install(&state.context.config.settings(), &root),
+    })
+}
+
 fn discover_uv_binary() -> Option<String> {
+    if let Some(cache) = offline_cache().filter(OfflineCache::consuming) {
+        let bundled = cache.dir.join("bin").join("uv");
+        if bundled.is_file() {
+            return Some(bundled.to_string_lossy().to_string());
+        }
+    }
     if command_available("uv", &["--version"]) {
         return Some("uv".to_string());
     }
@@ -2016,97 +4987,426 @@ fn resolve_uv_binary(shared_runtime_root: &Path, app: &AppHandle) -> Result<Stri
     )
 }
 
-fn emit_install_event(app: &AppHandle, phase: &str, message: &str) {
-    let _ = app.emit(
-        "comfyui-install-progress",
-        DownloadProgressEvent {
-            kind: "comfyui_install".to_string(),
-            phase: phase.to_string(),
-            artifact: None,
-            index: None,
-            total: None,
-            received: None,
-            size: None,
-            folder: None,
-            message: Some(message.to_string()),
-        },
-    );
+#[derive(Clone, Debug, Serialize)]
+struct ComfyInstallProgressEvent {
+    #[serde(flatten)]
+    base: DownloadProgressEvent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    step_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_steps: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percent: Option<u8>,
 }
 
-fn torch_profile_to_packages_linux(
-    profile: &str,
-) -> (&'static str, &'static str, &'static str, &'static str) {
-    match profile {
-        "torch271_cu128" => ("2.7.1", "0.22.1", "2.7.1", "https://download.pytorch.org/whl/cu128"),
-        "torch291_cu130" => ("2.9.1", "0.24.1", "2.9.1", "https://download.pytorch.org/whl/cu130"),
-        _ => ("2.8.0", "0.23.0", "2.8.0", "https://download.pytorch.org/whl/cu128"),
+/// Rough share of install time per step on a typical connection; the torch stack
+/// alone is most of it, while config writes are effectively free.
+fn install_step_weight(step: &str) -> u32 {
+    match step {
+        "torch_stack" => 40,
+        "addon_trellis2" => 30,
+        "comfy_requirements" => 12,
+        "linux_packages" | "finalize_torch_stack" => 6,
+        "addon_sageattention"
+        | "addon_sageattention3"
+        | "addon_flashattention"
+        | "addon_nunchaku"
+        | "addon_xformers"
+        | "addon_insightface" => 5,
+        "clone_comfyui" | "python_venv" => 4,
+        step if step.starts_with("node_") => 2,
+        _ => 1,
     }
 }
 
-fn torch_profile_from_versions(torch_v: &str, cuda_v: &str) -> Option<String> {
-    let t = torch_v.trim().to_ascii_lowercase();
-    let c = cuda_v.trim().to_ascii_lowercase();
-    if t.starts_with("2.7") && c.starts_with("12.8") {
-        return Some("torch271_cu128".to_string());
+/// Steps `run_comfyui_install_linux` will pass through for `request`, in order.
+fn planned_install_steps(
+    request: &ComfyInstallRequest,
+    clone_needed: bool,
+    apply_settings_template: bool,
+) -> Vec<&'static str> {
+    let has_path = |value: Option<&str>| value.is_some_and(|value| !value.trim().is_empty());
+    let mut steps = vec!["linux_packages"];
+    let optional = [
+        (clone_needed, "clone_comfyui"),
+        (
+            has_path(request.extra_model_root.as_deref()),
+            "extra_model_paths",
+        ),
+        (
+            has_path(request.output_dir.as_deref())
+                || has_path(request.input_dir.as_deref())
+                || has_path(request.user_dir.as_deref()),
+            "data_dirs",
+        ),
+        (apply_settings_template, "comfy_settings"),
+    ];
+    steps.extend(optional.iter().filter(|(on, _)| *on).map(|(_, step)| *step));
+    steps.extend(["python_venv", "torch_stack", "comfy_requirements"]);
+    if has_path(request.frontend_version.as_deref()) {
+        steps.push("comfy_frontend");
     }
-    if t.starts_with("2.8") && c.starts_with("12.8") {
-        return Some("torch280_cu128".to_string());
+    let addons = [
+        (request.include_sage_attention, "addon_sageattention"),
+        (
+            request.include_insight_face || request.include_nunchaku,
+            "addon_insightface",
+        ),
+        (request.include_flash_attention, "addon_flashattention"),
+        (request.include_sage_attention3, "addon_sageattention3"),
+        (request.include_nunchaku, "addon_nunchaku"),
+        (request.include_xformers, "addon_xformers"),
+        (request.include_trellis2, "addon_trellis2"),
+    ];
+    steps.extend(addons.iter().filter(|(on, _)| *on).map(|(_, step)| *step));
+    steps.extend(
+        requested_custom_nodes(request)
+            .into_iter()
+            .map(|(step, _, _)| step),
+    );
+    steps.push("finalize_torch_stack");
+    steps
+}
+
+/// Planned steps of a resumed install that already finished: everything before the step
+/// `.arctic_install_state.json` recorded as running when the install stopped.
+struct ResumePoint {
+    planned: Vec<&'static str>,
+    resume_index: Option<usize>,
+}
+
+impl ResumePoint {
+    fn new(planned: Vec<&'static str>, resume_from: Option<&str>) -> Self {
+        let resume_index =
+            resume_from.and_then(|step| planned.iter().position(|known| *known == step));
+        Self {
+            planned,
+            resume_index,
+        }
     }
-    if t.starts_with("2.9") && c.starts_with("13.0") {
-        return Some("torch291_cu130".to_string());
+
+    fn completed(&self, step: &str) -> bool {
+        let Some(resume_index) = self.resume_index else {
+            return false;
+        };
+        self.planned
+            .iter()
+            .position(|known| *known == step)
+            .is_some_and(|index| index < resume_index)
     }
-    None
 }
 
-fn triton_package_for_profile_linux(profile: &str) -> &'static str {
-    match profile {
-        "torch271_cu128" => "triton==3.3.1",
-        "torch291_cu130" => "triton<3.6",
-        _ => "triton==3.4.0",
+/// Where the install running on this thread is within its planned steps.
+struct InstallProgress {
+    steps: Vec<&'static str>,
+    current: Option<usize>,
+    completed: bool,
+}
+
+impl InstallProgress {
+    fn percent(&self) -> u8 {
+        if self.completed {
+            return 100;
+        }
+        let total: u32 = self
+            .steps
+            .iter()
+            .map(|step| install_step_weight(step))
+            .sum();
+        let done: u32 = self.steps[..self.current.unwrap_or(0)]
+            .iter()
+            .map(|step| install_step_weight(step))
+            .sum();
+        (done * 100 / total.max(1)).min(99) as u8
     }
 }
 
-fn enforce_torch_profile_linux(
-    uv_bin: &str,
-    py_path: &str,
+thread_local! {
+    static INSTALL_PROGRESS: RefCell<Option<InstallProgress>> = const { RefCell::new(None) };
+}
+
+/// Attaches step counts and a weighted percentage to install events on this thread
+/// until dropped.
+struct InstallProgressGuard {
+    previous: Option<InstallProgress>,
+}
+
+impl InstallProgressGuard {
+    fn start(steps: Vec<&'static str>) -> Self {
+        let progress = InstallProgress {
+            steps,
+            current: None,
+            completed: false,
+        };
+        Self {
+            previous: INSTALL_PROGRESS.with(|slot| slot.replace(Some(progress))),
+        }
+    }
+}
+
+impl Drop for InstallProgressGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        INSTALL_PROGRESS.with(|slot| *slot.borrow_mut() = previous);
+    }
+}
+
+fn advance_install_progress(status: &str, step: &str) {
+    INSTALL_PROGRESS.with(|slot| {
+        if let Some(progress) = slot.borrow_mut().as_mut() {
+            if status == "completed" {
+                progress.completed = true;
+            } else if let Some(index) = progress.steps.iter().position(|known| *known == step) {
+                progress.current = Some(index);
+            }
+        }
+    });
+}
+
+fn emit_install_event(app: &AppHandle, phase: &str, message: &str) {
+    if let Some(log) = install_log() {
+        log.write_line(&format!("[{phase}] {message}"));
+    }
+    let (step_index, total_steps, percent) =
+        INSTALL_PROGRESS.with(|slot| match slot.borrow().as_ref() {
+            Some(progress) => (
+                progress.current.map(|index| index + 1),
+                Some(progress.steps.len()),
+                Some(progress.percent()),
+            ),
+            None => (None, None, None),
+        });
+    let _ = app.emit(
+        "comfyui-install-progress",
+        ComfyInstallProgressEvent {
+            base: DownloadProgressEvent {
+                kind: "comfyui_install".to_string(),
+                phase: phase.to_string(),
+                artifact: None,
+                index: None,
+                total: None,
+                received: None,
+                size: None,
+                folder: None,
+                message: Some(message.to_string()),
+            },
+            step_index,
+            total_steps,
+            percent,
+        },
+    );
+}
+
+const TORCH_INDEX_BASE: &str = "https://download.pytorch.org/whl";
+
+/// Package index mirrors from settings; defaults before the app is set up.
+fn package_index_settings() -> PackageIndexSettings {
+    GLOBAL_APP
+        .get()
+        .map(|app| {
+            app.state::<AppState>()
+                .context
+                .config
+                .settings()
+                .package_index
+        })
+        .unwrap_or_default()
+}
+
+fn torch_profile_to_packages_linux(
+    profile: &str,
+) -> (&'static str, &'static str, &'static str, String) {
+    let (torch_v, vision_v, audio_v, channel) = match profile {
+        "torch271_cu128" => ("2.7.1", "0.22.1", "2.7.1", "cu128"),
+        "torch291_cu130" => ("2.9.1", "0.24.1", "2.9.1", "cu130"),
+        "torch280_rocm64" => ("2.8.0", "0.23.0", "2.8.0", "rocm6.4"),
+        "torch291_rocm64" => ("2.9.1", "0.24.1", "2.9.1", "rocm6.4"),
+        "torch_cpu" => ("2.8.0", "0.23.0", "2.8.0", "cpu"),
+        // Unpinned: whatever the nightly index currently serves.
+        TORCH_NIGHTLY_PROFILE => ("", "", "", "nightly/cu130"),
+        _ => ("2.8.0", "0.23.0", "2.8.0", "cu128"),
+    };
+    let base = package_index_settings()
+        .torch_index_base
+        .unwrap_or_else(|| TORCH_INDEX_BASE.to_string());
+    let index_url = format!("{}/{channel}", base.trim_end_matches('/'));
+    (torch_v, vision_v, audio_v, index_url)
+}
+
+/// `torch==<version>`, or just the package name for profiles that do not pin one.
+fn torch_requirement(package: &str, version: &str) -> String {
+    if version.is_empty() {
+        package.to_string()
+    } else {
+        format!("{package}=={version}")
+    }
+}
+
+fn torch_profile_from_versions(torch_v: &str, cuda_v: &str) -> Option<String> {
+    let t = torch_v.trim().to_ascii_lowercase();
+    let c = cuda_v.trim().to_ascii_lowercase();
+    // Nightlies report e.g. `2.10.0.dev20251015+cu130`. Any dev build on CUDA 13 counts,
+    // so an install stays recognised as the nightly moves past the next stable release.
+    if t.contains(".dev") && c.starts_with("13.") {
+        return Some(TORCH_NIGHTLY_PROFILE.to_string());
+    }
+    if t.starts_with("2.7") && c.starts_with("12.8") {
+        return Some("torch271_cu128".to_string());
+    }
+    if t.starts_with("2.8") && c.starts_with("12.8") {
+        return Some("torch280_cu128".to_string());
+    }
+    if t.starts_with("2.9") && c.starts_with("13.0") {
+        return Some("torch291_cu130".to_string());
+    }
+    if t.starts_with("2.8") && c.starts_with("rocm6.4") {
+        return Some("torch280_rocm64".to_string());
+    }
+    if t.starts_with("2.9") && c.starts_with("rocm6.4") {
+        return Some("torch291_rocm64".to_string());
+    }
+    if !t.is_empty() && c.is_empty() {
+        return Some("torch_cpu".to_string());
+    }
+    None
+}
+
+const TRITON_LATEST_COMPATIBLE: &str = "latest";
+
+/// Accepts `3.5.1`, `triton==3.5.1` or `latest` / `latest compatible`; blank clears it.
+fn normalize_triton_version(raw: Option<String>) -> Result<Option<String>, String> {
+    let Some(value) = raw.map(|value| value.trim().to_ascii_lowercase()) else {
+        return Ok(None);
+    };
+    let value = value.strip_prefix("triton==").unwrap_or(&value).trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if matches!(value, "latest" | "latest compatible" | "latest-compatible") {
+        return Ok(Some(TRITON_LATEST_COMPATIBLE.to_string()));
+    }
+    let valid = value.starts_with(|c: char| c.is_ascii_digit())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '+');
+    if !valid {
+        return Err(format!(
+            "Invalid Triton version {value}: use an exact version such as 3.5.1, or latest."
+        ));
+    }
+    Ok(Some(value.to_string()))
+}
+
+fn triton_version_override() -> Option<String> {
+    GLOBAL_APP.get().and_then(|app| {
+        app.state::<AppState>()
+            .context
+            .config
+            .settings()
+            .comfyui_triton_version
+    })
+}
+
+/// Triton requirement the venv's torch itself declares, e.g. `triton==3.4.0`.
+fn torch_declared_triton_requirement(py_path: &str) -> Option<String> {
+    let (stdout, _) = run_command_capture(
+        py_path,
+        &[
+            "-c",
+            "import importlib.metadata as m\n\
+             for r in m.requires('torch') or []:\n\
+             \x20   if r.split(';')[0].strip().startswith('triton'): print(r.split(';')[0].strip()); break",
+        ],
+        None,
+    )
+    .ok()?;
+    let requirement = stdout.trim().replace(' ', "");
+    (!requirement.is_empty()).then_some(requirement)
+}
+
+fn triton_package_for_profile_linux(py_path: &str, profile: &str) -> String {
+    match triton_version_override().as_deref() {
+        Some(TRITON_LATEST_COMPATIBLE) => {
+            torch_declared_triton_requirement(py_path).unwrap_or_else(|| "triton".to_string())
+        }
+        Some(version) => format!("triton=={version}"),
+        None => match profile {
+            "torch271_cu128" => "triton==3.3.1",
+            "torch291_cu130" => "triton<3.6",
+            _ => "triton==3.4.0",
+        }
+        .to_string(),
+    }
+}
+
+fn installed_triton_version(py_path: &str) -> Option<String> {
+    let (stdout, _) = run_command_capture(
+        py_path,
+        &[
+            "-c",
+            "import importlib.metadata as m; print(m.version('triton'))",
+        ],
+        None,
+    )
+    .ok()?;
+    let version = stdout.trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+fn enforce_torch_profile_linux(
+    uv_bin: &str,
+    py_path: &str,
     root: &Path,
     profile: &str,
     uv_python_install_dir: &str,
 ) -> Result<(), String> {
     let (torch_v, tv_v, ta_v, index_url) = torch_profile_to_packages_linux(profile);
+    let torch_req = torch_requirement("torch", torch_v);
+    let tv_req = torch_requirement("torchvision", tv_v);
+    let ta_req = torch_requirement("torchaudio", ta_v);
+    let mut args = vec![
+        "install",
+        "--upgrade",
+        "--reinstall",
+        torch_req.as_str(),
+        tv_req.as_str(),
+        ta_req.as_str(),
+        "--index-url",
+        index_url.as_str(),
+    ];
+    if is_nightly_torch_profile(profile) {
+        args.extend(["--prerelease", "allow"]);
+    }
     run_uv_pip_strict(
         uv_bin,
         py_path,
-        &[
-            "install",
-            "--upgrade",
-            "--reinstall",
-            &format!("torch=={torch_v}"),
-            &format!("torchvision=={tv_v}"),
-            &format!("torchaudio=={ta_v}"),
-            "--index-url",
-            index_url,
-        ],
-        Some(root),
-        &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
-    )?;
-    run_uv_pip_strict(
-        uv_bin,
-        py_path,
-        &[
-            "install",
-            "--upgrade",
-            "--reinstall",
-            triton_package_for_profile_linux(profile),
-        ],
+        &args,
         Some(root),
         &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
     )?;
+    // PyPI triton is CUDA-only; ROCm wheels pull pytorch-triton-rocm from their own index
+    // and CPU torch has no use for it. Nightly torch depends on the matching
+    // pytorch-triton build, which a PyPI release would clobber.
+    if torch_profile_uses_cuda(profile) && !is_nightly_torch_profile(profile) {
+        run_uv_pip_strict(
+            uv_bin,
+            py_path,
+            &[
+                "install",
+                "--upgrade",
+                "--reinstall",
+                &triton_package_for_profile_linux(py_path, profile),
+            ],
+            Some(root),
+            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+        )?;
+    }
     let mut verify_cmd = std::process::Command::new(py_path);
     verify_cmd.arg("-c").arg(
         "import torch, importlib.metadata as m; \
+         h = getattr(torch.version, 'hip', '') or ''; \
          print(getattr(torch, '__version__', '')); \
-         print(getattr(torch.version, 'cuda', '') or ''); \
+         print(getattr(torch.version, 'cuda', '') or ('rocm' + h if h else '')); \
          print(m.version('torchvision')); \
          print(m.version('torchaudio'))",
     );
@@ -2138,7 +5438,8 @@ fn infer_torch_profile_from_installed_packages(root: &Path) -> Option<String> {
     cmd.arg("-c").arg(
         "import importlib.metadata as m, torch; \
          ta = m.version('torchaudio') if m else ''; \
-         c = getattr(torch.version, 'cuda', '') or ''; \
+         h = getattr(torch.version, 'hip', '') or ''; \
+         c = getattr(torch.version, 'cuda', '') or ('rocm' + h if h else ''); \
          print(ta); print(c)",
     );
     cmd.current_dir(root);
@@ -2148,18 +5449,10 @@ fn infer_torch_profile_from_installed_packages(root: &Path) -> Option<String> {
     }
     let text = String::from_utf8_lossy(&out.stdout);
     let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
-    let ta_v = lines.next().unwrap_or_default().to_ascii_lowercase();
-    let cuda_v = lines.next().unwrap_or_default().to_ascii_lowercase();
-    if ta_v.starts_with("2.7") && cuda_v.starts_with("12.8") {
-        return Some("torch271_cu128".to_string());
-    }
-    if ta_v.starts_with("2.8") && cuda_v.starts_with("12.8") {
-        return Some("torch280_cu128".to_string());
-    }
-    if ta_v.starts_with("2.9") && cuda_v.starts_with("13.0") {
-        return Some("torch291_cu130".to_string());
-    }
-    None
+    let ta_v = lines.next().unwrap_or_default();
+    let cuda_v = lines.next().unwrap_or_default();
+    // torchaudio shares torch's version numbering, nightlies included.
+    torch_profile_from_versions(ta_v, cuda_v)
 }
 
 fn detect_torch_profile_for_root(root: &Path) -> Option<String> {
@@ -2178,38 +5471,19 @@ fn resolve_desired_torch_profile(settings: &AppSettings, root: &Path) -> String
                 .ok_or_else(|| "no profile hint".to_string())
         })
         .or_else(|_| {
-            settings
-                .comfyui_torch_profile
-                .clone()
+            install_config(settings, root)
+                .torch_profile
                 .ok_or_else(|| "no saved profile".to_string())
         })
         .unwrap_or_else(|_| get_comfyui_install_recommendation().torch_profile)
 }
 
-fn install_custom_node(
+fn install_custom_node_requirements(
     app: &AppHandle,
     install_root: &Path,
-    custom_nodes_root: &Path,
+    node_dir: &Path,
     py_exe: &Path,
-    repo_url: &str,
-    folder_name: &str,
 ) -> Result<(), String> {
-    emit_install_event(
-        app,
-        "step",
-        &format!("Installing custom node: {folder_name}..."),
-    );
-    let node_dir = custom_nodes_root.join(folder_name);
-    if node_dir.exists() {
-        let _ = std::fs::remove_dir_all(&node_dir);
-    }
-    run_command_with_retry(
-        "git",
-        &["clone", repo_url, &node_dir.to_string_lossy()],
-        Some(install_root),
-        2,
-    )?;
-
     let req = node_dir.join("requirements.txt");
     if req.exists() {
         let non_empty = std::fs::metadata(&req)
@@ -2244,6 +5518,55 @@ fn install_custom_node(
             )?;
         }
     }
+    Ok(())
+}
+
+fn install_custom_node(
+    app: &AppHandle,
+    install_root: &Path,
+    custom_nodes_root: &Path,
+    py_exe: &Path,
+    repo_url: &str,
+    folder_name: &str,
+) -> Result<(), String> {
+    emit_install_event(
+        app,
+        "step",
+        &format!("Installing custom node: {folder_name}..."),
+    );
+    let node_dir = fetch_custom_node(install_root, custom_nodes_root, repo_url, folder_name)?;
+    install_custom_node_deps(app, install_root, &node_dir, py_exe)
+}
+
+/// Replaces `folder_name` with a fresh clone (or the offline cache's copy).
+fn fetch_custom_node(
+    install_root: &Path,
+    custom_nodes_root: &Path,
+    repo_url: &str,
+    folder_name: &str,
+) -> Result<PathBuf, String> {
+    let node_dir = custom_nodes_root.join(folder_name);
+    if node_dir.exists() {
+        let _ = std::fs::remove_dir_all(&node_dir);
+    }
+    if !restore_offline_node(&node_dir)? {
+        run_command_with_retry(
+            "git",
+            &["clone", repo_url, &node_dir.to_string_lossy()],
+            Some(install_root),
+            retry_settings().git,
+        )?;
+    }
+    Ok(node_dir)
+}
+
+fn install_custom_node_deps(
+    app: &AppHandle,
+    install_root: &Path,
+    node_dir: &Path,
+    py_exe: &Path,
+) -> Result<(), String> {
+    install_custom_node_requirements(app, install_root, node_dir, py_exe)?;
 
     let installer = node_dir.join("install.py");
     if installer.exists() {
@@ -2262,18 +5585,93 @@ fn install_custom_node(
     Ok(())
 }
 
+/// Custom nodes cloned at the same time during a fresh install.
+const NODE_INSTALL_PARALLELISM: usize = 3;
+
+/// Installs `nodes` with their clones running in parallel. Requirement installs still
+/// take turns: concurrent uv runs against one venv can overwrite each other's files.
+/// Results come back in the order of `nodes`.
+fn install_custom_nodes_parallel(
+    app: &AppHandle,
+    install_root: &Path,
+    custom_nodes_root: &Path,
+    py_exe: &Path,
+    nodes: &[(&'static str, &'static str, &'static str)],
+) -> Vec<(&'static str, Result<(), String>)> {
+    let context = InstallThreadContext::capture();
+    let queue = Mutex::new(nodes.iter());
+    let venv_lock = Mutex::new(());
+    let results = Mutex::new(Vec::new());
+    let (queue, venv_lock, results_ref) = (&queue, &venv_lock, &results);
+    std::thread::scope(|scope| {
+        for _ in 0..NODE_INSTALL_PARALLELISM.min(nodes.len()) {
+            let context = context.clone();
+            scope.spawn(move || {
+                context.enter(|| loop {
+                    let next = queue.lock().ok().and_then(|mut queue| queue.next());
+                    let Some(&(step, name, repo_url)) = next else {
+                        break;
+                    };
+                    arm_forced_step_failure(Some(step));
+                    emit_install_event(app, "step", &format!("Installing custom node: {name}..."));
+                    let result = fetch_custom_node(install_root, custom_nodes_root, repo_url, name)
+                        .and_then(|node_dir| {
+                            let _venv = venv_lock
+                                .lock()
+                                .map_err(|_| "venv lock poisoned".to_string())?;
+                            install_custom_node_deps(app, install_root, &node_dir, py_exe)
+                        });
+                    if let Ok(mut results) = results_ref.lock() {
+                        results.push((name, result));
+                    }
+                })
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap_or_default();
+    results.sort_by_key(|(name, _)| nodes.iter().position(|(_, known, _)| known == name));
+    results
+}
+
 fn selected_attention_backend(request: &ComfyInstallRequest) -> &'static str {
-    if request.include_flash_attention {
-        "flash"
-    } else if request.include_sage_attention3 {
-        "sage3"
-    } else if request.include_sage_attention {
-        "sage"
-    } else if request.include_nunchaku {
-        "nunchaku"
-    } else {
-        "none"
+    let selected: Vec<&'static str> = [
+        (request.include_flash_attention, "flash"),
+        (request.include_sage_attention3, "sage3"),
+        (request.include_sage_attention, "sage"),
+        (request.include_nunchaku, "nunchaku"),
+    ]
+    .into_iter()
+    .filter(|(included, _)| *included)
+    .map(|(_, backend)| backend)
+    .collect();
+    let preferred = request.attention_backend.as_deref().map(str::trim);
+    if preferred == Some("none") {
+        return "none";
+    }
+    selected
+        .iter()
+        .copied()
+        .find(|backend| preferred == Some(*backend))
+        .or_else(|| selected.first().copied())
+        .unwrap_or("none")
+}
+
+/// Attention backends importable in the install, in launch-detection order.
+fn installed_attention_backends(root: &Path) -> Vec<&'static str> {
+    let mut backends = Vec::new();
+    if python_module_importable(root, "flash_attn") {
+        backends.push("flash");
+    }
+    if python_module_importable(root, "sageattn3") {
+        backends.push("sage3");
+    }
+    if python_module_importable(root, "sageattention") {
+        backends.push("sage");
+    }
+    if nunchaku_backend_present(root) {
+        backends.push("nunchaku");
     }
+    backends
 }
 
 fn append_attention_launch_arg(args: &mut Vec<String>, backend: Option<&str>) {
@@ -2350,10 +5748,10 @@ fn collect_cuda_runtime_library_paths(root: &Path) -> Vec<PathBuf> {
     dirs
 }
 
-fn apply_cuda_runtime_env_for_root(cmd: &mut std::process::Command, root: &Path) {
+fn cuda_runtime_library_path(root: &Path) -> Option<std::ffi::OsString> {
     let mut paths = collect_cuda_runtime_library_paths(root);
     if paths.is_empty() {
-        return;
+        return None;
     }
     if let Some(existing) = std::env::var_os("LD_LIBRARY_PATH") {
         for p in std::env::split_paths(&existing) {
@@ -2362,7 +5760,39 @@ fn apply_cuda_runtime_env_for_root(cmd: &mut std::process::Command, root: &Path)
             }
         }
     }
-    if let Ok(joined) = std::env::join_paths(paths) {
+    std::env::join_paths(paths).ok()
+}
+
+/// Whether the venv holds a CPU-only torch build (`+cpu` local version), read from
+/// `torch/version.py` so it is cheap enough to call on every launch.
+fn installed_torch_is_cpu_only(root: &Path) -> bool {
+    let install_dir = root.parent().unwrap_or(root);
+    [root, install_dir]
+        .iter()
+        .flat_map(|base| [base.join(".venv"), base.join("venv")])
+        .filter_map(|venv| std::fs::read_dir(venv.join("lib")).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter_map(|py_dir| {
+            std::fs::read_to_string(
+                py_dir
+                    .join("site-packages")
+                    .join("torch")
+                    .join("version.py"),
+            )
+            .ok()
+        })
+        .any(|content| {
+            content
+                .lines()
+                .any(|line| line.trim_start().starts_with("__version__") && line.contains("+cpu"))
+        })
+}
+
+fn apply_cuda_runtime_env_for_root(cmd: &mut std::process::Command, root: &Path) {
+    if installed_torch_is_cpu_only(root) {
+        return;
+    }
+    if let Some(joined) = cuda_runtime_library_path(root) {
         cmd.env("LD_LIBRARY_PATH", joined);
     }
 }
@@ -2402,25 +5832,425 @@ fn python_module_importable(root: &Path, module: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Per-install VRAM overrides; `auto` sizes the flags by the launch GPU's tier.
+const VRAM_MODES: &[&str] = &["auto", "high", "normal", "low", "none"];
+
+/// ComfyUI memory flags for a VRAM mode. Automatic mode keeps models resident on
+/// Tier S cards and offloads aggressively below Tier B.
+fn vram_launch_flags(
+    mode: Option<&str>,
+    tier: Option<VramTier>,
+    cache_none: bool,
+) -> Vec<&'static str> {
+    let mut flags = match mode.unwrap_or("auto") {
+        "high" => vec!["--highvram"],
+        "normal" => Vec::new(),
+        "low" => vec!["--lowvram"],
+        "none" => vec!["--novram"],
+        _ => match tier {
+            Some(VramTier::TierS) => vec!["--highvram"],
+            Some(VramTier::TierC) => vec!["--lowvram"],
+            _ => Vec::new(),
+        },
+    };
+    if cache_none {
+        flags.push("--cache-none");
+    }
+    flags
+}
+
+/// Tier of the GPU an install launches on: its first selected device, else GPU 0,
+/// else the tier last picked on the models tab when nvidia-smi finds nothing.
+fn launch_vram_tier(settings: &AppSettings, config: &InstallConfig) -> Option<VramTier> {
+    // The devices ComfyUI will actually see: the install's own variables win over the
+    // GPU pick, and the app's environment is inherited when neither sets them.
+    let selected = config
+        .launch_env()
+        .remove("CUDA_VISIBLE_DEVICES")
+        .or_else(|| std::env::var("CUDA_VISIBLE_DEVICES").ok())
+        .and_then(|devices| {
+            devices
+                .split(',')
+                .next()
+                .and_then(|index| index.trim().parse::<u32>().ok())
+        });
+    let gpus = query_nvidia_gpus_blocking();
+    let gpu = match selected {
+        Some(index) => gpus.into_iter().find(|gpu| gpu.index == index),
+        None => gpus.into_iter().next(),
+    };
+    gpu.and_then(|gpu| gpu.details.vram_mb)
+        .map(|mb| VramTier::from_vram_gb(mb as f64 / 1024.0))
+        .or_else(|| {
+            settings
+                .last_selection
+                .vram_tier
+                .as_deref()
+                .and_then(VramTier::from_identifier)
+        })
+}
+
 fn comfyui_launch_args(
     pinned_memory_enabled: bool,
     attention_backend: Option<&str>,
+    xformers_installed: bool,
+    data_dirs: &ComfyDataDirs,
+    cpu_only: bool,
+    vram_flags: &[&str],
 ) -> Vec<String> {
     let mut args: Vec<String> = Vec::new();
+    if cpu_only {
+        args.push("--cpu".to_string());
+    } else {
+        args.extend(vram_flags.iter().map(|flag| flag.to_string()));
+    }
     if !pinned_memory_enabled {
         args.push("--disable-pinned-memory".to_string());
     }
+    // ComfyUI uses xformers whenever it imports, ahead of --use-flash-attention, so
+    // turn it off when another backend was picked explicitly.
+    if xformers_installed && matches!(attention_backend, Some("flash" | "sage" | "sage3")) {
+        args.push("--disable-xformers".to_string());
+    }
     append_attention_launch_arg(&mut args, attention_backend);
+    for (flag, path) in data_dirs.entries() {
+        args.push(flag.to_string());
+        args.push(path.to_string_lossy().to_string());
+    }
     args
 }
 
-fn run_comfyui_install(
-    app: &AppHandle,
-    request: &ComfyInstallRequest,
-    shared_runtime_root: &Path,
-    cancel: &CancellationToken,
-) -> Result<PathBuf, String> {
-    run_comfyui_install_linux(app, request, shared_runtime_root, cancel)
+const OFFLINE_CACHE_MANIFEST: &str = "arctic-offline-cache.json";
+const OFFLINE_UV_CACHE_DIR: &str = "uv-cache";
+const OFFLINE_COMFYUI_BUNDLE: &str = "comfyui.bundle";
+const OFFLINE_NODES_DIR: &str = "nodes";
+
+/// An offline cache the install on this thread fills (`fill`) or reads exclusively.
+#[derive(Clone, Debug)]
+struct OfflineCache {
+    dir: PathBuf,
+    fill: bool,
+}
+
+impl OfflineCache {
+    fn consuming(&self) -> bool {
+        !self.fill
+    }
+
+    fn node_tarball(&self, folder_name: &str) -> PathBuf {
+        self.dir
+            .join(OFFLINE_NODES_DIR)
+            .join(format!("{folder_name}.tar.gz"))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OfflineCacheManifest {
+    created_at: u64,
+    python_version: String,
+    torch_profile: String,
+    comfyui_commit: Option<String>,
+    custom_nodes: Vec<String>,
+}
+
+thread_local! {
+    static OFFLINE_CACHE: RefCell<Option<OfflineCache>> = const { RefCell::new(None) };
+}
+
+/// Runs `op` with every uv call on this thread pointed at the cache's uv cache. When
+/// consuming, uv runs with `UV_OFFLINE` and repos come from the bundle and tarballs.
+fn with_offline_cache<T>(cache: OfflineCache, op: impl FnOnce() -> T) -> T {
+    let previous = OFFLINE_CACHE.with(|slot| slot.replace(Some(cache)));
+    let result = op();
+    OFFLINE_CACHE.with(|slot| *slot.borrow_mut() = previous);
+    result
+}
+
+fn offline_cache() -> Option<OfflineCache> {
+    OFFLINE_CACHE.with(|slot| slot.borrow().clone())
+}
+
+/// The per-thread install state (cancel token, offline cache, install.log) a helper
+/// thread needs to run installer commands the same way the installer thread does.
+#[derive(Clone)]
+struct InstallThreadContext {
+    cancel: Option<CancellationToken>,
+    offline: Option<OfflineCache>,
+    log: Option<InstallLog>,
+}
+
+impl InstallThreadContext {
+    fn capture() -> Self {
+        Self {
+            cancel: subprocess_cancel(),
+            offline: offline_cache(),
+            log: install_log(),
+        }
+    }
+
+    fn enter<T>(self, op: impl FnOnce() -> T) -> T {
+        let _log = InstallLogGuard::attach(self.log);
+        let with_cancel = || match self.cancel.as_ref() {
+            Some(cancel) => with_subprocess_cancel(cancel, op),
+            None => op(),
+        };
+        match self.offline {
+            Some(cache) => with_offline_cache(cache, with_cancel),
+            None => with_cancel(),
+        }
+    }
+}
+
+fn read_offline_cache_manifest(dir: &Path) -> Result<OfflineCacheManifest, String> {
+    let path = dir.join(OFFLINE_CACHE_MANIFEST);
+    let data = std::fs::read(&path).map_err(|err| {
+        format!(
+            "{} is not an offline install cache ({}: {err}).",
+            dir.display(),
+            path.display()
+        )
+    })?;
+    serde_json::from_slice(&data)
+        .map_err(|err| format!("Invalid offline cache manifest {}: {err}", path.display()))
+}
+
+/// Unpacks a cached custom node into `target_dir`. `Ok(false)` means no cache is being
+/// consumed and the caller should clone as usual.
+fn restore_offline_node(target_dir: &Path) -> Result<bool, String> {
+    let Some(cache) = offline_cache().filter(OfflineCache::consuming) else {
+        return Ok(false);
+    };
+    let (Some(parent), Some(name)) = (target_dir.parent(), target_dir.file_name()) else {
+        return Err(format!(
+            "Invalid custom node folder: {}",
+            target_dir.display()
+        ));
+    };
+    let tarball = cache.node_tarball(&name.to_string_lossy());
+    if !tarball.is_file() {
+        return Err(format!(
+            "{} is not in the offline cache; rebuild the cache with this node selected.",
+            name.to_string_lossy()
+        ));
+    }
+    std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    run_command(
+        "tar",
+        &[
+            "-xzf",
+            &tarball.to_string_lossy(),
+            "-C",
+            &parent.to_string_lossy(),
+        ],
+        None,
+    )?;
+    Ok(true)
+}
+
+fn uv_binary_path(uv_bin: &str) -> Option<PathBuf> {
+    let direct = PathBuf::from(uv_bin);
+    if direct.is_absolute() {
+        return direct.is_file().then_some(direct);
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(uv_bin))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Turns a finished staging install into the cache layout: a git bundle of ComfyUI, one
+/// tarball per custom node, the uv-managed Python and the uv binary.
+fn export_offline_cache(
+    cache_dir: &Path,
+    comfy_root: &Path,
+    shared_runtime_root: &Path,
+    torch_profile: &str,
+) -> Result<OfflineCacheManifest, String> {
+    let bundle = cache_dir.join(OFFLINE_COMFYUI_BUNDLE);
+    let _ = std::fs::remove_file(&bundle);
+    run_command(
+        "git",
+        &["bundle", "create", &bundle.to_string_lossy(), "--all"],
+        Some(comfy_root),
+    )?;
+
+    let nodes_dir = cache_dir.join(OFFLINE_NODES_DIR);
+    if nodes_dir.exists() {
+        std::fs::remove_dir_all(&nodes_dir).map_err(|err| err.to_string())?;
+    }
+    std::fs::create_dir_all(&nodes_dir).map_err(|err| err.to_string())?;
+    let custom_nodes_root = comfy_root.join("custom_nodes");
+    let mut custom_nodes = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(&custom_nodes_root) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !entry.path().is_dir() || name.starts_with('.') || name == "__pycache__" {
+                continue;
+            }
+            let tarball = nodes_dir.join(format!("{name}.tar.gz"));
+            run_command(
+                "tar",
+                &[
+                    "-czf",
+                    &tarball.to_string_lossy(),
+                    "--exclude=__pycache__",
+                    "-C",
+                    &custom_nodes_root.to_string_lossy(),
+                    &name,
+                ],
+                None,
+            )?;
+            custom_nodes.push(name);
+        }
+    }
+    custom_nodes.sort();
+
+    let python_dir = cache_dir.join("python");
+    std::fs::create_dir_all(&python_dir).map_err(|err| err.to_string())?;
+    let python_store = shared_runtime_root.join(".python");
+    run_command(
+        "cp",
+        &[
+            "-a",
+            &format!("{}/.", python_store.to_string_lossy()),
+            &python_dir.to_string_lossy(),
+        ],
+        None,
+    )?;
+
+    let uv_bin = discover_uv_binary()
+        .and_then(|found| uv_binary_path(&found))
+        .ok_or_else(|| "uv executable not found; cannot add it to the cache.".to_string())?;
+    let bin_dir = cache_dir.join("bin");
+    std::fs::create_dir_all(&bin_dir).map_err(|err| err.to_string())?;
+    std::fs::copy(&uv_bin, bin_dir.join("uv"))
+        .map_err(|err| format!("Failed to copy {}: {err}", uv_bin.display()))?;
+
+    let manifest = OfflineCacheManifest {
+        created_at: unix_now_secs(),
+        python_version: UV_PYTHON_VERSION.to_string(),
+        torch_profile: torch_profile.to_string(),
+        comfyui_commit: git_commit_for_ref(comfy_root, "HEAD"),
+        custom_nodes,
+    };
+    let data = serde_json::to_vec_pretty(&manifest).map_err(|err| err.to_string())?;
+    std::fs::write(cache_dir.join(OFFLINE_CACHE_MANIFEST), data).map_err(|err| err.to_string())?;
+    Ok(manifest)
+}
+
+/// Optional custom nodes selected in the install request as (install step, folder, repo).
+/// Custom nodes the installer offers, as (install step and toggle component, folder, repository).
+const BUNDLED_CUSTOM_NODES: [(&str, &str, &str); 6] = [
+    (
+        "node_comfyui_manager",
+        "ComfyUI-Manager",
+        "https://github.com/Comfy-Org/ComfyUI-Manager",
+    ),
+    (
+        "node_comfyui_easy_use",
+        "ComfyUI-Easy-Use",
+        "https://github.com/yolain/ComfyUI-Easy-Use",
+    ),
+    (
+        "node_rgthree_comfy",
+        "rgthree-comfy",
+        "https://github.com/rgthree/rgthree-comfy",
+    ),
+    (
+        "node_comfyui_gguf",
+        "ComfyUI-GGUF",
+        "https://github.com/city96/ComfyUI-GGUF",
+    ),
+    (
+        "node_comfyui_kjnodes",
+        "comfyui-kjnodes",
+        "https://github.com/kijai/ComfyUI-KJNodes",
+    ),
+    (
+        "node_comfyui_crystools",
+        "comfyui-crystools",
+        "https://github.com/crystian/comfyui-crystools.git",
+    ),
+];
+
+fn requested_custom_nodes(
+    request: &ComfyInstallRequest,
+) -> Vec<(&'static str, &'static str, &'static str)> {
+    [
+        request.node_comfyui_manager,
+        request.node_comfyui_easy_use,
+        request.node_rgthree_comfy,
+        request.node_comfyui_gguf,
+        request.node_comfyui_kjnodes,
+        request.node_comfyui_crystools,
+    ]
+    .into_iter()
+    .zip(BUNDLED_CUSTOM_NODES)
+    .filter(|(selected, _)| *selected)
+    .map(|(_, node)| node)
+    .collect()
+}
+
+fn run_comfyui_install(
+    app: &AppHandle,
+    request: &ComfyInstallRequest,
+    shared_runtime_root: &Path,
+    cancel: &CancellationToken,
+) -> Result<PathBuf, String> {
+    let offline_dir = request
+        .offline_cache
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(normalize_path)
+        .transpose()?;
+    let container_engine = request
+        .container_engine
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let result = match (offline_dir, container_engine) {
+        (Some(_), Some(_)) => Err("Container installs cannot use an offline cache.".to_string()),
+        (None, Some(engine)) => with_subprocess_cancel(cancel, || {
+            run_comfyui_install_container(app, request, engine, cancel)
+        }),
+        (Some(dir), None) => read_offline_cache_manifest(&dir).and_then(|manifest| {
+            emit_install_event(
+                app,
+                "info",
+                &format!(
+                    "Installing offline from {} (built for {}).",
+                    dir.display(),
+                    manifest.torch_profile
+                ),
+            );
+            let cache = OfflineCache { dir, fill: false };
+            with_offline_cache(cache, || {
+                with_subprocess_cancel(cancel, || {
+                    run_comfyui_install_linux(app, request, shared_runtime_root, cancel)
+                })
+            })
+        }),
+        (None, None) => with_subprocess_cancel(cancel, || {
+            run_comfyui_install_linux(app, request, shared_runtime_root, cancel)
+        }),
+    };
+    // Blocking-pool threads are reused; never leave a failure armed for the next job.
+    arm_forced_step_failure(None);
+    if let Err(err) = &result {
+        // The install folder is chosen inside the run, so find it again the
+        // same way resume does; only a still-running step gets marked.
+        let install_root = normalize_path(&request.install_root).ok().and_then(|base| {
+            if path_name_is_comfyui(&base) {
+                Some(base)
+            } else {
+                find_in_progress_install(&base).map(|(dir, _)| dir)
+            }
+        });
+        if let Some(install_root) = install_root {
+            record_install_step_failure(&install_root, err, cancel.is_cancelled());
+        }
+    }
+    result
 }
 fn run_comfyui_install_linux(
     app: &AppHandle,
@@ -2430,19 +6260,11 @@ fn run_comfyui_install_linux(
 ) -> Result<PathBuf, String> {
     let mut summary: Vec<InstallSummaryItem> = Vec::new();
     let include_insight_face = request.include_insight_face || request.include_nunchaku;
-    let selected_attention = [
-        request.include_sage_attention,
-        request.include_sage_attention3,
-        request.include_flash_attention,
-        request.include_nunchaku,
-    ]
-    .into_iter()
-    .filter(|v| *v)
-    .count();
-    if selected_attention > 1 {
+    let frontend_version = normalize_frontend_version(request.frontend_version.as_deref())?;
+    let offline = offline_cache();
+    if offline.is_some() && request.include_trellis2 {
         return Err(
-            "Choose only one of SageAttention, SageAttention3, FlashAttention, or Nunchaku."
-                .to_string(),
+            "TRELLIS2 downloads models during install and cannot be installed offline.".to_string(),
         );
     }
 
@@ -2452,6 +6274,15 @@ fn run_comfyui_install_linux(
 
     let base_root = normalize_path(&request.install_root)?;
     let extra_model_root = normalize_optional_path(request.extra_model_root.as_deref())?;
+    let repo_url = match request
+        .comfyui_repo_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(raw) => validate_comfyui_repo_url(raw)?,
+        None => COMFYUI_REPO_URL.to_string(),
+    };
     let selected_comfy_root = path_name_is_comfyui(&base_root);
     let comfy_dir = if selected_comfy_root {
         base_root.clone()
@@ -2461,12 +6292,56 @@ fn run_comfyui_install_linux(
     let install_root = comfy_dir.clone();
 
     std::fs::create_dir_all(&install_root).map_err(|err| err.to_string())?;
+    let _install_log = InstallLogGuard::start(&install_root);
+    let settings_template = app
+        .state::<AppState>()
+        .context
+        .config
+        .settings()
+        .comfy_settings_template;
+    let apply_settings_template =
+        settings_template.apply_after_install && !settings_template.values.is_empty();
+    let planned_steps = planned_install_steps(
+        request,
+        !comfy_dir.join("main.py").exists(),
+        apply_settings_template,
+    );
+    let resume = ResumePoint::new(planned_steps.clone(), request.resume_from.as_deref());
+    let _install_progress = InstallProgressGuard::start(planned_steps);
+    // Skipped steps are still recorded so a second interruption resumes from the right place.
+    let skip_completed = |step: &str| {
+        if !resume.completed(step) {
+            return false;
+        }
+        write_install_state(&install_root, "in_progress", step);
+        emit_install_event(
+            app,
+            "step",
+            &format!("Skipping {step}; it finished before the install was interrupted."),
+        );
+        true
+    };
+    let previous_failure = read_install_state(&install_root)
+        .filter(|state| state.status == "in_progress")
+        .and_then(|state| last_install_failure(&state).cloned());
     write_install_state(&install_root, "in_progress", "init");
     emit_install_event(
         app,
         "info",
         &format!("Install folder selected: {}", install_root.display()),
     );
+    if let Some(failed) = previous_failure {
+        emit_install_event(
+            app,
+            "info",
+            &format!(
+                "Resuming install. Previous attempt stopped at step '{}': {}",
+                failed.step,
+                failed.error.as_deref().unwrap_or("no error recorded")
+            ),
+        );
+    }
+    let requested_ref = requested_comfyui_ref(request.comfyui_ref.as_deref())?;
 
     let mut scan = get_linux_prereq_cache_or_scan()?;
     let distro = scan.distro.clone();
@@ -2478,6 +6353,37 @@ fn run_comfyui_install_linux(
     write_install_state(&install_root, "in_progress", "linux_packages");
     if scan.missing_required.is_empty() && scan.missing_optional.is_empty() {
         emit_install_event(app, "info", "Linux system prerequisites already installed.");
+    } else if let Some(system) = scan.immutable {
+        if !scan.missing_required.is_empty() {
+            return Err(format!(
+                "{system} system packages cannot be installed automatically. Missing on PATH: {}. To provide them, {}.",
+                scan.missing_required.join(", "),
+                immutable_os_guidance(system, &scan.missing_required)
+            ));
+        }
+        emit_install_event(
+            app,
+            "warn",
+            &format!(
+                "Optional build tools not on PATH ({system}): {}. Prebuilt wheels do not need them.",
+                scan.missing_optional.join(", ")
+            ),
+        );
+    } else if offline.as_ref().is_some_and(OfflineCache::consuming) {
+        if !scan.missing_required.is_empty() {
+            return Err(format!(
+                "Offline install cannot fetch system packages. Install these first: {}",
+                scan.missing_required.join(", ")
+            ));
+        }
+        emit_install_event(
+            app,
+            "warn",
+            &format!(
+                "Skipping optional Linux packages while offline: {}",
+                scan.missing_optional.join(", ")
+            ),
+        );
     } else {
         emit_install_event(
             app,
@@ -2500,7 +6406,11 @@ fn run_comfyui_install_linux(
     ensure_git_available(app)?;
     if !comfy_dir.join("main.py").exists() {
         write_install_state(&install_root, "in_progress", "clone_comfyui");
-        emit_install_event(app, "step", "Cloning ComfyUI...");
+        if repo_url == COMFYUI_REPO_URL {
+            emit_install_event(app, "step", "Cloning ComfyUI...");
+        } else {
+            emit_install_event(app, "step", &format!("Cloning ComfyUI from {repo_url}..."));
+        }
         if comfy_dir.exists() && !is_empty_dir(&comfy_dir) {
             if is_recoverable_preclone_dir(&comfy_dir) {
                 clear_directory_contents(&comfy_dir)?;
@@ -2511,24 +6421,56 @@ fn run_comfyui_install_linux(
                 ));
             }
         }
-        run_command_with_retry(
-            "git",
-            &[
-                "clone",
-                "https://github.com/comfyanonymous/ComfyUI.git",
-                &comfy_dir.to_string_lossy(),
-            ],
-            Some(&install_root),
-            2,
-        )?;
-        // Pin fresh installs to latest release tag so users do not see an
-        // immediate update prompt after a clean install.
-        if let Some((latest_tag, latest_version)) = git_latest_release_tag(&comfy_dir) {
+        match offline.as_ref().filter(|cache| cache.consuming()) {
+            Some(cache) => {
+                let bundle = cache.dir.join(OFFLINE_COMFYUI_BUNDLE);
+                run_command(
+                    "git",
+                    &[
+                        "clone",
+                        &bundle.to_string_lossy(),
+                        &comfy_dir.to_string_lossy(),
+                    ],
+                    Some(&install_root),
+                )?;
+                // Point origin back at the real repository so updates work once online.
+                run_command(
+                    "git",
+                    &["remote", "set-url", "origin", &repo_url],
+                    Some(&comfy_dir),
+                )?;
+            }
+            None => run_command_with_retry(
+                "git",
+                &["clone", &repo_url, &comfy_dir.to_string_lossy()],
+                Some(&install_root),
+                retry_settings().git,
+            )?,
+        }
+        // An explicitly requested ref wins. Otherwise pin fresh installs to the
+        // latest release tag so users do not see an immediate update prompt
+        // after a clean install.
+        if let Some(git_ref) = requested_ref.as_deref() {
+            checkout_comfyui_ref(&comfy_dir, git_ref).map_err(|err| {
+                format!("ComfyUI cloned, but failed to check out requested ref {git_ref}: {err}")
+            })?;
+            write_install_state_with_ref(
+                &install_root,
+                "in_progress",
+                "clone_comfyui",
+                Some(git_ref.to_string()),
+            );
+            emit_install_event(
+                app,
+                "info",
+                &format!("Pinned ComfyUI install to requested ref {git_ref}."),
+            );
+        } else if let Some((latest_tag, latest_version)) = git_latest_release_tag(&comfy_dir) {
             if let Err(err) = run_command_with_retry(
                 "git",
                 &["checkout", "-B", "master", &latest_tag],
                 Some(&comfy_dir),
-                1,
+                RetryPolicy::once(),
             ) {
                 emit_install_event(
                     app,
@@ -2594,101 +6536,214 @@ fn run_comfyui_install_linux(
         });
     }
 
+    let data_dirs = ComfyDataDirs::from_raw(
+        request.output_dir.as_deref(),
+        request.input_dir.as_deref(),
+        request.user_dir.as_deref(),
+    )?;
+    if !data_dirs.is_empty() {
+        write_install_state(&install_root, "in_progress", "data_dirs");
+        emit_install_event(app, "step", "Configuring ComfyUI data folders...");
+        write_comfy_data_dirs(
+            &app.state::<AppState>().context.config.settings(),
+            &comfy_dir,
+            &data_dirs,
+        )?;
+        summary.push(InstallSummaryItem {
+            name: "data_dirs".to_string(),
+            status: "ok".to_string(),
+            detail: data_dirs
+                .entries()
+                .iter()
+                .map(|(flag, path)| format!("{flag} {}", path.display()))
+                .collect::<Vec<_>>()
+                .join(", "),
+        });
+    }
+
+    if apply_settings_template {
+        write_install_state(&install_root, "in_progress", "comfy_settings");
+        emit_install_event(app, "step", "Applying default ComfyUI settings template...");
+        // A bad existing settings file should not fail the whole install.
+        let item = match apply_comfy_settings_values(&comfy_dir, &settings_template.values) {
+            Ok(path) => InstallSummaryItem {
+                name: "comfy_settings".to_string(),
+                status: "ok".to_string(),
+                detail: format!(
+                    "Seeded {} setting(s) into {}.",
+                    settings_template.values.len(),
+                    path.display()
+                ),
+            },
+            Err(err) => {
+                emit_install_event(app, "warn", &err);
+                InstallSummaryItem {
+                    name: "comfy_settings".to_string(),
+                    status: "failed".to_string(),
+                    detail: err,
+                }
+            }
+        };
+        summary.push(item);
+    }
+
     if cancel.is_cancelled() {
         return Err("Installation cancelled.".to_string());
     }
 
-    write_install_state(&install_root, "in_progress", "python_venv");
-    emit_install_event(app, "step", "Preparing uv-managed Python + local .venv...");
+    write_install_request(&install_root, request);
     let uv_bin = resolve_uv_binary(shared_runtime_root, app)?;
     let python_store = shared_runtime_root.join(".python");
     std::fs::create_dir_all(&python_store).map_err(|err| err.to_string())?;
     let python_store_s = python_store.to_string_lossy().to_string();
-    run_command_env(
-        &uv_bin,
-        &["python", "install", UV_PYTHON_VERSION],
-        Some(&comfy_dir),
-        &[
-            ("UV_PYTHON_INSTALL_DIR", &python_store_s),
-            ("UV_PYTHON_INSTALL_BIN", "false"),
-        ],
-    )?;
-
     let venv_dir = comfy_dir.join(".venv");
     let py_exe = venv_dir.join("bin").join("python");
-    if !py_exe.exists() {
-        let venv_s = venv_dir.to_string_lossy().to_string();
+    // A venv that vanished since the interruption has to be rebuilt, whatever the state says.
+    if !(py_exe.exists() && skip_completed("python_venv")) {
+        write_install_state(&install_root, "in_progress", "python_venv");
+        emit_install_event(app, "step", "Preparing uv-managed Python + local .venv...");
+        if let Some(cache) = offline.as_ref().filter(|cache| cache.consuming()) {
+            run_command(
+                "cp",
+                &[
+                    "-a",
+                    &format!("{}/.", cache.dir.join("python").to_string_lossy()),
+                    &python_store_s,
+                ],
+                None,
+            )?;
+        }
         run_command_env(
             &uv_bin,
-            &["venv", "--seed", "--python", UV_PYTHON_VERSION, &venv_s],
+            &["python", "install", UV_PYTHON_VERSION],
+            Some(&comfy_dir),
+            &[
+                ("UV_PYTHON_INSTALL_DIR", &python_store_s),
+                ("UV_PYTHON_INSTALL_BIN", "false"),
+            ],
+        )?;
+
+        if !py_exe.exists() {
+            let venv_s = venv_dir.to_string_lossy().to_string();
+            run_command_env(
+                &uv_bin,
+                &["venv", "--seed", "--python", UV_PYTHON_VERSION, &venv_s],
+                Some(&comfy_dir),
+                &[("UV_PYTHON_INSTALL_DIR", &python_store_s)],
+            )?;
+        } else {
+            emit_install_event(app, "step", "Existing .venv found; reusing.");
+        }
+        run_uv_pip_strict(
+            &uv_bin,
+            &py_exe.to_string_lossy(),
+            &["install", "--upgrade", "pip", "setuptools", "wheel"],
             Some(&comfy_dir),
             &[("UV_PYTHON_INSTALL_DIR", &python_store_s)],
         )?;
-    } else {
-        emit_install_event(app, "step", "Existing .venv found; reusing.");
     }
-    run_uv_pip_strict(
-        &uv_bin,
-        &py_exe.to_string_lossy(),
-        &["install", "--upgrade", "pip", "setuptools", "wheel"],
-        Some(&comfy_dir),
-        &[("UV_PYTHON_INSTALL_DIR", &python_store_s)],
-    )?;
 
     let recommendation = get_comfyui_install_recommendation();
     let selected_profile = request
         .torch_profile
         .clone()
         .unwrap_or(recommendation.torch_profile);
-    let hopper_sm90 = is_nvidia_hopper_sm90();
-    write_install_state(&install_root, "in_progress", "torch_stack");
-    emit_install_event(app, "step", "Installing Torch stack...");
-    enforce_torch_profile_linux(
-        &uv_bin,
-        &py_exe.to_string_lossy(),
-        &comfy_dir,
-        &selected_profile,
-        &python_store_s,
-    )?;
+    let wheel_arch = detect_wheel_arch();
+    if !skip_completed("torch_stack") {
+        write_install_state(&install_root, "in_progress", "torch_stack");
+        emit_install_event(app, "step", "Installing Torch stack...");
+        enforce_torch_profile_linux(
+            &uv_bin,
+            &py_exe.to_string_lossy(),
+            &comfy_dir,
+            &selected_profile,
+            &python_store_s,
+        )?;
+    }
 
-    write_install_state(&install_root, "in_progress", "comfy_requirements");
-    run_uv_pip_strict(
-        &uv_bin,
-        &py_exe.to_string_lossy(),
-        &["install", "-r", &comfy_dir.join("requirements.txt").to_string_lossy()],
-        Some(&comfy_dir),
-        &[("UV_PYTHON_INSTALL_DIR", &python_store_s)],
-    )?;
-    // Re-apply selected torch stack because requirements can drift torch/torchvision.
-    enforce_torch_profile_linux(
-        &uv_bin,
-        &py_exe.to_string_lossy(),
-        &comfy_dir,
-        &selected_profile,
-        &python_store_s,
-    )?;
-    run_uv_pip_strict(
-        &uv_bin,
-        &py_exe.to_string_lossy(),
-        &["install", "--upgrade", "pyyaml", "nvidia-ml-py"],
-        Some(&comfy_dir),
-        &[("UV_PYTHON_INSTALL_DIR", &python_store_s)],
-    )?;
+    if !skip_completed("comfy_requirements") {
+        write_install_state(&install_root, "in_progress", "comfy_requirements");
+        run_uv_pip_strict(
+            &uv_bin,
+            &py_exe.to_string_lossy(),
+            &[
+                "install",
+                "-r",
+                &comfy_dir.join("requirements.txt").to_string_lossy(),
+            ],
+            Some(&comfy_dir),
+            &[("UV_PYTHON_INSTALL_DIR", &python_store_s)],
+        )?;
+        // Re-apply selected torch stack because requirements can drift torch/torchvision.
+        enforce_torch_profile_linux(
+            &uv_bin,
+            &py_exe.to_string_lossy(),
+            &comfy_dir,
+            &selected_profile,
+            &python_store_s,
+        )?;
+        run_uv_pip_strict(
+            &uv_bin,
+            &py_exe.to_string_lossy(),
+            &["install", "--upgrade", "pyyaml", "nvidia-ml-py"],
+            Some(&comfy_dir),
+            &[("UV_PYTHON_INSTALL_DIR", &python_store_s)],
+        )?;
+    }
+    if let Some(version) = frontend_version.as_deref() {
+        if !skip_completed("comfy_frontend") {
+            write_install_state(&install_root, "in_progress", "comfy_frontend");
+            emit_install_event(
+                app,
+                "step",
+                &format!("Pinning ComfyUI frontend {version}..."),
+            );
+            install_frontend_package(
+                &uv_bin,
+                &py_exe.to_string_lossy(),
+                &comfy_dir,
+                Some(version),
+                &python_store_s,
+            )?;
+        }
+        summary.push(InstallSummaryItem {
+            name: FRONTEND_PACKAGE.to_string(),
+            status: "ok".to_string(),
+            detail: format!("Pinned to {version}."),
+        });
+    }
+    if torch_profile_uses_cuda(&selected_profile) {
+        let source = match triton_version_override() {
+            Some(version) if version == TRITON_LATEST_COMPATIBLE => {
+                "latest compatible, from the user override"
+            }
+            Some(_) => "pinned by the user override",
+            None => "profile default",
+        };
+        summary.push(InstallSummaryItem {
+            name: "Triton".to_string(),
+            status: "ok".to_string(),
+            detail: match installed_triton_version(&py_exe.to_string_lossy()) {
+                Some(version) => format!("triton {version} ({source})."),
+                None => format!("triton not installed ({source})."),
+            },
+        });
+    }
 
     let addon_root = comfy_dir.join("custom_nodes");
     std::fs::create_dir_all(&addon_root).map_err(|err| err.to_string())?;
 
-    if request.include_sage_attention {
+    if request.include_sage_attention && !skip_completed("addon_sageattention") {
         write_install_state(&install_root, "in_progress", "addon_sageattention");
         emit_install_event(app, "step", "Installing SageAttention...");
         install_sageattention_linux(
             &comfy_dir,
             &py_exe.to_string_lossy(),
             &selected_profile,
-            hopper_sm90,
+            wheel_arch,
         )?;
     }
-    if include_insight_face {
+    if include_insight_face && !skip_completed("addon_insightface") {
         write_install_state(&install_root, "in_progress", "addon_insightface");
         if request.include_nunchaku && !request.include_insight_face {
             emit_install_event(
@@ -2702,14 +6757,14 @@ fn run_comfyui_install_linux(
         install_insightface(&comfy_dir, &uv_bin, &py_exe.to_string_lossy(), &python_store_s)?;
     }
 
-    if request.include_flash_attention {
+    if request.include_flash_attention && !skip_completed("addon_flashattention") {
         write_install_state(&install_root, "in_progress", "addon_flashattention");
         emit_install_event(app, "step", "Installing FlashAttention...");
         install_flashattention_linux(
             &comfy_dir,
             &py_exe.to_string_lossy(),
             &selected_profile,
-            hopper_sm90,
+            wheel_arch,
         )?;
         summary.push(InstallSummaryItem {
             name: "flash-attention".to_string(),
@@ -2717,7 +6772,7 @@ fn run_comfyui_install_linux(
             detail: "Installed using Linux wheel stack.".to_string(),
         });
     }
-    if request.include_sage_attention3 {
+    if request.include_sage_attention3 && !skip_completed("addon_sageattention3") {
         write_install_state(&install_root, "in_progress", "addon_sageattention3");
         emit_install_event(app, "step", "Installing SageAttention3...");
         install_linux_wheel_for_profile(
@@ -2725,7 +6780,7 @@ fn run_comfyui_install_linux(
             &py_exe.to_string_lossy(),
             &selected_profile,
             "sage3",
-            hopper_sm90,
+            wheel_arch,
             true,
         )?;
         // Keep sageattention installed for ComfyUI --use-sage-attention compatibility checks.
@@ -2733,7 +6788,7 @@ fn run_comfyui_install_linux(
             &comfy_dir,
             &py_exe.to_string_lossy(),
             &selected_profile,
-            hopper_sm90,
+            wheel_arch,
         )?;
         summary.push(InstallSummaryItem {
             name: "sageattention3".to_string(),
@@ -2741,7 +6796,7 @@ fn run_comfyui_install_linux(
             detail: "Installed using Linux wheel stack.".to_string(),
         });
     }
-    if request.include_nunchaku {
+    if request.include_nunchaku && !skip_completed("addon_nunchaku") {
         write_install_state(&install_root, "in_progress", "addon_nunchaku");
         emit_install_event(app, "step", "Installing Nunchaku...");
         ensure_git_available(app)?;
@@ -2775,7 +6830,7 @@ fn run_comfyui_install_linux(
             &py_exe.to_string_lossy(),
             &selected_profile,
             "nunchaku",
-            hopper_sm90,
+            wheel_arch,
             true,
         )?;
         if !nunchaku_backend_present(&comfy_dir) {
@@ -2790,7 +6845,22 @@ fn run_comfyui_install_linux(
             detail: "Installed Linux nunchaku wheel and ComfyUI-nunchaku node.".to_string(),
         });
     }
-    if request.include_trellis2 {
+    if request.include_xformers && !skip_completed("addon_xformers") {
+        write_install_state(&install_root, "in_progress", "addon_xformers");
+        emit_install_event(app, "step", "Installing xformers...");
+        install_xformers(
+            &comfy_dir,
+            &uv_bin,
+            &py_exe.to_string_lossy(),
+            &python_store_s,
+        )?;
+        summary.push(InstallSummaryItem {
+            name: "xformers".to_string(),
+            status: "ok".to_string(),
+            detail: "Installed from the Torch profile's package index.".to_string(),
+        });
+    }
+    if request.include_trellis2 && !skip_completed("addon_trellis2") {
         write_install_state(&install_root, "in_progress", "addon_trellis2");
         emit_install_event(app, "step", "Installing Trellis2...");
         let custom_nodes_dir = comfy_dir.join("custom_nodes");
@@ -2876,153 +6946,35 @@ fn run_comfyui_install_linux(
         });
     }
 
-    if request.node_comfyui_manager {
-        write_install_state(&install_root, "in_progress", "node_comfyui_manager");
-        match install_custom_node(
-            app,
-            &comfy_dir,
-            &addon_root,
-            &py_exe,
-            "https://github.com/Comfy-Org/ComfyUI-Manager",
-            "ComfyUI-Manager",
-        ) {
-            Ok(_) => summary.push(InstallSummaryItem {
-                name: "ComfyUI-Manager".to_string(),
-                status: "ok".to_string(),
-                detail: "Installed successfully.".to_string(),
-            }),
-            Err(err) => {
-                summary.push(InstallSummaryItem {
-                    name: "ComfyUI-Manager".to_string(),
-                    status: "failed".to_string(),
-                    detail: err.clone(),
-                });
-                emit_install_event(app, "warn", &format!("ComfyUI-Manager failed: {err}"));
-            }
-        }
-    }
-    if request.node_comfyui_easy_use {
-        write_install_state(&install_root, "in_progress", "node_comfyui_easy_use");
-        match install_custom_node(
-            app,
-            &comfy_dir,
-            &addon_root,
-            &py_exe,
-            "https://github.com/yolain/ComfyUI-Easy-Use",
-            "ComfyUI-Easy-Use",
-        ) {
-            Ok(_) => summary.push(InstallSummaryItem {
-                name: "ComfyUI-Easy-Use".to_string(),
-                status: "ok".to_string(),
-                detail: "Installed successfully.".to_string(),
-            }),
-            Err(err) => {
-                summary.push(InstallSummaryItem {
-                    name: "ComfyUI-Easy-Use".to_string(),
-                    status: "failed".to_string(),
-                    detail: err.clone(),
-                });
-                emit_install_event(app, "warn", &format!("ComfyUI-Easy-Use failed: {err}"));
-            }
-        }
-    }
-    if request.node_rgthree_comfy {
-        write_install_state(&install_root, "in_progress", "node_rgthree_comfy");
-        match install_custom_node(
-            app,
-            &comfy_dir,
-            &addon_root,
-            &py_exe,
-            "https://github.com/rgthree/rgthree-comfy",
-            "rgthree-comfy",
-        ) {
-            Ok(_) => summary.push(InstallSummaryItem {
-                name: "rgthree-comfy".to_string(),
-                status: "ok".to_string(),
-                detail: "Installed successfully.".to_string(),
-            }),
-            Err(err) => {
-                summary.push(InstallSummaryItem {
-                    name: "rgthree-comfy".to_string(),
-                    status: "failed".to_string(),
-                    detail: err.clone(),
-                });
-                emit_install_event(app, "warn", &format!("rgthree-comfy failed: {err}"));
-            }
-        }
-    }
-    if request.node_comfyui_gguf {
-        write_install_state(&install_root, "in_progress", "node_comfyui_gguf");
-        match install_custom_node(
-            app,
-            &comfy_dir,
-            &addon_root,
-            &py_exe,
-            "https://github.com/city96/ComfyUI-GGUF",
-            "ComfyUI-GGUF",
-        ) {
-            Ok(_) => summary.push(InstallSummaryItem {
-                name: "ComfyUI-GGUF".to_string(),
-                status: "ok".to_string(),
-                detail: "Installed successfully.".to_string(),
-            }),
-            Err(err) => {
-                summary.push(InstallSummaryItem {
-                    name: "ComfyUI-GGUF".to_string(),
-                    status: "failed".to_string(),
-                    detail: err.clone(),
-                });
-                emit_install_event(app, "warn", &format!("ComfyUI-GGUF failed: {err}"));
-            }
-        }
+    let pending_nodes: Vec<_> = requested_custom_nodes(request)
+        .into_iter()
+        .filter(|(step, _, _)| !skip_completed(step))
+        .collect();
+    // Record the first pending node while they all run, so an interruption resumes the
+    // whole batch; the rest are recorded in order once it finishes.
+    if let Some((step, _, _)) = pending_nodes.first() {
+        write_install_state(&install_root, "in_progress", step);
     }
-    if request.node_comfyui_kjnodes {
-        write_install_state(&install_root, "in_progress", "node_comfyui_kjnodes");
-        match install_custom_node(
-            app,
-            &comfy_dir,
-            &addon_root,
-            &py_exe,
-            "https://github.com/kijai/ComfyUI-KJNodes",
-            "comfyui-kjnodes",
-        ) {
-            Ok(_) => summary.push(InstallSummaryItem {
-                name: "comfyui-kjnodes".to_string(),
-                status: "ok".to_string(),
-                detail: "Installed successfully.".to_string(),
-            }),
-            Err(err) => {
-                summary.push(InstallSummaryItem {
-                    name: "comfyui-kjnodes".to_string(),
-                    status: "failed".to_string(),
-                    detail: err.clone(),
-                });
-                emit_install_event(app, "warn", &format!("comfyui-kjnodes failed: {err}"));
-            }
-        }
+    let node_results =
+        install_custom_nodes_parallel(app, &comfy_dir, &addon_root, &py_exe, &pending_nodes);
+    if cancel.is_cancelled() {
+        return Err("Installation cancelled.".to_string());
     }
-    if request.node_comfyui_crystools {
-        write_install_state(&install_root, "in_progress", "node_comfyui_crystools");
-        match install_custom_node(
-            app,
-            &comfy_dir,
-            &addon_root,
-            &py_exe,
-            "https://github.com/crystian/comfyui-crystools.git",
-            "comfyui-crystools",
-        ) {
+    for ((step, _, _), (name, result)) in pending_nodes.iter().zip(node_results) {
+        write_install_state(&install_root, "in_progress", step);
+        match result {
             Ok(_) => summary.push(InstallSummaryItem {
-                name: "comfyui-crystools".to_string(),
+                name: name.to_string(),
                 status: "ok".to_string(),
                 detail: "Installed successfully.".to_string(),
             }),
             Err(err) => {
                 summary.push(InstallSummaryItem {
-                    name: "comfyui-crystools".to_string(),
+                    name: name.to_string(),
                     status: "failed".to_string(),
                     detail: err.clone(),
                 });
-                emit_install_event(app, "warn", &format!("comfyui-crystools failed: {err}"));
+                emit_install_event(app, "warn", &format!("{name} failed: {err}"));
             }
         }
     }
@@ -3039,2839 +6991,12442 @@ fn run_comfyui_install_linux(
         &python_store_s,
     )?;
 
+    if let Some(options) = &request.server {
+        let server = configure_server_mode(&comfy_dir, options)?;
+        summary.push(InstallSummaryItem {
+            name: "server".to_string(),
+            status: "ok".to_string(),
+            detail: server.describe(&comfy_dir),
+        });
+    }
+
+    if request.node_comfyui_manager && custom_node_exists(&comfy_dir, "ComfyUI-Manager") {
+        let mut options = request.manager.clone().unwrap_or_default();
+        options
+            .security_level
+            .get_or_insert_with(|| "normal".to_string());
+        options.network_mode.get_or_insert_with(|| {
+            if offline.is_some() {
+                "offline"
+            } else {
+                "public"
+            }
+            .to_string()
+        });
+        let (status, detail) = match write_manager_config(&comfy_dir, &options) {
+            Ok(path) => ("ok", format!("Wrote {}.", path.display())),
+            Err(err) => ("failed", err),
+        };
+        summary.push(InstallSummaryItem {
+            name: "ComfyUI-Manager config".to_string(),
+            status: status.to_string(),
+            detail,
+        });
+    }
+
     write_install_summary(&install_root, &summary);
     write_install_state(&install_root, "completed", "done");
+    let completion = InstallCompletion::new(&comfy_dir, request);
+    write_install_config(
+        &comfy_dir,
+        &InstallConfig {
+            data_dirs: read_comfy_data_dirs(&comfy_dir),
+            ..completion.install_config()
+        },
+    )?;
+    write_install_completion(&comfy_dir, &completion);
     Ok(comfy_dir)
 }
 
-#[tauri::command]
-async fn start_comfyui_install(
-    app: AppHandle,
-    state: State<'_, AppState>,
-    request: ComfyInstallRequest,
-) -> Result<(), String> {
-    {
-        let mut active = state
-            .install_cancel
-            .lock()
-            .map_err(|_| "install state lock poisoned".to_string())?;
-        if active.is_some() {
-            return Err("ComfyUI installation is already active.".to_string());
-        }
-        *active = Some(CancellationToken::new());
-    }
+const MANAGER_SECURITY_LEVELS: [&str; 4] = ["strong", "normal", "normal-", "weak"];
+const MANAGER_NETWORK_MODES: [&str; 3] = ["public", "private", "offline"];
 
-    let cancel = state
-        .install_cancel
-        .lock()
-        .map_err(|_| "install state lock poisoned".to_string())?
-        .as_ref()
-        .cloned()
-        .ok_or_else(|| "Failed to initialize install cancellation token.".to_string())?;
-    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComfyManagerOptions {
+    #[serde(default)]
+    security_level: Option<String>,
+    #[serde(default)]
+    network_mode: Option<String>,
+    #[serde(default)]
+    channel_url: Option<String>,
+}
 
-    let app_for_task = app.clone();
-    tauri::async_runtime::spawn(async move {
-        let result = run_comfyui_install(&app_for_task, &request, &shared_runtime_root, &cancel);
-        match result {
-            Ok(comfy_root) => {
-                let install_dir = comfy_root
-                    .parent()
-                    .map(Path::to_path_buf)
-                    .unwrap_or_else(|| comfy_root.clone());
-                let managed = app_for_task.state::<AppState>();
-                let normalized_shared_models =
-                    normalize_optional_path(request.extra_model_root.as_deref()).ok().flatten();
-                let _ = managed.context.config.update_settings(|settings| {
-                    settings.comfyui_root = Some(comfy_root.clone());
-                    settings.comfyui_last_install_dir = Some(install_dir.clone());
-                    settings.comfyui_pinned_memory_enabled = request.include_pinned_memory;
-                    settings.comfyui_torch_profile = Some(
-                        request
-                            .torch_profile
-                            .clone()
-                            .unwrap_or_else(|| get_comfyui_install_recommendation().torch_profile),
-                    );
-                    settings.comfyui_attention_backend =
-                        Some(selected_attention_backend(&request).to_string());
-                    settings.shared_models_root = normalized_shared_models.clone();
-                    settings.shared_models_use_default = normalized_shared_models
-                        .as_ref()
-                        .is_some_and(|_| request.extra_model_use_default);
-                });
-                let _ = app_for_task.emit(
-                    "comfyui-install-progress",
-                    DownloadProgressEvent {
-                        kind: "comfyui_install".to_string(),
-                        phase: "finished".to_string(),
-                        artifact: Some(install_dir.to_string_lossy().to_string()),
-                        index: None,
-                        total: None,
-                        received: None,
-                        size: None,
-                        folder: Some(comfy_root.to_string_lossy().to_string()),
-                        message: Some(format!(
-                            "ComfyUI installation completed. Root set to {}",
-                            comfy_root.display()
-                        )),
-                    },
-                );
+impl ComfyManagerOptions {
+    /// The `config.ini` keys to set, validated.
+    fn ini_values(&self) -> Result<Vec<(&'static str, String)>, String> {
+        let mut values = Vec::new();
+        if let Some(level) = self.security_level.as_deref().map(str::trim) {
+            if !MANAGER_SECURITY_LEVELS.contains(&level) {
+                return Err(format!(
+                    "Unknown ComfyUI-Manager security level '{level}'; use {}.",
+                    MANAGER_SECURITY_LEVELS.join(", ")
+                ));
             }
-            Err(err) => emit_install_event(&app_for_task, "failed", &err),
+            values.push(("security_level", level.to_string()));
         }
-        let managed = app_for_task.state::<AppState>();
-        if let Ok(mut active) = managed.install_cancel.lock() {
-            *active = None;
-        };
-    });
-
-    Ok(())
+        if let Some(mode) = self.network_mode.as_deref().map(str::trim) {
+            if !MANAGER_NETWORK_MODES.contains(&mode) {
+                return Err(format!(
+                    "Unknown ComfyUI-Manager network mode '{mode}'; use {}.",
+                    MANAGER_NETWORK_MODES.join(", ")
+                ));
+            }
+            values.push(("network_mode", mode.to_string()));
+        }
+        if let Some(raw) = self
+            .channel_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            let url = validate_repo_url(raw, "ComfyUI-Manager channel")?;
+            values.push((
+                "channel_url",
+                url.as_str().trim_end_matches('/').to_string(),
+            ));
+        }
+        Ok(values)
+    }
 }
 
-#[tauri::command]
-fn cancel_comfyui_install(state: State<'_, AppState>) -> Result<bool, String> {
-    let mut active = state
-        .install_cancel
-        .lock()
-        .map_err(|_| "install state lock poisoned".to_string())?;
-    if let Some(token) = active.as_ref() {
-        token.cancel();
-        *active = None;
-        Ok(true)
+/// Where ComfyUI-Manager reads its config: `user/__manager` for the pip-packaged
+/// Manager, `user/default/ComfyUI-Manager` for the custom-node one.
+fn manager_config_path(comfy_root: &Path) -> PathBuf {
+    let user_dir = read_comfy_data_dirs(comfy_root)
+        .user_dir
+        .unwrap_or_else(|| comfy_root.join("user"));
+    let packaged = user_dir.join("__manager");
+    if packaged.is_dir() {
+        packaged.join("config.ini")
     } else {
-        Ok(false)
+        user_dir
+            .join("default")
+            .join("ComfyUI-Manager")
+            .join("config.ini")
     }
 }
 
-#[tauri::command]
-fn get_catalog(state: State<'_, AppState>) -> ModelCatalog {
-    state.context.catalog.catalog_snapshot()
-}
-
-#[tauri::command]
-fn get_settings(state: State<'_, AppState>) -> AppSettings {
-    state.context.config.settings()
-}
-
-#[tauri::command]
-fn set_comfyui_root(
-    state: State<'_, AppState>,
-    comfyui_root: String,
-) -> Result<AppSettings, String> {
-    let trimmed = comfyui_root.trim();
-    let normalized = if trimmed.is_empty() {
-        None
-    } else {
-        let mut path = std::path::PathBuf::from(trimmed);
-        if !path.is_absolute() {
-            if let Ok(cwd) = std::env::current_dir() {
-                path = cwd.join(path);
-            }
+/// Sets `values` in the `[default]` section of an ini file, keeping every other line.
+fn set_ini_values(text: &str, values: &[(&str, String)]) -> String {
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let start = match lines
+        .iter()
+        .position(|line| line.trim().eq_ignore_ascii_case("[default]"))
+    {
+        Some(index) => index,
+        None => {
+            lines.insert(0, "[default]".to_string());
+            0
         }
-        Some(normalize_canonical_path(
-            &std::fs::canonicalize(&path).unwrap_or(path),
-        ))
     };
-    let detected_attention = normalized
-        .as_ref()
-        .map(|root| detect_launch_attention_backend_for_root(root).unwrap_or_else(|| "none".to_string()));
-    let detected_profile = normalized
-        .as_ref()
-        .and_then(|root| detect_torch_profile_for_root(root));
+    let mut end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+    for (key, value) in values {
+        let existing = lines[start + 1..end].iter().position(|line| {
+            line.split_once('=')
+                .is_some_and(|(name, _)| name.trim() == *key)
+        });
+        match existing {
+            Some(offset) => lines[start + 1 + offset] = format!("{key} = {value}"),
+            None => {
+                lines.insert(end, format!("{key} = {value}"));
+                end += 1;
+            }
+        }
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
 
-    state
-        .context
-        .config
-        .update_settings(|settings| {
-            settings.comfyui_root = normalized.clone();
-            settings.comfyui_attention_backend = detected_attention
-                .clone()
-                .or_else(|| Some("none".to_string()));
-            settings.comfyui_torch_profile = detected_profile.clone();
-        })
-        .map_err(|err| err.to_string())
+fn write_manager_config(
+    comfy_root: &Path,
+    options: &ComfyManagerOptions,
+) -> Result<PathBuf, String> {
+    let values = options.ini_values()?;
+    let path = manager_config_path(comfy_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create {}: {err}", parent.display()))?;
+    }
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+    std::fs::write(&path, set_ini_values(&existing, &values))
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path)
 }
 
-#[tauri::command]
-fn set_comfyui_install_base(
-    state: State<'_, AppState>,
-    comfyui_install_base: String,
-) -> Result<AppSettings, String> {
-    let trimmed = comfyui_install_base.trim();
-    let normalized = if trimmed.is_empty() {
-        None
-    } else {
-        let mut path = std::path::PathBuf::from(trimmed);
-        if !path.is_absolute() {
-            if let Ok(cwd) = std::env::current_dir() {
-                path = cwd.join(path);
-            }
+fn read_manager_config(comfy_root: &Path) -> ComfyManagerOptions {
+    let text = std::fs::read_to_string(manager_config_path(comfy_root)).unwrap_or_default();
+    let mut options = ComfyManagerOptions::default();
+    let mut in_default = false;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_default = line.eq_ignore_ascii_case("[default]");
+            continue;
         }
-        let resolved = normalize_canonical_path(&std::fs::canonicalize(&path).unwrap_or(path));
-        if is_forbidden_install_path(&resolved) {
-            return Err(
-                "Install base folder is blocked. Avoid system directories."
-                    .to_string(),
-            );
+        let Some((key, value)) = line.split_once('=').filter(|_| in_default) else {
+            continue;
+        };
+        let value = Some(value.trim().to_string());
+        match key.trim() {
+            "security_level" => options.security_level = value,
+            "network_mode" => options.network_mode = value,
+            "channel_url" => options.channel_url = value,
+            _ => {}
         }
-        Some(resolved)
-    };
-    state
-        .context
-        .config
-        .update_settings(|settings| {
-            settings.comfyui_install_base = normalized.clone();
-        })
-        .map_err(|err| err.to_string())
+    }
+    options
 }
 
 #[tauri::command]
-fn get_comfyui_extra_model_config(
+fn get_comfyui_manager_config(
     state: State<'_, AppState>,
     comfyui_root: Option<String>,
-) -> Result<ComfyExtraModelConfigResponse, String> {
+) -> Result<ComfyManagerOptions, String> {
     let root = resolve_root_path(&state.context, comfyui_root)?;
-    let config = comfy_extra_model_config(&root);
-    Ok(match config {
-        Some(cfg) => ComfyExtraModelConfigResponse {
-            configured: true,
-            base_path: Some(cfg.base_path.to_string_lossy().to_string()),
-            use_as_default: cfg.is_default,
-        },
-        None => ComfyExtraModelConfigResponse {
-            configured: false,
-            base_path: None,
-            use_as_default: false,
-        },
-    })
+    Ok(read_manager_config(&root))
 }
 
+/// Updates ComfyUI-Manager's `config.ini`; Manager reads it on the next ComfyUI start.
 #[tauri::command]
-fn set_comfyui_extra_model_config(
+fn set_comfyui_manager_config(
     state: State<'_, AppState>,
     comfyui_root: Option<String>,
-    extra_model_root: Option<String>,
-    use_as_default: bool,
-) -> Result<AppSettings, String> {
+    options: ComfyManagerOptions,
+) -> Result<ComfyManagerOptions, String> {
     let root = resolve_root_path(&state.context, comfyui_root)?;
-    let normalized_extra = normalize_optional_path(extra_model_root.as_deref())?;
-    let yaml_path = root.join("extra_model_paths.yaml");
-    let example_path = root.join("extra_model_paths.yaml.example");
+    if !custom_node_exists(&root, "ComfyUI-Manager")
+        && !custom_node_exists(&root, "comfyui-manager")
+    {
+        return Err("ComfyUI-Manager is not installed in this ComfyUI root.".to_string());
+    }
+    write_manager_config(&root, &options)?;
+    Ok(read_manager_config(&root))
+}
 
-    if let Some(extra_root) = normalized_extra.as_ref() {
-        write_extra_model_paths_yaml(&root, extra_root, use_as_default)?;
-    } else {
-        if yaml_path.exists() {
-            let _ = std::fs::remove_file(&yaml_path);
-        }
-        if !example_path.exists() {
-            let _ = std::fs::write(
-                &example_path,
-                "# Rename this to extra_model_paths.yaml and ComfyUI will load it\n",
-            );
+const SERVER_CONFIG_FILE: &str = ".arctic_server.json";
+
+/// How a server-profile install is exposed. With `auth_token` ComfyUI stays on
+/// loopback and the proxy configs in `server/` check the token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ComfyServerConfig {
+    listen: String,
+    port: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    proxy_port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth_token: Option<String>,
+}
+
+impl ComfyServerConfig {
+    fn describe(&self, comfy_root: &Path) -> String {
+        match (self.auth_token.as_ref(), self.proxy_port) {
+            (Some(_), Some(proxy_port)) => format!(
+                "ComfyUI listens on 127.0.0.1:{}. Put {} or {} in front of it to serve port {proxy_port}; sign in as 'comfy' with the token in {}.",
+                self.port,
+                comfy_root.join("server").join("Caddyfile").display(),
+                comfy_root.join("server").join("nginx.conf").display(),
+                comfy_root.join(SERVER_CONFIG_FILE).display()
+            ),
+            _ => format!(
+                "ComfyUI listens on {}:{} without authentication.",
+                self.listen, self.port
+            ),
         }
     }
+}
 
-    state
-        .context
-        .config
-        .update_settings(|settings| {
-            settings.shared_models_root = normalized_extra.clone();
-            settings.shared_models_use_default =
-                normalized_extra.is_some() && use_as_default;
-        })
-        .map_err(|err| err.to_string())
+fn read_server_config(comfy_root: &Path) -> Option<ComfyServerConfig> {
+    let data = std::fs::read(comfy_root.join(SERVER_CONFIG_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
 }
 
-#[tauri::command]
-fn save_civitai_token(state: State<'_, AppState>, token: String) -> Result<AppSettings, String> {
-    let trimmed = token.trim().to_string();
-    state
-        .context
-        .config
-        .update_settings(|settings| {
-            settings.civitai_token = if trimmed.is_empty() {
-                None
-            } else {
-                Some(trimmed)
-            };
-        })
-        .map_err(|err| err.to_string())
+/// Writes the server profile and, with auth, the proxy configs. Basic auth is checked
+/// against the literal header so neither proxy needs a password hash tool.
+fn configure_server_mode(
+    comfy_root: &Path,
+    options: &ComfyServerOptions,
+) -> Result<ComfyServerConfig, String> {
+    use base64::Engine;
+    use std::os::unix::fs::PermissionsExt;
+
+    let listen = options.listen.trim();
+    listen
+        .parse::<std::net::IpAddr>()
+        .map_err(|_| format!("Server listen address '{listen}' is not an IP address."))?;
+    if options.port == 0 || (options.auth && options.proxy_port == 0) {
+        return Err("Server ports must be between 1 and 65535.".to_string());
+    }
+    if options.auth && options.proxy_port == options.port {
+        return Err("The proxy port must differ from the ComfyUI port.".to_string());
+    }
+    let config = if options.auth {
+        let token = arctic_downloader::api_access::random_secret();
+        let credentials =
+            base64::engine::general_purpose::STANDARD.encode(format!("comfy:{token}"));
+        let port = options.port;
+        let proxy_port = options.proxy_port;
+        let server_dir = comfy_root.join("server");
+        std::fs::create_dir_all(&server_dir)
+            .map_err(|err| format!("Failed to create {}: {err}", server_dir.display()))?;
+        let caddyfile = format!(
+            r#"# ComfyUI behind HTTP basic auth: user "comfy", password from {SERVER_CONFIG_FILE}.
+:{proxy_port} {{
+	@unauthorized not header Authorization "Basic {credentials}"
+	handle @unauthorized {{
+		header WWW-Authenticate `Basic realm="ComfyUI"`
+		respond 401
+	}}
+	reverse_proxy 127.0.0.1:{port}
+}}
+"#
+        );
+        let nginx = format!(
+            r#"# ComfyUI behind HTTP basic auth: user "comfy", password from {SERVER_CONFIG_FILE}.
+server {{
+    listen {proxy_port};
+    client_max_body_size 0;
+    location / {{
+        if ($http_authorization != "Basic {credentials}") {{
+            add_header WWW-Authenticate 'Basic realm="ComfyUI"' always;
+            return 401;
+        }}
+        proxy_pass http://127.0.0.1:{port};
+        proxy_http_version 1.1;
+        proxy_set_header Upgrade $http_upgrade;
+        proxy_set_header Connection "upgrade";
+        proxy_set_header Host $host;
+        proxy_read_timeout 1d;
+    }}
+}}
+"#
+        );
+        for (name, content) in [("Caddyfile", caddyfile), ("nginx.conf", nginx)] {
+            let path = server_dir.join(name);
+            std::fs::write(&path, content)
+                .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+            let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+        }
+        ComfyServerConfig {
+            listen: "127.0.0.1".to_string(),
+            port,
+            proxy_port: Some(proxy_port),
+            auth_token: Some(token),
+        }
+    } else {
+        ComfyServerConfig {
+            listen: listen.to_string(),
+            port: options.port,
+            proxy_port: None,
+            auth_token: None,
+        }
+    };
+    let path = comfy_root.join(SERVER_CONFIG_FILE);
+    let data = serde_json::to_vec_pretty(&config).map_err(|err| err.to_string())?;
+    std::fs::write(&path, data)
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    Ok(config)
 }
 
-#[tauri::command]
-async fn check_updates_now(state: State<'_, AppState>) -> Result<UpdateCheckResponse, String> {
-    let updater = state.context.updater.clone();
-    let result = updater.check_for_update().await;
+const CONTAINER_INSTALL_FILE: &str = ".arctic_container.json";
+const CONTAINER_COMFYUI_DIR: &str = "/opt/ComfyUI";
+const CONTAINER_COMFYUI_PORT: u16 = 8188;
 
-    match result {
-        Ok(Ok(Some(update))) => Ok(UpdateCheckResponse {
-            available: true,
-            version: Some(update.version.to_string()),
-            notes: update.notes,
-        }),
-        Ok(Ok(None)) => Ok(UpdateCheckResponse {
-            available: false,
-            version: None,
-            notes: None,
-        }),
-        Ok(Err(err)) => Err(format!("Update check failed: {err:#}")),
-        Err(join_err) => Err(format!("Update task failed: {join_err}")),
-    }
+/// A ComfyUI that runs from a Podman/Docker image. The install folder holds only the
+/// data folders, which are bind-mounted so models and outputs outlive image rebuilds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ContainerInstall {
+    engine: String,
+    image: String,
+    container_name: String,
+    torch_profile: String,
+    repo_url: String,
+    #[serde(default)]
+    comfyui_ref: Option<String>,
 }
 
-#[tauri::command]
-async fn auto_update_startup(
-    app: AppHandle,
-    state: State<'_, AppState>,
-) -> Result<UpdateCheckResponse, String> {
-    if !auto_update_enabled() {
-        return Ok(UpdateCheckResponse {
-            available: false,
-            version: None,
-            notes: Some("Auto update disabled by environment.".to_string()),
-        });
-    }
+fn read_container_install(comfy_root: &Path) -> Option<ContainerInstall> {
+    let data = std::fs::read(comfy_root.join(CONTAINER_INSTALL_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
 
-    let updater = state.context.updater.clone();
+fn write_container_install(comfy_root: &Path, install: &ContainerInstall) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(install).map_err(|err| err.to_string())?;
+    std::fs::write(comfy_root.join(CONTAINER_INSTALL_FILE), data)
+        .map_err(|err| format!("Failed to write container install marker: {err}"))
+}
 
-    let checked = updater.check_for_update().await;
+fn resolve_container_engine(preference: &str) -> Result<&'static str, String> {
+    let candidates: &[&'static str] = match preference {
+        "podman" => &["podman"],
+        "docker" => &["docker"],
+        _ => &["podman", "docker"],
+    };
+    candidates
+        .iter()
+        .copied()
+        .find(|engine| command_available(engine, &["--version"]))
+        .ok_or_else(|| {
+            format!(
+                "{} is not installed. Install it or choose a host install.",
+                candidates.join(" or ")
+            )
+        })
+}
 
-    let Some(update) = (match checked {
-        Ok(Ok(Some(update))) => Some(update),
-        Ok(Ok(None)) => {
-            return Ok(UpdateCheckResponse {
-                available: false,
-                version: None,
-                notes: None,
-            });
+/// Device flags that give the container the GPU the torch profile targets. NVIDIA goes
+/// through CDI on Podman and the nvidia runtime on Docker, both set up by
+/// nvidia-container-toolkit.
+fn container_gpu_args(engine: &str, profile: &str) -> Result<Vec<String>, String> {
+    let args: &[&str] = if is_cpu_torch_profile(profile) {
+        &[]
+    } else if is_rocm_torch_profile(profile) {
+        &[
+            "--device",
+            "/dev/kfd",
+            "--device",
+            "/dev/dri",
+            "--group-add",
+            "video",
+        ]
+    } else if engine == "podman" {
+        let cdi_specs = [
+            "/etc/cdi/nvidia.yaml",
+            "/etc/cdi/nvidia.json",
+            "/var/run/cdi/nvidia.yaml",
+            "/var/run/cdi/nvidia.json",
+        ];
+        if !cdi_specs.iter().any(|spec| Path::new(spec).is_file()) {
+            return Err(
+                "Podman has no NVIDIA CDI spec. Install nvidia-container-toolkit and run: sudo nvidia-ctk cdi generate --output=/etc/cdi/nvidia.yaml"
+                    .to_string(),
+            );
         }
-        Ok(Err(err)) => return Err(format!("Update check failed: {err:#}")),
-        Err(join_err) => return Err(format!("Update task failed: {join_err}")),
-    }) else {
-        return Ok(UpdateCheckResponse {
-            available: false,
-            version: None,
-            notes: None,
-        });
+        &["--device", "nvidia.com/gpu=all"]
+    } else {
+        let (runtimes, _) =
+            run_command_capture("docker", &["info", "--format", "{{json .Runtimes}}"], None)?;
+        if !runtimes.contains("nvidia") {
+            return Err(
+                "Docker has no nvidia runtime. Install nvidia-container-toolkit and run: sudo nvidia-ctk runtime configure --runtime=docker && sudo systemctl restart docker"
+                    .to_string(),
+            );
+        }
+        &["--gpus", "all"]
     };
+    Ok(args.iter().map(|arg| arg.to_string()).collect())
+}
 
-    let _ = app.emit(
-        "update-state",
-        DownloadProgressEvent {
-            kind: "update".to_string(),
-            phase: "available".to_string(),
-            artifact: None,
-            index: None,
-            total: None,
-            received: None,
-            size: None,
-            folder: None,
-            message: Some(format!("Update v{} available; installing.", update.version)),
-        },
-    );
+/// Name fragment unique to an install folder, for container images and unit files
+/// that must not collide between installs.
+fn install_slug(comfy_root: &Path) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    comfy_root.hash(&mut hasher);
+    let name: String = comfy_root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("{}-{:08x}", name.trim_matches('-'), hasher.finish() as u32)
+}
 
-    let install = updater.download_and_install(update.clone()).await;
+fn container_file(install: &ContainerInstall) -> String {
+    let (torch_v, vision_v, audio_v, index_url) =
+        torch_profile_to_packages_linux(&install.torch_profile);
+    let pre = if is_nightly_torch_profile(&install.torch_profile) {
+        " --pre"
+    } else {
+        ""
+    };
+    let torch = [
+        torch_requirement("torch", torch_v),
+        torch_requirement("torchvision", vision_v),
+        torch_requirement("torchaudio", audio_v),
+    ]
+    .join(" ");
+    let checkout = install
+        .comfyui_ref
+        .as_deref()
+        .map(|git_ref| {
+            format!(
+                " && git -C {CONTAINER_COMFYUI_DIR} checkout --end-of-options {}",
+                shell_quote(git_ref)
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        r#"FROM docker.io/library/python:3.12-slim-bookworm
+RUN apt-get update \
+ && apt-get install -y --no-install-recommends git libgl1 libglib2.0-0 \
+ && rm -rf /var/lib/apt/lists/*
+RUN pip install --no-cache-dir{pre} --index-url {index_url} {torch}
+# Changing COMFYUI_BUILD re-clones ComfyUI without downloading Torch again.
+ARG COMFYUI_BUILD=0
+RUN git clone {repo} {CONTAINER_COMFYUI_DIR}{checkout}
+WORKDIR {CONTAINER_COMFYUI_DIR}
+RUN pip install --no-cache-dir -r requirements.txt
+EXPOSE {CONTAINER_COMFYUI_PORT}
+"#,
+        repo = shell_quote(&install.repo_url),
+    )
+}
 
-    match install {
-        Ok(Ok(applied)) => {
-            let _ = app.emit(
-                "update-state",
-                DownloadProgressEvent {
-                    kind: "update".to_string(),
-                    phase: "restarting".to_string(),
-                    artifact: None,
-                    index: None,
-                    total: None,
-                    received: None,
-                    size: None,
-                    folder: None,
-                    message: Some(format!(
-                        "Update v{} installed; restarting application.",
-                        applied.version
-                    )),
-                },
-            );
-            app.exit(0);
-            Ok(UpdateCheckResponse {
-                available: true,
-                version: Some(applied.version.to_string()),
-                notes: Some("Standalone update apply launched.".to_string()),
-            })
-        }
-        Ok(Err(err)) => Err(format!("Update install failed: {err:#}")),
-        Err(join_err) => Err(format!("Update install task failed: {join_err}")),
+/// Builds the image from a Containerfile kept in `.container/`; the install folder
+/// itself is not the build context because it holds the models. `refresh` pulls the
+/// base image and re-clones ComfyUI.
+fn build_container_image(
+    comfy_root: &Path,
+    install: &ContainerInstall,
+    refresh: bool,
+) -> Result<(), String> {
+    let context = comfy_root.join(".container");
+    std::fs::create_dir_all(&context)
+        .map_err(|err| format!("Failed to create {}: {err}", context.display()))?;
+    let containerfile = context.join("Containerfile");
+    std::fs::write(&containerfile, container_file(install))
+        .map_err(|err| format!("Failed to write {}: {err}", containerfile.display()))?;
+    let containerfile = containerfile.to_string_lossy().to_string();
+    let context = context.to_string_lossy().to_string();
+    let build_arg = format!("COMFYUI_BUILD={}", unix_now_secs());
+    let mut args = vec!["build", "-t", &install.image, "-f", &containerfile];
+    if refresh {
+        args.extend(["--pull", "--build-arg", &build_arg]);
     }
+    args.push(&context);
+    run_command(&install.engine, &args, None)
 }
 
-#[tauri::command]
-async fn download_model_assets(
-    app: AppHandle,
-    state: State<'_, AppState>,
-    model_id: String,
-    variant_id: String,
-    ram_tier: Option<String>,
-    comfyui_root: Option<String>,
-) -> Result<(), String> {
-    let root = resolve_root_path(&state.context, comfyui_root)?;
-    let effective_root = match comfy_extra_model_config(&root) {
-        Some(config) if config.is_default => {
-            log::info!(
-                "Using extra model base path for model downloads: {}",
-                config.base_path.display()
-            );
-            config.base_path
-        }
-        _ => root,
-    };
-    let resolved = state
-        .context
-        .catalog
-        .resolve_variant(&model_id, &variant_id)
-        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+/// Host folder to container folder; the data folders chosen at install replace the
+/// defaults inside the install folder.
+fn container_mounts(comfy_root: &Path) -> Vec<(PathBuf, String)> {
+    let data_dirs = read_comfy_data_dirs(comfy_root);
+    [
+        ("models", None),
+        ("custom_nodes", None),
+        ("output", data_dirs.output_dir),
+        ("input", data_dirs.input_dir),
+        ("user", data_dirs.user_dir),
+    ]
+    .into_iter()
+    .map(|(name, custom)| {
+        (
+            custom.unwrap_or_else(|| comfy_root.join(name)),
+            format!("{CONTAINER_COMFYUI_DIR}/{name}"),
+        )
+    })
+    .collect()
+}
 
-    let tier = ram_tier
-        .as_deref()
-        .and_then(parse_ram_tier)
-        .or_else(|| state.context.ram_tier());
-    let planned = resolved.artifacts_for_download(tier);
-    if planned.is_empty() {
-        return Err("No artifacts match the selected RAM tier.".to_string());
+fn run_comfyui_install_container(
+    app: &AppHandle,
+    request: &ComfyInstallRequest,
+    preference: &str,
+    cancel: &CancellationToken,
+) -> Result<PathBuf, String> {
+    let host_only = [
+        (request.include_sage_attention, "SageAttention"),
+        (request.include_sage_attention3, "SageAttention3"),
+        (request.include_flash_attention, "FlashAttention"),
+        (request.include_insight_face, "InsightFace"),
+        (request.include_nunchaku, "Nunchaku"),
+        (request.include_xformers, "xformers"),
+        (request.include_trellis2, "TRELLIS2"),
+        (request.node_comfyui_manager, "comfyui-manager"),
+        (request.node_comfyui_easy_use, "ComfyUI-Easy-Use"),
+        (request.node_rgthree_comfy, "rgthree-comfy"),
+        (request.node_comfyui_gguf, "ComfyUI-GGUF"),
+        (request.node_comfyui_kjnodes, "ComfyUI-KJNodes"),
+        (request.node_comfyui_crystools, "ComfyUI-Crystools"),
+        (
+            request
+                .frontend_version
+                .as_deref()
+                .is_some_and(|version| !version.trim().is_empty()),
+            "the frontend version pin",
+        ),
+    ]
+    .into_iter()
+    .filter(|(selected, _)| *selected)
+    .map(|(_, name)| name)
+    .collect::<Vec<_>>();
+    if !host_only.is_empty() {
+        return Err(format!(
+            "Container installs do not support add-ons or custom nodes yet; deselect {} or choose a host install.",
+            host_only.join(", ")
+        ));
     }
 
-    let cancel = CancellationToken::new();
+    let base_root = normalize_path(&request.install_root)?;
+    let repo_url = match request
+        .comfyui_repo_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
     {
-        let mut active = state
-            .active_cancel
-            .lock()
-            .map_err(|_| "download state lock poisoned".to_string())?;
-        if active.is_some() {
-            return Err("A download is already active. Cancel it first.".to_string());
-        }
-        *active = Some(cancel.clone());
+        Some(raw) => validate_comfyui_repo_url(raw)?,
+        None => COMFYUI_REPO_URL.to_string(),
+    };
+    let comfy_dir = if path_name_is_comfyui(&base_root) {
+        base_root.clone()
+    } else {
+        choose_install_folder(&base_root, request.force_fresh)
+    };
+    std::fs::create_dir_all(&comfy_dir).map_err(|err| err.to_string())?;
+    let _install_log = InstallLogGuard::start(&comfy_dir);
+    write_install_request(&comfy_dir, request);
+    write_install_state(&comfy_dir, "in_progress", "container_engine");
+    emit_install_event(
+        app,
+        "info",
+        &format!("Install folder selected: {}", comfy_dir.display()),
+    );
+
+    let engine = resolve_container_engine(preference)?;
+    let torch_profile = request
+        .torch_profile
+        .clone()
+        .unwrap_or_else(|| get_comfyui_install_recommendation().torch_profile);
+    // Checked before the build so a missing toolkit fails in seconds, not after Torch.
+    container_gpu_args(engine, &torch_profile)?;
+    emit_install_event(
+        app,
+        "step",
+        &format!("Using {engine} with Torch profile {torch_profile}."),
+    );
+
+    for dir in ["models", "custom_nodes", "input", "output", "user"] {
+        let path = comfy_dir.join(dir);
+        std::fs::create_dir_all(&path)
+            .map_err(|err| format!("Failed to create {}: {err}", path.display()))?;
+    }
+    let data_dirs = ComfyDataDirs::from_raw(
+        request.output_dir.as_deref(),
+        request.input_dir.as_deref(),
+        request.user_dir.as_deref(),
+    )?;
+    if !data_dirs.is_empty() {
+        write_comfy_data_dirs(
+            &app.state::<AppState>().context.config.settings(),
+            &comfy_dir,
+            &data_dirs,
+        )?;
     }
 
-    let mut resolved_for_download = resolved.clone();
-    resolved_for_download.variant.artifacts = planned;
+    let slug = install_slug(&comfy_dir);
+    let install = ContainerInstall {
+        engine: engine.to_string(),
+        image: format!("arctic-comfyui-{slug}:latest"),
+        container_name: format!("arctic-comfyui-{slug}"),
+        torch_profile,
+        repo_url,
+        comfyui_ref: requested_comfyui_ref(request.comfyui_ref.as_deref())?,
+    };
+    write_install_state(&comfy_dir, "in_progress", "container_build");
+    emit_install_event(
+        app,
+        "step",
+        &format!(
+            "Building image {} (Torch, ComfyUI and its requirements)...",
+            install.image
+        ),
+    );
+    build_container_image(&comfy_dir, &install, false)?;
+    if cancel.is_cancelled() {
+        return Err("Installation cancelled.".to_string());
+    }
+    write_container_install(&comfy_dir, &install)?;
+    if let Some(options) = &request.server {
+        let server = configure_server_mode(&comfy_dir, options)?;
+        emit_install_event(app, "info", &server.describe(&comfy_dir));
+    }
+    write_install_state(&comfy_dir, "completed", "done");
+    let completion = InstallCompletion::new(&comfy_dir, request);
+    write_install_config(
+        &comfy_dir,
+        &InstallConfig {
+            data_dirs: read_comfy_data_dirs(&comfy_dir),
+            ..completion.install_config()
+        },
+    )?;
+    write_install_completion(&comfy_dir, &completion);
+    Ok(comfy_dir)
+}
 
-    let (tx, rx) = std::sync::mpsc::channel();
-    let handle = state.context.downloads.download_variant_with_cancel(
-        effective_root,
-        resolved_for_download,
-        tx,
-        Some(cancel),
+/// The foreground `<engine> run` command for a container install.
+fn container_launch_command(
+    state: &AppState,
+    comfy_root: &Path,
+    install: &ContainerInstall,
+) -> Result<std::process::Command, String> {
+    let settings = state.context.config.settings();
+    let server = read_server_config(comfy_root);
+    let port = server.as_ref().map_or_else(
+        || comfyui_port_for_root(state, comfy_root),
+        |server| server.port,
     );
-    if let Ok(mut abort) = state.active_abort.lock() {
-        *abort = Some(handle.abort_handle());
+    let host_ip = match server.as_ref().map(|server| server.listen.as_str()) {
+        Some(listen) if listen.contains(':') => format!("[{listen}]"),
+        Some(listen) => listen.to_string(),
+        None => "127.0.0.1".to_string(),
+    };
+    let mut args: Vec<String> = vec![
+        "run".to_string(),
+        "--rm".to_string(),
+        "--name".to_string(),
+        install.container_name.clone(),
+        "-p".to_string(),
+        format!("{host_ip}:{port}:{CONTAINER_COMFYUI_PORT}"),
+    ];
+    args.extend(container_gpu_args(&install.engine, &install.torch_profile)?);
+    for (host, target) in container_mounts(comfy_root) {
+        if !host.is_dir() {
+            return Err(format!(
+                "Container data folder is not available: {}.",
+                host.display()
+            ));
+        }
+        args.push("-v".to_string());
+        // `:z` relabels for SELinux hosts; engines ignore it elsewhere.
+        args.push(format!("{}:{target}:z", host.display()));
     }
-    spawn_progress_emitter(app.clone(), "model".to_string(), rx);
-    let app_for_task = app.clone();
-    tauri::async_runtime::spawn(async move {
-        let result = handle.await;
-        let managed = app_for_task.state::<AppState>();
-        if let Ok(mut active) = managed.active_cancel.lock() {
-            *active = None;
+    let config = install_config(&settings, comfy_root);
+    for (name, value) in &config.launch_env() {
+        args.push("-e".to_string());
+        args.push(format!("{name}={value}"));
+    }
+    args.push(install.image.clone());
+    args.extend(
+        [
+            "python",
+            "main.py",
+            "--listen",
+            "0.0.0.0",
+            "--port",
+            &CONTAINER_COMFYUI_PORT.to_string(),
+        ]
+        .map(str::to_string),
+    );
+    let vram_flags = vram_launch_flags(
+        config.vram_mode.as_deref(),
+        launch_vram_tier(&settings, &config),
+        config.cache_none,
+    );
+    args.extend(comfyui_launch_args(
+        config.pinned_memory_enabled,
+        None,
+        false,
+        &ComfyDataDirs::default(),
+        is_cpu_torch_profile(&install.torch_profile),
+        &vram_flags,
+    ));
+
+    let mut cmd = std::process::Command::new(&install.engine);
+    cmd.args(&args);
+    Ok(cmd)
+}
+
+fn start_comfyui_container(
+    state: &AppState,
+    comfy_root: &Path,
+    install: &ContainerInstall,
+    instance: &ComfyInstance,
+) -> Result<(), String> {
+    let mut cmd = container_launch_command(state, comfy_root, install)?;
+    // A container left behind by a crash still holds the name.
+    let _ = run_command_capture(
+        &install.engine,
+        &["rm", "-f", &install.container_name],
+        None,
+    );
+    if !nerdstats_enabled() {
+        apply_background_command_flags(&mut cmd);
+    }
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| format!("Failed to start ComfyUI container: {err}"))?;
+    capture_comfyui_output(comfy_root, &mut child);
+    instance.attach(child)
+}
+
+/// Killing the `run` client does not stop a Podman container, so stop it by name.
+fn stop_comfyui_container(install: &ContainerInstall) -> bool {
+    run_command_capture(
+        &install.engine,
+        &["stop", "-t", "10", &install.container_name],
+        None,
+    )
+    .is_ok()
+}
+
+const INSTALL_CONFIG_FILE: &str = ".arctic_install.json";
+
+/// Launch settings of one install. The matching `AppSettings` fields described
+/// whichever root was active, so they are now only the migration source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstallConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    torch_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attention_backend: Option<String>,
+    #[serde(default = "default_true")]
+    pinned_memory_enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    flavor: Option<String>,
+    /// Extra environment for every launch, applied over the variables the app sets.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    /// `comfyui-frontend-package` pin that updates and venv rebuilds re-apply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    frontend_version: Option<String>,
+    /// GPU indices this install launches on, as `CUDA_VISIBLE_DEVICES` takes them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cuda_devices: Option<String>,
+    /// One of `VRAM_MODES` other than `auto`, which is stored as `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vram_mode: Option<String>,
+    /// Launch with `--cache-none`, trading re-execution for lower RAM use.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    cache_none: bool,
+    /// Output, input and user folders outside the install.
+    #[serde(default, skip_serializing_if = "ComfyDataDirs::is_empty")]
+    data_dirs: ComfyDataDirs,
+}
+
+impl InstallConfig {
+    /// Environment a launch gets: the GPU pick, then the user's variables over it.
+    /// Device indices come from nvidia-smi, which numbers GPUs in PCI bus order, so
+    /// CUDA is told to use that order too instead of its fastest-first default.
+    fn launch_env(&self) -> BTreeMap<String, String> {
+        let mut env = BTreeMap::new();
+        if let Some(devices) = &self.cuda_devices {
+            env.insert("CUDA_VISIBLE_DEVICES".to_string(), devices.clone());
         }
-        if let Ok(mut abort) = managed.active_abort.lock() {
-            *abort = None;
+        env.extend(self.env.clone());
+        if env.contains_key("CUDA_VISIBLE_DEVICES") {
+            env.entry("CUDA_DEVICE_ORDER".to_string())
+                .or_insert_with(|| "PCI_BUS_ID".to_string());
         }
+        env
+    }
+}
 
-        match result {
-            Ok(Ok(outcomes)) => {
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "model".to_string(),
-                        phase: "batch_finished".to_string(),
-                        artifact: None,
-                        index: None,
-                        total: Some(outcomes.len()),
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some("Model download batch completed.".to_string()),
-                    },
-                );
-            }
-            Ok(Err(err)) => {
-                let lower = err.to_string().to_ascii_lowercase();
-                let phase = if lower.contains("cancel") {
-                    "cancelled"
-                } else {
-                    "batch_failed"
-                };
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "model".to_string(),
-                        phase: phase.to_string(),
-                        artifact: None,
-                        index: None,
-                        total: None,
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some(err.to_string()),
-                    },
-                );
-            }
-            Err(join_err) => {
-                let phase = if join_err.is_cancelled() {
-                    "cancelled"
-                } else {
-                    "batch_failed"
-                };
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "model".to_string(),
-                        phase: phase.to_string(),
-                        artifact: None,
-                        index: None,
-                        total: None,
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some(join_err.to_string()),
-                    },
-                );
-            }
+impl Default for InstallConfig {
+    fn default() -> Self {
+        Self {
+            torch_profile: None,
+            attention_backend: None,
+            pinned_memory_enabled: true,
+            flavor: None,
+            env: BTreeMap::new(),
+            frontend_version: None,
+            cuda_devices: None,
+            vram_mode: None,
+            cache_none: false,
+            data_dirs: ComfyDataDirs::default(),
         }
-    });
+    }
+}
+
+/// Reads the install config, folding in a legacy `.arctic_data_dirs.json` sidecar
+/// once so data-dir overrides only live in the install config afterwards.
+fn read_install_config(comfy_root: &Path) -> Option<InstallConfig> {
+    let data = std::fs::read(comfy_root.join(INSTALL_CONFIG_FILE)).ok()?;
+    let mut config: InstallConfig = serde_json::from_slice(&data).ok()?;
+    if let Some(legacy) = read_legacy_data_dirs(comfy_root) {
+        if config.data_dirs.is_empty() {
+            config.data_dirs = legacy;
+        }
+        if let Err(err) = write_install_config(comfy_root, &config) {
+            log::warn!("Failed to migrate data folder overrides: {err}");
+        }
+    }
+    Some(config)
+}
 
+fn write_install_config(comfy_root: &Path, config: &InstallConfig) -> Result<(), String> {
+    let path = comfy_root.join(INSTALL_CONFIG_FILE);
+    let data = serde_json::to_vec_pretty(config).map_err(|err| err.to_string())?;
+    std::fs::write(&path, data)
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    let legacy = comfy_root.join(LEGACY_DATA_DIRS_FILE);
+    if legacy.exists() {
+        if let Err(err) = std::fs::remove_file(&legacy) {
+            log::warn!("Failed to remove {}: {err}", legacy.display());
+        }
+    }
     Ok(())
 }
 
+fn is_configured_root(settings: &AppSettings, root: &Path) -> bool {
+    let canonical = |path: &Path| {
+        normalize_canonical_path(
+            &std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+        )
+    };
+    settings
+        .comfyui_root
+        .as_deref()
+        .is_some_and(|configured| canonical(configured) == canonical(root))
+}
+
+/// The install's config. An install without one takes the global values if it is the
+/// configured root, which is the only install they ever described, and keeps them.
+fn install_config(settings: &AppSettings, comfy_root: &Path) -> InstallConfig {
+    if let Some(config) = read_install_config(comfy_root) {
+        return config;
+    }
+    let data_dirs = read_legacy_data_dirs(comfy_root).unwrap_or_default();
+    if !is_configured_root(settings, comfy_root) {
+        return InstallConfig {
+            data_dirs,
+            ..InstallConfig::default()
+        };
+    }
+    let config = InstallConfig {
+        torch_profile: settings.comfyui_torch_profile.clone(),
+        attention_backend: settings.comfyui_attention_backend.clone(),
+        pinned_memory_enabled: settings.comfyui_pinned_memory_enabled,
+        flavor: settings.comfyui_install_flavor.clone(),
+        env: BTreeMap::new(),
+        frontend_version: None,
+        cuda_devices: None,
+        vram_mode: None,
+        cache_none: false,
+        data_dirs,
+    };
+    if comfy_root.join("main.py").is_file() || read_container_install(comfy_root).is_some() {
+        if let Err(err) = write_install_config(comfy_root, &config) {
+            log::warn!("Failed to migrate install settings: {err}");
+        }
+    }
+    config
+}
+
+fn valid_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 #[tauri::command]
-async fn download_lora_asset(
-    app: AppHandle,
+fn get_install_env_vars(
     state: State<'_, AppState>,
-    lora_id: String,
-    token: Option<String>,
     comfyui_root: Option<String>,
-) -> Result<(), String> {
+) -> Result<BTreeMap<String, String>, String> {
     let root = resolve_root_path(&state.context, comfyui_root)?;
-    let effective_root = match comfy_extra_model_config(&root) {
-        Some(config) if config.is_default => {
-            log::info!(
-                "Using extra model base path for LoRA downloads: {}",
-                config.base_path.display()
-            );
-            config.base_path
-        }
-        _ => root,
-    };
-    let lora = state
-        .context
-        .catalog
-        .find_lora(&lora_id)
-        .ok_or_else(|| "Selected LoRA was not found in catalog.".to_string())?;
+    Ok(install_config(&state.context.config.settings(), &root).env)
+}
 
-    let cancel = CancellationToken::new();
-    {
-        let mut active = state
-            .active_cancel
-            .lock()
-            .map_err(|_| "download state lock poisoned".to_string())?;
-        if active.is_some() {
-            return Err("A download is already active. Cancel it first.".to_string());
+/// Replaces the launch environment of one install; takes effect on the next start.
+#[tauri::command]
+fn set_install_env_vars(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    vars: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let mut env = BTreeMap::new();
+    for (name, value) in vars {
+        let name = name.trim().to_string();
+        if !valid_env_var_name(&name) {
+            return Err(format!(
+                "'{name}' is not a valid environment variable name."
+            ));
         }
-        *active = Some(cancel.clone());
+        if value.contains('\0') || value.contains('\n') {
+            return Err(format!("The value of {name} contains a control character."));
+        }
+        env.insert(name, value);
     }
+    update_install_config(&state.context.config.settings(), &root, |config| {
+        config.env = env.clone()
+    })?;
+    Ok(env)
+}
 
-    let (tx, rx) = std::sync::mpsc::channel();
-    let handle =
-        state
-            .context
-            .downloads
-            .download_lora_with_cancel(effective_root, lora, token, tx, Some(cancel));
-    if let Ok(mut abort) = state.active_abort.lock() {
-        *abort = Some(handle.abort_handle());
-    }
-    spawn_progress_emitter(app.clone(), "lora".to_string(), rx);
-    let app_for_task = app.clone();
-    tauri::async_runtime::spawn(async move {
-        let result = handle.await;
-        let managed = app_for_task.state::<AppState>();
-        if let Ok(mut active) = managed.active_cancel.lock() {
-            *active = None;
+#[derive(Debug, Serialize)]
+struct ComfyGpuSelection {
+    gpus: Vec<NvidiaGpuEntry>,
+    /// `None` launches on every GPU.
+    cuda_devices: Option<String>,
+}
+
+/// Checks a comma-separated list of GPU indices against the detected GPUs and
+/// returns it without duplicates; empty means every GPU.
+fn normalize_cuda_devices(
+    raw: Option<&str>,
+    gpus: &[NvidiaGpuEntry],
+) -> Result<Option<String>, String> {
+    let Some(raw) = raw.map(str::trim).filter(|raw| !raw.is_empty()) else {
+        return Ok(None);
+    };
+    let mut indices: Vec<u32> = Vec::new();
+    for part in raw.split(',').map(str::trim) {
+        let index = part
+            .parse::<u32>()
+            .map_err(|_| format!("'{part}' is not a GPU index."))?;
+        if !gpus.is_empty() && !gpus.iter().any(|gpu| gpu.index == index) {
+            return Err(format!("No GPU with index {index} was detected."));
         }
-        if let Ok(mut abort) = managed.active_abort.lock() {
-            *abort = None;
+        if !indices.contains(&index) {
+            indices.push(index);
         }
+    }
+    Ok(Some(
+        indices
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+    ))
+}
 
-        match result {
-            Ok(Ok(_outcome)) => {
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "lora".to_string(),
-                        phase: "batch_finished".to_string(),
-                        artifact: None,
-                        index: None,
-                        total: Some(1),
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some("LoRA download completed.".to_string()),
-                    },
-                );
-            }
-            Ok(Err(err)) => {
-                let lower = err.to_string().to_ascii_lowercase();
-                let phase = if lower.contains("cancel") {
-                    "cancelled"
-                } else {
-                    "batch_failed"
-                };
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "lora".to_string(),
-                        phase: phase.to_string(),
-                        artifact: None,
-                        index: None,
-                        total: None,
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some(err.to_string()),
-                    },
-                );
-            }
-            Err(join_err) => {
-                let phase = if join_err.is_cancelled() {
-                    "cancelled"
-                } else {
-                    "batch_failed"
-                };
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "lora".to_string(),
-                        phase: phase.to_string(),
-                        artifact: None,
-                        index: None,
-                        total: None,
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some(join_err.to_string()),
-                    },
-                );
-            }
-        }
-    });
+#[tauri::command]
+async fn get_comfyui_gpus(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<ComfyGpuSelection, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let cuda_devices = install_config(&state.context.config.settings(), &root).cuda_devices;
+    let gpus = tauri::async_runtime::spawn_blocking(query_nvidia_gpus_blocking)
+        .await
+        .map_err(|err| format!("GPU detection failed: {err}"))?;
+    Ok(ComfyGpuSelection { gpus, cuda_devices })
+}
 
-    Ok(())
+/// Records which GPUs an install launches on; takes effect on its next start.
+#[tauri::command]
+async fn set_comfyui_gpu(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    cuda_devices: Option<String>,
+) -> Result<Option<String>, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let gpus = tauri::async_runtime::spawn_blocking(query_nvidia_gpus_blocking)
+        .await
+        .map_err(|err| format!("GPU detection failed: {err}"))?;
+    let devices = normalize_cuda_devices(cuda_devices.as_deref(), &gpus)?;
+    update_install_config(&state.context.config.settings(), &root, |config| {
+        config.cuda_devices = devices.clone()
+    })?;
+    Ok(devices)
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyVramMode {
+    mode: String,
+    cache_none: bool,
+    /// Tier automatic mode would size the flags by.
+    detected_tier: Option<String>,
+    /// Memory flags the next launch gets.
+    flags: Vec<String>,
+}
+
+fn comfy_vram_mode(settings: &AppSettings, config: &InstallConfig) -> ComfyVramMode {
+    let tier = launch_vram_tier(settings, config);
+    ComfyVramMode {
+        mode: config
+            .vram_mode
+            .clone()
+            .unwrap_or_else(|| "auto".to_string()),
+        cache_none: config.cache_none,
+        detected_tier: tier.map(|tier| tier.identifier().to_string()),
+        flags: vram_launch_flags(config.vram_mode.as_deref(), tier, config.cache_none)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+    }
 }
 
 #[tauri::command]
-async fn download_workflow_asset(
-    app: AppHandle,
+async fn get_comfyui_vram_mode(
     state: State<'_, AppState>,
-    workflow_id: String,
     comfyui_root: Option<String>,
-) -> Result<(), String> {
+) -> Result<ComfyVramMode, String> {
     let root = resolve_root_path(&state.context, comfyui_root)?;
-    let workflow: WorkflowDefinition = state
-        .context
-        .catalog
-        .find_workflow(&workflow_id)
-        .ok_or_else(|| "Selected workflow was not found in catalog.".to_string())?;
+    let settings = state.context.config.settings();
+    let config = install_config(&settings, &root);
+    tauri::async_runtime::spawn_blocking(move || comfy_vram_mode(&settings, &config))
+        .await
+        .map_err(|err| format!("VRAM detection failed: {err}"))
+}
 
-    let workflows_dir = root.join("user").join("default").join("workflows");
-    std::fs::create_dir_all(&workflows_dir).map_err(|err| {
-        format!(
-            "Failed to create ComfyUI workflows directory ({}): {err}",
-            workflows_dir.display()
-        )
+/// Overrides the VRAM flags of one install; takes effect on its next start.
+#[tauri::command]
+async fn set_comfyui_vram_mode(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    mode: Option<String>,
+    cache_none: Option<bool>,
+) -> Result<ComfyVramMode, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let mode = mode
+        .map(|mode| mode.trim().to_ascii_lowercase())
+        .filter(|mode| !mode.is_empty());
+    if let Some(mode) = mode.as_deref() {
+        if !VRAM_MODES.contains(&mode) {
+            return Err(format!(
+                "VRAM mode must be one of {}.",
+                VRAM_MODES.join(", ")
+            ));
+        }
+    }
+    let settings = state.context.config.settings();
+    update_install_config(&settings, &root, |config| {
+        config.vram_mode = mode.filter(|mode| mode != "auto");
+        if let Some(cache_none) = cache_none {
+            config.cache_none = cache_none;
+        }
     })?;
+    let config = install_config(&settings, &root);
+    tauri::async_runtime::spawn_blocking(move || comfy_vram_mode(&settings, &config))
+        .await
+        .map_err(|err| format!("VRAM detection failed: {err}"))
+}
 
-    let cancel = CancellationToken::new();
-    {
-        let mut active = state
-            .active_cancel
-            .lock()
-            .map_err(|_| "download state lock poisoned".to_string())?;
-        if active.is_some() {
-            return Err("A download is already active. Cancel it first.".to_string());
+fn update_install_config(
+    settings: &AppSettings,
+    comfy_root: &Path,
+    update: impl FnOnce(&mut InstallConfig),
+) -> Result<(), String> {
+    let mut config = install_config(settings, comfy_root);
+    update(&mut config);
+    write_install_config(comfy_root, &config)
+}
+
+const INSTALL_COMPLETION_FILE: &str = ".arctic_install_complete.json";
+
+/// Settings a finished install registers, written into the ComfyUI root before the app
+/// applies them. `registered` flips once settings.json has them, so an install that
+/// finished while the app was closing is picked up on the next start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstallCompletion {
+    comfyui_root: PathBuf,
+    install_dir: PathBuf,
+    torch_profile: String,
+    attention_backend: String,
+    pinned_memory_enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shared_models_root: Option<PathBuf>,
+    #[serde(default)]
+    shared_models_use_default: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    flavor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    frontend_version: Option<String>,
+    completed_at: u64,
+    #[serde(default)]
+    registered: bool,
+}
+
+impl InstallCompletion {
+    fn new(comfy_root: &Path, request: &ComfyInstallRequest) -> Self {
+        let shared_models_root = normalize_optional_path(request.extra_model_root.as_deref())
+            .ok()
+            .flatten();
+        Self {
+            comfyui_root: comfy_root.to_path_buf(),
+            install_dir: comfy_root
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| comfy_root.to_path_buf()),
+            torch_profile: request
+                .torch_profile
+                .clone()
+                .unwrap_or_else(|| get_comfyui_install_recommendation().torch_profile),
+            attention_backend: selected_attention_backend(request).to_string(),
+            pinned_memory_enabled: request.include_pinned_memory,
+            shared_models_use_default: shared_models_root.is_some()
+                && request.extra_model_use_default,
+            shared_models_root,
+            flavor: request.server.as_ref().map(|_| "server".to_string()),
+            frontend_version: normalize_frontend_version(request.frontend_version.as_deref())
+                .ok()
+                .flatten(),
+            completed_at: unix_now_secs(),
+            registered: false,
         }
-        *active = Some(cancel.clone());
     }
 
-    let (tx, rx) = std::sync::mpsc::channel();
-    let handle = state.context.downloads.download_workflow_with_cancel(
-        workflows_dir,
-        workflow,
-        tx,
-        Some(cancel),
-    );
-    if let Ok(mut abort) = state.active_abort.lock() {
-        *abort = Some(handle.abort_handle());
+    fn install_config(&self) -> InstallConfig {
+        InstallConfig {
+            torch_profile: Some(self.torch_profile.clone()),
+            attention_backend: Some(self.attention_backend.clone()),
+            pinned_memory_enabled: self.pinned_memory_enabled,
+            flavor: self.flavor.clone(),
+            env: BTreeMap::new(),
+            frontend_version: self.frontend_version.clone(),
+            cuda_devices: None,
+            vram_mode: None,
+            cache_none: false,
+            data_dirs: ComfyDataDirs::default(),
+        }
     }
-    spawn_progress_emitter(app.clone(), "workflow".to_string(), rx);
-    let app_for_task = app.clone();
-    tauri::async_runtime::spawn(async move {
-        let result = handle.await;
-        let managed = app_for_task.state::<AppState>();
-        if let Ok(mut active) = managed.active_cancel.lock() {
-            *active = None;
+
+    fn apply_to(&self, settings: &mut AppSettings) {
+        settings.comfyui_root = Some(self.comfyui_root.clone());
+        settings.comfyui_last_install_dir = Some(self.install_dir.clone());
+        settings.comfyui_pinned_memory_enabled = self.pinned_memory_enabled;
+        settings.comfyui_torch_profile = Some(self.torch_profile.clone());
+        settings.comfyui_attention_backend = Some(self.attention_backend.clone());
+        settings.shared_models_root = self.shared_models_root.clone();
+        settings.shared_models_use_default = self.shared_models_use_default;
+        settings.comfyui_install_flavor = self.flavor.clone();
+    }
+}
+
+fn read_install_completion(comfy_root: &Path) -> Option<InstallCompletion> {
+    let data = std::fs::read(comfy_root.join(INSTALL_COMPLETION_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+fn write_install_completion(comfy_root: &Path, completion: &InstallCompletion) {
+    let path = comfy_root.join(INSTALL_COMPLETION_FILE);
+    let tmp = path.with_extension("json.tmp");
+    if let Ok(data) = serde_json::to_vec_pretty(completion) {
+        // Write-then-rename so a crash mid-write never leaves a truncated marker.
+        if std::fs::write(&tmp, data).is_ok() {
+            let _ = std::fs::rename(&tmp, &path);
         }
-        if let Ok(mut abort) = managed.active_abort.lock() {
-            *abort = None;
+    }
+}
+
+fn register_install_completion(
+    state: &AppState,
+    completion: &mut InstallCompletion,
+) -> Result<(), String> {
+    state
+        .context
+        .config
+        .update_settings(|settings| completion.apply_to(settings))
+        .map_err(|err| err.to_string())?;
+    completion.registered = true;
+    write_install_completion(&completion.comfyui_root, completion);
+    Ok(())
+}
+
+/// Registers installs that finished but never made it into settings (app closed
+/// right at completion). Looks in the install base and last install folder.
+fn reconcile_completed_installs(state: &AppState) {
+    let settings = state.context.config.settings();
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    for base in [
+        settings.comfyui_install_base.clone(),
+        settings.comfyui_last_install_dir.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        candidates.push(base.clone());
+        if let Ok(entries) = std::fs::read_dir(&base) {
+            candidates.extend(
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_dir() && path_name_is_comfyui(path)),
+            );
+        }
+    }
+    candidates.sort();
+    candidates.dedup();
+
+    let mut pending: Vec<InstallCompletion> = candidates
+        .iter()
+        .filter_map(|root| read_install_completion(root))
+        .filter(|completion| {
+            !completion.registered && completion.comfyui_root.join("main.py").is_file()
+        })
+        .collect();
+    // Only the newest completion becomes the active root; older ones are just marked.
+    pending.sort_by_key(|completion| completion.completed_at);
+    let Some(mut newest) = pending.pop() else {
+        return;
+    };
+    match register_install_completion(state, &mut newest) {
+        Ok(()) => log::info!(
+            "Registered completed ComfyUI install found at startup: {}",
+            newest.comfyui_root.display()
+        ),
+        Err(err) => {
+            log::warn!("Failed to register completed ComfyUI install: {err}");
+            return;
         }
+    }
+    for mut older in pending {
+        older.registered = true;
+        write_install_completion(&older.comfyui_root, &older);
+    }
+}
 
-        match result {
-            Ok(Ok(outcome)) => {
-                let message = match outcome.status {
-                    DownloadStatus::SkippedExisting => {
-                        "Workflow already exists. Skipped download.".to_string()
-                    }
-                    DownloadStatus::Downloaded => "Workflow download completed.".to_string(),
-                };
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "workflow".to_string(),
-                        phase: "batch_finished".to_string(),
-                        artifact: None,
-                        index: None,
-                        total: Some(1),
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some(message),
-                    },
-                );
-            }
-            Ok(Err(err)) => {
-                let lower = err.to_string().to_ascii_lowercase();
-                let phase = if lower.contains("cancel") {
-                    "cancelled"
-                } else {
-                    "batch_failed"
-                };
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "workflow".to_string(),
-                        phase: phase.to_string(),
-                        artifact: None,
-                        index: None,
-                        total: None,
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some(err.to_string()),
-                    },
-                );
-            }
-            Err(join_err) => {
-                let phase = if join_err.is_cancelled() {
-                    "cancelled"
-                } else {
-                    "batch_failed"
-                };
-                let _ = app_for_task.emit(
-                    "download-progress",
-                    DownloadProgressEvent {
-                        kind: "workflow".to_string(),
-                        phase: phase.to_string(),
-                        artifact: None,
-                        index: None,
-                        total: None,
-                        received: None,
-                        size: None,
-                        folder: None,
-                        message: Some(join_err.to_string()),
-                    },
-                );
-            }
-        }
-    });
+#[derive(Debug, Serialize)]
+struct InstallPlanStep {
+    step: String,
+    title: String,
+    detail: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    packages: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sources: Vec<String>,
+    estimated_bytes: Option<u64>,
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+struct ComfyInstallPlan {
+    install_dir: String,
+    reuses_existing: bool,
+    torch_profile: String,
+    repo_url: String,
+    comfyui_ref: Option<String>,
+    steps: Vec<InstallPlanStep>,
+    estimated_download_bytes: u64,
+    warnings: Vec<String>,
 }
 
-#[tauri::command]
-async fn get_lora_metadata(
-    state: State<'_, AppState>,
-    lora_id: String,
-    token: Option<String>,
-) -> Result<LoraMetadataResponse, String> {
-    let lora: LoraDefinition = state
-        .context
-        .catalog
-        .find_lora(&lora_id)
-        .ok_or_else(|| "Selected LoRA was not found in catalog.".to_string())?;
+const MIB: u64 = 1024 * 1024;
 
-    if !lora
-        .download_url
-        .to_ascii_lowercase()
-        .contains("civitai.com")
-    {
-        return Ok(LoraMetadataResponse {
-            creator: "N/A".to_string(),
-            creator_url: None,
-            strength: "N/A".to_string(),
-            triggers: Vec::new(),
-            description: lora
-                .note
-                .unwrap_or_else(|| "Metadata is available for Civitai LoRAs only.".to_string()),
-            preview_url: None,
-            preview_kind: "none".to_string(),
-        });
+/// Rough download sizes used only for the dry-run plan; real sizes vary by release.
+fn torch_profile_download_estimate(profile: &str) -> u64 {
+    match profile {
+        "torch_cpu" => 250 * MIB,
+        "torch280_rocm64" | "torch291_rocm64" => 4800 * MIB,
+        _ => 3200 * MIB,
     }
+}
 
-    let result = state
-        .context
-        .downloads
-        .civitai_model_metadata(lora.download_url.clone(), token)
-        .await;
-
-    match result {
-        Ok(Ok(metadata)) => {
-            let (preview_kind, preview_url) = match metadata.preview {
-                Some(CivitaiPreview::Video { url }) => ("video".to_string(), Some(url)),
-                Some(CivitaiPreview::Image(_)) => (
-                    if metadata
-                        .preview_url
-                        .as_deref()
-                        .map(is_video_url)
-                        .unwrap_or(false)
-                    {
-                        "video".to_string()
-                    } else {
-                        "image".to_string()
-                    },
-                    metadata.preview_url.clone(),
-                ),
-                None => (
-                    if metadata
-                        .preview_url
-                        .as_deref()
-                        .map(is_video_url)
-                        .unwrap_or(false)
-                    {
-                        "video".to_string()
-                    } else {
-                        "none".to_string()
-                    },
-                    metadata.preview_url.clone(),
-                ),
-            };
-
-            Ok(LoraMetadataResponse {
-                creator: metadata
-                    .creator_username
-                    .unwrap_or_else(|| "Unknown creator".to_string()),
-                creator_url: metadata.creator_link,
-                strength: metadata
-                    .usage_strength
-                    .map(|value| format!("{value:.2}"))
-                    .unwrap_or_else(|| "Not provided".to_string()),
-                triggers: metadata.trained_words,
-                description: metadata
-                    .description
-                    .map(|text| strip_html_tags(&text))
-                    .filter(|text| !text.trim().is_empty())
-                    .unwrap_or_else(|| "No description available.".to_string()),
-                preview_url,
-                preview_kind,
-            })
-        }
-        Ok(Err(err)) => Err(format!("Failed to load LoRA metadata: {err:#}")),
-        Err(join_err) => Err(format!("LoRA metadata task failed: {join_err}")),
+fn plan_step(
+    step: &str,
+    title: &str,
+    detail: String,
+    packages: Vec<String>,
+    sources: Vec<String>,
+    estimated_bytes: Option<u64>,
+) -> InstallPlanStep {
+    InstallPlanStep {
+        step: step.to_string(),
+        title: title.to_string(),
+        detail,
+        packages,
+        sources,
+        estimated_bytes,
     }
 }
 
-fn resolve_root_path(
-    context: &AppContext,
-    comfyui_root: Option<String>,
-) -> Result<std::path::PathBuf, String> {
-    fn normalize_existing(path: std::path::PathBuf) -> Option<std::path::PathBuf> {
-        let absolute = if path.is_absolute() {
-            path
-        } else if let Ok(cwd) = std::env::current_dir() {
-            cwd.join(path)
-        } else {
-            path
-        };
-        if !absolute.exists() {
-            return None;
-        }
-        let canonical = std::fs::canonicalize(&absolute).ok().or(Some(absolute))?;
-        Some(normalize_canonical_path(&canonical).to_path_buf())
+/// Mirrors `run_comfyui_install_linux` step for step without touching the disk or
+/// installing anything, so the plan can be reviewed before committing to it.
+#[tauri::command]
+fn plan_comfyui_install(request: ComfyInstallRequest) -> Result<ComfyInstallPlan, String> {
+    let selected_attention = [
+        request.include_sage_attention,
+        request.include_sage_attention3,
+        request.include_flash_attention,
+        request.include_nunchaku,
+    ]
+    .into_iter()
+    .filter(|v| *v)
+    .count();
+    if selected_attention > 1 {
+        return Err(
+            "Choose only one of SageAttention, SageAttention3, FlashAttention, or Nunchaku."
+                .to_string(),
+        );
     }
+    let base_root = normalize_path(&request.install_root)?;
+    let extra_model_root = normalize_optional_path(request.extra_model_root.as_deref())?;
+    let repo_url = match request
+        .comfyui_repo_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(raw) => validate_comfyui_repo_url(raw)?,
+        None => COMFYUI_REPO_URL.to_string(),
+    };
+    let comfyui_ref = requested_comfyui_ref(request.comfyui_ref.as_deref())?;
+    let comfy_dir = if path_name_is_comfyui(&base_root) {
+        base_root.clone()
+    } else {
+        choose_install_folder(&base_root, request.force_fresh)
+    };
+    let reuses_existing = comfy_dir.join("main.py").exists();
+    let profile = request
+        .torch_profile
+        .clone()
+        .unwrap_or_else(|| get_comfyui_install_recommendation().torch_profile);
+    let wheel_arch = detect_wheel_arch();
+    let include_insight_face = request.include_insight_face || request.include_nunchaku;
+    let mut warnings = Vec::new();
+    let mut steps = Vec::new();
 
-    if let Some(root) = comfyui_root {
-        let trimmed = root.trim();
-        if !trimmed.is_empty() {
-            let path = std::path::PathBuf::from(trimmed);
-            if let Some(normalized) = normalize_existing(path) {
-                return Ok(normalized);
-            }
+    match get_linux_prereq_cache_or_scan() {
+        Ok(scan) => {
+            let optional: &[String] = if request.server.is_some() {
+                &[]
+            } else {
+                &scan.missing_optional
+            };
+            let missing: Vec<String> = scan
+                .missing_required
+                .iter()
+                .chain(optional)
+                .cloned()
+                .collect();
+            let detail = if missing.is_empty() {
+                format!("All {} prerequisites are already installed.", scan.distro)
+            } else if let Some(system) = scan.immutable {
+                format!(
+                    "{system} cannot install packages from here; {}.",
+                    immutable_os_guidance(system, &missing)
+                )
+            } else {
+                format!(
+                    "Install missing {} packages with the system package manager (asks for your password).",
+                    scan.distro
+                )
+            };
+            steps.push(plan_step(
+                "linux_packages",
+                "Linux prerequisites",
+                detail,
+                missing,
+                Vec::new(),
+                None,
+            ));
         }
+        Err(err) => warnings.push(format!("Could not scan Linux prerequisites: {err}")),
     }
 
-    if let Some(path) = context.config.settings().comfyui_root {
-        if let Some(normalized) = normalize_existing(path) {
-            return Ok(normalized);
-        }
+    if reuses_existing {
+        steps.push(plan_step(
+            "clone_comfyui",
+            "ComfyUI core",
+            format!("Reuse the existing checkout in {}.", comfy_dir.display()),
+            Vec::new(),
+            Vec::new(),
+            None,
+        ));
+    } else {
+        let target = match comfyui_ref.as_deref() {
+            Some(git_ref) => format!("then check out {git_ref}"),
+            None => "then pin to the latest release tag".to_string(),
+        };
+        steps.push(plan_step(
+            "clone_comfyui",
+            "ComfyUI core",
+            format!("Clone into {}, {target}.", comfy_dir.display()),
+            Vec::new(),
+            vec![repo_url.clone()],
+            Some(120 * MIB),
+        ));
     }
 
-    Err("Select a valid ComfyUI root folder first.".to_string())
-}
-
-fn parse_yaml_scalar(value: &str) -> String {
-    let trimmed = value.trim();
-    if trimmed.len() >= 2 {
-        let single = trimmed.starts_with('\'') && trimmed.ends_with('\'');
-        let double = trimmed.starts_with('"') && trimmed.ends_with('"');
-        if single || double {
-            let inner = &trimmed[1..trimmed.len() - 1];
-            if single {
-                return inner.replace("''", "'");
-            }
-            return inner.replace("\\\"", "\"");
-        }
+    if let Some(extra_root) = extra_model_root.as_ref() {
+        steps.push(plan_step(
+            "extra_model_paths",
+            "Extra model paths",
+            format!(
+                "Write extra_model_paths.yaml pointing at {}.",
+                extra_root.display()
+            ),
+            Vec::new(),
+            Vec::new(),
+            None,
+        ));
     }
-    trimmed.to_string()
-}
-
-fn parse_yaml_bool(value: &str) -> Option<bool> {
-    match value.trim().to_ascii_lowercase().as_str() {
-        "true" | "yes" | "on" | "1" => Some(true),
-        "false" | "no" | "off" | "0" => Some(false),
-        _ => None,
+    let data_dirs = ComfyDataDirs::from_raw(
+        request.output_dir.as_deref(),
+        request.input_dir.as_deref(),
+        request.user_dir.as_deref(),
+    )?;
+    if !data_dirs.is_empty() {
+        steps.push(plan_step(
+            "data_dirs",
+            "Data folders",
+            data_dirs
+                .entries()
+                .iter()
+                .map(|(flag, path)| format!("{flag} {}", path.display()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            Vec::new(),
+            Vec::new(),
+            None,
+        ));
     }
-}
 
-#[derive(Debug, Clone)]
-struct ComfyExtraModelConfig {
-    base_path: PathBuf,
-    is_default: bool,
-}
+    let venv_detail = if comfy_dir.join(".venv").join("bin").join("python").exists() {
+        format!("Reuse the existing .venv with Python {UV_PYTHON_VERSION}.")
+    } else {
+        format!("Create .venv with uv-managed Python {UV_PYTHON_VERSION}.")
+    };
+    steps.push(plan_step(
+        "python_venv",
+        "Python environment",
+        venv_detail,
+        vec![
+            format!("python=={UV_PYTHON_VERSION}"),
+            "pip".to_string(),
+            "setuptools".to_string(),
+            "wheel".to_string(),
+        ],
+        Vec::new(),
+        Some(60 * MIB),
+    ));
+
+    let (torch_v, vision_v, audio_v, index_url) = torch_profile_to_packages_linux(&profile);
+    steps.push(plan_step(
+        "torch_stack",
+        "Torch stack",
+        format!("Install Torch profile {profile}."),
+        vec![
+            torch_requirement("torch", torch_v),
+            torch_requirement("torchvision", vision_v),
+            torch_requirement("torchaudio", audio_v),
+        ],
+        vec![index_url],
+        Some(torch_profile_download_estimate(&profile)),
+    ));
+    steps.push(plan_step(
+        "comfy_requirements",
+        "ComfyUI requirements",
+        "Install requirements.txt, then re-apply the Torch profile.".to_string(),
+        vec![
+            "-r requirements.txt".to_string(),
+            "pyyaml".to_string(),
+            "nvidia-ml-py".to_string(),
+        ],
+        Vec::new(),
+        Some(700 * MIB),
+    ));
+    match normalize_frontend_version(request.frontend_version.as_deref()) {
+        Ok(Some(version)) => steps.push(plan_step(
+            "comfy_frontend",
+            "ComfyUI frontend pin",
+            format!("Install {FRONTEND_PACKAGE} {version} over the requirements.txt version."),
+            vec![format!("{FRONTEND_PACKAGE}=={version}")],
+            Vec::new(),
+            Some(20 * MIB),
+        )),
+        Ok(None) => {}
+        Err(err) => warnings.push(err),
+    }
 
-#[derive(Debug, Serialize)]
-struct ComfyExtraModelConfigResponse {
-    configured: bool,
-    base_path: Option<String>,
-    use_as_default: bool,
+    let mut wheel_step =
+        |step: &str, title: &str, kinds: &[&str], extra: Vec<String>, bytes: u64| {
+            let mut sources = Vec::new();
+            for kind in kinds {
+                match linux_wheel_url(&profile, kind, wheel_arch) {
+                    Some(url) => sources.push(url.to_string()),
+                    None => warnings.push(format!(
+                        "No prebuilt {kind} wheel for profile {profile}; {title} would fail."
+                    )),
+                }
+            }
+            steps.push(plan_step(
+                step,
+                title,
+                format!("Install prebuilt wheel(s) for {profile}."),
+                extra,
+                sources,
+                Some(bytes),
+            ));
+        };
+    if request.include_sage_attention {
+        wheel_step(
+            "addon_sageattention",
+            "SageAttention",
+            &["sage"],
+            Vec::new(),
+            30 * MIB,
+        );
+    }
+    if include_insight_face {
+        wheel_step(
+            "addon_insightface",
+            "InsightFace",
+            &["insightface"],
+            vec!["onnx".to_string(), "onnxruntime".to_string()],
+            120 * MIB,
+        );
+    }
+    if request.include_flash_attention {
+        wheel_step(
+            "addon_flashattention",
+            "FlashAttention",
+            &["flash"],
+            Vec::new(),
+            250 * MIB,
+        );
+    }
+    if request.include_sage_attention3 {
+        wheel_step(
+            "addon_sageattention3",
+            "SageAttention3",
+            &["sage3", "sage"],
+            Vec::new(),
+            60 * MIB,
+        );
+    }
+    if request.include_nunchaku {
+        wheel_step(
+            "addon_nunchaku",
+            "Nunchaku",
+            &["nunchaku"],
+            Vec::new(),
+            200 * MIB,
+        );
+        if let Some(step) = steps.last_mut() {
+            step.sources
+                .push("https://github.com/nunchaku-ai/ComfyUI-nunchaku".to_string());
+        }
+    }
+    if request.include_xformers {
+        match xformers_requirement_for_profile(&profile) {
+            Some(requirement) => steps.push(plan_step(
+                "addon_xformers",
+                "xformers",
+                format!("Install xformers built for {profile}."),
+                vec![requirement.to_string()],
+                vec![torch_profile_to_packages_linux(&profile).3],
+                Some(60 * MIB),
+            )),
+            None => warnings.push(format!(
+                "No xformers build for profile {profile}; xformers would fail."
+            )),
+        }
+    }
+    if request.include_trellis2 {
+        steps.push(plan_step(
+            "addon_trellis2",
+            "Trellis2",
+            "Clone TRELLIS2, GeometryPack and UltraShape1, install their requirements and download the UltraShape model.".to_string(),
+            vec!["tomli".to_string(), "accelerate".to_string()],
+            vec![
+                "https://github.com/ArcticLatent/ComfyUI-TRELLIS2".to_string(),
+                "https://github.com/PozzettiAndrea/ComfyUI-GeometryPack".to_string(),
+                "https://github.com/jtydhr88/ComfyUI-UltraShape1".to_string(),
+                "https://huggingface.co/infinith/UltraShape/resolve/main/ultrashape_v1.pt".to_string(),
+            ],
+            Some(3000 * MIB),
+        ));
+    }
+    for (step, name, url) in requested_custom_nodes(&request) {
+        steps.push(plan_step(
+            step,
+            name,
+            "Clone into custom_nodes and install its requirements.".to_string(),
+            Vec::new(),
+            vec![url.to_string()],
+            Some(50 * MIB),
+        ));
+    }
+    steps.push(plan_step(
+        "finalize_torch_stack",
+        "Finalize Torch stack",
+        format!("Re-apply {profile} in case custom node requirements changed Torch."),
+        Vec::new(),
+        Vec::new(),
+        None,
+    ));
+
+    let estimated_download_bytes = steps.iter().filter_map(|step| step.estimated_bytes).sum();
+    Ok(ComfyInstallPlan {
+        install_dir: comfy_dir.to_string_lossy().to_string(),
+        reuses_existing,
+        torch_profile: profile,
+        repo_url,
+        comfyui_ref,
+        steps,
+        estimated_download_bytes,
+        warnings,
+    })
 }
 
-fn comfy_extra_model_config(comfy_root: &Path) -> Option<ComfyExtraModelConfig> {
-    let path = comfy_root.join("extra_model_paths.yaml");
-    let content = std::fs::read_to_string(path).ok()?;
-    let mut in_comfyui = false;
-    let mut base_path: Option<PathBuf> = None;
-    let mut is_default = false;
-
-    for line in content.lines() {
-        let without_comment = line.split('#').next().unwrap_or_default();
-        if without_comment.trim().is_empty() {
-            continue;
+#[tauri::command]
+async fn start_comfyui_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: ComfyInstallRequest,
+) -> Result<(), String> {
+    if !request.allow_incompatible {
+        let profile = request
+            .torch_profile
+            .clone()
+            .unwrap_or_else(|| get_comfyui_install_recommendation().torch_profile);
+        if let Some(block) = install_compat_blocks(&request, &profile).into_iter().next() {
+            return Err(block.to_string());
         }
-
-        let indent = without_comment
-            .chars()
-            .take_while(|c| c.is_whitespace())
-            .count();
-        let trimmed = without_comment.trim();
-
-        if trimmed == "comfyui:" {
-            in_comfyui = true;
-            continue;
+    }
+    if request.triton_version.is_some() {
+        let triton_version = normalize_triton_version(request.triton_version.clone())?;
+        state
+            .context
+            .config
+            .update_settings(|settings| settings.comfyui_triton_version = triton_version)
+            .map_err(|err| err.to_string())?;
+    }
+    {
+        let mut active = state
+            .install_cancel
+            .lock()
+            .map_err(|_| "install state lock poisoned".to_string())?;
+        if active.is_some() {
+            return Err("ComfyUI installation is already active.".to_string());
         }
+        *active = Some(CancellationToken::new());
+    }
 
-        if in_comfyui {
-            if let Some(raw) = trimmed.strip_prefix("base_path:") {
-                let scalar = parse_yaml_scalar(raw);
-                if !scalar.trim().is_empty() {
-                    let parsed = PathBuf::from(scalar.trim());
-                    let resolved = if parsed.is_absolute() {
-                        parsed
-                    } else {
-                        comfy_root.join(parsed)
-                    };
-                    base_path = Some(normalize_canonical_path(&resolved));
-                }
-                continue;
-            }
+    let cancel = state
+        .install_cancel
+        .lock()
+        .map_err(|_| "install state lock poisoned".to_string())?
+        .as_ref()
+        .cloned()
+        .ok_or_else(|| "Failed to initialize install cancellation token.".to_string())?;
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
 
-            if let Some(raw) = trimmed.strip_prefix("is_default:") {
-                let scalar = parse_yaml_scalar(raw);
-                if let Some(parsed) = parse_yaml_bool(&scalar) {
-                    is_default = parsed;
+    let app_for_task = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = run_comfyui_install(&app_for_task, &request, &shared_runtime_root, &cancel);
+        let mut hook_env = vec![(
+            "ARCTIC_STATUS",
+            if result.is_ok() { "success" } else { "failed" }.to_string(),
+        )];
+        if let Ok(comfy_root) = &result {
+            hook_env.push((
+                "ARCTIC_COMFYUI_ROOT",
+                comfy_root.to_string_lossy().to_string(),
+            ));
+        }
+        match result {
+            Ok(comfy_root) => {
+                let mut completion = read_install_completion(&comfy_root)
+                    .unwrap_or_else(|| InstallCompletion::new(&comfy_root, &request));
+                let install_dir = completion.install_dir.clone();
+                let managed = app_for_task.state::<AppState>();
+                if let Err(err) = register_install_completion(&managed, &mut completion) {
+                    log::warn!("Failed to register completed ComfyUI install: {err}");
                 }
-                continue;
+                let _ = app_for_task.emit(
+                    "comfyui-install-progress",
+                    DownloadProgressEvent {
+                        kind: "comfyui_install".to_string(),
+                        phase: "finished".to_string(),
+                        artifact: Some(install_dir.to_string_lossy().to_string()),
+                        index: None,
+                        total: None,
+                        received: None,
+                        size: None,
+                        folder: Some(comfy_root.to_string_lossy().to_string()),
+                        message: Some(format!(
+                            "ComfyUI installation completed. Root set to {}",
+                            comfy_root.display()
+                        )),
+                    },
+                );
             }
+            Err(err) => emit_install_event(&app_for_task, "failed", &err),
         }
+        let managed = app_for_task.state::<AppState>();
+        if let Ok(mut active) = managed.install_cancel.lock() {
+            *active = None;
+        };
+        let _ = run_user_hook(&managed, HookEvent::PostInstall, hook_env).await;
+    });
 
-        if indent == 0 && trimmed.ends_with(':') {
-            in_comfyui = false;
-            continue;
-        }
+    Ok(())
+}
 
-        if !in_comfyui {
-            continue;
+/// Restarts an interrupted install with its original options, skipping the steps that
+/// finished before it stopped.
+#[tauri::command]
+async fn resume_comfyui_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    install_dir: String,
+) -> Result<(), String> {
+    let install_dir = normalize_path(install_dir.trim())?;
+    let install_state = read_install_state(&install_dir)
+        .filter(|install_state| install_state.status == "in_progress")
+        .ok_or_else(|| format!("No interrupted install found in {}.", install_dir.display()))?;
+    let mut request = read_install_request(&install_dir).ok_or_else(|| {
+        "This install stopped before its options were saved; start it again from Install."
+            .to_string()
+    })?;
+    request.install_root = install_dir.to_string_lossy().to_string();
+    request.force_fresh = false;
+    request.resume_from = Some(install_state.step);
+    start_comfyui_install(app, state, request).await
+}
+
+/// Runs a full install of `request` into a staging folder under `cache_dir` with uv
+/// caching into the folder, then packs everything an air-gapped machine needs.
+#[tauri::command]
+async fn build_offline_install_cache(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: ComfyInstallRequest,
+    cache_dir: String,
+) -> Result<(), String> {
+    let cache_dir = normalize_path(cache_dir.trim())?;
+    if request.include_trellis2 {
+        return Err(
+            "TRELLIS2 downloads models during install and cannot be installed offline.".to_string(),
+        );
+    }
+    std::fs::create_dir_all(&cache_dir)
+        .map_err(|err| format!("Failed to create {}: {err}", cache_dir.display()))?;
+
+    let cancel = {
+        let mut active = state
+            .install_cancel
+            .lock()
+            .map_err(|_| "install state lock poisoned".to_string())?;
+        if active.is_some() {
+            return Err("ComfyUI installation is already active.".to_string());
         }
+        let token = CancellationToken::new();
+        *active = Some(token.clone());
+        token
+    };
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let torch_profile = request
+        .torch_profile
+        .clone()
+        .unwrap_or_else(|| get_comfyui_install_recommendation().torch_profile);
+    let staging = cache_dir.join("staging");
+    let staging_request = ComfyInstallRequest {
+        install_root: staging.join("ComfyUI").to_string_lossy().to_string(),
+        extra_model_root: None,
+        extra_model_use_default: false,
+        torch_profile: Some(torch_profile.clone()),
+        force_fresh: false,
+        output_dir: None,
+        input_dir: None,
+        user_dir: None,
+        offline_cache: None,
+        container_engine: None,
+        ..request
+    };
 
-        if let Some(raw) = trimmed.strip_prefix("base_path:") {
-            let scalar = parse_yaml_scalar(raw);
-            if scalar.trim().is_empty() {
-                continue;
+    let app_for_task = app.clone();
+    tauri::async_runtime::spawn(async move {
+        emit_install_event(
+            &app_for_task,
+            "step",
+            &format!(
+                "Building offline install cache in {}...",
+                cache_dir.display()
+            ),
+        );
+        let cache = OfflineCache {
+            dir: cache_dir.clone(),
+            fill: true,
+        };
+        let result = with_offline_cache(cache, || {
+            run_comfyui_install(
+                &app_for_task,
+                &staging_request,
+                &shared_runtime_root,
+                &cancel,
+            )
+        })
+        .and_then(|comfy_root| {
+            emit_install_event(&app_for_task, "step", "Packing offline install cache...");
+            export_offline_cache(
+                &cache_dir,
+                &comfy_root,
+                &shared_runtime_root,
+                &torch_profile,
+            )
+        });
+        match result {
+            Ok(manifest) => {
+                // Staging is kept on failure so a retry resumes instead of starting over.
+                let _ = std::fs::remove_dir_all(&staging);
+                let _ = app_for_task.emit(
+                    "comfyui-install-progress",
+                    DownloadProgressEvent {
+                        kind: "comfyui_install".to_string(),
+                        phase: "finished".to_string(),
+                        artifact: None,
+                        index: None,
+                        total: None,
+                        received: None,
+                        size: None,
+                        folder: None,
+                        message: Some(format!(
+                            "Offline install cache ready in {} ({} custom node(s), {}).",
+                            cache_dir.display(),
+                            manifest.custom_nodes.len(),
+                            manifest.torch_profile
+                        )),
+                    },
+                );
             }
-            let parsed = PathBuf::from(scalar.trim());
-            let resolved = if parsed.is_absolute() {
-                parsed
-            } else {
-                comfy_root.join(parsed)
-            };
-            base_path = Some(normalize_canonical_path(&resolved));
-            continue;
+            Err(err) => emit_install_event(
+                &app_for_task,
+                "failed",
+                &format!("Offline cache build failed: {err}"),
+            ),
         }
+        let managed = app_for_task.state::<AppState>();
+        if let Ok(mut active) = managed.install_cancel.lock() {
+            *active = None;
+        };
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+fn cancel_comfyui_install(state: State<'_, AppState>) -> Result<bool, String> {
+    let mut active = state
+        .install_cancel
+        .lock()
+        .map_err(|_| "install state lock poisoned".to_string())?;
+    if let Some(token) = active.as_ref() {
+        token.cancel();
+        *active = None;
+        Ok(true)
+    } else {
+        Ok(false)
     }
+}
 
-    base_path.map(|base| ComfyExtraModelConfig {
-        base_path: base,
-        is_default,
-    })
+#[tauri::command]
+fn get_catalog(state: State<'_, AppState>) -> ModelCatalog {
+    let mut catalog = state.context.catalog.catalog_snapshot();
+    catalog.mark_favorite_loras(&state.context.config.settings().favorite_loras);
+    catalog
 }
 
-fn comfyui_instance_name_from_path(path: &Path) -> String {
-    path.file_name()
-        .and_then(|value| value.to_str())
-        .map(|value| value.trim())
-        .filter(|value| !value.is_empty())
-        .unwrap_or("ComfyUI")
-        .to_string()
+#[tauri::command]
+fn get_favorites(state: State<'_, AppState>) -> Vec<String> {
+    state.context.config.settings().favorite_loras
 }
 
-fn resolve_comfyui_instance_name(context: &AppContext, comfyui_root: Option<String>) -> String {
-    resolve_root_path(context, comfyui_root)
-        .ok()
-        .as_deref()
-        .map(comfyui_instance_name_from_path)
-        .unwrap_or_else(|| "ComfyUI".to_string())
+#[tauri::command]
+fn get_last_selection(state: State<'_, AppState>) -> LastSelection {
+    state.context.config.settings().last_selection
 }
 
-fn parse_ram_tier(value: &str) -> Option<RamTier> {
-    RamTier::from_identifier(value)
+#[tauri::command]
+fn set_last_selection(state: State<'_, AppState>, selection: LastSelection) -> Result<(), String> {
+    let clean = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let selection = LastSelection {
+        family: clean(selection.family),
+        model_id: clean(selection.model_id),
+        variant_id: clean(selection.variant_id),
+        vram_tier: clean(selection.vram_tier),
+        ram_tier: clean(selection.ram_tier),
+    };
+    if state.context.config.settings().last_selection == selection {
+        return Ok(());
+    }
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.last_selection = selection)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
 }
 
-fn is_video_url(url: &str) -> bool {
-    let lower = url.to_ascii_lowercase();
-    lower.ends_with(".mp4")
-        || lower.ends_with(".webm")
-        || lower.ends_with(".mov")
-        || lower.contains(".mp4?")
-        || lower.contains(".webm?")
-        || lower.contains(".mov?")
+#[tauri::command]
+fn toggle_lora_favorite(
+    state: State<'_, AppState>,
+    lora_id: String,
+) -> Result<Vec<String>, String> {
+    let lora_id = lora_id.trim().to_string();
+    if state.context.catalog.find_lora(&lora_id).is_none() {
+        return Err(format!("Unknown LoRA id: {lora_id}"));
+    }
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            if let Some(pos) = settings.favorite_loras.iter().position(|id| id == &lora_id) {
+                settings.favorite_loras.remove(pos);
+            } else {
+                settings.favorite_loras.push(lora_id.clone());
+            }
+        })
+        .map(|settings| settings.favorite_loras)
+        .map_err(|err| err.to_string())
 }
 
-fn strip_html_tags(input: &str) -> String {
-    let mut raw = String::with_capacity(input.len());
-    let mut in_tag = false;
-    for ch in input.chars() {
-        match ch {
-            '<' => in_tag = true,
-            '>' => {
-                if in_tag {
-                    in_tag = false;
-                    raw.push(' ');
-                }
-            }
-            _ if !in_tag => raw.push(ch),
-            _ => {}
+#[derive(Debug, Serialize)]
+struct ComfyDataDirsResponse {
+    output_dir: Option<String>,
+    input_dir: Option<String>,
+    user_dir: Option<String>,
+}
+
+impl From<ComfyDataDirs> for ComfyDataDirsResponse {
+    fn from(dirs: ComfyDataDirs) -> Self {
+        let show = |path: Option<PathBuf>| path.map(|p| p.to_string_lossy().to_string());
+        Self {
+            output_dir: show(dirs.output_dir),
+            input_dir: show(dirs.input_dir),
+            user_dir: show(dirs.user_dir),
         }
     }
-    raw.replace("&nbsp;", " ")
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'")
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
 }
 
-fn detect_existing_comfyui_root(path: &Path) -> Option<PathBuf> {
-    if path.join("main.py").is_file() {
-        return Some(path.to_path_buf());
+#[tauri::command]
+fn get_comfyui_data_dirs(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<ComfyDataDirsResponse, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    Ok(read_comfy_data_dirs(&root).into())
+}
+
+#[tauri::command]
+fn set_comfyui_data_dirs(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    output_dir: Option<String>,
+    input_dir: Option<String>,
+    user_dir: Option<String>,
+) -> Result<ComfyDataDirsResponse, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let dirs = ComfyDataDirs::from_raw(
+        output_dir.as_deref(),
+        input_dir.as_deref(),
+        user_dir.as_deref(),
+    )?;
+    write_comfy_data_dirs(&state.context.config.settings(), &root, &dirs)?;
+    Ok(dirs.into())
+}
+
+#[tauri::command]
+fn validate_catalog(
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<Vec<CatalogIssue>, String> {
+    let path = path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+    state
+        .context
+        .catalog
+        .validate_catalog(path.as_deref())
+        .map_err(|err| format!("{err:#}"))
+}
+
+#[tauri::command]
+fn get_catalog_changes(state: State<'_, AppState>) -> Option<CatalogChanges> {
+    state.context.catalog.catalog_changes()
+}
+
+#[derive(Debug, Serialize)]
+struct CatalogSourcesResponse {
+    sources: Vec<CatalogSource>,
+    conflicts: Vec<CatalogConflict>,
+}
+
+fn catalog_sources_response(state: &AppState) -> CatalogSourcesResponse {
+    CatalogSourcesResponse {
+        sources: state.context.config.settings().catalog_sources,
+        conflicts: state.context.catalog.catalog_conflicts(),
     }
+}
 
-    let mut candidates: Vec<PathBuf> = Vec::new();
-    let entries = std::fs::read_dir(path).ok()?;
-    for entry in entries.flatten() {
-        let child = entry.path();
-        if !child.is_dir() {
-            continue;
+#[tauri::command]
+fn get_catalog_sources(state: State<'_, AppState>) -> CatalogSourcesResponse {
+    catalog_sources_response(&state)
+}
+
+#[tauri::command]
+async fn set_catalog_sources(
+    state: State<'_, AppState>,
+    sources: Vec<CatalogSource>,
+) -> Result<CatalogSourcesResponse, String> {
+    let mut names = std::collections::HashSet::new();
+    let mut normalized = Vec::with_capacity(sources.len());
+    for mut source in sources {
+        source.name = source.name.trim().to_string();
+        source.location = source.location.trim().to_string();
+        if source.name.is_empty() || source.location.is_empty() {
+            return Err("Every catalog source needs a name and a URL or file path.".to_string());
         }
-        let name = child
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or_default()
-            .to_ascii_lowercase();
-        if !name.starts_with("comfyui") {
-            continue;
+        if source.name.eq_ignore_ascii_case(OFFICIAL_SOURCE_NAME) {
+            return Err(format!(
+                "'{OFFICIAL_SOURCE_NAME}' is reserved for the built-in catalog."
+            ));
         }
-        if child.join("main.py").is_file() {
-            candidates.push(child);
+        if !names.insert(source.name.to_ascii_lowercase()) {
+            return Err(format!("Duplicate catalog source name: {}", source.name));
+        }
+        if !source.is_remote() && !Path::new(&source.location).is_file() {
+            return Err(format!("Catalog file not found: {}", source.location));
         }
+        normalized.push(source);
     }
 
-    if candidates.is_empty() {
-        return None;
-    }
-    candidates.sort_by(|a, b| {
-        let an = a.file_name().and_then(|n| n.to_str()).unwrap_or_default();
-        let bn = b.file_name().and_then(|n| n.to_str()).unwrap_or_default();
-        an.cmp(bn)
-    });
-    candidates.into_iter().next()
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.catalog_sources = normalized)
+        .map_err(|err| err.to_string())?;
+    state.context.catalog.refresh_catalog_sources().await;
+    state.context.catalog.rebuild();
+    Ok(catalog_sources_response(&state))
 }
 
 #[tauri::command]
-fn inspect_comfyui_path(path: String) -> Result<ComfyPathInspection, String> {
-    let selected = path.trim();
-    if selected.is_empty() {
-        return Err("Folder is empty.".to_string());
-    }
-    let selected_path = PathBuf::from(selected);
-    if !selected_path.exists() || !selected_path.is_dir() {
-        return Err("Folder does not exist.".to_string());
-    }
-    let normalized = std::fs::canonicalize(&selected_path).unwrap_or(selected_path.clone());
-    let normalized = normalize_canonical_path(&normalized).to_path_buf();
-    let detected_root = detect_existing_comfyui_root(&normalized).map(|p| {
-        normalize_canonical_path(&p)
-            .to_string_lossy()
-            .to_string()
-    });
-    Ok(ComfyPathInspection {
-        selected: normalize_canonical_path(&normalized)
-            .to_string_lossy()
-            .to_string(),
-        detected_root,
-    })
+fn get_settings(state: State<'_, AppState>) -> AppSettings {
+    state.context.config.settings()
 }
 
 #[tauri::command]
-fn list_comfyui_installations(
+fn set_comfyui_root(
     state: State<'_, AppState>,
-    base_path: Option<String>,
-) -> Result<Vec<ComfyInstallationEntry>, String> {
-    let candidate = if let Some(raw) = base_path {
-        let trimmed = raw.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(PathBuf::from(trimmed))
-        }
+    comfyui_root: String,
+) -> Result<AppSettings, String> {
+    let trimmed = comfyui_root.trim();
+    let normalized = if trimmed.is_empty() {
+        None
     } else {
-        state.context.config.settings().comfyui_install_base
-    };
-
-    let Some(base) = candidate else {
-        return Ok(Vec::new());
+        let mut path = std::path::PathBuf::from(trimmed);
+        if !path.is_absolute() {
+            if let Ok(cwd) = std::env::current_dir() {
+                path = cwd.join(path);
+            }
+        }
+        Some(normalize_canonical_path(
+            &std::fs::canonicalize(&path).unwrap_or(path),
+        ))
     };
+    let detected_attention = normalized
+        .as_ref()
+        .map(|root| detect_launch_attention_backend_for_root(root).unwrap_or_else(|| "none".to_string()));
+    let detected_profile = normalized
+        .as_ref()
+        .and_then(|root| detect_torch_profile_for_root(root));
 
-    let base = normalize_canonical_path(&base).to_path_buf();
-    if !base.exists() || !base.is_dir() {
-        return Ok(Vec::new());
+    // The global values below stop describing the previous root, so give it its own copy.
+    let settings = state.context.config.settings();
+    if let Some(previous) = settings.comfyui_root.as_deref() {
+        install_config(&settings, previous);
     }
 
-    let base = std::fs::canonicalize(&base).unwrap_or(base);
-    let mut entries: Vec<ComfyInstallationEntry> = Vec::new();
-
-    if base.join("main.py").is_file() {
-        let name = base
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("ComfyUI")
-            .to_string();
-        let root = normalize_canonical_path(&base)
-            .to_string_lossy()
-            .to_string();
-        entries.push(ComfyInstallationEntry { name, root });
-    }
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings.comfyui_root = normalized.clone();
+            settings.comfyui_attention_backend = detected_attention
+                .clone()
+                .or_else(|| Some("none".to_string()));
+            settings.comfyui_torch_profile = detected_profile.clone();
+        })
+        .map_err(|err| err.to_string())
+}
 
-    if let Ok(read_dir) = std::fs::read_dir(&base) {
-        for entry in read_dir.flatten() {
-            let path = entry.path();
-            if !path.is_dir() {
-                continue;
-            }
-            let name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or_default()
-                .to_string();
-            if !name.to_ascii_lowercase().starts_with("comfyui") {
-                continue;
-            }
-            if !path.join("main.py").is_file() {
-                continue;
+#[tauri::command]
+fn set_comfyui_install_base(
+    state: State<'_, AppState>,
+    comfyui_install_base: String,
+) -> Result<AppSettings, String> {
+    let trimmed = comfyui_install_base.trim();
+    let normalized = if trimmed.is_empty() {
+        None
+    } else {
+        let mut path = std::path::PathBuf::from(trimmed);
+        if !path.is_absolute() {
+            if let Ok(cwd) = std::env::current_dir() {
+                path = cwd.join(path);
             }
-            let root = normalize_canonical_path(&path)
-                .to_string_lossy()
-                .to_string();
-            entries.push(ComfyInstallationEntry { name, root });
         }
-    }
-
-    entries.sort_by(|a, b| {
-        a.name
-            .to_ascii_lowercase()
-            .cmp(&b.name.to_ascii_lowercase())
-    });
-    entries.dedup_by(|a, b| a.root.eq_ignore_ascii_case(&b.root));
-    Ok(entries)
+        let resolved = normalize_canonical_path(&std::fs::canonicalize(&path).unwrap_or(path));
+        if is_forbidden_install_path(&resolved) {
+            return Err(
+                "Install base folder is blocked. Avoid system directories."
+                    .to_string(),
+            );
+        }
+        Some(resolved)
+    };
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings.comfyui_install_base = normalized.clone();
+        })
+        .map_err(|err| err.to_string())
 }
 
-fn spawn_progress_emitter(
-    app: AppHandle,
-    kind: String,
-    rx: std::sync::mpsc::Receiver<DownloadSignal>,
-) {
-    std::thread::spawn(move || {
-        while let Ok(signal) = rx.recv() {
-            let payload = match signal {
-                DownloadSignal::Started {
-                    artifact,
-                    index,
-                    total,
-                    size,
-                } => DownloadProgressEvent {
-                    kind: kind.clone(),
-                    phase: "started".to_string(),
-                    artifact: Some(artifact),
-                    index: Some(index + 1),
-                    total: Some(total),
-                    received: None,
-                    size,
-                    folder: None,
-                    message: None,
-                },
-                DownloadSignal::Progress {
-                    artifact,
-                    index,
-                    received,
-                    size,
-                } => DownloadProgressEvent {
-                    kind: kind.clone(),
-                    phase: "progress".to_string(),
-                    artifact: Some(artifact),
-                    index: Some(index + 1),
-                    total: None,
-                    received: Some(received),
-                    size,
-                    folder: None,
-                    message: None,
-                },
-                DownloadSignal::Finished {
-                    artifact,
-                    index,
-                    size,
-                    folder,
-                } => DownloadProgressEvent {
-                    kind: kind.clone(),
-                    phase: "finished".to_string(),
-                    artifact: Some(artifact),
-                    index: Some(index + 1),
-                    total: None,
-                    received: None,
-                    size,
-                    folder,
-                    message: None,
-                },
-                DownloadSignal::Failed { artifact, error } => DownloadProgressEvent {
-                    kind: kind.clone(),
-                    phase: "failed".to_string(),
-                    artifact: Some(artifact),
-                    index: None,
-                    total: None,
-                    received: None,
-                    size: None,
-                    folder: None,
-                    message: Some(error),
-                },
-            };
-            let _ = app.emit("download-progress", payload);
-        }
-    });
+#[tauri::command]
+fn get_comfyui_extra_model_config(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<ComfyExtraModelConfigResponse, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let config = comfy_extra_model_config(&root);
+    Ok(match config {
+        Some(cfg) => ComfyExtraModelConfigResponse {
+            configured: true,
+            base_path: Some(cfg.base_path.to_string_lossy().to_string()),
+            use_as_default: cfg.is_default,
+        },
+        None => ComfyExtraModelConfigResponse {
+            configured: false,
+            base_path: None,
+            use_as_default: false,
+        },
+    })
 }
 
 #[tauri::command]
-fn open_folder(path: String) -> Result<String, String> {
-    let trimmed = path.trim();
-    if trimmed.is_empty() {
-        return Err("Folder path is empty.".to_string());
-    }
-    let mut target = std::path::PathBuf::from(trimmed);
-    if !target.is_absolute() {
-        if let Ok(cwd) = std::env::current_dir() {
-            target = cwd.join(target);
+fn set_comfyui_extra_model_config(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    extra_model_root: Option<String>,
+    use_as_default: bool,
+) -> Result<AppSettings, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let normalized_extra = normalize_optional_path(extra_model_root.as_deref())?;
+    let yaml_path = root.join("extra_model_paths.yaml");
+    let example_path = root.join("extra_model_paths.yaml.example");
+
+    if let Some(extra_root) = normalized_extra.as_ref() {
+        write_extra_model_paths_yaml(&root, extra_root, use_as_default)?;
+    } else {
+        if yaml_path.exists() {
+            let _ = std::fs::remove_file(&yaml_path);
         }
-    }
-    if target.is_file() {
-        if let Some(parent) = target.parent() {
-            target = parent.to_path_buf();
+        if !example_path.exists() {
+            let _ = std::fs::write(
+                &example_path,
+                "# Rename this to extra_model_paths.yaml and ComfyUI will load it\n",
+            );
         }
     }
-    if let Ok(canon) = std::fs::canonicalize(&target) {
-        target = canon;
-    }
-    if !target.exists() {
-        return Err("Folder does not exist.".to_string());
-    }
 
-    open::that(target).map_err(|err| format!("Failed to open folder: {err}"))?;
-    Ok(path)
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings.shared_models_root = normalized_extra.clone();
+            settings.shared_models_use_default =
+                normalized_extra.is_some() && use_as_default;
+        })
+        .map_err(|err| err.to_string())
 }
 
 #[tauri::command]
-fn open_external_url(url: String) -> Result<(), String> {
-    let trimmed = url.trim();
-    if !(trimmed.starts_with("https://") || trimmed.starts_with("http://")) {
-        return Err("Only http/https links are allowed.".to_string());
+fn save_civitai_token(state: State<'_, AppState>, token: String) -> Result<AppSettings, String> {
+    if !state.context.config.settings_unlocked() {
+        return Err("Settings are encrypted. Unlock them with your passphrase first.".to_string());
     }
+    let trimmed = token.trim().to_string();
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings.civitai_token = if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed)
+            };
+        })
+        .map_err(|err| err.to_string())
+}
 
-    open::that(trimmed).map_err(|err| format!("Failed to open link: {err}"))?;
-    Ok(())
+#[tauri::command]
+fn set_civitai_examples_enabled(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<AppSettings, String> {
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.civitai_download_examples = enabled)
+        .map_err(|err| err.to_string())
 }
 
-fn start_comfyui_root_impl(
-    app: &AppHandle,
-    state: &AppState,
-    comfyui_root: Option<String>,
-) -> Result<(), String> {
-    if comfyui_runtime_running(state) {
-        return Ok(());
+#[derive(Debug, Serialize)]
+struct SettingsEncryptionStatus {
+    enabled: bool,
+    unlocked: bool,
+}
+
+#[tauri::command]
+fn get_settings_encryption_status(state: State<'_, AppState>) -> SettingsEncryptionStatus {
+    SettingsEncryptionStatus {
+        enabled: state.context.config.settings_encrypted(),
+        unlocked: state.context.config.settings_unlocked(),
     }
+}
 
-    let root = if let Some(raw) = comfyui_root {
-        let trimmed = raw.trim();
-        if trimmed.is_empty() {
-            state
-                .context
-                .config
-                .settings()
-                .comfyui_root
-                .ok_or_else(|| "ComfyUI root is not configured.".to_string())?
-        } else {
-            PathBuf::from(trimmed)
-        }
+#[tauri::command]
+fn unlock_settings(state: State<'_, AppState>, passphrase: String) -> Result<AppSettings, String> {
+    state
+        .context
+        .config
+        .unlock_settings(&passphrase)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn set_settings_encryption(
+    state: State<'_, AppState>,
+    enabled: bool,
+    passphrase: String,
+) -> Result<AppSettings, String> {
+    let config = &state.context.config;
+    let result = if enabled {
+        config.enable_settings_encryption(&passphrase)
     } else {
-        state
-            .context
-            .config
-            .settings()
-            .comfyui_root
-            .ok_or_else(|| "ComfyUI root is not configured.".to_string())?
+        config.disable_settings_encryption(&passphrase)
     };
+    result.map_err(|err| err.to_string())
+}
 
-    let root = normalize_canonical_path(&std::fs::canonicalize(&root).unwrap_or(root));
-    let main_py = root.join("main.py");
-    if !main_py.exists() {
-        return Err(format!("ComfyUI main.py not found in {}", root.display()));
-    }
+#[tauri::command]
+async fn check_updates_now(state: State<'_, AppState>) -> Result<UpdateCheckResponse, String> {
+    let updater = state.context.updater.clone();
+    let result = updater.check_for_update().await;
 
-    let py_exe = resolve_start_python_exe(app, state, &root)?;
-    let settings = state.context.config.settings();
+    match result {
+        Ok(Ok(Some(update))) => Ok(UpdateCheckResponse {
+            available: true,
+            version: Some(update.version.to_string()),
+            notes: update.notes,
+        }),
+        Ok(Ok(None)) => Ok(UpdateCheckResponse {
+            available: false,
+            version: None,
+            notes: None,
+        }),
+        Ok(Err(err)) => Err(format!("Update check failed: {err:#}")),
+        Err(join_err) => Err(format!("Update task failed: {join_err}")),
+    }
+}
 
-    let mut cmd = std::process::Command::new(py_exe);
-    if !nerdstats_enabled() {
-        apply_background_command_flags(&mut cmd);
+#[tauri::command]
+async fn auto_update_startup(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UpdateCheckResponse, String> {
+    if !auto_update_enabled() {
+        return Ok(UpdateCheckResponse {
+            available: false,
+            version: None,
+            notes: Some("Auto update disabled by environment.".to_string()),
+        });
     }
-    apply_cuda_runtime_env_for_root(&mut cmd, &root);
-    configure_python_runtime_env_for_root(&mut cmd, &root);
 
-    let configured_root_matches = settings
-        .comfyui_root
-        .as_ref()
-        .map(|configured_root| {
-            normalize_canonical_path(
-                &std::fs::canonicalize(configured_root)
-                    .unwrap_or_else(|_| configured_root.clone()),
-            ) == root
-        })
-        .unwrap_or(false);
+    let updater = state.context.updater.clone();
 
-    let effective_attention = {
-        let configured = if configured_root_matches {
-            settings.comfyui_attention_backend.clone()
-        } else {
-            None
-        };
-        match configured.as_deref() {
-            Some("none") => None,
-            Some("sage3") => {
-                if python_module_importable(&root, "sageattn3") {
-                    Some("sage3".to_string())
-                } else {
-                    return Err(
-                        "SageAttention3 is selected but not importable in this install. Re-apply SageAttention3 for this ComfyUI root."
-                            .to_string(),
-                    );
-                }
-            }
-            Some("sage") => {
-                if python_module_importable(&root, "sageattention")
-                    || python_module_importable(&root, "sageattn3")
-                {
-                    Some("sage".to_string())
-                } else {
-                    return Err(
-                        "SageAttention is selected but not importable in this install. Re-apply SageAttention for this ComfyUI root."
-                            .to_string(),
-                    );
-                }
-            }
-            Some("flash") => {
-                if python_module_importable(&root, "flash_attn") {
-                    Some("flash".to_string())
-                } else {
-                    return Err(
-                        "FlashAttention is selected but not importable in this install. Re-apply FlashAttention for this ComfyUI root."
-                            .to_string(),
-                    );
-                }
-            }
-            Some("nunchaku") => {
-                if nunchaku_backend_present(&root) {
-                    Some("nunchaku".to_string())
-                } else {
-                    return Err(
-                        "Nunchaku is selected but backend is not installed correctly for this ComfyUI root. Re-apply Nunchaku."
-                            .to_string(),
-                    );
-                }
-            }
-            _ => detect_launch_attention_backend_for_root(&root),
+    let checked = updater.check_for_update().await;
+
+    let Some(update) = (match checked {
+        Ok(Ok(Some(update))) => Some(update),
+        Ok(Ok(None)) => {
+            return Ok(UpdateCheckResponse {
+                available: false,
+                version: None,
+                notes: None,
+            });
         }
+        Ok(Err(err)) => return Err(format!("Update check failed: {err:#}")),
+        Err(join_err) => return Err(format!("Update task failed: {join_err}")),
+    }) else {
+        return Ok(UpdateCheckResponse {
+            available: false,
+            version: None,
+            notes: None,
+        });
     };
-    cmd.arg("-W").arg("ignore::FutureWarning").arg(main_py);
-    let launch_args = comfyui_launch_args(
-        settings.comfyui_pinned_memory_enabled,
-        effective_attention.as_deref(),
-    );
-    emit_comfyui_runtime_event(
-        app,
-        "launch_args",
-        format!(
-            "Launching with attention backend: {}",
-            effective_attention.as_deref().unwrap_or("none")
-        ),
+
+    let _ = app.emit(
+        "update-state",
+        DownloadProgressEvent {
+            kind: "update".to_string(),
+            phase: "available".to_string(),
+            artifact: None,
+            index: None,
+            total: None,
+            received: None,
+            size: None,
+            folder: None,
+            message: Some(format!("Update v{} available; installing.", update.version)),
+        },
     );
-    cmd.args(launch_args);
-    cmd.current_dir(root);
-    if nerdstats_enabled() {
-        cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
-    }
 
-    let child = cmd
-        .spawn()
-        .map_err(|err| format!("Failed to start ComfyUI: {err}"))?;
-    let mut guard = state
-        .comfyui_process
-        .lock()
-        .map_err(|_| "comfyui process lock poisoned".to_string())?;
-    *guard = Some(child);
-    Ok(())
-}
+    let install = updater.download_and_install(update.clone()).await;
 
-fn wait_for_comfyui_start(state: &AppState, timeout: Duration) -> Result<(), String> {
-    let started_at = Instant::now();
-    loop {
-        if comfyui_external_running(state) {
-            return Ok(());
+    match install {
+        Ok(Ok(applied)) => {
+            let _ = app.emit(
+                "update-state",
+                DownloadProgressEvent {
+                    kind: "update".to_string(),
+                    phase: "restarting".to_string(),
+                    artifact: None,
+                    index: None,
+                    total: None,
+                    received: None,
+                    size: None,
+                    folder: None,
+                    message: Some(format!(
+                        "Update v{} installed; restarting application.",
+                        applied.version
+                    )),
+                },
+            );
+            app.exit(0);
+            Ok(UpdateCheckResponse {
+                available: true,
+                version: Some(applied.version.to_string()),
+                notes: Some("Standalone update apply launched.".to_string()),
+            })
         }
+        Ok(Err(err)) => Err(format!("Update install failed: {err:#}")),
+        Err(join_err) => Err(format!("Update install task failed: {join_err}")),
+    }
+}
 
-        {
-            let mut guard = state
-                .comfyui_process
-                .lock()
-                .map_err(|_| "comfyui process lock poisoned".to_string())?;
-            if let Some(child) = guard.as_mut() {
-                match child.try_wait() {
-                    Ok(Some(status)) => {
-                        *guard = None;
-                        return Err(format!(
-                            "ComfyUI process exited during startup with status {status}."
-                        ));
-                    }
-                    Ok(None) => {}
-                    Err(err) => {
-                        *guard = None;
-                        return Err(format!("Failed to monitor ComfyUI startup: {err}"));
-                    }
-                }
-            }
+/// Runs a configured hook off the async runtime. Only a failing pre-download hook is
+/// reported as an error; post hooks are logged and otherwise ignored.
+async fn run_user_hook(
+    state: &AppState,
+    event: HookEvent,
+    env: Vec<(&'static str, String)>,
+) -> Result<(), String> {
+    let hooks = state.context.config.settings().hooks;
+    if hooks.command_for(event).is_none() {
+        return Ok(());
+    }
+    let outcome = tauri::async_runtime::spawn_blocking(move || run_hook(&hooks, event, &env))
+        .await
+        .map_err(|err| format!("{} hook task failed: {err}", event.identifier()))?;
+    match outcome {
+        Ok(Some(outcome)) if !outcome.success() && event == HookEvent::PreDownload => {
+            Err(if outcome.timed_out {
+                "pre_download hook timed out; download skipped.".to_string()
+            } else {
+                format!(
+                    "pre_download hook exited with status {}; download skipped.",
+                    outcome
+                        .exit_code
+                        .map(|code| code.to_string())
+                        .unwrap_or_else(|| "unknown".to_string())
+                )
+            })
         }
-
-        if started_at.elapsed() > timeout {
-            if comfyui_process_running(state) || comfyui_external_running(state) {
-                return Ok(());
-            }
-            return Err("ComfyUI did not become ready on 127.0.0.1:8188 in time.".to_string());
+        Err(err) if event == HookEvent::PreDownload => Err(format!("{err:#}")),
+        Err(err) => {
+            log::warn!("{err:#}");
+            Ok(())
         }
-        std::thread::sleep(Duration::from_millis(220));
+        _ => Ok(()),
     }
 }
 
-fn spawn_comfyui_start_monitor(app: &AppHandle, instance_name: String) {
-    let app_handle = app.clone();
-    std::thread::spawn(move || {
-        let state = app_handle.state::<AppState>();
-        match wait_for_comfyui_start(&state, Duration::from_secs(45)) {
-            Ok(()) => {
-                update_tray_comfy_status(&app_handle, true);
-                emit_comfyui_runtime_event(
-                    &app_handle,
-                    "started",
-                    format!("{instance_name} started."),
-                );
-                if let Err(err) = open::that("http://127.0.0.1:8188") {
-                    log::warn!("Failed to open ComfyUI in browser: {err}");
-                }
-            }
-            Err(err) => {
-                let running = comfyui_runtime_running(&state);
-                update_tray_comfy_status(&app_handle, running);
-                emit_comfyui_runtime_event(
-                    &app_handle,
-                    "start_failed",
-                    format!("{instance_name} start failed: {err}"),
-                );
-            }
-        }
-    });
+fn download_hook_status<T>(
+    result: &Result<anyhow::Result<T>, tokio::task::JoinError>,
+) -> &'static str {
+    match result {
+        Ok(Ok(_)) => "success",
+        Ok(Err(err)) if err.to_string().to_ascii_lowercase().contains("cancel") => "cancelled",
+        Err(join_err) if join_err.is_cancelled() => "cancelled",
+        _ => "failed",
+    }
 }
 
-fn start_comfyui_root_background(app: &AppHandle, comfyui_root: Option<String>) {
-    let app_handle = app.clone();
-    let instance_name = {
-        let state = app_handle.state::<AppState>();
-        resolve_comfyui_instance_name(&state.context, comfyui_root.clone())
+#[tauri::command]
+fn set_hook_settings(
+    state: State<'_, AppState>,
+    hooks: HookSettings,
+) -> Result<AppSettings, String> {
+    let clean = |value: Option<String>| {
+        value
+            .map(|cmd| cmd.trim().to_string())
+            .filter(|cmd| !cmd.is_empty())
     };
-    emit_comfyui_runtime_event(
-        &app_handle,
-        "starting",
-        format!("Starting {instance_name}..."),
-    );
-    update_tray_comfy_status(&app_handle, true);
-    let instance_name_for_task = instance_name.clone();
-    std::thread::spawn(move || {
-        let state = app_handle.state::<AppState>();
-        if let Err(err) = start_comfyui_root_impl(&app_handle, &state, comfyui_root) {
-            let running = comfyui_runtime_running(&state);
-            update_tray_comfy_status(&app_handle, running);
-            emit_comfyui_runtime_event(
-                &app_handle,
-                "start_failed",
-                format!("{instance_name_for_task} start failed: {err}"),
-            );
-            return;
-        }
-        spawn_comfyui_start_monitor(&app_handle, instance_name_for_task);
-    });
+    if hooks.timeout_secs == Some(0) {
+        return Err("Hook timeout must be at least one second.".to_string());
+    }
+    let hooks = HookSettings {
+        pre_download: clean(hooks.pre_download),
+        post_download: clean(hooks.post_download),
+        post_install: clean(hooks.post_install),
+        timeout_secs: hooks.timeout_secs,
+    };
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.hooks = hooks)
+        .map_err(|err| err.to_string())
 }
 
 #[tauri::command]
-fn start_comfyui_root(
-    app: AppHandle,
+fn set_retry_settings(
     state: State<'_, AppState>,
-    comfyui_root: Option<String>,
-) -> Result<(), String> {
-    if comfyui_runtime_running(&state) {
-        let instance_name = resolve_comfyui_instance_name(&state.context, comfyui_root.clone());
-        update_tray_comfy_status(&app, true);
-        emit_comfyui_runtime_event(
-            &app,
-            "started",
-            format!("{instance_name} is already running."),
-        );
-        return Ok(());
+    retry: RetrySettings,
+) -> Result<AppSettings, String> {
+    for (name, policy) in [
+        ("network", retry.network),
+        ("git", retry.git),
+        ("pip", retry.pip),
+    ] {
+        if !(1..=20).contains(&policy.attempts) {
+            return Err(format!("{name} retry attempts must be between 1 and 20."));
+        }
+        if policy.max_delay_secs > 3600 {
+            return Err(format!("{name} retry delay cannot exceed one hour."));
+        }
     }
-    start_comfyui_root_background(&app, comfyui_root);
-    Ok(())
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.retry = retry)
+        .map_err(|err| err.to_string())
 }
 
-fn comfyui_process_running(state: &AppState) -> bool {
-    let mut guard = match state.comfyui_process.lock() {
-        Ok(g) => g,
-        Err(_) => return false,
-    };
-    let Some(child) = guard.as_mut() else {
-        return false;
-    };
-    match child.try_wait() {
-        Ok(Some(_)) => {
-            *guard = None;
-            false
-        }
-        Ok(None) => true,
-        Err(_) => {
-            *guard = None;
-            false
-        }
+#[tauri::command]
+fn set_comfyui_auto_restart(
+    state: State<'_, AppState>,
+    auto_restart: AutoRestartSettings,
+) -> Result<AppSettings, String> {
+    if !(1..=20).contains(&auto_restart.policy.attempts) {
+        return Err("Restart attempts must be between 1 and 20.".to_string());
     }
+    if auto_restart.policy.max_delay_secs > 3600 {
+        return Err("Restart delay cannot exceed one hour.".to_string());
+    }
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.comfyui_auto_restart = auto_restart)
+        .map_err(|err| err.to_string())
 }
 
-fn comfyui_external_running(state: &AppState) -> bool {
-    let _ = state;
-    let addr = ("127.0.0.1", 8188)
-        .to_socket_addrs()
-        .ok()
-        .and_then(|mut iter| iter.next());
-    let Some(addr) = addr else {
-        return false;
+#[tauri::command]
+fn set_comfy_settings_template(
+    state: State<'_, AppState>,
+    template: ComfySettingsTemplate,
+) -> Result<AppSettings, String> {
+    let mut values = BTreeMap::new();
+    for (key, value) in template.values {
+        let key = key.trim().to_string();
+        if key.is_empty() {
+            return Err("ComfyUI setting ids cannot be empty.".to_string());
+        }
+        values.insert(key, value);
+    }
+    let template = ComfySettingsTemplate {
+        apply_after_install: template.apply_after_install,
+        values,
     };
-    TcpStream::connect_timeout(&addr, Duration::from_millis(180)).is_ok()
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.comfy_settings_template = template)
+        .map_err(|err| err.to_string())
 }
 
-fn comfyui_runtime_running(state: &AppState) -> bool {
-    comfyui_process_running(state) || comfyui_external_running(state)
+#[tauri::command]
+fn apply_comfy_settings_template(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    if !root.join("main.py").is_file() {
+        return Err(format!("{} is not a valid ComfyUI root.", root.display()));
+    }
+    let template = state.context.config.settings().comfy_settings_template;
+    if template.values.is_empty() {
+        return Err("The ComfyUI settings template is empty.".to_string());
+    }
+    let path = apply_comfy_settings_values(&root, &template.values)?;
+    log::info!(
+        "Applied {} ComfyUI setting(s) to {}",
+        template.values.len(),
+        path.display()
+    );
+    Ok(path.to_string_lossy().to_string())
 }
 
 #[derive(Debug, Serialize)]
-struct ComfyRuntimeStatus {
-    running: bool,
+struct IssuedApiToken {
+    #[serde(flatten)]
+    token: ApiToken,
+    /// Shown once; only its hash is stored.
+    secret: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
-struct ComfyRuntimeEvent {
-    phase: String,
-    message: String,
+#[tauri::command]
+fn create_api_token(
+    state: State<'_, AppState>,
+    name: String,
+    scope: String,
+) -> Result<IssuedApiToken, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("API token name is empty.".to_string());
+    }
+    let scope = ApiScope::from_identifier(&scope).ok_or_else(|| {
+        format!("Unknown API scope '{scope}'; use read_only, download or mutate.")
+    })?;
+    let (token, secret) = issue_token(name, scope, unix_now_secs());
+    let stored = token.clone();
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.api_tokens.push(stored))
+        .map_err(|err| err.to_string())?;
+    Ok(IssuedApiToken { token, secret })
 }
 
-#[derive(Debug, Serialize)]
-struct ComfyAddonState {
-    torch_profile: Option<String>,
-    sage_attention: bool,
-    sage_attention3: bool,
-    flash_attention: bool,
-    nunchaku: bool,
-    insight_face: bool,
-    trellis2: bool,
-    node_comfyui_manager: bool,
-    node_comfyui_easy_use: bool,
-    node_rgthree_comfy: bool,
-    node_comfyui_gguf: bool,
-    node_comfyui_kjnodes: bool,
-    node_comfyui_crystools: bool,
+#[tauri::command]
+fn list_api_tokens(state: State<'_, AppState>) -> Vec<ApiToken> {
+    state.context.config.settings().api_tokens
 }
 
-fn emit_comfyui_runtime_event(app: &AppHandle, phase: &str, message: impl Into<String>) {
-    let msg = message.into();
-    let _ = app.emit(
-        "comfyui-runtime",
-        ComfyRuntimeEvent {
-            phase: phase.to_string(),
-            message: msg.clone(),
-        },
-    );
+#[tauri::command]
+fn revoke_api_token(state: State<'_, AppState>, id: String) -> Result<bool, String> {
+    let id = id.trim().to_string();
+    let mut removed = false;
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            let before = settings.api_tokens.len();
+            settings.api_tokens.retain(|token| token.id != id);
+            removed = settings.api_tokens.len() != before;
+        })
+        .map_err(|err| err.to_string())?;
+    Ok(removed)
+}
 
-    if matches!(
-        phase,
-        "starting" | "started" | "stopping" | "stopped" | "start_failed" | "stop_failed"
-    ) {
-        let _ = app
-            .notification()
-            .builder()
-            .title("Arctic ComfyUI Helper")
-            .body(msg)
-            .show();
-    }
+/// Channels a paired desktop polls through `agent_events`.
+const AGENT_EVENT_CHANNELS: &[&str] = &[
+    "download-progress",
+    "model-superseded",
+    "models-migration",
+    "creator-updates",
+    "comfyui-install-progress",
+    "comfyui-install-log",
+    "comfyui-runtime",
+    "comfyui-log",
+];
+const AGENT_PAIRING_TOKEN_NAME: &str = "agent pairing";
+/// Holds a freshly issued pairing secret when stdout is not a terminal (systemd,
+/// nohup), so the secret never ends up in a journal.
+const AGENT_PAIRING_TOKEN_FILE: &str = "agent-pairing-token";
+/// Set on the re-executed agent so a missing display under xvfb-run cannot loop.
+const AGENT_XVFB_ENV: &str = "ARCTIC_AGENT_UNDER_XVFB";
+
+/// Parsed `--agent [--listen ADDR] [--pair]` command line.
+#[derive(Clone, Debug)]
+struct AgentLaunch {
+    listen: String,
+    /// Issue a fresh pairing token even if tokens already exist.
+    pair: bool,
 }
 
-fn python_for_root(root: &Path) -> std::process::Command {
-    let install_dir = root
-        .parent()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| root.to_path_buf());
-    let linux_dot_venv_py = root.join(".venv").join("bin").join("python");
-    let legacy_linux_dot_venv_py = install_dir.join(".venv").join("bin").join("python");
-    let linux_venv_py = root.join("venv").join("bin").join("python");
-    let legacy_linux_venv_py = install_dir.join("venv").join("bin").join("python");
+fn agent_launch_from_args() -> Option<AgentLaunch> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.iter().any(|arg| arg == "--agent") {
+        return None;
+    }
+    let listen = args
+        .iter()
+        .position(|arg| arg == "--listen")
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+        .unwrap_or_else(|| format!("127.0.0.1:{DEFAULT_AGENT_PORT}"));
+    Some(AgentLaunch {
+        listen,
+        pair: args.iter().any(|arg| arg == "--pair"),
+    })
+}
 
-    let mut cmd = if linux_dot_venv_py.exists() {
-        std::process::Command::new(linux_dot_venv_py)
-    } else if legacy_linux_dot_venv_py.exists() {
-        std::process::Command::new(legacy_linux_dot_venv_py)
-    } else if linux_venv_py.exists() {
-        std::process::Command::new(linux_venv_py)
-    } else if legacy_linux_venv_py.exists() {
-        std::process::Command::new(legacy_linux_venv_py)
-    } else if command_available("python3", &["--version"]) {
-        std::process::Command::new("python3")
-    } else {
-        std::process::Command::new("python")
-    };
-    if !nerdstats_enabled() {
-        apply_background_command_flags(&mut cmd);
+/// Restarts the agent inside a virtual X display on machines without one. Only
+/// returns if that is impossible, after which GTK fails to start.
+fn reexec_agent_under_xvfb() {
+    if std::env::var_os(AGENT_XVFB_ENV).is_some() {
+        log::error!("Agent mode still has no display under xvfb-run.");
+        return;
     }
-    cmd
+    if !binary_on_path("xvfb-run") {
+        log::error!("Agent mode needs a display server; install xvfb-run (package xvfb or xorg-x11-server-Xvfb) to run it on a bare server.");
+        return;
+    }
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    log::info!("No display found; restarting the agent under xvfb-run.");
+    let err = std::process::Command::new("xvfb-run")
+        .arg("-a")
+        .arg(exe)
+        .args(std::env::args_os().skip(1))
+        .env(AGENT_XVFB_ENV, "1")
+        .exec();
+    log::error!("Failed to start xvfb-run: {err}");
 }
 
-fn python_exe_candidates_for_root(root: &Path) -> Vec<PathBuf> {
-    let install_dir = root
-        .parent()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| root.to_path_buf());
-    vec![
-        root.join(".venv").join("bin").join("python"),
-        install_dir.join(".venv").join("bin").join("python"),
-        root.join("venv").join("bin").join("python"),
-        install_dir.join("venv").join("bin").join("python"),
-    ]
+fn agent_param<T: serde::de::DeserializeOwned>(
+    params: &serde_json::Value,
+    key: &str,
+) -> Result<T, String> {
+    let value = params.get(key).cloned().unwrap_or(serde_json::Value::Null);
+    serde_json::from_value(value).map_err(|err| format!("Invalid parameter '{key}': {err}"))
 }
 
-fn python_exe_works(py_exe: &Path, root: &Path) -> bool {
-    if !py_exe.exists() {
-        return false;
-    }
-    let mut cmd = std::process::Command::new(py_exe);
-    cmd.arg("--version");
-    cmd.current_dir(root);
-    apply_background_command_flags(&mut cmd);
-    cmd.output()
-        .map(|out| out.status.success())
-        .unwrap_or(false)
+fn agent_value<T: Serialize>(value: T) -> Result<serde_json::Value, String> {
+    serde_json::to_value(value).map_err(|err| err.to_string())
 }
 
-fn resolve_start_python_exe(
-    _app: &AppHandle,
-    _state: &AppState,
-    root: &Path,
-) -> Result<PathBuf, String> {
-    let candidates = python_exe_candidates_for_root(root);
-    for candidate in &candidates {
-        if python_exe_works(candidate, root) {
-            return Ok(candidate.clone());
+/// Runs one agent API call through the same command functions the UI invokes.
+/// Parameter names are the camelCase ones the frontend passes to `invoke`.
+fn dispatch_agent_method(
+    app: &AppHandle,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let state = app.state::<AppState>();
+    match method {
+        "agent_events" => {
+            let since = agent_param::<Option<u64>>(&params, "since")?.unwrap_or(0);
+            agent_value(agent::events_since(since))
+        }
+        "get_app_snapshot" => agent_value(get_app_snapshot(state)),
+        "detect_vram_tier" => agent_value(detect_vram_tier()),
+        "get_host_stats" => agent_value(get_host_stats(state)),
+        "get_perf_profile" => agent_value(get_perf_profile()),
+        "list_comfyui_installations" => agent_value(list_comfyui_installations(
+            state,
+            agent_param(&params, "basePath")?,
+        )?),
+        "get_comfyui_resume_state" => agent_value(get_comfyui_resume_state(
+            state,
+            agent_param(&params, "installBase")?,
+        )?),
+        "get_install_log_tail" => agent_value(get_install_log_tail(
+            agent_param(&params, "installRoot")?,
+            agent_param(&params, "lines")?,
+        )?),
+        "get_comfyui_log_tail" => agent_value(get_comfyui_log_tail(
+            state,
+            agent_param(&params, "comfyuiRoot")?,
+            agent_param(&params, "lines")?,
+        )?),
+        "run_comfyui_preflight" => agent_value(run_comfyui_preflight(
+            state,
+            agent_param(&params, "request")?,
+        )),
+        "plan_comfyui_install" => {
+            agent_value(plan_comfyui_install(agent_param(&params, "request")?)?)
+        }
+        "start_comfyui_install" => agent_value(tauri::async_runtime::block_on(
+            start_comfyui_install(app.clone(), state, agent_param(&params, "request")?),
+        )?),
+        "resume_comfyui_install" => agent_value(tauri::async_runtime::block_on(
+            resume_comfyui_install(app.clone(), state, agent_param(&params, "installDir")?),
+        )?),
+        "cancel_comfyui_install" => agent_value(cancel_comfyui_install(state)?),
+        "download_model_assets" => {
+            agent_value(tauri::async_runtime::block_on(download_model_assets(
+                app.clone(),
+                state,
+                agent_param(&params, "modelId")?,
+                agent_param(&params, "variantId")?,
+                agent_param(&params, "ramTier")?,
+                agent_param(&params, "comfyuiRoot")?,
+                agent_param(&params, "artifactPaths")?,
+            ))?)
         }
+        "download_lora_asset" => agent_value(tauri::async_runtime::block_on(download_lora_asset(
+            app.clone(),
+            state,
+            agent_param(&params, "loraId")?,
+            agent_param(&params, "token")?,
+            agent_param(&params, "comfyuiRoot")?,
+        ))?),
+        "download_workflow_asset" => {
+            agent_value(tauri::async_runtime::block_on(download_workflow_asset(
+                app.clone(),
+                state,
+                agent_param(&params, "workflowId")?,
+                agent_param(&params, "comfyuiRoot")?,
+            ))?)
+        }
+        "cancel_active_download" => agent_value(cancel_active_download(state)?),
+        "get_comfyui_runtime_status" => agent_value(get_comfyui_runtime_status(
+            state,
+            agent_param(&params, "comfyuiRoot")?,
+        )),
+        "start_comfyui_root" => agent_value(start_comfyui_root(
+            app.clone(),
+            state,
+            agent_param(&params, "comfyuiRoot")?,
+            agent_param(&params, "onConflict")?,
+            agent_param(&params, "port")?,
+        )?),
+        "stop_comfyui_root" => agent_value(stop_comfyui_root(
+            app.clone(),
+            state,
+            agent_param(&params, "comfyuiRoot")?,
+        )?),
+        _ => Err(format!("{method} is not available through the agent API.")),
     }
+}
 
-    if candidates.iter().any(|c| c.exists()) {
-        return Err(
-            "Detected Python runtime candidates, but none are executable. Reinstall ComfyUI runtime."
-                .to_string(),
-        );
+/// Binds the agent API, mirrors progress events into the poll buffer and makes sure
+/// there is a token to pair with.
+fn start_agent_server(app: &AppHandle, launch: &AgentLaunch) -> Result<(), String> {
+    let listener = TcpListener::bind(&launch.listen)
+        .map_err(|err| format!("Unable to listen on {}: {err}", launch.listen))?;
+    let bound = listener.local_addr().map_err(|err| err.to_string())?;
+    if !bound.ip().is_loopback() {
+        return Err(format!(
+            "Refusing to serve the agent API on {bound}: it is plain HTTP and would send tokens in the clear. \
+             Listen on 127.0.0.1 and reach it over an SSH tunnel or a TLS reverse proxy."
+        ));
     }
-
-    if command_available("python3", &["--version"]) {
-        return Ok(PathBuf::from("python3"));
+    for channel in AGENT_EVENT_CHANNELS {
+        let channel = *channel;
+        app.listen_any(channel, move |event| {
+            match serde_json::from_str::<serde_json::Value>(event.payload()) {
+                Ok(payload) => agent::record_event(channel, payload),
+                Err(err) => log::warn!("Unable to buffer {channel} event for the agent: {err}"),
+            }
+        });
     }
-    if command_available("python", &["--version"]) {
-        return Ok(PathBuf::from("python"));
+
+    let state = app.state::<AppState>();
+    if launch.pair || state.context.config.settings().api_tokens.is_empty() {
+        let (token, secret) =
+            issue_token(AGENT_PAIRING_TOKEN_NAME, ApiScope::Mutate, unix_now_secs());
+        state
+            .context
+            .config
+            .update_settings(|settings| settings.api_tokens.push(token))
+            .map_err(|err| err.to_string())?;
+        announce_pairing_secret(&state, &launch.listen, &secret)?;
+    } else {
+        log::info!("Agent accepts the existing API tokens; start with --pair to issue a new one.");
     }
 
-    Err(
-        "No working Python executable found for this ComfyUI install. Reinstall or run Install New once to bootstrap runtime."
-            .to_string(),
-    )
-}
-fn python_exe_for_root(root: &Path) -> Result<PathBuf, String> {
-    let install_dir = root
-        .parent()
-        .map(PathBuf::from)
-        .unwrap_or_else(|| root.to_path_buf());
-    let candidates = [
-        root.join(".venv").join("bin").join("python"),
-        install_dir.join(".venv").join("bin").join("python"),
-        root.join("venv").join("bin").join("python"),
-        install_dir.join("venv").join("bin").join("python"),
-    ];
-    for candidate in candidates {
-        if candidate.exists() {
-            return Ok(candidate);
+    let tokens_app = app.clone();
+    let dispatch_app = app.clone();
+    let server = AgentServer::new(
+        move || {
+            tokens_app
+                .state::<AppState>()
+                .context
+                .config
+                .settings()
+                .api_tokens
+        },
+        move |method, params| dispatch_agent_method(&dispatch_app, method, params),
+    );
+    std::thread::spawn(move || {
+        if let Err(err) = server.run(listener) {
+            log::error!("Agent server stopped: {err}");
         }
-    }
-    Err("Python executable for this ComfyUI install was not found.".to_string())
+    });
+    log::info!(
+        "Agent mode: serving the operations API on {}",
+        launch.listen
+    );
+    Ok(())
 }
 
-fn pip_has_package(root: &Path, package: &str) -> bool {
-    let mut cmd = python_for_root(root);
-    cmd.arg("-m").arg("pip").arg("show").arg(package);
-    cmd.current_dir(root);
-    cmd.output()
-        .map(|out| out.status.success())
-        .unwrap_or(false)
+/// Shows a new pairing secret on the terminal, or writes it to a 0600 file in the
+/// config folder when stdout is redirected and only prints where to find it.
+fn announce_pairing_secret(state: &AppState, listen: &str, secret: &str) -> Result<(), String> {
+    use std::io::IsTerminal;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    if std::io::stdout().is_terminal() {
+        // Printed rather than logged so it shows up regardless of the log filter.
+        println!("Agent pairing: host {listen} token {secret}");
+        return Ok(());
+    }
+    let path = state
+        .context
+        .config
+        .config_path()
+        .join(AGENT_PAIRING_TOKEN_FILE);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&path)
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    // `mode` only applies to new files; tighten one left over from an older run.
+    let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    writeln!(file, "{secret}")
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    println!(
+        "Agent pairing: host {listen}; the token is in {}",
+        path.display()
+    );
+    Ok(())
 }
 
-fn custom_node_exists(root: &Path, name: &str) -> bool {
-    root.join("custom_nodes").join(name).is_dir()
+#[derive(Debug, Serialize)]
+struct RemoteAgentStatus {
+    url: String,
+    /// The agent's `get_app_snapshot`, so the UI can show which machine it paired with.
+    snapshot: serde_json::Value,
 }
 
-fn read_comfyui_installed_version(root: &Path) -> Option<String> {
-    let path = root.join("comfyui_version.py");
-    let content = std::fs::read_to_string(path).ok()?;
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if !trimmed.starts_with("__version__") {
-            continue;
-        }
-        let (_, rhs) = trimmed.split_once('=')?;
-        let value = rhs.trim().trim_matches('"').trim_matches('\'').trim();
-        if !value.is_empty() {
-            return Some(value.to_string());
-        }
+fn paired_remote_agent(state: &AppState) -> Result<(String, String), String> {
+    let settings = state.context.config.settings();
+    match (settings.remote_agent_url, settings.remote_agent_token) {
+        (Some(url), Some(token)) => Ok((url, token)),
+        _ => Err("No remote agent is paired.".to_string()),
     }
-    None
 }
 
-fn parse_semver_triplet(input: &str) -> Option<(u64, u64, u64)> {
-    let trimmed = input.trim().trim_start_matches('v').trim_start_matches('V');
-    let core = trimmed.split('-').next().unwrap_or(trimmed);
-    let mut parts = core.split('.');
-    let major = parts.next()?.parse::<u64>().ok()?;
-    let minor = parts.next()?.parse::<u64>().ok()?;
-    let patch = parts.next()?.parse::<u64>().ok()?;
-    if parts.next().is_some() {
-        return None;
+#[tauri::command]
+async fn pair_remote_agent(
+    state: State<'_, AppState>,
+    host: String,
+    token: String,
+) -> Result<RemoteAgentStatus, String> {
+    if !state.context.config.settings_unlocked() {
+        return Err("Settings are encrypted. Unlock them with your passphrase first.".to_string());
     }
-    Some((major, minor, patch))
+    let url = agent::normalize_agent_url(&host).map_err(|err| err.to_string())?;
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err("Agent token is empty.".to_string());
+    }
+    let snapshot = agent::call(&url, &token, "get_app_snapshot", serde_json::Value::Null)
+        .await
+        .map_err(|err| err.to_string())?;
+    let stored_url = url.clone();
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings.remote_agent_url = Some(stored_url);
+            settings.remote_agent_token = Some(token);
+        })
+        .map_err(|err| err.to_string())?;
+    Ok(RemoteAgentStatus { url, snapshot })
 }
 
-fn normalize_release_version(input: &str) -> Option<String> {
-    let (major, minor, patch) = parse_semver_triplet(input)?;
-    Some(format!("{major}.{minor}.{patch}"))
+#[tauri::command]
+fn unpair_remote_agent(state: State<'_, AppState>) -> Result<AppSettings, String> {
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings.remote_agent_url = None;
+            settings.remote_agent_token = None;
+        })
+        .map_err(|err| err.to_string())
 }
 
-fn git_latest_release_tag(root: &Path) -> Option<(String, String)> {
-    let (stdout, _) = run_command_capture("git", &["ls-remote", "--tags", "--refs", "origin"], Some(root)).ok()?;
-    let mut best: Option<((u64, u64, u64), String, String)> = None;
-
-    for line in stdout.lines() {
-        let mut cols = line.split_whitespace();
-        let Some(_sha) = cols.next() else {
-            continue;
-        };
-        let Some(ref_name) = cols.next() else {
-            continue;
-        };
-        let Some(tag) = ref_name.strip_prefix("refs/tags/") else {
-            continue;
-        };
-        let Some(version) = normalize_release_version(tag) else {
-            continue;
-        };
-        let Some(parsed) = parse_semver_triplet(&version) else {
-            continue;
-        };
+/// Forwards a command to the paired agent. The UI routes operations here when their
+/// target is set to the remote machine.
+#[tauri::command]
+async fn invoke_remote_agent(
+    state: State<'_, AppState>,
+    method: String,
+    params: Option<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let (url, token) = paired_remote_agent(&state)?;
+    agent::call(&url, &token, &method, params.unwrap_or_default())
+        .await
+        .map_err(|err| err.to_string())
+}
 
-        match &best {
-            Some((current, _, _)) if *current >= parsed => {}
-            _ => best = Some((parsed, tag.to_string(), version)),
-        }
+fn validate_index_url(label: &str, raw: Option<String>) -> Result<Option<String>, String> {
+    let Some(value) = raw.map(|value| value.trim().trim_end_matches('/').to_string()) else {
+        return Ok(None);
+    };
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let parsed =
+        reqwest::Url::parse(&value).map_err(|err| format!("Invalid {label} URL {value}: {err}"))?;
+    if !matches!(parsed.scheme(), "http" | "https" | "file") {
+        return Err(format!("{label} URL must use http, https or file: {value}"));
     }
+    Ok(Some(value))
+}
 
-    best.map(|(_, tag, version)| (tag, version))
+#[tauri::command]
+fn set_package_index_settings(
+    state: State<'_, AppState>,
+    package_index: PackageIndexSettings,
+) -> Result<AppSettings, String> {
+    let package_index = PackageIndexSettings {
+        pypi_index_url: validate_index_url("PyPI index", package_index.pypi_index_url)?,
+        torch_index_base: validate_index_url("Torch index", package_index.torch_index_base)?,
+    };
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.package_index = package_index)
+        .map_err(|err| err.to_string())
 }
 
-fn git_current_branch(root: &Path) -> Option<String> {
-    let (stdout, _) =
-        run_command_capture("git", &["rev-parse", "--abbrev-ref", "HEAD"], Some(root)).ok()?;
-    let branch = stdout.lines().next().unwrap_or_default().trim().to_string();
-    if branch.is_empty() {
-        None
-    } else {
-        Some(branch)
-    }
+#[tauri::command]
+fn set_triton_version_override(
+    state: State<'_, AppState>,
+    version: Option<String>,
+) -> Result<AppSettings, String> {
+    let version = normalize_triton_version(version)?;
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.comfyui_triton_version = version)
+        .map_err(|err| err.to_string())
 }
 
-fn git_commit_for_ref(root: &Path, git_ref: &str) -> Option<String> {
-    let (stdout, _) = run_command_capture("git", &["rev-parse", git_ref], Some(root)).ok()?;
-    let commit = stdout.lines().next().unwrap_or_default().trim().to_string();
-    if commit.len() >= 7 {
-        Some(commit)
-    } else {
-        None
+/// UI event channels mirrored to the configured event export sink.
+const EXPORTED_EVENT_CHANNELS: &[&str] = &[
+    "download-progress",
+    "comfyui-install-progress",
+    "comfyui-runtime",
+    "update-state",
+];
+
+fn setup_event_export(app: &AppHandle) {
+    for channel in EXPORTED_EVENT_CHANNELS {
+        let handle = app.clone();
+        app.listen_any(*channel, move |event| {
+            let state = handle.state::<AppState>();
+            if !state.context.events.is_enabled() {
+                return;
+            }
+            match serde_json::from_str::<serde_json::Value>(event.payload()) {
+                Ok(payload) => state.context.events.export(channel, &payload),
+                Err(err) => log::warn!("Unable to export {channel} event: {err}"),
+            }
+        });
     }
 }
 
-fn stop_comfyui_for_mutation(app: &AppHandle, state: &AppState) -> Result<bool, String> {
-    if !comfyui_runtime_running(state) {
-        return Ok(false);
-    }
-    emit_comfyui_runtime_event(
-        app,
-        "stopping_for_changes",
-        "Stopping ComfyUI before applying changes...",
-    );
-    stop_comfyui_root_impl(state)?;
-    let running = comfyui_runtime_running(state);
-    update_tray_comfy_status(app, running);
-    if running {
-        return Err("ComfyUI is still running. Stop it before applying changes.".to_string());
+#[tauri::command]
+fn set_event_export_settings(
+    state: State<'_, AppState>,
+    export: EventExportSettings,
+) -> Result<AppSettings, String> {
+    let ndjson_path = export
+        .ndjson_path
+        .filter(|path| !path.as_os_str().is_empty());
+    if let Some(path) = &ndjson_path {
+        if !path.is_absolute() {
+            return Err("NDJSON event log path must be absolute.".to_string());
+        }
     }
-    emit_comfyui_runtime_event(
-        app,
-        "stopped_for_changes",
-        "ComfyUI stopped for install/remove operation.",
-    );
-    Ok(true)
+    let export = EventExportSettings {
+        target: export.target,
+        ndjson_path: if export.target == Some(EventExportTarget::Ndjson) {
+            ndjson_path
+        } else {
+            None
+        },
+        include_progress: export.include_progress,
+    };
+    let state_dir = state
+        .context
+        .config
+        .state_path()
+        .ok_or_else(|| "State directory is unavailable.".to_string())?;
+    state
+        .context
+        .events
+        .configure(&export, &state_dir)
+        .map_err(|err| format!("{err:#}"))?;
+    state
+        .context
+        .config
+        .update_settings(|settings| settings.event_export = export)
+        .map_err(|err| err.to_string())
 }
-fn kill_python_processes_for_root(_root: &Path, _py_exe: &Path) -> Result<bool, String> {
-    Ok(false)
+
+#[derive(Debug, Serialize)]
+struct HostStatsEntry {
+    #[serde(flatten)]
+    stats: HostStats,
+    failure_rate: f64,
+    bytes_per_sec: Option<u64>,
+    flagged_reason: Option<String>,
+    blacklisted: bool,
 }
 
-fn restart_comfyui_after_mutation(
-    app: &AppHandle,
-    state: &AppState,
-    was_running: bool,
-) -> Result<(), String> {
-    if !was_running {
-        return Ok(());
+fn host_stats_entries(state: &AppState) -> Vec<HostStatsEntry> {
+    let blacklist = state.context.config.settings().blacklisted_hosts;
+    let mut stats = state.context.downloads.host_stats().snapshot();
+    for host in &blacklist {
+        if !stats.iter().any(|entry| &entry.host == host) {
+            stats.push(HostStats {
+                host: host.clone(),
+                ..HostStats::default()
+            });
+        }
     }
-    start_comfyui_root_impl(app, state, None)?;
-    wait_for_comfyui_start(state, Duration::from_secs(45))?;
-    update_tray_comfy_status(app, true);
-    emit_comfyui_runtime_event(
-        app,
-        "restarted_after_changes",
-        "ComfyUI restarted after install/remove operation.",
-    );
-    Ok(())
+    stats.sort_by(|a, b| a.host.cmp(&b.host));
+    stats
+        .into_iter()
+        .map(|stats| HostStatsEntry {
+            failure_rate: stats.failure_rate(),
+            bytes_per_sec: stats.bytes_per_sec(),
+            flagged_reason: stats.problem(),
+            blacklisted: is_blacklisted(&stats.host, &blacklist),
+            stats,
+        })
+        .collect()
 }
 
 #[tauri::command]
-fn get_comfyui_addon_state(
-    state: State<'_, AppState>,
-    comfyui_root: Option<String>,
-) -> Result<ComfyAddonState, String> {
-    let root = resolve_root_path(&state.context, comfyui_root)?;
-    let settings = state.context.config.settings();
-    let same_as_configured_root = settings
-        .comfyui_root
-        .as_ref()
-        .map(|p| normalize_canonical_path(&std::fs::canonicalize(p).unwrap_or_else(|_| p.clone())))
-        == Some(root.clone());
-    let has_sage3 = python_module_importable(&root, "sageattn3");
-    let has_sage =
-        python_module_importable(&root, "sageattention") || python_module_importable(&root, "sageattn3");
-    let has_flash = python_module_importable(&root, "flash_attn");
-    let has_nunchaku = python_module_importable(&root, "nunchaku")
-        || pip_has_package(&root, "nunchaku")
-        || custom_node_exists(&root, "nunchaku_nodes")
-        || custom_node_exists(&root, "ComfyUI-nunchaku");
-    let active_attention: String = if same_as_configured_root {
-        match settings.comfyui_attention_backend.as_deref() {
-            Some("none") => "none".to_string(),
-            Some("flash") if has_flash => "flash".to_string(),
-            Some("sage3") if has_sage3 => "sage3".to_string(),
-            Some("sage") if has_sage => "sage".to_string(),
-            Some("nunchaku") if has_nunchaku => "nunchaku".to_string(),
-            _ => detect_launch_attention_backend_for_root(&root).unwrap_or_else(|| "none".to_string()),
-        }
-    } else {
-        detect_launch_attention_backend_for_root(&root).unwrap_or_else(|| "none".to_string())
-    };
-
-    Ok(ComfyAddonState {
-        torch_profile: detect_torch_profile_for_root(&root).or_else(|| {
-            if same_as_configured_root {
-                settings.comfyui_torch_profile.clone()
-            } else {
-                None
-            }
-        }),
-        sage_attention: active_attention == "sage",
-        sage_attention3: active_attention == "sage3",
-        flash_attention: active_attention == "flash",
-        nunchaku: active_attention == "nunchaku",
-        insight_face: pip_has_package(&root, "insightface"),
-        trellis2: custom_node_exists(&root, "ComfyUI-Trellis2")
-            || custom_node_exists(&root, "ComfyUI-TRELLIS2"),
-        node_comfyui_manager: custom_node_exists(&root, "ComfyUI-Manager")
-            || custom_node_exists(&root, "comfyui-manager"),
-        node_comfyui_easy_use: custom_node_exists(&root, "ComfyUI-Easy-Use"),
-        node_rgthree_comfy: custom_node_exists(&root, "rgthree-comfy"),
-        node_comfyui_gguf: custom_node_exists(&root, "ComfyUI-GGUF"),
-        node_comfyui_kjnodes: custom_node_exists(&root, "comfyui-kjnodes"),
-        node_comfyui_crystools: custom_node_exists(&root, "comfyui-crystools"),
-    })
+fn get_host_stats(state: State<'_, AppState>) -> Vec<HostStatsEntry> {
+    host_stats_entries(&state)
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct AttentionBackendChangeRequest {
-    #[serde(default)]
-    comfyui_root: Option<String>,
-    target_backend: String, // none | sage | sage3 | flash | nunchaku
-    #[serde(default)]
-    torch_profile: Option<String>,
+#[tauri::command]
+fn get_perf_profile() -> PerfProfile {
+    perf::snapshot()
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct ComfyComponentToggleRequest {
-    #[serde(default)]
-    comfyui_root: Option<String>,
-    component: String,
-    enabled: bool,
+/// Times every command for `get_perf_profile`. Sync commands run inside the handler,
+/// so their full latency (and any UI stall they cause) is captured; async commands
+/// only count their dispatch.
+fn timed_invoke_handler<R: tauri::Runtime>(
+    handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let started = Instant::now();
+        let handled = handler(invoke);
+        perf::record_command(&command, started.elapsed());
+        handled
+    }
 }
 
 #[tauri::command]
-fn apply_attention_backend_change(
-    app: AppHandle,
+fn set_host_blacklisted(
     state: State<'_, AppState>,
-    request: AttentionBackendChangeRequest,
-) -> Result<String, String> {
-    let was_running = stop_comfyui_for_mutation(&app, &state)?;
-    let root = resolve_root_path(&state.context, request.comfyui_root)?;
-    let target = request.target_backend.trim().to_ascii_lowercase();
-    if !matches!(
-        target.as_str(),
-        "none" | "sage" | "sage3" | "flash" | "nunchaku"
-    ) {
-        return Err("Unknown attention backend target.".to_string());
+    host: String,
+    blacklisted: bool,
+) -> Result<Vec<HostStatsEntry>, String> {
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() || host.contains('/') || host.contains(char::is_whitespace) {
+        return Err("Enter a bare host name such as huggingface.co.".to_string());
     }
-    if target == "sage3" {
-        let gpu = detect_nvidia_gpu_details();
-        let is_50_series = gpu
-            .name
-            .as_deref()
-            .map(|name| name.to_ascii_lowercase().contains("rtx 50"))
-            .unwrap_or(false);
-        if !is_50_series {
-            return Err(
-                "SageAttention3 is available only for NVIDIA RTX 50-series GPUs.".to_string(),
-            );
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings.blacklisted_hosts.retain(|entry| entry != &host);
+            if blacklisted {
+                settings.blacklisted_hosts.push(host.clone());
+            }
+        })
+        .map_err(|err| err.to_string())?;
+    Ok(host_stats_entries(&state))
+}
+
+/// Artifacts to fetch for a variant: the explicit selection when one is given,
+/// otherwise the automatic pick for `tier`.
+fn planned_model_artifacts(
+    resolved: &ResolvedModel,
+    tier: Option<RamTier>,
+    artifact_paths: Option<&[String]>,
+) -> Result<Vec<ModelArtifact>, String> {
+    match artifact_paths.filter(|paths| !paths.is_empty()) {
+        Some(paths) => resolved
+            .artifacts_for_selection(paths)
+            .map_err(|err| err.to_string()),
+        None => {
+            let planned = resolved.artifacts_for_download(tier);
+            if planned.is_empty() {
+                return Err("No artifacts match the selected RAM tier.".to_string());
+            }
+            Ok(planned)
         }
     }
+}
 
-    let py_path = {
-        let probe = python_for_root(&root);
-        probe.get_program().to_string_lossy().to_string()
-    };
-    let py_exe = PathBuf::from(&py_path);
-    let _ = kill_python_processes_for_root(&root, &py_exe);
-
-    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
-    let uv_bin = resolve_uv_binary(&shared_runtime_root, &app)?;
-    let uv_python_install_dir = shared_runtime_root
-        .join(".python")
-        .to_string_lossy()
-        .to_string();
-    let profile = if let Some(profile) = request.torch_profile.clone() {
-        profile
-    } else {
-        profile_from_torch_env(&root)?
-    };
-    let hopper_sm90 = is_nvidia_hopper_sm90();
-    let triton_pkg = triton_package_for_profile_linux(&profile);
+#[tauri::command]
+fn get_variant_artifact_options(
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+    ram_tier: Option<String>,
+) -> Result<Vec<ArtifactOption>, String> {
+    let resolved = state
+        .context
+        .catalog
+        .resolve_variant(&model_id, &variant_id)
+        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+    let tier = ram_tier
+        .as_deref()
+        .and_then(parse_ram_tier)
+        .or_else(|| detected_ram_tier(&state, &resolved.master));
+    Ok(resolved.artifact_options(tier))
+}
 
-    force_cleanup_attention_backends(&root, &py_path)?;
+#[derive(Debug, Serialize)]
+struct ModelDownloadPlan {
+    model_id: String,
+    variant_id: String,
+    ram_tier: Option<String>,
+    destination_root: String,
+    artifacts: Vec<PlannedArtifact>,
+    total_bytes: u64,
+    download_bytes: u64,
+    existing_count: usize,
+    unknown_size_count: usize,
+    free_space_bytes: Option<u64>,
+    enough_space: Option<bool>,
+    /// Set when the hardware gate blocks this download; cleared by an override.
+    compat: Option<CompatBlock>,
+}
 
-    match target.as_str() {
-        "none" => {}
-        "sage" => {
-            run_uv_pip_strict(
-                &uv_bin,
-                &py_path,
-                &["install", "--upgrade", "--force-reinstall", triton_pkg],
-                Some(&root),
-                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
-            )?;
-            install_sageattention_linux(&root, &py_path, &profile, hopper_sm90)?;
-        }
-        "flash" => {
-            run_uv_pip_strict(
-                &uv_bin,
+#[tauri::command]
+async fn plan_model_download(
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+    ram_tier: Option<String>,
+    comfyui_root: Option<String>,
+    artifact_paths: Option<Vec<String>>,
+) -> Result<ModelDownloadPlan, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let effective_root = match comfy_extra_model_config(&root) {
+        Some(config) if config.is_default => config.base_path,
+        _ => root,
+    };
+    let resolved = state
+        .context
+        .catalog
+        .resolve_variant(&model_id, &variant_id)
+        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+    let tier = ram_tier
+        .as_deref()
+        .and_then(parse_ram_tier)
+        .or_else(|| detected_ram_tier(&state, &resolved.master));
+    let planned = planned_model_artifacts(&resolved, tier, artifact_paths.as_deref())?;
+    let compat = model_compat_block(&planned).filter(|_| {
+        !state
+            .context
+            .config
+            .settings()
+            .compat_overrides
+            .contains(&compat_override_key(&model_id, &variant_id))
+    });
+
+    let mut resolved_for_plan = resolved.clone();
+    resolved_for_plan.variant.artifacts = planned;
+    let artifacts = state
+        .context
+        .downloads
+        .plan_variant_download(effective_root.clone(), resolved_for_plan)
+        .await
+        .map_err(|err| format!("Download planning failed: {err}"))?;
+
+    let total_bytes = artifacts.iter().filter_map(|a| a.size_bytes).sum();
+    let download_bytes: u64 = artifacts
+        .iter()
+        .filter(|a| !a.exists)
+        .filter_map(|a| a.size_bytes)
+        .sum();
+    let unknown_size_count = artifacts
+        .iter()
+        .filter(|a| !a.exists && a.size_bytes.is_none())
+        .count();
+    // The destination may not exist yet; measure the closest existing ancestor.
+    let free_space_bytes = effective_root
+        .ancestors()
+        .find(|path| path.exists())
+        .and_then(|path| fs2::available_space(path).ok());
+    Ok(ModelDownloadPlan {
+        model_id,
+        variant_id,
+        ram_tier: tier.map(|tier| tier.identifier().to_string()),
+        destination_root: effective_root.to_string_lossy().to_string(),
+        existing_count: artifacts.iter().filter(|a| a.exists).count(),
+        unknown_size_count,
+        total_bytes,
+        download_bytes,
+        enough_space: free_space_bytes.map(|free| free >= download_bytes),
+        free_space_bytes,
+        artifacts,
+        compat,
+    })
+}
+
+#[tauri::command]
+async fn download_model_assets(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+    ram_tier: Option<String>,
+    comfyui_root: Option<String>,
+    artifact_paths: Option<Vec<String>>,
+) -> Result<(), String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let effective_root = match comfy_extra_model_config(&root) {
+        Some(config) if config.is_default => {
+            log::info!(
+                "Using extra model base path for model downloads: {}",
+                config.base_path.display()
+            );
+            config.base_path
+        }
+        _ => root.clone(),
+    };
+    let resolved = state
+        .context
+        .catalog
+        .resolve_variant(&model_id, &variant_id)
+        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+    if !variant_warnings_acknowledged(&state, &resolved) {
+        return Err(
+            "This variant has installation warnings that must be acknowledged before downloading."
+                .to_string(),
+        );
+    }
+
+    let tier = ram_tier
+        .as_deref()
+        .and_then(parse_ram_tier)
+        .or_else(|| detected_ram_tier(&state, &resolved.master));
+    let planned = planned_model_artifacts(&resolved, tier, artifact_paths.as_deref())?;
+    if let Some(block) = model_compat_block(&planned) {
+        let overridden = state
+            .context
+            .config
+            .settings()
+            .compat_overrides
+            .contains(&compat_override_key(&model_id, &variant_id));
+        if !overridden {
+            return Err(block.to_string());
+        }
+    }
+
+    let hook_env = vec![
+        ("ARCTIC_DOWNLOAD_KIND", "model".to_string()),
+        (
+            "ARCTIC_COMFYUI_ROOT",
+            effective_root.to_string_lossy().to_string(),
+        ),
+        ("ARCTIC_MODEL_ID", model_id.clone()),
+        ("ARCTIC_VARIANT_ID", variant_id.clone()),
+        (
+            "ARCTIC_RAM_TIER",
+            tier.map(|tier| tier.identifier().to_string())
+                .unwrap_or_default(),
+        ),
+    ];
+    let cancel = claim_download_slot(&state)?;
+    if let Err(err) = run_user_hook(&state, HookEvent::PreDownload, hook_env.clone()).await {
+        release_download_slot(&state);
+        return Err(err);
+    }
+
+    let mut resolved_for_download = resolved.clone();
+    resolved_for_download.variant.artifacts = planned;
+
+    let eject_root = effective_root.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let handle = state.context.downloads.download_variant_with_cancel(
+        effective_root,
+        resolved_for_download,
+        tx,
+        Some(cancel),
+    );
+    if let Ok(mut abort) = state.active_abort.lock() {
+        *abort = Some(handle.abort_handle());
+    }
+    spawn_progress_emitter(app.clone(), "model".to_string(), rx);
+    let app_for_task = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = handle.await;
+        let hook_status = download_hook_status(&result);
+        let superseded_root = root;
+        let hook_files = match &result {
+            Ok(Ok(outcomes)) => outcomes
+                .iter()
+                .map(|outcome| outcome.destination.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        };
+        let managed = app_for_task.state::<AppState>();
+        if let Ok(mut active) = managed.active_cancel.lock() {
+            *active = None;
+        }
+        if let Ok(mut abort) = managed.active_abort.lock() {
+            *abort = None;
+        }
+
+        match result {
+            Ok(Ok(outcomes)) => {
+                let _ = app_for_task.emit(
+                    "download-progress",
+                    DownloadProgressEvent {
+                        kind: "model".to_string(),
+                        phase: "batch_finished".to_string(),
+                        artifact: None,
+                        index: None,
+                        total: Some(outcomes.len()),
+                        received: None,
+                        size: None,
+                        folder: None,
+                        message: Some("Model download batch completed.".to_string()),
+                    },
+                );
+                let report = superseded_artifacts_report(&superseded_root, &resolved);
+                if !report.artifacts.is_empty() {
+                    let _ = app_for_task.emit("model-superseded", report);
+                }
+                emit_eject_status(&app_for_task, "model", eject_root).await;
+            }
+            Ok(Err(err)) => {
+                let lower = err.to_string().to_ascii_lowercase();
+                let phase = if lower.contains("cancel") {
+                    "cancelled"
+                } else {
+                    "batch_failed"
+                };
+                let _ = app_for_task.emit(
+                    "download-progress",
+                    DownloadProgressEvent {
+                        kind: "model".to_string(),
+                        phase: phase.to_string(),
+                        artifact: None,
+                        index: None,
+                        total: None,
+                        received: None,
+                        size: None,
+                        folder: None,
+                        message: Some(err.to_string()),
+                    },
+                );
+            }
+            Err(join_err) => {
+                let phase = if join_err.is_cancelled() {
+                    "cancelled"
+                } else {
+                    "batch_failed"
+                };
+                let _ = app_for_task.emit(
+                    "download-progress",
+                    DownloadProgressEvent {
+                        kind: "model".to_string(),
+                        phase: phase.to_string(),
+                        artifact: None,
+                        index: None,
+                        total: None,
+                        received: None,
+                        size: None,
+                        folder: None,
+                        message: Some(join_err.to_string()),
+                    },
+                );
+            }
+        }
+
+        let mut env = hook_env;
+        env.push(("ARCTIC_STATUS", hook_status.to_string()));
+        env.push(("ARCTIC_FILES", hook_files));
+        let _ = run_user_hook(&managed, HookEvent::PostDownload, env).await;
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn download_lora_asset(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    lora_id: String,
+    token: Option<String>,
+    comfyui_root: Option<String>,
+) -> Result<(), String> {
+    let lora = state
+        .context
+        .catalog
+        .find_lora(&lora_id)
+        .ok_or_else(|| "Selected LoRA was not found in catalog.".to_string())?;
+    start_lora_download(app, state, comfyui_root, lora, token).await
+}
+
+/// Shared by catalog LoRAs and followed-creator releases, which have no catalog entry.
+async fn start_lora_download(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    lora: LoraDefinition,
+    token: Option<String>,
+) -> Result<(), String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let effective_root = match comfy_extra_model_config(&root) {
+        Some(config) if config.is_default => {
+            log::info!(
+                "Using extra model base path for LoRA downloads: {}",
+                config.base_path.display()
+            );
+            config.base_path
+        }
+        _ => root,
+    };
+
+    let hook_env = vec![
+        ("ARCTIC_DOWNLOAD_KIND", "lora".to_string()),
+        (
+            "ARCTIC_COMFYUI_ROOT",
+            effective_root.to_string_lossy().to_string(),
+        ),
+        ("ARCTIC_LORA_ID", lora.id.clone()),
+    ];
+    let cancel = claim_download_slot(&state)?;
+    if let Err(err) = run_user_hook(&state, HookEvent::PreDownload, hook_env.clone()).await {
+        release_download_slot(&state);
+        return Err(err);
+    }
+
+    let eject_root = effective_root.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let handle =
+        state
+            .context
+            .downloads
+            .download_lora_with_cancel(effective_root, lora, token, tx, Some(cancel));
+    spawn_download_task(
+        &app,
+        "lora",
+        handle,
+        rx,
+        eject_root,
+        Some(hook_env),
+        |outcome| {
+            let message = if outcome.examples_saved > 0 {
+                format!(
+                    "LoRA download completed ({} example files saved).",
+                    outcome.examples_saved
+                )
+            } else {
+                "LoRA download completed.".to_string()
+            };
+            (message, outcome.destination.clone())
+        },
+    );
+    Ok(())
+}
+
+/// Takes the single active-download slot, failing while another download holds it.
+fn claim_download_slot(state: &AppState) -> Result<CancellationToken, String> {
+    let cancel = CancellationToken::new();
+    let mut active = state
+        .active_cancel
+        .lock()
+        .map_err(|_| "download state lock poisoned".to_string())?;
+    if active.is_some() {
+        return Err("A download is already active. Cancel it first.".to_string());
+    }
+    *active = Some(cancel.clone());
+    Ok(cancel)
+}
+
+fn release_download_slot(state: &AppState) {
+    if let Ok(mut active) = state.active_cancel.lock() {
+        *active = None;
+    }
+    if let Ok(mut abort) = state.active_abort.lock() {
+        *abort = None;
+    }
+}
+
+/// Drives a started single-file download: forwards its progress, frees the download
+/// slot when it ends, emits the final `download-progress` event and runs the
+/// post-download hook when `hook_env` is set. `finished` gives the success message
+/// and the file written.
+fn spawn_download_task<T: Send + 'static>(
+    app: &AppHandle,
+    kind: &str,
+    handle: tokio::task::JoinHandle<anyhow::Result<T>>,
+    rx: std::sync::mpsc::Receiver<DownloadSignal>,
+    eject_root: PathBuf,
+    hook_env: Option<Vec<(&'static str, String)>>,
+    finished: impl FnOnce(&T) -> (String, PathBuf) + Send + 'static,
+) {
+    let state = app.state::<AppState>();
+    if let Ok(mut abort) = state.active_abort.lock() {
+        *abort = Some(handle.abort_handle());
+    }
+    spawn_progress_emitter(app.clone(), kind.to_string(), rx);
+    let app_for_task = app.clone();
+    let kind = kind.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = handle.await;
+        let hook_status = download_hook_status(&result);
+        let managed = app_for_task.state::<AppState>();
+        release_download_slot(&managed);
+
+        let mut hook_files = String::new();
+        let (phase, message) = match result {
+            Ok(Ok(outcome)) => {
+                let (message, destination) = finished(&outcome);
+                hook_files = destination.to_string_lossy().to_string();
+                ("batch_finished", message)
+            }
+            Ok(Err(err)) => {
+                let lower = err.to_string().to_ascii_lowercase();
+                let phase = if lower.contains("cancel") {
+                    "cancelled"
+                } else {
+                    "batch_failed"
+                };
+                (phase, err.to_string())
+            }
+            Err(join_err) => {
+                let phase = if join_err.is_cancelled() {
+                    "cancelled"
+                } else {
+                    "batch_failed"
+                };
+                (phase, join_err.to_string())
+            }
+        };
+        let _ = app_for_task.emit(
+            "download-progress",
+            DownloadProgressEvent {
+                kind: kind.clone(),
+                phase: phase.to_string(),
+                artifact: None,
+                index: None,
+                total: (phase == "batch_finished").then_some(1),
+                received: None,
+                size: None,
+                folder: None,
+                message: Some(message),
+            },
+        );
+        if phase == "batch_finished" {
+            emit_eject_status(&app_for_task, &kind, eject_root).await;
+        }
+
+        if let Some(mut env) = hook_env {
+            env.push(("ARCTIC_STATUS", hook_status.to_string()));
+            env.push(("ARCTIC_FILES", hook_files));
+            let _ = run_user_hook(&managed, HookEvent::PostDownload, env).await;
+        }
+    });
+}
+
+#[tauri::command]
+async fn download_catalog_asset(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    class: String,
+    asset_id: String,
+    comfyui_root: Option<String>,
+) -> Result<(), String> {
+    let class = AssetClass::from_identifier(&class)
+        .ok_or_else(|| format!("Unknown asset class: {class}"))?;
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let effective_root = match comfy_extra_model_config(&root) {
+        Some(config) if config.is_default => {
+            log::info!(
+                "Using extra model base path for {} downloads: {}",
+                class.identifier(),
+                config.base_path.display()
+            );
+            config.base_path
+        }
+        _ => root,
+    };
+    let asset = state
+        .context
+        .catalog
+        .find_asset(class, &asset_id)
+        .ok_or_else(|| format!("Selected {} was not found in catalog.", class.identifier()))?;
+
+    let hook_env = vec![
+        ("ARCTIC_DOWNLOAD_KIND", class.identifier().to_string()),
+        (
+            "ARCTIC_COMFYUI_ROOT",
+            effective_root.to_string_lossy().to_string(),
+        ),
+        ("ARCTIC_ASSET_ID", asset_id.clone()),
+    ];
+    let cancel = claim_download_slot(&state)?;
+    if let Err(err) = run_user_hook(&state, HookEvent::PreDownload, hook_env.clone()).await {
+        release_download_slot(&state);
+        return Err(err);
+    }
+
+    let eject_root = effective_root.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let handle = state.context.downloads.download_asset_with_cancel(
+        effective_root,
+        class,
+        asset,
+        tx,
+        Some(cancel),
+    );
+    spawn_download_task(
+        &app,
+        class.identifier(),
+        handle,
+        rx,
+        eject_root,
+        Some(hook_env),
+        |outcome| {
+            (
+                format!("{} downloaded.", outcome.asset.display_name),
+                outcome.destination.clone(),
+            )
+        },
+    );
+    Ok(())
+}
+
+#[tauri::command]
+async fn download_workflow_asset(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    workflow_id: String,
+    comfyui_root: Option<String>,
+) -> Result<(), String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let workflow: WorkflowDefinition = state
+        .context
+        .catalog
+        .find_workflow(&workflow_id)
+        .ok_or_else(|| "Selected workflow was not found in catalog.".to_string())?;
+
+    let workflows_dir = root.join("user").join("default").join("workflows");
+    std::fs::create_dir_all(&workflows_dir).map_err(|err| {
+        format!(
+            "Failed to create ComfyUI workflows directory ({}): {err}",
+            workflows_dir.display()
+        )
+    })?;
+
+    let cancel = claim_download_slot(&state)?;
+    let eject_root = workflows_dir.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let handle = state.context.downloads.download_workflow_with_cancel(
+        workflows_dir,
+        workflow,
+        tx,
+        Some(cancel),
+    );
+    spawn_download_task(&app, "workflow", handle, rx, eject_root, None, |outcome| {
+        let message = match outcome.status {
+            DownloadStatus::SkippedExisting => {
+                "Workflow already exists. Skipped download.".to_string()
+            }
+            DownloadStatus::Downloaded => "Workflow download completed.".to_string(),
+        };
+        (message, outcome.destination.clone())
+    });
+    Ok(())
+}
+
+/// How often followed Civitai creators are checked, and how often the poller wakes up
+/// to see whether a check is due.
+const CREATOR_POLL_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const CREATOR_POLL_TICK: Duration = Duration::from_secs(15 * 60);
+const CREATOR_UPDATES_FILE: &str = "creator-updates.json";
+const MAX_CREATOR_UPDATES: usize = 200;
+static CREATOR_POLL_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CreatorUpdate {
+    #[serde(flatten)]
+    release: CivitaiCreatorRelease,
+    found_at: u64,
+    /// Recomputed on every read: the base model matches a family installed in the
+    /// configured ComfyUI root.
+    #[serde(default, skip_deserializing)]
+    compatible: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct FollowedCreatorUpdates {
+    creators: Vec<FollowedCreator>,
+    updates: Vec<CreatorUpdate>,
+    installed_families: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+}
+
+fn creator_updates_path(state: &AppState) -> Option<PathBuf> {
+    state
+        .context
+        .config
+        .state_path()
+        .map(|dir| dir.join(CREATOR_UPDATES_FILE))
+}
+
+fn read_creator_updates(state: &AppState) -> Vec<CreatorUpdate> {
+    creator_updates_path(state)
+        .and_then(|path| std::fs::read(path).ok())
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn write_creator_updates(state: &AppState, updates: &[CreatorUpdate]) -> Result<(), String> {
+    let path =
+        creator_updates_path(state).ok_or_else(|| "State directory is unavailable.".to_string())?;
+    let json = serde_json::to_vec_pretty(updates).map_err(|err| err.to_string())?;
+    std::fs::write(&path, json).map_err(|err| format!("Failed to write {}: {err}", path.display()))
+}
+
+/// Catalog model families with at least one variant fully present under `root`.
+fn installed_model_families(state: &AppState, root: &Path) -> BTreeSet<String> {
+    let catalog = state.context.catalog.catalog_snapshot();
+    let model_roots = model_roots_for_comfy_root(root);
+    catalog
+        .models
+        .iter()
+        .filter(|master| {
+            master
+                .variants
+                .iter()
+                .any(|variant| installed_variant_files(&model_roots, master, variant).is_some())
+        })
+        .map(|master| master.family.clone())
+        .collect()
+}
+
+fn family_key(value: &str) -> String {
+    value
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Civitai base models read like "Flux.1 D" or "Wan Video 2.2 T2V-A14B"; one matches
+/// a catalog family when it starts with the family name once punctuation is dropped.
+fn base_model_matches_family(base_model: &str, families: &BTreeSet<String>) -> bool {
+    let base = family_key(base_model);
+    families
+        .iter()
+        .map(|family| family_key(family))
+        .any(|family| !family.is_empty() && base.starts_with(&family))
+}
+
+/// Flags updates usable with the configured root and returns the families found there.
+fn mark_compatible_updates(state: &AppState, updates: &mut [CreatorUpdate]) -> BTreeSet<String> {
+    let families = state
+        .context
+        .config
+        .settings()
+        .comfyui_root
+        .map(|root| installed_model_families(state, &root))
+        .unwrap_or_default();
+    for update in updates.iter_mut() {
+        update.compatible = update
+            .release
+            .base_model
+            .as_deref()
+            .is_some_and(|base| base_model_matches_family(base, &families));
+    }
+    families
+}
+
+fn followed_creator_updates(state: &AppState, errors: Vec<String>) -> FollowedCreatorUpdates {
+    let mut updates = read_creator_updates(state);
+    let families = mark_compatible_updates(state, &mut updates);
+    FollowedCreatorUpdates {
+        creators: state.context.config.settings().followed_creators,
+        updates,
+        installed_families: families.into_iter().collect(),
+        errors,
+    }
+}
+
+/// Accepts a bare username, `@name` or a profile link such as
+/// `https://civitai.com/user/name/models`.
+fn normalize_creator_username(value: &str) -> Option<String> {
+    let value = value.trim().trim_end_matches('/');
+    let name = match value.split_once("/user/") {
+        Some((_, rest)) => rest.split('/').next().unwrap_or_default(),
+        None => value.trim_start_matches('@'),
+    };
+    let name = name.trim();
+    (!name.is_empty() && !name.contains(['/', '?', ' '])).then(|| name.to_string())
+}
+
+/// Checks every followed creator's feed and records versions newer than the last one
+/// seen. Returns the new updates and one message per creator that could not be read.
+async fn poll_followed_creators(app: &AppHandle) -> (Vec<CreatorUpdate>, Vec<String>) {
+    if CREATOR_POLL_RUNNING.swap(true, Ordering::SeqCst) {
+        return (
+            Vec::new(),
+            vec!["Followed creators are already being checked.".to_string()],
+        );
+    }
+    let state = app.state::<AppState>();
+    let settings = state.context.config.settings();
+    let now = unix_now_secs();
+    let mut fresh = Vec::new();
+    let mut errors = Vec::new();
+    let mut checked = Vec::new();
+    for creator in &settings.followed_creators {
+        let releases = match state
+            .context
+            .downloads
+            .civitai_creator_releases(creator.username.clone(), settings.civitai_token.clone())
+            .await
+        {
+            Ok(Ok(releases)) => releases,
+            Ok(Err(err)) => {
+                log::warn!(
+                    "Failed to check Civitai creator {}: {err:#}",
+                    creator.username
+                );
+                errors.push(format!("{}: {err:#}", creator.username));
+                continue;
+            }
+            Err(err) => {
+                errors.push(format!("{}: {err}", creator.username));
+                continue;
+            }
+        };
+        let newest = releases
+            .iter()
+            .map(|release| release.version_id)
+            .chain(creator.last_seen_version_id)
+            .max();
+        if let Some(last_seen) = creator.last_seen_version_id {
+            fresh.extend(
+                releases
+                    .into_iter()
+                    .filter(|release| release.version_id > last_seen)
+                    .map(|release| CreatorUpdate {
+                        release,
+                        found_at: now,
+                        compatible: false,
+                    }),
+            );
+        }
+        checked.push((creator.username.clone(), newest));
+    }
+
+    // Match by name: the list may have been edited while the feeds were loading.
+    if let Err(err) = state.context.config.update_settings(|settings| {
+        for creator in &mut settings.followed_creators {
+            if let Some((_, newest)) = checked.iter().find(|(name, _)| *name == creator.username) {
+                creator.last_seen_version_id = *newest;
+                creator.last_checked_at = Some(now);
+            }
+        }
+    }) {
+        log::warn!("Failed to save followed creator state: {err}");
+    }
+
+    if !fresh.is_empty() {
+        mark_compatible_updates(&state, &mut fresh);
+        let mut updates = fresh.clone();
+        updates.extend(read_creator_updates(&state).into_iter().filter(|old| {
+            !fresh
+                .iter()
+                .any(|new| new.release.version_id == old.release.version_id)
+        }));
+        updates.truncate(MAX_CREATOR_UPDATES);
+        if let Err(err) = write_creator_updates(&state, &updates) {
+            log::warn!("{err}");
+        }
+        let body = match fresh.as_slice() {
+            [only] => format!(
+                "{} published {} ({}).",
+                only.release.creator, only.release.model_name, only.release.version_name
+            ),
+            _ => format!("{} new LoRAs from creators you follow.", fresh.len()),
+        };
+        let _ = app
+            .notification()
+            .builder()
+            .title("Arctic ComfyUI Helper")
+            .body(body)
+            .show();
+        let _ = app.emit("creator-updates", &fresh);
+    }
+    CREATOR_POLL_RUNNING.store(false, Ordering::SeqCst);
+    (fresh, errors)
+}
+
+/// Wakes up periodically and checks followed creators once their last check is older
+/// than [`CREATOR_POLL_INTERVAL`], so restarts do not reset the schedule.
+fn start_creator_poller(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CREATOR_POLL_TICK);
+        let now = unix_now_secs();
+        let interval = CREATOR_POLL_INTERVAL.as_secs();
+        let due = app
+            .state::<AppState>()
+            .context
+            .config
+            .settings()
+            .followed_creators
+            .iter()
+            .any(|creator| {
+                !matches!(creator.last_checked_at, Some(at) if now.saturating_sub(at) < interval)
+            });
+        if due {
+            tauri::async_runtime::block_on(poll_followed_creators(&app));
+        }
+    });
+}
+
+#[tauri::command]
+fn get_followed_creator_updates(state: State<'_, AppState>) -> FollowedCreatorUpdates {
+    followed_creator_updates(&state, Vec::new())
+}
+
+#[tauri::command]
+fn follow_civitai_creator(
+    state: State<'_, AppState>,
+    username: String,
+) -> Result<FollowedCreatorUpdates, String> {
+    let username = normalize_creator_username(&username)
+        .ok_or_else(|| "Enter a Civitai username or profile link.".to_string())?;
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            if !settings
+                .followed_creators
+                .iter()
+                .any(|creator| creator.username.eq_ignore_ascii_case(&username))
+            {
+                settings.followed_creators.push(FollowedCreator {
+                    username,
+                    last_seen_version_id: None,
+                    last_checked_at: None,
+                });
+            }
+        })
+        .map_err(|err| err.to_string())?;
+    Ok(followed_creator_updates(&state, Vec::new()))
+}
+
+#[tauri::command]
+fn unfollow_civitai_creator(
+    state: State<'_, AppState>,
+    username: String,
+) -> Result<FollowedCreatorUpdates, String> {
+    state
+        .context
+        .config
+        .update_settings(|settings| {
+            settings
+                .followed_creators
+                .retain(|creator| !creator.username.eq_ignore_ascii_case(&username))
+        })
+        .map_err(|err| err.to_string())?;
+    let mut updates = read_creator_updates(&state);
+    let before = updates.len();
+    updates.retain(|update| !update.release.creator.eq_ignore_ascii_case(&username));
+    if updates.len() != before {
+        write_creator_updates(&state, &updates)?;
+    }
+    Ok(followed_creator_updates(&state, Vec::new()))
+}
+
+/// Drops the listed updates, or all of them when `version_ids` is omitted.
+#[tauri::command]
+fn dismiss_creator_updates(
+    state: State<'_, AppState>,
+    version_ids: Option<Vec<u64>>,
+) -> Result<FollowedCreatorUpdates, String> {
+    let mut updates = read_creator_updates(&state);
+    match version_ids {
+        Some(ids) => updates.retain(|update| !ids.contains(&update.release.version_id)),
+        None => updates.clear(),
+    }
+    write_creator_updates(&state, &updates)?;
+    Ok(followed_creator_updates(&state, Vec::new()))
+}
+
+#[tauri::command]
+async fn check_followed_creators(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<FollowedCreatorUpdates, String> {
+    if state.context.config.settings().followed_creators.is_empty() {
+        return Err("Follow a Civitai creator first.".to_string());
+    }
+    let (_, errors) = poll_followed_creators(&app).await;
+    Ok(followed_creator_updates(&state, errors))
+}
+
+#[tauri::command]
+async fn download_creator_update(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    version_id: u64,
+    token: Option<String>,
+    comfyui_root: Option<String>,
+) -> Result<(), String> {
+    let release = read_creator_updates(&state)
+        .into_iter()
+        .find(|update| update.release.version_id == version_id)
+        .map(|update| update.release)
+        .ok_or_else(|| "That creator update is no longer listed.".to_string())?;
+    let lora = LoraDefinition {
+        id: format!("civitai-{}", release.version_id),
+        display_name: format!("{} ({})", release.model_name, release.version_name),
+        family: release.base_model,
+        download_url: release.download_url,
+        note: None,
+        file_name: release.file_name,
+        favorite: false,
+    };
+    start_lora_download(app, state, comfyui_root, lora, token).await
+}
+
+#[tauri::command]
+async fn get_lora_metadata(
+    state: State<'_, AppState>,
+    lora_id: String,
+    token: Option<String>,
+) -> Result<LoraMetadataResponse, String> {
+    let lora: LoraDefinition = state
+        .context
+        .catalog
+        .find_lora(&lora_id)
+        .ok_or_else(|| "Selected LoRA was not found in catalog.".to_string())?;
+
+    if !lora
+        .download_url
+        .to_ascii_lowercase()
+        .contains("civitai.com")
+    {
+        return Ok(LoraMetadataResponse {
+            creator: "N/A".to_string(),
+            creator_url: None,
+            strength: "N/A".to_string(),
+            triggers: Vec::new(),
+            description: lora
+                .note
+                .unwrap_or_else(|| "Metadata is available for Civitai LoRAs only.".to_string()),
+            preview_url: None,
+            preview_kind: "none".to_string(),
+            preview_cached: false,
+        });
+    }
+
+    let result = state
+        .context
+        .downloads
+        .civitai_model_metadata(lora.download_url.clone(), token)
+        .await;
+
+    match result {
+        Ok(Ok(metadata)) => {
+            let (preview_kind, preview_url) = match metadata.preview {
+                Some(CivitaiPreview::Video { url }) => ("video".to_string(), Some(url)),
+                Some(CivitaiPreview::Image(_)) => (
+                    if metadata
+                        .preview_url
+                        .as_deref()
+                        .map(is_video_url)
+                        .unwrap_or(false)
+                    {
+                        "video".to_string()
+                    } else {
+                        "image".to_string()
+                    },
+                    metadata.preview_url.clone(),
+                ),
+                None => (
+                    if metadata
+                        .preview_url
+                        .as_deref()
+                        .map(is_video_url)
+                        .unwrap_or(false)
+                    {
+                        "video".to_string()
+                    } else {
+                        "none".to_string()
+                    },
+                    metadata.preview_url.clone(),
+                ),
+            };
+
+            Ok(LoraMetadataResponse {
+                creator: metadata
+                    .creator_username
+                    .unwrap_or_else(|| "Unknown creator".to_string()),
+                creator_url: metadata.creator_link,
+                strength: metadata
+                    .usage_strength
+                    .map(|value| format!("{value:.2}"))
+                    .unwrap_or_else(|| "Not provided".to_string()),
+                triggers: metadata.trained_words,
+                description: metadata
+                    .description
+                    .map(|text| strip_html_tags(&text))
+                    .filter(|text| !text.trim().is_empty())
+                    .unwrap_or_else(|| "No description available.".to_string()),
+                preview_cached: state
+                    .context
+                    .downloads
+                    .cached_lora_preview(&lora.download_url)
+                    .is_some(),
+                preview_url,
+                preview_kind,
+            })
+        }
+        Ok(Err(err)) => Err(format!("Failed to load LoRA metadata: {err:#}")),
+        Err(join_err) => Err(format!("LoRA metadata task failed: {join_err}")),
+    }
+}
+
+#[tauri::command]
+fn get_lora_metadata_queue_position(state: State<'_, AppState>, lora_id: String) -> Option<usize> {
+    let lora = state.context.catalog.find_lora(&lora_id)?;
+    state
+        .context
+        .downloads
+        .civitai_metadata_queue_position(&lora.download_url)
+}
+
+/// Raw bytes of a LoRA's preview media, fetched into the preview cache on first use.
+/// Fails when the media is over the cache's size cap, so the webview streams it from
+/// `preview_url` instead. `prefetch` only fills the cache and returns no bytes.
+#[tauri::command]
+async fn get_lora_preview_media(
+    state: State<'_, AppState>,
+    lora_id: String,
+    token: Option<String>,
+    prefetch: Option<bool>,
+) -> Result<tauri::ipc::Response, String> {
+    let lora = state
+        .context
+        .catalog
+        .find_lora(&lora_id)
+        .ok_or_else(|| "Selected LoRA was not found in catalog.".to_string())?;
+    // Served from the in-memory metadata cache once get_lora_metadata has run.
+    let metadata = state
+        .context
+        .downloads
+        .civitai_model_metadata(lora.download_url.clone(), token.clone())
+        .await
+        .map_err(|err| format!("LoRA metadata task failed: {err}"))?
+        .map_err(|err| format!("Failed to load LoRA metadata: {err:#}"))?;
+    let preview_url = metadata
+        .preview_url
+        .ok_or_else(|| "This LoRA has no preview.".to_string())?;
+    let cached = state
+        .context
+        .downloads
+        .lora_preview_media(lora.download_url, preview_url, token)
+        .await
+        .map_err(|err| format!("Preview task failed: {err}"))?
+        .map_err(|err| format!("Failed to fetch preview: {err:#}"))?
+        .ok_or_else(|| "Preview is too large to cache; stream it from its URL.".to_string())?;
+    if prefetch.unwrap_or(false) {
+        return Ok(tauri::ipc::Response::new(Vec::new()));
+    }
+    let bytes = std::fs::read(&cached.path)
+        .map_err(|err| format!("Failed to read {}: {err}", cached.path.display()))?;
+    Ok(tauri::ipc::Response::new(bytes))
+}
+
+#[derive(Debug, Serialize)]
+struct OrphanedModelEntry {
+    path: String,
+    category: String,
+    file_name: String,
+    size_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct OrphanCleanupResponse {
+    orphans: Vec<OrphanedModelEntry>,
+    reclaimable_bytes: u64,
+    deleted: Vec<String>,
+    failed: Vec<String>,
+    freed_bytes: u64,
+}
+
+fn model_roots_for_comfy_root(comfy_root: &Path) -> Vec<PathBuf> {
+    let mut roots = vec![comfy_root.to_path_buf()];
+    if let Some(config) = comfy_extra_model_config(comfy_root) {
+        if config.base_path != comfy_root {
+            roots.push(config.base_path);
+        }
+    }
+    roots
+}
+
+#[tauri::command]
+fn cleanup_orphaned_models(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    delete_paths: Option<Vec<String>>,
+    confirm: Option<bool>,
+) -> Result<OrphanCleanupResponse, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let model_roots = model_roots_for_comfy_root(&root);
+    let catalog = state.context.catalog.catalog_snapshot();
+    let mut orphans = find_orphaned_models(&root, &model_roots, &catalog);
+
+    let mut deleted = Vec::new();
+    let mut failed = Vec::new();
+    let mut freed_bytes = 0u64;
+    let requested = delete_paths.unwrap_or_default();
+    if !requested.is_empty() {
+        if !confirm.unwrap_or(false) {
+            return Err("Deleting orphaned models requires confirmation.".to_string());
+        }
+        for raw in requested {
+            // Only files reported as orphaned by this scan may be deleted.
+            let Some(pos) = orphans
+                .iter()
+                .position(|entry| entry.path.to_string_lossy() == raw.trim())
+            else {
+                failed.push(format!("{raw}: not an orphaned model file"));
+                continue;
+            };
+            let entry = orphans.remove(pos);
+            let stop_at = model_roots
+                .iter()
+                .map(|base| base.join("models"))
+                .find(|models| entry.path.starts_with(models))
+                .unwrap_or_else(|| root.join("models"));
+            match remove_model_file(&entry.path, &stop_at) {
+                Ok(size) => {
+                    freed_bytes += size;
+                    log::info!("Removed orphaned model file {}", entry.path.display());
+                    deleted.push(entry.path.to_string_lossy().to_string());
+                }
+                Err(err) => {
+                    failed.push(format!("{}: {err:#}", entry.path.display()));
+                    orphans.push(entry);
+                }
+            }
+        }
+    }
+
+    let reclaimable_bytes = orphans.iter().map(|entry| entry.size_bytes).sum();
+    Ok(OrphanCleanupResponse {
+        orphans: orphans
+            .into_iter()
+            .map(|entry| OrphanedModelEntry {
+                path: entry.path.to_string_lossy().to_string(),
+                category: entry.category,
+                file_name: entry.file_name,
+                size_bytes: entry.size_bytes,
+            })
+            .collect(),
+        reclaimable_bytes,
+        deleted,
+        failed,
+        freed_bytes,
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct AssetRemovalResponse {
+    removed: Vec<String>,
+    kept: Vec<String>,
+    missing: Vec<String>,
+    /// Files that could not be removed, with the reason.
+    failed: Vec<String>,
+    freed_bytes: u64,
+}
+
+fn ensure_no_active_download(state: &AppState) -> Result<(), String> {
+    let active = state
+        .active_cancel
+        .lock()
+        .map_err(|_| "download state lock poisoned".to_string())?;
+    if active.is_some() {
+        return Err("A download is already active. Cancel it first.".to_string());
+    }
+    Ok(())
+}
+
+/// Removes one file and records the outcome; a failure goes into the report so the
+/// remaining files are still removed.
+fn remove_asset_file(path: &Path, model_roots: &[PathBuf], response: &mut AssetRemovalResponse) {
+    let stop_at = model_roots
+        .iter()
+        .map(|base| base.join("models"))
+        .find(|models| path.starts_with(models))
+        .unwrap_or_else(|| path.parent().unwrap_or(path).to_path_buf());
+    match remove_model_file(path, &stop_at) {
+        Ok(size) => {
+            log::info!("Removed {}", path.display());
+            response.freed_bytes += size;
+            response.removed.push(path.to_string_lossy().to_string());
+        }
+        Err(err) => {
+            log::warn!("Failed to remove {}: {err:#}", path.display());
+            response.failed.push(format!("{}: {err:#}", path.display()));
+        }
+    }
+}
+
+#[tauri::command]
+fn remove_model_assets(
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+    comfyui_root: Option<String>,
+) -> Result<AssetRemovalResponse, String> {
+    ensure_no_active_download(&state)?;
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let model_roots = model_roots_for_comfy_root(&root);
+    let resolved = state
+        .context
+        .catalog
+        .resolve_variant(&model_id, &variant_id)
+        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+    let master = &resolved.master;
+
+    let installed_paths = |artifact: &ModelArtifact| -> Vec<PathBuf> {
+        model_roots
+            .iter()
+            .map(|base| artifact_destination_dir(base, master, artifact).join(artifact.file_name()))
+            .filter(|path| path.is_file())
+            .collect()
+    };
+    let same_file = |a: &ModelArtifact, b: &ModelArtifact| {
+        a.target_category.slug() == b.target_category.slug() && a.file_name() == b.file_name()
+    };
+
+    // Files shared with another variant of this model stay as long as that variant is
+    // installed: it still has files of its own on disk, or every file it needs is there.
+    // The latter covers lighter variants whose files are a subset of this one's.
+    let other_installed: Vec<&ModelVariant> = master
+        .variants
+        .iter()
+        .filter(|variant| variant.id != resolved.variant.id)
+        .filter(|variant| {
+            let has_own_files = variant
+                .artifacts
+                .iter()
+                .filter(|artifact| {
+                    !resolved
+                        .variant
+                        .artifacts
+                        .iter()
+                        .any(|own| same_file(own, artifact))
+                })
+                .any(|artifact| !installed_paths(artifact).is_empty());
+            let complete = !variant.artifacts.is_empty()
+                && variant
+                    .artifacts
+                    .iter()
+                    .all(|artifact| !installed_paths(artifact).is_empty());
+            has_own_files || complete
+        })
+        .collect();
+
+    let mut response = AssetRemovalResponse {
+        removed: Vec::new(),
+        kept: Vec::new(),
+        missing: Vec::new(),
+        failed: Vec::new(),
+        freed_bytes: 0,
+    };
+    let mut seen: Vec<&ModelArtifact> = Vec::new();
+    let candidates = master
+        .always
+        .iter()
+        .flat_map(|group| group.artifacts.iter())
+        .chain(resolved.variant.artifacts.iter());
+    for artifact in candidates {
+        if seen.iter().any(|prev| same_file(prev, artifact)) {
+            continue;
+        }
+        seen.push(artifact);
+
+        let paths = installed_paths(artifact);
+        if paths.is_empty() {
+            response.missing.push(artifact.file_name().to_string());
+            continue;
+        }
+        let shared_with = other_installed.iter().find(|variant| {
+            let in_always = master
+                .always
+                .iter()
+                .any(|group| group.artifacts.iter().any(|a| same_file(a, artifact)));
+            in_always || variant.artifacts.iter().any(|a| same_file(a, artifact))
+        });
+        if let Some(variant) = shared_with {
+            response.kept.push(format!(
+                "{} (still used by {})",
+                artifact.file_name(),
+                variant.selection_label()
+            ));
+            continue;
+        }
+        for path in paths {
+            remove_asset_file(&path, &model_roots, &mut response);
+        }
+    }
+
+    Ok(response)
+}
+
+/// Lower-quant files are moved here instead of deleted when the user picks "archive".
+/// ComfyUI only scans the category folders, so they drop out of its model lists.
+const SUPERSEDED_ARCHIVE_DIR: &str = ".arctic-archive";
+
+/// A file left behind by a lower quantization of a model that is now installed at a
+/// higher one.
+#[derive(Clone, Debug, Serialize)]
+struct SupersededArtifact {
+    path: String,
+    file_name: String,
+    category: String,
+    quantization: Option<String>,
+    variant: String,
+    size_bytes: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SupersededArtifactsReport {
+    model_id: String,
+    variant_id: String,
+    comfyui_root: String,
+    replacement: String,
+    artifacts: Vec<SupersededArtifact>,
+    reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct SupersededCleanupResponse {
+    deleted: Vec<String>,
+    archived: Vec<String>,
+    failed: Vec<String>,
+    freed_bytes: u64,
+}
+
+/// Superseded siblings of `resolved` that are on disk. Empty unless every quantized
+/// file of `resolved` itself is installed, so nothing is offered for removal before
+/// its replacement is actually there.
+fn superseded_artifacts_report(
+    comfy_root: &Path,
+    resolved: &ResolvedModel,
+) -> SupersededArtifactsReport {
+    let model_roots = model_roots_for_comfy_root(comfy_root);
+    let master = &resolved.master;
+    let installed_paths = |artifact: &ModelArtifact| -> Vec<PathBuf> {
+        model_roots
+            .iter()
+            .map(|base| artifact_destination_dir(base, master, artifact).join(artifact.file_name()))
+            .filter(|path| path.is_file())
+            .collect()
+    };
+
+    let replacement_installed = resolved
+        .variant
+        .artifacts
+        .iter()
+        .filter(|artifact| quantization_from_file_name(artifact.file_name()).is_some())
+        .all(|artifact| !installed_paths(artifact).is_empty());
+    let mut artifacts = Vec::new();
+    if replacement_installed {
+        for (variant, artifact) in master.superseded_by(&resolved.variant) {
+            for path in installed_paths(artifact) {
+                artifacts.push(SupersededArtifact {
+                    size_bytes: std::fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0),
+                    path: path.to_string_lossy().to_string(),
+                    file_name: artifact.file_name().to_string(),
+                    category: artifact.target_category.slug().to_string(),
+                    quantization: quantization_from_file_name(artifact.file_name()),
+                    variant: variant.selection_label(),
+                });
+            }
+        }
+    }
+
+    SupersededArtifactsReport {
+        model_id: master.id.clone(),
+        variant_id: resolved.variant.id.clone(),
+        comfyui_root: comfy_root.to_string_lossy().to_string(),
+        replacement: resolved.variant.selection_label(),
+        reclaimable_bytes: artifacts.iter().map(|artifact| artifact.size_bytes).sum(),
+        artifacts,
+    }
+}
+
+/// Moves `path` from `<base>/models/<category>/...` to the same place under
+/// `<base>/models/.arctic-archive/`.
+fn archive_superseded_file(path: &Path, model_roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let (models, relative) = model_roots
+        .iter()
+        .map(|base| base.join("models"))
+        .find_map(|models| {
+            let relative = path.strip_prefix(&models).ok()?.to_path_buf();
+            Some((models, relative))
+        })
+        .ok_or_else(|| format!("{} is outside the models folders", path.display()))?;
+    let target = models.join(SUPERSEDED_ARCHIVE_DIR).join(relative);
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+    }
+    std::fs::rename(path, &target).map_err(|err| {
+        format!(
+            "failed to move {} to {}: {err}",
+            path.display(),
+            target.display()
+        )
+    })?;
+    Ok(target)
+}
+
+#[tauri::command]
+fn find_superseded_artifacts(
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+    comfyui_root: Option<String>,
+) -> Result<SupersededArtifactsReport, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let resolved = state
+        .context
+        .catalog
+        .resolve_variant(&model_id, &variant_id)
+        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+    Ok(superseded_artifacts_report(&root, &resolved))
+}
+
+/// Deletes or archives files reported by [`find_superseded_artifacts`]. The candidates
+/// are recomputed here, so only paths that are still superseded are touched.
+#[tauri::command]
+fn resolve_superseded_artifacts(
+    state: State<'_, AppState>,
+    model_id: String,
+    variant_id: String,
+    comfyui_root: Option<String>,
+    paths: Vec<String>,
+    action: String,
+) -> Result<SupersededCleanupResponse, String> {
+    let archive = match action.trim() {
+        "delete" => false,
+        "archive" => true,
+        other => return Err(format!("Unknown action '{other}'; use delete or archive.")),
+    };
+    ensure_no_active_download(&state)?;
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let model_roots = model_roots_for_comfy_root(&root);
+    let resolved = state
+        .context
+        .catalog
+        .resolve_variant(&model_id, &variant_id)
+        .ok_or_else(|| "Selected model variant was not found in catalog.".to_string())?;
+    let report = superseded_artifacts_report(&root, &resolved);
+
+    let mut response = SupersededCleanupResponse {
+        deleted: Vec::new(),
+        archived: Vec::new(),
+        failed: Vec::new(),
+        freed_bytes: 0,
+    };
+    for raw in paths {
+        let Some(candidate) = report
+            .artifacts
+            .iter()
+            .find(|artifact| artifact.path == raw.trim())
+        else {
+            response
+                .failed
+                .push(format!("{raw}: not a superseded file of this model"));
+            continue;
+        };
+        let path = PathBuf::from(&candidate.path);
+        if archive {
+            match archive_superseded_file(&path, &model_roots) {
+                Ok(target) => {
+                    log::info!("Archived {} to {}", path.display(), target.display());
+                    response.archived.push(candidate.path.clone());
+                }
+                Err(err) => response.failed.push(err),
+            }
+            continue;
+        }
+        let stop_at = model_roots
+            .iter()
+            .map(|base| base.join("models"))
+            .find(|models| path.starts_with(models))
+            .unwrap_or_else(|| root.join("models"));
+        match remove_model_file(&path, &stop_at) {
+            Ok(size) => {
+                log::info!("Removed superseded model file {}", path.display());
+                response.freed_bytes += size;
+                response.deleted.push(candidate.path.clone());
+            }
+            Err(err) => response.failed.push(format!("{}: {err:#}", path.display())),
+        }
+    }
+    Ok(response)
+}
+
+#[tauri::command]
+async fn remove_lora_asset(
+    state: State<'_, AppState>,
+    lora_id: String,
+    token: Option<String>,
+    comfyui_root: Option<String>,
+) -> Result<AssetRemovalResponse, String> {
+    ensure_no_active_download(&state)?;
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let model_roots = model_roots_for_comfy_root(&root);
+    let lora = state
+        .context
+        .catalog
+        .find_lora(&lora_id)
+        .ok_or_else(|| "Selected LoRA was not found in catalog.".to_string())?;
+
+    // Civitai downloads are saved under the name reported by the API, not the URL.
+    let mut file_names = vec![lora.derived_file_name()];
+    if lora.download_url.contains("civitai.com") {
+        let token = token.or_else(|| state.context.config.settings().civitai_token);
+        if let Ok(Ok(metadata)) = state
+            .context
+            .downloads
+            .civitai_model_metadata(lora.download_url.clone(), token)
+            .await
+        {
+            file_names.insert(0, metadata.file_name);
+        }
+    }
+
+    let mut response = AssetRemovalResponse {
+        removed: Vec::new(),
+        kept: Vec::new(),
+        missing: Vec::new(),
+        failed: Vec::new(),
+        freed_bytes: 0,
+    };
+    let others: Vec<LoraDefinition> = state
+        .context
+        .catalog
+        .loras()
+        .into_iter()
+        .filter(|other| other.id != lora.id)
+        .collect();
+    let mut found = false;
+    for base in &model_roots {
+        for file_name in &file_names {
+            let path = lora_destination(base, &lora, file_name);
+            if !path.is_file() {
+                continue;
+            }
+            found = true;
+            let shared_with = others
+                .iter()
+                .find(|other| lora_destination(base, other, &other.derived_file_name()) == path);
+            if let Some(other) = shared_with {
+                response.kept.push(format!(
+                    "{} (still used by {})",
+                    path.display(),
+                    other.display_name
+                ));
+                continue;
+            }
+            remove_asset_file(&path, &model_roots, &mut response);
+        }
+    }
+    if !found {
+        response.missing.push(lora.derived_file_name());
+    }
+
+    Ok(response)
+}
+
+fn resolve_root_path(
+    context: &AppContext,
+    comfyui_root: Option<String>,
+) -> Result<std::path::PathBuf, String> {
+    fn normalize_existing(path: std::path::PathBuf) -> Option<std::path::PathBuf> {
+        let absolute = if path.is_absolute() {
+            path
+        } else if let Ok(cwd) = std::env::current_dir() {
+            cwd.join(path)
+        } else {
+            path
+        };
+        if !absolute.exists() {
+            return None;
+        }
+        let canonical = std::fs::canonicalize(&absolute).ok().or(Some(absolute))?;
+        Some(normalize_canonical_path(&canonical).to_path_buf())
+    }
+
+    if let Some(root) = comfyui_root {
+        let trimmed = root.trim();
+        if !trimmed.is_empty() {
+            let path = std::path::PathBuf::from(trimmed);
+            if let Some(normalized) = normalize_existing(path) {
+                return Ok(normalized);
+            }
+        }
+    }
+
+    if let Some(path) = context.config.settings().comfyui_root {
+        if let Some(normalized) = normalize_existing(path) {
+            return Ok(normalized);
+        }
+    }
+
+    Err("Select a valid ComfyUI root folder first.".to_string())
+}
+
+fn parse_yaml_scalar(value: &str) -> String {
+    let trimmed = value.trim();
+    if trimmed.len() >= 2 {
+        let single = trimmed.starts_with('\'') && trimmed.ends_with('\'');
+        let double = trimmed.starts_with('"') && trimmed.ends_with('"');
+        if single || double {
+            let inner = &trimmed[1..trimmed.len() - 1];
+            if single {
+                return inner.replace("''", "'");
+            }
+            return inner.replace("\\\"", "\"");
+        }
+    }
+    trimmed.to_string()
+}
+
+fn parse_yaml_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ComfyExtraModelConfig {
+    base_path: PathBuf,
+    is_default: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyExtraModelConfigResponse {
+    configured: bool,
+    base_path: Option<String>,
+    use_as_default: bool,
+}
+
+fn comfy_extra_model_config(comfy_root: &Path) -> Option<ComfyExtraModelConfig> {
+    let path = comfy_root.join("extra_model_paths.yaml");
+    let content = std::fs::read_to_string(path).ok()?;
+    let mut in_comfyui = false;
+    let mut base_path: Option<PathBuf> = None;
+    let mut is_default = false;
+
+    for line in content.lines() {
+        let without_comment = line.split('#').next().unwrap_or_default();
+        if without_comment.trim().is_empty() {
+            continue;
+        }
+
+        let indent = without_comment
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+        let trimmed = without_comment.trim();
+
+        if trimmed == "comfyui:" {
+            in_comfyui = true;
+            continue;
+        }
+
+        if in_comfyui {
+            if let Some(raw) = trimmed.strip_prefix("base_path:") {
+                let scalar = parse_yaml_scalar(raw);
+                if !scalar.trim().is_empty() {
+                    let parsed = PathBuf::from(scalar.trim());
+                    let resolved = if parsed.is_absolute() {
+                        parsed
+                    } else {
+                        comfy_root.join(parsed)
+                    };
+                    base_path = Some(normalize_canonical_path(&resolved));
+                }
+                continue;
+            }
+
+            if let Some(raw) = trimmed.strip_prefix("is_default:") {
+                let scalar = parse_yaml_scalar(raw);
+                if let Some(parsed) = parse_yaml_bool(&scalar) {
+                    is_default = parsed;
+                }
+                continue;
+            }
+        }
+
+        if indent == 0 && trimmed.ends_with(':') {
+            in_comfyui = false;
+            continue;
+        }
+
+        if !in_comfyui {
+            continue;
+        }
+
+        if let Some(raw) = trimmed.strip_prefix("base_path:") {
+            let scalar = parse_yaml_scalar(raw);
+            if scalar.trim().is_empty() {
+                continue;
+            }
+            let parsed = PathBuf::from(scalar.trim());
+            let resolved = if parsed.is_absolute() {
+                parsed
+            } else {
+                comfy_root.join(parsed)
+            };
+            base_path = Some(normalize_canonical_path(&resolved));
+            continue;
+        }
+    }
+
+    base_path.map(|base| ComfyExtraModelConfig {
+        base_path: base,
+        is_default,
+    })
+}
+
+fn comfyui_instance_name_from_path(path: &Path) -> String {
+    path.file_name()
+        .and_then(|value| value.to_str())
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .unwrap_or("ComfyUI")
+        .to_string()
+}
+
+fn resolve_comfyui_instance_name(context: &AppContext, comfyui_root: Option<String>) -> String {
+    resolve_root_path(context, comfyui_root)
+        .ok()
+        .as_deref()
+        .map(comfyui_instance_name_from_path)
+        .unwrap_or_else(|| "ComfyUI".to_string())
+}
+
+fn parse_ram_tier(value: &str) -> Option<RamTier> {
+    RamTier::from_identifier(value)
+}
+
+/// Model thresholds with the user's settings overrides applied on top.
+fn effective_ram_thresholds(state: &AppState, master: &MasterModel) -> ResolvedRamTierThresholds {
+    master
+        .resolved_ram_thresholds()
+        .with_user_overrides(state.context.config.settings().ram_tier_thresholds.as_ref())
+}
+
+fn detected_ram_tier(state: &AppState, master: &MasterModel) -> Option<RamTier> {
+    let total_gb = state
+        .context
+        .total_ram_gb()
+        .or_else(|| detect_ram_profile().map(|profile| profile.total_gb))?;
+    Some(effective_ram_thresholds(state, master).tier_for(total_gb))
+}
+
+fn is_video_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.ends_with(".mp4")
+        || lower.ends_with(".webm")
+        || lower.ends_with(".mov")
+        || lower.contains(".mp4?")
+        || lower.contains(".webm?")
+        || lower.contains(".mov?")
+}
+
+fn strip_html_tags(input: &str) -> String {
+    let mut raw = String::with_capacity(input.len());
+    let mut in_tag = false;
+    for ch in input.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                raw.push(' ');
+            }
+            '>' => {}
+            _ if !in_tag => raw.push(ch),
+            _ => {}
+        }
+    }
+    raw.replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn detect_existing_comfyui_root(path: &Path) -> Option<PathBuf> {
+    if path.join("main.py").is_file() {
+        return Some(path.to_path_buf());
+    }
+
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    let entries = std::fs::read_dir(path).ok()?;
+    for entry in entries.flatten() {
+        let child = entry.path();
+        if !child.is_dir() {
+            continue;
+        }
+        let name = child
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !name.starts_with("comfyui") {
+            continue;
+        }
+        if child.join("main.py").is_file() {
+            candidates.push(child);
+        }
+    }
+
+    if candidates.is_empty() {
+        return None;
+    }
+    candidates.sort_by(|a, b| {
+        let an = a.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let bn = b.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        an.cmp(bn)
+    });
+    candidates.into_iter().next()
+}
+
+const INSTALL_MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InstallManifest {
+    manifest_version: u32,
+    app_version: String,
+    exported_at: u64,
+    comfyui_root: String,
+    #[serde(default)]
+    torch_profile: Option<String>,
+    #[serde(default)]
+    attention_backend: Option<String>,
+    #[serde(default)]
+    models: Vec<ManifestModel>,
+    #[serde(default)]
+    loras: Vec<ManifestLora>,
+    #[serde(default)]
+    custom_nodes: Vec<ManifestCustomNode>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ManifestModel {
+    model_id: String,
+    variant_id: String,
+    tier: String,
+    files: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ManifestLora {
+    lora_id: String,
+    file_name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ManifestCustomNode {
+    name: String,
+    #[serde(default)]
+    repo_url: Option<String>,
+    #[serde(default)]
+    commit: Option<String>,
+}
+
+/// Files of a variant found on disk, or `None` unless every artifact that is not
+/// RAM-tier specific is present.
+fn installed_variant_files(
+    model_roots: &[PathBuf],
+    master: &MasterModel,
+    variant: &ModelVariant,
+) -> Option<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for artifact in &variant.artifacts {
+        let found = model_roots
+            .iter()
+            .map(|base| artifact_destination_dir(base, master, artifact).join(artifact.file_name()))
+            .find(|path| path.is_file());
+        match found {
+            Some(path) => files.push(path),
+            None if artifact.min_ram_tier.is_some() => {}
+            None => return None,
+        }
+    }
+    if files.is_empty() {
+        None
+    } else {
+        Some(files)
+    }
+}
+
+fn manifest_custom_nodes(root: &Path) -> Vec<ManifestCustomNode> {
+    let Ok(entries) = std::fs::read_dir(root.join("custom_nodes")) else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<ManifestCustomNode> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name == "__pycache__" {
+                return None;
+            }
+            let dir = entry.path();
+            let (repo_url, commit) = if dir.join(".git").exists() {
+                let repo_url = run_command_capture(
+                    "git",
+                    &["config", "--get", "remote.origin.url"],
+                    Some(&dir),
+                )
+                .ok()
+                .map(|(stdout, _)| stdout.trim().to_string())
+                .filter(|url| !url.is_empty());
+                (repo_url, git_commit_for_ref(&dir, "HEAD"))
+            } else {
+                (None, None)
+            };
+            Some(ManifestCustomNode {
+                name,
+                repo_url,
+                commit,
+            })
+        })
+        .collect();
+    nodes.sort_by(|a, b| {
+        a.name
+            .to_ascii_lowercase()
+            .cmp(&b.name.to_ascii_lowercase())
+    });
+    nodes
+}
+
+fn build_install_manifest(state: &AppState, root: &Path) -> InstallManifest {
+    let catalog = state.context.catalog.catalog_snapshot();
+    let model_roots = model_roots_for_comfy_root(root);
+
+    let mut models = Vec::new();
+    for master in &catalog.models {
+        for variant in &master.variants {
+            if let Some(files) = installed_variant_files(&model_roots, master, variant) {
+                models.push(ManifestModel {
+                    model_id: master.id.clone(),
+                    variant_id: variant.id.clone(),
+                    tier: variant.tier.identifier().to_string(),
+                    files: files
+                        .iter()
+                        .map(|path| path.to_string_lossy().to_string())
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    let loras = catalog
+        .loras
+        .iter()
+        .filter_map(|lora| {
+            let file_name = lora.derived_file_name();
+            model_roots
+                .iter()
+                .any(|base| lora_destination(base, lora, &file_name).is_file())
+                .then(|| ManifestLora {
+                    lora_id: lora.id.clone(),
+                    file_name,
+                })
+        })
+        .collect();
+
+    InstallManifest {
+        manifest_version: INSTALL_MANIFEST_VERSION,
+        app_version: state.context.display_version.clone(),
+        exported_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0),
+        comfyui_root: root.to_string_lossy().to_string(),
+        torch_profile: detect_torch_profile_for_root(root),
+        attention_backend: detect_launch_attention_backend_for_root(root),
+        models,
+        loras,
+        custom_nodes: manifest_custom_nodes(root),
+    }
+}
+
+#[tauri::command]
+fn export_manifest(
+    state: State<'_, AppState>,
+    path: String,
+    comfyui_root: Option<String>,
+) -> Result<String, String> {
+    let target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err("Manifest path is empty.".to_string());
+    }
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let manifest = build_install_manifest(&state, &root);
+    let data = serde_json::to_vec_pretty(&manifest)
+        .map_err(|err| format!("Failed to serialize manifest: {err}"))?;
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create {}: {err}", parent.display()))?;
+    }
+    std::fs::write(&target, data)
+        .map_err(|err| format!("Failed to write manifest to {}: {err}", target.display()))?;
+    log::info!(
+        "Exported manifest for {} ({} models, {} LoRAs, {} custom nodes) to {}",
+        root.display(),
+        manifest.models.len(),
+        manifest.loras.len(),
+        manifest.custom_nodes.len(),
+        target.display()
+    );
+    Ok(target.to_string_lossy().to_string())
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestImportPlan {
+    dry_run: bool,
+    queued: bool,
+    models: Vec<ManifestModel>,
+    loras: Vec<ManifestLora>,
+    custom_nodes: Vec<ManifestCustomNode>,
+    already_present: usize,
+    skipped: Vec<String>,
+    warnings: Vec<String>,
+}
+
+fn emit_manifest_progress(app: &AppHandle, phase: &str, message: String) {
+    let _ = app.emit(
+        "download-progress",
+        DownloadProgressEvent {
+            kind: "manifest".to_string(),
+            phase: phase.to_string(),
+            artifact: None,
+            index: None,
+            total: None,
+            received: None,
+            size: None,
+            folder: None,
+            message: Some(message),
+        },
+    );
+}
+
+fn plan_manifest_import(
+    state: &AppState,
+    root: &Path,
+    manifest: &InstallManifest,
+    dry_run: bool,
+) -> ManifestImportPlan {
+    let catalog = state.context.catalog.catalog_snapshot();
+    let model_roots = model_roots_for_comfy_root(root);
+    let mut plan = ManifestImportPlan {
+        dry_run,
+        queued: false,
+        models: Vec::new(),
+        loras: Vec::new(),
+        custom_nodes: Vec::new(),
+        already_present: 0,
+        skipped: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    for entry in &manifest.models {
+        let variant = catalog.find_model(&entry.model_id).and_then(|master| {
+            master
+                .variants
+                .iter()
+                .find(|v| v.id == entry.variant_id && v.tier.identifier() == entry.tier)
+                .map(|variant| (master, variant))
+        });
+        match variant {
+            None => plan.skipped.push(format!(
+                "Model {}/{} ({}) is not in the current catalog.",
+                entry.model_id, entry.variant_id, entry.tier
+            )),
+            Some((master, variant)) => {
+                if installed_variant_files(&model_roots, master, variant).is_some() {
+                    plan.already_present += 1;
+                } else {
+                    plan.models.push(entry.clone());
+                }
+            }
+        }
+    }
+
+    for entry in &manifest.loras {
+        match catalog.find_lora(&entry.lora_id) {
+            None => plan.skipped.push(format!(
+                "LoRA {} is not in the current catalog.",
+                entry.lora_id
+            )),
+            Some(lora) => {
+                let present = model_roots.iter().any(|base| {
+                    lora_destination(base, &lora, &entry.file_name).is_file()
+                        || lora_destination(base, &lora, &lora.derived_file_name()).is_file()
+                });
+                if present {
+                    plan.already_present += 1;
+                } else {
+                    plan.loras.push(entry.clone());
+                }
+            }
+        }
+    }
+
+    for node in &manifest.custom_nodes {
+        let mut components = Path::new(&node.name).components();
+        let single_folder = matches!(components.next(), Some(std::path::Component::Normal(_)))
+            && components.next().is_none()
+            && !node.name.starts_with('.');
+        if !single_folder {
+            plan.skipped.push(format!(
+                "Custom node '{}' is not a plain folder name.",
+                node.name
+            ));
+            continue;
+        }
+        if custom_node_exists(root, &node.name) {
+            plan.already_present += 1;
+            continue;
+        }
+        let Some(repo_url) = node.repo_url.as_deref() else {
+            plan.skipped.push(format!(
+                "Custom node {} has no git remote in the manifest.",
+                node.name
+            ));
+            continue;
+        };
+        let repo_url = match custom_node_repo_from_url(repo_url) {
+            Ok((repo_url, _)) => repo_url,
+            Err(err) => {
+                plan.skipped
+                    .push(format!("Custom node {}: {err}", node.name));
+                continue;
+            }
+        };
+        if let Some(commit) = node.commit.as_deref().filter(|commit| !is_git_sha(commit)) {
+            plan.skipped.push(format!(
+                "Custom node {} pins '{commit}', which is not a commit hash.",
+                node.name
+            ));
+            continue;
+        }
+        plan.custom_nodes.push(ManifestCustomNode {
+            repo_url: Some(repo_url),
+            ..node.clone()
+        });
+    }
+
+    let local_torch = detect_torch_profile_for_root(root);
+    if manifest.torch_profile.is_some() && manifest.torch_profile != local_torch {
+        plan.warnings.push(format!(
+            "Manifest uses torch profile {}; this install has {}.",
+            manifest.torch_profile.as_deref().unwrap_or("-"),
+            local_torch.as_deref().unwrap_or("none detected")
+        ));
+    }
+    let local_attention = detect_launch_attention_backend_for_root(root);
+    if manifest.attention_backend.is_some() && manifest.attention_backend != local_attention {
+        plan.warnings.push(format!(
+            "Manifest uses attention backend {}; this install has {}.",
+            manifest.attention_backend.as_deref().unwrap_or("-"),
+            local_attention.as_deref().unwrap_or("none")
+        ));
+    }
+    plan
+}
+
+async fn run_manifest_import(
+    app: AppHandle,
+    root: PathBuf,
+    plan_models: Vec<ManifestModel>,
+    plan_loras: Vec<ManifestLora>,
+    plan_nodes: Vec<ManifestCustomNode>,
+    token: Option<String>,
+    cancel: CancellationToken,
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+
+    if !plan_nodes.is_empty() {
+        let _maintenance = enter_maintenance(&app, &root, "importing custom nodes");
+        let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+        let py_exe = match python_exe_for_root(&root) {
+            Ok(py_exe) => py_exe,
+            Err(err) => {
+                restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+                return Err(err);
+            }
+        };
+        let app_for_nodes = app.clone();
+        let root_for_nodes = root.clone();
+        let failures = tauri::async_runtime::spawn_blocking(move || {
+            let mut failures = Vec::new();
+            for node in plan_nodes {
+                // plan_manifest_import only passes nodes with a validated GitHub URL.
+                let Some(repo_url) = node.repo_url else {
+                    continue;
+                };
+                let result = install_named_custom_node(
+                    &app_for_nodes,
+                    &root_for_nodes,
+                    &py_exe,
+                    &repo_url,
+                    &node.name,
+                )
+                .and_then(|_| match node.commit.as_deref() {
+                    Some(commit) => run_command_capture(
+                        "git",
+                        &["checkout", commit, "--"],
+                        Some(&root_for_nodes.join("custom_nodes").join(&node.name)),
+                    )
+                    .map(|_| ()),
+                    None => Ok(()),
+                });
+                if let Err(err) = result {
+                    failures.push(format!("{}: {err}", node.name));
+                }
+            }
+            failures
+        })
+        .await
+        .unwrap_or_else(|err| vec![format!("custom node install task failed: {err}")]);
+        for failure in failures {
+            emit_manifest_progress(
+                &app,
+                "warning",
+                format!("Custom node not installed: {failure}"),
+            );
+        }
+        restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    }
+
+    let effective_root = match comfy_extra_model_config(&root) {
+        Some(config) if config.is_default => config.base_path,
+        _ => root.clone(),
+    };
+    let catalog = state.context.catalog.catalog_snapshot();
+
+    for entry in plan_models {
+        if cancel.is_cancelled() {
+            return Err("Manifest import cancelled.".to_string());
+        }
+        let Some(master) = catalog.find_model(&entry.model_id) else {
+            continue;
+        };
+        let Some(variant) = master
+            .variants
+            .iter()
+            .find(|v| v.id == entry.variant_id && v.tier.identifier() == entry.tier)
+        else {
+            continue;
+        };
+        // Only fetch the RAM-tier specific files the source machine actually had.
+        let wanted: Vec<String> = entry
+            .files
+            .iter()
+            .filter_map(|file| Path::new(file).file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect();
+        let mut resolved = ResolvedModel {
+            master: master.clone(),
+            variant: variant.clone(),
+        };
+        resolved.variant.artifacts = master
+            .artifacts_for_variant(variant, None)
+            .into_iter()
+            .chain(
+                variant
+                    .artifacts
+                    .iter()
+                    .filter(|artifact| artifact.min_ram_tier.is_some())
+                    .filter(|artifact| wanted.iter().any(|name| name == artifact.file_name()))
+                    .cloned(),
+            )
+            .collect();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handle = state.context.downloads.download_variant_with_cancel(
+            effective_root.clone(),
+            resolved,
+            tx,
+            Some(cancel.clone()),
+        );
+        if let Ok(mut abort) = state.active_abort.lock() {
+            *abort = Some(handle.abort_handle());
+        }
+        spawn_progress_emitter(app.clone(), "model".to_string(), rx);
+        match handle.await {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => {
+                if cancel.is_cancelled() {
+                    return Err("Manifest import cancelled.".to_string());
+                }
+                emit_manifest_progress(
+                    &app,
+                    "warning",
+                    format!(
+                        "Model {}/{} failed: {err:#}",
+                        entry.model_id, entry.variant_id
+                    ),
+                );
+            }
+            Err(_) => return Err("Manifest import cancelled.".to_string()),
+        }
+    }
+
+    for entry in plan_loras {
+        if cancel.is_cancelled() {
+            return Err("Manifest import cancelled.".to_string());
+        }
+        let Some(lora) = catalog.find_lora(&entry.lora_id) else {
+            continue;
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handle = state.context.downloads.download_lora_with_cancel(
+            effective_root.clone(),
+            lora,
+            token.clone(),
+            tx,
+            Some(cancel.clone()),
+        );
+        if let Ok(mut abort) = state.active_abort.lock() {
+            *abort = Some(handle.abort_handle());
+        }
+        spawn_progress_emitter(app.clone(), "lora".to_string(), rx);
+        match handle.await {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => {
+                if cancel.is_cancelled() {
+                    return Err("Manifest import cancelled.".to_string());
+                }
+                emit_manifest_progress(
+                    &app,
+                    "warning",
+                    format!("LoRA {} failed: {err:#}", entry.lora_id),
+                );
+            }
+            Err(_) => return Err("Manifest import cancelled.".to_string()),
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn import_manifest(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    comfyui_root: Option<String>,
+    dry_run: Option<bool>,
+    token: Option<String>,
+) -> Result<ManifestImportPlan, String> {
+    let source = PathBuf::from(path.trim());
+    let raw = std::fs::read(&source)
+        .map_err(|err| format!("Failed to read manifest {}: {err}", source.display()))?;
+    let manifest: InstallManifest = serde_json::from_slice(&raw)
+        .map_err(|err| format!("Invalid manifest {}: {err}", source.display()))?;
+    if manifest.manifest_version > INSTALL_MANIFEST_VERSION {
+        return Err(format!(
+            "Manifest version {} is newer than this app supports ({INSTALL_MANIFEST_VERSION}).",
+            manifest.manifest_version
+        ));
+    }
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let dry_run = dry_run.unwrap_or(false);
+    let mut plan = plan_manifest_import(&state, &root, &manifest, dry_run);
+    let nothing_to_do =
+        plan.models.is_empty() && plan.loras.is_empty() && plan.custom_nodes.is_empty();
+    if dry_run || nothing_to_do {
+        return Ok(plan);
+    }
+
+    let cancel = CancellationToken::new();
+    {
+        let mut active = state
+            .active_cancel
+            .lock()
+            .map_err(|_| "download state lock poisoned".to_string())?;
+        if active.is_some() {
+            return Err("A download is already active. Cancel it first.".to_string());
+        }
+        *active = Some(cancel.clone());
+    }
+    plan.queued = true;
+
+    let token = token.or_else(|| state.context.config.settings().civitai_token);
+    let models = plan.models.clone();
+    let loras = plan.loras.clone();
+    let nodes = plan.custom_nodes.clone();
+    let app_for_task = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = run_manifest_import(
+            app_for_task.clone(),
+            root,
+            models,
+            loras,
+            nodes,
+            token,
+            cancel,
+        )
+        .await;
+        let managed = app_for_task.state::<AppState>();
+        if let Ok(mut active) = managed.active_cancel.lock() {
+            *active = None;
+        }
+        if let Ok(mut abort) = managed.active_abort.lock() {
+            *abort = None;
+        }
+        match result {
+            Ok(()) => emit_manifest_progress(
+                &app_for_task,
+                "batch_finished",
+                "Manifest import completed.".to_string(),
+            ),
+            Err(err) => {
+                let phase = if err.to_ascii_lowercase().contains("cancel") {
+                    "cancelled"
+                } else {
+                    "batch_failed"
+                };
+                emit_manifest_progress(&app_for_task, phase, err)
+            }
+        }
+    });
+    Ok(plan)
+}
+
+#[tauri::command]
+fn inspect_comfyui_path(path: String) -> Result<ComfyPathInspection, String> {
+    let selected = path.trim();
+    if selected.is_empty() {
+        return Err("Folder is empty.".to_string());
+    }
+    let selected_path = PathBuf::from(selected);
+    if !selected_path.exists() || !selected_path.is_dir() {
+        return Err("Folder does not exist.".to_string());
+    }
+    let normalized = std::fs::canonicalize(&selected_path).unwrap_or(selected_path.clone());
+    let normalized = normalize_canonical_path(&normalized).to_path_buf();
+    let detected_root = detect_existing_comfyui_root(&normalized).map(|p| {
+        normalize_canonical_path(&p)
+            .to_string_lossy()
+            .to_string()
+    });
+    Ok(ComfyPathInspection {
+        selected: normalize_canonical_path(&normalized)
+            .to_string_lossy()
+            .to_string(),
+        detected_root,
+    })
+}
+
+#[tauri::command]
+fn list_comfyui_installations(
+    state: State<'_, AppState>,
+    base_path: Option<String>,
+) -> Result<Vec<ComfyInstallationEntry>, String> {
+    let candidate = if let Some(raw) = base_path {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(trimmed))
+        }
+    } else {
+        state.context.config.settings().comfyui_install_base
+    };
+
+    let Some(base) = candidate else {
+        return Ok(Vec::new());
+    };
+
+    let base = normalize_canonical_path(&base).to_path_buf();
+    if !base.exists() || !base.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let base = std::fs::canonicalize(&base).unwrap_or(base);
+    let mut entries: Vec<ComfyInstallationEntry> = Vec::new();
+
+    if base.join("main.py").is_file() {
+        let name = base
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("ComfyUI")
+            .to_string();
+        let root = normalize_canonical_path(&base)
+            .to_string_lossy()
+            .to_string();
+        entries.push(ComfyInstallationEntry { name, root });
+    }
+
+    if let Ok(read_dir) = std::fs::read_dir(&base) {
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string();
+            if !name.to_ascii_lowercase().starts_with("comfyui") {
+                continue;
+            }
+            if !path.join("main.py").is_file() {
+                continue;
+            }
+            let root = normalize_canonical_path(&path)
+                .to_string_lossy()
+                .to_string();
+            entries.push(ComfyInstallationEntry { name, root });
+        }
+    }
+
+    entries.sort_by(|a, b| {
+        a.name
+            .to_ascii_lowercase()
+            .cmp(&b.name.to_ascii_lowercase())
+    });
+    entries.dedup_by(|a, b| a.root.eq_ignore_ascii_case(&b.root));
+    Ok(entries)
+}
+
+/// Once a batch has landed on a removable drive, flush the whole filesystem and tell
+/// the UI whether the drive can be ejected.
+async fn emit_eject_status(app: &AppHandle, kind: &str, destination: PathBuf) {
+    let Some(mount) = removable_mount(&destination) else {
+        return;
+    };
+    let mount_for_sync = mount.clone();
+    let synced =
+        tauri::async_runtime::spawn_blocking(move || sync_filesystem(&mount_for_sync)).await;
+    let (phase, message) = match synced {
+        Ok(Ok(())) => (
+            "safe_to_eject",
+            format!(
+                "All files are written to {}. It is safe to eject the drive.",
+                mount.display()
+            ),
+        ),
+        Ok(Err(err)) => (
+            "eject_unsafe",
+            format!(
+                "Could not flush {} ({err}). Do not eject the drive yet.",
+                mount.display()
+            ),
+        ),
+        Err(err) => (
+            "eject_unsafe",
+            format!(
+                "Could not flush {} ({err}). Do not eject the drive yet.",
+                mount.display()
+            ),
+        ),
+    };
+    let _ = app.emit(
+        "download-progress",
+        DownloadProgressEvent {
+            kind: kind.to_string(),
+            phase: phase.to_string(),
+            artifact: None,
+            index: None,
+            total: None,
+            received: None,
+            size: None,
+            folder: Some(mount.to_string_lossy().to_string()),
+            message: Some(message),
+        },
+    );
+}
+
+fn spawn_progress_emitter(
+    app: AppHandle,
+    kind: String,
+    rx: std::sync::mpsc::Receiver<DownloadSignal>,
+) {
+    std::thread::spawn(move || {
+        while let Ok(signal) = rx.recv() {
+            let payload = match signal {
+                DownloadSignal::Started {
+                    artifact,
+                    index,
+                    total,
+                    size,
+                } => DownloadProgressEvent {
+                    kind: kind.clone(),
+                    phase: "started".to_string(),
+                    artifact: Some(artifact),
+                    index: Some(index + 1),
+                    total: Some(total),
+                    received: None,
+                    size,
+                    folder: None,
+                    message: None,
+                },
+                DownloadSignal::Progress {
+                    artifact,
+                    index,
+                    received,
+                    size,
+                } => DownloadProgressEvent {
+                    kind: kind.clone(),
+                    phase: "progress".to_string(),
+                    artifact: Some(artifact),
+                    index: Some(index + 1),
+                    total: None,
+                    received: Some(received),
+                    size,
+                    folder: None,
+                    message: None,
+                },
+                DownloadSignal::Finished {
+                    artifact,
+                    index,
+                    size,
+                    folder,
+                } => DownloadProgressEvent {
+                    kind: kind.clone(),
+                    phase: "finished".to_string(),
+                    artifact: Some(artifact),
+                    index: Some(index + 1),
+                    total: None,
+                    received: None,
+                    size,
+                    folder,
+                    message: None,
+                },
+                DownloadSignal::Failed { artifact, error } => DownloadProgressEvent {
+                    kind: kind.clone(),
+                    phase: "failed".to_string(),
+                    artifact: Some(artifact),
+                    index: None,
+                    total: None,
+                    received: None,
+                    size: None,
+                    folder: None,
+                    message: Some(error),
+                },
+            };
+            let _ = app.emit("download-progress", payload);
+        }
+    });
+}
+
+#[tauri::command]
+fn open_folder(path: String) -> Result<String, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Folder path is empty.".to_string());
+    }
+    let mut target = std::path::PathBuf::from(trimmed);
+    if !target.is_absolute() {
+        if let Ok(cwd) = std::env::current_dir() {
+            target = cwd.join(target);
+        }
+    }
+    if target.is_file() {
+        if let Some(parent) = target.parent() {
+            target = parent.to_path_buf();
+        }
+    }
+    if let Ok(canon) = std::fs::canonicalize(&target) {
+        target = canon;
+    }
+    if !target.exists() {
+        return Err("Folder does not exist.".to_string());
+    }
+
+    open::that(target).map_err(|err| format!("Failed to open folder: {err}"))?;
+    Ok(path)
+}
+
+// Terminal emulators tried when $TERMINAL is unset, with the flag that precedes the command.
+const TERMINAL_CANDIDATES: &[(&str, &[&str])] = &[
+    ("x-terminal-emulator", &["-e"]),
+    ("gnome-terminal", &["--"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("kitty", &[]),
+    ("alacritty", &["-e"]),
+    ("wezterm", &["start", "--"]),
+    ("foot", &[]),
+    ("xterm", &["-e"]),
+];
+
+fn shell_single_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn venv_shell_rcfile(
+    root: &Path,
+    venv_dir: &Path,
+    launch_env: &BTreeMap<String, String>,
+) -> Result<PathBuf, String> {
+    let var_dir = venv_dir.join("var");
+    std::fs::create_dir_all(&var_dir)
+        .map_err(|err| format!("Failed to create {}: {err}", var_dir.display()))?;
+    let mpl_cache = venv_dir.join("var").join("matplotlib");
+    let _ = std::fs::create_dir_all(&mpl_cache);
+
+    let mut script = String::from("[ -f \"$HOME/.bashrc\" ] && . \"$HOME/.bashrc\"\n");
+    // Same order as the launcher: CUDA libraries unless torch is CPU-only, then the
+    // install's own variables on top.
+    if !installed_torch_is_cpu_only(root) {
+        if let Some(ld_path) = cuda_runtime_library_path(root) {
+            script.push_str(&format!(
+                "export LD_LIBRARY_PATH={}\n",
+                shell_single_quote(&ld_path.to_string_lossy())
+            ));
+        }
+    }
+    script.push_str("export MPLBACKEND=Agg\n");
+    script.push_str(&format!(
+        "export MPLCONFIGDIR={}\n",
+        shell_single_quote(&mpl_cache.to_string_lossy())
+    ));
+    for (name, value) in launch_env {
+        script.push_str(&format!("export {name}={}\n", shell_single_quote(value)));
+    }
+    script.push_str(&format!(
+        ". {}\n",
+        shell_single_quote(&venv_dir.join("bin").join("activate").to_string_lossy())
+    ));
+    script.push_str(&format!(
+        "cd {}\n",
+        shell_single_quote(&root.to_string_lossy())
+    ));
+    script.push_str("echo \"ComfyUI venv active: $VIRTUAL_ENV\"\n");
+
+    let rcfile = var_dir.join("arctic-shell.rc");
+    std::fs::write(&rcfile, script)
+        .map_err(|err| format!("Failed to write {}: {err}", rcfile.display()))?;
+    Ok(rcfile)
+}
+
+#[tauri::command]
+fn open_venv_shell(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let py_exe = python_exe_for_root(&root)?;
+    // python_exe_for_root returns <venv>/bin/python.
+    let venv_dir = py_exe
+        .parent()
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .ok_or_else(|| "Could not resolve the install's virtual environment.".to_string())?;
+    let settings = state.context.config.settings();
+    let launch_env = install_config(&settings, &root).launch_env();
+    // Terminals that hand off to a running server drop the spawn environment, so the
+    // rcfile carries it too.
+    let rcfile = venv_shell_rcfile(&root, &venv_dir, &launch_env)?;
+    let shell_args = [
+        "bash".to_string(),
+        "--rcfile".to_string(),
+        rcfile.to_string_lossy().to_string(),
+        "-i".to_string(),
+    ];
+
+    let mut candidates: Vec<(String, Vec<String>)> = Vec::new();
+    if let Some(terminal) = std::env::var("TERMINAL")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        candidates.push((terminal, vec!["-e".to_string()]));
+    }
+    for (program, flags) in TERMINAL_CANDIDATES {
+        candidates.push((
+            program.to_string(),
+            flags.iter().map(|flag| flag.to_string()).collect(),
+        ));
+    }
+
+    for (program, flags) in candidates {
+        let mut cmd = std::process::Command::new(&program);
+        cmd.args(&flags).args(&shell_args).current_dir(&root);
+        apply_cuda_runtime_env_for_root(&mut cmd, &root);
+        configure_python_runtime_env_for_root(&mut cmd, &root);
+        cmd.envs(&launch_env);
+        match cmd.spawn() {
+            Ok(mut child) => {
+                std::thread::spawn(move || {
+                    let _ = child.wait();
+                });
+                return Ok(program);
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => log::warn!("Failed to launch terminal {program}: {err}"),
+        }
+    }
+    Err(
+        "No supported terminal emulator found. Set $TERMINAL to your terminal's command."
+            .to_string(),
+    )
+}
+
+#[tauri::command]
+fn open_external_url(url: String) -> Result<(), String> {
+    let trimmed = url.trim();
+    if !(trimmed.starts_with("https://") || trimmed.starts_with("http://")) {
+        return Err("Only http/https links are allowed.".to_string());
+    }
+
+    open::that(trimmed).map_err(|err| format!("Failed to open link: {err}"))?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyServiceStatus {
+    unit: String,
+    unit_path: String,
+    installed: bool,
+    enabled: bool,
+    active: bool,
+}
+
+/// An XDG base directory, falling back to `$HOME/<fallback>` like the spec says.
+fn xdg_dir(var: &str, fallback: &str) -> Result<PathBuf, String> {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))
+        .ok_or_else(|| "HOME is not set.".to_string())
+}
+
+fn comfyui_service_unit(root: &Path) -> String {
+    format!("arctic-comfyui-{}.service", install_slug(root))
+}
+
+fn comfyui_service_path(root: &Path) -> Result<PathBuf, String> {
+    Ok(xdg_dir("XDG_CONFIG_HOME", ".config")?
+        .join("systemd")
+        .join("user")
+        .join(comfyui_service_unit(root)))
+}
+
+/// Quotes one word of an `ExecStart=` line; `%` and `$` would otherwise expand.
+fn systemd_exec_word(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    format!("\"{escaped}\"")
+}
+
+fn systemd_exec_line(program: &std::ffi::OsStr, args: &[&std::ffi::OsStr]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().copied())
+        .map(|word| systemd_exec_word(&word.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Unit running `cmd` the way the app would launch it. Container installs get the same
+/// stale-name cleanup and stop-by-name the app uses.
+fn comfyui_service_unit_file(
+    root: &Path,
+    cmd: &std::process::Command,
+    container: Option<&ContainerInstall>,
+) -> String {
+    let folder = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.display().to_string());
+    let working_dir = cmd.get_current_dir().unwrap_or(root);
+    let mut lines = vec![
+        "[Unit]".to_string(),
+        format!("Description=ComfyUI ({folder}), set up by Arctic ComfyUI Helper"),
+        "Wants=network-online.target".to_string(),
+        "After=network-online.target".to_string(),
+        String::new(),
+        "[Service]".to_string(),
+        "Type=simple".to_string(),
+        format!(
+            "WorkingDirectory={}",
+            working_dir.to_string_lossy().replace('%', "%%")
+        ),
+    ];
+    for (key, value) in cmd.get_envs() {
+        if let Some(value) = value {
+            let assignment = format!("{}={}", key.to_string_lossy(), value.to_string_lossy());
+            lines.push(format!(
+                "Environment=\"{}\"",
+                assignment
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('%', "%%")
+            ));
+        }
+    }
+    let args: Vec<&std::ffi::OsStr> = cmd.get_args().collect();
+    if let Some(install) = container {
+        let engine = std::ffi::OsStr::new(&install.engine);
+        let name = std::ffi::OsStr::new(&install.container_name);
+        lines.push(format!(
+            "ExecStartPre=-{}",
+            systemd_exec_line(engine, &["rm".as_ref(), "-f".as_ref(), name])
+        ));
+        lines.push(format!(
+            "ExecStop={}",
+            systemd_exec_line(
+                engine,
+                &["stop".as_ref(), "-t".as_ref(), "10".as_ref(), name]
+            )
+        ));
+    }
+    lines.push(format!(
+        "ExecStart={}",
+        systemd_exec_line(cmd.get_program(), &args)
+    ));
+    lines.extend([
+        "Restart=on-failure".to_string(),
+        "RestartSec=5".to_string(),
+        String::new(),
+        "[Install]".to_string(),
+        "WantedBy=default.target".to_string(),
+        String::new(),
+    ]);
+    lines.join("\n")
+}
+
+/// Writes (or refreshes) the unit from the current launch settings and reloads systemd.
+fn write_comfyui_service(app: &AppHandle, state: &AppState, root: &Path) -> Result<(), String> {
+    let container = read_container_install(root);
+    let cmd = match &container {
+        Some(install) => container_launch_command(state, root, install)?,
+        None => comfyui_launch_command(app, state, root)?.0,
+    };
+    let path = comfyui_service_path(root)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create {}: {err}", parent.display()))?;
+    }
+    std::fs::write(
+        &path,
+        comfyui_service_unit_file(root, &cmd, container.as_ref()),
+    )
+    .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    run_command_capture("systemctl", &["--user", "daemon-reload"], None)?;
+    Ok(())
+}
+
+fn comfyui_service_installed(root: &Path) -> bool {
+    comfyui_service_path(root).is_ok_and(|path| path.is_file())
+}
+
+fn comfyui_service_status(root: &Path) -> Result<ComfyServiceStatus, String> {
+    let unit = comfyui_service_unit(root);
+    let path = comfyui_service_path(root)?;
+    let installed = path.is_file();
+    let systemctl_ok = |verb: &str| {
+        installed
+            && run_command_capture("systemctl", &["--user", verb, "--quiet", &unit], None).is_ok()
+    };
+    Ok(ComfyServiceStatus {
+        enabled: systemctl_ok("is-enabled"),
+        active: systemctl_ok("is-active"),
+        unit,
+        unit_path: path.to_string_lossy().to_string(),
+        installed,
+    })
+}
+
+/// Writes a systemd user unit for the install and enables it, so ComfyUI starts at
+/// login without the helper. Start and stop then go through systemd.
+#[tauri::command]
+fn create_comfyui_service(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    install_root: Option<String>,
+) -> Result<ComfyServiceStatus, String> {
+    if !command_available("systemctl", &["--user", "--version"]) {
+        return Err("systemd user services are not available on this system.".to_string());
+    }
+    let root = resolve_root_path(&state.context, install_root)?;
+    write_comfyui_service(&app, &state, &root)?;
+    run_command_capture(
+        "systemctl",
+        &["--user", "enable", &comfyui_service_unit(&root)],
+        None,
+    )?;
+    comfyui_service_status(&root)
+}
+
+#[tauri::command]
+fn remove_comfyui_service(
+    state: State<'_, AppState>,
+    install_root: Option<String>,
+) -> Result<ComfyServiceStatus, String> {
+    let root = resolve_root_path(&state.context, install_root)?;
+    let path = comfyui_service_path(&root)?;
+    if path.is_file() {
+        let _ = run_command_capture(
+            "systemctl",
+            &["--user", "disable", "--now", &comfyui_service_unit(&root)],
+            None,
+        );
+        std::fs::remove_file(&path)
+            .map_err(|err| format!("Failed to remove {}: {err}", path.display()))?;
+        let _ = run_command_capture("systemctl", &["--user", "daemon-reload"], None);
+    }
+    comfyui_service_status(&root)
+}
+
+#[tauri::command]
+fn get_comfyui_service_status(
+    state: State<'_, AppState>,
+    install_root: Option<String>,
+) -> Result<ComfyServiceStatus, String> {
+    let root = resolve_root_path(&state.context, install_root)?;
+    comfyui_service_status(&root)
+}
+
+const START_COMFYUI_ARG: &str = "--start-comfyui";
+
+/// Root passed as `--start-comfyui <root>` (or `--start-comfyui=<root>`), as written
+/// by the per-install desktop entries.
+fn start_root_from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == START_COMFYUI_ARG {
+            return iter.next().cloned();
+        }
+        if let Some(root) = arg
+            .strip_prefix(START_COMFYUI_ARG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(root.to_string());
+        }
+    }
+    None
+}
+
+fn comfyui_desktop_entry_path(root: &Path) -> Result<PathBuf, String> {
+    Ok(xdg_dir("XDG_DATA_HOME", ".local/share")?
+        .join("applications")
+        .join(format!("arctic-comfyui-{}.desktop", install_slug(root))))
+}
+
+/// Quotes one `Exec=` argument. The spec escapes inside quotes, then the whole value
+/// goes through the string escaping every key gets, so backslashes double twice.
+fn desktop_exec_word(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '`' | '$' => {
+                quoted.push_str("\\\\");
+                quoted.push(c);
+            }
+            '\\' => quoted.push_str("\\\\\\\\"),
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// An AppImage runs from a temporary mount, so launchers must point at the image.
+fn helper_launch_path() -> Result<PathBuf, String> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE").filter(|value| !value.is_empty()) {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().map_err(|err| format!("Could not locate the helper executable: {err}"))
+}
+
+fn helper_desktop_icon() -> Result<PathBuf, String> {
+    let path = xdg_dir("XDG_DATA_HOME", ".local/share")?
+        .join("icons")
+        .join("arctic-comfyui-helper.png");
+    if !path.is_file() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {}: {err}", parent.display()))?;
+        }
+        std::fs::write(&path, include_bytes!("../icons/icon.png"))
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    }
+    Ok(path)
+}
+
+/// Writes an app-grid entry that opens the helper and starts this install.
+#[tauri::command]
+fn create_comfyui_desktop_entry(
+    state: State<'_, AppState>,
+    install_root: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, install_root)?;
+    let folder = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.display().to_string());
+    let exec = [
+        helper_launch_path()?.to_string_lossy().to_string(),
+        START_COMFYUI_ARG.to_string(),
+        root.to_string_lossy().to_string(),
+    ]
+    .iter()
+    .map(|word| desktop_exec_word(word))
+    .collect::<Vec<_>>()
+    .join(" ");
+    let entry = [
+        "[Desktop Entry]".to_string(),
+        "Type=Application".to_string(),
+        format!("Name=ComfyUI ({folder})"),
+        format!(
+            "Comment=Start {} with Arctic ComfyUI Helper",
+            root.display()
+        ),
+        format!("Exec={exec}"),
+        format!("Icon={}", helper_desktop_icon()?.display()),
+        "Terminal=false".to_string(),
+        "Categories=Graphics;".to_string(),
+        String::new(),
+    ]
+    .join("\n");
+    let path = comfyui_desktop_entry_path(&root)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create {}: {err}", parent.display()))?;
+        let _ = run_command_capture(
+            "update-desktop-database",
+            &[&parent.to_string_lossy()],
+            None,
+        );
+    }
+    std::fs::write(&path, entry)
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn remove_comfyui_desktop_entry(
+    state: State<'_, AppState>,
+    install_root: Option<String>,
+) -> Result<bool, String> {
+    let root = resolve_root_path(&state.context, install_root)?;
+    let path = comfyui_desktop_entry_path(&root)?;
+    if !path.is_file() {
+        return Ok(false);
+    }
+    std::fs::remove_file(&path)
+        .map_err(|err| format!("Failed to remove {}: {err}", path.display()))?;
+    Ok(true)
+}
+
+/// The `python main.py` command for a host install, with the environment and flags
+/// the app would launch it with. Also returns the attention backend it picked.
+fn comfyui_launch_command(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+) -> Result<(std::process::Command, Option<String>), String> {
+    let main_py = root.join("main.py");
+    if !main_py.exists() {
+        return Err(format!("ComfyUI main.py not found in {}", root.display()));
+    }
+
+    let py_exe = resolve_start_python_exe(app, state, root)?;
+    let settings = state.context.config.settings();
+
+    let mut cmd = std::process::Command::new(py_exe);
+    if !nerdstats_enabled() {
+        apply_background_command_flags(&mut cmd);
+    }
+    apply_cuda_runtime_env_for_root(&mut cmd, root);
+    configure_python_runtime_env_for_root(&mut cmd, root);
+
+    let config = install_config(&settings, root);
+    cmd.envs(config.launch_env());
+
+    let effective_attention = {
+        match config.attention_backend.as_deref() {
+            Some("none") => None,
+            Some("sage3") => {
+                if python_module_importable(root, "sageattn3") {
+                    Some("sage3".to_string())
+                } else {
+                    return Err(
+                        "SageAttention3 is selected but not importable in this install. Re-apply SageAttention3 for this ComfyUI root."
+                            .to_string(),
+                    );
+                }
+            }
+            Some("sage") => {
+                if python_module_importable(root, "sageattention")
+                    || python_module_importable(root, "sageattn3")
+                {
+                    Some("sage".to_string())
+                } else {
+                    return Err(
+                        "SageAttention is selected but not importable in this install. Re-apply SageAttention for this ComfyUI root."
+                            .to_string(),
+                    );
+                }
+            }
+            Some("flash") => {
+                if python_module_importable(root, "flash_attn") {
+                    Some("flash".to_string())
+                } else {
+                    return Err(
+                        "FlashAttention is selected but not importable in this install. Re-apply FlashAttention for this ComfyUI root."
+                            .to_string(),
+                    );
+                }
+            }
+            Some("nunchaku") => {
+                if nunchaku_backend_present(root) {
+                    Some("nunchaku".to_string())
+                } else {
+                    return Err(
+                        "Nunchaku is selected but backend is not installed correctly for this ComfyUI root. Re-apply Nunchaku."
+                            .to_string(),
+                    );
+                }
+            }
+            _ => detect_launch_attention_backend_for_root(root),
+        }
+    };
+    cmd.arg("-W").arg("ignore::FutureWarning").arg(main_py);
+    let data_dirs = read_comfy_data_dirs(root);
+    for (_, dir) in data_dirs.entries() {
+        if !dir.is_dir() {
+            return Err(format!(
+                "Configured ComfyUI data folder is not available: {}. Mount the drive or update the install's data folders.",
+                dir.display()
+            ));
+        }
+    }
+    let vram_flags = vram_launch_flags(
+        config.vram_mode.as_deref(),
+        launch_vram_tier(&settings, &config),
+        config.cache_none,
+    );
+    let launch_args = comfyui_launch_args(
+        config.pinned_memory_enabled,
+        effective_attention.as_deref(),
+        pip_has_package(root, "xformers"),
+        &data_dirs,
+        installed_torch_is_cpu_only(root),
+        &vram_flags,
+    );
+    cmd.args(launch_args);
+    let server = read_server_config(root);
+    if let Some(server) = &server {
+        cmd.arg("--listen").arg(&server.listen);
+    }
+    let port = server.map_or_else(|| comfyui_port_for_root(state, root), |server| server.port);
+    if port != COMFYUI_DEFAULT_PORT {
+        cmd.arg("--port").arg(port.to_string());
+    }
+    cmd.current_dir(root);
+    Ok((cmd, effective_attention))
+}
+
+/// The install root a start or stop applies to: the given path, or the configured root
+/// when none is given. Canonical so it can key `AppState::comfyui_instances`.
+fn comfyui_launch_root(state: &AppState, comfyui_root: Option<String>) -> Result<PathBuf, String> {
+    let root = match comfyui_root
+        .as_deref()
+        .map(str::trim)
+        .filter(|raw| !raw.is_empty())
+    {
+        Some(raw) => PathBuf::from(raw),
+        None => state
+            .context
+            .config
+            .settings()
+            .comfyui_root
+            .ok_or_else(|| "ComfyUI root is not configured.".to_string())?,
+    };
+    Ok(normalize_canonical_path(
+        &std::fs::canonicalize(&root).unwrap_or(root),
+    ))
+}
+
+fn start_comfyui_root_impl(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+    port: Option<u16>,
+) -> Result<(), String> {
+    let port = port.unwrap_or_else(|| comfyui_port_for_root(state, root));
+    let service = comfyui_service_installed(root);
+    let Some(instance) = claim_comfyui_instance(state, root, ComfyInstance::new(port, service))?
+    else {
+        return Ok(());
+    };
+    let result = launch_comfyui_instance(app, state, root, &instance);
+    if result.is_err() {
+        forget_comfyui_instance(state, root);
+    }
+    result
+}
+
+fn launch_comfyui_instance(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+    instance: &ComfyInstance,
+) -> Result<(), String> {
+    if comfyui_service_installed(root) {
+        // Refresh first so attention or memory changes made since reach the unit.
+        write_comfyui_service(app, state, root)?;
+        run_command_capture(
+            "systemctl",
+            &["--user", "start", &comfyui_service_unit(root)],
+            None,
+        )?;
+        return Ok(());
+    }
+    if let Some(install) = read_container_install(root) {
+        return start_comfyui_container(state, root, &install, instance);
+    }
+    let (mut cmd, effective_attention) = comfyui_launch_command(app, state, root)?;
+    let gpus = install_config(&state.context.config.settings(), root)
+        .cuda_devices
+        .map(|devices| format!(" on GPU {devices}"))
+        .unwrap_or_default();
+    emit_comfyui_root_event(
+        app,
+        Some(root),
+        "launch_args",
+        format!(
+            "Launching{gpus} with attention backend: {}",
+            effective_attention.as_deref().unwrap_or("none")
+        ),
+    );
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| format!("Failed to start ComfyUI: {err}"))?;
+    capture_comfyui_output(root, &mut child);
+    instance.attach(child)
+}
+
+const COMFYUI_LOG_FILE: &str = "comfyui-output.log";
+/// `comfyui-output.log` rolls over to `.1` at this size; `.1` moves to `.2` and so on.
+const COMFYUI_LOG_ROTATE_BYTES: u64 = 8 * 1024 * 1024;
+const COMFYUI_LOG_KEEP: usize = 3;
+/// Output lines are sent to the UI in batches this often rather than one event each.
+const COMFYUI_LOG_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+/// Lines kept per batch; a flood beyond this only reaches the log file.
+const COMFYUI_LOG_BATCH_MAX: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+struct ComfyLogLines {
+    root: String,
+    lines: Vec<String>,
+}
+
+/// Lines waiting for the next `comfyui-log` event of one instance.
+#[derive(Default)]
+struct ComfyLogBatch {
+    lines: Vec<String>,
+    dropped: usize,
+    open_streams: usize,
+}
+
+impl ComfyLogBatch {
+    fn push(&mut self, line: String) {
+        if self.lines.len() >= COMFYUI_LOG_BATCH_MAX {
+            self.lines.remove(0);
+            self.dropped += 1;
+        }
+        self.lines.push(line);
+    }
+
+    fn take(&mut self) -> Vec<String> {
+        let mut lines = std::mem::take(&mut self.lines);
+        if self.dropped > 0 {
+            lines.insert(
+                0,
+                format!(
+                    "... {} lines skipped, see {COMFYUI_LOG_FILE}",
+                    std::mem::take(&mut self.dropped)
+                ),
+            );
+        }
+        lines
+    }
+}
+
+/// Output log of one running instance, shared by its stdout and stderr readers.
+struct ComfyOutputLog {
+    path: PathBuf,
+    file: Option<std::fs::File>,
+    written: u64,
+}
+
+impl ComfyOutputLog {
+    fn open(root: &Path) -> Self {
+        let path = root.join(COMFYUI_LOG_FILE);
+        let written = std::fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+        let mut log = Self {
+            path,
+            file: None,
+            written,
+        };
+        if log.written > COMFYUI_LOG_ROTATE_BYTES {
+            log.rotate();
+        } else {
+            log.reopen();
+        }
+        log
+    }
+
+    fn reopen(&mut self) {
+        self.file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|err| log::warn!("Failed to open {}: {err}", self.path.display()))
+            .ok();
+    }
+
+    fn rotate(&mut self) {
+        self.file = None;
+        let numbered = |index: usize| PathBuf::from(format!("{}.{index}", self.path.display()));
+        let _ = std::fs::remove_file(numbered(COMFYUI_LOG_KEEP));
+        for index in (1..COMFYUI_LOG_KEEP).rev() {
+            let _ = std::fs::rename(numbered(index), numbered(index + 1));
+        }
+        let _ = std::fs::rename(&self.path, numbered(1));
+        self.written = 0;
+        self.reopen();
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.written > COMFYUI_LOG_ROTATE_BYTES {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut() {
+            if writeln!(file, "{line}").is_ok() {
+                self.written += line.len() as u64 + 1;
+            }
+        }
+    }
+}
+
+/// Drains the child's piped stdout and stderr into `comfyui-output.log` and batched
+/// `comfyui-log` events; nerdstats also echoes them to this terminal. The readers
+/// never stop before EOF: an undrained pipe would block ComfyUI once it fills up.
+fn capture_comfyui_output(root: &Path, child: &mut std::process::Child) {
+    let log = Arc::new(Mutex::new(ComfyOutputLog::open(root)));
+    if let Ok(mut log) = log.lock() {
+        log.write_line(&format!(
+            "===== ComfyUI started at {} =====",
+            unix_now_secs()
+        ));
+    }
+    let streams: Vec<Box<dyn Read + Send>> = [
+        child
+            .stdout
+            .take()
+            .map(|out| Box::new(out) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|err| Box::new(err) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let batch = Arc::new(Mutex::new(ComfyLogBatch {
+        open_streams: streams.len(),
+        ..ComfyLogBatch::default()
+    }));
+    for reader in streams {
+        let log = Arc::clone(&log);
+        let batch = Arc::clone(&batch);
+        std::thread::spawn(move || {
+            let echo = nerdstats_enabled();
+            let mut reader = BufReader::new(reader);
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                match reader.read_until(b'\n', &mut buf) {
+                    Ok(0) => break,
+                    Ok(_) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(err) => {
+                        log::debug!("ComfyUI output pipe closed: {err}");
+                        break;
+                    }
+                }
+                // Custom nodes print whatever bytes they like; never let that stop draining.
+                let line = String::from_utf8_lossy(&buf)
+                    .trim_end_matches(['\n', '\r'])
+                    .to_string();
+                if echo {
+                    eprintln!("{line}");
+                }
+                if let Ok(mut log) = log.lock() {
+                    log.write_line(&line);
+                }
+                if let Ok(mut batch) = batch.lock() {
+                    batch.push(line);
+                }
+            }
+            if let Ok(mut batch) = batch.lock() {
+                batch.open_streams -= 1;
+            }
+        });
+    }
+
+    let root_name = root.to_string_lossy().to_string();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(COMFYUI_LOG_EMIT_INTERVAL);
+        let (lines, finished) = match batch.lock() {
+            Ok(mut batch) => (batch.take(), batch.open_streams == 0),
+            Err(_) => return,
+        };
+        if !lines.is_empty() {
+            if let Some(app) = GLOBAL_APP.get() {
+                let _ = app.emit(
+                    "comfyui-log",
+                    ComfyLogLines {
+                        root: root_name.clone(),
+                        lines,
+                    },
+                );
+            }
+        }
+        if finished {
+            return;
+        }
+    });
+}
+
+/// Last `lines` lines ComfyUI printed for `comfyui_root`, continuing into the most
+/// recent rotated file when the current one is short.
+#[tauri::command]
+fn get_comfyui_log_tail(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    lines: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    Ok(comfyui_log_tail(&root, lines.unwrap_or(200).clamp(1, 5000)))
+}
+
+fn comfyui_log_tail(root: &Path, limit: usize) -> Vec<String> {
+    let current = root.join(COMFYUI_LOG_FILE);
+    let previous = root.join(format!("{COMFYUI_LOG_FILE}.1"));
+    let mut tail = std::collections::VecDeque::with_capacity(limit);
+    for path in [previous, current] {
+        let Ok(file) = std::fs::File::open(&path) else {
+            continue;
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if tail.len() == limit {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+    }
+    tail.into()
+}
+
+fn wait_for_comfyui_start(state: &AppState, root: &Path, timeout: Duration) -> Result<(), String> {
+    let instance = comfyui_instance(state, root)
+        .ok_or_else(|| format!("ComfyUI was not started from {}.", root.display()))?;
+    let result = wait_for_comfyui_ready(&instance.process, instance.port, timeout);
+    instance.set_status(if result.is_ok() { "running" } else { "failed" });
+    result
+}
+
+/// Waits until something listens on `port`, failing early when the launched process
+/// exits first.
+fn wait_for_comfyui_ready(
+    process: &Mutex<Option<std::process::Child>>,
+    port: u16,
+    timeout: Duration,
+) -> Result<(), String> {
+    let started_at = Instant::now();
+    loop {
+        if port_accepts_connections(port) {
+            return Ok(());
+        }
+
+        {
+            let mut guard = process
+                .lock()
+                .map_err(|_| "comfyui process lock poisoned".to_string())?;
+            if let Some(child) = guard.as_mut() {
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        *guard = None;
+                        return Err(format!(
+                            "ComfyUI process exited during startup with status {status}."
+                        ));
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        *guard = None;
+                        return Err(format!("Failed to monitor ComfyUI startup: {err}"));
+                    }
+                }
+            }
+        }
+
+        if started_at.elapsed() > timeout {
+            let still_running = process
+                .lock()
+                .ok()
+                .and_then(|mut guard| guard.as_mut().map(|child| child.try_wait()))
+                .is_some_and(|status| matches!(status, Ok(None)));
+            if still_running || port_accepts_connections(port) {
+                return Ok(());
+            }
+            return Err(format!(
+                "ComfyUI did not become ready on 127.0.0.1:{port} in time."
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(220));
+    }
+}
+
+/// A run that stays up this long resets the restart count.
+const COMFYUI_STABLE_RUN: Duration = Duration::from_secs(600);
+
+/// With auto-restart on, relaunches the instance for `root` whenever its process exits
+/// while it is still registered. `stop_comfyui_root_impl` unregisters before killing,
+/// so deliberate stops and restarts end the watch instead of triggering it.
+fn watch_comfyui_instance(app: &AppHandle, root: PathBuf) {
+    let state = app.state::<AppState>();
+    if !state.context.config.settings().comfyui_auto_restart.enabled {
+        return;
+    }
+    let Some(mut watched) = comfyui_instance(&state, &root).filter(|instance| !instance.detached)
+    else {
+        return;
+    };
+    let app_handle = app.clone();
+    std::thread::spawn(move || {
+        let state = app_handle.state::<AppState>();
+        let name = comfyui_instance_name_from_path(&root);
+        let still_watched = |watched: &Arc<ComfyInstance>| {
+            comfyui_instance(&state, &root).is_some_and(|current| Arc::ptr_eq(&current, watched))
+        };
+        let mut restarts = 0u32;
+        let mut started_at = Instant::now();
+        loop {
+            std::thread::sleep(Duration::from_secs(2));
+            if !still_watched(&watched) {
+                return;
+            }
+            if watched.running() {
+                if started_at.elapsed() > COMFYUI_STABLE_RUN {
+                    restarts = 0;
+                }
+                continue;
+            }
+            if !still_watched(&watched) {
+                return;
+            }
+            let policy = state.context.config.settings().comfyui_auto_restart;
+            let last_line = comfyui_log_tail(&root, 1).pop().unwrap_or_default();
+            let cause = if last_line.trim().is_empty() {
+                watched.exit_description()
+            } else {
+                format!("{}: {}", watched.exit_description(), last_line.trim())
+            };
+            if !policy.enabled || restarts >= policy.policy.attempts() {
+                forget_comfyui_instance(&state, &root);
+                update_tray_comfy_status(&app_handle);
+                let message = if policy.enabled {
+                    format!(
+                        "{name} crashed ({cause}) and was not restarted after {restarts} attempts."
+                    )
+                } else {
+                    format!("{name} crashed ({cause}).")
+                };
+                emit_comfyui_root_event(&app_handle, Some(&root), "restart_gave_up", message);
+                return;
+            }
+            restarts += 1;
+            let delay = policy.policy.delay_after(restarts);
+            emit_comfyui_root_event(
+                &app_handle,
+                Some(&root),
+                "crashed",
+                format!(
+                    "{name} crashed ({cause}). Restarting in {}s (attempt {restarts} of {}).",
+                    delay.as_secs(),
+                    policy.policy.attempts()
+                ),
+            );
+            std::thread::sleep(delay);
+            if !still_watched(&watched) {
+                return;
+            }
+            let port = watched.port;
+            forget_comfyui_instance(&state, &root);
+            let restarted = start_comfyui_root_impl(&app_handle, &state, &root, Some(port))
+                .and_then(|()| wait_for_comfyui_start(&state, &root, Duration::from_secs(45)));
+            update_tray_comfy_status(&app_handle);
+            match (restarted, comfyui_instance(&state, &root)) {
+                (Ok(()), Some(instance)) => {
+                    watched = instance;
+                    started_at = Instant::now();
+                    emit_comfyui_root_event(
+                        &app_handle,
+                        Some(&root),
+                        "restarted",
+                        format!("{name} restarted after a crash."),
+                    );
+                }
+                (Ok(()), None) => return,
+                (Err(err), instance) => {
+                    emit_comfyui_root_event(
+                        &app_handle,
+                        Some(&root),
+                        "restart_failed",
+                        format!("{name} restart failed: {err}"),
+                    );
+                    // Keep a placeholder so the next pass counts this as another crash.
+                    match instance {
+                        Some(instance) => watched = instance,
+                        None => {
+                            watched = register_comfyui_instance(
+                                &state,
+                                &root,
+                                ComfyInstance::new(port, false),
+                            );
+                            watched.set_status("failed");
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn spawn_comfyui_start_monitor(app: &AppHandle, root: PathBuf, instance_name: String) {
+    let app_handle = app.clone();
+    std::thread::spawn(move || {
+        let state = app_handle.state::<AppState>();
+        match wait_for_comfyui_start(&state, &root, Duration::from_secs(45)) {
+            Ok(()) => {
+                update_tray_comfy_status(&app_handle);
+                emit_comfyui_root_event(
+                    &app_handle,
+                    Some(&root),
+                    "started",
+                    format!("{instance_name} started."),
+                );
+                let url = comfyui_base_url(comfyui_port_for_root(&state, &root));
+                if let Err(err) = open::that(url) {
+                    log::warn!("Failed to open ComfyUI in browser: {err}");
+                }
+                watch_comfyui_instance(&app_handle, root);
+            }
+            Err(err) => {
+                update_tray_comfy_status(&app_handle);
+                emit_comfyui_root_event(
+                    &app_handle,
+                    Some(&root),
+                    "start_failed",
+                    format!("{instance_name} start failed: {err}"),
+                );
+            }
+        }
+    });
+}
+
+fn start_comfyui_root_background(app: &AppHandle, comfyui_root: Option<String>, port: Option<u16>) {
+    let app_handle = app.clone();
+    let root = {
+        let state = app_handle.state::<AppState>();
+        comfyui_launch_root(&state, comfyui_root)
+    };
+    let root = match root {
+        Ok(root) => root,
+        Err(err) => {
+            emit_comfyui_runtime_event(
+                &app_handle,
+                "start_failed",
+                format!("ComfyUI start failed: {err}"),
+            );
+            return;
+        }
+    };
+    let instance_name = comfyui_instance_name_from_path(&root);
+    let maintenance = maintenance_for_root(&app_handle.state::<AppState>(), &root);
+    if let Some(maintenance) = maintenance {
+        update_tray_comfy_status(&app_handle);
+        emit_comfyui_root_event(
+            &app_handle,
+            Some(&root),
+            "start_blocked",
+            format!(
+                "{instance_name} cannot start yet: {}.",
+                maintenance.busy_message()
+            ),
+        );
+        return;
+    }
+    emit_comfyui_root_event(
+        &app_handle,
+        Some(&root),
+        "starting",
+        format!("Starting {instance_name}..."),
+    );
+    std::thread::spawn(move || {
+        let state = app_handle.state::<AppState>();
+        let result = start_comfyui_root_impl(&app_handle, &state, &root, port);
+        update_tray_comfy_status(&app_handle);
+        if let Err(err) = result {
+            emit_comfyui_root_event(
+                &app_handle,
+                Some(&root),
+                "start_failed",
+                format!("{instance_name} start failed: {err}"),
+            );
+            return;
+        }
+        spawn_comfyui_start_monitor(&app_handle, root, instance_name);
+    });
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyPortConflict {
+    port: u16,
+    comfyui_detected: bool,
+    suggested_port: Option<u16>,
+    choices: Vec<String>,
+    detail: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyStartResponse {
+    status: String, // starting | already_running | adopted | conflict | aborted | busy
+    port: u16,
+    conflict: Option<ComfyPortConflict>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maintenance: Option<MaintenanceStatus>,
+}
+
+fn comfyui_base_url(port: u16) -> String {
+    format!("http://127.0.0.1:{port}")
+}
+
+fn port_accepts_connections(port: u16) -> bool {
+    let addr = ("127.0.0.1", port)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut iter| iter.next());
+    let Some(addr) = addr else {
+        return false;
+    };
+    TcpStream::connect_timeout(&addr, Duration::from_millis(180)).is_ok()
+}
+
+/// True when the listener on `port` answers ComfyUI's `/system_stats` endpoint.
+fn port_serves_comfyui(port: u16) -> bool {
+    let Ok(client) = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+    else {
+        return false;
+    };
+    client
+        .get(format!("http://127.0.0.1:{port}/system_stats"))
+        .send()
+        .ok()
+        .filter(|response| response.status().is_success())
+        .and_then(|response| response.json::<serde_json::Value>().ok())
+        .map(|body| body.get("system").is_some() || body.get("devices").is_some())
+        .unwrap_or(false)
+}
+
+fn find_free_comfyui_port(after: u16) -> Option<u16> {
+    (after.saturating_add(1)..after.saturating_add(32))
+        .find(|port| std::net::TcpListener::bind(("127.0.0.1", *port)).is_ok())
+}
+
+const MODEL_WARMUP_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Serialize)]
+struct ModelWarmupResult {
+    model_file: String,
+    prompt_id: String,
+    load_ms: u64,
+    vram_total_bytes: Option<u64>,
+    vram_used_bytes: Option<u64>,
+    vram_delta_bytes: Option<u64>,
+}
+
+/// `(total, free)` VRAM of the first device ComfyUI reports in `/system_stats`.
+fn comfyui_vram(client: &reqwest::blocking::Client, base_url: &str) -> Option<(u64, u64)> {
+    let body = client
+        .get(format!("{base_url}/system_stats"))
+        .send()
+        .ok()?
+        .json::<serde_json::Value>()
+        .ok()?;
+    let device = body.get("devices")?.as_array()?.first()?;
+    Some((
+        device.get("vram_total")?.as_u64()?,
+        device.get("vram_free")?.as_u64()?,
+    ))
+}
+
+/// Loading the checkpoint alone leaves it in system RAM; a single 64x64 step forces
+/// ComfyUI to move it onto the GPU. The preview output is never saved.
+fn model_warmup_prompt(ckpt_name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "1": {
+            "class_type": "CheckpointLoaderSimple",
+            "inputs": { "ckpt_name": ckpt_name }
+        },
+        "2": {
+            "class_type": "CLIPTextEncode",
+            "inputs": { "text": "", "clip": ["1", 1] }
+        },
+        "3": {
+            "class_type": "EmptyLatentImage",
+            "inputs": { "width": 64, "height": 64, "batch_size": 1 }
+        },
+        "4": {
+            "class_type": "KSampler",
+            "inputs": {
+                "model": ["1", 0],
+                "positive": ["2", 0],
+                "negative": ["2", 0],
+                "latent_image": ["3", 0],
+                "seed": 0,
+                "steps": 1,
+                "cfg": 1.0,
+                "sampler_name": "euler",
+                "scheduler": "normal",
+                "denoise": 1.0
+            }
+        },
+        "5": {
+            "class_type": "VAEDecode",
+            "inputs": { "samples": ["4", 0], "vae": ["1", 2] }
+        },
+        "6": {
+            "class_type": "PreviewImage",
+            "inputs": { "images": ["5", 0] }
+        }
+    })
+}
+
+/// Error text from a failed prompt's history entry.
+fn comfyui_history_error(entry: &serde_json::Value) -> String {
+    entry
+        .pointer("/status/messages")
+        .and_then(|messages| messages.as_array())
+        .and_then(|messages| {
+            messages.iter().find_map(|message| {
+                let pair = message.as_array()?;
+                (pair.first()?.as_str()? == "execution_error")
+                    .then(|| pair.get(1)?.get("exception_message")?.as_str())
+                    .flatten()
+                    .map(|text| text.trim().to_string())
+            })
+        })
+        .unwrap_or_else(|| "ComfyUI reported an execution error.".to_string())
+}
+
+fn run_model_warmup(base_url: &str, ckpt_name: &str) -> Result<ModelWarmupResult, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|err| format!("Failed to create HTTP client: {err}"))?;
+    let before = comfyui_vram(&client, base_url);
+
+    let started = Instant::now();
+    let response = client
+        .post(format!("{base_url}/prompt"))
+        .json(&serde_json::json!({
+            "prompt": model_warmup_prompt(ckpt_name),
+            "client_id": "arctic-warmup",
+        }))
+        .send()
+        .map_err(|err| format!("Failed to queue warm-up prompt: {err}"))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        return Err(format!(
+            "ComfyUI rejected the warm-up prompt ({status}): {}",
+            body.trim()
+        ));
+    }
+    let prompt_id = response
+        .json::<serde_json::Value>()
+        .ok()
+        .and_then(|body| body.get("prompt_id")?.as_str().map(str::to_string))
+        .ok_or_else(|| "ComfyUI did not return a prompt id.".to_string())?;
+
+    loop {
+        if started.elapsed() > MODEL_WARMUP_TIMEOUT {
+            return Err(format!(
+                "Warm-up did not finish within {} seconds.",
+                MODEL_WARMUP_TIMEOUT.as_secs()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(500));
+        let history = client
+            .get(format!("{base_url}/history/{prompt_id}"))
+            .send()
+            .ok()
+            .and_then(|response| response.json::<serde_json::Value>().ok());
+        let Some(entry) = history.as_ref().and_then(|history| history.get(&prompt_id)) else {
+            continue;
+        };
+        match entry.pointer("/status/status_str").and_then(|v| v.as_str()) {
+            Some("error") => return Err(comfyui_history_error(entry)),
+            Some("success") => break,
+            _ => {
+                if entry.pointer("/status/completed").and_then(|v| v.as_bool()) == Some(true) {
+                    break;
+                }
+            }
+        }
+    }
+    let load_ms = started.elapsed().as_millis() as u64;
+
+    let after = comfyui_vram(&client, base_url);
+    Ok(ModelWarmupResult {
+        model_file: ckpt_name.to_string(),
+        prompt_id,
+        load_ms,
+        vram_total_bytes: after.map(|(total, _)| total),
+        vram_used_bytes: after.map(|(total, free)| total.saturating_sub(free)),
+        vram_delta_bytes: before
+            .zip(after)
+            .map(|((_, free_before), (_, free_after))| free_before.saturating_sub(free_after)),
+    })
+}
+
+/// Loads a checkpoint into the running ComfyUI so the first real generation starts
+/// warm. `model_file` is a name under `models/checkpoints` or an absolute path there.
+#[tauri::command]
+async fn warm_model_cache(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    model_file: String,
+) -> Result<ModelWarmupResult, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let requested = PathBuf::from(model_file.trim());
+    let ckpt_name = model_roots_for_comfy_root(&root)
+        .into_iter()
+        .map(|base| base.join("models").join("checkpoints"))
+        .find_map(|dir| {
+            let relative = if requested.is_absolute() {
+                requested.strip_prefix(&dir).ok()?.to_path_buf()
+            } else {
+                requested.clone()
+            };
+            dir.join(&relative)
+                .is_file()
+                .then(|| relative.to_string_lossy().replace('\\', "/"))
+        })
+        .ok_or_else(|| {
+            format!(
+                "{} was not found in models/checkpoints.",
+                requested.display()
+            )
+        })?;
+
+    let port = comfyui_port_for_root(&state, &root);
+    if !port_serves_comfyui(port) {
+        return Err(format!(
+            "ComfyUI is not running on port {port}; start it before warming a model."
+        ));
+    }
+    let base_url = comfyui_base_url(port);
+    let result =
+        tauri::async_runtime::spawn_blocking(move || run_model_warmup(&base_url, &ckpt_name))
+            .await
+            .map_err(|err| format!("Model warm-up failed: {err}"))??;
+    log::info!(
+        "Warmed {} in {} ms ({} bytes VRAM in use)",
+        result.model_file,
+        result.load_ms,
+        result.vram_used_bytes.unwrap_or(0)
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+fn start_comfyui_root(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    on_conflict: Option<String>,
+    port: Option<u16>,
+) -> Result<ComfyStartResponse, String> {
+    let root = comfyui_launch_root(&state, comfyui_root)?;
+    let root_arg = Some(root.to_string_lossy().to_string());
+    let instance_name = comfyui_instance_name_from_path(&root);
+    if let Some(maintenance) = maintenance_for_root(&state, &root) {
+        return Ok(ComfyStartResponse {
+            status: "busy".to_string(),
+            port: comfyui_port_for_root(&state, &root),
+            conflict: None,
+            maintenance: Some(maintenance),
+        });
+    }
+    if comfyui_root_running(&state, &root) {
+        update_tray_comfy_status(&app);
+        emit_comfyui_root_event(
+            &app,
+            Some(&root),
+            "started",
+            format!("{instance_name} is already running."),
+        );
+        return Ok(ComfyStartResponse {
+            status: "already_running".to_string(),
+            port: comfyui_port_for_root(&state, &root),
+            conflict: None,
+            maintenance: None,
+        });
+    }
+
+    let server_port = read_server_config(&root).map(|server| server.port);
+    let target_port = port
+        .or(server_port)
+        .unwrap_or_else(|| unclaimed_comfyui_port(&state));
+    if !port_accepts_connections(target_port) {
+        start_comfyui_root_background(&app, root_arg, Some(target_port));
+        return Ok(ComfyStartResponse {
+            status: "starting".to_string(),
+            port: target_port,
+            conflict: None,
+            maintenance: None,
+        });
+    }
+
+    // Something else already owns the port; let the caller decide. Another instance
+    // started by this app is never offered for adoption.
+    let owner = running_comfyui_instances(&state)
+        .into_iter()
+        .find(|(_, instance)| instance.port == target_port)
+        .map(|(owner, _)| owner);
+    let comfyui_detected = owner.is_none() && port_serves_comfyui(target_port);
+    let suggested_port = find_free_comfyui_port(target_port);
+    match on_conflict.as_deref().map(str::trim) {
+        Some("adopt") if comfyui_detected => {
+            register_comfyui_instance(&state, &root, ComfyInstance::adopted(target_port));
+            update_tray_comfy_status(&app);
+            emit_comfyui_root_event(
+                &app,
+                Some(&root),
+                "started",
+                format!("Adopted ComfyUI already running on port {target_port}."),
+            );
+            Ok(ComfyStartResponse {
+                status: "adopted".to_string(),
+                port: target_port,
+                conflict: None,
+                maintenance: None,
+            })
+        }
+        Some("adopt") => Err(format!(
+            "Port {target_port} is not used by an outside ComfyUI; it cannot be adopted."
+        )),
+        Some("other_port") => {
+            let free_port =
+                suggested_port.ok_or_else(|| format!("No free port found after {target_port}."))?;
+            start_comfyui_root_background(&app, root_arg, Some(free_port));
+            Ok(ComfyStartResponse {
+                status: "starting".to_string(),
+                port: free_port,
+                conflict: None,
+                maintenance: None,
+            })
+        }
+        Some("abort") => Ok(ComfyStartResponse {
+            status: "aborted".to_string(),
+            port: target_port,
+            conflict: None,
+            maintenance: None,
+        }),
+        _ => {
+            let mut choices = Vec::new();
+            if comfyui_detected {
+                choices.push("adopt".to_string());
+            }
+            if suggested_port.is_some() {
+                choices.push("other_port".to_string());
+            }
+            choices.push("abort".to_string());
+            let detail = if let Some(owner) = &owner {
+                format!(
+                    "Port {target_port} is used by {}, another ComfyUI started by this app.",
+                    comfyui_instance_name_from_path(owner)
+                )
+            } else if comfyui_detected {
+                format!("A ComfyUI instance not started by this app is already running on port {target_port}.")
+            } else {
+                format!("Port {target_port} is already in use by another application.")
+            };
+            Ok(ComfyStartResponse {
+                status: "conflict".to_string(),
+                port: target_port,
+                conflict: Some(ComfyPortConflict {
+                    port: target_port,
+                    comfyui_detected,
+                    suggested_port,
+                    choices,
+                    detail,
+                }),
+                maintenance: None,
+            })
+        }
+    }
+}
+
+/// A ComfyUI the app started or adopted. Services and adopted servers have no child
+/// process and count as running while their port answers.
+struct ComfyInstance {
+    port: u16,
+    process: Mutex<Option<std::process::Child>>,
+    detached: bool,
+    status: Mutex<&'static str>, // starting | running | failed
+    /// How the child ended, once `running` has seen it exit.
+    exit_status: Mutex<Option<std::process::ExitStatus>>,
+}
+
+impl ComfyInstance {
+    fn new(port: u16, detached: bool) -> Self {
+        Self {
+            port,
+            process: Mutex::new(None),
+            detached,
+            status: Mutex::new("starting"),
+            exit_status: Mutex::new(None),
+        }
+    }
+
+    fn adopted(port: u16) -> Self {
+        let instance = Self::new(port, true);
+        instance.set_status("running");
+        instance
+    }
+
+    fn attach(&self, child: std::process::Child) -> Result<(), String> {
+        let mut guard = self
+            .process
+            .lock()
+            .map_err(|_| "comfyui process lock poisoned".to_string())?;
+        *guard = Some(child);
+        Ok(())
+    }
+
+    fn status(&self) -> &'static str {
+        self.status.lock().map(|status| *status).unwrap_or("failed")
+    }
+
+    fn set_status(&self, status: &'static str) {
+        if let Ok(mut guard) = self.status.lock() {
+            *guard = status;
+        }
+    }
+
+    fn running(&self) -> bool {
+        if self.detached {
+            return port_accepts_connections(self.port);
+        }
+        let mut guard = match self.process.lock() {
+            Ok(g) => g,
+            Err(_) => return false,
+        };
+        let Some(child) = guard.as_mut() else {
+            return false;
+        };
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                *guard = None;
+                if let Ok(mut exit) = self.exit_status.lock() {
+                    *exit = Some(status);
+                }
+                false
+            }
+            Ok(None) => true,
+            Err(_) => {
+                *guard = None;
+                false
+            }
+        }
+    }
+
+    fn exit_description(&self) -> String {
+        match self.exit_status.lock().ok().and_then(|exit| *exit) {
+            Some(status) => format!("exited with {status}"),
+            None => "exited".to_string(),
+        }
+    }
+}
+
+fn comfyui_instance(state: &AppState, root: &Path) -> Option<Arc<ComfyInstance>> {
+    state.comfyui_instances.lock().ok()?.get(root).cloned()
+}
+
+fn register_comfyui_instance(
+    state: &AppState,
+    root: &Path,
+    instance: ComfyInstance,
+) -> Arc<ComfyInstance> {
+    let instance = Arc::new(instance);
+    if let Ok(mut guard) = state.comfyui_instances.lock() {
+        guard.insert(root.to_path_buf(), Arc::clone(&instance));
+    }
+    instance
+}
+
+/// Registers `instance` for `root` unless one is already running or still starting
+/// there, checking and inserting under one lock so concurrent starts launch only once.
+/// Returns `None` when the root is taken.
+fn claim_comfyui_instance(
+    state: &AppState,
+    root: &Path,
+    instance: ComfyInstance,
+) -> Result<Option<Arc<ComfyInstance>>, String> {
+    let mut guard = state
+        .comfyui_instances
+        .lock()
+        .map_err(|_| "comfyui instance lock poisoned".to_string())?;
+    if guard
+        .get(root)
+        .is_some_and(|existing| existing.status() == "starting" || existing.running())
+    {
+        return Ok(None);
+    }
+    let instance = Arc::new(instance);
+    guard.insert(root.to_path_buf(), Arc::clone(&instance));
+    Ok(Some(instance))
+}
+
+fn forget_comfyui_instance(state: &AppState, root: &Path) -> Option<Arc<ComfyInstance>> {
+    state.comfyui_instances.lock().ok()?.remove(root)
+}
+
+/// Instances that are up or still starting. Exited ones stay registered until the
+/// next start or stop of their root so the crash watchdog can still find them.
+fn running_comfyui_instances(state: &AppState) -> Vec<(PathBuf, Arc<ComfyInstance>)> {
+    let Ok(guard) = state.comfyui_instances.lock() else {
+        return Vec::new();
+    };
+    guard
+        .iter()
+        .filter(|(_, instance)| instance.status() == "starting" || instance.running())
+        .map(|(root, instance)| (root.clone(), Arc::clone(instance)))
+        .collect()
+}
+
+fn comfyui_root_running(state: &AppState, root: &Path) -> bool {
+    comfyui_instance(state, root).is_some_and(|instance| instance.running())
+}
+
+/// The port `root` runs (or would run) on: its instance's, then its server profile's.
+fn comfyui_port_for_root(state: &AppState, root: &Path) -> u16 {
+    comfyui_instance(state, root)
+        .map(|instance| instance.port)
+        .or_else(|| read_server_config(root).map(|server| server.port))
+        .unwrap_or(COMFYUI_DEFAULT_PORT)
+}
+
+/// Default port for a root without a server profile, stepping past ports other
+/// instances hold. Starting instances may not listen yet, so bind checks are not enough.
+fn unclaimed_comfyui_port(state: &AppState) -> u16 {
+    let claimed: Vec<u16> = running_comfyui_instances(state)
+        .iter()
+        .map(|(_, instance)| instance.port)
+        .collect();
+    if !claimed.contains(&COMFYUI_DEFAULT_PORT) {
+        return COMFYUI_DEFAULT_PORT;
+    }
+    (COMFYUI_DEFAULT_PORT + 1..COMFYUI_DEFAULT_PORT + 32)
+        .find(|port| {
+            !claimed.contains(port) && std::net::TcpListener::bind(("127.0.0.1", *port)).is_ok()
+        })
+        .unwrap_or(COMFYUI_DEFAULT_PORT)
+}
+
+/// A ComfyUI on the configured root's port that the app has no record of, e.g. one
+/// left running by a previous session.
+fn comfyui_external_running(state: &AppState) -> bool {
+    let Ok(root) = comfyui_launch_root(state, None) else {
+        return false;
+    };
+    comfyui_instance(state, &root).is_none()
+        && port_accepts_connections(comfyui_port_for_root(state, &root))
+}
+
+fn comfyui_runtime_running(state: &AppState) -> bool {
+    !running_comfyui_instances(state).is_empty() || comfyui_external_running(state)
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyRuntimeStatus {
+    running: bool,
+    port: u16,
+    instances: Vec<ComfyInstanceStatus>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyInstanceStatus {
+    root: String,
+    name: String,
+    port: u16,
+    status: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ComfyRuntimeEvent {
+    phase: String,
+    message: String,
+    /// Install root the event is about; absent for app-wide events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    root: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyAddonState {
+    torch_profile: Option<String>,
+    pinned_memory: bool,
+    /// Backend launches use; the flags below list every backend installed.
+    attention_backend: String,
+    sage_attention: bool,
+    sage_attention3: bool,
+    flash_attention: bool,
+    nunchaku: bool,
+    xformers: bool,
+    insight_face: bool,
+    trellis2: bool,
+    node_comfyui_manager: bool,
+    node_comfyui_easy_use: bool,
+    node_rgthree_comfy: bool,
+    node_comfyui_gguf: bool,
+    node_comfyui_kjnodes: bool,
+    node_comfyui_crystools: bool,
+}
+
+fn emit_comfyui_runtime_event(app: &AppHandle, phase: &str, message: impl Into<String>) {
+    emit_comfyui_root_event(app, None, phase, message);
+}
+
+fn emit_comfyui_root_event(
+    app: &AppHandle,
+    root: Option<&Path>,
+    phase: &str,
+    message: impl Into<String>,
+) {
+    let msg = message.into();
+    let _ = app.emit(
+        "comfyui-runtime",
+        ComfyRuntimeEvent {
+            phase: phase.to_string(),
+            message: msg.clone(),
+            root: root.map(|root| root.to_string_lossy().to_string()),
+        },
+    );
+
+    if matches!(
+        phase,
+        "starting"
+            | "started"
+            | "stopping"
+            | "stopped"
+            | "start_failed"
+            | "stop_failed"
+            | "crashed"
+            | "restarted"
+            | "restart_failed"
+            | "restart_gave_up"
+    ) {
+        let _ = app
+            .notification()
+            .builder()
+            .title("Arctic ComfyUI Helper")
+            .body(msg)
+            .show();
+    }
+}
+
+fn python_for_root(root: &Path) -> std::process::Command {
+    let install_dir = root
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root.to_path_buf());
+    let linux_dot_venv_py = root.join(".venv").join("bin").join("python");
+    let legacy_linux_dot_venv_py = install_dir.join(".venv").join("bin").join("python");
+    let linux_venv_py = root.join("venv").join("bin").join("python");
+    let legacy_linux_venv_py = install_dir.join("venv").join("bin").join("python");
+
+    let mut cmd = if linux_dot_venv_py.exists() {
+        std::process::Command::new(linux_dot_venv_py)
+    } else if legacy_linux_dot_venv_py.exists() {
+        std::process::Command::new(legacy_linux_dot_venv_py)
+    } else if linux_venv_py.exists() {
+        std::process::Command::new(linux_venv_py)
+    } else if legacy_linux_venv_py.exists() {
+        std::process::Command::new(legacy_linux_venv_py)
+    } else if command_available("python3", &["--version"]) {
+        std::process::Command::new("python3")
+    } else {
+        std::process::Command::new("python")
+    };
+    if !nerdstats_enabled() {
+        apply_background_command_flags(&mut cmd);
+    }
+    cmd
+}
+
+fn python_exe_candidates_for_root(root: &Path) -> Vec<PathBuf> {
+    let install_dir = root
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root.to_path_buf());
+    vec![
+        root.join(".venv").join("bin").join("python"),
+        install_dir.join(".venv").join("bin").join("python"),
+        root.join("venv").join("bin").join("python"),
+        install_dir.join("venv").join("bin").join("python"),
+    ]
+}
+
+fn python_exe_works(py_exe: &Path, root: &Path) -> bool {
+    if !py_exe.exists() {
+        return false;
+    }
+    let mut cmd = std::process::Command::new(py_exe);
+    cmd.arg("--version");
+    cmd.current_dir(root);
+    apply_background_command_flags(&mut cmd);
+    cmd.output()
+        .map(|out| out.status.success())
+        .unwrap_or(false)
+}
+
+fn resolve_start_python_exe(
+    _app: &AppHandle,
+    _state: &AppState,
+    root: &Path,
+) -> Result<PathBuf, String> {
+    let candidates = python_exe_candidates_for_root(root);
+    for candidate in &candidates {
+        if python_exe_works(candidate, root) {
+            return Ok(candidate.clone());
+        }
+    }
+
+    if candidates.iter().any(|c| c.exists()) {
+        return Err(
+            "Detected Python runtime candidates, but none are executable. Reinstall ComfyUI runtime."
+                .to_string(),
+        );
+    }
+
+    if command_available("python3", &["--version"]) {
+        return Ok(PathBuf::from("python3"));
+    }
+    if command_available("python", &["--version"]) {
+        return Ok(PathBuf::from("python"));
+    }
+
+    Err(
+        "No working Python executable found for this ComfyUI install. Reinstall or run Install New once to bootstrap runtime."
+            .to_string(),
+    )
+}
+fn python_exe_for_root(root: &Path) -> Result<PathBuf, String> {
+    let install_dir = root
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| root.to_path_buf());
+    let candidates = [
+        root.join(".venv").join("bin").join("python"),
+        install_dir.join(".venv").join("bin").join("python"),
+        root.join("venv").join("bin").join("python"),
+        install_dir.join("venv").join("bin").join("python"),
+    ];
+    for candidate in candidates {
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+    Err("Python executable for this ComfyUI install was not found.".to_string())
+}
+
+fn pip_has_package(root: &Path, package: &str) -> bool {
+    let mut cmd = python_for_root(root);
+    cmd.arg("-m").arg("pip").arg("show").arg(package);
+    cmd.current_dir(root);
+    cmd.output()
+        .map(|out| out.status.success())
+        .unwrap_or(false)
+}
+
+fn custom_node_exists(root: &Path, name: &str) -> bool {
+    root.join("custom_nodes").join(name).is_dir()
+}
+
+fn read_comfyui_installed_version(root: &Path) -> Option<String> {
+    let path = root.join("comfyui_version.py");
+    let content = std::fs::read_to_string(path).ok()?;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !trimmed.starts_with("__version__") {
+            continue;
+        }
+        let (_, rhs) = trimmed.split_once('=')?;
+        let value = rhs.trim().trim_matches('"').trim_matches('\'').trim();
+        if !value.is_empty() {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+fn parse_semver_triplet(input: &str) -> Option<(u64, u64, u64)> {
+    let trimmed = input.trim().trim_start_matches('v').trim_start_matches('V');
+    let core = trimmed.split('-').next().unwrap_or(trimmed);
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse::<u64>().ok()?;
+    let minor = parts.next()?.parse::<u64>().ok()?;
+    let patch = parts.next()?.parse::<u64>().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+fn normalize_release_version(input: &str) -> Option<String> {
+    let (major, minor, patch) = parse_semver_triplet(input)?;
+    Some(format!("{major}.{minor}.{patch}"))
+}
+
+fn git_latest_release_tag(root: &Path) -> Option<(String, String)> {
+    let (stdout, _) = run_command_capture("git", &["ls-remote", "--tags", "--refs", "origin"], Some(root)).ok()?;
+    let mut best: Option<((u64, u64, u64), String, String)> = None;
+
+    for line in stdout.lines() {
+        let mut cols = line.split_whitespace();
+        let Some(_sha) = cols.next() else {
+            continue;
+        };
+        let Some(ref_name) = cols.next() else {
+            continue;
+        };
+        let Some(tag) = ref_name.strip_prefix("refs/tags/") else {
+            continue;
+        };
+        let Some(version) = normalize_release_version(tag) else {
+            continue;
+        };
+        let Some(parsed) = parse_semver_triplet(&version) else {
+            continue;
+        };
+
+        match &best {
+            Some((current, _, _)) if *current >= parsed => {}
+            _ => best = Some((parsed, tag.to_string(), version)),
+        }
+    }
+
+    best.map(|(_, tag, version)| (tag, version))
+}
+
+fn git_current_branch(root: &Path) -> Option<String> {
+    let (stdout, _) =
+        run_command_capture("git", &["rev-parse", "--abbrev-ref", "HEAD"], Some(root)).ok()?;
+    let branch = stdout.lines().next().unwrap_or_default().trim().to_string();
+    if branch.is_empty() {
+        None
+    } else {
+        Some(branch)
+    }
+}
+
+fn git_commit_for_ref(root: &Path, git_ref: &str) -> Option<String> {
+    let (stdout, _) = run_command_capture("git", &["rev-parse", git_ref], Some(root)).ok()?;
+    let commit = stdout.lines().next().unwrap_or_default().trim().to_string();
+    if commit.len() >= 7 {
+        Some(commit)
+    } else {
+        None
+    }
+}
+
+/// An install in maintenance mode: an operation is rewriting its code or venv, so
+/// launching it would race pip. Cleared when the operation's guard drops.
+#[derive(Clone, Debug, Serialize)]
+struct MaintenanceStatus {
+    comfyui_root: String,
+    operation: String,
+    step: Option<String>,
+    started_at: u64,
+    /// Nested operations (a repair that reinstalls the attention backend) share one entry.
+    #[serde(skip)]
+    depth: usize,
+}
+
+impl MaintenanceStatus {
+    fn busy_message(&self) -> String {
+        match &self.step {
+            Some(step) => format!("busy: {} (step: {step})", self.operation),
+            None => format!("busy: {}", self.operation),
+        }
+    }
+}
+
+fn maintenance_key(root: &Path) -> PathBuf {
+    std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf())
+}
+
+#[must_use]
+struct MaintenanceGuard {
+    app: AppHandle,
+    key: PathBuf,
+}
+
+impl MaintenanceGuard {
+    fn step(&self, step: impl Into<String>) {
+        set_maintenance_step(&self.app, &self.key, step);
+    }
+}
+
+impl Drop for MaintenanceGuard {
+    fn drop(&mut self) {
+        let state = self.app.state::<AppState>();
+        let Ok(mut maintenance) = state.maintenance.lock() else {
+            return;
+        };
+        if let Some(entry) = maintenance.get_mut(&self.key) {
+            entry.depth = entry.depth.saturating_sub(1);
+            if entry.depth == 0 {
+                maintenance.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Puts `root` in maintenance mode until the returned guard drops.
+fn enter_maintenance(app: &AppHandle, root: &Path, operation: &str) -> MaintenanceGuard {
+    let key = maintenance_key(root);
+    let state = app.state::<AppState>();
+    if let Ok(mut maintenance) = state.maintenance.lock() {
+        maintenance
+            .entry(key.clone())
+            .and_modify(|entry| entry.depth += 1)
+            .or_insert_with(|| MaintenanceStatus {
+                comfyui_root: key.to_string_lossy().to_string(),
+                operation: operation.to_string(),
+                step: None,
+                started_at: unix_now_secs(),
+                depth: 1,
+            });
+    }
+    MaintenanceGuard {
+        app: app.clone(),
+        key,
+    }
+}
+
+fn set_maintenance_step(app: &AppHandle, root: &Path, step: impl Into<String>) {
+    let state = app.state::<AppState>();
+    if let Ok(mut maintenance) = state.maintenance.lock() {
+        if let Some(entry) = maintenance.get_mut(&maintenance_key(root)) {
+            entry.step = Some(step.into());
+        }
+    };
+}
+
+fn maintenance_for_root(state: &AppState, root: &Path) -> Option<MaintenanceStatus> {
+    state
+        .maintenance
+        .lock()
+        .ok()?
+        .get(&maintenance_key(root))
+        .cloned()
+}
+
+#[tauri::command]
+fn get_comfyui_maintenance(state: State<'_, AppState>) -> Vec<MaintenanceStatus> {
+    state
+        .maintenance
+        .lock()
+        .map(|maintenance| maintenance.values().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Open `begin_mutation` session for one install: its ComfyUI stays stopped across
+/// every change made until `commit_mutation`, which restarts it once if it was running.
+#[derive(Debug)]
+struct MutationSession {
+    was_running: bool,
+    depth: usize,
+    opened_at: Instant,
+}
+
+/// A session nobody commits is closed after this long, so ComfyUI does not stay
+/// stopped because a client went away mid-batch.
+const MUTATION_SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Serialize)]
+struct MutationSessionStatus {
+    active: bool,
+    comfyui_root: Option<String>,
+    was_running: bool,
+    expires_in_secs: Option<u64>,
+}
+
+fn begin_mutation_session(app: &AppHandle, state: &AppState, root: &Path) -> Result<(), String> {
+    let root = comfyui_launch_root(state, Some(root.to_string_lossy().to_string()))?;
+    let opened_at = Instant::now();
+    {
+        let mut sessions = state
+            .mutation_sessions
+            .lock()
+            .map_err(|_| "mutation session lock poisoned".to_string())?;
+        if let Some(active) = sessions.get_mut(&root) {
+            active.depth += 1;
+            return Ok(());
+        }
+        sessions.insert(
+            root.clone(),
+            MutationSession {
+                was_running: false,
+                depth: 1,
+                opened_at,
+            },
+        );
+    }
+    // Goes through the session path, which records whether a restart is owed.
+    if let Err(err) = stop_comfyui_for_mutation(app, state, &root) {
+        if let Ok(mut sessions) = state.mutation_sessions.lock() {
+            sessions.remove(&root);
+        }
+        return Err(err);
+    }
+    expire_mutation_session(app, root, opened_at);
+    Ok(())
+}
+
+fn expire_mutation_session(app: &AppHandle, root: PathBuf, opened_at: Instant) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(MUTATION_SESSION_TIMEOUT);
+        let state = app.state::<AppState>();
+        let still_open = state
+            .mutation_sessions
+            .lock()
+            .map(|sessions| {
+                sessions
+                    .get(&root)
+                    .is_some_and(|active| active.opened_at == opened_at)
+            })
+            .unwrap_or(false);
+        if !still_open {
+            return;
+        }
+        emit_comfyui_root_event(
+            &app,
+            Some(&root),
+            "mutation_session_expired",
+            "Batched changes were not applied within 30 minutes; closing the batch.",
+        );
+        if let Err(err) = abort_mutation_session(&app, &state, &root) {
+            log::warn!(
+                "Closing the expired change session for {} failed: {err}",
+                root.display()
+            );
+        }
+    });
+}
+
+/// Closes one level of the session of `root`; returns true once the outermost level
+/// restarted (or did not need to restart) ComfyUI.
+fn commit_mutation_session(app: &AppHandle, state: &AppState, root: &Path) -> Result<bool, String> {
+    let root = comfyui_launch_root(state, Some(root.to_string_lossy().to_string()))?;
+    let was_running = {
+        let mut sessions = state
+            .mutation_sessions
+            .lock()
+            .map_err(|_| "mutation session lock poisoned".to_string())?;
+        let Some(active) = sessions.get_mut(&root) else {
+            return Ok(false);
+        };
+        active.depth = active.depth.saturating_sub(1);
+        if active.depth > 0 {
+            return Ok(false);
+        }
+        let was_running = active.was_running;
+        sessions.remove(&root);
+        was_running
+    };
+    restart_comfyui_after_mutation(app, state, &root, was_running)?;
+    Ok(true)
+}
+
+/// Drops the session of `root` however deeply it is nested and restarts ComfyUI if
+/// the session stopped it. Changes already applied stay applied.
+fn abort_mutation_session(app: &AppHandle, state: &AppState, root: &Path) -> Result<bool, String> {
+    let root = comfyui_launch_root(state, Some(root.to_string_lossy().to_string()))?;
+    let removed = state
+        .mutation_sessions
+        .lock()
+        .map_err(|_| "mutation session lock poisoned".to_string())?
+        .remove(&root);
+    let Some(session) = removed else {
+        return Ok(false);
+    };
+    restart_comfyui_after_mutation(app, state, &root, session.was_running)?;
+    Ok(true)
+}
+
+fn stop_comfyui_for_mutation(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+) -> Result<bool, String> {
+    let root = comfyui_launch_root(state, Some(root.to_string_lossy().to_string()))?;
+    let in_session = state
+        .mutation_sessions
+        .lock()
+        .map(|sessions| sessions.contains_key(&root))
+        .unwrap_or(false);
+    if in_session {
+        // The session owns the restart, so callers must not restart on their own.
+        let stopped = stop_comfyui_for_mutation_now(app, state, &root)?;
+        if let Ok(mut sessions) = state.mutation_sessions.lock() {
+            if let Some(active) = sessions.get_mut(&root) {
+                active.was_running |= stopped;
+            }
+        }
+        return Ok(false);
+    }
+    stop_comfyui_for_mutation_now(app, state, &root)
+}
+
+/// Stops the instance of `root`, the install about to change; other installs keep running.
+fn stop_comfyui_for_mutation_now(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+) -> Result<bool, String> {
+    if !comfyui_root_running(state, root) {
+        return Ok(false);
+    }
+    emit_comfyui_root_event(
+        app,
+        Some(root),
+        "stopping_for_changes",
+        "Stopping ComfyUI before applying changes...",
+    );
+    stop_comfyui_root_impl(state, root)?;
+    update_tray_comfy_status(app);
+    if comfyui_root_running(state, root) {
+        return Err("ComfyUI is still running. Stop it before applying changes.".to_string());
+    }
+    emit_comfyui_root_event(
+        app,
+        Some(root),
+        "stopped_for_changes",
+        "ComfyUI stopped for install/remove operation.",
+    );
+    Ok(true)
+}
+fn kill_python_processes_for_root(_root: &Path, _py_exe: &Path) -> Result<bool, String> {
+    Ok(false)
+}
+
+fn restart_comfyui_after_mutation(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+    was_running: bool,
+) -> Result<(), String> {
+    if !was_running {
+        return Ok(());
+    }
+    let root = comfyui_launch_root(state, Some(root.to_string_lossy().to_string()))?;
+    start_comfyui_root_impl(app, state, &root, None)?;
+    wait_for_comfyui_start(state, &root, Duration::from_secs(45))?;
+    emit_comfyui_root_event(
+        app,
+        Some(&root),
+        "restarted_after_changes",
+        "ComfyUI restarted after install/remove operation.",
+    );
+    watch_comfyui_instance(app, root);
+    update_tray_comfy_status(app);
+    Ok(())
+}
+
+#[tauri::command]
+async fn begin_mutation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<MutationSessionStatus, String> {
+    let root = comfyui_launch_root(&state, comfyui_root)?;
+    begin_mutation_session(&app, &state, &root)?;
+    Ok(mutation_session_status(&state, &root))
+}
+
+#[tauri::command]
+async fn commit_mutation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<MutationSessionStatus, String> {
+    let root = comfyui_launch_root(&state, comfyui_root)?;
+    commit_mutation_session(&app, &state, &root)?;
+    Ok(mutation_session_status(&state, &root))
+}
+
+#[tauri::command]
+async fn abort_mutation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<MutationSessionStatus, String> {
+    let root = comfyui_launch_root(&state, comfyui_root)?;
+    abort_mutation_session(&app, &state, &root)?;
+    Ok(mutation_session_status(&state, &root))
+}
+
+#[tauri::command]
+fn get_mutation_session(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<MutationSessionStatus, String> {
+    let root = comfyui_launch_root(&state, comfyui_root)?;
+    Ok(mutation_session_status(&state, &root))
+}
+
+fn mutation_session_status(state: &AppState, root: &Path) -> MutationSessionStatus {
+    let sessions = state.mutation_sessions.lock().ok();
+    let active = sessions.as_ref().and_then(|sessions| sessions.get(root));
+    MutationSessionStatus {
+        active: active.is_some(),
+        comfyui_root: Some(root.to_string_lossy().to_string()),
+        was_running: active.is_some_and(|active| active.was_running),
+        expires_in_secs: active.map(|active| {
+            MUTATION_SESSION_TIMEOUT
+                .saturating_sub(active.opened_at.elapsed())
+                .as_secs()
+        }),
+    }
+}
+
+#[tauri::command]
+fn get_comfyui_addon_state(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<ComfyAddonState, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let config = install_config(&state.context.config.settings(), &root);
+    let installed = installed_attention_backends(&root);
+    let has_sage3 = installed.contains(&"sage3");
+    let has_sage = installed.contains(&"sage") || has_sage3;
+    let has_flash = installed.contains(&"flash");
+    let has_nunchaku = installed.contains(&"nunchaku");
+    let active_attention: String = match config.attention_backend.as_deref() {
+        Some("none") => "none".to_string(),
+        Some("flash") if has_flash => "flash".to_string(),
+        Some("sage3") if has_sage3 => "sage3".to_string(),
+        Some("sage") if has_sage => "sage".to_string(),
+        Some("nunchaku") if has_nunchaku => "nunchaku".to_string(),
+        _ => detect_launch_attention_backend_for_root(&root).unwrap_or_else(|| "none".to_string()),
+    };
+
+    Ok(ComfyAddonState {
+        torch_profile: detect_torch_profile_for_root(&root).or(config.torch_profile),
+        pinned_memory: config.pinned_memory_enabled,
+        sage_attention: installed.contains(&"sage"),
+        sage_attention3: has_sage3,
+        flash_attention: has_flash,
+        nunchaku: has_nunchaku,
+        attention_backend: active_attention,
+        xformers: pip_has_package(&root, "xformers"),
+        insight_face: pip_has_package(&root, "insightface"),
+        trellis2: custom_node_exists(&root, "ComfyUI-Trellis2")
+            || custom_node_exists(&root, "ComfyUI-TRELLIS2"),
+        node_comfyui_manager: custom_node_exists(&root, "ComfyUI-Manager")
+            || custom_node_exists(&root, "comfyui-manager"),
+        node_comfyui_easy_use: custom_node_exists(&root, "ComfyUI-Easy-Use"),
+        node_rgthree_comfy: custom_node_exists(&root, "rgthree-comfy"),
+        node_comfyui_gguf: custom_node_exists(&root, "ComfyUI-GGUF"),
+        node_comfyui_kjnodes: custom_node_exists(&root, "comfyui-kjnodes"),
+        node_comfyui_crystools: custom_node_exists(&root, "comfyui-crystools"),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttentionBackendChangeRequest {
+    #[serde(default)]
+    comfyui_root: Option<String>,
+    target_backend: String, // none | sage | sage3 | flash | nunchaku
+    #[serde(default)]
+    torch_profile: Option<String>,
+    #[serde(default)]
+    allow_incompatible: bool,
+    /// Install the target next to the backends already present instead of replacing them.
+    #[serde(default)]
+    keep_others: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComfyComponentToggleRequest {
+    #[serde(default)]
+    comfyui_root: Option<String>,
+    component: String,
+    enabled: bool,
+}
+
+#[tauri::command]
+fn apply_attention_backend_change(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: AttentionBackendChangeRequest,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, request.comfyui_root)?;
+    let target = request.target_backend.trim().to_ascii_lowercase();
+    if !matches!(
+        target.as_str(),
+        "none" | "sage" | "sage3" | "flash" | "nunchaku"
+    ) {
+        return Err("Unknown attention backend target.".to_string());
+    }
+    if !request.allow_incompatible {
+        if let Some(block) = compat::check_addon(&machine_profile(), &target, &target) {
+            return Err(block.to_string());
+        }
+    }
+    if target == "sage3" {
+        let gpu = detect_nvidia_gpu_details();
+        let is_50_series = gpu
+            .name
+            .as_deref()
+            .map(|name| name.to_ascii_lowercase().contains("rtx 50"))
+            .unwrap_or(false);
+        if !is_50_series {
+            return Err(
+                "SageAttention3 is available only for NVIDIA RTX 50-series GPUs.".to_string(),
+            );
+        }
+    }
+
+    let maintenance = enter_maintenance(&app, &root, "changing attention backend");
+    let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+    let result = install_attention_backend(
+        &app,
+        &state,
+        &root,
+        &maintenance,
+        &target,
+        request.torch_profile,
+        request.keep_others,
+    );
+    restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    result?;
+    Ok(format!("Applied attention backend: {target}"))
+}
+
+/// Swaps the attention backend of a stopped install to `target` and records it.
+fn install_attention_backend(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+    maintenance: &MaintenanceGuard,
+    target: &str,
+    torch_profile: Option<String>,
+    keep_others: bool,
+) -> Result<(), String> {
+    let py_path = {
+        let probe = python_for_root(root);
+        probe.get_program().to_string_lossy().to_string()
+    };
+    let py_exe = PathBuf::from(&py_path);
+    let _ = kill_python_processes_for_root(root, &py_exe);
+
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = resolve_uv_binary(&shared_runtime_root, app)?;
+    let uv_python_install_dir = shared_runtime_root
+        .join(".python")
+        .to_string_lossy()
+        .to_string();
+    snapshot_env_before(app, root, &uv_bin, &py_path, "attention-backend");
+    let profile = if let Some(profile) = torch_profile {
+        profile
+    } else {
+        profile_from_torch_env(root)?
+    };
+    if is_nightly_torch_profile(&profile) && target != "none" {
+        return Err(format!(
+            "No prebuilt {target} wheels exist for the torch nightly profile."
+        ));
+    }
+    let wheel_arch = detect_wheel_arch();
+    let triton_pkg = triton_package_for_profile_linux(&py_path, &profile);
+    let triton_pkg = triton_pkg.as_str();
+
+    if !keep_others || target == "none" {
+        maintenance.step("removing the previous backend");
+        force_cleanup_attention_backends(root, &py_path)?;
+    }
+
+    maintenance.step(format!("installing {target}"));
+    let progress = StepProgress { app, cancel: None };
+    match target {
+        "none" => {}
+        "sage" => {
+            progress.step("SageAttention: reinstalling Triton...")?;
+            run_uv_pip_strict(
+                &uv_bin,
+                &py_path,
+                &["install", "--upgrade", "--force-reinstall", triton_pkg],
+                Some(root),
+                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
+            )?;
+            progress.step("SageAttention: installing the prebuilt wheel...")?;
+            install_sageattention_linux(root, &py_path, &profile, wheel_arch)?;
+        }
+        "flash" => {
+            progress.step("FlashAttention: reinstalling Triton...")?;
+            run_uv_pip_strict(
+                &uv_bin,
+                &py_path,
+                &["install", "--upgrade", "--force-reinstall", triton_pkg],
+                Some(root),
+                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
+            )?;
+            progress.step("FlashAttention: installing the prebuilt wheel...")?;
+            install_flashattention_linux(root, &py_path, &profile, wheel_arch)?;
+        }
+        "sage3" => {
+            progress.step("SageAttention3: reinstalling Triton...")?;
+            run_uv_pip_strict(
+                &uv_bin,
+                &py_path,
+                &["install", "--upgrade", "--force-reinstall", triton_pkg],
+                Some(root),
+                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
+            )?;
+            progress.step("SageAttention3: installing the prebuilt wheel...")?;
+            install_linux_wheel_for_profile(root, &py_path, &profile, "sage3", wheel_arch, true)?;
+            // Keep sageattention installed for ComfyUI --use-sage-attention compatibility checks.
+            progress.step("SageAttention3: installing SageAttention alongside...")?;
+            install_sageattention_linux(root, &py_path, &profile, wheel_arch)?;
+        }
+        "nunchaku" => {
+            ensure_git_available(app)?;
+            let custom_nodes_root = root.join("custom_nodes");
+            std::fs::create_dir_all(&custom_nodes_root).map_err(|err| err.to_string())?;
+            let nunchaku_node = root.join("custom_nodes").join("ComfyUI-nunchaku");
+            progress.step("Nunchaku: cloning ComfyUI-nunchaku...")?;
+            clone_or_update_repo(
+                root,
+                &nunchaku_node,
+                "https://github.com/nunchaku-ai/ComfyUI-nunchaku",
+            )?;
+            let versions_json = nunchaku_node.join("nunchaku_versions.json");
+            let _ = download_http_file(
+                "https://nunchaku.tech/cdn/nunchaku_versions.json",
+                &versions_json,
+            );
+            progress.step("Nunchaku: reinstalling Triton...")?;
+            run_uv_pip_strict(
+                &uv_bin,
+                &py_path,
+                &["install", "--upgrade", "--force-reinstall", triton_pkg],
+                Some(root),
+                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
+            )?;
+            progress.step("Nunchaku: installing InsightFace...")?;
+            install_insightface(root, &uv_bin, &py_path, &uv_python_install_dir)?;
+            progress.step("Nunchaku: installing node requirements...")?;
+            install_nunchaku_node_requirements(
+                root,
+                &uv_bin,
+                &py_path,
+                &uv_python_install_dir,
+                &nunchaku_node,
+            )?;
+            progress.step("Nunchaku: installing the prebuilt wheel...")?;
+            install_linux_wheel_for_profile(
+                root, &py_path, &profile, "nunchaku", wheel_arch, true,
+            )?;
+            if !nunchaku_backend_present(root) {
+                return Err(
+                    "Nunchaku backend install incomplete: module or custom node not detected."
+                        .to_string(),
+                );
+            }
+        }
+        _ => return Err("Unknown attention backend target.".to_string()),
+    }
+
+    if target == "none" {
+        let mut lingering: Vec<&str> = Vec::new();
+        for pkg in [
+            "sageattention",
+            "sageattn3",
+            "flash-attn",
+            "flash_attn",
+            "nunchaku",
+        ] {
+            if pip_has_package(root, pkg) {
+                lingering.push(pkg);
+            }
+        }
+        let mut lingering_nodes: Vec<&str> = Vec::new();
+        for node in ["ComfyUI-nunchaku", "nunchaku_nodes"] {
+            if custom_node_exists(root, node) {
+                lingering_nodes.push(node);
+            }
+        }
+        if !lingering.is_empty() || !lingering_nodes.is_empty() {
+            let mut detail = String::new();
+            if !lingering.is_empty() {
+                detail.push_str(&format!(
+                    "packages still installed: {}",
+                    lingering.join(", ")
+                ));
+            }
+            if !lingering_nodes.is_empty() {
+                if !detail.is_empty() {
+                    detail.push_str("; ");
+                }
+                detail.push_str(&format!(
+                    "nodes still present: {}",
+                    lingering_nodes.join(", ")
+                ));
+            }
+            return Err(format!(
+                "Attention backend removal incomplete ({detail}). Stop ComfyUI and retry."
+            ));
+        }
+    }
+    save_attention_backend(state, root, target, Some(profile))
+}
+
+/// Makes `backend` the one launches of `root` use, keeping the global copy in step
+/// for the configured root.
+fn save_attention_backend(
+    state: &AppState,
+    root: &Path,
+    backend: &str,
+    profile: Option<String>,
+) -> Result<(), String> {
+    let settings = state.context.config.settings();
+    update_install_config(&settings, root, |config| {
+        config.attention_backend = Some(backend.to_string());
+        if profile.is_some() {
+            config.torch_profile = profile.clone();
+        }
+    })?;
+    if is_configured_root(&settings, root) {
+        let _ = state.context.config.update_settings(|settings| {
+            settings.comfyui_attention_backend = Some(backend.to_string());
+            if profile.is_some() {
+                settings.comfyui_torch_profile = profile.clone();
+            }
+        });
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn select_attention_backend(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    backend: String,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let backend = backend.trim().to_ascii_lowercase();
+    if !matches!(
+        backend.as_str(),
+        "none" | "sage" | "sage3" | "flash" | "nunchaku"
+    ) {
+        return Err("Unknown attention backend target.".to_string());
+    }
+    if backend != "none" && !installed_attention_backends(&root).contains(&backend.as_str()) {
+        return Err(format!(
+            "The {backend} attention backend is not installed in this ComfyUI root."
+        ));
+    }
+    save_attention_backend(&state, &root, &backend, None)?;
+    Ok(format!("Next launch uses attention backend: {backend}"))
+}
+
+/// Uninstalls one attention backend and leaves the others in place. Launches fall
+/// back to another installed backend when the removed one was active.
+#[tauri::command]
+fn remove_attention_backend(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    backend: String,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let backend = backend.trim().to_ascii_lowercase();
+    let (packages, label): (&[&str], &str) = match backend.as_str() {
+        "sage" => (&["sageattention"], "SageAttention"),
+        "sage3" => (&["sageattn3"], "SageAttention3"),
+        "flash" => (&["flash-attn", "flash_attn"], "FlashAttention"),
+        "nunchaku" => (&["nunchaku"], "Nunchaku"),
+        _ => return Err("Unknown attention backend target.".to_string()),
+    };
+    if backend == "sage" && python_module_importable(&root, "sageattn3") {
+        return Err(
+            "SageAttention3 launches through the SageAttention package; remove SageAttention3 first."
+                .to_string(),
+        );
+    }
+    let maintenance = enter_maintenance(&app, &root, "removing an attention backend");
+    let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+    let result =
+        uninstall_attention_backend(&app, &state, &root, &maintenance, &backend, packages, label);
+    restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    result?;
+    Ok(format!("Removed attention backend: {label}"))
+}
+
+/// Uninstalls `backend` from a stopped install, falling back to another installed
+/// backend (or none) when it was the active one.
+fn uninstall_attention_backend(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+    maintenance: &MaintenanceGuard,
+    backend: &str,
+    packages: &[&str],
+    label: &str,
+) -> Result<(), String> {
+    let py_path = {
+        let probe = python_for_root(root);
+        probe.get_program().to_string_lossy().to_string()
+    };
+    let _ = kill_python_processes_for_root(root, &PathBuf::from(&py_path));
+
+    maintenance.step(format!("removing {label}"));
+    emit_toggle_event(app, "toggle_step", &format!("{label}: uninstalling..."));
+    pip_uninstall_best_effort(root, &py_path, packages);
+    let markers: Vec<String> = packages
+        .iter()
+        .map(|pkg| normalize_pkg_token(pkg))
+        .collect();
+    remove_site_packages_artifacts_with_markers(root, &markers)?;
+    if backend == "nunchaku" {
+        remove_custom_node_dirs(root, &["ComfyUI-nunchaku", "nunchaku_nodes"]);
+    }
+    let remaining = installed_attention_backends(root);
+    if remaining.contains(&backend) {
+        return Err(format!(
+            "{label} removal incomplete. Stop ComfyUI and retry."
+        ));
+    }
+
+    let active = install_config(&state.context.config.settings(), root).attention_backend;
+    if active.as_deref() == Some(backend) {
+        let fallback = remaining.first().copied().unwrap_or("none");
+        save_attention_backend(state, root, fallback, None)?;
+    }
+    Ok(())
+}
+
+fn remove_custom_node_dirs(root: &Path, names: &[&str]) {
+    let custom_nodes = root.join("custom_nodes");
+    for name in names {
+        let path = custom_nodes.join(name);
+        if path.exists() {
+            let _ = std::fs::remove_dir_all(path);
+        }
+    }
+}
+
+fn install_insightface(
+    root: &Path,
+    uv_bin: &str,
+    py_path: &str,
+    uv_python_install_dir: &str,
+) -> Result<(), String> {
+    let profile = profile_from_torch_env(root)?;
+    install_linux_wheel_for_profile(
+        root,
+        py_path,
+        &profile,
+        "insightface",
+        detect_wheel_arch(),
+        true,
+    )?;
+    run_uv_pip_strict(
+        uv_bin,
+        py_path,
+        &["install", "--upgrade", "onnx", "onnxruntime"],
+        Some(root),
+        &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+    )?;
+    if !python_module_importable(root, "onnx") {
+        return Err("InsightFace install incomplete: missing 'onnx' module.".to_string());
+    }
+    if !insightface_present(root) {
+        return Err("InsightFace install incomplete: package/module not detected.".to_string());
+    }
+    Ok(())
+}
+
+/// xformers release built against each profile's torch, served from the profile's
+/// own package index.
+fn xformers_requirement_for_profile(profile: &str) -> Option<&'static str> {
+    match profile {
+        "torch271_cu128" => Some("xformers==0.0.31.post1"),
+        "torch280_cu128" => Some("xformers==0.0.32.post2"),
+        "torch291_cu130" => Some("xformers==0.0.33.post2"),
+        _ => None,
+    }
+}
+
+fn install_xformers(
+    root: &Path,
+    uv_bin: &str,
+    py_path: &str,
+    uv_python_install_dir: &str,
+) -> Result<(), String> {
+    let profile = profile_from_torch_env(root)?;
+    let requirement = xformers_requirement_for_profile(&profile)
+        .ok_or_else(|| format!("No xformers build is published for {profile}."))?;
+    let index_url = torch_profile_to_packages_linux(&profile).3;
+    // xformers pins an exact torch; without --no-deps it could replace the profile's.
+    run_uv_pip_strict(
+        uv_bin,
+        py_path,
+        &[
+            "install",
+            "--no-deps",
+            requirement,
+            "--index-url",
+            &index_url,
+        ],
+        Some(root),
+        &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+    )?;
+    if !python_module_importable(root, "xformers") {
+        return Err("xformers install incomplete: module not importable.".to_string());
+    }
+    Ok(())
+}
+
+fn uninstall_xformers(root: &Path, py_path: &str) -> Result<(), String> {
+    pip_uninstall_best_effort(root, py_path, &["xformers"]);
+    if pip_has_package(root, "xformers") {
+        return Err("xformers is still installed after uninstall.".to_string());
+    }
+    Ok(())
+}
+
+fn uninstall_insightface(
+    root: &Path,
+    _uv_bin: &str,
+    py_path: &str,
+    _uv_python_install_dir: &str,
+) -> Result<(), String> {
+    pip_uninstall_best_effort(root, py_path, &["insightface", "filterpywhl", "facexlib"]);
+    remove_insightface_site_packages_artifacts(root)?;
+    if insightface_present(root)
+        || pip_has_package(root, "facexlib")
+        || pip_has_package(root, "filterpywhl")
+    {
+        return Err(
+            "Failed to fully remove InsightFace dependencies. Stop ComfyUI and retry."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn install_trellis2(
+    root: &Path,
+    uv_bin: &str,
+    py_path: &str,
+    uv_python_install_dir: &str,
+    progress: &StepProgress,
+) -> Result<(), String> {
+    // Trellis2 stack is pinned to torch280_cu128 in this app.
+    progress.step("Trellis2: switching torch stack to torch280_cu128...")?;
+    enforce_torch_profile_linux(
+        uv_bin,
+        py_path,
+        root,
+        "torch280_cu128",
+        uv_python_install_dir,
+    )?;
+
+    let custom_nodes_dir = root.join("custom_nodes");
+    std::fs::create_dir_all(&custom_nodes_dir).map_err(|err| err.to_string())?;
+
+    let trellis_dir = custom_nodes_dir.join("ComfyUI-TRELLIS2");
+    progress.step("Trellis2: cloning ComfyUI-TRELLIS2...")?;
+    clone_or_update_repo(
+        root,
+        &trellis_dir,
+        "https://github.com/ArcticLatent/ComfyUI-TRELLIS2",
+    )?;
+    let trellis_req = trellis_dir.join("requirements.txt");
+    if trellis_req.exists() {
+        progress.step("Trellis2: installing ComfyUI-TRELLIS2 requirements...")?;
+        run_uv_pip_strict(
+            uv_bin,
+            py_path,
+            &["install", "-r", &trellis_req.to_string_lossy(), "--no-deps"],
+            Some(root),
+            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+        )?;
+        run_uv_pip_strict(
+            uv_bin,
+            py_path,
+            &["install", "--upgrade", "open3d"],
+            Some(root),
+            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+        )?;
+    }
+
+    let geometry_dir = custom_nodes_dir.join("ComfyUI-GeometryPack");
+    progress.step("Trellis2: cloning ComfyUI-GeometryPack...")?;
+    clone_or_update_repo(
+        root,
+        &geometry_dir,
+        "https://github.com/PozzettiAndrea/ComfyUI-GeometryPack",
+    )?;
+    let geometry_req = geometry_dir.join("requirements.txt");
+    if geometry_req.exists() {
+        progress.step("Trellis2: installing ComfyUI-GeometryPack requirements...")?;
+        run_uv_pip_strict(
+            uv_bin,
+            py_path,
+            &["install", "-r", &geometry_req.to_string_lossy(), "--no-deps"],
+            Some(root),
+            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+        )?;
+    }
+    run_uv_pip_strict(
+        uv_bin,
+        py_path,
+        &["install", "--upgrade", "tomli"],
+        Some(root),
+        &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+    )?;
+
+    let ultrashape_dir = custom_nodes_dir.join("ComfyUI-UltraShape1");
+    progress.step("Trellis2: cloning ComfyUI-UltraShape1...")?;
+    clone_or_update_repo(
+        root,
+        &ultrashape_dir,
+        "https://github.com/jtydhr88/ComfyUI-UltraShape1",
+    )?;
+    let ultrashape_req = ultrashape_dir.join("requirements.txt");
+    if ultrashape_req.exists() {
+        progress.step("Trellis2: installing ComfyUI-UltraShape1 requirements...")?;
+        run_uv_pip_strict(
+            uv_bin,
+            py_path,
+            &["install", "-r", &ultrashape_req.to_string_lossy(), "--no-deps"],
+            Some(&ultrashape_dir),
+            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+        )?;
+        run_uv_pip_strict(
+            uv_bin,
+            py_path,
+            &["install", "-U", "accelerate"],
+            Some(&ultrashape_dir),
+            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+        )?;
+    }
+
+    let ultrashape_models_dir = root.join("models").join("UltraShape");
+    std::fs::create_dir_all(&ultrashape_models_dir).map_err(|err| err.to_string())?;
+    let ultrashape_model_file = ultrashape_models_dir.join("ultrashape_v1.pt");
+    if !ultrashape_model_file.exists() {
+        progress.step("Trellis2: downloading UltraShape model (ultrashape_v1.pt)...")?;
+        download_http_file(
+            "https://huggingface.co/infinith/UltraShape/resolve/main/ultrashape_v1.pt",
+            &ultrashape_model_file,
+        )?;
+    }
+
+    // Re-assert stack after Trellis requirements/custom nodes.
+    progress.step("Trellis2: re-applying torch280_cu128 stack...")?;
+    enforce_torch_profile_linux(
+        uv_bin,
+        py_path,
+        root,
+        "torch280_cu128",
+        uv_python_install_dir,
+    )?;
+
+    Ok(())
+}
+
+fn uninstall_trellis2(
+    root: &Path,
+    _uv_bin: &str,
+    py_path: &str,
+    _uv_python_install_dir: &str,
+) -> Result<(), String> {
+    remove_custom_node_dirs(
+        root,
+        &[
+            "ComfyUI-TRELLIS2",
+            "ComfyUI-GeometryPack",
+            "ComfyUI-UltraShape1",
+        ],
+    );
+    pip_uninstall_best_effort(root, py_path, &["accelerate", "open3d"]);
+    Ok(())
+}
+
+fn install_named_custom_node(
+    app: &AppHandle,
+    root: &Path,
+    py_exe: &Path,
+    repo_url: &str,
+    folder_name: &str,
+) -> Result<(), String> {
+    let custom_nodes = root.join("custom_nodes");
+    std::fs::create_dir_all(&custom_nodes).map_err(|err| err.to_string())?;
+    install_custom_node(app, root, &custom_nodes, py_exe, repo_url, folder_name)
+}
+
+#[tauri::command]
+async fn apply_comfyui_component_toggle(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: ComfyComponentToggleRequest,
+) -> Result<String, String> {
+    let cancel = begin_component_toggle(&state)?;
+    let component = request.component.trim().to_string();
+    let action = if request.enabled {
+        "Installing"
+    } else {
+        "Removing"
+    };
+    emit_toggle_event(
+        &app,
+        "toggle_started",
+        &format!("{action} component '{component}'..."),
+    );
+
+    let result = run_comfyui_component_toggle(&app, &state, request, &cancel).await;
+
+    end_component_toggle(&state);
+    match &result {
+        Ok(message) => emit_toggle_event(&app, "toggle_finished", message),
+        Err(_) if cancel.is_cancelled() => emit_toggle_event(
+            &app,
+            "toggle_cancelled",
+            &format!("Component change for '{component}' cancelled."),
+        ),
+        Err(err) => emit_toggle_event(&app, "toggle_failed", err),
+    }
+    result
+}
+
+fn begin_component_toggle(state: &AppState) -> Result<CancellationToken, String> {
+    let mut active = state
+        .toggle_cancel
+        .lock()
+        .map_err(|_| "toggle state lock poisoned".to_string())?;
+    if active.is_some() {
+        return Err("Another component change is already running.".to_string());
+    }
+    let token = CancellationToken::new();
+    *active = Some(token.clone());
+    Ok(token)
+}
+
+fn end_component_toggle(state: &AppState) {
+    if let Ok(mut active) = state.toggle_cancel.lock() {
+        *active = None;
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComfyComponentChange {
+    component: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComfyComponentBatchRequest {
+    #[serde(default)]
+    comfyui_root: Option<String>,
+    changes: Vec<ComfyComponentChange>,
+}
+
+/// Applies several toggles in order under one ComfyUI stop/restart. A failed change
+/// does not stop the rest; after a cancel the remaining changes are skipped.
+#[tauri::command]
+async fn apply_comfyui_component_toggles(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: ComfyComponentBatchRequest,
+) -> Result<Vec<InstallSummaryItem>, String> {
+    if request.changes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let cancel = begin_component_toggle(&state)?;
+    let total = request.changes.len();
+    emit_toggle_event(
+        &app,
+        "toggle_started",
+        &format!("Applying {total} queued component changes..."),
+    );
+
+    let result = run_comfyui_component_batch(&app, &state, request, &cancel).await;
+
+    end_component_toggle(&state);
+    match &result {
+        Ok(items) => {
+            let failed = items.iter().filter(|item| item.status == "failed").count();
+            emit_toggle_event(
+                &app,
+                "toggle_finished",
+                &format!(
+                    "Queued component changes finished: {} ok, {failed} failed.",
+                    items.iter().filter(|item| item.status == "ok").count()
+                ),
+            );
+        }
+        Err(err) => emit_toggle_event(&app, "toggle_failed", err),
+    }
+    result
+}
+
+async fn run_comfyui_component_batch(
+    app: &AppHandle,
+    state: &AppState,
+    request: ComfyComponentBatchRequest,
+    cancel: &CancellationToken,
+) -> Result<Vec<InstallSummaryItem>, String> {
+    let root = resolve_root_path(&state.context, request.comfyui_root)?;
+    begin_mutation_session(app, state, &root)?;
+    let total = request.changes.len();
+    let mut items = Vec::with_capacity(total);
+    for (index, change) in request.changes.into_iter().enumerate() {
+        let component = change.component.trim().to_string();
+        if cancel.is_cancelled() {
+            items.push(InstallSummaryItem {
+                name: component,
+                status: "skipped".to_string(),
+                detail: "Cancelled before this change started.".to_string(),
+            });
+            continue;
+        }
+        let action = if change.enabled {
+            "Installing"
+        } else {
+            "Removing"
+        };
+        emit_toggle_event(
+            app,
+            "toggle_step",
+            &format!(
+                "({}/{total}) {action} component '{component}'...",
+                index + 1
+            ),
+        );
+        let item =
+            match apply_component_change(app, state, &root, &component, change.enabled, cancel)
+                .await
+            {
+                Ok(detail) => InstallSummaryItem {
+                    name: component,
+                    status: "ok".to_string(),
+                    detail,
+                },
+                Err(err) => InstallSummaryItem {
+                    name: component,
+                    status: if cancel.is_cancelled() {
+                        "skipped"
+                    } else {
+                        "failed"
+                    }
+                    .to_string(),
+                    detail: err,
+                },
+            };
+        items.push(item);
+    }
+    commit_mutation_session(app, state, &root)?;
+    Ok(items)
+}
+
+#[tauri::command]
+fn cancel_comfyui_component_toggle(state: State<'_, AppState>) -> Result<bool, String> {
+    let active = state
+        .toggle_cancel
+        .lock()
+        .map_err(|_| "toggle state lock poisoned".to_string())?;
+    if let Some(token) = active.as_ref() {
+        token.cancel();
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+async fn run_comfyui_component_toggle(
+    app: &AppHandle,
+    state: &AppState,
+    request: ComfyComponentToggleRequest,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, request.comfyui_root.clone())?;
+    let _maintenance = enter_maintenance(
+        app,
+        &root,
+        &format!("changing component {}", request.component),
+    );
+    let was_running = stop_comfyui_for_mutation(app, state, &root)?;
+    let result = apply_component_change(
+        app,
+        state,
+        &root,
+        &request.component,
+        request.enabled,
+        cancel,
+    )
+    .await;
+    restart_comfyui_after_mutation(app, state, &root, was_running)?;
+    result
+}
+
+/// Installs or removes one component on a stopped ComfyUI; callers own stop/restart.
+async fn apply_component_change(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+    component: &str,
+    enabled: bool,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    let root = root.to_path_buf();
+    let py_path = {
+        let probe = python_for_root(&root);
+        probe.get_program().to_string_lossy().to_string()
+    };
+    let py_exe = PathBuf::from(&py_path);
+    let _ = kill_python_processes_for_root(&root, &py_exe);
+    let component = component.trim().to_ascii_lowercase();
+
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = resolve_uv_binary(&shared_runtime_root, app)?;
+    let uv_python_install_dir = shared_runtime_root
+        .join(".python")
+        .to_string_lossy()
+        .to_string();
+
+    if matches!(component.as_str(), "addon_pinned_memory" | "pinned_memory") {
+        match component.as_str() {
+            "addon_pinned_memory" | "pinned_memory" => {
+                let settings = state.context.config.settings();
+                update_install_config(&settings, &root, |config| {
+                    config.pinned_memory_enabled = enabled
+                })?;
+                if is_configured_root(&settings, &root) {
+                    state
+                        .context
+                        .config
+                        .update_settings(|settings| {
+                            settings.comfyui_pinned_memory_enabled = enabled
+                        })
+                        .map_err(|err| err.to_string())?;
+                }
+                if enabled {
+                    Ok("Pinned memory enabled.".to_string())
+                } else {
+                    Ok("Pinned memory disabled.".to_string())
+                }
+            }
+            _ => Err("Unknown component toggle target.".to_string()),
+        }
+    } else {
+        let app_clone = app.clone();
+        let root_clone = root.clone();
+        let py_path_clone = py_path.clone();
+        let py_exe_clone = py_exe.clone();
+        let component_clone = component.clone();
+        let uv_bin_clone = uv_bin.clone();
+        let uv_python_install_dir_clone = uv_python_install_dir.clone();
+        let cancel_clone = cancel.clone();
+        tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
+            let progress = StepProgress {
+                app: &app_clone,
+                cancel: Some(&cancel_clone),
+            };
+            with_subprocess_cancel(&cancel_clone, || match component_clone.as_str() {
+                "addon_insightface" | "insightface" => {
+                    if enabled {
+                        progress.step("InsightFace: installing the prebuilt wheel and ONNX runtime...")?;
+                        install_insightface(
+                            &root_clone,
+                            &uv_bin_clone,
+                            &py_path_clone,
+                            &uv_python_install_dir_clone,
+                        )?;
+                        Ok("Installed InsightFace.".to_string())
+                    } else {
+                        if detect_launch_attention_backend_for_root(&root_clone).as_deref()
+                            == Some("nunchaku")
+                        {
+                            return Err(
+                                "Cannot remove InsightFace while Nunchaku is selected. Switch attention backend first."
+                                    .to_string(),
+                            );
+                        }
+                        progress.step("InsightFace: uninstalling packages...")?;
+                        uninstall_insightface(
+                            &root_clone,
+                            &uv_bin_clone,
+                            &py_path_clone,
+                            &uv_python_install_dir_clone,
+                        )?;
+                        Ok("Removed InsightFace.".to_string())
+                    }
+                }
+                "addon_xformers" | "xformers" => {
+                    if enabled {
+                        progress.step("xformers: installing the build for this torch profile...")?;
+                        install_xformers(
+                            &root_clone,
+                            &uv_bin_clone,
+                            &py_path_clone,
+                            &uv_python_install_dir_clone,
+                        )?;
+                        Ok("Installed xformers.".to_string())
+                    } else {
+                        progress.step("xformers: uninstalling...")?;
+                        uninstall_xformers(&root_clone, &py_path_clone)?;
+                        Ok("Removed xformers.".to_string())
+                    }
+                }
+                "addon_trellis2" | "trellis2" => {
+                    if enabled {
+                        ensure_git_available(&app_clone)?;
+                        install_trellis2(
+                            &root_clone,
+                            &uv_bin_clone,
+                            &py_path_clone,
+                            &uv_python_install_dir_clone,
+                            &progress,
+                        )?;
+                        Ok("Installed Trellis2.".to_string())
+                    } else {
+                        progress.step("Trellis2: removing custom nodes and packages...")?;
+                        uninstall_trellis2(
+                            &root_clone,
+                            &uv_bin_clone,
+                            &py_path_clone,
+                            &uv_python_install_dir_clone,
+                        )?;
+                        Ok("Removed Trellis2.".to_string())
+                    }
+                }
+                "node_comfyui_manager" => {
+                    if enabled {
+                        progress.step("ComfyUI-Manager: cloning and installing requirements...")?;
+                        ensure_git_available(&app_clone)?;
+                        install_named_custom_node(
+                            &app_clone,
+                            &root_clone,
+                            &py_exe_clone,
+                            "https://github.com/Comfy-Org/ComfyUI-Manager",
+                            "ComfyUI-Manager",
+                        )?;
+                        Ok("Installed ComfyUI-Manager.".to_string())
+                    } else {
+                        progress.step("ComfyUI-Manager: removing...")?;
+                        remove_custom_node_dirs(&root_clone, &["ComfyUI-Manager", "comfyui-manager"]);
+                        Ok("Removed ComfyUI-Manager.".to_string())
+                    }
+                }
+                "node_comfyui_easy_use" => {
+                    if enabled {
+                        progress.step("ComfyUI-Easy-Use: cloning and installing requirements...")?;
+                        ensure_git_available(&app_clone)?;
+                        install_named_custom_node(
+                            &app_clone,
+                            &root_clone,
+                            &py_exe_clone,
+                            "https://github.com/yolain/ComfyUI-Easy-Use",
+                            "ComfyUI-Easy-Use",
+                        )?;
+                        Ok("Installed ComfyUI-Easy-Use.".to_string())
+                    } else {
+                        progress.step("ComfyUI-Easy-Use: removing...")?;
+                        remove_custom_node_dirs(&root_clone, &["ComfyUI-Easy-Use"]);
+                        Ok("Removed ComfyUI-Easy-Use.".to_string())
+                    }
+                }
+                "node_rgthree_comfy" => {
+                    if enabled {
+                        progress.step("rgthree-comfy: cloning and installing requirements...")?;
+                        ensure_git_available(&app_clone)?;
+                        install_named_custom_node(
+                            &app_clone,
+                            &root_clone,
+                            &py_exe_clone,
+                            "https://github.com/rgthree/rgthree-comfy",
+                            "rgthree-comfy",
+                        )?;
+                        Ok("Installed rgthree-comfy.".to_string())
+                    } else {
+                        progress.step("rgthree-comfy: removing...")?;
+                        remove_custom_node_dirs(&root_clone, &["rgthree-comfy"]);
+                        Ok("Removed rgthree-comfy.".to_string())
+                    }
+                }
+                "node_comfyui_gguf" => {
+                    if enabled {
+                        progress.step("ComfyUI-GGUF: cloning and installing requirements...")?;
+                        ensure_git_available(&app_clone)?;
+                        install_named_custom_node(
+                            &app_clone,
+                            &root_clone,
+                            &py_exe_clone,
+                            "https://github.com/city96/ComfyUI-GGUF",
+                            "ComfyUI-GGUF",
+                        )?;
+                        Ok("Installed ComfyUI-GGUF.".to_string())
+                    } else {
+                        progress.step("ComfyUI-GGUF: removing...")?;
+                        remove_custom_node_dirs(&root_clone, &["ComfyUI-GGUF"]);
+                        Ok("Removed ComfyUI-GGUF.".to_string())
+                    }
+                }
+                "node_comfyui_kjnodes" => {
+                    if enabled {
+                        progress.step("comfyui-kjnodes: cloning and installing requirements...")?;
+                        ensure_git_available(&app_clone)?;
+                        install_named_custom_node(
+                            &app_clone,
+                            &root_clone,
+                            &py_exe_clone,
+                            "https://github.com/kijai/ComfyUI-KJNodes",
+                            "comfyui-kjnodes",
+                        )?;
+                        Ok("Installed comfyui-kjnodes.".to_string())
+                    } else {
+                        progress.step("comfyui-kjnodes: removing...")?;
+                        remove_custom_node_dirs(&root_clone, &["comfyui-kjnodes", "ComfyUI-KJNodes"]);
+                        Ok("Removed comfyui-kjnodes.".to_string())
+                    }
+                }
+                "node_comfyui_crystools" => {
+                    if enabled {
+                        progress.step("comfyui-crystools: cloning and installing requirements...")?;
+                        ensure_git_available(&app_clone)?;
+                        install_named_custom_node(
+                            &app_clone,
+                            &root_clone,
+                            &py_exe_clone,
+                            "https://github.com/crystian/comfyui-crystools.git",
+                            "comfyui-crystools",
+                        )?;
+                        Ok("Installed comfyui-crystools.".to_string())
+                    } else {
+                        progress.step("comfyui-crystools: removing...")?;
+                        remove_custom_node_dirs(&root_clone, &["comfyui-crystools", "ComfyUI-Crystools"]);
+                        Ok("Removed comfyui-crystools.".to_string())
+                    }
+                }
+                _ => Err("Unknown component toggle target.".to_string()),
+            })
+        })
+        .await
+        .map_err(|err| format!("Component operation task failed: {err}"))?
+    }
+}
+
+#[tauri::command]
+async fn install_custom_node_by_url(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    repo_url: String,
+) -> Result<InstallSummaryItem, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    if !root.join("main.py").is_file() {
+        return Err("Selected folder is not a valid ComfyUI root.".to_string());
+    }
+    let (repo_url, folder_name) = custom_node_repo_from_url(&repo_url)?;
+    let node_dir = root.join("custom_nodes").join(&folder_name);
+    if node_dir.exists() {
+        return Err(format!(
+            "Custom node folder already exists: {}. Remove it first or update it instead.",
+            node_dir.display()
+        ));
+    }
+    let py_exe = python_exe_for_root(&root)?;
+
+    let _maintenance = enter_maintenance(&app, &root, "installing a custom node");
+    let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+    let app_for_task = app.clone();
+    let root_for_task = root.clone();
+    let name_for_task = folder_name.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        ensure_git_available(&app_for_task)?;
+        install_named_custom_node(
+            &app_for_task,
+            &root_for_task,
+            &py_exe,
+            &repo_url,
+            &name_for_task,
+        )
+    })
+    .await
+    .map_err(|err| format!("Custom node install task failed: {err}"))
+    .and_then(|installed| installed);
+
+    let item = match result {
+        Ok(()) => {
+            emit_install_event(
+                &app,
+                "info",
+                &format!("Custom node {folder_name} installed."),
+            );
+            InstallSummaryItem {
+                name: folder_name,
+                status: "ok".to_string(),
+                detail: "Installed successfully.".to_string(),
+            }
+        }
+        Err(err) => {
+            emit_install_event(&app, "warn", &format!("{folder_name} failed: {err}"));
+            // Do not leave a half-cloned node behind for ComfyUI to import.
+            let _ = std::fs::remove_dir_all(&node_dir);
+            InstallSummaryItem {
+                name: folder_name,
+                status: "failed".to_string(),
+                detail: err,
+            }
+        }
+    };
+    record_install_summary_item(&root, item.clone());
+    restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    Ok(item)
+}
+
+/// Git checkouts under `custom_nodes/`, sorted by folder name. Disabled and hidden
+/// folders are left alone.
+fn git_custom_node_dirs(root: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(root.join("custom_nodes")) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path(),
+            )
+        })
+        .filter(|(name, path)| {
+            path.is_dir()
+                && !name.starts_with('.')
+                && !name.ends_with(".disabled")
+                && path.join(".git").exists()
+        })
+        .collect();
+    dirs.sort_by(|a, b| a.0.cmp(&b.0));
+    dirs
+}
+
+fn update_custom_node(
+    app: &AppHandle,
+    root: &Path,
+    node_dir: &Path,
+    py_exe: &Path,
+) -> Result<String, String> {
+    let before = git_commit_for_ref(node_dir, "HEAD");
+    run_command_with_retry(
+        "git",
+        &["pull", "--ff-only"],
+        Some(node_dir),
+        retry_settings().git,
+    )?;
+    let after = git_commit_for_ref(node_dir, "HEAD");
+    install_custom_node_requirements(app, root, node_dir, py_exe)?;
+    Ok(match (before, after) {
+        (Some(before), Some(after)) if before != after => {
+            format!("Updated {} -> {}.", &before[..7], &after[..7])
+        }
+        _ => "Already up to date; requirements re-checked.".to_string(),
+    })
+}
+
+#[tauri::command]
+async fn update_all_custom_nodes(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<Vec<InstallSummaryItem>, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    if !root.join("main.py").is_file() {
+        return Err("Selected folder is not a valid ComfyUI root.".to_string());
+    }
+    let nodes = git_custom_node_dirs(&root);
+    if nodes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let py_exe = python_exe_for_root(&root)?;
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = resolve_uv_binary(&shared_runtime_root, &app)?;
+    let uv_python_install_dir = shared_runtime_root
+        .join(".python")
+        .to_string_lossy()
+        .to_string();
+    let selected_profile = resolve_desired_torch_profile(&state.context.config.settings(), &root);
+
+    let _maintenance = enter_maintenance(&app, &root, "updating custom nodes");
+    let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+    let app_for_task = app.clone();
+    let root_for_task = root.clone();
+    let summary = tauri::async_runtime::spawn_blocking(move || -> Result<_, String> {
+        ensure_git_available(&app_for_task)?;
+        let total = nodes.len();
+        let mut summary = Vec::with_capacity(total);
+        for (index, (name, node_dir)) in nodes.into_iter().enumerate() {
+            emit_install_event(
+                &app_for_task,
+                "step",
+                &format!("Updating custom node {}/{}: {name}...", index + 1, total),
+            );
+            let item = match update_custom_node(&app_for_task, &root_for_task, &node_dir, &py_exe) {
+                Ok(detail) => InstallSummaryItem {
+                    name,
+                    status: "ok".to_string(),
+                    detail,
+                },
+                Err(err) => {
+                    emit_install_event(
+                        &app_for_task,
+                        "warn",
+                        &format!("{name} update failed: {err}"),
+                    );
+                    InstallSummaryItem {
+                        name,
+                        status: "failed".to_string(),
+                        detail: err,
+                    }
+                }
+            };
+            summary.push(item);
+        }
+
+        // Node requirements routinely pull in a different torch; put the profile back.
+        emit_install_event(
+            &app_for_task,
+            "step",
+            &format!("Re-applying torch profile {selected_profile}..."),
+        );
+        let torch_item = match enforce_torch_profile_linux(
+            &uv_bin,
+            py_exe.to_string_lossy().as_ref(),
+            &root_for_task,
+            &selected_profile,
+            &uv_python_install_dir,
+        ) {
+            Ok(()) => InstallSummaryItem {
+                name: "Torch profile".to_string(),
+                status: "ok".to_string(),
+                detail: format!("{selected_profile} re-applied."),
+            },
+            Err(err) => InstallSummaryItem {
+                name: "Torch profile".to_string(),
+                status: "failed".to_string(),
+                detail: err,
+            },
+        };
+        summary.push(torch_item);
+        Ok(summary)
+    })
+    .await
+    .map_err(|err| format!("Custom node update task failed: {err}"))
+    .and_then(|summary| summary);
+    let summary = match summary {
+        Ok(summary) => summary,
+        Err(err) => {
+            restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+            return Err(err);
+        }
+    };
+
+    let failed = summary
+        .iter()
+        .filter(|item| item.status == "failed")
+        .count();
+    emit_install_event(
+        &app,
+        "info",
+        &format!(
+            "Custom node update finished: {} ok, {failed} failed.",
+            summary.len() - failed
+        ),
+    );
+    restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    Ok(summary)
+}
+
+/// Status of `comfyui_root`, or of any instance when no root is given, plus every
+/// instance the app is running.
+#[tauri::command]
+fn get_comfyui_runtime_status(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> ComfyRuntimeStatus {
+    let instances = running_comfyui_instances(&state)
+        .into_iter()
+        .map(|(root, instance)| ComfyInstanceStatus {
+            name: comfyui_instance_name_from_path(&root),
+            root: root.to_string_lossy().to_string(),
+            port: instance.port,
+            status: instance.status().to_string(),
+        })
+        .collect();
+    let selected = comfyui_root
+        .filter(|raw| !raw.trim().is_empty())
+        .and_then(|raw| comfyui_launch_root(&state, Some(raw)).ok());
+    match selected {
+        Some(root) => ComfyRuntimeStatus {
+            running: comfyui_root_running(&state, &root),
+            port: comfyui_port_for_root(&state, &root),
+            instances,
+        },
+        None => ComfyRuntimeStatus {
+            running: comfyui_runtime_running(&state),
+            port: comfyui_launch_root(&state, None).map_or(COMFYUI_DEFAULT_PORT, |root| {
+                comfyui_port_for_root(&state, &root)
+            }),
+            instances,
+        },
+    }
+}
+
+#[tauri::command]
+fn get_comfyui_update_status(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<ComfyUiUpdateStatus, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let installed_version = read_comfyui_installed_version(&root);
+    let pinned_ref = pinned_comfyui_ref(&root);
+
+    if let Some(install) = read_container_install(&root) {
+        return Ok(ComfyUiUpdateStatus {
+            installed_version,
+            latest_version: None,
+            head_matches_latest_tag: false,
+            update_available: false,
+            checked: false,
+            pinned_ref: pinned_ref.clone(),
+            detail: format!(
+                "Runs from container image {}; Update rebuilds it.",
+                install.image
+            ),
+        });
+    }
+
+    if !root.join(".git").exists() {
+        return Ok(ComfyUiUpdateStatus {
+            installed_version,
+            latest_version: None,
+            head_matches_latest_tag: false,
+            update_available: false,
+            checked: false,
+            pinned_ref: pinned_ref.clone(),
+            detail: "Not a git-based ComfyUI install.".to_string(),
+        });
+    }
+
+    let Some((latest_tag, latest_version)) = git_latest_release_tag(&root) else {
+        return Ok(ComfyUiUpdateStatus {
+            installed_version,
+            latest_version: None,
+            head_matches_latest_tag: false,
+            update_available: false,
+            checked: false,
+            pinned_ref: pinned_ref.clone(),
+            detail: "Could not read remote ComfyUI release tags.".to_string(),
+        });
+    };
+
+    let head_commit = git_commit_for_ref(&root, "HEAD");
+    let tag_commit = git_commit_for_ref(&root, &latest_tag);
+    if head_commit.is_some() && head_commit == tag_commit {
+        return Ok(ComfyUiUpdateStatus {
+            installed_version,
+            latest_version: Some(latest_version.clone()),
+            head_matches_latest_tag: true,
+            update_available: false,
+            checked: true,
+            pinned_ref: pinned_ref.clone(),
+            detail: format!(
+                "ComfyUI is up to date by release tags (HEAD matches {latest_tag})."
+            ),
+        });
+    }
+
+    match installed_version.clone().and_then(|v| normalize_release_version(&v)) {
+        Some(local_version) => {
+            let local_triplet = parse_semver_triplet(&local_version);
+            let latest_triplet = parse_semver_triplet(&latest_version);
+            let update_available = matches!(
+                (local_triplet, latest_triplet),
+                (Some(local), Some(latest)) if latest > local
+            );
+
+            Ok(ComfyUiUpdateStatus {
+                installed_version,
+                latest_version: Some(latest_version.clone()),
+                head_matches_latest_tag: false,
+                update_available,
+                checked: true,
+                pinned_ref: pinned_ref.clone(),
+                detail: if update_available {
+                    format!(
+                        "ComfyUI update available from release tags (local v{local_version}, latest tag {latest_tag})."
+                    )
+                } else {
+                    format!(
+                        "ComfyUI is up to date by release tags (local v{local_version}, latest tag {latest_tag})."
+                    )
+                },
+            })
+        }
+        None => Ok(ComfyUiUpdateStatus {
+            installed_version,
+            latest_version: Some(latest_version.clone()),
+            head_matches_latest_tag: false,
+            update_available: false,
+            checked: true,
+            pinned_ref: pinned_ref.clone(),
+            detail: format!(
+                "Detected latest release tag {latest_tag}, but local ComfyUI version metadata is unavailable."
+            ),
+        }),
+    }
+}
+
+/// Drops the ref an install was pinned to so updates follow release tags again. The
+/// checkout itself stays where it is until the next update.
+#[tauri::command]
+fn unpin_comfyui_ref(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let Some(mut install_state) = read_install_state(&root) else {
+        return Err("No install state recorded for the selected ComfyUI install.".to_string());
+    };
+    let Some(git_ref) = install_state.comfyui_ref.take() else {
+        return Ok("ComfyUI is not pinned to a ref.".to_string());
+    };
+    let data = serde_json::to_vec_pretty(&install_state)
+        .map_err(|err| format!("Failed to serialize install state: {err}"))?;
+    std::fs::write(root.join(".arctic_install_state.json"), data)
+        .map_err(|err| format!("Failed to update install state: {err}"))?;
+    Ok(format!(
+        "Unpinned ComfyUI from {git_ref}; updates follow release tags again."
+    ))
+}
+
+/// Stops the instance for `comfyui_root`, or every instance when no root is given.
+#[tauri::command]
+fn stop_comfyui_root(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<bool, String> {
+    let root = comfyui_root
+        .filter(|raw| !raw.trim().is_empty())
+        .map(|raw| comfyui_launch_root(&state, Some(raw)))
+        .transpose()?;
+    let Some(root) = root else {
+        return stop_all_comfyui_roots(&app, &state);
+    };
+    let instance_name = comfyui_instance_name_from_path(&root);
+    emit_comfyui_root_event(
+        &app,
+        Some(&root),
+        "stopping",
+        format!("Stopping {instance_name}..."),
+    );
+    let result = stop_comfyui_root_impl(&state, &root);
+    update_tray_comfy_status(&app);
+    match &result {
+        Ok(_) if comfyui_root_running(&state, &root) => emit_comfyui_root_event(
+            &app,
+            Some(&root),
+            "stop_failed",
+            format!("{instance_name} stop did not fully complete."),
+        ),
+        Ok(_) => emit_comfyui_root_event(
+            &app,
+            Some(&root),
+            "stopped",
+            format!("{instance_name} stopped."),
+        ),
+        Err(err) => emit_comfyui_root_event(
+            &app,
+            Some(&root),
+            "stop_failed",
+            format!("{instance_name} stop failed: {err}"),
+        ),
+    }
+    result
+}
+
+/// Stops every instance the app knows of and the configured root's service or container.
+fn stop_all_comfyui_roots(app: &AppHandle, state: &AppState) -> Result<bool, String> {
+    let mut roots: Vec<PathBuf> = running_comfyui_instances(state)
+        .into_iter()
+        .map(|(root, _)| root)
+        .collect();
+    if let Ok(configured) = comfyui_launch_root(state, None) {
+        if !roots.contains(&configured) {
+            roots.push(configured);
+        }
+    }
+    emit_comfyui_runtime_event(app, "stopping", "Stopping ComfyUI...");
+    let mut stopped_any = false;
+    let mut failures = Vec::new();
+    for root in &roots {
+        match stop_comfyui_root_impl(state, root) {
+            Ok(stopped) => stopped_any |= stopped,
+            Err(err) => failures.push(format!("{}: {err}", comfyui_instance_name_from_path(root))),
+        }
+    }
+    update_tray_comfy_status(app);
+    if !failures.is_empty() {
+        let err = failures.join("; ");
+        emit_comfyui_runtime_event(app, "stop_failed", format!("ComfyUI stop failed: {err}"));
+        return Err(err);
+    }
+    if comfyui_runtime_running(state) {
+        emit_comfyui_runtime_event(app, "stop_failed", "ComfyUI stop did not fully complete.");
+    } else {
+        emit_comfyui_runtime_event(app, "stopped", "ComfyUI stopped.");
+    }
+    Ok(stopped_any)
+}
+
+#[tauri::command]
+async fn update_selected_comfyui(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let _maintenance = enter_maintenance(&app, &root, "updating");
+    let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+    let result = update_stopped_comfyui(&app, &state, &root).await;
+    restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    result
+}
+
+/// Moves a stopped install to its latest release tag (or pinned branch head) and
+/// reinstalls requirements; container installs get their image rebuilt instead.
+async fn update_stopped_comfyui(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+) -> Result<String, String> {
+    if let Some(install) = read_container_install(root) {
+        let app_for_task = app.clone();
+        let root_for_task = root.to_path_buf();
+        let image = install.image.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            set_maintenance_step(&app_for_task, &root_for_task, "rebuilding container image");
+            build_container_image(&root_for_task, &install, true)
+        })
+        .await
+        .map_err(|err| format!("ComfyUI update task failed: {err}"))??;
+        return Ok(format!("Rebuilt {image} with the latest ComfyUI."));
+    }
+    if !root.join("main.py").is_file() {
+        return Err("Selected folder is not a valid ComfyUI root.".to_string());
+    }
+    if !root.join(".git").exists() {
+        return Err("Selected ComfyUI install is not git-based.".to_string());
+    }
+    let pinned_branch = match pinned_comfyui_ref(root) {
+        Some(git_ref)
+            if git_commit_for_ref(root, &format!("refs/remotes/origin/{git_ref}")).is_some() =>
+        {
+            Some(git_ref)
+        }
+        Some(git_ref) => {
+            return Ok(format!(
+                "ComfyUI is pinned to {git_ref}. Unpin it to follow release tags."
+            ));
+        }
+        None => None,
+    };
+
+    // A pinned branch follows its remote head instead of the latest release tag.
+    let (latest_tag, latest_version) = match pinned_branch.as_deref() {
+        Some(branch) => (format!("origin/{branch}"), branch.to_string()),
+        None => git_latest_release_tag(root).ok_or_else(|| {
+            "Could not resolve latest ComfyUI release tag from remote.".to_string()
+        })?,
+    };
+    let installed_version_norm = read_comfyui_installed_version(root)
+        .and_then(|v| normalize_release_version(&v))
+        .filter(|_| pinned_branch.is_none());
+    let target_label = match pinned_branch.as_deref() {
+        Some(branch) => format!("pinned branch {branch}"),
+        None => format!("release tag {latest_tag} (v{latest_version})"),
+    };
+    if let Some(current) = installed_version_norm {
+        let current_triplet = parse_semver_triplet(&current);
+        let latest_triplet = parse_semver_triplet(&latest_version);
+        if matches!(
+            (current_triplet, latest_triplet),
+            (Some(local), Some(latest)) if local >= latest
+        ) {
+            return Ok(format!(
+                "ComfyUI is already on latest release tag (v{latest_version})."
+            ));
+        }
+    }
+
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = resolve_uv_binary(&shared_runtime_root, app)?;
+    let uv_python_install_dir = shared_runtime_root
+        .join(".python")
+        .to_string_lossy()
+        .to_string();
+    let selected_profile = resolve_desired_torch_profile(&state.context.config.settings(), root);
+    let latest_tag_for_task = latest_tag.clone();
+    let target_label_for_task = target_label.clone();
+    let branch_for_task_raw = git_current_branch(root).unwrap_or_else(|| "master".to_string());
+    let branch_for_task = if branch_for_task_raw.eq_ignore_ascii_case("head") {
+        "master".to_string()
+    } else {
+        branch_for_task_raw
+    };
+    let py_for_snapshot = python_exe_for_root(root)?;
+    snapshot_env_before(
+        app,
+        root,
+        &uv_bin,
+        &py_for_snapshot.to_string_lossy(),
+        UPDATE_SNAPSHOT_REASON,
+    );
+    let app_for_task = app.clone();
+    let root_for_task = root.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
+        set_maintenance_step(&app_for_task, &root_for_task, "fetching release tags");
+        run_command_with_retry("git", &["fetch", "--tags", "origin"], Some(&root_for_task), retry_settings().git)?;
+        set_maintenance_step(&app_for_task, &root_for_task, format!("checking out {latest_tag_for_task}"));
+        if let Err(err) =
+            run_command_with_retry("git", &["merge", "--ff-only", &latest_tag_for_task], Some(&root_for_task), retry_settings().git)
+        {
+            let lower = err.to_ascii_lowercase();
+            let can_repoint_branch = lower.contains("unrelated histories")
+                || lower.contains("not possible to fast-forward")
+                || lower.contains("not possible to fast forward")
+                || lower.contains("cannot fast-forward")
+                || lower.contains("diverging");
+            if can_repoint_branch {
+                // Preserve recoverability before repointing branch tip to release tag.
+                let ts = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                let backup_branch = format!("arctic-backup-before-tag-update-{ts}");
+                run_command_with_retry("git", &["branch", &backup_branch], Some(&root_for_task), RetryPolicy::once())
+                    .map_err(|backup_err| {
+                        format!(
+                            "Failed to create backup branch before tag migration ({backup_branch}). Details: {backup_err}"
+                        )
+                    })?;
+                run_command_with_retry(
+                    "git",
+                    &["checkout", "-B", &branch_for_task, &latest_tag_for_task],
+                    Some(&root_for_task),
+                    RetryPolicy::once(),
+                )
+                .map_err(|checkout_err| {
+                    format!(
+                        "Failed to switch branch '{}' to release tag {} after merge fast-forward failed. Backup branch: {}. Details: {}",
+                        branch_for_task, latest_tag_for_task, backup_branch, checkout_err
+                    )
+                })?;
+            } else {
+                return Err(format!(
+                    "Failed to fast-forward ComfyUI to release tag {latest_tag_for_task}. Resolve local git divergence first. Details: {err}"
+                ));
+            }
+        }
+
+        set_maintenance_step(&app_for_task, &root_for_task, "installing requirements");
+        reinstall_comfyui_requirements(&uv_bin, &root_for_task, &selected_profile, &uv_python_install_dir)?;
+        Ok(format!(
+            "ComfyUI updated successfully to {target_label_for_task}."
+        ))
+    })
+    .await
+    .map_err(|err| format!("ComfyUI update task failed: {err}"))??;
+    Ok(format!("ComfyUI updated successfully to {target_label}."))
+}
+
+fn reinstall_comfyui_requirements(
+    uv_bin: &str,
+    root: &Path,
+    selected_profile: &str,
+    uv_python_install_dir: &str,
+) -> Result<(), String> {
+    let py = python_exe_for_root(root)?;
+    if !root.join("requirements.txt").exists() {
+        return Ok(());
+    }
+    run_uv_pip_strict(
+        uv_bin,
+        py.to_string_lossy().as_ref(),
+        &["install", "-r", "requirements.txt", "--no-cache"],
+        Some(root),
+        &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+    )
+    .map_err(|err| format!("Failed to install ComfyUI requirements: {err}"))?;
+    if let Some(version) = read_install_config(root).and_then(|config| config.frontend_version) {
+        install_frontend_package(
+            uv_bin,
+            py.to_string_lossy().as_ref(),
+            root,
+            Some(&version),
+            uv_python_install_dir,
+        )?;
+    }
+    enforce_torch_profile_linux(
+        uv_bin,
+        py.to_string_lossy().as_ref(),
+        root,
+        selected_profile,
+        uv_python_install_dir,
+    )
+    .map_err(|err| format!("Failed to re-apply selected torch profile: {err}"))
+}
+
+const FRONTEND_PACKAGE: &str = "comfyui-frontend-package";
+
+/// A frontend version such as `1.28.7`; empty means no pin.
+fn normalize_frontend_version(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(version) = raw.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    let version = version.trim_start_matches(['v', '=']);
+    let valid = version.starts_with(|c: char| c.is_ascii_digit())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.');
+    if !valid {
+        return Err(format!(
+            "'{version}' is not a {FRONTEND_PACKAGE} version (expected something like 1.28.7)."
+        ));
+    }
+    Ok(Some(version.to_string()))
+}
+
+/// Installs `version` of the frontend package, or with `None` the version
+/// requirements.txt asks for.
+fn install_frontend_package(
+    uv_bin: &str,
+    py_path: &str,
+    root: &Path,
+    version: Option<&str>,
+    uv_python_install_dir: &str,
+) -> Result<(), String> {
+    let requirement = match version {
+        Some(version) => format!("{FRONTEND_PACKAGE}=={version}"),
+        None => std::fs::read_to_string(root.join("requirements.txt"))
+            .ok()
+            .and_then(|text| {
+                text.lines()
+                    .map(str::trim)
+                    .find(|line| line.starts_with(FRONTEND_PACKAGE))
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| FRONTEND_PACKAGE.to_string()),
+    };
+    run_uv_pip_strict(
+        uv_bin,
+        py_path,
+        &["install", &requirement],
+        Some(root),
+        &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
+    )
+    .map_err(|err| format!("Failed to install {requirement}: {err}"))
+}
+
+/// Pins the install's frontend package to `version`, or with no version goes back to
+/// the requirements.txt one. The pin is recorded so updates keep it.
+#[tauri::command]
+async fn set_comfyui_frontend_version(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    version: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    if read_container_install(&root).is_some() {
+        return Err("Container installs take the frontend from the image build.".to_string());
+    }
+    if !root.join("main.py").is_file() {
+        return Err("Selected folder is not a valid ComfyUI root.".to_string());
+    }
+    let version = normalize_frontend_version(version.as_deref())?;
+    let py_path = python_exe_for_root(&root)?.to_string_lossy().to_string();
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = resolve_uv_binary(&shared_runtime_root, &app)?;
+    let uv_python_install_dir = shared_runtime_root
+        .join(".python")
+        .to_string_lossy()
+        .to_string();
+
+    let _maintenance = enter_maintenance(&app, &root, "changing the frontend version");
+    let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+    let root_for_task = root.clone();
+    let version_for_task = version.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        install_frontend_package(
+            &uv_bin,
+            &py_path,
+            &root_for_task,
+            version_for_task.as_deref(),
+            &uv_python_install_dir,
+        )
+    })
+    .await
+    .map_err(|err| format!("Frontend install task failed: {err}"))
+    .and_then(|installed| installed)
+    .and_then(|()| {
+        update_install_config(&state.context.config.settings(), &root, |config| {
+            config.frontend_version = version.clone()
+        })
+    });
+
+    restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    result?;
+    Ok(match version {
+        Some(version) => format!("ComfyUI frontend pinned to {version}."),
+        None => "ComfyUI frontend follows requirements.txt again.".to_string(),
+    })
+}
+
+const UPDATE_SNAPSHOT_REASON: &str = "comfyui-update";
+
+/// The snapshot `update_selected_comfyui` took before moving ComfyUI, while the
+/// install is still on a different commit than the one it recorded.
+fn update_rollback_snapshot(root: &Path) -> Option<EnvSnapshot> {
+    let head = git_commit_for_ref(root, "HEAD");
+    env_snapshots_for_root(root)
+        .into_iter()
+        .find(|snapshot| snapshot.reason == UPDATE_SNAPSHOT_REASON)
+        .filter(|snapshot| snapshot.comfyui_commit.is_some() && snapshot.comfyui_commit != head)
+}
+
+#[tauri::command]
+fn get_comfyui_rollback(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<Option<EnvSnapshotSummary>, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    Ok(update_rollback_snapshot(&root).map(EnvSnapshotSummary::from))
+}
+
+/// Undoes the last ComfyUI update by restoring the environment snapshot taken before
+/// it, which brings back both the previous commit and its exact package set.
+#[tauri::command]
+async fn rollback_comfyui_update(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    if !root.join(".git").exists() {
+        return Err("Selected ComfyUI install is not git-based.".to_string());
+    }
+    let snapshot = update_rollback_snapshot(&root)
+        .ok_or_else(|| "No pre-update snapshot recorded for this install.".to_string())?;
+    let commit = snapshot.comfyui_commit.clone().unwrap_or_default();
+    restore_snapshot(&app, &state, &root, snapshot).await?;
+    Ok(format!(
+        "ComfyUI rolled back to {}.",
+        commit.get(..7).unwrap_or(&commit)
+    ))
+}
+
+const ENV_SNAPSHOT_DIR: &str = ".arctic_snapshots";
+const ENV_SNAPSHOT_KEEP: usize = 10;
+
+/// `uv pip freeze` plus the ComfyUI commit, taken before risky changes so the exact
+/// environment can be rebuilt with `restore_env_snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EnvSnapshot {
+    id: String,
+    created_at: u64,
+    reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comfyui_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    torch_profile: Option<String>,
+    packages: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct EnvSnapshotSummary {
+    id: String,
+    created_at: u64,
+    reason: String,
+    comfyui_commit: Option<String>,
+    torch_profile: Option<String>,
+    package_count: usize,
+}
+
+impl From<EnvSnapshot> for EnvSnapshotSummary {
+    fn from(snapshot: EnvSnapshot) -> Self {
+        Self {
+            package_count: snapshot.packages.len(),
+            id: snapshot.id,
+            created_at: snapshot.created_at,
+            reason: snapshot.reason,
+            comfyui_commit: snapshot.comfyui_commit,
+            torch_profile: snapshot.torch_profile,
+        }
+    }
+}
+
+fn env_snapshots_for_root(root: &Path) -> Vec<EnvSnapshot> {
+    let Ok(entries) = std::fs::read_dir(root.join(ENV_SNAPSHOT_DIR)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<EnvSnapshot> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| std::fs::read(path).ok())
+        .filter_map(|data| serde_json::from_slice(&data).ok())
+        .collect();
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    snapshots
+}
+
+fn capture_env_snapshot(
+    root: &Path,
+    uv_bin: &str,
+    py_path: &str,
+    reason: &str,
+) -> Result<EnvSnapshot, String> {
+    let (stdout, _) =
+        run_command_capture(uv_bin, &["pip", "freeze", "--python", py_path], Some(root))
+            .map_err(|err| format!("Failed to freeze Python environment: {err}"))?;
+    let packages: Vec<String> = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    if packages.is_empty() {
+        return Err("Python environment reported no installed packages.".to_string());
+    }
+    let created_at = unix_now_secs();
+    let snapshot = EnvSnapshot {
+        id: format!("{created_at}-{reason}"),
+        created_at,
+        reason: reason.to_string(),
+        comfyui_commit: git_commit_for_ref(root, "HEAD"),
+        torch_profile: profile_from_torch_env(root).ok(),
+        packages,
+    };
+
+    let dir = root.join(ENV_SNAPSHOT_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+    let data = serde_json::to_vec_pretty(&snapshot).map_err(|err| err.to_string())?;
+    std::fs::write(dir.join(format!("{}.json", snapshot.id)), data)
+        .map_err(|err| format!("Failed to write environment snapshot: {err}"))?;
+
+    for stale in env_snapshots_for_root(root)
+        .into_iter()
+        .skip(ENV_SNAPSHOT_KEEP)
+    {
+        let _ = std::fs::remove_file(dir.join(format!("{}.json", stale.id)));
+    }
+    Ok(snapshot)
+}
+
+/// Best-effort snapshot ahead of a risky change; a failure is reported but does not
+/// block the change itself.
+fn snapshot_env_before(app: &AppHandle, root: &Path, uv_bin: &str, py_path: &str, reason: &str) {
+    match capture_env_snapshot(root, uv_bin, py_path, reason) {
+        Ok(snapshot) => emit_install_event(
+            app,
+            "info",
+            &format!(
+                "Saved environment snapshot {} ({} packages).",
+                snapshot.id,
+                snapshot.packages.len()
+            ),
+        ),
+        Err(err) => emit_install_event(
+            app,
+            "warn",
+            &format!("Could not save environment snapshot before {reason}: {err}"),
+        ),
+    }
+}
+
+#[tauri::command]
+fn list_env_snapshots(
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<Vec<EnvSnapshotSummary>, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    Ok(env_snapshots_for_root(&root)
+        .into_iter()
+        .map(EnvSnapshotSummary::from)
+        .collect())
+}
+
+#[tauri::command]
+async fn restore_env_snapshot(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    snapshot_id: String,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    let snapshot = env_snapshots_for_root(&root)
+        .into_iter()
+        .find(|snapshot| snapshot.id == snapshot_id.trim())
+        .ok_or_else(|| format!("Environment snapshot '{}' not found.", snapshot_id.trim()))?;
+    restore_snapshot(&app, &state, &root, snapshot).await
+}
+
+/// Checks out the snapshot's ComfyUI commit and syncs the venv to its package list,
+/// snapshotting the current state first.
+async fn restore_snapshot(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+    snapshot: EnvSnapshot,
+) -> Result<String, String> {
+    let py_path = python_exe_for_root(root)?.to_string_lossy().to_string();
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = resolve_uv_binary(&shared_runtime_root, app)?;
+    let uv_python_install_dir = shared_runtime_root
+        .join(".python")
+        .to_string_lossy()
+        .to_string();
+
+    let _maintenance = enter_maintenance(app, root, "restoring an environment snapshot");
+    let was_running = stop_comfyui_for_mutation(app, state, root)?;
+    let app_for_task = app.clone();
+    let root_for_task = root.to_path_buf();
+    let message = tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
+        // Keep the current state restorable too, in case the snapshot is worse.
+        snapshot_env_before(
+            &app_for_task,
+            &root_for_task,
+            &uv_bin,
+            &py_path,
+            "pre-restore",
+        );
+
+        if let Some(commit) = snapshot.comfyui_commit.as_deref() {
+            if git_commit_for_ref(&root_for_task, "HEAD").as_deref() != Some(commit) {
+                let branch = git_current_branch(&root_for_task)
+                    .filter(|branch| !branch.eq_ignore_ascii_case("head"))
+                    .unwrap_or_else(|| "master".to_string());
+                emit_install_event(
+                    &app_for_task,
+                    "step",
+                    &format!(
+                        "Checking out ComfyUI commit {}...",
+                        commit.get(..7).unwrap_or(commit)
+                    ),
+                );
+                run_command_with_retry(
+                    "git",
+                    &["checkout", "-B", &branch, commit],
+                    Some(&root_for_task),
+                    RetryPolicy::once(),
+                )
+                .map_err(|err| format!("Failed to restore ComfyUI commit {commit}: {err}"))?;
+            }
+        }
+
+        let requirements = root_for_task
+            .join(ENV_SNAPSHOT_DIR)
+            .join(format!("{}.requirements.txt", snapshot.id));
+        std::fs::write(&requirements, snapshot.packages.join("\n") + "\n")
+            .map_err(|err| format!("Failed to write snapshot requirements: {err}"))?;
+        let requirements_arg = requirements.to_string_lossy().to_string();
+        let mut args = vec!["sync", requirements_arg.as_str()];
+        // Local versions such as `torch==2.8.0+cu128` only resolve from the profile's index.
+        let torch_index = snapshot
+            .torch_profile
+            .as_deref()
+            .map(|profile| torch_profile_to_packages_linux(profile).3);
+        if let Some(index_url) = torch_index.as_ref() {
+            args.extend([
+                "--extra-index-url",
+                index_url,
+                "--index-strategy",
+                "unsafe-best-match",
+            ]);
+        }
+        emit_install_event(
+            &app_for_task,
+            "step",
+            &format!(
+                "Restoring {} packages from snapshot {}...",
+                snapshot.packages.len(),
+                snapshot.id
+            ),
+        );
+        let result = run_uv_pip_strict(
+            &uv_bin,
+            &py_path,
+            &args,
+            Some(&root_for_task),
+            &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
+        );
+        let _ = std::fs::remove_file(&requirements);
+        result.map_err(|err| format!("Failed to restore snapshot packages: {err}"))?;
+        Ok(format!("Restored environment snapshot {}.", snapshot.id))
+    })
+    .await
+    .map_err(|err| format!("Snapshot restore task failed: {err}"))
+    .and_then(|message| message);
+
+    restart_comfyui_after_mutation(app, state, root, was_running)?;
+    message
+}
+
+#[derive(Debug, Serialize)]
+struct RepairStage {
+    stage: &'static str,
+    label: &'static str,
+    status: &'static str, // ok | repaired | failed | skipped
+    detail: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ComfyRepairReport {
+    comfyui_root: String,
+    ok: bool,
+    repaired: usize,
+    failed: usize,
+    stages: Vec<RepairStage>,
+    report_path: Option<String>,
+}
+
+const REPAIR_STAGES: [(&str, &str); 6] = [
+    ("venv", "Python environment"),
+    ("torch", "Torch profile"),
+    ("dependencies", "Dependency conflicts"),
+    ("attention", "Attention backend"),
+    ("custom_nodes", "Custom nodes"),
+    ("model_paths", "Model paths"),
+];
+
+/// A phase-and-message line on `comfyui-install-progress`, for operations whose
+/// progress is just text: component toggles and repairs.
+fn emit_progress_message(app: &AppHandle, kind: &str, phase: &str, message: &str) {
+    let _ = app.emit(
+        "comfyui-install-progress",
+        DownloadProgressEvent {
+            kind: kind.to_string(),
+            phase: phase.to_string(),
+            artifact: None,
+            index: None,
+            total: None,
+            received: None,
+            size: None,
+            folder: None,
+            message: Some(message.to_string()),
+        },
+    );
+}
+
+fn emit_toggle_event(app: &AppHandle, phase: &str, message: &str) {
+    emit_progress_message(app, "component_toggle", phase, message);
+}
+
+/// Step reporter for long component installs: emits a progress line per step and,
+/// for cancellable operations, stops between steps once they are cancelled.
+struct StepProgress<'a> {
+    app: &'a AppHandle,
+    cancel: Option<&'a CancellationToken>,
+}
+
+impl StepProgress<'_> {
+    fn step(&self, message: &str) -> Result<(), String> {
+        if self.cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(SUBPROCESS_CANCELLED.to_string());
+        }
+        emit_toggle_event(self.app, "toggle_step", message);
+        Ok(())
+    }
+}
+
+fn emit_repair_event(app: &AppHandle, phase: &str, message: &str) {
+    emit_progress_message(app, "comfyui_repair", phase, message);
+}
+
+fn push_repair_stage(
+    app: &AppHandle,
+    stages: &mut Vec<RepairStage>,
+    stage: &'static str,
+    status: &'static str,
+    detail: String,
+) {
+    let label = REPAIR_STAGES
+        .iter()
+        .find(|(id, _)| *id == stage)
+        .map(|(_, label)| *label)
+        .unwrap_or(stage);
+    let phase = if status == "failed" {
+        "repair_stage_failed"
+    } else {
+        "repair_stage"
+    };
+    emit_repair_event(app, phase, &format!("[{label}] {status}: {detail}"));
+    stages.push(RepairStage {
+        stage,
+        label,
+        status,
+        detail,
+    });
+}
+
+/// Custom node folders that look broken: no top-level `__init__.py`, or a git
+/// checkout whose HEAD no longer resolves.
+fn broken_custom_nodes(root: &Path) -> Vec<(String, PathBuf, &'static str)> {
+    let Ok(entries) = std::fs::read_dir(root.join("custom_nodes")) else {
+        return Vec::new();
+    };
+    let mut broken = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir()
+            || name.starts_with('.')
+            || name == "__pycache__"
+            || name.ends_with(".disabled")
+        {
+            continue;
+        }
+        if !path.join("__init__.py").is_file() {
+            broken.push((name, path, "missing __init__.py"));
+        } else if path.join(".git").exists()
+            && run_command_capture("git", &["rev-parse", "HEAD"], Some(&path)).is_err()
+        {
+            broken.push((name, path, "git checkout is corrupt"));
+        }
+    }
+    broken.sort_by(|a, b| a.0.cmp(&b.0));
+    broken
+}
+
+/// Origin of `dir` when it is a git checkout of its own. A folder without `.git`
+/// would otherwise report the remote of the ComfyUI checkout around it.
+fn custom_node_own_remote(dir: &Path) -> Option<String> {
+    let (toplevel, _) =
+        run_command_capture("git", &["rev-parse", "--show-toplevel"], Some(dir)).ok()?;
+    let toplevel = std::fs::canonicalize(toplevel.trim()).ok()?;
+    if toplevel != std::fs::canonicalize(dir).ok()? {
+        return None;
+    }
+    run_command_capture("git", &["remote", "get-url", "origin"], Some(dir))
+        .ok()
+        .map(|(stdout, _)| stdout.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// Moves a broken node aside as `<name>.repair-<ts>.disabled`, which ComfyUI skips,
+/// so a failed or wrong reinstall never costs the user their files.
+fn set_aside_custom_node(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let backup = dir.with_file_name(format!("{name}.repair-{}.disabled", unix_now_secs()));
+    std::fs::rename(dir, &backup)
+        .map_err(|err| format!("cannot move {} aside: {err}", dir.display()))?;
+    Ok(backup)
+}
+
+fn run_comfyui_repair(
+    app: &AppHandle,
+    root: &Path,
+    uv_bin: &str,
+    uv_python_install_dir: &str,
+    profile: &str,
+    expected_backend: Option<&str>,
+) -> Vec<RepairStage> {
+    let mut stages = Vec::new();
+    let envs = [("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)];
+
+    let py = match python_exe_for_root(root) {
+        Ok(py) if python_exe_works(&py, root) => {
+            push_repair_stage(
+                app,
+                &mut stages,
+                "venv",
+                "ok",
+                format!("Using {}.", py.display()),
+            );
+            py
+        }
+        outcome => {
+            let detail = match outcome {
+                Ok(py) => format!("{} exists but does not run.", py.display()),
+                Err(err) => err,
+            };
+            push_repair_stage(app, &mut stages, "venv", "failed", detail);
+            for (stage, _) in &REPAIR_STAGES[1..] {
+                push_repair_stage(
+                    app,
+                    &mut stages,
+                    stage,
+                    "skipped",
+                    "No working Python environment.".to_string(),
+                );
+            }
+            return stages;
+        }
+    };
+    let py_path = py.to_string_lossy().to_string();
+
+    match detect_torch_profile_for_root(root) {
+        Some(found) if found == profile => push_repair_stage(
+            app,
+            &mut stages,
+            "torch",
+            "ok",
+            format!("{profile} is installed."),
+        ),
+        found => {
+            emit_repair_event(
+                app,
+                "repair_step",
+                &format!("Re-applying Torch profile {profile}..."),
+            );
+            match enforce_torch_profile_linux(
+                uv_bin,
                 &py_path,
-                &["install", "--upgrade", "--force-reinstall", triton_pkg],
-                Some(&root),
-                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
-            )?;
-            install_flashattention_linux(&root, &py_path, &profile, hopper_sm90)?;
+                root,
+                profile,
+                uv_python_install_dir,
+            ) {
+                Ok(()) => push_repair_stage(
+                    app,
+                    &mut stages,
+                    "torch",
+                    "repaired",
+                    format!(
+                        "Reinstalled {profile} (found {}).",
+                        found.as_deref().unwrap_or("no usable Torch")
+                    ),
+                ),
+                Err(err) => push_repair_stage(app, &mut stages, "torch", "failed", err),
+            }
+        }
+    }
+
+    match run_uv_pip_strict(uv_bin, &py_path, &["check"], Some(root), &envs) {
+        Ok(()) => push_repair_stage(
+            app,
+            &mut stages,
+            "dependencies",
+            "ok",
+            "No conflicting packages.".to_string(),
+        ),
+        Err(conflicts) => {
+            emit_repair_event(
+                app,
+                "repair_step",
+                "Reinstalling ComfyUI requirements to resolve conflicts...",
+            );
+            let fixed = run_uv_pip_strict(
+                uv_bin,
+                &py_path,
+                &["install", "-r", "requirements.txt"],
+                Some(root),
+                &envs,
+            )
+            .and_then(|_| {
+                enforce_torch_profile_linux(uv_bin, &py_path, root, profile, uv_python_install_dir)
+            })
+            .and_then(|_| run_uv_pip_strict(uv_bin, &py_path, &["check"], Some(root), &envs));
+            match fixed {
+                Ok(()) => push_repair_stage(
+                    app,
+                    &mut stages,
+                    "dependencies",
+                    "repaired",
+                    "Requirements reinstalled; no conflicts remain.".to_string(),
+                ),
+                Err(err) => push_repair_stage(
+                    app,
+                    &mut stages,
+                    "dependencies",
+                    "failed",
+                    format!("{err} (before repair: {conflicts})"),
+                ),
+            }
+        }
+    }
+
+    let detected =
+        detect_launch_attention_backend_for_root(root).unwrap_or_else(|| "none".to_string());
+    let expected = expected_backend.unwrap_or(&detected).to_string();
+    if expected == "none" || installed_attention_backends(root).contains(&expected.as_str()) {
+        push_repair_stage(
+            app,
+            &mut stages,
+            "attention",
+            "ok",
+            format!("Backend '{expected}' is installed."),
+        );
+    } else {
+        emit_repair_event(
+            app,
+            "repair_step",
+            &format!("Reinstalling attention backend '{expected}'..."),
+        );
+        let request = AttentionBackendChangeRequest {
+            comfyui_root: Some(root.to_string_lossy().to_string()),
+            target_backend: expected.clone(),
+            torch_profile: Some(profile.to_string()),
+            // Restoring a backend the install already had.
+            allow_incompatible: true,
+            keep_others: true,
+        };
+        match apply_attention_backend_change(app.clone(), app.state::<AppState>(), request) {
+            Ok(_) => push_repair_stage(
+                app,
+                &mut stages,
+                "attention",
+                "repaired",
+                format!("Reinstalled '{expected}' (launch detection found '{detected}')."),
+            ),
+            Err(err) => push_repair_stage(app, &mut stages, "attention", "failed", err),
+        }
+    }
+
+    let broken = broken_custom_nodes(root);
+    if broken.is_empty() {
+        push_repair_stage(
+            app,
+            &mut stages,
+            "custom_nodes",
+            "ok",
+            "No broken custom nodes found.".to_string(),
+        );
+    } else {
+        let mut reinstalled = Vec::new();
+        let mut unresolved = Vec::new();
+        for (name, dir, reason) in broken {
+            let Some(remote) = custom_node_own_remote(&dir) else {
+                unresolved.push(format!(
+                    "{name} ({reason}; not a git checkout of its own to reinstall from)"
+                ));
+                continue;
+            };
+            let backup = match set_aside_custom_node(&dir, &name) {
+                Ok(backup) => backup,
+                Err(err) => {
+                    unresolved.push(format!("{name} ({reason}; {err})"));
+                    continue;
+                }
+            };
+            match install_named_custom_node(app, root, &py, &remote, &name) {
+                Ok(()) => reinstalled.push(format!(
+                    "{name} (old copy kept as {})",
+                    backup.file_name().unwrap_or_default().to_string_lossy()
+                )),
+                Err(err) => unresolved.push(format!(
+                    "{name} ({reason}; reinstall failed: {err}; old copy kept as {})",
+                    backup.display()
+                )),
+            }
+        }
+        let mut parts = Vec::new();
+        if !reinstalled.is_empty() {
+            parts.push(format!("reinstalled {}", reinstalled.join(", ")));
+        }
+        if !unresolved.is_empty() {
+            parts.push(format!("could not fix {}", unresolved.join(", ")));
+        }
+        let status = if unresolved.is_empty() {
+            "repaired"
+        } else {
+            "failed"
+        };
+        push_repair_stage(app, &mut stages, "custom_nodes", status, parts.join("; "));
+    }
+
+    let mut created = false;
+    let mut problems = Vec::new();
+    let models_dir = root.join("models");
+    if !models_dir.is_dir() {
+        match std::fs::create_dir_all(&models_dir) {
+            Ok(()) => created = true,
+            Err(err) => problems.push(format!("cannot create {}: {err}", models_dir.display())),
+        }
+    }
+    if let Some(config) = comfy_extra_model_config(root) {
+        if !config.base_path.is_dir() {
+            problems.push(format!(
+                "extra_model_paths.yaml points to missing folder {}",
+                config.base_path.display()
+            ));
+        }
+    }
+    if !problems.is_empty() {
+        push_repair_stage(
+            app,
+            &mut stages,
+            "model_paths",
+            "failed",
+            problems.join("; "),
+        );
+    } else if created {
+        push_repair_stage(
+            app,
+            &mut stages,
+            "model_paths",
+            "repaired",
+            "Recreated the models folder.".to_string(),
+        );
+    } else {
+        push_repair_stage(
+            app,
+            &mut stages,
+            "model_paths",
+            "ok",
+            "Model folders are reachable.".to_string(),
+        );
+    }
+
+    stages
+}
+
+#[tauri::command]
+async fn repair_comfyui_installation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<ComfyRepairReport, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    if !root.join("main.py").is_file() {
+        return Err("Selected folder is not a valid ComfyUI root.".to_string());
+    }
+    let _maintenance = enter_maintenance(&app, &root, "repairing");
+    let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+
+    let settings = state.context.config.settings();
+    let expected_backend = install_config(&settings, &root).attention_backend;
+    let profile = resolve_desired_torch_profile(&settings, &root);
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = match resolve_uv_binary(&shared_runtime_root, &app) {
+        Ok(uv_bin) => uv_bin,
+        Err(err) => {
+            restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+            return Err(err);
+        }
+    };
+    let uv_python_install_dir = shared_runtime_root
+        .join(".python")
+        .to_string_lossy()
+        .to_string();
+
+    emit_repair_event(
+        &app,
+        "repair_started",
+        &format!("Repairing {}...", root.display()),
+    );
+    let app_for_task = app.clone();
+    let root_for_task = root.clone();
+    let stages = tauri::async_runtime::spawn_blocking(move || {
+        run_comfyui_repair(
+            &app_for_task,
+            &root_for_task,
+            &uv_bin,
+            &uv_python_install_dir,
+            &profile,
+            expected_backend.as_deref(),
+        )
+    })
+    .await;
+    let stages = match stages {
+        Ok(stages) => stages,
+        Err(err) => {
+            restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+            return Err(format!("Repair task failed: {err}"));
+        }
+    };
+
+    let repaired = stages.iter().filter(|s| s.status == "repaired").count();
+    let failed = stages.iter().filter(|s| s.status == "failed").count();
+    let mut report = ComfyRepairReport {
+        comfyui_root: root.to_string_lossy().to_string(),
+        ok: failed == 0,
+        repaired,
+        failed,
+        stages,
+        report_path: None,
+    };
+    let report_path = root.join("repair-report.json");
+    if let Ok(data) = serde_json::to_vec_pretty(&report) {
+        if std::fs::write(&report_path, data).is_ok() {
+            report.report_path = Some(report_path.to_string_lossy().to_string());
+        }
+    }
+    let summary = format!("Repair finished: {repaired} stage(s) repaired, {failed} failed.");
+    emit_repair_event(
+        &app,
+        if failed == 0 {
+            "repair_finished"
+        } else {
+            "repair_failed"
+        },
+        &summary,
+    );
+
+    if report.ok {
+        restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    } else if was_running {
+        log::warn!("Not restarting ComfyUI after repair with {failed} failed stage(s).");
+    }
+    Ok(report)
+}
+
+/// Creates a fresh `.venv` and installs the ComfyUI stack and every custom node's
+/// requirements into it. Models, custom node folders and user settings live outside
+/// the venv and are left alone.
+fn rebuild_comfyui_venv(
+    app: &AppHandle,
+    root: &Path,
+    uv_bin: &str,
+    python_store: &str,
+    profile: &str,
+) -> Result<Vec<String>, String> {
+    let envs = [("UV_PYTHON_INSTALL_DIR", python_store)];
+    emit_repair_event(app, "repair_step", "Installing uv-managed Python...");
+    run_command_env(
+        uv_bin,
+        &["python", "install", UV_PYTHON_VERSION],
+        Some(root),
+        &[
+            ("UV_PYTHON_INSTALL_DIR", python_store),
+            ("UV_PYTHON_INSTALL_BIN", "false"),
+        ],
+    )?;
+    let venv_dir = root.join(".venv");
+    let venv_s = venv_dir.to_string_lossy().to_string();
+    emit_repair_event(app, "repair_step", "Creating a new .venv...");
+    run_command_env(
+        uv_bin,
+        &["venv", "--seed", "--python", UV_PYTHON_VERSION, &venv_s],
+        Some(root),
+        &envs,
+    )?;
+    let py_exe = venv_dir.join("bin").join("python");
+    let py_path = py_exe.to_string_lossy().to_string();
+    run_uv_pip_strict(
+        uv_bin,
+        &py_path,
+        &["install", "--upgrade", "pip", "setuptools", "wheel"],
+        Some(root),
+        &envs,
+    )?;
+
+    emit_repair_event(
+        app,
+        "repair_step",
+        &format!("Installing Torch profile {profile}..."),
+    );
+    enforce_torch_profile_linux(uv_bin, &py_path, root, profile, python_store)?;
+    emit_repair_event(app, "repair_step", "Installing ComfyUI requirements...");
+    reinstall_comfyui_requirements(uv_bin, root, profile, python_store)?;
+    run_uv_pip_strict(
+        uv_bin,
+        &py_path,
+        &["install", "--upgrade", "pyyaml", "nvidia-ml-py"],
+        Some(root),
+        &envs,
+    )?;
+
+    // A node whose requirements no longer resolve should not cost the user the whole
+    // rebuild; report it and move on.
+    let mut node_failures = Vec::new();
+    let mut node_dirs: Vec<(String, PathBuf)> = std::fs::read_dir(root.join("custom_nodes"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| {
+                    (
+                        entry.file_name().to_string_lossy().to_string(),
+                        entry.path(),
+                    )
+                })
+                .filter(|(name, path)| {
+                    path.is_dir()
+                        && !name.starts_with('.')
+                        && name != "__pycache__"
+                        && !name.ends_with(".disabled")
+                        && path.join("requirements.txt").is_file()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    node_dirs.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, dir) in node_dirs {
+        emit_repair_event(
+            app,
+            "repair_step",
+            &format!("Installing requirements for {name}..."),
+        );
+        if let Err(err) = install_custom_node_requirements(app, root, &dir, &py_exe) {
+            node_failures.push(format!("{name}: {err}"));
         }
-        "sage3" => {
-            run_uv_pip_strict(
-                &uv_bin,
-                &py_path,
-                &["install", "--upgrade", "--force-reinstall", triton_pkg],
-                Some(&root),
-                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
-            )?;
-            install_linux_wheel_for_profile(
-                &root,
-                &py_path,
-                &profile,
-                "sage3",
-                hopper_sm90,
-                true,
-            )?;
-            // Keep sageattention installed for ComfyUI --use-sage-attention compatibility checks.
-            install_sageattention_linux(&root, &py_path, &profile, hopper_sm90)?;
+    }
+    // Custom node requirements are free to drag torch along with them.
+    enforce_torch_profile_linux(uv_bin, &py_path, root, profile, python_store)?;
+    Ok(node_failures)
+}
+
+#[tauri::command]
+async fn repair_comfyui_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_root_path(&state.context, comfyui_root)?;
+    if !root.join("main.py").is_file() {
+        return Err("Selected folder is not a valid ComfyUI root.".to_string());
+    }
+    let settings = state.context.config.settings();
+    let profile = resolve_desired_torch_profile(&settings, &root);
+    let backends = installed_attention_backends(&root);
+    let active = active_attention_backend(&settings, &root);
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = resolve_uv_binary(&shared_runtime_root, &app)?;
+    let python_store = shared_runtime_root.join(".python");
+    std::fs::create_dir_all(&python_store).map_err(|err| err.to_string())?;
+    let python_store_s = python_store.to_string_lossy().to_string();
+
+    let _maintenance = enter_maintenance(&app, &root, "rebuilding the venv");
+    let was_running = stop_comfyui_for_mutation(&app, &state, &root)?;
+    emit_repair_event(
+        &app,
+        "repair_started",
+        &format!(
+            "Rebuilding the Python environment for {}...",
+            root.display()
+        ),
+    );
+    let app_for_task = app.clone();
+    let root_for_task = root.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<Vec<String>, String> {
+        let venv_dir = root_for_task.join(".venv");
+        let previous = root_for_task.join(format!(".venv.repair-{}", unix_now_secs()));
+        if venv_dir.exists() {
+            std::fs::rename(&venv_dir, &previous)
+                .map_err(|err| format!("Failed to move the old .venv aside: {err}"))?;
         }
-        "nunchaku" => {
-            ensure_git_available(&app)?;
-            let custom_nodes_root = root.join("custom_nodes");
-            std::fs::create_dir_all(&custom_nodes_root).map_err(|err| err.to_string())?;
-            let nunchaku_node = root.join("custom_nodes").join("ComfyUI-nunchaku");
-            clone_or_update_repo(
-                &root,
-                &nunchaku_node,
-                "https://github.com/nunchaku-ai/ComfyUI-nunchaku",
-            )?;
-            let versions_json = nunchaku_node.join("nunchaku_versions.json");
-            let _ = download_http_file(
-                "https://nunchaku.tech/cdn/nunchaku_versions.json",
-                &versions_json,
-            );
-            run_uv_pip_strict(
-                &uv_bin,
-                &py_path,
-                &["install", "--upgrade", "--force-reinstall", triton_pkg],
-                Some(&root),
-                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
-            )?;
-            install_insightface(&root, &uv_bin, &py_path, &uv_python_install_dir)?;
-            install_nunchaku_node_requirements(
-                &root,
-                &uv_bin,
-                &py_path,
-                &uv_python_install_dir,
-                &nunchaku_node,
-            )?;
-            install_linux_wheel_for_profile(
-                &root,
-                &py_path,
-                &profile,
-                "nunchaku",
-                hopper_sm90,
-                true,
-            )?;
-            if !nunchaku_backend_present(&root) {
-                return Err(
-                    "Nunchaku backend install incomplete: module or custom node not detected."
-                        .to_string(),
-                );
+        match rebuild_comfyui_venv(
+            &app_for_task,
+            &root_for_task,
+            &uv_bin,
+            &python_store_s,
+            &profile,
+        ) {
+            Ok(node_failures) => {
+                let _ = std::fs::remove_dir_all(&previous);
+                Ok(node_failures)
+            }
+            Err(err) => {
+                // Put the old environment back so a failed rebuild leaves things as they were.
+                let _ = std::fs::remove_dir_all(&venv_dir);
+                if previous.exists() {
+                    let _ = std::fs::rename(&previous, &venv_dir);
+                }
+                Err(err)
             }
         }
-        _ => return Err("Unknown attention backend target.".to_string()),
+    })
+    .await
+    .map_err(|err| format!("Venv rebuild task failed: {err}"))
+    .and_then(|rebuilt| rebuilt);
+
+    let node_failures = match result {
+        Ok(node_failures) => node_failures,
+        Err(err) => {
+            emit_repair_event(
+                &app,
+                "repair_failed",
+                &format!("Venv rebuild failed: {err}"),
+            );
+            restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+            return Err(err);
+        }
+    };
+
+    reinstall_attention_backends(&app, &state, &root, &backends, &active);
+
+    let summary = if node_failures.is_empty() {
+        "Python environment rebuilt.".to_string()
+    } else {
+        format!(
+            "Python environment rebuilt; requirements failed for {}.",
+            node_failures.join("; ")
+        )
+    };
+    emit_repair_event(&app, "repair_finished", &summary);
+    restart_comfyui_after_mutation(&app, &state, &root, was_running)?;
+    Ok(summary)
+}
+
+/// The backend launches of `root` use: the saved choice, else what detection picks.
+fn active_attention_backend(settings: &AppSettings, root: &Path) -> String {
+    install_config(settings, root)
+        .attention_backend
+        .or_else(|| detect_launch_attention_backend_for_root(root))
+        .unwrap_or_else(|| "none".to_string())
+}
+
+/// Installs `backends` into a fresh venv at `root`, the active one last so it stays
+/// selected. Failures are reported and skipped.
+fn reinstall_attention_backends(
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+    root: &Path,
+    backends: &[&'static str],
+    active: &str,
+) {
+    let mut order: Vec<&str> = backends.iter().copied().filter(|b| *b != active).collect();
+    if backends.contains(&active) {
+        order.push(active);
+    }
+    for backend in order {
+        emit_repair_event(
+            app,
+            "repair_step",
+            &format!("Installing attention backend '{backend}'..."),
+        );
+        let request = AttentionBackendChangeRequest {
+            comfyui_root: Some(root.to_string_lossy().to_string()),
+            target_backend: backend.to_string(),
+            torch_profile: None,
+            allow_incompatible: true,
+            keep_others: true,
+        };
+        if let Err(err) = apply_attention_backend_change(app.clone(), state.clone(), request) {
+            emit_repair_event(
+                app,
+                "repair_step",
+                &format!("Attention backend '{backend}' could not be installed: {err}"),
+            );
+        }
     }
+    if active == "none" && !backends.is_empty() {
+        let _ = save_attention_backend(state, root, "none", None);
+    }
+}
 
-    if target == "none" {
-        let mut lingering: Vec<&str> = Vec::new();
-        for pkg in [
-            "sageattention",
-            "sageattn3",
-            "flash-attn",
-            "flash_attn",
-            "nunchaku",
-        ] {
-            if pip_has_package(&root, pkg) {
-                lingering.push(pkg);
-            }
+#[derive(Debug, Serialize)]
+struct DuplicateInstallReport {
+    source_root: String,
+    comfyui_root: String,
+    torch_profile: String,
+    models_linked: bool,
+    /// Custom nodes whose requirements failed to install in the new venv.
+    node_failures: Vec<String>,
+}
+
+/// Copies one entry of an install with `cp --reflink=auto`, so filesystems that
+/// support it (btrfs, XFS) share the data blocks instead of duplicating them.
+fn copy_install_entry(source: &Path, dest_dir: &Path) -> Result<(), String> {
+    let source_s = source.to_string_lossy().to_string();
+    let dest_s = dest_dir.to_string_lossy().to_string();
+    run_command_capture("cp", &["-a", "--reflink=auto", &source_s, &dest_s], None)
+        .map(|_| ())
+        .map_err(|err| format!("failed to copy {}: {err}", source.display()))
+}
+
+/// Copies the ComfyUI tree (code, custom nodes, user settings and, unless linked,
+/// models) into the next free `ComfyUI-XX` folder, then builds a fresh `.venv` there
+/// with the source install's torch profile and custom node requirements. The venv
+/// itself is never copied because its scripts hard-code the old path.
+fn duplicate_install_tree(
+    app: &AppHandle,
+    source_root: &Path,
+    new_root: &Path,
+    link_models: bool,
+    uv_bin: &str,
+    python_store: &str,
+    profile: &str,
+) -> Result<Vec<String>, String> {
+    std::fs::create_dir_all(new_root)
+        .map_err(|err| format!("failed to create {}: {err}", new_root.display()))?;
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(source_root)
+        .map_err(|err| format!("failed to read {}: {err}", source_root.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            !(name == ".venv"
+                || name == "venv"
+                || name.starts_with(".venv.repair-")
+                || name == "__pycache__"
+                || (link_models && name == "models"))
+        })
+        .collect();
+    entries.sort();
+    for entry in &entries {
+        emit_repair_event(
+            app,
+            "repair_step",
+            &format!(
+                "Copying {}...",
+                entry.file_name().unwrap_or_default().to_string_lossy()
+            ),
+        );
+        copy_install_entry(entry, new_root)?;
+    }
+    if link_models {
+        let models = source_root.join("models");
+        if models.exists() {
+            let target = std::fs::canonicalize(&models).unwrap_or(models);
+            std::os::unix::fs::symlink(&target, new_root.join("models"))
+                .map_err(|err| format!("failed to link the models folder: {err}"))?;
         }
-        let mut lingering_nodes: Vec<&str> = Vec::new();
-        for node in ["ComfyUI-nunchaku", "nunchaku_nodes"] {
-            if custom_node_exists(&root, node) {
-                lingering_nodes.push(node);
-            }
+    }
+    rebuild_comfyui_venv(app, new_root, uv_bin, python_store, profile)
+}
+
+/// Clones an install into a new `ComfyUI-XX` folder next to it, e.g. to try an update
+/// without touching the working one. With `link_models` the copy symlinks the
+/// source's models folder instead of copying it.
+#[tauri::command]
+async fn duplicate_comfyui_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    comfyui_root: Option<String>,
+    link_models: Option<bool>,
+) -> Result<DuplicateInstallReport, String> {
+    let source_root = resolve_root_path(&state.context, comfyui_root)?;
+    if !source_root.join("main.py").is_file() {
+        return Err("Selected folder is not a valid ComfyUI root.".to_string());
+    }
+    let install_active = state
+        .install_cancel
+        .lock()
+        .map(|active| active.is_some())
+        .unwrap_or(true);
+    if install_active {
+        return Err("A ComfyUI install is running. Duplicate after it finishes.".to_string());
+    }
+    let link_models = link_models.unwrap_or(true);
+
+    // Managed installs are `<base>/ComfyUI-XX/ComfyUI`; hand-made ones may be flat.
+    let nested = source_root
+        .file_name()
+        .is_some_and(|name| name == "ComfyUI")
+        && source_root.parent().is_some_and(path_name_is_comfyui);
+    let install_dir = if nested {
+        source_root.parent().unwrap_or(&source_root)
+    } else {
+        &source_root
+    };
+    let base = install_dir
+        .parent()
+        .ok_or_else(|| "Selected install has no parent folder.".to_string())?;
+    let new_install_dir = choose_install_folder(base, true);
+    let new_root = if nested {
+        new_install_dir.join("ComfyUI")
+    } else {
+        new_install_dir.clone()
+    };
+
+    let settings = state.context.config.settings();
+    let profile = resolve_desired_torch_profile(&settings, &source_root);
+    let backends = installed_attention_backends(&source_root);
+    let active = active_attention_backend(&settings, &source_root);
+    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let uv_bin = resolve_uv_binary(&shared_runtime_root, &app)?;
+    let python_store = shared_runtime_root.join(".python");
+    std::fs::create_dir_all(&python_store).map_err(|err| err.to_string())?;
+    let python_store_s = python_store.to_string_lossy().to_string();
+
+    emit_repair_event(
+        &app,
+        "duplicate_started",
+        &format!(
+            "Duplicating {} into {}...",
+            source_root.display(),
+            new_root.display()
+        ),
+    );
+    let app_for_task = app.clone();
+    let source_for_task = source_root.clone();
+    let new_root_for_task = new_root.clone();
+    let profile_for_task = profile.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        duplicate_install_tree(
+            &app_for_task,
+            &source_for_task,
+            &new_root_for_task,
+            link_models,
+            &uv_bin,
+            &python_store_s,
+            &profile_for_task,
+        )
+    })
+    .await
+    .map_err(|err| format!("Duplicate task failed: {err}"))?;
+    let node_failures = match result {
+        Ok(node_failures) => node_failures,
+        Err(err) => {
+            // Nothing else points at the half-made copy yet, so drop it entirely.
+            let _ = std::fs::remove_dir_all(&new_install_dir);
+            emit_repair_event(
+                &app,
+                "duplicate_failed",
+                &format!("Duplicating the install failed: {err}"),
+            );
+            return Err(err);
         }
-        if !lingering.is_empty() || !lingering_nodes.is_empty() {
-            let mut detail = String::new();
-            if !lingering.is_empty() {
-                detail.push_str(&format!(
-                    "packages still installed: {}",
-                    lingering.join(", ")
-                ));
+    };
+
+    reinstall_attention_backends(&app, &state, &new_root, &backends, &active);
+
+    emit_repair_event(
+        &app,
+        "duplicate_finished",
+        &format!("Install duplicated to {}.", new_root.display()),
+    );
+    Ok(DuplicateInstallReport {
+        source_root: source_root.to_string_lossy().to_string(),
+        comfyui_root: new_root.to_string_lossy().to_string(),
+        torch_profile: profile,
+        models_linked: link_models,
+        node_failures,
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct RelocateInstallReport {
+    old_root: String,
+    comfyui_root: String,
+    /// `false` when the destination is on another filesystem and the tree was copied.
+    renamed: bool,
+    rewritten_files: usize,
+    venv_rebuilt: bool,
+    relinked: Vec<String>,
+    settings_updated: Vec<String>,
+}
+
+/// Replaces `old` with `new` in a small text file. Binaries and non-UTF-8 files are
+/// skipped. Returns whether the file changed.
+fn rewrite_path_references(path: &Path, old: &str, new: &str) -> bool {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return false;
+    };
+    if !meta.is_file() || meta.len() > 1024 * 1024 {
+        return false;
+    }
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    text.contains(old) && std::fs::write(path, text.replace(old, new)).is_ok()
+}
+
+/// A venv embeds its absolute path in the activate scripts, the shebangs of every
+/// console script in `bin/` and any `.pth` file; rewrite them for the new location.
+fn fix_relocated_venv(venv: &Path, old_venv: &Path) -> usize {
+    let old = old_venv.to_string_lossy().to_string();
+    let new = venv.to_string_lossy().to_string();
+    let mut files: Vec<PathBuf> = std::fs::read_dir(venv.join("bin"))
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    if let Ok(lib) = std::fs::read_dir(venv.join("lib")) {
+        for python_dir in lib.flatten() {
+            if let Ok(site) = std::fs::read_dir(python_dir.path().join("site-packages")) {
+                files.extend(
+                    site.flatten()
+                        .map(|entry| entry.path())
+                        .filter(|path| path.extension().is_some_and(|ext| ext == "pth")),
+                );
             }
-            if !lingering_nodes.is_empty() {
-                if !detail.is_empty() {
-                    detail.push_str("; ");
-                }
-                detail.push_str(&format!(
-                    "nodes still present: {}",
-                    lingering_nodes.join(", ")
-                ));
+        }
+    }
+    files
+        .iter()
+        .filter(|path| rewrite_path_references(path, &old, &new))
+        .count()
+}
+
+/// Renames `source` to `dest`, or copies it entry by entry and then removes the
+/// source when the two are on different filesystems. Returns whether the rename worked.
+fn move_install_tree(app: &AppHandle, source: &Path, dest: &Path) -> Result<bool, String> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+    }
+    if std::fs::rename(source, dest).is_ok() {
+        return Ok(true);
+    }
+    std::fs::create_dir_all(dest)
+        .map_err(|err| format!("failed to create {}: {err}", dest.display()))?;
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(source)
+        .map_err(|err| format!("failed to read {}: {err}", source.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+    let total = entries.len();
+    for (index, entry) in entries.iter().enumerate() {
+        emit_repair_event(
+            app,
+            "repair_step",
+            &format!(
+                "Copying {} ({}/{total})...",
+                entry.file_name().unwrap_or_default().to_string_lossy(),
+                index + 1
+            ),
+        );
+        if let Err(err) = copy_install_entry(entry, dest) {
+            // The source is untouched until every entry copied, so the copy can go.
+            let _ = std::fs::remove_dir_all(dest);
+            return Err(err);
+        }
+    }
+    std::fs::remove_dir_all(source).map_err(|err| {
+        format!(
+            "copied to {}, but failed to remove {}: {err}",
+            dest.display(),
+            source.display()
+        )
+    })?;
+    Ok(false)
+}
+
+fn rebase_path(path: &Path, old_dirs: &[&Path], new_dir: &Path) -> Option<PathBuf> {
+    let rest = old_dirs
+        .iter()
+        .find_map(|old| path.strip_prefix(old).ok())?;
+    Some(if rest.as_os_str().is_empty() {
+        new_dir.to_path_buf()
+    } else {
+        new_dir.join(rest)
+    })
+}
+
+/// Re-points `models` symlinks of sibling installs, as made by
+/// `duplicate_comfyui_install`, that led into the moved tree.
+fn relink_sibling_models(base: &Path, old_dir: &Path, new_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return Vec::new();
+    };
+    let mut relinked = Vec::new();
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        for link in [dir.join("models"), dir.join("ComfyUI").join("models")] {
+            let Ok(target) = std::fs::read_link(&link) else {
+                continue;
+            };
+            let Some(new_target) = rebase_path(&target, &[old_dir], new_dir) else {
+                continue;
+            };
+            if std::fs::remove_file(&link).is_ok()
+                && std::os::unix::fs::symlink(&new_target, &link).is_ok()
+            {
+                relinked.push(link.to_string_lossy().to_string());
             }
-            return Err(format!(
-                "Attention backend removal incomplete ({detail}). Stop ComfyUI and retry."
-            ));
         }
     }
-    let target_setting = match target.as_str() {
-        "sage" => Some("sage".to_string()),
-        "sage3" => Some("sage3".to_string()),
-        "flash" => Some("flash".to_string()),
-        "nunchaku" => Some("nunchaku".to_string()),
-        _ => Some("none".to_string()),
+    relinked
+}
+
+/// Moves an install to a new path, `mv`-style: when `new_path` is an existing folder
+/// the install keeps its name inside it. The venv is patched in place and only rebuilt
+/// if its scripts still do not run; settings pointing into the old tree follow it.
+#[tauri::command]
+async fn relocate_installation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    old_root: String,
+    new_path: String,
+) -> Result<RelocateInstallReport, String> {
+    let source_root = normalize_optional_path(Some(&old_root))?
+        .ok_or_else(|| "Choose the install to move.".to_string())?;
+    if !source_root.join("main.py").is_file() {
+        return Err("Selected folder is not a valid ComfyUI root.".to_string());
+    }
+    ensure_no_active_download(&state)?;
+    if state
+        .install_cancel
+        .lock()
+        .map(|active| active.is_some())
+        .unwrap_or(true)
+    {
+        return Err("A ComfyUI install is running. Move installs after it finishes.".to_string());
+    }
+    if comfyui_runtime_running(&state) {
+        return Err("Stop ComfyUI before moving an install.".to_string());
+    }
+
+    let nested = source_root
+        .file_name()
+        .is_some_and(|name| name == "ComfyUI")
+        && source_root.parent().is_some_and(path_name_is_comfyui);
+    let install_dir = if nested {
+        source_root.parent().unwrap_or(&source_root).to_path_buf()
+    } else {
+        source_root.clone()
+    };
+    let old_dir = std::fs::canonicalize(&install_dir).map_err(|err| err.to_string())?;
+    let requested = normalize_optional_path(Some(&new_path))?
+        .ok_or_else(|| "Choose where to move the install.".to_string())?;
+    let dest_dir = if requested.is_dir() {
+        requested.join(old_dir.file_name().unwrap_or_default())
+    } else {
+        requested
+    };
+    if dest_dir.exists() {
+        return Err(format!("{} already exists.", dest_dir.display()));
+    }
+    let dest_parent = dest_dir
+        .parent()
+        .and_then(|parent| std::fs::canonicalize(parent).ok());
+    if dest_parent.is_some_and(|parent| parent.starts_with(&old_dir)) {
+        return Err("An install cannot be moved into itself.".to_string());
+    }
+    let new_root = if nested {
+        dest_dir.join("ComfyUI")
+    } else {
+        dest_dir.clone()
+    };
+    let old_comfy_root = if nested {
+        old_dir.join("ComfyUI")
+    } else {
+        old_dir.clone()
+    };
+
+    let maintenance = enter_maintenance(&app, &source_root, "moving the install");
+    emit_repair_event(
+        &app,
+        "relocate_started",
+        &format!("Moving {} to {}...", old_dir.display(), dest_dir.display()),
+    );
+    let app_for_task = app.clone();
+    let old_for_task = old_dir.clone();
+    let dest_for_task = dest_dir.clone();
+    let moved = tauri::async_runtime::spawn_blocking(move || {
+        move_install_tree(&app_for_task, &old_for_task, &dest_for_task)
+    })
+    .await
+    .map_err(|err| format!("Move task failed: {err}"))?;
+    let renamed = match moved {
+        Ok(renamed) => renamed,
+        Err(err) => {
+            emit_repair_event(
+                &app,
+                "relocate_failed",
+                &format!("Moving the install failed: {err}"),
+            );
+            return Err(err);
+        }
     };
-    let _ = state
+
+    // The tree has moved; point settings at it before anything else can fail.
+    let old_dirs = [old_dir.as_path(), install_dir.as_path()];
+    let mut settings_updated = Vec::new();
+    state
         .context
         .config
         .update_settings(|settings| {
-            settings.comfyui_attention_backend = target_setting;
-            settings.comfyui_torch_profile = Some(profile.clone());
-        });
+            let fields = [
+                ("comfyui_root", &mut settings.comfyui_root),
+                ("comfyui_install_base", &mut settings.comfyui_install_base),
+                (
+                    "comfyui_last_install_dir",
+                    &mut settings.comfyui_last_install_dir,
+                ),
+                ("shared_models_root", &mut settings.shared_models_root),
+            ];
+            for (name, field) in fields {
+                if let Some(rebased) = field
+                    .as_deref()
+                    .and_then(|path| rebase_path(path, &old_dirs, &dest_dir))
+                {
+                    *field = Some(rebased);
+                    settings_updated.push(name.to_string());
+                }
+            }
+        })
+        .map_err(|err| err.to_string())?;
+    let relinked = old_dir
+        .parent()
+        .map(|base| relink_sibling_models(base, &old_dir, &dest_dir))
+        .unwrap_or_default();
 
-    restart_comfyui_after_mutation(&app, &state, was_running)?;
-    Ok(format!("Applied attention backend: {target}"))
+    maintenance.step("fixing venv paths");
+    let old_dir_s = old_dir.to_string_lossy().to_string();
+    let dest_dir_s = dest_dir.to_string_lossy().to_string();
+    let mut rewritten_files =
+        fix_relocated_venv(&new_root.join(".venv"), &old_comfy_root.join(".venv"));
+    if rewrite_path_references(
+        &new_root.join("extra_model_paths.yaml"),
+        &old_dir_s,
+        &dest_dir_s,
+    ) {
+        rewritten_files += 1;
+    }
+    // Console scripts are what break after a move; pip is one of them.
+    let pip = new_root.join(".venv").join("bin").join("pip");
+    let venv_ok =
+        run_command_capture(&pip.to_string_lossy(), &["--version"], Some(&new_root)).is_ok();
+    let mut venv_rebuilt = false;
+    if !venv_ok {
+        maintenance.step("rebuilding the venv");
+        emit_repair_event(
+            &app,
+            "repair_step",
+            "The moved venv does not run; rebuilding it...",
+        );
+        let settings = state.context.config.settings();
+        let profile = resolve_desired_torch_profile(&settings, &new_root);
+        let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+        let uv_bin = resolve_uv_binary(&shared_runtime_root, &app)?;
+        let python_store = shared_runtime_root
+            .join(".python")
+            .to_string_lossy()
+            .to_string();
+        let stale = new_root.join(format!(".venv.repair-{}", unix_now_secs()));
+        let _ = std::fs::rename(new_root.join(".venv"), &stale);
+        let app_for_task = app.clone();
+        let root_for_task = new_root.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            rebuild_comfyui_venv(
+                &app_for_task,
+                &root_for_task,
+                &uv_bin,
+                &python_store,
+                &profile,
+            )
+        })
+        .await
+        .map_err(|err| format!("Venv rebuild task failed: {err}"))??;
+        let _ = std::fs::remove_dir_all(&stale);
+        venv_rebuilt = true;
+    }
+    drop(maintenance);
+
+    emit_repair_event(
+        &app,
+        "relocate_finished",
+        &format!("Install moved to {}.", new_root.display()),
+    );
+    Ok(RelocateInstallReport {
+        old_root: source_root.to_string_lossy().to_string(),
+        comfyui_root: new_root.to_string_lossy().to_string(),
+        renamed,
+        rewritten_files,
+        venv_rebuilt,
+        relinked,
+        settings_updated,
+    })
 }
 
-fn remove_custom_node_dirs(root: &Path, names: &[&str]) {
-    let custom_nodes = root.join("custom_nodes");
-    for name in names {
-        let path = custom_nodes.join(name);
-        if path.exists() {
-            let _ = std::fs::remove_dir_all(path);
-        }
-    }
+/// Packages most likely to explain "works here, not there" differences.
+const DIFF_KEY_PACKAGES: &[&str] = &[
+    "torch",
+    "torchvision",
+    "torchaudio",
+    "triton",
+    "xformers",
+    "sageattention",
+    "sageattn3",
+    "flash-attn",
+    "nunchaku",
+    "insightface",
+    "onnxruntime",
+    "onnxruntime-gpu",
+    "numpy",
+    "transformers",
+    "diffusers",
+    "accelerate",
+    "safetensors",
+    "spandrel",
+    "kornia",
+    "av",
+    "opencv-python",
+    "pillow",
+    "comfyui-frontend-package",
+    "comfyui-workflow-templates",
+    "comfyui-embedded-docs",
+];
+
+#[derive(Debug, Default, Serialize)]
+struct InstallFingerprint {
+    root: String,
+    comfyui_version: Option<String>,
+    comfyui_commit: Option<String>,
+    python_version: Option<String>,
+    torch_version: Option<String>,
+    cuda_version: Option<String>,
+    torch_profile: Option<String>,
+    attention_backend: String,
+    custom_nodes: BTreeMap<String, String>,
+    packages: BTreeMap<String, String>,
 }
 
-fn install_insightface(
-    root: &Path,
-    uv_bin: &str,
-    py_path: &str,
-    uv_python_install_dir: &str,
-) -> Result<(), String> {
-    let profile = profile_from_torch_env(root)?;
-    install_linux_wheel_for_profile(
-        root,
-        py_path,
-        &profile,
-        "insightface",
-        is_nvidia_hopper_sm90(),
-        true,
-    )?;
-    run_uv_pip_strict(
-        uv_bin,
-        py_path,
-        &["install", "--upgrade", "onnx", "onnxruntime"],
-        Some(root),
-        &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
-    )?;
-    if !python_module_importable(root, "onnx") {
-        return Err("InsightFace install incomplete: missing 'onnx' module.".to_string());
+#[derive(Debug, Serialize)]
+struct InstallDiffEntry {
+    category: &'static str,
+    key: String,
+    a: Option<String>,
+    b: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct InstallDiff {
+    a: InstallFingerprint,
+    b: InstallFingerprint,
+    identical: bool,
+    differences: Vec<InstallDiffEntry>,
+}
+
+/// `version` from a custom node's pyproject.toml, if it declares one.
+fn custom_node_pyproject_version(node_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(node_dir.join("pyproject.toml")).ok()?;
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "version")
+            .then(|| {
+                value
+                    .trim()
+                    .trim_matches('"')
+                    .trim_matches('\'')
+                    .to_string()
+            })
+            .filter(|value| !value.is_empty())
+    })
+}
+
+fn install_fingerprint(root: &Path) -> InstallFingerprint {
+    let mut fingerprint = InstallFingerprint {
+        root: root.to_string_lossy().to_string(),
+        comfyui_version: read_comfyui_installed_version(root),
+        comfyui_commit: git_commit_for_ref(root, "HEAD"),
+        torch_profile: detect_torch_profile_for_root(root),
+        attention_backend: detect_launch_attention_backend_for_root(root)
+            .unwrap_or_else(|| "none".to_string()),
+        ..InstallFingerprint::default()
+    };
+
+    let mut cmd = python_for_root(root);
+    cmd.arg("-c").arg(format!(
+        "import json, platform, importlib.metadata as m\n\
+         out = {{'python': platform.python_version(), 'packages': {{}}}}\n\
+         for name in {packages:?}:\n\
+         \x20   try: out['packages'][name] = m.version(name)\n\
+         \x20   except Exception: pass\n\
+         try:\n\
+         \x20   import torch\n\
+         \x20   out['torch'] = torch.__version__\n\
+         \x20   out['cuda'] = torch.version.cuda or (('rocm' + torch.version.hip) if getattr(torch.version, 'hip', None) else None)\n\
+         except Exception: pass\n\
+         print(json.dumps(out))",
+        packages = DIFF_KEY_PACKAGES
+    ));
+    cmd.current_dir(root);
+    if let Ok(out) = cmd.output() {
+        let parsed = String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .rev()
+            .find_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok());
+        if let Some(value) = parsed {
+            let text = |key: &str| value.get(key).and_then(|v| v.as_str()).map(str::to_string);
+            fingerprint.python_version = text("python");
+            fingerprint.torch_version = text("torch");
+            fingerprint.cuda_version = text("cuda");
+            if let Some(packages) = value.get("packages").and_then(|v| v.as_object()) {
+                fingerprint.packages = packages
+                    .iter()
+                    .filter_map(|(name, version)| {
+                        version.as_str().map(|v| (name.clone(), v.to_string()))
+                    })
+                    .collect();
+            }
+        }
     }
-    if !insightface_present(root) {
-        return Err("InsightFace install incomplete: package/module not detected.".to_string());
+
+    if let Ok(entries) = std::fs::read_dir(root.join("custom_nodes")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !path.is_dir() || name.starts_with('.') || name == "__pycache__" {
+                continue;
+            }
+            let commit =
+                git_commit_for_ref(&path, "HEAD").map(|c| c.chars().take(10).collect::<String>());
+            let version = match (custom_node_pyproject_version(&path), commit) {
+                (Some(version), Some(commit)) => format!("{version} ({commit})"),
+                (Some(version), None) => version,
+                (None, Some(commit)) => commit,
+                (None, None) => "present".to_string(),
+            };
+            fingerprint.custom_nodes.insert(name, version);
+        }
     }
-    Ok(())
+    fingerprint
 }
 
-fn uninstall_insightface(
-    root: &Path,
-    _uv_bin: &str,
-    py_path: &str,
-    _uv_python_install_dir: &str,
-) -> Result<(), String> {
-    pip_uninstall_best_effort(root, py_path, &["insightface", "filterpywhl", "facexlib"]);
-    remove_insightface_site_packages_artifacts(root)?;
-    if insightface_present(root)
-        || pip_has_package(root, "facexlib")
-        || pip_has_package(root, "filterpywhl")
-    {
-        return Err(
-            "Failed to fully remove InsightFace dependencies. Stop ComfyUI and retry."
-                .to_string(),
-        );
+fn diff_maps(
+    category: &'static str,
+    a: &BTreeMap<String, String>,
+    b: &BTreeMap<String, String>,
+    out: &mut Vec<InstallDiffEntry>,
+) {
+    let keys: std::collections::BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    for key in keys {
+        let (left, right) = (a.get(key), b.get(key));
+        if left != right {
+            out.push(InstallDiffEntry {
+                category,
+                key: key.clone(),
+                a: left.cloned(),
+                b: right.cloned(),
+            });
+        }
     }
-    Ok(())
 }
 
-fn install_trellis2(
-    root: &Path,
-    uv_bin: &str,
-    py_path: &str,
-    uv_python_install_dir: &str,
-) -> Result<(), String> {
-    // Trellis2 stack is pinned to torch280_cu128 in this app.
-    enforce_torch_profile_linux(
-        uv_bin,
-        py_path,
-        root,
-        "torch280_cu128",
-        uv_python_install_dir,
-    )?;
+#[tauri::command]
+async fn diff_installs(
+    state: State<'_, AppState>,
+    root_a: String,
+    root_b: String,
+) -> Result<InstallDiff, String> {
+    let root_a = resolve_root_path(&state.context, Some(root_a))?;
+    let root_b = resolve_root_path(&state.context, Some(root_b))?;
+    for root in [&root_a, &root_b] {
+        if !root.join("main.py").is_file() {
+            return Err(format!("{} is not a valid ComfyUI root.", root.display()));
+        }
+    }
+    let (a, b) = tauri::async_runtime::spawn_blocking(move || {
+        (install_fingerprint(&root_a), install_fingerprint(&root_b))
+    })
+    .await
+    .map_err(|err| format!("Install comparison failed: {err}"))?;
+
+    let mut differences = Vec::new();
+    for (key, left, right) in [
+        ("comfyui_version", &a.comfyui_version, &b.comfyui_version),
+        ("comfyui_commit", &a.comfyui_commit, &b.comfyui_commit),
+        ("python", &a.python_version, &b.python_version),
+        ("torch", &a.torch_version, &b.torch_version),
+        ("cuda", &a.cuda_version, &b.cuda_version),
+        ("torch_profile", &a.torch_profile, &b.torch_profile),
+    ] {
+        if left != right {
+            differences.push(InstallDiffEntry {
+                category: "runtime",
+                key: key.to_string(),
+                a: left.clone(),
+                b: right.clone(),
+            });
+        }
+    }
+    if a.attention_backend != b.attention_backend {
+        differences.push(InstallDiffEntry {
+            category: "runtime",
+            key: "attention_backend".to_string(),
+            a: Some(a.attention_backend.clone()),
+            b: Some(b.attention_backend.clone()),
+        });
+    }
+    diff_maps(
+        "custom_node",
+        &a.custom_nodes,
+        &b.custom_nodes,
+        &mut differences,
+    );
+    diff_maps("package", &a.packages, &b.packages, &mut differences);
 
-    let custom_nodes_dir = root.join("custom_nodes");
-    std::fs::create_dir_all(&custom_nodes_dir).map_err(|err| err.to_string())?;
+    Ok(InstallDiff {
+        identical: differences.is_empty(),
+        a,
+        b,
+        differences,
+    })
+}
 
-    let trellis_dir = custom_nodes_dir.join("ComfyUI-TRELLIS2");
-    clone_or_update_repo(
-        root,
-        &trellis_dir,
-        "https://github.com/ArcticLatent/ComfyUI-TRELLIS2",
-    )?;
-    let trellis_req = trellis_dir.join("requirements.txt");
-    if trellis_req.exists() {
-        run_uv_pip_strict(
-            uv_bin,
-            py_path,
-            &["install", "-r", &trellis_req.to_string_lossy(), "--no-deps"],
-            Some(root),
-            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
-        )?;
-        run_uv_pip_strict(
-            uv_bin,
-            py_path,
-            &["install", "--upgrade", "open3d"],
-            Some(root),
-            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
-        )?;
-    }
+struct ReportSection {
+    title: &'static str,
+    rows: Vec<(String, String)>,
+}
 
-    let geometry_dir = custom_nodes_dir.join("ComfyUI-GeometryPack");
-    clone_or_update_repo(
-        root,
-        &geometry_dir,
-        "https://github.com/PozzettiAndrea/ComfyUI-GeometryPack",
-    )?;
-    let geometry_req = geometry_dir.join("requirements.txt");
-    if geometry_req.exists() {
-        run_uv_pip_strict(
-            uv_bin,
-            py_path,
-            &["install", "-r", &geometry_req.to_string_lossy(), "--no-deps"],
-            Some(root),
-            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
-        )?;
+fn report_bytes(bytes: u64) -> String {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    if bytes as f64 >= GIB {
+        format!("{:.2} GiB", bytes as f64 / GIB)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
     }
-    run_uv_pip_strict(
-        uv_bin,
-        py_path,
-        &["install", "--upgrade", "tomli"],
-        Some(root),
-        &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
-    )?;
+}
 
-    let ultrashape_dir = custom_nodes_dir.join("ComfyUI-UltraShape1");
-    clone_or_update_repo(
-        root,
-        &ultrashape_dir,
-        "https://github.com/jtydhr88/ComfyUI-UltraShape1",
-    )?;
-    let ultrashape_req = ultrashape_dir.join("requirements.txt");
-    if ultrashape_req.exists() {
-        run_uv_pip_strict(
-            uv_bin,
-            py_path,
-            &["install", "-r", &ultrashape_req.to_string_lossy(), "--no-deps"],
-            Some(&ultrashape_dir),
-            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
-        )?;
-        run_uv_pip_strict(
-            uv_bin,
-            py_path,
-            &["install", "-U", "accelerate"],
-            Some(&ultrashape_dir),
-            &[("UV_PYTHON_INSTALL_DIR", uv_python_install_dir)],
-        )?;
+/// Apparent size of everything under `path`; symlinks are not followed so shared
+/// model folders linked into the install are not counted twice.
+fn dir_size_bytes(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
     }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| dir_size_bytes(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
 
-    let ultrashape_models_dir = root.join("models").join("UltraShape");
-    std::fs::create_dir_all(&ultrashape_models_dir).map_err(|err| err.to_string())?;
-    let ultrashape_model_file = ultrashape_models_dir.join("ultrashape_v1.pt");
-    if !ultrashape_model_file.exists() {
-        download_http_file(
-            "https://huggingface.co/infinith/UltraShape/resolve/main/ultrashape_v1.pt",
-            &ultrashape_model_file,
-        )?;
+/// Toolchain folders uv keeps next to the ones it installs; never reported or removed.
+const UV_PYTHON_STORE_INTERNAL: &[&str] = &[".lock", ".temp", ".cache", ".gitignore"];
+
+#[derive(Debug, Serialize)]
+struct PythonToolchainEntry {
+    name: String,
+    path: String,
+    bytes: u64,
+    /// Referenced by a known install's venv, or the version new installs use.
+    in_use: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct RuntimeCacheInfo {
+    runtime_root: String,
+    runtime_bytes: u64,
+    uv_cache_dir: Option<String>,
+    uv_cache_bytes: u64,
+    toolchains: Vec<PythonToolchainEntry>,
+    /// Unused toolchains only; what `uv cache prune` frees is not known up front.
+    reclaimable_bytes: u64,
+    total_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct RuntimeCacheCleanReport {
+    freed_bytes: u64,
+    removed_toolchains: Vec<String>,
+    uv_cache_pruned: bool,
+    messages: Vec<String>,
+}
+
+/// uv's wheel/build cache: `uv cache dir` when uv is around, else its documented default.
+fn uv_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("UV_CACHE_DIR").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(uv) = discover_uv_binary() {
+        if let Ok((output, _)) = run_command_capture(&uv, &["cache", "dir"], None) {
+            let dir = output.trim();
+            if !dir.is_empty() {
+                return Some(PathBuf::from(dir));
+            }
+        }
     }
+    let cache_home = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache_home.join("uv"))
+}
 
-    // Re-assert stack after Trellis requirements/custom nodes.
-    enforce_torch_profile_linux(
-        uv_bin,
-        py_path,
-        root,
-        "torch280_cu128",
-        uv_python_install_dir,
-    )?;
+/// `home` lines from the `pyvenv.cfg` of every install the app knows about.
+fn known_venv_python_homes(state: &AppState) -> Vec<PathBuf> {
+    known_comfyui_roots(state)
+        .iter()
+        .filter_map(|root| std::fs::read_to_string(root.join(".venv").join("pyvenv.cfg")).ok())
+        .filter_map(|cfg| {
+            cfg.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                (key.trim() == "home").then(|| PathBuf::from(value.trim()))
+            })
+        })
+        .collect()
+}
 
-    Ok(())
+fn python_toolchains(state: &AppState, python_store: &Path) -> Vec<PythonToolchainEntry> {
+    let homes = known_venv_python_homes(state);
+    let current = format!("cpython-{UV_PYTHON_VERSION}-");
+    let Ok(entries) = std::fs::read_dir(python_store) else {
+        return Vec::new();
+    };
+    let mut toolchains: Vec<PythonToolchainEntry> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if UV_PYTHON_STORE_INTERNAL.contains(&name.as_str()) {
+                return None;
+            }
+            let path = entry.path();
+            let in_use =
+                name.starts_with(&current) || homes.iter().any(|home| home.starts_with(&path));
+            Some(PythonToolchainEntry {
+                name,
+                bytes: dir_size_bytes(&path),
+                path: path.to_string_lossy().to_string(),
+                in_use,
+            })
+        })
+        .collect();
+    toolchains.sort_by(|a, b| a.name.cmp(&b.name));
+    toolchains
 }
 
-fn uninstall_trellis2(
-    root: &Path,
-    _uv_bin: &str,
-    py_path: &str,
-    _uv_python_install_dir: &str,
-) -> Result<(), String> {
-    remove_custom_node_dirs(
-        root,
-        &[
-            "ComfyUI-TRELLIS2",
-            "ComfyUI-GeometryPack",
-            "ComfyUI-UltraShape1",
-        ],
-    );
-    pip_uninstall_best_effort(root, py_path, &["accelerate", "open3d"]);
-    Ok(())
+fn runtime_cache_info(state: &AppState) -> RuntimeCacheInfo {
+    let runtime_root = state.context.config.cache_path().join("comfyui-runtime");
+    let runtime_bytes = dir_size_bytes(&runtime_root);
+    let uv_cache_dir = uv_cache_dir();
+    let uv_cache_bytes = uv_cache_dir.as_deref().map(dir_size_bytes).unwrap_or(0);
+    let toolchains = python_toolchains(state, &runtime_root.join(".python"));
+    let reclaimable_bytes = toolchains
+        .iter()
+        .filter(|toolchain| !toolchain.in_use)
+        .map(|toolchain| toolchain.bytes)
+        .sum();
+    RuntimeCacheInfo {
+        runtime_root: runtime_root.to_string_lossy().to_string(),
+        runtime_bytes,
+        uv_cache_dir: uv_cache_dir.map(|dir| dir.to_string_lossy().to_string()),
+        uv_cache_bytes,
+        toolchains,
+        reclaimable_bytes,
+        total_bytes: runtime_bytes + uv_cache_bytes,
+    }
 }
 
-fn install_named_custom_node(
-    app: &AppHandle,
-    root: &Path,
-    py_exe: &Path,
-    repo_url: &str,
-    folder_name: &str,
-) -> Result<(), String> {
-    let custom_nodes = root.join("custom_nodes");
-    std::fs::create_dir_all(&custom_nodes).map_err(|err| err.to_string())?;
-    install_custom_node(app, root, &custom_nodes, py_exe, repo_url, folder_name)
+#[tauri::command]
+async fn get_runtime_cache_info(app: AppHandle) -> Result<RuntimeCacheInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || runtime_cache_info(&app.state::<AppState>()))
+        .await
+        .map_err(|err| err.to_string())
 }
 
+/// Prunes uv's cache (`uv cache prune` keeps whatever is still referenced) and removes
+/// Python toolchains no known install uses. Refused while an install is running, since
+/// both are in use then.
 #[tauri::command]
-async fn apply_comfyui_component_toggle(
+async fn clean_runtime_cache(
     app: AppHandle,
     state: State<'_, AppState>,
-    request: ComfyComponentToggleRequest,
-) -> Result<String, String> {
-    let was_running = stop_comfyui_for_mutation(&app, &state)?;
-    let root = resolve_root_path(&state.context, request.comfyui_root)?;
-    let py_path = {
-        let probe = python_for_root(&root);
-        probe.get_program().to_string_lossy().to_string()
-    };
-    let py_exe = PathBuf::from(&py_path);
-    let _ = kill_python_processes_for_root(&root, &py_exe);
-    let component = request.component.trim().to_ascii_lowercase();
-
-    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
-    let uv_bin = resolve_uv_binary(&shared_runtime_root, &app)?;
-    let uv_python_install_dir = shared_runtime_root
-        .join(".python")
-        .to_string_lossy()
-        .to_string();
+    prune_uv_cache: Option<bool>,
+    remove_unused_toolchains: Option<bool>,
+) -> Result<RuntimeCacheCleanReport, String> {
+    let install_active = state
+        .install_cancel
+        .lock()
+        .map(|active| active.is_some())
+        .unwrap_or(true);
+    if install_active {
+        return Err("A ComfyUI install is running. Clean the cache after it finishes.".to_string());
+    }
+    let prune_uv_cache = prune_uv_cache.unwrap_or(true);
+    let remove_unused_toolchains = remove_unused_toolchains.unwrap_or(true);
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let before = runtime_cache_info(&state);
+        let mut report = RuntimeCacheCleanReport {
+            freed_bytes: 0,
+            removed_toolchains: Vec::new(),
+            uv_cache_pruned: false,
+            messages: Vec::new(),
+        };
 
-    let result = if matches!(component.as_str(), "addon_pinned_memory" | "pinned_memory") {
-        match component.as_str() {
-            "addon_pinned_memory" | "pinned_memory" => {
-                let enabled = request.enabled;
-                state
-                    .context
-                    .config
-                    .update_settings(|settings| settings.comfyui_pinned_memory_enabled = enabled)
-                    .map_err(|err| err.to_string())?;
-                if enabled {
-                    Ok("Pinned memory enabled.".to_string())
-                } else {
-                    Ok("Pinned memory disabled.".to_string())
-                }
+        if prune_uv_cache {
+            match discover_uv_binary() {
+                Some(uv) => match run_command_capture(&uv, &["cache", "prune"], None) {
+                    Ok(_) => report.uv_cache_pruned = true,
+                    Err(err) => report
+                        .messages
+                        .push(format!("uv cache prune failed: {err}")),
+                },
+                None => report
+                    .messages
+                    .push("uv is not installed; skipped pruning its cache.".to_string()),
             }
-            _ => Err("Unknown component toggle target.".to_string()),
         }
-    } else {
-        let app_clone = app.clone();
-        let root_clone = root.clone();
-        let py_path_clone = py_path.clone();
-        let py_exe_clone = py_exe.clone();
-        let component_clone = component.clone();
-        let uv_bin_clone = uv_bin.clone();
-        let uv_python_install_dir_clone = uv_python_install_dir.clone();
-        let enabled = request.enabled;
-        tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
-            match component_clone.as_str() {
-                "addon_insightface" | "insightface" => {
-                    if enabled {
-                        install_insightface(
-                            &root_clone,
-                            &uv_bin_clone,
-                            &py_path_clone,
-                            &uv_python_install_dir_clone,
-                        )?;
-                        Ok("Installed InsightFace.".to_string())
-                    } else {
-                        if detect_launch_attention_backend_for_root(&root_clone).as_deref()
-                            == Some("nunchaku")
-                        {
-                            return Err(
-                                "Cannot remove InsightFace while Nunchaku is selected. Switch attention backend first."
-                                    .to_string(),
-                            );
-                        }
-                        uninstall_insightface(
-                            &root_clone,
-                            &uv_bin_clone,
-                            &py_path_clone,
-                            &uv_python_install_dir_clone,
-                        )?;
-                        Ok("Removed InsightFace.".to_string())
-                    }
-                }
-                "addon_trellis2" | "trellis2" => {
-                    if enabled {
-                        ensure_git_available(&app_clone)?;
-                        install_trellis2(
-                            &root_clone,
-                            &uv_bin_clone,
-                            &py_path_clone,
-                            &uv_python_install_dir_clone,
-                        )?;
-                        Ok("Installed Trellis2.".to_string())
-                    } else {
-                        uninstall_trellis2(
-                            &root_clone,
-                            &uv_bin_clone,
-                            &py_path_clone,
-                            &uv_python_install_dir_clone,
-                        )?;
-                        Ok("Removed Trellis2.".to_string())
-                    }
-                }
-                "node_comfyui_manager" => {
-                    if enabled {
-                        ensure_git_available(&app_clone)?;
-                        install_named_custom_node(
-                            &app_clone,
-                            &root_clone,
-                            &py_exe_clone,
-                            "https://github.com/Comfy-Org/ComfyUI-Manager",
-                            "ComfyUI-Manager",
-                        )?;
-                        Ok("Installed ComfyUI-Manager.".to_string())
-                    } else {
-                        remove_custom_node_dirs(&root_clone, &["ComfyUI-Manager", "comfyui-manager"]);
-                        Ok("Removed ComfyUI-Manager.".to_string())
-                    }
-                }
-                "node_comfyui_easy_use" => {
-                    if enabled {
-                        ensure_git_available(&app_clone)?;
-                        install_named_custom_node(
-                            &app_clone,
-                            &root_clone,
-                            &py_exe_clone,
-                            "https://github.com/yolain/ComfyUI-Easy-Use",
-                            "ComfyUI-Easy-Use",
-                        )?;
-                        Ok("Installed ComfyUI-Easy-Use.".to_string())
-                    } else {
-                        remove_custom_node_dirs(&root_clone, &["ComfyUI-Easy-Use"]);
-                        Ok("Removed ComfyUI-Easy-Use.".to_string())
-                    }
-                }
-                "node_rgthree_comfy" => {
-                    if enabled {
-                        ensure_git_available(&app_clone)?;
-                        install_named_custom_node(
-                            &app_clone,
-                            &root_clone,
-                            &py_exe_clone,
-                            "https://github.com/rgthree/rgthree-comfy",
-                            "rgthree-comfy",
-                        )?;
-                        Ok("Installed rgthree-comfy.".to_string())
-                    } else {
-                        remove_custom_node_dirs(&root_clone, &["rgthree-comfy"]);
-                        Ok("Removed rgthree-comfy.".to_string())
-                    }
-                }
-                "node_comfyui_gguf" => {
-                    if enabled {
-                        ensure_git_available(&app_clone)?;
-                        install_named_custom_node(
-                            &app_clone,
-                            &root_clone,
-                            &py_exe_clone,
-                            "https://github.com/city96/ComfyUI-GGUF",
-                            "ComfyUI-GGUF",
-                        )?;
-                        Ok("Installed ComfyUI-GGUF.".to_string())
-                    } else {
-                        remove_custom_node_dirs(&root_clone, &["ComfyUI-GGUF"]);
-                        Ok("Removed ComfyUI-GGUF.".to_string())
-                    }
-                }
-                "node_comfyui_kjnodes" => {
-                    if enabled {
-                        ensure_git_available(&app_clone)?;
-                        install_named_custom_node(
-                            &app_clone,
-                            &root_clone,
-                            &py_exe_clone,
-                            "https://github.com/kijai/ComfyUI-KJNodes",
-                            "comfyui-kjnodes",
-                        )?;
-                        Ok("Installed comfyui-kjnodes.".to_string())
-                    } else {
-                        remove_custom_node_dirs(&root_clone, &["comfyui-kjnodes", "ComfyUI-KJNodes"]);
-                        Ok("Removed comfyui-kjnodes.".to_string())
-                    }
-                }
-                "node_comfyui_crystools" => {
-                    if enabled {
-                        ensure_git_available(&app_clone)?;
-                        install_named_custom_node(
-                            &app_clone,
-                            &root_clone,
-                            &py_exe_clone,
-                            "https://github.com/crystian/comfyui-crystools.git",
-                            "comfyui-crystools",
-                        )?;
-                        Ok("Installed comfyui-crystools.".to_string())
-                    } else {
-                        remove_custom_node_dirs(&root_clone, &["comfyui-crystools", "ComfyUI-Crystools"]);
-                        Ok("Removed comfyui-crystools.".to_string())
-                    }
+
+        if remove_unused_toolchains {
+            for toolchain in before
+                .toolchains
+                .iter()
+                .filter(|toolchain| !toolchain.in_use)
+            {
+                match std::fs::remove_dir_all(&toolchain.path) {
+                    Ok(()) => report.removed_toolchains.push(toolchain.name.clone()),
+                    Err(err) => report
+                        .messages
+                        .push(format!("Could not remove {}: {err}", toolchain.name)),
                 }
-                _ => Err("Unknown component toggle target.".to_string()),
             }
-        })
-        .await
-        .map_err(|err| format!("Component operation task failed: {err}"))?
-    }?;
+        }
 
-    restart_comfyui_after_mutation(&app, &state, was_running)?;
-    Ok(result)
+        let after = runtime_cache_info(&state);
+        report.freed_bytes = before.total_bytes.saturating_sub(after.total_bytes);
+        report.messages.push(format!(
+            "Freed {}; runtime cache now {}.",
+            report_bytes(report.freed_bytes),
+            report_bytes(after.total_bytes)
+        ));
+        report
+    })
+    .await
+    .map_err(|err| err.to_string())
 }
 
-#[tauri::command]
-fn get_comfyui_runtime_status(state: State<'_, AppState>) -> ComfyRuntimeStatus {
-    ComfyRuntimeStatus {
-        running: comfyui_runtime_running(&state),
-    }
+fn os_pretty_name() -> Option<String> {
+    std::fs::read_to_string(host_os_release_path())
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|value| value.trim_matches('"').to_string())
 }
 
-#[tauri::command]
-fn get_comfyui_update_status(
-    state: State<'_, AppState>,
-    comfyui_root: Option<String>,
-) -> Result<ComfyUiUpdateStatus, String> {
-    let root = resolve_root_path(&state.context, comfyui_root)?;
-    let installed_version = read_comfyui_installed_version(&root);
+fn build_install_report(state: &AppState, root: &Path) -> Vec<ReportSection> {
+    let fingerprint = install_fingerprint(root);
+    let manifest = build_install_manifest(state, root);
+    let gpu = detect_nvidia_gpu_details();
+    let text = |value: Option<String>| value.unwrap_or_else(|| "unknown".to_string());
 
-    if !root.join(".git").exists() {
-        return Ok(ComfyUiUpdateStatus {
-            installed_version,
-            latest_version: None,
-            head_matches_latest_tag: false,
-            update_available: false,
-            checked: false,
-            detail: "Not a git-based ComfyUI install.".to_string(),
-        });
+    let mut environment = vec![
+        ("App".to_string(), state.context.display_version.clone()),
+        (
+            "OS".to_string(),
+            os_pretty_name().unwrap_or_else(detect_linux_distro_family),
+        ),
+        ("GPU".to_string(), text(gpu.name)),
+    ];
+    if let Some(vram_mb) = gpu.vram_mb {
+        environment.push(("VRAM".to_string(), format!("{vram_mb} MB")));
     }
+    if let Some(driver) = gpu.driver_version {
+        environment.push(("Driver".to_string(), driver));
+    }
+    if let Some(capability) = gpu.compute_capability {
+        environment.push(("Compute capability".to_string(), capability));
+    }
+    environment.extend([
+        ("ComfyUI".to_string(), text(fingerprint.comfyui_version)),
+        (
+            "ComfyUI commit".to_string(),
+            text(fingerprint.comfyui_commit),
+        ),
+        ("Python".to_string(), text(fingerprint.python_version)),
+        ("Torch".to_string(), text(fingerprint.torch_version)),
+        ("CUDA / ROCm".to_string(), text(fingerprint.cuda_version)),
+        ("Torch profile".to_string(), text(fingerprint.torch_profile)),
+        (
+            "Attention backend".to_string(),
+            fingerprint.attention_backend,
+        ),
+    ]);
+
+    let addons = [
+        ("InsightFace", pip_has_package(root, "insightface")),
+        (
+            "Trellis2",
+            custom_node_exists(root, "ComfyUI-Trellis2")
+                || custom_node_exists(root, "ComfyUI-TRELLIS2"),
+        ),
+        (
+            "SageAttention",
+            python_module_importable(root, "sageattention"),
+        ),
+        (
+            "SageAttention3",
+            python_module_importable(root, "sageattn3"),
+        ),
+        (
+            "FlashAttention",
+            python_module_importable(root, "flash_attn"),
+        ),
+        ("Nunchaku", pip_has_package(root, "nunchaku")),
+    ]
+    .into_iter()
+    .map(|(name, installed)| {
+        let status = if installed {
+            "installed"
+        } else {
+            "not installed"
+        };
+        (name.to_string(), status.to_string())
+    })
+    .collect();
+
+    let packages = fingerprint.packages.into_iter().collect();
+    let custom_nodes = fingerprint.custom_nodes.into_iter().collect();
+
+    let mut models: Vec<(String, String)> = manifest
+        .models
+        .iter()
+        .map(|model| {
+            (
+                format!("{} / {}", model.model_id, model.variant_id),
+                format!("{} ({} files)", model.tier, model.files.len()),
+            )
+        })
+        .collect();
+    models.extend(
+        manifest
+            .loras
+            .iter()
+            .map(|lora| (format!("LoRA {}", lora.lora_id), lora.file_name.clone())),
+    );
 
-    let Some((latest_tag, latest_version)) = git_latest_release_tag(&root) else {
-        return Ok(ComfyUiUpdateStatus {
-            installed_version,
-            latest_version: None,
-            head_matches_latest_tag: false,
-            update_available: false,
-            checked: false,
-            detail: "Could not read remote ComfyUI release tags.".to_string(),
-        });
-    };
-
-    let head_commit = git_commit_for_ref(&root, "HEAD");
-    let tag_commit = git_commit_for_ref(&root, &latest_tag);
-    if head_commit.is_some() && head_commit == tag_commit {
-        return Ok(ComfyUiUpdateStatus {
-            installed_version,
-            latest_version: Some(latest_version.clone()),
-            head_matches_latest_tag: true,
-            update_available: false,
-            checked: true,
-            detail: format!(
-                "ComfyUI is up to date by release tags (HEAD matches {latest_tag})."
-            ),
-        });
+    let mut disk = vec![
+        (
+            ".venv".to_string(),
+            report_bytes(dir_size_bytes(&root.join(".venv"))),
+        ),
+        (
+            "custom_nodes".to_string(),
+            report_bytes(dir_size_bytes(&root.join("custom_nodes"))),
+        ),
+    ];
+    for base in model_roots_for_comfy_root(root) {
+        let models_dir = base.join("models");
+        disk.push((
+            models_dir.to_string_lossy().to_string(),
+            report_bytes(dir_size_bytes(&models_dir)),
+        ));
+    }
+    disk.extend(["input", "output"].into_iter().map(|name| {
+        (
+            name.to_string(),
+            report_bytes(dir_size_bytes(&root.join(name))),
+        )
+    }));
+    if let Ok(free) = fs2::available_space(root) {
+        disk.push(("Free space".to_string(), report_bytes(free)));
     }
 
-    match installed_version.clone().and_then(|v| normalize_release_version(&v)) {
-        Some(local_version) => {
-            let local_triplet = parse_semver_triplet(&local_version);
-            let latest_triplet = parse_semver_triplet(&latest_version);
-            let update_available = matches!(
-                (local_triplet, latest_triplet),
-                (Some(local), Some(latest)) if latest > local
-            );
+    vec![
+        ReportSection {
+            title: "Environment",
+            rows: environment,
+        },
+        ReportSection {
+            title: "Addons",
+            rows: addons,
+        },
+        ReportSection {
+            title: "Key packages",
+            rows: packages,
+        },
+        ReportSection {
+            title: "Custom nodes",
+            rows: custom_nodes,
+        },
+        ReportSection {
+            title: "Catalog models present",
+            rows: models,
+        },
+        ReportSection {
+            title: "Disk usage",
+            rows: disk,
+        },
+    ]
+}
 
-            Ok(ComfyUiUpdateStatus {
-                installed_version,
-                latest_version: Some(latest_version.clone()),
-                head_matches_latest_tag: false,
-                update_available,
-                checked: true,
-                detail: if update_available {
-                    format!(
-                        "ComfyUI update available from release tags (local v{local_version}, latest tag {latest_tag})."
-                    )
-                } else {
-                    format!(
-                        "ComfyUI is up to date by release tags (local v{local_version}, latest tag {latest_tag})."
-                    )
-                },
-            })
+fn render_report_markdown(root: &Path, sections: &[ReportSection]) -> String {
+    let mut out = format!("# ComfyUI install report\n\n`{}`\n", root.display());
+    for section in sections {
+        out.push_str(&format!("\n## {}\n\n", section.title));
+        if section.rows.is_empty() {
+            out.push_str("_none_\n");
+        }
+        for (label, value) in &section.rows {
+            out.push_str(&format!("- **{label}**: {value}\n"));
         }
-        None => Ok(ComfyUiUpdateStatus {
-            installed_version,
-            latest_version: Some(latest_version.clone()),
-            head_matches_latest_tag: false,
-            update_available: false,
-            checked: true,
-            detail: format!(
-                "Detected latest release tag {latest_tag}, but local ComfyUI version metadata is unavailable."
-            ),
-        }),
     }
+    out
 }
 
-#[tauri::command]
-fn stop_comfyui_root(app: AppHandle, state: State<'_, AppState>) -> Result<bool, String> {
-    let instance_name = resolve_comfyui_instance_name(&state.context, None);
-    emit_comfyui_runtime_event(&app, "stopping", format!("Stopping {instance_name}..."));
-    let result = stop_comfyui_root_impl(&state);
-    if result.is_ok() {
-        let running = comfyui_runtime_running(&state);
-        update_tray_comfy_status(&app, running);
-        if running {
-            emit_comfyui_runtime_event(
-                &app,
-                "stop_failed",
-                format!("{instance_name} stop did not fully complete."),
-            );
-        } else {
-            emit_comfyui_runtime_event(&app, "stopped", format!("{instance_name} stopped."));
+fn html_escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_report_html(root: &Path, sections: &[ReportSection]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>ComfyUI install report</title></head>\n<body>\n<h1>ComfyUI install report</h1>\n<p><code>{}</code></p>\n",
+        html_escape(&root.to_string_lossy())
+    );
+    for section in sections {
+        out.push_str(&format!("<h2>{}</h2>\n", section.title));
+        if section.rows.is_empty() {
+            out.push_str("<p><em>none</em></p>\n");
+            continue;
         }
-    } else if let Err(err) = &result {
-        emit_comfyui_runtime_event(
-            &app,
-            "stop_failed",
-            format!("{instance_name} stop failed: {err}"),
-        );
+        out.push_str("<table>\n");
+        for (label, value) in &section.rows {
+            out.push_str(&format!(
+                "<tr><th align=\"left\">{}</th><td>{}</td></tr>\n",
+                html_escape(label),
+                html_escape(value)
+            ));
+        }
+        out.push_str("</table>\n");
     }
-    result
+    out.push_str("</body></html>\n");
+    out
 }
 
+/// Writes a shareable summary of the install next to it and returns the file path.
+/// The home directory is replaced with `~` so the report can be posted publicly.
 #[tauri::command]
-async fn update_selected_comfyui(
+async fn export_install_report(
     app: AppHandle,
     state: State<'_, AppState>,
     comfyui_root: Option<String>,
+    format: Option<String>,
 ) -> Result<String, String> {
-    let was_running = stop_comfyui_for_mutation(&app, &state)?;
     let root = resolve_root_path(&state.context, comfyui_root)?;
     if !root.join("main.py").is_file() {
-        return Err("Selected folder is not a valid ComfyUI root.".to_string());
-    }
-    if !root.join(".git").exists() {
-        return Err("Selected ComfyUI install is not git-based.".to_string());
-    }
-
-    let Some((latest_tag, latest_version)) = git_latest_release_tag(&root) else {
-        return Err("Could not resolve latest ComfyUI release tag from remote.".to_string());
-    };
-    let installed_version_norm = read_comfyui_installed_version(&root)
-        .and_then(|v| normalize_release_version(&v));
-    if let Some(current) = installed_version_norm {
-        let current_triplet = parse_semver_triplet(&current);
-        let latest_triplet = parse_semver_triplet(&latest_version);
-        if matches!(
-            (current_triplet, latest_triplet),
-            (Some(local), Some(latest)) if local >= latest
-        ) {
-            return Ok(format!(
-                "ComfyUI is already on latest release tag (v{latest_version})."
-            ));
-        }
+        return Err(format!("{} is not a valid ComfyUI root.", root.display()));
     }
-
-    let shared_runtime_root = state.context.config.cache_path().join("comfyui-runtime");
-    let uv_bin = resolve_uv_binary(&shared_runtime_root, &app)?;
-    let uv_python_install_dir = shared_runtime_root
-        .join(".python")
-        .to_string_lossy()
-        .to_string();
-    let selected_profile = resolve_desired_torch_profile(&state.context.config.settings(), &root);
-    let latest_tag_for_task = latest_tag.clone();
-    let latest_version_for_task = latest_version.clone();
-    let branch_for_task_raw = git_current_branch(&root).unwrap_or_else(|| "master".to_string());
-    let branch_for_task = if branch_for_task_raw.eq_ignore_ascii_case("head") {
-        "master".to_string()
-    } else {
-        branch_for_task_raw
+    let html = match format.as_deref().map(str::trim) {
+        None | Some("") | Some("md") | Some("markdown") => false,
+        Some("html") => true,
+        Some(other) => return Err(format!("Unknown report format: {other}")),
     };
-    tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
-        run_command_with_retry("git", &["fetch", "--tags", "origin"], Some(&root), 2)?;
-        if let Err(err) =
-            run_command_with_retry("git", &["merge", "--ff-only", &latest_tag_for_task], Some(&root), 2)
-        {
-            let lower = err.to_ascii_lowercase();
-            let can_repoint_branch = lower.contains("unrelated histories")
-                || lower.contains("not possible to fast-forward")
-                || lower.contains("not possible to fast forward")
-                || lower.contains("cannot fast-forward")
-                || lower.contains("diverging");
-            if can_repoint_branch {
-                // Preserve recoverability before repointing branch tip to release tag.
-                let ts = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .map(|d| d.as_secs())
-                    .unwrap_or(0);
-                let backup_branch = format!("arctic-backup-before-tag-update-{ts}");
-                run_command_with_retry("git", &["branch", &backup_branch], Some(&root), 1)
-                    .map_err(|backup_err| {
-                        format!(
-                            "Failed to create backup branch before tag migration ({backup_branch}). Details: {backup_err}"
-                        )
-                    })?;
-                run_command_with_retry(
-                    "git",
-                    &["checkout", "-B", &branch_for_task, &latest_tag_for_task],
-                    Some(&root),
-                    1,
-                )
-                .map_err(|checkout_err| {
-                    format!(
-                        "Failed to switch branch '{}' to release tag {} after merge fast-forward failed. Backup branch: {}. Details: {}",
-                        branch_for_task, latest_tag_for_task, backup_branch, checkout_err
-                    )
-                })?;
-            } else {
-                return Err(format!(
-                    "Failed to fast-forward ComfyUI to release tag {latest_tag_for_task}. Resolve local git divergence first. Details: {err}"
-                ));
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let sections = build_install_report(&state, &root);
+        let mut report = if html {
+            render_report_html(&root, &sections)
+        } else {
+            render_report_markdown(&root, &sections)
+        };
+        if let Ok(home) = std::env::var("HOME") {
+            if !home.is_empty() {
+                report = report.replace(&home, "~");
             }
         }
-
-        let py = python_exe_for_root(&root)?;
-        let req = root.join("requirements.txt");
-        if req.exists() {
-            run_uv_pip_strict(
-                &uv_bin,
-                py.to_string_lossy().as_ref(),
-                &["install", "-r", "requirements.txt", "--no-cache"],
-                Some(&root),
-                &[("UV_PYTHON_INSTALL_DIR", &uv_python_install_dir)],
-            )
-            .map_err(|err| format!("Failed to install ComfyUI requirements: {err}"))?;
-            enforce_torch_profile_linux(
-                &uv_bin,
-                py.to_string_lossy().as_ref(),
-                &root,
-                &selected_profile,
-                &uv_python_install_dir,
-            )
-            .map_err(|err| format!("Failed to re-apply selected torch profile: {err}"))?;
-        }
-        Ok(format!(
-            "ComfyUI updated successfully to release tag {latest_tag_for_task} (v{latest_version_for_task})."
-        ))
+        let target = root.join(if html {
+            "arctic-install-report.html"
+        } else {
+            "arctic-install-report.md"
+        });
+        std::fs::write(&target, report)
+            .map_err(|err| format!("Failed to write report to {}: {err}", target.display()))?;
+        log::info!(
+            "Exported install report for {} to {}",
+            root.display(),
+            target.display()
+        );
+        Ok(target.to_string_lossy().to_string())
     })
     .await
-    .map_err(|err| format!("ComfyUI update task failed: {err}"))??;
-
-    restart_comfyui_after_mutation(&app, &state, was_running)?;
-    Ok(format!(
-        "ComfyUI updated successfully to release tag {latest_tag} (v{latest_version})."
-    ))
+    .map_err(|err| format!("Install report failed: {err}"))?
 }
 
-fn stop_comfyui_root_impl(state: &AppState) -> Result<bool, String> {
+fn stop_comfyui_root_impl(state: &AppState, root: &Path) -> Result<bool, String> {
     let mut stopped_any = false;
+    if comfyui_service_status(root).is_ok_and(|status| status.active) {
+        run_command_capture(
+            "systemctl",
+            &["--user", "stop", &comfyui_service_unit(root)],
+            None,
+        )?;
+        stopped_any = true;
+    }
+    if let Some(install) = read_container_install(root) {
+        stopped_any |= stop_comfyui_container(&install);
+    }
 
-    let mut guard = state
-        .comfyui_process
+    let Some(instance) = forget_comfyui_instance(state, root) else {
+        return Ok(stopped_any);
+    };
+    let mut guard = instance
+        .process
         .lock()
         .map_err(|_| "comfyui process lock poisoned".to_string())?;
     if let Some(child) = guard.as_mut() {
@@ -5882,13 +19437,6 @@ fn stop_comfyui_root_impl(state: &AppState) -> Result<bool, String> {
         *guard = None;
         stopped_any = true;
     }
-    drop(guard);
-
-    // After app restart, we may no longer have a child handle but ComfyUI can still
-    // be running and listening on 8188. In that case, stop the listener process.
-    if comfyui_external_running(state) {
-        let _ = state;
-    }
 
     Ok(stopped_any)
 }
@@ -5937,12 +19485,24 @@ fn started_tray_icon() -> Option<Image<'static>> {
         .clone()
 }
 
-fn update_tray_comfy_status(app: &AppHandle, running: bool) {
+fn update_tray_comfy_status(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let mut names: Vec<String> = running_comfyui_instances(&state)
+        .iter()
+        .map(|(root, _)| comfyui_instance_name_from_path(root))
+        .collect();
+    if names.is_empty() && comfyui_external_running(&state) {
+        names.push(resolve_comfyui_instance_name(&state.context, None));
+    }
+    let running = !names.is_empty();
+    // The tray starts the configured root, so Start stays available while only
+    // other installs run.
+    let configured_running = comfyui_launch_root(&state, None)
+        .is_ok_and(|root| comfyui_root_running(&state, &root))
+        || comfyui_external_running(&state);
     if let Some(tray) = app.tray_by_id("arctic_tray") {
         let tooltip = if running {
-            let state = app.state::<AppState>();
-            let name = resolve_comfyui_instance_name(&state.context, None);
-            format!("Arctic ComfyUI Helper - Running: {name}")
+            format!("Arctic ComfyUI Helper - Running: {}", names.join(", "))
         } else {
             "Arctic ComfyUI Helper - ComfyUI: Stopped".to_string()
         };
@@ -5960,7 +19520,7 @@ fn update_tray_comfy_status(app: &AppHandle, running: bool) {
 
     if let Ok(guard) = tray_menu_items().lock() {
         if let Some(items) = guard.as_ref() {
-            let _ = items.start.set_enabled(!running);
+            let _ = items.start.set_enabled(!configured_running);
             let _ = items.stop.set_enabled(running);
         }
     }
@@ -5994,45 +19554,26 @@ fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
             }
             "tray_start" => {
                 let state = app.state::<AppState>();
-                if comfyui_runtime_running(&state) {
-                    let instance_name = resolve_comfyui_instance_name(&state.context, None);
-                    update_tray_comfy_status(app, true);
-                    emit_comfyui_runtime_event(
+                let configured = comfyui_launch_root(&state, None).ok();
+                if let Some(root) = configured.filter(|root| comfyui_root_running(&state, root)) {
+                    update_tray_comfy_status(app);
+                    emit_comfyui_root_event(
                         app,
+                        Some(&root),
                         "started",
-                        format!("{instance_name} is already running."),
+                        format!(
+                            "{} is already running.",
+                            comfyui_instance_name_from_path(&root)
+                        ),
                     );
                 } else {
-                    start_comfyui_root_background(app, None);
+                    start_comfyui_root_background(app, None, None);
                 }
             }
             "tray_stop" => {
                 let state = app.state::<AppState>();
-                let instance_name = resolve_comfyui_instance_name(&state.context, None);
-                emit_comfyui_runtime_event(app, "stopping", format!("Stopping {instance_name}..."));
-                if let Err(err) = stop_comfyui_root_impl(&state) {
+                if let Err(err) = stop_all_comfyui_roots(app, &state) {
                     log::warn!("Tray stop ComfyUI failed: {err}");
-                    emit_comfyui_runtime_event(
-                        app,
-                        "stop_failed",
-                        format!("{instance_name} stop failed: {err}"),
-                    );
-                } else {
-                    let running = comfyui_runtime_running(&state);
-                    update_tray_comfy_status(app, running);
-                    if running {
-                        emit_comfyui_runtime_event(
-                            app,
-                            "stop_failed",
-                            format!("{instance_name} stop did not fully complete."),
-                        );
-                    } else {
-                        emit_comfyui_runtime_event(
-                            app,
-                            "stopped",
-                            format!("{instance_name} stopped."),
-                        );
-                    }
                 }
             }
             "tray_quit" => {
@@ -6063,9 +19604,7 @@ fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
     }
 
     let _tray = builder.build(app)?;
-    let state = app.state::<AppState>();
-    let running = comfyui_runtime_running(&state);
-    update_tray_comfy_status(app, running);
+    update_tray_comfy_status(app);
     Ok(())
 }
 
@@ -6097,6 +19636,7 @@ fn cancel_active_download(state: State<'_, AppState>) -> Result<bool, String> {
 }
 
 fn main() {
+    perf::start();
     #[cfg(target_os = "linux")]
     {
         // Work around blank window / GBM allocation failures on some Wayland+NVIDIA setups.
@@ -6130,8 +19670,19 @@ fn main() {
     if nerdstats {
         log::info!("Nerdstats mode enabled (verbose runtime logging).");
     }
+    if faults::settings().is_active() {
+        log::warn!("Fault injection enabled: {:?}", faults::settings());
+    }
+    let agent_launch = agent_launch_from_args();
+    if agent_launch.is_some()
+        && std::env::var_os("DISPLAY").is_none()
+        && std::env::var_os("WAYLAND_DISPLAY").is_none()
+    {
+        // The agent has no window, but the Tauri runtime still initialises GTK.
+        reexec_agent_under_xvfb();
+    }
 
-    let context = match build_context() {
+    let context = match perf::time_startup("context_build", build_context) {
         Ok(context) => context,
         Err(err) => {
             eprintln!("Failed to initialize app context: {err:#}");
@@ -6142,17 +19693,34 @@ fn main() {
     tauri_context.set_default_window_icon(main_window_icon());
 
     tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
             let _ = show_main_window(app);
+            if let Some(root) = start_root_from_args(&argv) {
+                start_comfyui_root_background(app, Some(root), None);
+            }
         }))
         .plugin(tauri_plugin_notification::init())
-        .setup(|app| {
+        .setup(move |app| {
             if tray_enabled_for_platform() {
                 setup_tray(app.handle())?;
             } else {
                 log::info!("System tray disabled for this platform/runtime.");
             }
             warm_linux_prereq_cache_background();
+            setup_event_export(app.handle());
+            let _ = GLOBAL_APP.set(app.handle().clone());
+            reconcile_completed_installs(&app.state::<AppState>());
+            start_creator_poller(app.handle());
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            if let Some(root) = start_root_from_args(&args) {
+                start_comfyui_root_background(app.handle(), Some(root), None);
+            }
+            if let Some(launch) = &agent_launch {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+                start_agent_server(app.handle(), launch)?;
+            }
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -6178,46 +19746,155 @@ fn main() {
             active_cancel: Mutex::new(None),
             active_abort: Mutex::new(None),
             install_cancel: Mutex::new(None),
-            comfyui_process: Mutex::new(None),
+            toggle_cancel: Mutex::new(None),
+            mutation_sessions: Mutex::new(BTreeMap::new()),
+            maintenance: Mutex::new(BTreeMap::new()),
+            comfyui_instances: Mutex::new(BTreeMap::new()),
             quitting: Mutex::new(false),
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(timed_invoke_handler(tauri::generate_handler![
             get_app_snapshot,
+            detect_vram_tier,
             get_catalog,
+            get_favorites,
+            get_last_selection,
+            set_last_selection,
+            get_followed_creator_updates,
+            follow_civitai_creator,
+            unfollow_civitai_creator,
+            dismiss_creator_updates,
+            check_followed_creators,
+            download_creator_update,
+            toggle_lora_favorite,
+            validate_catalog,
+            get_catalog_sources,
+            get_catalog_changes,
+            set_ram_tier_thresholds,
+            set_hook_settings,
+            set_retry_settings,
+            set_comfyui_auto_restart,
+            set_package_index_settings,
+            set_triton_version_override,
+            set_comfy_settings_template,
+            apply_comfy_settings_template,
+            create_api_token,
+            list_api_tokens,
+            revoke_api_token,
+            pair_remote_agent,
+            unpair_remote_agent,
+            invoke_remote_agent,
+            set_event_export_settings,
+            get_host_stats,
+            get_perf_profile,
+            get_runtime_cache_info,
+            clean_runtime_cache,
+            set_host_blacklisted,
+            get_variant_details,
+            acknowledge_variant_warnings,
+            override_compat_block,
+            get_model_details,
+            set_catalog_sources,
             get_settings,
             inspect_comfyui_path,
             list_comfyui_installations,
             get_comfyui_install_recommendation,
             get_comfyui_resume_state,
+            get_install_log_tail,
+            get_comfyui_log_tail,
+            get_install_history,
+            get_install_state_detail,
             get_comfyui_addon_state,
             apply_attention_backend_change,
+            select_attention_backend,
+            remove_attention_backend,
             apply_comfyui_component_toggle,
+            apply_comfyui_component_toggles,
+            begin_mutation,
+            commit_mutation,
+            abort_mutation,
+            get_mutation_session,
+            get_comfyui_maintenance,
+            list_env_snapshots,
+            get_comfyui_rollback,
+            rollback_comfyui_update,
+            restore_env_snapshot,
+            cancel_comfyui_component_toggle,
+            install_custom_node_by_url,
+            update_all_custom_nodes,
             get_comfyui_update_status,
+            unpin_comfyui_ref,
             update_selected_comfyui,
+            repair_comfyui_installation,
+            repair_comfyui_install,
+            duplicate_comfyui_install,
+            relocate_installation,
+            diff_installs,
+            export_install_report,
+            warm_model_cache,
+            build_offline_install_cache,
             run_comfyui_preflight,
+            plan_comfyui_install,
             get_hf_xet_preflight,
             set_hf_xet_enabled,
             set_comfyui_root,
             set_comfyui_install_base,
             get_comfyui_extra_model_config,
             set_comfyui_extra_model_config,
+            sync_shared_models_dir,
+            diagnose_models_folders,
+            migrate_models,
+            get_comfyui_data_dirs,
+            set_comfyui_data_dirs,
             save_civitai_token,
+            set_civitai_examples_enabled,
+            get_settings_encryption_status,
+            unlock_settings,
+            set_settings_encryption,
             check_updates_now,
             auto_update_startup,
+            plan_model_download,
+            get_variant_artifact_options,
             download_model_assets,
             download_lora_asset,
             download_workflow_asset,
+            download_catalog_asset,
             get_lora_metadata,
+            get_lora_metadata_queue_position,
+            get_lora_preview_media,
+            cleanup_orphaned_models,
+            remove_model_assets,
+            find_superseded_artifacts,
+            resolve_superseded_artifacts,
+            remove_lora_asset,
+            export_manifest,
+            import_manifest,
             start_comfyui_install,
+            resume_comfyui_install,
             cancel_comfyui_install,
             start_comfyui_root,
             stop_comfyui_root,
             get_comfyui_runtime_status,
             open_folder,
             open_external_url,
+            open_venv_shell,
+            create_comfyui_service,
+            remove_comfyui_service,
+            get_comfyui_service_status,
+            get_install_summary,
+            get_install_env_vars,
+            set_install_env_vars,
+            get_comfyui_gpus,
+            set_comfyui_gpu,
+            get_comfyui_vram_mode,
+            set_comfyui_vram_mode,
+            get_comfyui_manager_config,
+            set_comfyui_manager_config,
+            set_comfyui_frontend_version,
+            create_comfyui_desktop_entry,
+            remove_comfyui_desktop_entry,
             pick_folder,
             cancel_active_download
-        ])
+        ]))
         .run(tauri_context)
         .expect("failed to run tauri application");
 }
@@ -6284,3 +19961,8 @@ fn install_linux_gdk_log_filter() {
 
     let _ = INSTALLED.set(());
 }
+
+#[cfg(test)]
+mod fake_comfyui;
+#[cfg(test)]
+mod runtime_tests;
@@ -0,0 +1,156 @@
+use super::*;
+use crate::fake_comfyui::{free_port, spawn_fake_comfyui, FakeComfyServer, FakeLaunch};
+
+#[test]
+fn readiness_probe_recognizes_comfyui() {
+    let server = FakeComfyServer::start();
+    assert!(port_accepts_connections(server.port()));
+    assert!(port_serves_comfyui(server.port()));
+    assert_eq!(
+        server.with_state(|state| state.requests.clone()),
+        vec!["GET /system_stats".to_string()]
+    );
+}
+
+#[test]
+fn readiness_probe_rejects_other_listeners() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut stream = stream;
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        }
+    });
+    assert!(port_accepts_connections(port));
+    assert!(!port_serves_comfyui(port));
+    assert!(!port_serves_comfyui(free_port()));
+}
+
+#[test]
+fn start_waits_for_a_slow_server() {
+    let port = free_port();
+    let process = Mutex::new(Some(spawn_fake_comfyui(FakeLaunch::Runs)));
+    let server = FakeComfyServer::start_later(port, Duration::from_millis(600));
+    wait_for_comfyui_ready(&process, port, Duration::from_secs(10)).unwrap();
+    let _server = server.join().unwrap();
+    let child = process.lock().unwrap().take();
+    if let Some(mut child) = child {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+#[test]
+fn start_fails_fast_when_the_process_exits() {
+    let port = free_port();
+    let process = Mutex::new(Some(spawn_fake_comfyui(FakeLaunch::Exits(3))));
+    let started = Instant::now();
+    let err = wait_for_comfyui_ready(&process, port, Duration::from_secs(30)).unwrap_err();
+    assert!(err.contains("exited during startup"), "{err}");
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(process.lock().unwrap().is_none());
+}
+
+#[test]
+fn start_times_out_without_a_process_or_server() {
+    let port = free_port();
+    let process = Mutex::new(None);
+    let err = wait_for_comfyui_ready(&process, port, Duration::from_millis(500)).unwrap_err();
+    assert!(err.contains("did not become ready"), "{err}");
+}
+
+#[test]
+fn instance_tracks_its_own_process() {
+    let crashed = ComfyInstance::new(free_port(), false);
+    crashed
+        .attach(spawn_fake_comfyui(FakeLaunch::Exits(1)))
+        .unwrap();
+    let running = ComfyInstance::new(free_port(), false);
+    running
+        .attach(spawn_fake_comfyui(FakeLaunch::Runs))
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while crashed.running() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(!crashed.running());
+    assert!(running.running());
+    let child = running.process.lock().unwrap().take();
+    if let Some(mut child) = child {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+#[test]
+fn adopted_instance_follows_its_port() {
+    let server = FakeComfyServer::start();
+    let adopted = ComfyInstance::adopted(server.port());
+    assert_eq!(adopted.status(), "running");
+    assert!(adopted.running());
+    assert!(!ComfyInstance::adopted(free_port()).running());
+}
+
+#[test]
+fn vram_is_read_from_system_stats() {
+    let server = FakeComfyServer::start();
+    server.with_state(|state| {
+        state.vram_total = 8 << 30;
+        state.vram_free = 6 << 30;
+    });
+    let client = reqwest::blocking::Client::new();
+    assert_eq!(
+        comfyui_vram(&client, &server.base_url()),
+        Some((8 << 30, 6 << 30))
+    );
+}
+
+#[test]
+fn warmup_queues_a_prompt_and_reports_the_vram_delta() {
+    let server = FakeComfyServer::start();
+    server.with_state(|state| state.prompt_vram_bytes = 2 << 30);
+    let result = run_model_warmup(&server.base_url(), "sdxl.safetensors").unwrap();
+    assert_eq!(result.prompt_id, "fake-0");
+    assert_eq!(result.vram_delta_bytes, Some(2 << 30));
+    let prompt = server.with_state(|state| state.prompts[0].clone());
+    assert_eq!(
+        prompt.pointer("/prompt/1/inputs/ckpt_name"),
+        Some(&serde_json::json!("sdxl.safetensors"))
+    );
+}
+
+#[test]
+fn warmup_surfaces_execution_errors() {
+    let server = FakeComfyServer::start();
+    server.with_state(|state| state.prompt_error = Some("CUDA out of memory".to_string()));
+    let err = run_model_warmup(&server.base_url(), "flux.safetensors").unwrap_err();
+    assert_eq!(err, "CUDA out of memory");
+}
+
+#[test]
+fn vram_flags_follow_tier_unless_overridden() {
+    assert_eq!(
+        vram_launch_flags(None, Some(VramTier::TierS), false),
+        vec!["--highvram"]
+    );
+    assert!(vram_launch_flags(None, Some(VramTier::TierB), false).is_empty());
+    assert_eq!(
+        vram_launch_flags(Some("auto"), Some(VramTier::TierC), true),
+        vec!["--lowvram", "--cache-none"]
+    );
+    assert!(vram_launch_flags(Some("normal"), Some(VramTier::TierC), false).is_empty());
+    assert_eq!(
+        vram_launch_flags(Some("none"), Some(VramTier::TierS), false),
+        vec!["--novram"]
+    );
+    let args = comfyui_launch_args(
+        false,
+        None,
+        false,
+        &ComfyDataDirs::default(),
+        true,
+        &["--lowvram"],
+    );
+    assert!(!args.iter().any(|arg| arg == "--lowvram"));
+}
@@ -0,0 +1,388 @@
+//! Headless agent mode. A GPU server runs the helper with `--agent` and a desktop
+//! install pairs with it (host + API token) to drive installs, downloads and the
+//! ComfyUI runtime remotely. The wire format is one JSON-over-HTTP endpoint:
+//!
+//! `POST /v1/invoke` with `Authorization: Bearer <token>` and
+//! `{"method": "<command>", "params": {...}}`; the reply is `{"result": ...}` or
+//! `{"error": "..."}`. Method names and parameters are the Tauri command ones, and
+//! tokens are checked with [`api_access::authorize`].
+//!
+//! The server speaks plain HTTP and only binds loopback addresses, so tokens never
+//! cross the network in the clear. Remote desktops reach it over an SSH tunnel
+//! (`ssh -L 47821:127.0.0.1:47821 gpu-box`) or a TLS reverse proxy in front of it.
+
+use crate::api_access::{self, ApiAccessError, ApiToken};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::Duration,
+};
+use thiserror::Error;
+
+pub const DEFAULT_AGENT_PORT: u16 = 47821;
+const INVOKE_PATH: &str = "/v1/invoke";
+const MAX_REQUEST_BYTES: usize = 4 * 1024 * 1024;
+const MAX_HEADER_LINES: usize = 64;
+/// Request line plus headers; anything longer is rejected before it is buffered.
+const MAX_HEADER_BYTES: u64 = 16 * 1024;
+/// Events kept for paired desktops to poll; older ones are dropped.
+const EVENT_BUFFER_LEN: usize = 2000;
+const CLIENT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Connections served at once; calls block for a whole install, so this also bounds
+/// how many long operations a client can pile up.
+const MAX_CONNECTIONS: usize = 16;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AgentRequest {
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// An event the agent emitted, numbered so pollers can ask for what they missed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AgentEvent {
+    pub seq: u64,
+    pub channel: String,
+    pub payload: Value,
+}
+
+#[derive(Default)]
+struct EventBuffer {
+    next_seq: u64,
+    events: VecDeque<AgentEvent>,
+}
+
+static EVENTS: OnceLock<Mutex<EventBuffer>> = OnceLock::new();
+
+fn event_buffer() -> &'static Mutex<EventBuffer> {
+    EVENTS.get_or_init(|| Mutex::new(EventBuffer::default()))
+}
+
+pub fn record_event(channel: &str, payload: Value) {
+    let Ok(mut buffer) = event_buffer().lock() else {
+        return;
+    };
+    buffer.next_seq += 1;
+    let seq = buffer.next_seq;
+    buffer.events.push_back(AgentEvent {
+        seq,
+        channel: channel.to_string(),
+        payload,
+    });
+    while buffer.events.len() > EVENT_BUFFER_LEN {
+        buffer.events.pop_front();
+    }
+}
+
+/// Events newer than `since`, oldest first.
+pub fn events_since(since: u64) -> Vec<AgentEvent> {
+    event_buffer()
+        .lock()
+        .map(|buffer| {
+            buffer
+                .events
+                .iter()
+                .filter(|event| event.seq > since)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+type TokenSource = dyn Fn() -> Vec<ApiToken> + Send + Sync;
+type Dispatcher = dyn Fn(&str, Value) -> Result<Value, String> + Send + Sync;
+
+/// Serves the agent API. `tokens` is read on every request so revocations apply
+/// immediately; `dispatch` runs the named command and may block for as long as it takes.
+pub struct AgentServer {
+    tokens: Box<TokenSource>,
+    dispatch: Box<Dispatcher>,
+}
+
+impl AgentServer {
+    pub fn new(
+        tokens: impl Fn() -> Vec<ApiToken> + Send + Sync + 'static,
+        dispatch: impl Fn(&str, Value) -> Result<Value, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            tokens: Box::new(tokens),
+            dispatch: Box::new(dispatch),
+        }
+    }
+
+    /// Accepts connections until the listener fails, one thread per connection and at
+    /// most [`MAX_CONNECTIONS`] of them; the rest are turned away with 503.
+    pub fn run(self, listener: TcpListener) -> io::Result<()> {
+        let server = Arc::new(self);
+        let active = Arc::new(AtomicUsize::new(0));
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::warn!("Agent connection failed: {err}");
+                    continue;
+                }
+            };
+            if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                active.fetch_sub(1, Ordering::SeqCst);
+                let body = json!({ "error": "agent is busy, retry later" });
+                let _ = write_response(&mut stream, 503, &body);
+                continue;
+            }
+            let server = Arc::clone(&server);
+            let active = Arc::clone(&active);
+            std::thread::spawn(move || {
+                if let Err(err) = server.handle_connection(stream) {
+                    log::debug!("Agent connection closed with error: {err}");
+                }
+                active.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        Ok(())
+    }
+
+    fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(CLIENT_READ_TIMEOUT))?;
+        let peer = stream
+            .peer_addr()
+            .map(|addr| addr.to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+        let mut writer = stream.try_clone()?;
+        let (status, body) = match read_request(stream) {
+            Ok(http) => self.respond(&peer, http),
+            Err(err) => (400, json!({ "error": err })),
+        };
+        write_response(&mut writer, status, &body)
+    }
+
+    fn respond(&self, peer: &str, http: HttpRequest) -> (u16, Value) {
+        if http.method != "POST" || http.path != INVOKE_PATH {
+            return (404, json!({ "error": format!("use POST {INVOKE_PATH}") }));
+        }
+        let Some(presented) = http.bearer else {
+            return (401, json!({ "error": "missing bearer token" }));
+        };
+        let request: AgentRequest = match serde_json::from_slice(&http.body) {
+            Ok(request) => request,
+            Err(err) => return (400, json!({ "error": format!("invalid request: {err}") })),
+        };
+        let tokens = (self.tokens)();
+        let token = match api_access::authorize(&tokens, &presented, &request.method) {
+            Ok(token) => token,
+            Err(err @ ApiAccessError::UnknownToken) => {
+                log::warn!("Agent rejected {} from {peer}: {err}", request.method);
+                return (401, json!({ "error": err.to_string() }));
+            }
+            Err(err) => return (403, json!({ "error": err.to_string() })),
+        };
+        log::info!(
+            "Agent call {} from {peer} (token '{}')",
+            request.method,
+            token.name
+        );
+        match (self.dispatch)(&request.method, request.params) {
+            Ok(result) => (200, json!({ "result": result })),
+            Err(err) => (422, json!({ "error": err })),
+        }
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    bearer: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Reads one line of the header section; a line cut short by the byte budget or the
+/// connection closing is an error rather than a header.
+fn read_header_line(reader: &mut impl BufRead, line: &mut String) -> Result<(), String> {
+    line.clear();
+    reader
+        .read_line(line)
+        .map_err(|err| format!("failed to read request: {err}"))?;
+    if !line.ends_with('\n') {
+        return Err(format!(
+            "request headers incomplete or longer than {MAX_HEADER_BYTES} bytes"
+        ));
+    }
+    Ok(())
+}
+
+fn read_request(stream: TcpStream) -> Result<HttpRequest, String> {
+    let mut reader = BufReader::new(stream.take(MAX_HEADER_BYTES));
+    let mut line = String::new();
+    read_header_line(&mut reader, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    if method.is_empty() || path.is_empty() {
+        return Err("malformed request line".to_string());
+    }
+
+    let mut content_length = 0usize;
+    let mut bearer = None;
+    let mut headers_done = false;
+    for _ in 0..MAX_HEADER_LINES {
+        read_header_line(&mut reader, &mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            headers_done = true;
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .parse()
+                .map_err(|_| "invalid Content-Length".to_string())?;
+        } else if name.eq_ignore_ascii_case("authorization") {
+            bearer = value
+                .strip_prefix("Bearer ")
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty());
+        }
+    }
+    if !headers_done {
+        return Err(format!(
+            "request has more than {MAX_HEADER_LINES} header lines"
+        ));
+    }
+    if content_length > MAX_REQUEST_BYTES {
+        return Err(format!("request body exceeds {MAX_REQUEST_BYTES} bytes"));
+    }
+
+    // Part of the body may already sit in the buffer; the rest must fit the new limit.
+    reader.get_mut().set_limit(content_length as u64);
+    let mut body = vec![0u8; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|err| format!("failed to read request body: {err}"))?;
+    Ok(HttpRequest {
+        method,
+        path,
+        bearer,
+        body,
+    })
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: &Value) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        503 => "Service Unavailable",
+        _ => "Unprocessable Entity",
+    };
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
+
+#[derive(Debug, Error)]
+pub enum AgentError {
+    #[error("agent address is empty")]
+    EmptyAddress,
+    #[error("could not reach agent: {0}")]
+    Transport(#[from] reqwest::Error),
+    #[error("agent refused the request ({status}): {message}")]
+    Rejected { status: u16, message: String },
+}
+
+/// Turns what the user typed (`gpu-box`, `localhost:9000`, `http://gpu-box:47821/`)
+/// into the agent's base URL. Without a scheme, loopback hosts (SSH tunnels) get
+/// `http` and anything else `https`, since only a TLS proxy exposes an agent.
+pub fn normalize_agent_url(host: &str) -> Result<String, AgentError> {
+    let host = host.trim().trim_end_matches('/');
+    if host.is_empty() {
+        return Err(AgentError::EmptyAddress);
+    }
+    let with_scheme = if host.contains("://") {
+        host.to_string()
+    } else if is_loopback_host(host) {
+        format!("http://{host}")
+    } else {
+        format!("https://{host}")
+    };
+    let authority = with_scheme
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or_default();
+    let has_port = authority
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok());
+    if has_port {
+        Ok(with_scheme)
+    } else {
+        Ok(format!("{with_scheme}:{DEFAULT_AGENT_PORT}"))
+    }
+}
+
+fn is_loopback_host(authority: &str) -> bool {
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if port.parse::<u16>().is_ok() => host,
+        _ => authority,
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Calls `method` on a paired agent. No overall timeout is set because installs run
+/// inside the call; only connecting is bounded.
+pub async fn call(
+    base_url: &str,
+    token: &str,
+    method: &str,
+    params: Value,
+) -> Result<Value, AgentError> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()?;
+    let response = client
+        .post(format!("{base_url}{INVOKE_PATH}"))
+        .bearer_auth(token)
+        .json(&AgentRequest {
+            method: method.to_string(),
+            params,
+        })
+        .send()
+        .await?;
+    let status = response.status();
+    let mut body: Value = response.json().await?;
+    if status.is_success() {
+        return Ok(body
+            .get_mut("result")
+            .map(Value::take)
+            .unwrap_or(Value::Null));
+    }
+    let message = body
+        .get("error")
+        .and_then(Value::as_str)
+        .unwrap_or("no error message")
+        .to_string();
+    Err(AgentError::Rejected {
+        status: status.as_u16(),
+        message,
+    })
+}
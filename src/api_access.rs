@@ -0,0 +1,192 @@
+use chacha20poly1305::aead::{rand_core::RngCore, OsRng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Prefix of every issued token, so leaked tokens are easy to grep for.
+const TOKEN_PREFIX: &str = "arctic_";
+
+/// Methods that only report state. Settings are excluded because they can carry
+/// credentials.
+const READ_ONLY_METHODS: &[&str] = &[
+    "agent_events",
+    "get_app_snapshot",
+    "detect_vram_tier",
+    "get_catalog",
+    "get_favorites",
+    "get_last_selection",
+    "get_followed_creator_updates",
+    "validate_catalog",
+    "get_catalog_sources",
+    "get_catalog_changes",
+    "get_host_stats",
+    "get_perf_profile",
+    "get_runtime_cache_info",
+    "get_variant_details",
+    "get_model_details",
+    "inspect_comfyui_path",
+    "list_comfyui_installations",
+    "get_comfyui_install_recommendation",
+    "get_comfyui_resume_state",
+    "get_install_history",
+    "get_install_state_detail",
+    "get_comfyui_addon_state",
+    "get_mutation_session",
+    "get_comfyui_maintenance",
+    "list_env_snapshots",
+    "get_comfyui_rollback",
+    "get_comfyui_update_status",
+    "diff_installs",
+    "run_comfyui_preflight",
+    "plan_comfyui_install",
+    "get_hf_xet_preflight",
+    "get_comfyui_extra_model_config",
+    "get_comfyui_data_dirs",
+    "get_settings_encryption_status",
+    "plan_model_download",
+    "get_variant_artifact_options",
+    "find_superseded_artifacts",
+    "get_lora_metadata",
+    "get_lora_metadata_queue_position",
+    "get_lora_preview_media",
+    "get_comfyui_runtime_status",
+    "get_comfyui_service_status",
+    "get_install_summary",
+    "get_comfyui_manager_config",
+    "diagnose_models_folders",
+    "get_comfyui_log_tail",
+    "get_comfyui_gpus",
+    "get_comfyui_vram_mode",
+];
+
+/// Methods that fetch catalog assets into the configured ComfyUI root.
+const DOWNLOAD_METHODS: &[&str] = &[
+    "acknowledge_variant_warnings",
+    "override_compat_block",
+    "download_model_assets",
+    "download_lora_asset",
+    "download_creator_update",
+    "download_workflow_asset",
+    "download_catalog_asset",
+    "cancel_active_download",
+];
+
+/// What an automation token may do. Scopes are ordered: each one includes the ones
+/// before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiScope {
+    ReadOnly,
+    Download,
+    /// Installs, updates, removals, settings and system package installs.
+    Mutate,
+}
+
+impl ApiScope {
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            ApiScope::ReadOnly => "read_only",
+            ApiScope::Download => "download",
+            ApiScope::Mutate => "mutate",
+        }
+    }
+
+    pub fn from_identifier(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "read_only" | "readonly" | "read" => Some(ApiScope::ReadOnly),
+            "download" => Some(ApiScope::Download),
+            "mutate" | "install" => Some(ApiScope::Mutate),
+            _ => None,
+        }
+    }
+
+    /// Least scope allowed to call `method`. Anything not listed needs `Mutate`, so a
+    /// newly added command is never reachable with a weaker token by accident.
+    pub fn required_for(method: &str) -> Self {
+        if READ_ONLY_METHODS.contains(&method) {
+            ApiScope::ReadOnly
+        } else if DOWNLOAD_METHODS.contains(&method) {
+            ApiScope::Download
+        } else {
+            ApiScope::Mutate
+        }
+    }
+
+    pub fn allows(&self, method: &str) -> bool {
+        *self >= Self::required_for(method)
+    }
+}
+
+/// A stored token. Only the SHA-256 of the secret is kept.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scope: ApiScope,
+    pub token_sha256: String,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum ApiAccessError {
+    #[error("unknown or revoked API token")]
+    UnknownToken,
+    #[error("token '{name}' has scope {scope} but {method} requires {required}")]
+    Forbidden {
+        name: String,
+        method: String,
+        scope: &'static str,
+        required: &'static str,
+    },
+}
+
+fn hash_token(secret: &str) -> String {
+    Sha256::digest(secret.trim().as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// 32 random bytes from the OS, hex encoded.
+pub fn random_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Creates a token record and returns it with the secret, which is not stored anywhere
+/// and must be shown to the user once.
+pub fn issue_token(name: &str, scope: ApiScope, created_at: u64) -> (ApiToken, String) {
+    let secret = format!("{TOKEN_PREFIX}{}", random_secret());
+    let token_sha256 = hash_token(&secret);
+    let token = ApiToken {
+        id: token_sha256[..12].to_string(),
+        name: name.trim().to_string(),
+        scope,
+        token_sha256,
+        created_at,
+    };
+    (token, secret)
+}
+
+/// Resolves the token presented with a request and checks it may call `method`.
+pub fn authorize<'a>(
+    tokens: &'a [ApiToken],
+    presented: &str,
+    method: &str,
+) -> Result<&'a ApiToken, ApiAccessError> {
+    let digest = hash_token(presented);
+    let token = tokens
+        .iter()
+        .find(|token| token.token_sha256 == digest)
+        .ok_or(ApiAccessError::UnknownToken)?;
+    if !token.scope.allows(method) {
+        return Err(ApiAccessError::Forbidden {
+            name: token.name.clone(),
+            method: method.to_string(),
+            scope: token.scope.identifier(),
+            required: ApiScope::required_for(method).identifier(),
+        });
+    }
+    Ok(token)
+}
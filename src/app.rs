@@ -2,6 +2,8 @@ use crate::{
     catalog::CatalogService,
     config::ConfigStore,
     download::DownloadManager,
+    events::EventExporter,
+    perf,
     ram::{RamProfile, RamTier},
     updater::Updater,
 };
@@ -19,6 +21,7 @@ pub struct AppContext {
     pub catalog: Arc<CatalogService>,
     pub downloads: Arc<DownloadManager>,
     pub updater: Arc<Updater>,
+    pub events: Arc<EventExporter>,
     pub ram_profile: Option<RamProfile>,
     pub display_version: String,
 }
@@ -42,10 +45,15 @@ pub fn build_context() -> Result<AppContext> {
         );
 
         let config = Arc::new(ConfigStore::new()?);
-        let catalog = Arc::new(CatalogService::new(config.clone())?);
+        let catalog = Arc::new(perf::time_startup("catalog_load", || {
+            CatalogService::new(config.clone())
+        })?);
 
         // Ensure catalog is always refreshed from remote before the UI boots.
-        if let Err(err) = runtime.block_on(catalog.refresh_from_remote()) {
+        let refreshed = perf::time_startup("catalog_refresh", || {
+            runtime.block_on(catalog.refresh_from_remote())
+        });
+        if let Err(err) = refreshed {
             warn!("Unable to refresh catalog from remote source: {err:#}");
         } else {
             info!("Catalog refreshed from remote at startup.");
@@ -58,12 +66,20 @@ pub fn build_context() -> Result<AppContext> {
             config.clone(),
             display_version.clone(),
         )?);
+        let events = Arc::new(EventExporter::default());
+        let export_settings = config.settings().event_export;
+        if let Some(state_dir) = config.state_path() {
+            if let Err(err) = events.configure(&export_settings, &state_dir) {
+                warn!("Event export disabled: {err:#}");
+            }
+        }
         Ok(AppContext {
             runtime,
             config,
             catalog,
             downloads,
             updater,
+            events,
             ram_profile: None,
             display_version,
         })
@@ -1,25 +1,35 @@
 use crate::{
-    config::{default_catalog_endpoint, ConfigStore},
+    config::{default_catalog_endpoint, CatalogSource, ConfigStore},
     env_flags::prefer_local_catalog,
-    model::{LoraDefinition, ModelCatalog, ModelVariant, ResolvedModel, WorkflowDefinition},
+    model::{
+        diff_catalogs, validate_catalog_json, AssetClass, AssetDefinition, CatalogChanges,
+        CatalogConflict, CatalogIssue, LoraDefinition, ModelCatalog, ModelVariant, ResolvedModel,
+        WorkflowDefinition,
+    },
     vram::VramTier,
 };
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use log::{info, warn};
 use reqwest::{header, Client, StatusCode};
 use std::{
     fs,
     path::{Path, PathBuf},
     sync::{Arc, RwLock},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 const BUNDLED_CATALOG: &str = include_str!("../data/catalog.json");
 const CACHED_CATALOG_FILE: &str = "catalog.json";
+const SOURCE_CACHE_DIR: &str = "catalog-sources";
+const CATALOG_CHANGES_FILE: &str = "catalog-changes.json";
+pub const OFFICIAL_SOURCE_NAME: &str = "official";
 
 #[derive(Debug)]
 pub struct CatalogService {
     catalog: RwLock<ModelCatalog>,
+    official: RwLock<ModelCatalog>,
+    conflicts: RwLock<Vec<CatalogConflict>>,
+    changes: RwLock<Option<CatalogChanges>>,
     config: Arc<ConfigStore>,
 }
 
@@ -38,22 +48,80 @@ impl CatalogService {
                     serde_json::from_str(BUNDLED_CATALOG).expect("valid bundled JSON")
                 })
         };
+        let service = Self {
+            catalog: RwLock::new(catalog.clone()),
+            official: RwLock::new(catalog),
+            conflicts: RwLock::new(Vec::new()),
+            changes: RwLock::new(load_catalog_changes(&config)),
+            config,
+        };
+        service.rebuild();
+        let catalog = service.catalog_snapshot();
         info!(
             "Catalog initialised with {} models ({} LoRAs, {} workflows).",
             catalog.models.len(),
             catalog.loras.len(),
             catalog.workflows.len()
         );
-        Ok(Self {
-            catalog: RwLock::new(catalog),
-            config,
-        })
+        Ok(service)
     }
 
     pub fn catalog_snapshot(&self) -> ModelCatalog {
         self.catalog.read().expect("catalog poisoned").clone()
     }
 
+    /// Entries defined by several catalog sources during the last merge.
+    pub fn catalog_conflicts(&self) -> Vec<CatalogConflict> {
+        self.conflicts.read().expect("catalog poisoned").clone()
+    }
+
+    /// Diff recorded by the most recent refresh that changed the catalog.
+    pub fn catalog_changes(&self) -> Option<CatalogChanges> {
+        self.changes.read().expect("catalog poisoned").clone()
+    }
+
+    /// Re-merges the official catalog with the enabled sources from settings. Remote
+    /// sources are read from their last downloaded copy.
+    pub fn rebuild(&self) {
+        let official = self.official.read().expect("catalog poisoned").clone();
+        let mut layers = vec![(0, OFFICIAL_SOURCE_NAME.to_string(), official)];
+        for source in self
+            .config
+            .settings()
+            .catalog_sources
+            .into_iter()
+            .filter(|source| source.enabled)
+        {
+            let path = self.source_catalog_path(&source);
+            if !path.exists() {
+                warn!(
+                    "Catalog source '{}' has no data yet at {:?}; skipping.",
+                    source.name, path
+                );
+                continue;
+            }
+            if let Some(catalog) = load_catalog_from_path(&path) {
+                layers.push((source.priority, source.name, catalog));
+            }
+        }
+        // Stable sort keeps the official catalog ahead of sources with the same priority.
+        layers.sort_by_key(|layer| std::cmp::Reverse(layer.0));
+        let (merged, conflicts) = ModelCatalog::merge_layers(
+            layers
+                .into_iter()
+                .map(|(_, name, catalog)| (name, catalog))
+                .collect(),
+        );
+        for conflict in &conflicts {
+            info!(
+                "Catalog {} '{}' from '{}' overridden by '{}'.",
+                conflict.kind, conflict.id, conflict.dropped_source, conflict.kept_source
+            );
+        }
+        *self.catalog.write().expect("catalog poisoned for write") = merged;
+        *self.conflicts.write().expect("catalog poisoned for write") = conflicts;
+    }
+
     pub fn variants_for_tier(&self, model_id: &str, tier: VramTier) -> Vec<ModelVariant> {
         let catalog = self.catalog_snapshot();
         catalog
@@ -99,7 +167,79 @@ impl CatalogService {
         self.catalog_snapshot().find_workflow(id)
     }
 
+    pub fn find_asset(&self, class: AssetClass, id: &str) -> Option<AssetDefinition> {
+        self.catalog_snapshot().find_asset(class, id)
+    }
+
+    /// Refreshes the official catalog and every enabled remote source, then re-merges.
     pub async fn refresh_from_remote(&self) -> Result<bool> {
+        let previous = self.catalog_snapshot();
+        let official = self.refresh_official_catalog().await;
+        let sources = self.refresh_catalog_sources().await;
+        if sources || matches!(official, Ok(true)) {
+            self.rebuild();
+            self.record_changes(&previous);
+        }
+        official.map(|updated| updated || sources)
+    }
+
+    /// Downloads enabled remote sources into the cache. Failures are logged per source so
+    /// one unreachable catalog does not block the others.
+    pub async fn refresh_catalog_sources(&self) -> bool {
+        let sources: Vec<CatalogSource> = self
+            .config
+            .settings()
+            .catalog_sources
+            .into_iter()
+            .filter(|source| source.enabled && source.is_remote())
+            .collect();
+        if sources.is_empty() {
+            return false;
+        }
+        let client = match catalog_http_client() {
+            Ok(client) => client,
+            Err(err) => {
+                warn!("Skipping catalog sources: {err:#}");
+                return false;
+            }
+        };
+        let mut updated = false;
+        for source in sources {
+            match self.fetch_catalog_source(&client, &source).await {
+                Ok(()) => updated = true,
+                Err(err) => warn!("Catalog source '{}' not refreshed: {err:#}", source.name),
+            }
+        }
+        updated
+    }
+
+    async fn fetch_catalog_source(&self, client: &Client, source: &CatalogSource) -> Result<()> {
+        let url = source.location.trim();
+        info!("Refreshing catalog source '{}' from {url}", source.name);
+        let response = client
+            .get(url)
+            .send()
+            .await
+            .with_context(|| format!("failed to fetch {url}"))?
+            .error_for_status()
+            .with_context(|| format!("server rejected {url}"))?;
+        let bytes = response
+            .bytes()
+            .await
+            .context("failed to read catalog source body")?;
+        report_catalog_issues(url, &String::from_utf8_lossy(&bytes));
+        serde_json::from_slice::<ModelCatalog>(&bytes)
+            .with_context(|| format!("failed to parse catalog JSON from {url}"))?;
+        let path = self.source_catalog_path(source);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("failed to create {parent:?}"))?;
+        }
+        fs::write(&path, &bytes)
+            .with_context(|| format!("failed to write catalog source to {path:?}"))?;
+        Ok(())
+    }
+
+    async fn refresh_official_catalog(&self) -> Result<bool> {
         let settings = self.config.settings();
         let endpoint = settings
             .catalog_endpoint
@@ -111,15 +251,7 @@ impl CatalogService {
             return Ok(false);
         };
 
-        let client = Client::builder()
-            .user_agent(format!(
-                "ArcticDownloader/{} ({})",
-                env!("CARGO_PKG_VERSION"),
-                env!("CARGO_PKG_NAME")
-            ))
-            .timeout(Duration::from_secs(10))
-            .build()
-            .context("failed to build HTTP client for catalog refresh")?;
+        let client = catalog_http_client()?;
 
         let mut request = client.get(&url);
         if let Some(etag) = settings
@@ -187,13 +319,14 @@ impl CatalogService {
             .bytes()
             .await
             .context("failed to read remote catalog body")?;
+        report_catalog_issues(&url, &String::from_utf8_lossy(&bytes));
         let catalog: ModelCatalog = serde_json::from_slice(&bytes)
             .with_context(|| format!("failed to parse remote catalog JSON from {url}"))?;
 
         self.persist_catalog(&catalog)?;
 
         {
-            let mut guard = self.catalog.write().expect("catalog poisoned for write");
+            let mut guard = self.official.write().expect("catalog poisoned for write");
             *guard = catalog;
         }
 
@@ -205,6 +338,80 @@ impl CatalogService {
         Ok(true)
     }
 
+    /// Validates a catalog file, or the catalog currently in use when `path` is `None`
+    /// (local override, then cache, then the bundled copy). `path` must be one of the
+    /// catalog files the app itself loads, so callers cannot read arbitrary files.
+    pub fn validate_catalog(&self, path: Option<&Path>) -> Result<Vec<CatalogIssue>> {
+        let source = match path {
+            Some(path) => {
+                let not_allowed =
+                    || anyhow!("{path:?} is not a catalog or catalog source file used by the app");
+                let requested = fs::canonicalize(path).map_err(|_| not_allowed())?;
+                let mut known = catalog_candidate_paths();
+                known.push(self.cached_catalog_path());
+                known.extend(
+                    self.config
+                        .settings()
+                        .catalog_sources
+                        .iter()
+                        .map(|source| self.source_catalog_path(source)),
+                );
+                if !known
+                    .iter()
+                    .filter_map(|known| fs::canonicalize(known).ok())
+                    .any(|known| known == requested)
+                {
+                    return Err(not_allowed());
+                }
+                Some(requested)
+            }
+            None => {
+                let mut candidates = catalog_candidate_paths();
+                let cached = self.cached_catalog_path();
+                if prefer_local_catalog() {
+                    candidates.push(cached);
+                } else {
+                    candidates.insert(0, cached);
+                }
+                candidates.into_iter().find(|path| path.exists())
+            }
+        };
+        let contents = match source {
+            Some(path) => fs::read_to_string(&path)
+                .with_context(|| format!("failed to read catalog at {path:?}"))?,
+            None => BUNDLED_CATALOG.to_string(),
+        };
+        Ok(validate_catalog_json(&contents))
+    }
+
+    fn record_changes(&self, previous: &ModelCatalog) {
+        let mut changes = diff_catalogs(previous, &self.catalog_snapshot());
+        if changes.is_empty() {
+            return;
+        }
+        changes.detected_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        info!(
+            "Catalog changed: {} new models, {} new variants, {} new LoRAs, {} new workflows.",
+            changes.added_models.len(),
+            changes.added_variants.len(),
+            changes.added_loras.len(),
+            changes.added_workflows.len()
+        );
+        let path = self.config.cache_path().join(CATALOG_CHANGES_FILE);
+        match serde_json::to_vec_pretty(&changes) {
+            Ok(data) => {
+                if let Err(err) = fs::write(&path, data) {
+                    warn!("Failed to write catalog changes to {:?}: {err}", path);
+                }
+            }
+            Err(err) => warn!("Failed to serialize catalog changes: {err}"),
+        }
+        *self.changes.write().expect("catalog poisoned for write") = Some(changes);
+    }
+
     fn persist_catalog(&self, catalog: &ModelCatalog) -> Result<()> {
         let path = self.cached_catalog_path();
         let data = serde_json::to_vec_pretty(catalog)?;
@@ -216,6 +423,43 @@ impl CatalogService {
     fn cached_catalog_path(&self) -> PathBuf {
         self.config.cache_path().join(CACHED_CATALOG_FILE)
     }
+
+    fn source_catalog_path(&self, source: &CatalogSource) -> PathBuf {
+        if !source.is_remote() {
+            return PathBuf::from(source.location.trim());
+        }
+        let slug: String = source
+            .name
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        // Names like "My Catalog" and "my-catalog" share a slug; the hash of the exact
+        // name keeps their caches apart.
+        let hash = blake3::hash(source.name.trim().as_bytes()).to_hex();
+        self.config
+            .cache_path()
+            .join(SOURCE_CACHE_DIR)
+            .join(format!("{slug}-{}.json", &hash.as_str()[..8]))
+    }
+}
+
+fn catalog_http_client() -> Result<Client> {
+    Client::builder()
+        .user_agent(format!(
+            "ArcticDownloader/{} ({})",
+            env!("CARGO_PKG_VERSION"),
+            env!("CARGO_PKG_NAME")
+        ))
+        .timeout(Duration::from_secs(10))
+        .build()
+        .context("failed to build HTTP client for catalog refresh")
 }
 
 fn resolve_catalog() -> Option<ModelCatalog> {
@@ -230,16 +474,19 @@ fn resolve_catalog() -> Option<ModelCatalog> {
 
 fn load_catalog_from_path(path: &Path) -> Option<ModelCatalog> {
     match fs::read_to_string(path) {
-        Ok(contents) => match serde_json::from_str::<ModelCatalog>(&contents) {
-            Ok(parsed) => {
-                info!("Loaded catalog from {:?}", path);
-                Some(parsed)
-            }
-            Err(err) => {
-                warn!("Failed to parse catalog at {:?}: {err}", path);
-                None
+        Ok(contents) => {
+            report_catalog_issues(&path.display().to_string(), &contents);
+            match serde_json::from_str::<ModelCatalog>(&contents) {
+                Ok(parsed) => {
+                    info!("Loaded catalog from {:?}", path);
+                    Some(parsed)
+                }
+                Err(err) => {
+                    warn!("Failed to parse catalog at {:?}: {err}", path);
+                    None
+                }
             }
-        },
+        }
         Err(err) => {
             warn!("Failed to read catalog at {:?}: {err}", path);
             None
@@ -284,6 +531,18 @@ fn load_cached_catalog(config: &ConfigStore) -> Option<ModelCatalog> {
     }
 }
 
+fn load_catalog_changes(config: &ConfigStore) -> Option<CatalogChanges> {
+    let path = config.cache_path().join(CATALOG_CHANGES_FILE);
+    let data = fs::read(&path).ok()?;
+    match serde_json::from_slice(&data) {
+        Ok(changes) => Some(changes),
+        Err(err) => {
+            warn!("Ignoring unreadable catalog changes at {:?}: {err}", path);
+            None
+        }
+    }
+}
+
 fn cached_catalog_path(config: &ConfigStore) -> PathBuf {
     config.cache_path().join(CACHED_CATALOG_FILE)
 }
@@ -295,3 +554,14 @@ fn cached_catalog_contains_key(path: &Path, key: &str) -> bool {
     let needle = format!("\"{key}\"");
     text.contains(&needle)
 }
+
+fn report_catalog_issues(source: &str, contents: &str) {
+    let issues = validate_catalog_json(contents);
+    if issues.is_empty() {
+        return;
+    }
+    warn!("Catalog at {source} has {} issue(s):", issues.len());
+    for issue in issues {
+        warn!("  {issue}");
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Hardware facts the compatibility gate decides on. Unknown values never block.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MachineProfile {
+    pub ram_gb: Option<f64>,
+    pub vram_gb: Option<f64>,
+    pub compute_capability: Option<(u32, u32)>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatReason {
+    /// The largest file of a download does not fit in RAM and VRAM combined.
+    ModelExceedsMemory,
+    /// The GPU is older than the oldest architecture an add-on's wheels target.
+    ComputeCapabilityTooLow,
+    /// The selected torch profile ships no kernels for the GPU's architecture.
+    TorchProfileUnsupportedGpu,
+}
+
+impl CompatReason {
+    pub const fn code(self) -> &'static str {
+        match self {
+            CompatReason::ModelExceedsMemory => "model_exceeds_memory",
+            CompatReason::ComputeCapabilityTooLow => "compute_capability_too_low",
+            CompatReason::TorchProfileUnsupportedGpu => "torch_profile_unsupported_gpu",
+        }
+    }
+}
+
+/// A choice the gate refuses unless the caller passes an override.
+#[derive(Clone, Debug, Serialize)]
+pub struct CompatBlock {
+    pub reason: CompatReason,
+    pub message: String,
+}
+
+/// Errors carry the reason code in front, e.g. `[model_exceeds_memory] ...`, so the UI
+/// can offer the matching override.
+impl fmt::Display for CompatBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.reason.code(), self.message)
+    }
+}
+
+/// Parses `nvidia-smi`'s `compute_cap` column, e.g. `8.6` or `12.0`.
+pub fn parse_compute_capability(raw: &str) -> Option<(u32, u32)> {
+    let (major, minor) = raw.trim().split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Oldest and newest compute capability a CUDA torch profile ships native kernels for.
+/// cu128 is the first toolkit with Blackwell (sm_120); CUDA 12.8+ builds dropped
+/// Maxwell, Pascal and Volta.
+pub fn torch_profile_arch_range(profile: &str) -> Option<((u32, u32), (u32, u32))> {
+    match profile {
+        "torch271_cu128" | "torch280_cu128" => Some(((7, 5), (12, 0))),
+        "torch291_cu130" | "torch_nightly_cu130" => Some(((7, 5), (12, 1))),
+        _ => None,
+    }
+}
+
+pub fn check_torch_profile(machine: &MachineProfile, profile: &str) -> Option<CompatBlock> {
+    let ((min_major, min_minor), _) = torch_profile_arch_range(profile)?;
+    let (major, minor) = machine.compute_capability?;
+    if (major, minor) >= (min_major, min_minor) {
+        return None;
+    }
+    Some(CompatBlock {
+        reason: CompatReason::TorchProfileUnsupportedGpu,
+        message: format!(
+            "{profile} supports compute capability {min_major}.{min_minor} and newer; this GPU is {major}.{minor} and Torch would fail to run on it."
+        ),
+    })
+}
+
+/// Lowest compute capability the prebuilt wheels of an attention add-on support.
+pub fn min_compute_capability(addon: &str) -> Option<(u32, u32)> {
+    match addon {
+        "flash" | "sage" => Some((8, 0)),
+        "sage3" => Some((10, 0)),
+        "nunchaku" => Some((7, 5)),
+        _ => None,
+    }
+}
+
+pub fn check_addon(machine: &MachineProfile, addon: &str, label: &str) -> Option<CompatBlock> {
+    let (min_major, min_minor) = min_compute_capability(addon)?;
+    let (major, minor) = machine.compute_capability?;
+    if (major, minor) >= (min_major, min_minor) {
+        return None;
+    }
+    Some(CompatBlock {
+        reason: CompatReason::ComputeCapabilityTooLow,
+        message: format!(
+            "{label} needs compute capability {min_major}.{min_minor} or newer; this GPU is {major}.{minor}."
+        ),
+    })
+}
+
+/// ComfyUI can offload weights to system RAM, so a file only cannot load at all when
+/// it is larger than RAM and VRAM together.
+pub fn check_model_size(machine: &MachineProfile, largest_file_bytes: u64) -> Option<CompatBlock> {
+    let ram_gb = machine.ram_gb?;
+    let vram_gb = machine.vram_gb.unwrap_or(0.0);
+    let file_gb = largest_file_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+    if file_gb <= ram_gb + vram_gb {
+        return None;
+    }
+    Some(CompatBlock {
+        reason: CompatReason::ModelExceedsMemory,
+        message: format!(
+            "A {file_gb:.1} GB model file cannot load on {ram_gb:.0} GB RAM + {vram_gb:.0} GB VRAM."
+        ),
+    })
+}
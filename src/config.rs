@@ -1,14 +1,24 @@
-use crate::app::APP_ID;
+use crate::{
+    api_access::ApiToken,
+    app::APP_ID,
+    hooks::HookEvent,
+    model::RamTierThresholds,
+    secrets::{EncryptedSecrets, SecretValues, SecretsKey},
+};
 use anyhow::{anyhow, Context, Result};
 use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
     sync::RwLock,
+    time::Duration,
 };
 
 const SETTINGS_FILE: &str = "settings.json";
+/// Bumped whenever stored settings need rewriting on load; see `migrate_settings`.
+pub const SETTINGS_VERSION: u32 = 1;
 const FALLBACK_REMOTE_CATALOG_URL: &str =
     "https://raw.githubusercontent.com/ArcticLatent/Arctic-Helper/refs/heads/main/assets/catalog.json";
 
@@ -19,6 +29,7 @@ pub struct ConfigStore {
     state_dir: PathBuf,
     cache_dir: PathBuf,
     settings: RwLock<AppSettings>,
+    secrets_key: RwLock<Option<SecretsKey>>,
 }
 
 impl ConfigStore {
@@ -54,6 +65,9 @@ impl ConfigStore {
             settings.catalog_endpoint = default_catalog_endpoint();
             persist_defaults = settings_path.exists();
         }
+        if migrate_settings(&mut settings) {
+            persist_defaults = settings_path.exists();
+        }
 
         let store = Self {
             root_dir,
@@ -61,6 +75,7 @@ impl ConfigStore {
             state_dir,
             cache_dir,
             settings: RwLock::new(settings),
+            secrets_key: RwLock::new(None),
         };
 
         if persist_defaults {
@@ -108,29 +123,144 @@ impl ConfigStore {
         self.root_dir.clone()
     }
 
+    pub fn settings_encrypted(&self) -> bool {
+        self.settings
+            .read()
+            .expect("settings lock poisoned")
+            .encrypted_secrets
+            .is_some()
+    }
+
+    pub fn settings_unlocked(&self) -> bool {
+        !self.settings_encrypted()
+            || self
+                .secrets_key
+                .read()
+                .expect("secrets key lock poisoned")
+                .is_some()
+    }
+
+    /// Decrypts the stored secrets into memory for this session.
+    pub fn unlock_settings(&self, passphrase: &str) -> Result<AppSettings> {
+        let mut guard = self
+            .settings
+            .write()
+            .expect("settings lock poisoned for write");
+        let blob = guard
+            .encrypted_secrets
+            .clone()
+            .ok_or_else(|| anyhow!("settings are not encrypted"))?;
+        let key = SecretsKey::for_blob(passphrase, &blob)?;
+        let values = key.open(&blob)?;
+        guard.apply_secrets(values);
+        *self
+            .secrets_key
+            .write()
+            .expect("secrets key lock poisoned for write") = Some(key);
+        Ok(guard.clone())
+    }
+
+    /// Moves secrets out of the plaintext settings file into a passphrase-sealed blob.
+    pub fn enable_settings_encryption(&self, passphrase: &str) -> Result<AppSettings> {
+        let mut guard = self
+            .settings
+            .write()
+            .expect("settings lock poisoned for write");
+        if guard.encrypted_secrets.is_some() {
+            return Err(anyhow!("settings are already encrypted"));
+        }
+        let key = SecretsKey::generate(passphrase)?;
+        guard.encrypted_secrets = Some(key.seal(&guard.secret_values())?);
+        *self
+            .secrets_key
+            .write()
+            .expect("secrets key lock poisoned for write") = Some(key);
+        let snapshot = guard.clone();
+        self.persist_locked(&snapshot)?;
+        Ok(snapshot)
+    }
+
+    /// Verifies the passphrase and writes secrets back to the settings file in plaintext.
+    pub fn disable_settings_encryption(&self, passphrase: &str) -> Result<AppSettings> {
+        let mut guard = self
+            .settings
+            .write()
+            .expect("settings lock poisoned for write");
+        let blob = guard
+            .encrypted_secrets
+            .take()
+            .ok_or_else(|| anyhow!("settings are not encrypted"))?;
+        let values = match SecretsKey::for_blob(passphrase, &blob).and_then(|key| key.open(&blob)) {
+            Ok(values) => values,
+            Err(err) => {
+                guard.encrypted_secrets = Some(blob);
+                return Err(err);
+            }
+        };
+        guard.apply_secrets(values);
+        *self
+            .secrets_key
+            .write()
+            .expect("secrets key lock poisoned for write") = None;
+        let snapshot = guard.clone();
+        self.persist_locked(&snapshot)?;
+        Ok(snapshot)
+    }
+
     fn persist_locked(&self, settings: &AppSettings) -> Result<()> {
         let path = self.config_path().join(SETTINGS_FILE);
-        let data = serde_json::to_vec_pretty(settings)?;
+        let mut on_disk = settings.clone();
+        if on_disk.encrypted_secrets.is_some() {
+            let secrets = on_disk.take_secret_values();
+            let key = self
+                .secrets_key
+                .read()
+                .expect("secrets key lock poisoned")
+                .clone();
+            match key {
+                Some(key) => on_disk.encrypted_secrets = Some(key.seal(&secrets)?),
+                None if !secrets.is_empty() => {
+                    return Err(anyhow!(
+                        "settings are locked; unlock them with your passphrase before changing tokens or hooks"
+                    ));
+                }
+                None => {}
+            }
+        }
+        let data = serde_json::to_vec_pretty(&on_disk)?;
         fs::write(&path, data).with_context(|| format!("failed to write settings to {path:?}"))?;
         Ok(())
     }
 }
 
+/// Settings shared by every frontend build. Fields older files may lack default on
+/// load, so a config written by any earlier release still parses.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AppSettings {
+    /// Schema version the file was last written with; 0 for files from before versioning.
+    #[serde(default)]
+    pub settings_version: u32,
+    #[serde(default)]
     pub comfyui_root: Option<PathBuf>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub comfyui_install_base: Option<PathBuf>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub comfyui_last_install_dir: Option<PathBuf>,
+    #[serde(default = "default_true")]
     pub prefer_quantized: bool,
+    #[serde(default = "default_concurrent_downloads")]
     pub concurrent_downloads: usize,
+    #[serde(default)]
     pub bandwidth_cap_mbps: Option<u32>,
+    #[serde(default)]
     pub last_catalog_etag: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub catalog_endpoint: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub civitai_token: Option<String>,
+    /// Also save a Civitai LoRA's example images, their parameters and attached workflows.
+    #[serde(default)]
+    pub civitai_download_examples: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_installed_version: Option<String>,
     #[serde(default = "default_true")]
@@ -139,12 +269,317 @@ pub struct AppSettings {
     pub comfyui_attention_backend: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub comfyui_torch_profile: Option<String>,
+    /// Replaces the Triton pin of CUDA torch profiles: an exact version, or `latest`
+    /// for whatever the installed torch declares it needs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comfyui_triton_version: Option<String>,
+    /// `server` when the active install was made with the headless server profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comfyui_install_flavor: Option<String>,
     #[serde(default)]
     pub hf_xet_enabled: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shared_models_root: Option<PathBuf>,
     #[serde(default)]
     pub shared_models_use_default: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_secrets: Option<EncryptedSecrets>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub catalog_sources: Vec<CatalogSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ram_tier_thresholds: Option<RamTierThresholds>,
+    #[serde(default, skip_serializing_if = "HookSettings::is_empty")]
+    pub hooks: HookSettings,
+    #[serde(default, skip_serializing_if = "EventExportSettings::is_disabled")]
+    pub event_export: EventExportSettings,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub favorite_loras: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blacklisted_hosts: Vec<String>,
+    #[serde(default, skip_serializing_if = "RetrySettings::is_default")]
+    pub retry: RetrySettings,
+    #[serde(default, skip_serializing_if = "AutoRestartSettings::is_default")]
+    pub comfyui_auto_restart: AutoRestartSettings,
+    /// `ModelVariant::warnings_fingerprint` values the user has confirmed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub acknowledged_variant_warnings: Vec<String>,
+    /// `<model_id>/<variant_id>` downloads the user chose to keep despite a hardware
+    /// compatibility block.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compat_overrides: Vec<String>,
+    #[serde(default, skip_serializing_if = "PackageIndexSettings::is_default")]
+    pub package_index: PackageIndexSettings,
+    #[serde(default, skip_serializing_if = "ComfySettingsTemplate::is_empty")]
+    pub comfy_settings_template: ComfySettingsTemplate,
+    /// Scoped tokens for the local automation API. See `api_access`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_tokens: Vec<ApiToken>,
+    /// Base URL of the paired headless agent. See `agent`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_agent_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_agent_token: Option<String>,
+    #[serde(default, skip_serializing_if = "LastSelection::is_empty")]
+    pub last_selection: LastSelection,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub followed_creators: Vec<FollowedCreator>,
+}
+
+/// Model tab picks restored when the app reopens. Ids that no longer exist in the
+/// catalog are simply not selected.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LastSelection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vram_tier: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ram_tier: Option<String>,
+}
+
+impl LastSelection {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A Civitai user whose new LoRAs are reported. `last_seen_version_id` stays `None`
+/// until the first poll, which records a baseline instead of flooding the user with
+/// the creator's back catalog.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FollowedCreator {
+    pub username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_version_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_checked_at: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventExportTarget {
+    Journald,
+    Ndjson,
+}
+
+/// Mirrors emitted UI events to an external sink. See `events::EventExporter`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct EventExportSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<EventExportTarget>,
+    /// NDJSON output file; defaults to `state/events.ndjson` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ndjson_path: Option<PathBuf>,
+    /// Byte-level `progress` events are skipped unless this is set.
+    #[serde(default)]
+    pub include_progress: bool,
+}
+
+impl EventExportSettings {
+    pub fn is_disabled(&self) -> bool {
+        self.target.is_none() && self.ndjson_path.is_none() && !self.include_progress
+    }
+}
+
+/// Shell commands run around downloads and installs. See `hooks::run_hook`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct HookSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_download: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_download: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_install: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl HookSettings {
+    pub fn command_for(&self, event: HookEvent) -> Option<&str> {
+        let command = match event {
+            HookEvent::PreDownload => &self.pre_download,
+            HookEvent::PostDownload => &self.post_download,
+            HookEvent::PostInstall => &self.post_install,
+        };
+        command
+            .as_deref()
+            .map(str::trim)
+            .filter(|cmd| !cmd.is_empty())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre_download.is_none()
+            && self.post_download.is_none()
+            && self.post_install.is_none()
+            && self.timeout_secs.is_none()
+    }
+}
+
+/// How persistently one class of flaky operation is retried. The delay before retry `n`
+/// is `initial_delay_secs * 2^(n-1)`, capped at `max_delay_secs`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    #[serde(default)]
+    pub initial_delay_secs: u64,
+    #[serde(default)]
+    pub max_delay_secs: u64,
+}
+
+impl RetryPolicy {
+    pub const fn new(attempts: u32, initial_delay_secs: u64, max_delay_secs: u64) -> Self {
+        Self {
+            attempts,
+            initial_delay_secs,
+            max_delay_secs,
+        }
+    }
+
+    /// A single attempt, for local operations where retrying cannot help.
+    pub const fn once() -> Self {
+        Self::new(1, 0, 0)
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts.max(1)
+    }
+
+    /// Delay after failed attempt `attempt` (1-based) before the next one.
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        let secs = self.initial_delay_secs.saturating_mul(factor);
+        Duration::from_secs(secs.min(self.max_delay_secs.max(self.initial_delay_secs)))
+    }
+}
+
+/// Retry policies for installer subprocesses, grouped by what tends to fail the same way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RetrySettings {
+    /// Plain HTTP downloads (curl/wget) of installers and wheels.
+    #[serde(default = "RetrySettings::default_network")]
+    pub network: RetryPolicy,
+    /// git clone/fetch/merge against remotes.
+    #[serde(default = "RetrySettings::default_git")]
+    pub git: RetryPolicy,
+    /// uv pip installs, which mostly fail on index timeouts.
+    #[serde(default = "RetrySettings::default_pip")]
+    pub pip: RetryPolicy,
+}
+
+impl RetrySettings {
+    fn default_network() -> RetryPolicy {
+        RetryPolicy::new(4, 2, 30)
+    }
+
+    fn default_git() -> RetryPolicy {
+        RetryPolicy::new(2, 2, 30)
+    }
+
+    fn default_pip() -> RetryPolicy {
+        RetryPolicy::new(1, 5, 60)
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            network: Self::default_network(),
+            git: Self::default_git(),
+            pip: Self::default_pip(),
+        }
+    }
+}
+
+/// Restarting a ComfyUI the app launched when it exits on its own. `policy.attempts`
+/// is how many restarts are tried in a row before giving up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AutoRestartSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "AutoRestartSettings::default_policy")]
+    pub policy: RetryPolicy,
+}
+
+impl AutoRestartSettings {
+    fn default_policy() -> RetryPolicy {
+        RetryPolicy::new(3, 5, 60)
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for AutoRestartSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            policy: Self::default_policy(),
+        }
+    }
+}
+
+/// Mirrors used instead of pypi.org and download.pytorch.org by the ComfyUI installer.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PackageIndexSettings {
+    /// Replaces PyPI as the default index for every uv install.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pypi_index_url: Option<String>,
+    /// Replaces `https://download.pytorch.org/whl`; the profile's channel (`cu128`,
+    /// `rocm6.4`, `cpu`, ...) is appended to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub torch_index_base: Option<String>,
+}
+
+impl PackageIndexSettings {
+    pub fn is_default(&self) -> bool {
+        self.pypi_index_url.is_none() && self.torch_index_base.is_none()
+    }
+}
+
+/// ComfyUI frontend settings merged into `user/default/comfy.settings.json`. Keys are
+/// ComfyUI setting ids as stored in that file, e.g. `Comfy.ColorPalette` or `Comfy.Locale`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct ComfySettingsTemplate {
+    /// Seed every new install right after it finishes.
+    #[serde(default)]
+    pub apply_after_install: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub values: BTreeMap<String, serde_json::Value>,
+}
+
+impl ComfySettingsTemplate {
+    pub fn is_empty(&self) -> bool {
+        !self.apply_after_install && self.values.is_empty()
+    }
+}
+
+/// An extra catalog merged with the official one. `location` is either an `http(s)` URL or
+/// a local file path. The official catalog has priority 0: sources above it override its
+/// entries, sources below it only add entries it does not define.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogSource {
+    pub name: String,
+    pub location: String,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl CatalogSource {
+    pub fn is_remote(&self) -> bool {
+        let location = self.location.trim().to_ascii_lowercase();
+        location.starts_with("https://") || location.starts_with("http://")
+    }
 }
 
 impl AppSettings {
@@ -153,27 +588,77 @@ impl AppSettings {
             .as_deref()
             .filter(|path| path.join("models").is_dir())
     }
+
+    fn secret_values(&self) -> SecretValues {
+        SecretValues {
+            civitai_token: self.civitai_token.clone(),
+            remote_agent_token: self.remote_agent_token.clone(),
+            pre_download_hook: self.hooks.pre_download.clone(),
+            post_download_hook: self.hooks.post_download.clone(),
+            post_install_hook: self.hooks.post_install.clone(),
+        }
+    }
+
+    fn take_secret_values(&mut self) -> SecretValues {
+        SecretValues {
+            civitai_token: self.civitai_token.take(),
+            remote_agent_token: self.remote_agent_token.take(),
+            pre_download_hook: self.hooks.pre_download.take(),
+            post_download_hook: self.hooks.post_download.take(),
+            post_install_hook: self.hooks.post_install.take(),
+        }
+    }
+
+    fn apply_secrets(&mut self, values: SecretValues) {
+        self.civitai_token = values.civitai_token;
+        self.remote_agent_token = values.remote_agent_token;
+        self.hooks.pre_download = values.pre_download_hook;
+        self.hooks.post_download = values.post_download_hook;
+        self.hooks.post_install = values.post_install_hook;
+    }
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            settings_version: SETTINGS_VERSION,
             comfyui_root: None,
             comfyui_install_base: None,
             comfyui_last_install_dir: None,
             prefer_quantized: true,
-            concurrent_downloads: 2,
+            concurrent_downloads: default_concurrent_downloads(),
             bandwidth_cap_mbps: None,
             last_catalog_etag: None,
             catalog_endpoint: default_catalog_endpoint(),
             civitai_token: None,
+            civitai_download_examples: false,
             last_installed_version: None,
             comfyui_pinned_memory_enabled: true,
             comfyui_attention_backend: None,
             comfyui_torch_profile: None,
+            comfyui_triton_version: None,
+            comfyui_install_flavor: None,
             hf_xet_enabled: false,
             shared_models_root: None,
             shared_models_use_default: false,
+            encrypted_secrets: None,
+            catalog_sources: Vec::new(),
+            ram_tier_thresholds: None,
+            hooks: HookSettings::default(),
+            event_export: EventExportSettings::default(),
+            favorite_loras: Vec::new(),
+            blacklisted_hosts: Vec::new(),
+            retry: RetrySettings::default(),
+            comfyui_auto_restart: AutoRestartSettings::default(),
+            acknowledged_variant_warnings: Vec::new(),
+            compat_overrides: Vec::new(),
+            package_index: PackageIndexSettings::default(),
+            comfy_settings_template: ComfySettingsTemplate::default(),
+            api_tokens: Vec::new(),
+            remote_agent_url: None,
+            remote_agent_token: None,
+            last_selection: LastSelection::default(),
+            followed_creators: Vec::new(),
         }
     }
 }
@@ -185,3 +670,46 @@ pub(crate) fn default_catalog_endpoint() -> Option<String> {
 fn default_true() -> bool {
     true
 }
+
+fn default_concurrent_downloads() -> usize {
+    2
+}
+
+/// Brings settings written by older builds, including the retired GTK frontend that
+/// shared this file, up to the current schema. Returns whether anything changed.
+fn migrate_settings(settings: &mut AppSettings) -> bool {
+    if settings.settings_version >= SETTINGS_VERSION {
+        return false;
+    }
+    if let Some(root) = settings.comfyui_root.take() {
+        let (root, base) = reconcile_comfyui_root(root);
+        if settings.comfyui_install_base.is_none() {
+            settings.comfyui_install_base = base;
+        }
+        settings.comfyui_root = Some(root);
+    }
+    if settings.concurrent_downloads == 0 {
+        settings.concurrent_downloads = default_concurrent_downloads();
+    }
+    settings.settings_version = SETTINGS_VERSION;
+    true
+}
+
+/// Older builds stored whichever folder was picked: the ComfyUI checkout, its `models`
+/// folder, or the base folder holding `ComfyUI`. Everything now expects the checkout;
+/// a base folder is handed back so it can become the install base.
+fn reconcile_comfyui_root(root: PathBuf) -> (PathBuf, Option<PathBuf>) {
+    if root.join("main.py").is_file() {
+        return (root, None);
+    }
+    if root.file_name().is_some_and(|name| name == "models") {
+        if let Some(checkout) = root.parent().filter(|dir| dir.join("main.py").is_file()) {
+            return (checkout.to_path_buf(), None);
+        }
+    }
+    let nested = root.join("ComfyUI");
+    if nested.join("main.py").is_file() {
+        return (nested, Some(root));
+    }
+    (root, None)
+}
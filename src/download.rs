@@ -1,13 +1,19 @@
 use crate::{
     config::ConfigStore,
-    model::{LoraDefinition, ModelArtifact, ResolvedModel, TargetCategory, WorkflowDefinition},
+    faults,
+    host_stats::{is_blacklisted, url_host, HostStatsTracker, HOST_STATS_FILE},
+    model::{
+        AssetClass, AssetDefinition, LoraDefinition, MasterModel, ModelArtifact, ResolvedModel,
+        TargetCategory, WorkflowDefinition,
+    },
+    removable,
 };
 use anyhow::{anyhow, Context, Result};
 use futures::{StreamExt, TryStreamExt};
 use log::{info, warn};
 use percent_encoding::percent_decode_str;
 use reqwest::{header, Client, Url};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::{
@@ -19,7 +25,7 @@ use std::{
         mpsc::Sender,
         Arc, OnceLock,
     },
-    time::Instant,
+    time::{Duration, Instant, SystemTime},
 };
 use thiserror::Error;
 use tokio::{
@@ -42,6 +48,18 @@ const ADAPTIVE_STEP_BYTES: u64 = 5 * 1024 * 1024;
 const ADAPTIVE_GROW_MBPS: f64 = 50.0;
 const ADAPTIVE_SHRINK_MBPS: f64 = 5.0;
 
+const PREVIEW_CACHE_DIR: &str = "lora-previews";
+const PREVIEW_CACHE_MAX_BYTES: u64 = 512 * 1024 * 1024;
+const PREVIEW_CACHE_MAX_ITEM_BYTES: u64 = 64 * 1024 * 1024;
+const PREVIEW_CACHE_TTL: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// Civitai answers 429 once metadata calls arrive faster than about one every few
+/// hundred milliseconds, which scrolling the LoRA list easily does.
+const CIVITAI_BURST: f64 = 3.0;
+const CIVITAI_REFILL_PER_SEC: f64 = 2.0;
+const CIVITAI_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(5);
+const CIVITAI_RATE_LIMIT_RETRIES: usize = 2;
+
 static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
 static HF_CLI_AVAILABLE: OnceLock<bool> = OnceLock::new();
 static HF_BIN_AVAILABLE: OnceLock<bool> = OnceLock::new();
@@ -59,6 +77,8 @@ pub struct LoraDownloadOutcome {
     pub lora: LoraDefinition,
     pub destination: PathBuf,
     pub status: DownloadStatus,
+    /// Example images, parameter sidecars and workflows written next to the LoRA.
+    pub examples_saved: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -68,6 +88,25 @@ pub struct WorkflowDownloadOutcome {
     pub status: DownloadStatus,
 }
 
+/// One artifact of a variant download as it would be fetched right now.
+#[derive(Clone, Debug, Serialize)]
+pub struct PlannedArtifact {
+    pub file_name: String,
+    pub category: String,
+    pub destination: PathBuf,
+    /// Catalog size, or the size reported by the host when the catalog has none.
+    pub size_bytes: Option<u64>,
+    pub exists: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct AssetDownloadOutcome {
+    pub class: AssetClass,
+    pub asset: AssetDefinition,
+    pub destination: PathBuf,
+    pub status: DownloadStatus,
+}
+
 #[derive(Clone, Debug)]
 pub struct CivitaiModelMetadata {
     pub file_name: String,
@@ -81,12 +120,177 @@ pub struct CivitaiModelMetadata {
     pub creator_link: Option<String>,
 }
 
+/// One LoRA version from a Civitai creator's feed, newest first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CivitaiCreatorRelease {
+    pub creator: String,
+    pub model_id: u64,
+    pub model_name: String,
+    pub version_id: u64,
+    pub version_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_model: Option<String>,
+    pub download_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_url: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub enum CivitaiPreview {
     Image(Vec<u8>),
     Video { url: String },
 }
 
+/// Preview media for a Civitai model version in the on-disk cache.
+#[derive(Clone, Debug)]
+pub struct CachedPreview {
+    pub mime_type: &'static str,
+    pub path: PathBuf,
+}
+
+/// On-disk cache of LoRA preview images and videos. Entries are named
+/// `<model version>-<url hash>.<ext>`, expire after a TTL and are evicted oldest-first
+/// once the directory grows past its size cap.
+#[derive(Debug)]
+pub struct PreviewCache {
+    dir: PathBuf,
+}
+
+impl PreviewCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn entry_path(&self, model_version_id: u64, url: &str) -> PathBuf {
+        let hash = blake3::hash(url.as_bytes()).to_hex();
+        let ext = preview_extension(url);
+        self.dir
+            .join(format!("{model_version_id}-{}.{ext}", &hash.as_str()[..16]))
+    }
+
+    fn is_fresh(path: &Path) -> bool {
+        std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(|age| age < PREVIEW_CACHE_TTL)
+            .unwrap_or(false)
+    }
+
+    pub fn get(&self, model_version_id: u64, url: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(model_version_id, url);
+        if !Self::is_fresh(&path) {
+            return None;
+        }
+        std::fs::read(&path).ok()
+    }
+
+    /// The fresh entry for `url`, without reading it.
+    pub fn entry(&self, model_version_id: u64, url: &str) -> Option<CachedPreview> {
+        let path = self.entry_path(model_version_id, url);
+        Self::is_fresh(&path).then(|| CachedPreview {
+            mime_type: preview_mime_type(&preview_extension(url)),
+            path,
+        })
+    }
+
+    /// Newest fresh entry for a model version, whatever URL it came from.
+    pub fn latest(&self, model_version_id: u64) -> Option<CachedPreview> {
+        let prefix = format!("{model_version_id}-");
+        let path = std::fs::read_dir(&self.dir)
+            .ok()?
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path())
+            .filter(|path| Self::is_fresh(path))
+            .max_by_key(|path| {
+                std::fs::metadata(path)
+                    .and_then(|meta| meta.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH)
+            })?;
+        let ext = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some(CachedPreview {
+            mime_type: preview_mime_type(&ext),
+            path,
+        })
+    }
+
+    pub fn put(&self, model_version_id: u64, url: &str, bytes: &[u8]) {
+        if bytes.is_empty() || bytes.len() as u64 > PREVIEW_CACHE_MAX_ITEM_BYTES {
+            return;
+        }
+        if let Err(err) = std::fs::create_dir_all(&self.dir) {
+            warn!("Failed to create preview cache {:?}: {err}", self.dir);
+            return;
+        }
+        let path = self.entry_path(model_version_id, url);
+        if let Err(err) = std::fs::write(&path, bytes) {
+            warn!("Failed to write preview cache entry {:?}: {err}", path);
+            return;
+        }
+        self.prune();
+    }
+
+    fn prune(&self) {
+        let Ok(read_dir) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut entries: Vec<(PathBuf, u64, SystemTime)> = Vec::new();
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            if !Self::is_fresh(&path) {
+                let _ = std::fs::remove_file(&path);
+                continue;
+            }
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((path, meta.len(), modified));
+        }
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, size, _) in entries {
+            if total <= PREVIEW_CACHE_MAX_BYTES {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                total = total.saturating_sub(size);
+            }
+        }
+    }
+}
+
+fn preview_extension(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .filter(|ext| !ext.is_empty() && ext.len() <= 5 && !ext.contains('/'))
+        .unwrap_or_else(|| "img".to_string())
+}
+
+fn preview_mime_type(ext: &str) -> &'static str {
+    match ext {
+        "png" => "image/png",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        _ => "image/jpeg",
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DownloadStatus {
     Downloaded,
@@ -123,8 +327,123 @@ pub enum DownloadSignal {
 pub enum DownloadError {
     #[error("unauthorized")]
     Unauthorized,
+    #[error("{} is already being downloaded by another process", .0.display())]
+    AlreadyDownloading(PathBuf),
+    #[error("{} was unmounted while downloading; reconnect the drive and retry", .0.display())]
+    MediaRemoved(PathBuf),
+    #[error("download cancelled by user")]
+    Cancelled,
 }
 
+/// Token bucket in front of the Civitai metadata API. Waiters are served in arrival
+/// order so [`CivitaiRateLimiter::position`] can tell the UI where a request stands.
+#[derive(Debug)]
+struct CivitaiRateLimiter {
+    state: std::sync::Mutex<RateLimiterState>,
+}
+
+#[derive(Debug)]
+struct RateLimiterState {
+    tokens: f64,
+    refilled_at: Instant,
+    /// Set after a 429; nothing is sent before then.
+    blocked_until: Option<Instant>,
+    queue: VecDeque<u64>,
+}
+
+/// Drops a waiter from the queue even if its task is aborted mid-wait.
+struct QueueSlot<'a> {
+    limiter: &'a CivitaiRateLimiter,
+    model_version_id: u64,
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.limiter.state.lock() {
+            if let Some(index) = state
+                .queue
+                .iter()
+                .position(|id| *id == self.model_version_id)
+            {
+                state.queue.remove(index);
+            }
+        }
+    }
+}
+
+impl CivitaiRateLimiter {
+    fn new() -> Self {
+        Self {
+            state: std::sync::Mutex::new(RateLimiterState {
+                tokens: CIVITAI_BURST,
+                refilled_at: Instant::now(),
+                blocked_until: None,
+                queue: VecDeque::new(),
+            }),
+        }
+    }
+
+    async fn acquire(&self, model_version_id: u64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.queue.push_back(model_version_id);
+        }
+        let _slot = QueueSlot {
+            limiter: self,
+            model_version_id,
+        };
+        loop {
+            let wait = {
+                let Ok(mut state) = self.state.lock() else {
+                    return;
+                };
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * CIVITAI_REFILL_PER_SEC).min(CIVITAI_BURST);
+                state.refilled_at = now;
+                let blocked = state.blocked_until.filter(|until| *until > now);
+                let first = state.queue.front() == Some(&model_version_id);
+                match blocked {
+                    Some(until) => until - now,
+                    None if first && state.tokens >= 1.0 => {
+                        state.tokens -= 1.0;
+                        return;
+                    }
+                    None => Duration::from_secs_f64(
+                        ((1.0 - state.tokens).max(0.0) / CIVITAI_REFILL_PER_SEC).max(0.05),
+                    ),
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Called on a 429: empties the bucket and holds every caller for the backoff.
+    fn back_off(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.tokens = 0.0;
+            state.blocked_until = Some(Instant::now() + CIVITAI_RATE_LIMIT_BACKOFF);
+        }
+    }
+
+    /// 1-based place in line, or `None` once the request is sent (or was never queued).
+    fn position(&self, model_version_id: u64) -> Option<usize> {
+        let state = self.state.lock().ok()?;
+        state
+            .queue
+            .iter()
+            .position(|id| *id == model_version_id)
+            .map(|index| index + 1)
+    }
+}
+
+fn is_rate_limited(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status)
+        .is_some_and(|status| status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+}
+
+type InflightMetadata = std::sync::Mutex<HashMap<u64, Arc<Mutex<()>>>>;
+
 #[derive(Debug)]
 pub struct DownloadManager {
     runtime: Arc<Runtime>,
@@ -133,12 +452,27 @@ pub struct DownloadManager {
     download_clients: Vec<Client>,
     civitai_metadata_cache: Arc<Mutex<HashMap<u64, CivitaiModelMetadata>>>,
     civitai_metadata_order: Arc<Mutex<VecDeque<u64>>>,
+    civitai_limiter: Arc<CivitaiRateLimiter>,
+    /// One lock per model version being fetched, so repeat selections wait for the first
+    /// request instead of sending their own.
+    civitai_inflight: Arc<InflightMetadata>,
+    preview_cache: Arc<PreviewCache>,
+    host_stats: Arc<HostStatsTracker>,
 }
 
 impl DownloadManager {
     pub fn new(runtime: Arc<Runtime>, config: Arc<ConfigStore>) -> Self {
         let api_client = make_http_client();
         let download_clients = make_download_clients();
+        let preview_cache = Arc::new(PreviewCache::new(
+            config.cache_path().join(PREVIEW_CACHE_DIR),
+        ));
+        let host_stats = Arc::new(HostStatsTracker::load(
+            config
+                .state_path()
+                .unwrap_or_else(|| config.cache_path())
+                .join(HOST_STATS_FILE),
+        ));
 
         Self {
             runtime,
@@ -147,9 +481,17 @@ impl DownloadManager {
             download_clients,
             civitai_metadata_cache: Arc::new(Mutex::new(HashMap::new())),
             civitai_metadata_order: Arc::new(Mutex::new(VecDeque::new())),
+            civitai_limiter: Arc::new(CivitaiRateLimiter::new()),
+            civitai_inflight: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            preview_cache,
+            host_stats,
         }
     }
 
+    pub fn host_stats(&self) -> Arc<HostStatsTracker> {
+        Arc::clone(&self.host_stats)
+    }
+
     pub fn download_variant(
         &self,
         comfy_root: PathBuf,
@@ -167,59 +509,67 @@ impl DownloadManager {
         cancel: Option<CancellationToken>,
     ) -> tokio::task::JoinHandle<Result<Vec<DownloadOutcome>>> {
         let download_clients = self.download_clients.clone();
-        let xet_enabled = self.config.settings().hf_xet_enabled;
+        let settings = self.config.settings();
+        let xet_enabled = settings.hf_xet_enabled;
+        let blacklist = Arc::new(settings.blacklisted_hosts);
+        let host_stats = Arc::clone(&self.host_stats);
         self.runtime.spawn(async move {
+            let _flush = HostStatsFlush(Arc::clone(&host_stats));
             let mut outcomes = Vec::new();
-            let model_folder = resolved.master.id.clone();
+            let master = Arc::new(resolved.master);
             let artifacts = dedupe_artifacts(resolved.variant.artifacts);
             let total = artifacts.len();
 
-            let mut stream = futures::stream::iter(
-                artifacts
-                    .into_iter()
-                    .enumerate()
-                    .map(|(index, artifact)| {
-                        let download_clients = download_clients.clone();
-                        let comfy_root = comfy_root.clone();
-                        let model_folder = model_folder.clone();
-                        let progress = progress.clone();
-                        let cancel = cancel.clone();
-                        async move {
-                            if is_cancelled(cancel.as_ref()) {
-                                return Err(anyhow!("download cancelled by user"));
-                            }
-                            let artifact_name = artifact.file_name().to_string();
-                            let _ = progress.send(DownloadSignal::Started {
-                                artifact: artifact_name.clone(),
-                                index,
-                                total,
-                                size: artifact.size_bytes,
-                            });
-
-                            info!("Starting download: {}", artifact.file_name());
-                            match download_artifact(
-                                &download_clients,
-                                &comfy_root,
-                                &model_folder,
-                                &artifact,
-                                Some((progress.clone(), index, artifact_name.clone())),
-                                xet_enabled,
-                                cancel.as_ref(),
-                            )
-                            .await
-                            {
-                                Ok(outcome) => Ok(outcome),
-                                Err(err) => {
-                                    let _ = progress.send(DownloadSignal::Failed {
-                                        artifact: artifact_name,
-                                        error: err.to_string(),
-                                    });
-                                    Err(err)
-                                }
+            let mut stream = futures::stream::iter(artifacts.into_iter().enumerate().map(
+                |(index, artifact)| {
+                    let download_clients = download_clients.clone();
+                    let comfy_root = comfy_root.clone();
+                    let master = Arc::clone(&master);
+                    let progress = progress.clone();
+                    let cancel = cancel.clone();
+                    let host_stats = Arc::clone(&host_stats);
+                    let blacklist = Arc::clone(&blacklist);
+                    async move {
+                        if is_cancelled(cancel.as_ref()) {
+                            return Err(DownloadError::Cancelled.into());
+                        }
+                        let artifact_name = artifact.file_name().to_string();
+                        let _ = progress.send(DownloadSignal::Started {
+                            artifact: artifact_name.clone(),
+                            index,
+                            total,
+                            size: artifact.size_bytes,
+                        });
+
+                        info!("Starting download: {}", artifact.file_name());
+                        let ctx = TransferContext {
+                            clients: &download_clients,
+                            xet_enabled,
+                            host_stats: &host_stats,
+                            blacklist: &blacklist,
+                            cancel: cancel.as_ref(),
+                        };
+                        match download_artifact(
+                            ctx,
+                            &comfy_root,
+                            &master,
+                            &artifact,
+                            Some((progress.clone(), index, artifact_name.clone())),
+                        )
+                        .await
+                        {
+                            Ok(outcome) => Ok(outcome),
+                            Err(err) => {
+                                let _ = progress.send(DownloadSignal::Failed {
+                                    artifact: artifact_name,
+                                    error: err.to_string(),
+                                });
+                                Err(err)
                             }
                         }
-                    }),
-            )
+                    }
+                },
+            ))
             .buffer_unordered(1);
 
             while let Some(result) = stream.next().await {
@@ -241,6 +591,44 @@ impl DownloadManager {
         })
     }
 
+    /// Resolves destinations and sizes for the artifacts `download_variant` would fetch,
+    /// probing hosts for sizes the catalog does not list. Nothing is downloaded.
+    pub fn plan_variant_download(
+        &self,
+        comfy_root: PathBuf,
+        resolved: ResolvedModel,
+    ) -> tokio::task::JoinHandle<Vec<PlannedArtifact>> {
+        let client = self.api_client.clone();
+        self.runtime.spawn(async move {
+            let master = resolved.master;
+            let artifacts = dedupe_artifacts(resolved.variant.artifacts);
+            futures::future::join_all(artifacts.into_iter().map(|artifact| {
+                let client = client.clone();
+                let destination = artifact_destination_dir(&comfy_root, &master, &artifact)
+                    .join(artifact.file_name());
+                async move {
+                    let exists = destination.is_file();
+                    let size_bytes = if exists {
+                        std::fs::metadata(&destination).ok().map(|meta| meta.len())
+                    } else {
+                        match artifact.size_bytes {
+                            Some(size) => Some(size),
+                            None => probe_artifact_size(&client, &artifact).await,
+                        }
+                    };
+                    PlannedArtifact {
+                        file_name: artifact.file_name().to_string(),
+                        category: artifact.target_category.slug().to_string(),
+                        destination,
+                        size_bytes,
+                        exists,
+                    }
+                }
+            }))
+            .await
+        })
+    }
+
     pub fn download_lora(
         &self,
         comfy_root: PathBuf,
@@ -261,19 +649,18 @@ impl DownloadManager {
     ) -> tokio::task::JoinHandle<Result<LoraDownloadOutcome>> {
         let download_clients = self.download_clients.clone();
         let api_client = self.api_client.clone();
-        let xet_enabled = self.config.settings().hf_xet_enabled;
+        let settings = self.config.settings();
+        let xet_enabled = settings.hf_xet_enabled;
+        let fetch_examples = settings.civitai_download_examples;
+        let blacklist = settings.blacklisted_hosts;
+        let host_stats = Arc::clone(&self.host_stats);
+        let limiter = Arc::clone(&self.civitai_limiter);
         self.runtime.spawn(async move {
+            let _flush = HostStatsFlush(Arc::clone(&host_stats));
             if is_cancelled(cancel.as_ref()) {
-                return Err(anyhow!("download cancelled by user"));
+                return Err(DownloadError::Cancelled.into());
             }
-            let folder_name = lora
-                .family
-                .as_deref()
-                .map(normalize_folder_name)
-                .filter(|name| !name.is_empty())
-                .unwrap_or_else(|| sanitize_file_name(&lora.id));
-            let loras_root = comfy_root.join(TargetCategory::from_slug("loras").comfyui_subdir());
-            let lora_dir = loras_root.join(&folder_name);
+            let lora_dir = lora_destination_dir(&comfy_root, &lora);
 
             let base_url = lora.download_url.clone();
             let token_value = token.clone().and_then(|t| {
@@ -307,6 +694,7 @@ impl DownloadManager {
             file_name = sanitize_file_name(&file_name);
 
             let dest_path = lora_dir.join(&file_name);
+            let fetch_examples = fetch_examples && base_url.contains("civitai.com");
 
             if fs::try_exists(&dest_path)
                 .await
@@ -326,10 +714,25 @@ impl DownloadManager {
                         .parent()
                         .map(|p| p.to_string_lossy().to_string()),
                 });
+                let examples_saved = if fetch_examples {
+                    save_civitai_examples(
+                        &api_client,
+                        &limiter,
+                        &base_url,
+                        &lora_dir,
+                        &file_name,
+                        token_value.as_deref(),
+                        cancel.as_ref(),
+                    )
+                    .await
+                } else {
+                    0
+                };
                 return Ok(LoraDownloadOutcome {
                     lora,
                     destination: dest_path,
                     status: DownloadStatus::SkippedExisting,
+                    examples_saved,
                 });
             }
 
@@ -355,23 +758,45 @@ impl DownloadManager {
                 size: None,
             });
 
-            match download_direct(
-                &download_clients,
+            let ctx = TransferContext {
+                clients: &download_clients,
+                xet_enabled,
+                host_stats: &host_stats,
+                blacklist: &blacklist,
+                cancel: cancel.as_ref(),
+            };
+            match download_direct_tracked(
+                ctx,
                 &url,
                 &lora_dir,
                 &file_name,
                 Some((progress.clone(), 0, file_name.clone())),
                 auth_token.as_deref(),
-                xet_enabled,
-                cancel.as_ref(),
             )
             .await
             {
-                Ok(destination) => Ok(LoraDownloadOutcome {
-                    lora,
-                    destination,
-                    status: DownloadStatus::Downloaded,
-                }),
+                Ok(destination) => {
+                    let examples_saved = if fetch_examples {
+                        save_civitai_examples(
+                            &api_client,
+                            &limiter,
+                            &base_url,
+                            &lora_dir,
+                            &file_name,
+                            token_value.as_deref(),
+                            cancel.as_ref(),
+                        )
+                        .await
+                    } else {
+                        0
+                    };
+                    Ok(LoraDownloadOutcome {
+                        lora,
+                        destination,
+                        status: DownloadStatus::Downloaded,
+                        examples_saved,
+                    })
+                }
                 Err(err) => {
                     if matches!(
                         err.downcast_ref::<DownloadError>(),
@@ -414,26 +839,61 @@ impl DownloadManager {
         let client = self.api_client.clone();
         let cache = Arc::clone(&self.civitai_metadata_cache);
         let order = Arc::clone(&self.civitai_metadata_order);
+        let preview_cache = Arc::clone(&self.preview_cache);
+        let limiter = Arc::clone(&self.civitai_limiter);
+        let inflight = Arc::clone(&self.civitai_inflight);
         self.runtime.spawn(async move {
             let model_version_id = extract_civitai_model_version_id(&download_url)
                 .ok_or_else(|| anyhow!("unable to parse model version ID from {download_url}"))?;
 
+            let flight = inflight
+                .lock()
+                .map(|mut map| Arc::clone(map.entry(model_version_id).or_default()))
+                .unwrap_or_default();
+            let coalesced = flight.try_lock().is_err();
+            let _flight_guard = flight.lock().await;
+            let release_flight = || {
+                if let Ok(mut map) = inflight.lock() {
+                    // The map and this task hold the only references when nobody waits.
+                    if Arc::strong_count(&flight) <= 2 {
+                        map.remove(&model_version_id);
+                    }
+                }
+            };
+
             if let Some(cached) = {
                 let cache_guard = cache.lock().await;
                 cache_guard.get(&model_version_id).cloned()
             } {
-                if cached.usage_strength.is_some() {
+                // Whatever the request we waited on fetched is as fresh as it gets.
+                if coalesced || cached.usage_strength.is_some() {
+                    release_flight();
                     return Ok(cached);
                 }
             }
 
-            let metadata = fetch_civitai_model_metadata_internal(
-                &client,
-                model_version_id,
-                &download_url,
-                token.as_deref(),
-            )
-            .await?;
+            let mut attempt = 0;
+            let metadata = loop {
+                limiter.acquire(model_version_id).await;
+                match fetch_civitai_model_metadata_internal(
+                    &client,
+                    model_version_id,
+                    &download_url,
+                    token.as_deref(),
+                    Some(&preview_cache),
+                )
+                .await
+                {
+                    Err(err) if is_rate_limited(&err) && attempt < CIVITAI_RATE_LIMIT_RETRIES => {
+                        warn!("Civitai rate limited metadata for {model_version_id}; backing off");
+                        limiter.back_off();
+                        attempt += 1;
+                    }
+                    result => break result,
+                }
+            };
+            release_flight();
+            let metadata = metadata?;
 
             {
                 let mut cache_guard = cache.lock().await;
@@ -457,6 +917,46 @@ impl DownloadManager {
         })
     }
 
+    /// Where a pending metadata request for `download_url` stands in the rate limiter's
+    /// queue, so the UI can say "queued" rather than look stuck.
+    pub fn civitai_metadata_queue_position(&self, download_url: &str) -> Option<usize> {
+        let model_version_id = extract_civitai_model_version_id(download_url)?;
+        self.civitai_limiter.position(model_version_id)
+    }
+
+    /// Cached preview media for a Civitai LoRA, if one was fetched recently.
+    pub fn cached_lora_preview(&self, download_url: &str) -> Option<CachedPreview> {
+        let model_version_id = extract_civitai_model_version_id(download_url)?;
+        self.preview_cache.latest(model_version_id)
+    }
+
+    /// Preview media at `preview_url` for a Civitai LoRA, fetched into the on-disk
+    /// cache unless it is already there. `None` means it is over the cache's per-item
+    /// cap or could not be fetched, and is best streamed from the URL instead.
+    pub fn lora_preview_media(
+        &self,
+        download_url: String,
+        preview_url: String,
+        token: Option<String>,
+    ) -> tokio::task::JoinHandle<Result<Option<CachedPreview>>> {
+        let client = self.api_client.clone();
+        let preview_cache = Arc::clone(&self.preview_cache);
+        self.runtime.spawn(async move {
+            let model_version_id = extract_civitai_model_version_id(&download_url)
+                .ok_or_else(|| anyhow!("unable to parse model version ID from {download_url}"))?;
+            if let Some(cached) = preview_cache.entry(model_version_id, &preview_url) {
+                return Ok(Some(cached));
+            }
+            let Some(bytes) =
+                fetch_preview_media_capped(&client, &preview_url, token.as_deref()).await
+            else {
+                return Ok(None);
+            };
+            preview_cache.put(model_version_id, &preview_url, &bytes);
+            Ok(preview_cache.entry(model_version_id, &preview_url))
+        })
+    }
+
     pub fn civitai_preview_image(
         &self,
         image_url: String,
@@ -470,6 +970,31 @@ impl DownloadManager {
         })
     }
 
+    /// Latest LoRA versions published by a Civitai user. Feed requests share the
+    /// metadata rate limiter so a poll never starves the LoRA tab of its quota.
+    pub fn civitai_creator_releases(
+        &self,
+        username: String,
+        token: Option<String>,
+    ) -> tokio::task::JoinHandle<Result<Vec<CivitaiCreatorRelease>>> {
+        let client = self.api_client.clone();
+        let limiter = Arc::clone(&self.civitai_limiter);
+        self.runtime.spawn(async move {
+            let mut attempt = 0;
+            loop {
+                limiter.acquire(0).await;
+                match fetch_civitai_creator_releases(&client, &username, token.as_deref()).await {
+                    Err(err) if is_rate_limited(&err) && attempt < CIVITAI_RATE_LIMIT_RETRIES => {
+                        warn!("Civitai rate limited the feed for {username}; backing off");
+                        limiter.back_off();
+                        attempt += 1;
+                    }
+                    result => break result,
+                }
+            }
+        })
+    }
+
     pub fn download_workflow_with_cancel(
         &self,
         workflows_dir: PathBuf,
@@ -478,9 +1003,12 @@ impl DownloadManager {
         cancel: Option<CancellationToken>,
     ) -> tokio::task::JoinHandle<Result<WorkflowDownloadOutcome>> {
         let download_clients = self.download_clients.clone();
+        let blacklist = self.config.settings().blacklisted_hosts;
+        let host_stats = Arc::clone(&self.host_stats);
         self.runtime.spawn(async move {
+            let _flush = HostStatsFlush(Arc::clone(&host_stats));
             if is_cancelled(cancel.as_ref()) {
-                return Err(anyhow!("download cancelled by user"));
+                return Err(DownloadError::Cancelled.into());
             }
             let url = workflow.workflow_json_url.trim().to_string();
             if url.is_empty() {
@@ -536,15 +1064,20 @@ impl DownloadManager {
                 size: None,
             });
 
-            let destination = download_direct(
-                &download_clients,
+            let ctx = TransferContext {
+                clients: &download_clients,
+                xet_enabled: false,
+                host_stats: &host_stats,
+                blacklist: &blacklist,
+                cancel: cancel.as_ref(),
+            };
+            let destination = download_direct_tracked(
+                ctx,
                 &url,
                 &workflows_dir,
                 &file_name,
                 Some((progress.clone(), 0, file_name.clone())),
                 None,
-                false,
-                cancel.as_ref(),
             )
             .await?;
 
@@ -555,6 +1088,143 @@ impl DownloadManager {
             })
         })
     }
+
+    pub fn download_asset_with_cancel(
+        &self,
+        comfy_root: PathBuf,
+        class: AssetClass,
+        asset: AssetDefinition,
+        progress: Sender<DownloadSignal>,
+        cancel: Option<CancellationToken>,
+    ) -> tokio::task::JoinHandle<Result<AssetDownloadOutcome>> {
+        let download_clients = self.download_clients.clone();
+        let settings = self.config.settings();
+        let xet_enabled = settings.hf_xet_enabled;
+        let blacklist = settings.blacklisted_hosts;
+        let host_stats = Arc::clone(&self.host_stats);
+        self.runtime.spawn(async move {
+            let _flush = HostStatsFlush(Arc::clone(&host_stats));
+            if is_cancelled(cancel.as_ref()) {
+                return Err(DownloadError::Cancelled.into());
+            }
+            let url = asset.download_url.trim().to_string();
+            if url.is_empty() {
+                return Err(anyhow!(
+                    "{} {} is missing download_url",
+                    class.identifier(),
+                    asset.id
+                ));
+            }
+            let file_name = sanitize_file_name(&asset.derived_file_name());
+            let dest_dir = asset_destination_dir(&comfy_root, class);
+            let dest_path = dest_dir.join(&file_name);
+
+            if fs::try_exists(&dest_path)
+                .await
+                .with_context(|| format!("failed to check {:?} existence", dest_path))?
+            {
+                let _ = progress.send(DownloadSignal::Started {
+                    artifact: file_name.clone(),
+                    index: 0,
+                    total: 1,
+                    size: Some(0),
+                });
+                let _ = progress.send(DownloadSignal::Finished {
+                    artifact: file_name.clone(),
+                    index: 0,
+                    size: Some(0),
+                    folder: Some(dest_dir.to_string_lossy().to_string()),
+                });
+                return Ok(AssetDownloadOutcome {
+                    class,
+                    asset,
+                    destination: dest_path,
+                    status: DownloadStatus::SkippedExisting,
+                });
+            }
+            fs::create_dir_all(&dest_dir)
+                .await
+                .with_context(|| format!("failed to create directory {:?}", dest_dir))?;
+
+            let _ = progress.send(DownloadSignal::Started {
+                artifact: file_name.clone(),
+                index: 0,
+                total: 1,
+                size: asset.size_bytes,
+            });
+
+            let ctx = TransferContext {
+                clients: &download_clients,
+                xet_enabled,
+                host_stats: &host_stats,
+                blacklist: &blacklist,
+                cancel: cancel.as_ref(),
+            };
+            let result = download_direct_tracked(
+                ctx,
+                &url,
+                &dest_dir,
+                &file_name,
+                Some((progress.clone(), 0, file_name.clone())),
+                None,
+            )
+            .await;
+            let result = match (result, asset.sha256.as_deref()) {
+                (Ok(destination), Some(expected)) => verify_file_sha256(&destination, expected)
+                    .await
+                    .map(|_| destination),
+                (result, _) => result,
+            };
+            match result {
+                Ok(destination) => Ok(AssetDownloadOutcome {
+                    class,
+                    asset,
+                    destination,
+                    status: DownloadStatus::Downloaded,
+                }),
+                Err(err) => {
+                    let _ = progress.send(DownloadSignal::Failed {
+                        artifact: file_name,
+                        error: err.to_string(),
+                    });
+                    Err(err)
+                }
+            }
+        })
+    }
+}
+
+/// Folder a catalog artifact is stored in: `<root>/models/<category>/<model id>` for image
+/// and video families, `<root>/models/<category>` for audio and 3D families.
+pub fn artifact_destination_dir(
+    comfy_root: &Path,
+    master: &MasterModel,
+    artifact: &ModelArtifact,
+) -> PathBuf {
+    comfy_root.join(master.artifact_subdir(artifact))
+}
+
+/// Folder a LoRA is stored in: `<root>/models/loras/<normalized family or id>`.
+pub fn lora_destination_dir(comfy_root: &Path, lora: &LoraDefinition) -> PathBuf {
+    let folder_name = lora
+        .family
+        .as_deref()
+        .map(normalize_folder_name)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| sanitize_file_name(&lora.id));
+    comfy_root
+        .join(TargetCategory::from_slug("loras").comfyui_subdir())
+        .join(folder_name)
+}
+
+/// Folder a standalone asset is stored in, e.g. `<root>/models/upscale_models`.
+pub fn asset_destination_dir(comfy_root: &Path, class: AssetClass) -> PathBuf {
+    comfy_root.join(class.comfyui_subdir())
+}
+
+/// Full path of a LoRA file, sanitizing the name the same way downloads do.
+pub fn lora_destination(comfy_root: &Path, lora: &LoraDefinition, file_name: &str) -> PathBuf {
+    lora_destination_dir(comfy_root, lora).join(sanitize_file_name(file_name))
 }
 
 fn make_http_client() -> Client {
@@ -604,26 +1274,34 @@ fn make_download_clients() -> Vec<Client> {
     clients
 }
 
+/// What every transfer of one download job shares: the HTTP clients, the Xet switch,
+/// host statistics with the user's blacklist, and the job's cancellation token.
+#[derive(Clone, Copy)]
+struct TransferContext<'a> {
+    clients: &'a [Client],
+    xet_enabled: bool,
+    host_stats: &'a HostStatsTracker,
+    blacklist: &'a [String],
+    cancel: Option<&'a CancellationToken>,
+}
+
 async fn download_artifact(
-    clients: &[Client],
+    ctx: TransferContext<'_>,
     comfy_root: &Path,
-    model_folder: &str,
+    master: &MasterModel,
     artifact: &ModelArtifact,
     progress: Option<(Sender<DownloadSignal>, usize, String)>,
-    xet_enabled: bool,
-    cancel: Option<&CancellationToken>,
 ) -> Result<DownloadOutcome> {
-    if is_cancelled(cancel) {
-        return Err(anyhow!("download cancelled by user"));
+    if is_cancelled(ctx.cancel) {
+        return Err(DownloadError::Cancelled.into());
     }
-    let subdir = artifact.target_category.comfyui_subdir();
-    let dest_dir = comfy_root.join(subdir).join(model_folder);
+    let dest_dir = artifact_destination_dir(comfy_root, master, artifact);
     fs::create_dir_all(&dest_dir)
         .await
         .with_context(|| format!("failed to create directory {:?}", dest_dir))?;
 
     let initial_file_name = artifact.file_name().to_string();
-    let mut dest_path = dest_dir.join(&initial_file_name);
+    let dest_path = dest_dir.join(&initial_file_name);
 
     if fs::try_exists(&dest_path)
         .await
@@ -646,11 +1324,105 @@ async fn download_artifact(
         });
     }
 
-    let url = if let Some(direct) = &artifact.direct_url {
-        ensure_hf_download_url(direct)
-    } else {
-        build_download_url(&artifact.repo, &artifact.path)?
-    };
+    let removable_mount = removable::removable_mount(&dest_dir);
+    let candidates = artifact_candidate_urls(artifact)?;
+    let total_candidates = candidates.len();
+    let candidates = ctx.host_stats.order_candidates(candidates, ctx.blacklist);
+    if candidates.is_empty() {
+        return Err(anyhow!(
+            "all download hosts for {} are blacklisted",
+            initial_file_name
+        ));
+    }
+    if candidates.len() < total_candidates {
+        info!(
+            "Skipping {} blacklisted host(s) for {}",
+            total_candidates - candidates.len(),
+            initial_file_name
+        );
+    }
+
+    let mut last_err = None;
+    for (attempt, url) in candidates.iter().enumerate() {
+        if attempt > 0 {
+            warn!("Retrying {} from alternate URL {}", initial_file_name, url);
+        }
+        let started = Instant::now();
+        match download_artifact_from_url(
+            ctx,
+            artifact,
+            &dest_dir,
+            &initial_file_name,
+            url,
+            progress.clone(),
+        )
+        .await
+        {
+            Ok(outcome) => {
+                if outcome.status == DownloadStatus::Downloaded {
+                    record_host_success(ctx.host_stats, url, &outcome.destination, started);
+                }
+                return Ok(outcome);
+            }
+            Err(err) => {
+                let err = media_removed_or(removable_mount.as_deref(), err);
+                if !record_host_failure(ctx.host_stats, url, &err, ctx.cancel) {
+                    return Err(err);
+                }
+                last_err = Some(err);
+            }
+        }
+    }
+    Err(last_err.unwrap_or_else(|| anyhow!("no download URL for {initial_file_name}")))
+}
+
+async fn probe_artifact_size(client: &Client, artifact: &ModelArtifact) -> Option<u64> {
+    for url in artifact_candidate_urls(artifact).ok()? {
+        if let Ok(Some(metadata)) =
+            fetch_head_metadata(client, &url, None, artifact.file_name()).await
+        {
+            if metadata.content_length.is_some() {
+                return metadata.content_length;
+            }
+        }
+    }
+    None
+}
+
+/// Direct URL first, then the URL derived from the artifact's repo, without duplicates.
+fn artifact_candidate_urls(artifact: &ModelArtifact) -> Result<Vec<String>> {
+    let mut urls = Vec::new();
+    if let Some(direct) = &artifact.direct_url {
+        urls.push(ensure_hf_download_url(direct));
+    }
+    if !artifact.repo.trim().is_empty() {
+        match build_download_url(&artifact.repo, &artifact.path) {
+            Ok(url) if !urls.contains(&url) => urls.push(url),
+            Ok(_) => {}
+            Err(err) if urls.is_empty() => return Err(err),
+            Err(err) => warn!("Ignoring repo URL for {}: {err}", artifact.file_name()),
+        }
+    }
+    Ok(urls)
+}
+
+async fn download_artifact_from_url(
+    ctx: TransferContext<'_>,
+    artifact: &ModelArtifact,
+    dest_dir: &Path,
+    initial_file_name: &str,
+    url: &str,
+    progress: Option<(Sender<DownloadSignal>, usize, String)>,
+) -> Result<DownloadOutcome> {
+    let TransferContext {
+        clients,
+        xet_enabled,
+        cancel,
+        ..
+    } = ctx;
+    let url = url.to_string();
+    let initial_file_name = initial_file_name.to_string();
+    let mut dest_path = dest_dir.join(&initial_file_name);
     log::info!("Requesting {}", url);
 
     let mut xet_size_hint = artifact.size_bytes;
@@ -674,14 +1446,8 @@ async fn download_artifact(
             parsed.file_path
         );
         if xet_enabled && cli_available {
-            match download_via_hf_cli(
-                &parsed,
-                &dest_dir,
-                progress.clone(),
-                xet_size_hint,
-                cancel,
-            )
-            .await
+            match download_via_hf_cli(&parsed, dest_dir, progress.clone(), xet_size_hint, cancel)
+                .await
             {
                 Ok(dest_path) => {
                     if let Some((sender, index, artifact_name)) = progress {
@@ -761,7 +1527,7 @@ async fn download_artifact(
                 let dest_path = download_ranged_to_file(
                     clients,
                     &url,
-                    &dest_dir,
+                    dest_dir,
                     &final_file_name,
                     total_size,
                     progress.clone(),
@@ -791,6 +1557,9 @@ async fn download_artifact(
         }
     }
 
+    faults::before_request()
+        .await
+        .with_context(|| format!("request failed for {url}"))?;
     let response = client
         .get(url.clone())
         .send()
@@ -806,8 +1575,6 @@ async fn download_artifact(
     if final_file_name == initial_file_name {
         final_file_name = filename_from_headers(response.headers(), &initial_file_name);
     }
-    if accept_ranges {
-    }
     if final_file_name != initial_file_name {
         dest_path = dest_dir.join(&final_file_name);
         if fs::try_exists(&dest_path)
@@ -832,7 +1599,23 @@ async fn download_artifact(
         }
     }
 
-    let tmp_path = unique_tmp_path(&dest_dir, &final_file_name);
+    let Some(_dest_lock) = DestinationLock::acquire_unless_exists(dest_dir, &final_file_name)?
+    else {
+        if let Some((sender, index, artifact_name)) = progress.as_ref() {
+            let _ = sender.send(DownloadSignal::Finished {
+                artifact: artifact_name.clone(),
+                index: *index,
+                size: Some(0),
+                folder: Some(dest_dir.to_string_lossy().to_string()),
+            });
+        }
+        return Ok(DownloadOutcome {
+            artifact: artifact.clone(),
+            destination: dest_path,
+            status: DownloadStatus::SkippedExisting,
+        });
+    };
+    let tmp_path = unique_tmp_path(dest_dir, &final_file_name);
     let file = fs::File::create(&tmp_path)
         .await
         .with_context(|| format!("failed to create temporary file {:?}", tmp_path))?;
@@ -846,7 +1629,7 @@ async fn download_artifact(
 
     let stream = response
         .bytes_stream()
-        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
+        .map_err(std::io::Error::other);
     let mut reader = StreamReader::new(stream);
     let mut hasher = artifact.sha256.as_ref().map(|_| Sha256::new());
     let mut received: u64 = 0;
@@ -857,7 +1640,7 @@ async fn download_artifact(
     loop {
         if is_cancelled(cancel) {
             fs::remove_file(&tmp_path).await.ok();
-            return Err(anyhow!("download cancelled by user"));
+            return Err(DownloadError::Cancelled.into());
         }
         let n = match timeout(std::time::Duration::from_millis(500), reader.read(&mut buffer)).await {
             Ok(Ok(n)) => n,
@@ -870,6 +1653,7 @@ async fn download_artifact(
         file.write_all(&buffer[..n])
             .await
             .with_context(|| format!("failed writing to {:?}", tmp_path))?;
+        faults::throttle(n).await;
         received += n as u64;
         if let Some(hasher) = hasher.as_mut() {
             hasher.update(&buffer[..n]);
@@ -929,6 +1713,7 @@ async fn download_artifact(
             format!("failed to move {:?} to {:?}", tmp_path, dest_path)
         });
     }
+    sync_if_removable(&dest_path).await?;
 
     log::info!("Finished download: {:?}", dest_path);
 
@@ -950,6 +1735,154 @@ async fn download_artifact(
     })
 }
 
+/// Hashes a finished download and removes it when it does not match `expected`.
+async fn verify_file_sha256(path: &Path, expected: &str) -> Result<()> {
+    let mut file = fs::File::open(path)
+        .await
+        .with_context(|| format!("failed to open {:?} for verification", path))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; IO_BUFFER_MAX];
+    loop {
+        let n = file
+            .read(&mut buffer)
+            .await
+            .with_context(|| format!("failed reading {:?}", path))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    let actual = format!("{:x}", hasher.finalize());
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        fs::remove_file(path).await.ok();
+        return Err(anyhow!(
+            "checksum mismatch for {:?} (expected {}, got {})",
+            path,
+            expected,
+            actual
+        ));
+    }
+    Ok(())
+}
+
+/// `download_direct` for a single URL with no alternates: refuses blacklisted hosts
+/// and records the result in the per-host statistics.
+async fn download_direct_tracked(
+    ctx: TransferContext<'_>,
+    url: &str,
+    dest_dir: &Path,
+    file_name: &str,
+    progress: Option<(Sender<DownloadSignal>, usize, String)>,
+    auth_token: Option<&str>,
+) -> Result<PathBuf> {
+    let TransferContext {
+        clients,
+        xet_enabled,
+        host_stats,
+        blacklist,
+        cancel,
+    } = ctx;
+    if let Some(host) = url_host(url) {
+        if is_blacklisted(&host, blacklist) {
+            return Err(anyhow!(
+                "download host {host} is blacklisted; remove it from the blacklist to download {file_name}"
+            ));
+        }
+    }
+    let removable_mount = removable::removable_mount(dest_dir);
+    let started = Instant::now();
+    let result = download_direct(
+        clients,
+        url,
+        dest_dir,
+        file_name,
+        progress,
+        auth_token,
+        xet_enabled,
+        cancel,
+    )
+    .await
+    .map_err(|err| media_removed_or(removable_mount.as_deref(), err));
+    match &result {
+        Ok(path) => record_host_success(host_stats, url, path, started),
+        Err(err) => {
+            record_host_failure(host_stats, url, err, cancel);
+        }
+    }
+    result
+}
+
+fn record_host_success(host_stats: &HostStatsTracker, url: &str, path: &Path, started: Instant) {
+    let bytes = std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    host_stats.record_success(url, bytes, started.elapsed());
+}
+
+/// Writes out host statistics still pending from a job's transfers once the job ends,
+/// however it ends.
+struct HostStatsFlush(Arc<HostStatsTracker>);
+
+impl Drop for HostStatsFlush {
+    fn drop(&mut self) {
+        self.0.flush();
+    }
+}
+
+/// Records a failed transfer against the URL's host. Cancellations, rejected
+/// credentials, unplugged drives and another process holding the destination say
+/// nothing about the host and are not counted; returns whether the failure was
+/// recorded (and so is worth retrying elsewhere).
+fn record_host_failure(
+    host_stats: &HostStatsTracker,
+    url: &str,
+    err: &anyhow::Error,
+    cancel: Option<&CancellationToken>,
+) -> bool {
+    if is_cancelled(cancel)
+        || matches!(
+            err.downcast_ref::<DownloadError>(),
+            Some(
+                DownloadError::Unauthorized
+                    | DownloadError::MediaRemoved(_)
+                    | DownloadError::AlreadyDownloading(_)
+                    | DownloadError::Cancelled
+            )
+        )
+    {
+        return false;
+    }
+    host_stats.record_failure(url, &format!("{err:#}"));
+    true
+}
+
+/// Replaces whatever I/O error surfaced with `MediaRemoved` when the removable drive
+/// the download was writing to has disappeared from the mount table.
+fn media_removed_or(removable_mount: Option<&Path>, err: anyhow::Error) -> anyhow::Error {
+    match removable_mount {
+        Some(mount) if !removable::is_mounted(mount) => {
+            DownloadError::MediaRemoved(mount.to_path_buf()).into()
+        }
+        _ => err,
+    }
+}
+
+/// On removable media a rename is not durable until the data and directory entry are
+/// written back, so finished files are synced before they are reported complete.
+async fn sync_if_removable(dest_path: &Path) -> Result<()> {
+    let Some(dest_dir) = dest_path.parent() else {
+        return Ok(());
+    };
+    let Some(mount) = removable::removable_mount(dest_dir) else {
+        return Ok(());
+    };
+    let path = dest_path.to_path_buf();
+    tokio::task::spawn_blocking(move || removable::sync_file_and_parent(&path))
+        .await
+        .context("sync task failed")?
+        .map_err(|err| media_removed_or(Some(&mount), err.into()))
+        .with_context(|| format!("failed to sync {:?} to removable media", dest_path))
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn download_direct(
     clients: &[Client],
     url: &str,
@@ -961,7 +1894,7 @@ async fn download_direct(
     cancel: Option<&CancellationToken>,
 ) -> Result<PathBuf> {
     if is_cancelled(cancel) {
-        return Err(anyhow!("download cancelled by user"));
+        return Err(DownloadError::Cancelled.into());
     }
     let url = ensure_hf_download_url(url);
 
@@ -1093,6 +2026,9 @@ async fn download_direct(
         request = request.header("Authorization", format!("Bearer {}", token));
     }
 
+    faults::before_request()
+        .await
+        .with_context(|| format!("request failed for {url}"))?;
     let response = request
         .send()
         .await
@@ -1118,8 +2054,6 @@ async fn download_direct(
     if final_file_name == file_name {
         final_file_name = filename_from_headers(response.headers(), file_name);
     }
-    if accept_ranges {
-    }
 
     let dest_path = dest_dir.join(&final_file_name);
     if fs::try_exists(&dest_path).await.unwrap_or(false) {
@@ -1139,6 +2073,18 @@ async fn download_direct(
     if content_length.is_none() {
         content_length = response.content_length();
     }
+    let Some(_dest_lock) = DestinationLock::acquire_unless_exists(dest_dir, &final_file_name)?
+    else {
+        if let Some((sender, index, artifact_name)) = progress {
+            let _ = sender.send(DownloadSignal::Finished {
+                artifact: artifact_name,
+                index,
+                size: Some(0),
+                folder: Some(dest_dir.to_string_lossy().to_string()),
+            });
+        }
+        return Ok(dest_path);
+    };
     let tmp_path = unique_tmp_path(dest_dir, &final_file_name);
     let file = fs::File::create(&tmp_path)
         .await
@@ -1147,7 +2093,7 @@ async fn download_direct(
 
     let stream = response
         .bytes_stream()
-        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
+        .map_err(std::io::Error::other);
     let mut reader = StreamReader::new(stream);
     let mut received: u64 = 0;
     let mut buffer = vec![0u8; IO_BUFFER_INITIAL];
@@ -1158,7 +2104,7 @@ async fn download_direct(
     loop {
         if is_cancelled(cancel) {
             fs::remove_file(&tmp_path).await.ok();
-            return Err(anyhow!("download cancelled by user"));
+            return Err(DownloadError::Cancelled.into());
         }
         let n = reader
             .read(&mut buffer)
@@ -1175,6 +2121,7 @@ async fn download_direct(
             let take = std::cmp::min(remaining, n);
             sniff.extend_from_slice(&buffer[..take]);
         }
+        faults::throttle(n).await;
         received += n as u64;
         bytes_since += n as u64;
         adapt_buffer_size(&mut buffer, &mut bytes_since, &mut last_adjust);
@@ -1229,6 +2176,7 @@ async fn download_direct(
             format!("failed to move {:?} to {:?}", tmp_path, dest_path)
         });
     }
+    sync_if_removable(&dest_path).await?;
 
     if let Some((sender, index, artifact_name)) = progress {
         let _ = sender.send(DownloadSignal::Finished {
@@ -1380,6 +2328,7 @@ fn is_cancelled(cancel: Option<&CancellationToken>) -> bool {
     cancel.map(|token| token.is_cancelled()).unwrap_or(false)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn download_ranged_to_file(
     clients: &[Client],
     url: &str,
@@ -1392,7 +2341,7 @@ async fn download_ranged_to_file(
     cancel: Option<&CancellationToken>,
 ) -> Result<PathBuf> {
     if is_cancelled(cancel) {
-        return Err(anyhow!("download cancelled by user"));
+        return Err(DownloadError::Cancelled.into());
     }
     fs::create_dir_all(dest_dir)
         .await
@@ -1403,6 +2352,10 @@ async fn download_ranged_to_file(
         return Ok(dest_path);
     }
 
+    let Some(_dest_lock) = DestinationLock::acquire_unless_exists(dest_dir, final_file_name)?
+    else {
+        return Ok(dest_path);
+    };
     let tmp_path = unique_tmp_path(dest_dir, final_file_name);
     let file = fs::File::create(&tmp_path)
         .await
@@ -1424,7 +2377,6 @@ async fn download_ranged_to_file(
     let semaphore = Arc::new(Semaphore::new(CHUNK_CONCURRENCY));
     let received = Arc::new(AtomicU64::new(0));
     let artifact_name = progress.as_ref().map(|(_, _, name)| name.clone());
-    let total_size = total_size;
 
     let client_count = clients.len();
     if client_count == 0 {
@@ -1443,7 +2395,7 @@ async fn download_ranged_to_file(
         let cancel = cancel.cloned();
         async move {
             if is_cancelled(cancel.as_ref()) {
-                return Err(anyhow!("download cancelled by user"));
+                return Err(DownloadError::Cancelled.into());
             }
             let _permit = semaphore.acquire().await?;
             let mut request = client
@@ -1453,6 +2405,9 @@ async fn download_ranged_to_file(
                 request = request.header("Authorization", format!("Bearer {}", token));
             }
 
+            faults::before_request()
+                .await
+                .with_context(|| format!("request failed for {url}"))?;
             let response = request
                 .send()
                 .await
@@ -1474,7 +2429,7 @@ async fn download_ranged_to_file(
                 .with_context(|| format!("failed to seek in {:?}", tmp_path))?;
             let stream = response
                 .bytes_stream()
-                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
+                .map_err(std::io::Error::other);
             let mut reader = StreamReader::new(stream);
             let mut buffer = vec![0u8; IO_BUFFER_INITIAL];
             let mut bytes_since = 0u64;
@@ -1482,7 +2437,7 @@ async fn download_ranged_to_file(
 
             loop {
                 if is_cancelled(cancel.as_ref()) {
-                    return Err(anyhow!("download cancelled by user"));
+                    return Err(DownloadError::Cancelled.into());
                 }
                 let n = match timeout(
                     std::time::Duration::from_millis(500),
@@ -1502,6 +2457,7 @@ async fn download_ranged_to_file(
                 file.write_all(&buffer[..n])
                     .await
                     .with_context(|| format!("failed writing to {:?}", tmp_path))?;
+                faults::throttle(n).await;
                 let new_total =
                     received.fetch_add(n as u64, Ordering::Relaxed) + n as u64;
                 bytes_since += n as u64;
@@ -1570,6 +2526,7 @@ async fn download_ranged_to_file(
             format!("failed to move {:?} to {:?}", tmp_path, dest_path)
         });
     }
+    sync_if_removable(&dest_path).await?;
 
     Ok(dest_path)
 }
@@ -1607,7 +2564,7 @@ fn extract_civitai_model_version_id(url: &str) -> Option<u64> {
     if let Some(pos) = lower.find("modelversionid=") {
         let remainder = &url[pos + "modelversionid=".len()..];
         let id_str = remainder
-            .split(|c| c == '&' || c == '#' || c == '/')
+            .split(['&', '#', '/'])
             .next()
             .unwrap_or_default();
         if let Ok(id) = id_str.parse() {
@@ -1618,7 +2575,7 @@ fn extract_civitai_model_version_id(url: &str) -> Option<u64> {
     if let Some(pos) = lower.find("/model-versions/") {
         let remainder = &url[pos + "/model-versions/".len()..];
         let id_str = remainder
-            .split(|c| c == '?' || c == '/' || c == '&')
+            .split(['?', '/', '&'])
             .next()
             .unwrap_or_default();
         if let Ok(id) = id_str.parse() {
@@ -1629,7 +2586,7 @@ fn extract_civitai_model_version_id(url: &str) -> Option<u64> {
     if let Some(pos) = lower.find("/models/") {
         let remainder = &url[pos + "/models/".len()..];
         let id_str = remainder
-            .split(|c| c == '?' || c == '/' || c == '&')
+            .split(['?', '/', '&'])
             .next()
             .unwrap_or_default();
         if let Ok(id) = id_str.parse() {
@@ -1674,6 +2631,69 @@ fn unique_tmp_path(dest_dir: &Path, final_file_name: &str) -> PathBuf {
     dest_dir.join(format!("{final_file_name}.part.{suffix}"))
 }
 
+/// Exclusive `flock` on `<file>.part.lock`, held while a destination is being written.
+/// Temp files are unique per download, so this is what stops two running copies of the
+/// app (a desktop window and a headless agent, say) streaming the same file side by side.
+struct DestinationLock {
+    path: PathBuf,
+    _file: std::fs::File,
+}
+
+impl DestinationLock {
+    /// Locks the destination, or returns `None` when the file already exists once the
+    /// lock is held: another process may have finished it after the caller last looked.
+    fn acquire_unless_exists(dest_dir: &Path, final_file_name: &str) -> Result<Option<Self>> {
+        let lock = Self::acquire(dest_dir, final_file_name)?;
+        if dest_dir.join(final_file_name).exists() {
+            return Ok(None);
+        }
+        Ok(Some(lock))
+    }
+
+    fn acquire(dest_dir: &Path, final_file_name: &str) -> Result<Self> {
+        use std::os::unix::{fs::MetadataExt, io::AsRawFd};
+
+        let path = dest_dir.join(format!("{final_file_name}.part.lock"));
+        loop {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)
+                .with_context(|| format!("failed to open lock file {:?}", path))?;
+            // SAFETY: the descriptor stays open for as long as `file` lives.
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+                let err = std::io::Error::last_os_error();
+                if err.raw_os_error() == Some(libc::EWOULDBLOCK) {
+                    return Err(
+                        DownloadError::AlreadyDownloading(dest_dir.join(final_file_name)).into(),
+                    );
+                }
+                return Err(anyhow!(err).context(format!("failed to lock {:?}", path)));
+            }
+
+            // The previous holder unlinks the lock file on release; if that happened between
+            // our open and flock we hold a lock nobody else can see, so start over.
+            let held = file
+                .metadata()
+                .with_context(|| format!("failed to stat lock file {:?}", path))?;
+            match std::fs::metadata(&path) {
+                Ok(current) if current.dev() == held.dev() && current.ino() == held.ino() => {
+                    return Ok(Self { path, _file: file });
+                }
+                _ => continue,
+            }
+        }
+    }
+}
+
+impl Drop for DestinationLock {
+    fn drop(&mut self) {
+        // Unlink before the descriptor closes so waiters re-check against a fresh file.
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
 fn adapt_buffer_size(buffer: &mut Vec<u8>, bytes_since: &mut u64, last_adjust: &mut Instant) {
     if *bytes_since < ADAPTIVE_STEP_BYTES {
         return;
@@ -1831,6 +2851,11 @@ async fn download_via_hf_cli(
         return Ok(flat_existing);
     }
 
+    // Held until the staged file is moved into place, like the HTTP paths' lock.
+    let Some(_dest_lock) = DestinationLock::acquire_unless_exists(dest_dir, &parsed.file_name)?
+    else {
+        return Ok(flat_existing);
+    };
     let stage_id = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
     let stage_dir = staging_root.join(format!("job-{stage_id}"));
     if let Some(parent) = Path::new(&parsed.file_path).parent() {
@@ -1892,7 +2917,7 @@ async fn download_via_hf_cli(
                 let _ = child.kill().await;
                 let _ = child.wait().await;
                 cleanup_xet_local_sidecars(dest_dir, &staging_root).await;
-                return Err(anyhow!("download cancelled by user"));
+                return Err(DownloadError::Cancelled.into());
             }
         }
 
@@ -2188,7 +3213,7 @@ async fn fetch_civitai_model_metadata(
 ) -> Result<CivitaiModelMetadata> {
     let model_version_id = extract_civitai_model_version_id(download_url)
         .ok_or_else(|| anyhow!("unable to parse model version ID from {download_url}"))?;
-    fetch_civitai_model_metadata_internal(client, model_version_id, download_url, token).await
+    fetch_civitai_model_metadata_internal(client, model_version_id, download_url, token, None).await
 }
 
 async fn fetch_civitai_model_metadata_internal(
@@ -2196,6 +3221,7 @@ async fn fetch_civitai_model_metadata_internal(
     model_version_id: u64,
     download_url: &str,
     token: Option<&str>,
+    preview_cache: Option<&PreviewCache>,
 ) -> Result<CivitaiModelMetadata> {
     let api_url = format!("https://civitai.com/api/v1/model-versions/{model_version_id}");
 
@@ -2248,7 +3274,7 @@ async fn fetch_civitai_model_metadata_internal(
         .or(api_download_url.clone());
 
     let (preview, preview_url) =
-        resolve_preview(client, &images, token, model_version_id).await;
+        resolve_preview(client, &images, token, model_version_id, preview_cache).await;
 
     let mut description = select_richest_description(description, model_description);
     let mut usage_strength = extract_usage_strength(settings.as_ref(), meta.as_ref(), &images);
@@ -2363,7 +3389,7 @@ fn select_civitai_file<'a>(
             file.download_url
                 .as_deref()
                 .and_then(|candidate| Url::parse(candidate).ok())
-                .map_or(false, |candidate| urls_equivalent(&candidate, &reference))
+                .is_some_and(|candidate| urls_equivalent(&candidate, &reference))
         }) {
             return Some(matched);
         }
@@ -2403,6 +3429,7 @@ async fn resolve_preview(
     images: &[CivitaiImage],
     token: Option<&str>,
     model_version_id: u64,
+    preview_cache: Option<&PreviewCache>,
 ) -> (Option<CivitaiPreview>, Option<String>) {
     let mut first_image: Option<&str> = None;
     let mut first_video: Option<&str> = None;
@@ -2424,7 +3451,17 @@ async fn resolve_preview(
 
     if let Some(image_url) = first_image {
         let preview_url = Some(image_url.to_string());
-        let bytes = fetch_preview_image_bytes(client, image_url, token).await;
+        let cached = preview_cache.and_then(|cache| cache.get(model_version_id, image_url));
+        let bytes = match cached {
+            Some(bytes) => Some(bytes),
+            None => {
+                let bytes = fetch_preview_image_bytes(client, image_url, token).await;
+                if let (Some(cache), Some(bytes)) = (preview_cache, bytes.as_ref()) {
+                    cache.put(model_version_id, image_url, bytes);
+                }
+                bytes
+            }
+        };
         let preview = bytes.map(CivitaiPreview::Image);
         if preview.is_none() {
             warn!("Failed to download image bytes for model version {model_version_id}");
@@ -2432,6 +3469,7 @@ async fn resolve_preview(
         return (preview, preview_url);
     }
 
+    // Videos are only fetched once the preview is shown; see `lora_preview_media`.
     if let Some(video_url) = first_video {
         return (
             Some(CivitaiPreview::Video {
@@ -2444,11 +3482,333 @@ async fn resolve_preview(
     (None, None)
 }
 
+/// Folder holding a LoRA's showcase media, e.g. `loras/foo_examples/` for `foo.safetensors`.
+pub fn lora_examples_dir(lora_dir: &Path, lora_file_name: &str) -> PathBuf {
+    let stem = Path::new(lora_file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| lora_file_name.to_string());
+    lora_dir.join(format!("{stem}_examples"))
+}
+
+/// Civitai serves resized copies (`/width=450/`) without the PNG-info chunk that holds
+/// the generation parameters; `original=true` returns the upload unchanged.
+fn original_civitai_image_url(url: &str) -> String {
+    url.split('/')
+        .map(|segment| {
+            if segment.starts_with("width=") {
+                "original=true"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Attached files worth keeping with the examples: workflows ship as JSON or zipped.
+fn is_example_attachment(file: &CivitaiFile) -> bool {
+    let is_model = matches!(
+        file.r#type.as_deref(),
+        Some("Model" | "Pruned Model" | "Training Data" | "VAE" | "Negative")
+    );
+    let name = file
+        .name
+        .as_deref()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    !is_model && (name.ends_with(".json") || name.ends_with(".zip"))
+}
+
+/// ComfyUI images carry their graph in `meta.comfy`, either as a JSON string or inline,
+/// wrapping `{ "prompt", "workflow" }`. Returns the loadable workflow.
+fn comfy_workflow_from_meta(meta: &Value) -> Option<Value> {
+    let comfy = meta.get("comfy")?;
+    let comfy = match comfy {
+        Value::String(raw) => serde_json::from_str(raw).ok()?,
+        other => other.clone(),
+    };
+    match comfy.get("workflow") {
+        Some(workflow) if !workflow.is_null() => Some(workflow.clone()),
+        _ => Some(comfy),
+    }
+}
+
+async fn write_example_file(path: &Path, bytes: &[u8]) -> Result<bool> {
+    if fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(false);
+    }
+    fs::write(path, bytes)
+        .await
+        .with_context(|| format!("failed to write {path:?}"))?;
+    Ok(true)
+}
+
+/// Streams one example file into `examples_dir` through a temporary file, giving up on
+/// anything over the preview cache's per-item cap. Returns whether the file was saved.
+async fn download_example_file(
+    client: &Client,
+    url: &str,
+    examples_dir: &Path,
+    file_name: &str,
+    token: Option<&str>,
+    cancel: Option<&CancellationToken>,
+) -> Result<bool> {
+    let mut request = client.get(url);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    let mut response = match request.send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            warn!(
+                "Example request for {url} returned status {}",
+                response.status()
+            );
+            return Ok(false);
+        }
+        Err(err) => {
+            warn!("Failed to request example {url}: {err}");
+            return Ok(false);
+        }
+    };
+    if response
+        .content_length()
+        .is_some_and(|len| len > PREVIEW_CACHE_MAX_ITEM_BYTES)
+    {
+        warn!("Skipping example {url}: larger than the per-file cap");
+        return Ok(false);
+    }
+
+    let path = examples_dir.join(file_name);
+    let part_path = unique_tmp_path(examples_dir, file_name);
+    let mut file = fs::File::create(&part_path)
+        .await
+        .with_context(|| format!("failed to create {part_path:?}"))?;
+    let mut written = 0u64;
+    let outcome = loop {
+        if is_cancelled(cancel) {
+            break Err(DownloadError::Cancelled.into());
+        }
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                written += chunk.len() as u64;
+                if written > PREVIEW_CACHE_MAX_ITEM_BYTES {
+                    warn!("Skipping example {url}: larger than the per-file cap");
+                    break Ok(false);
+                }
+                if let Err(err) = file.write_all(&chunk).await {
+                    break Err(anyhow!(err).context(format!("failed to write {part_path:?}")));
+                }
+            }
+            Ok(None) => {
+                break file
+                    .flush()
+                    .await
+                    .map(|()| true)
+                    .with_context(|| format!("failed flushing {part_path:?}"))
+            }
+            Err(err) => {
+                warn!("Failed to download example {url}: {err}");
+                break Ok(false);
+            }
+        }
+    };
+    drop(file);
+    if !matches!(outcome, Ok(true)) {
+        let _ = fs::remove_file(&part_path).await;
+        return outcome;
+    }
+    fs::rename(&part_path, &path)
+        .await
+        .with_context(|| format!("failed to move {part_path:?} to {path:?}"))?;
+    Ok(true)
+}
+
+/// Best-effort fetch of a Civitai LoRA's example media and attached workflows into
+/// [`lora_examples_dir`]. Failures are logged and never fail the LoRA itself.
+async fn save_civitai_examples(
+    client: &Client,
+    limiter: &CivitaiRateLimiter,
+    download_url: &str,
+    lora_dir: &Path,
+    lora_file_name: &str,
+    token: Option<&str>,
+    cancel: Option<&CancellationToken>,
+) -> usize {
+    let examples_dir = lora_examples_dir(lora_dir, lora_file_name);
+    match fetch_civitai_examples(client, limiter, download_url, &examples_dir, token, cancel).await
+    {
+        Ok(saved) => saved,
+        Err(err) if matches!(err.downcast_ref(), Some(DownloadError::Cancelled)) => {
+            info!("Stopped saving Civitai examples for {download_url}: download cancelled");
+            0
+        }
+        Err(err) => {
+            warn!("Failed to save Civitai examples for {download_url}: {err:#}");
+            0
+        }
+    }
+}
+
+async fn fetch_civitai_examples(
+    client: &Client,
+    limiter: &CivitaiRateLimiter,
+    download_url: &str,
+    examples_dir: &Path,
+    token: Option<&str>,
+    cancel: Option<&CancellationToken>,
+) -> Result<usize> {
+    let model_version_id = extract_civitai_model_version_id(download_url)
+        .ok_or_else(|| anyhow!("no model version id in {download_url}"))?;
+    let api_url = format!("https://civitai.com/api/v1/model-versions/{model_version_id}");
+    let mut request = client.get(&api_url);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    limiter.acquire(model_version_id).await;
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("request failed for {api_url}"))?;
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        limiter.back_off();
+    }
+    let payload: CivitaiExamplesPayload = response
+        .error_for_status()
+        .with_context(|| format!("unexpected status fetching examples from {api_url}"))?
+        .json()
+        .await
+        .with_context(|| format!("failed to parse examples payload for {api_url}"))?;
+
+    fs::create_dir_all(examples_dir)
+        .await
+        .with_context(|| format!("failed to create {examples_dir:?}"))?;
+    let mut saved = 0usize;
+
+    for (index, image) in payload.images.iter().enumerate() {
+        if is_cancelled(cancel) {
+            return Err(DownloadError::Cancelled.into());
+        }
+        let Some(url) = image.url.as_deref().filter(|url| !url.is_empty()) else {
+            continue;
+        };
+        let url = original_civitai_image_url(url);
+        let name = url
+            .rsplit('/')
+            .next()
+            .map(sanitize_file_name)
+            .filter(|name| name.contains('.'))
+            .unwrap_or_else(|| format!("example-{}.png", index + 1));
+        let stem = Path::new(&name)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| name.clone());
+
+        let media_path = examples_dir.join(&name);
+        if !fs::try_exists(&media_path).await.unwrap_or(false) {
+            if !download_example_file(client, &url, examples_dir, &name, token, cancel).await? {
+                continue;
+            }
+            saved += 1;
+        }
+
+        // JPEG/WebP uploads are often stripped, so the parameters are kept beside them too.
+        if let Some(meta) = image.meta.as_ref().filter(|meta| !meta.is_null()) {
+            let sidecar = serde_json::to_vec_pretty(meta)?;
+            if write_example_file(&examples_dir.join(format!("{stem}.json")), &sidecar).await? {
+                saved += 1;
+            }
+            if let Some(workflow) = comfy_workflow_from_meta(meta) {
+                let workflow = serde_json::to_vec_pretty(&workflow)?;
+                let path = examples_dir.join(format!("{stem}.workflow.json"));
+                if write_example_file(&path, &workflow).await? {
+                    saved += 1;
+                }
+            }
+        }
+    }
+
+    for file in payload
+        .files
+        .iter()
+        .filter(|file| is_example_attachment(file))
+    {
+        if is_cancelled(cancel) {
+            return Err(DownloadError::Cancelled.into());
+        }
+        let (Some(name), Some(url)) = (file.name.as_deref(), file.download_url.as_deref()) else {
+            continue;
+        };
+        let name = sanitize_file_name(name);
+        if fs::try_exists(examples_dir.join(&name))
+            .await
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        if download_example_file(client, url, examples_dir, &name, token, cancel).await? {
+            saved += 1;
+        }
+    }
+
+    Ok(saved)
+}
+
 fn is_video_url(url: &str) -> bool {
     let lower = url.to_ascii_lowercase();
     lower.ends_with(".mp4") || lower.ends_with(".webm") || lower.ends_with(".mov")
 }
 
+/// Downloads preview media unless it is larger than a cache entry may be, which is
+/// checked against the reported length up front and again while the body streams in.
+async fn fetch_preview_media_capped(
+    client: &Client,
+    url: &str,
+    token: Option<&str>,
+) -> Option<Vec<u8>> {
+    let mut request = client.get(url);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    let mut response = match request.send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            warn!(
+                "Preview request for {url} returned status {}",
+                response.status()
+            );
+            return None;
+        }
+        Err(err) => {
+            warn!("Failed to request preview media for {url}: {err}");
+            return None;
+        }
+    };
+    if response
+        .content_length()
+        .is_some_and(|len| len > PREVIEW_CACHE_MAX_ITEM_BYTES)
+    {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                if (bytes.len() + chunk.len()) as u64 > PREVIEW_CACHE_MAX_ITEM_BYTES {
+                    return None;
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            Ok(None) => return Some(bytes),
+            Err(err) => {
+                warn!("Failed to download preview media from {url}: {err}");
+                return None;
+            }
+        }
+    }
+}
+
 async fn fetch_preview_image_bytes(
     client: &Client,
     image_url: &str,
@@ -2664,6 +4024,23 @@ struct CivitaiModelVersion {
     settings: Option<CivitaiModelSettings>,
 }
 
+/// The parts of a model-version payload that [`fetch_civitai_examples`] needs. `meta` is
+/// kept verbatim so the sidecar files carry every generation parameter Civitai has.
+#[derive(Debug, Deserialize)]
+struct CivitaiExamplesPayload {
+    #[serde(default)]
+    images: Vec<CivitaiExampleImage>,
+    #[serde(default)]
+    files: Vec<CivitaiFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CivitaiExampleImage {
+    url: Option<String>,
+    #[serde(default)]
+    meta: Option<Value>,
+}
+
 #[derive(Debug, Deserialize)]
 struct CivitaiImage {
     url: Option<String>,
@@ -2709,6 +4086,45 @@ struct CivitaiModelResponse {
     model_versions: Vec<CivitaiModelVersionSummary>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CivitaiCreatorFeed {
+    #[serde(default)]
+    items: Vec<CivitaiCreatorFeedModel>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CivitaiCreatorFeedModel {
+    id: u64,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    creator: Option<CivitaiCreator>,
+    #[serde(default)]
+    model_versions: Vec<CivitaiCreatorFeedVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CivitaiCreatorFeedVersion {
+    id: u64,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    base_model: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    download_url: Option<String>,
+    #[serde(default)]
+    files: Vec<CivitaiFile>,
+    #[serde(default)]
+    images: Vec<CivitaiImage>,
+}
+
 #[derive(Debug)]
 struct CivitaiModelDetails {
     creator: Option<CivitaiCreator>,
@@ -2759,6 +4175,76 @@ struct CivitaiModelVersionSummary {
     settings: Option<CivitaiModelSettings>,
 }
 
+async fn fetch_civitai_creator_releases(
+    client: &Client,
+    username: &str,
+    token: Option<&str>,
+) -> Result<Vec<CivitaiCreatorRelease>> {
+    let api_url = "https://civitai.com/api/v1/models";
+    let mut request = client.get(api_url).query(&[
+        ("username", username),
+        ("types", "LORA"),
+        ("sort", "Newest"),
+        ("limit", "20"),
+    ]);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("request failed for {api_url}"))?;
+
+    if response.status().as_u16() == 401 {
+        return Err(DownloadError::Unauthorized.into());
+    }
+
+    let response = response
+        .error_for_status()
+        .with_context(|| format!("unexpected status fetching the feed for {username}"))?;
+
+    let feed: CivitaiCreatorFeed = response
+        .json()
+        .await
+        .with_context(|| format!("failed to parse the Civitai feed for {username}"))?;
+
+    let mut releases = Vec::new();
+    for model in feed.items {
+        let creator = model
+            .creator
+            .and_then(|creator| creator.username)
+            .unwrap_or_else(|| username.to_string());
+        for version in model.model_versions {
+            let primary = version
+                .files
+                .iter()
+                .find(|file| matches!(file.r#type.as_deref(), None | Some("Model")))
+                .or_else(|| version.files.first());
+            let Some(download_url) = version
+                .download_url
+                .clone()
+                .or_else(|| primary.and_then(|file| file.download_url.clone()))
+            else {
+                continue;
+            };
+            releases.push(CivitaiCreatorRelease {
+                creator: creator.clone(),
+                model_id: model.id,
+                model_name: model.name.clone(),
+                version_id: version.id,
+                version_name: version.name,
+                base_model: version.base_model,
+                download_url,
+                file_name: primary.and_then(|file| file.name.clone()),
+                published_at: version.published_at.or(version.created_at),
+                preview_url: version.images.into_iter().find_map(|image| image.url),
+            });
+        }
+    }
+    Ok(releases)
+}
+
 async fn fetch_civitai_model_details(
     client: &Client,
     model_id: u64,
@@ -1,3 +1,7 @@
+use std::time::Duration;
+
+const DEFAULT_SUBPROCESS_IDLE_MINUTES: u64 = 10;
+
 fn parse_env_bool(var: &str) -> Option<bool> {
     std::env::var(var).ok().and_then(|value| {
         let normalized = value.trim().to_ascii_lowercase();
@@ -26,3 +30,13 @@ pub fn auto_update_enabled() -> bool {
 
     parse_env_bool("ARCTIC_AUTO_UPDATE").unwrap_or(true)
 }
+
+/// Minutes a uv/pip subprocess may stay silent and idle before it is treated as hung.
+/// `ARCTIC_SUBPROCESS_IDLE_MINUTES=0` disables the watchdog.
+pub fn subprocess_idle_timeout() -> Option<Duration> {
+    let minutes = std::env::var("ARCTIC_SUBPROCESS_IDLE_MINUTES")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_SUBPROCESS_IDLE_MINUTES);
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+}
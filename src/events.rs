@@ -0,0 +1,221 @@
+use crate::config::{EventExportSettings, EventExportTarget};
+use anyhow::{bail, Context, Result};
+use log::warn;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    os::unix::net::UnixDatagram,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+const SYSLOG_IDENTIFIER: &str = "arctic-downloader";
+const MAX_JOURNAL_FIELD_BYTES: usize = 16 * 1024;
+pub const DEFAULT_NDJSON_FILE: &str = "events.ndjson";
+
+/// Journald priorities (syslog levels) used for exported events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl EventSeverity {
+    fn from_phase(phase: &str) -> Self {
+        let phase = phase.to_ascii_lowercase();
+        if phase.contains("fail") || phase.contains("error") {
+            EventSeverity::Error
+        } else if phase.contains("cancel") || phase.contains("conflict") || phase.contains("warn") {
+            EventSeverity::Warning
+        } else {
+            EventSeverity::Info
+        }
+    }
+
+    const fn priority(self) -> u8 {
+        match self {
+            EventSeverity::Error => 3,
+            EventSeverity::Warning => 4,
+            EventSeverity::Info => 6,
+        }
+    }
+}
+
+/// One exported event. `type` is the UI channel the event was emitted on and `code`
+/// is `<kind>.<phase>`, e.g. `model.failed` or `comfyui_runtime.start_failed`.
+#[derive(Debug, Serialize)]
+pub struct ExportedEvent<'a> {
+    pub ts_ms: u64,
+    #[serde(rename = "type")]
+    pub event_type: &'a str,
+    pub code: String,
+    pub severity: EventSeverity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<&'a str>,
+    pub payload: &'a Value,
+}
+
+impl<'a> ExportedEvent<'a> {
+    pub fn new(event_type: &'a str, payload: &'a Value) -> Self {
+        let phase = payload
+            .get("phase")
+            .and_then(Value::as_str)
+            .unwrap_or("event");
+        let kind = payload
+            .get("kind")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| event_type.replace('-', "_"));
+        Self {
+            ts_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            event_type,
+            code: format!("{kind}.{phase}"),
+            severity: EventSeverity::from_phase(phase),
+            message: payload.get("message").and_then(Value::as_str),
+            payload,
+        }
+    }
+
+    fn is_progress(&self) -> bool {
+        self.payload.get("phase").and_then(Value::as_str) == Some("progress")
+    }
+}
+
+enum EventSink {
+    Journald(UnixDatagram),
+    Ndjson { path: PathBuf, file: File },
+}
+
+/// Mirrors UI events to journald or an NDJSON file for unattended monitoring.
+#[derive(Default)]
+pub struct EventExporter {
+    sink: Mutex<Option<EventSink>>,
+    include_progress: Mutex<bool>,
+}
+
+impl EventExporter {
+    /// Replaces the active sink. `state_dir` supplies the default NDJSON location.
+    pub fn configure(&self, settings: &EventExportSettings, state_dir: &Path) -> Result<()> {
+        let sink = match settings.target {
+            None => None,
+            Some(EventExportTarget::Journald) => Some(open_journald()?),
+            Some(EventExportTarget::Ndjson) => {
+                let path = settings
+                    .ndjson_path
+                    .clone()
+                    .unwrap_or_else(|| state_dir.join(DEFAULT_NDJSON_FILE));
+                Some(open_ndjson(path)?)
+            }
+        };
+        if let Ok(mut current) = self.sink.lock() {
+            *current = sink;
+        }
+        if let Ok(mut include) = self.include_progress.lock() {
+            *include = settings.include_progress;
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sink.lock().map(|sink| sink.is_some()).unwrap_or(false)
+    }
+
+    /// Writes one event to the configured sink. Failures are logged, never returned,
+    /// so a broken sink cannot interrupt downloads or installs.
+    pub fn export(&self, event_type: &str, payload: &Value) {
+        let Ok(mut guard) = self.sink.lock() else {
+            return;
+        };
+        let Some(sink) = guard.as_mut() else {
+            return;
+        };
+        let event = ExportedEvent::new(event_type, payload);
+        if event.is_progress() && !self.include_progress.lock().map(|v| *v).unwrap_or(false) {
+            return;
+        }
+        let result = match sink {
+            EventSink::Journald(socket) => send_journal_entry(socket, &event),
+            EventSink::Ndjson { path, file } => write_ndjson_line(file, &event)
+                .with_context(|| format!("failed to append event to {}", path.display())),
+        };
+        if let Err(err) = result {
+            warn!("Event export failed: {err:#}");
+        }
+    }
+}
+
+fn open_journald() -> Result<EventSink> {
+    if !Path::new(JOURNALD_SOCKET).exists() {
+        bail!("journald socket {JOURNALD_SOCKET} not found; is systemd-journald running?");
+    }
+    let socket = UnixDatagram::unbound().context("failed to create journald socket")?;
+    socket
+        .connect(JOURNALD_SOCKET)
+        .with_context(|| format!("failed to connect to {JOURNALD_SOCKET}"))?;
+    Ok(EventSink::Journald(socket))
+}
+
+fn open_ndjson(path: PathBuf) -> Result<EventSink> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("failed to open event log {}", path.display()))?;
+    Ok(EventSink::Ndjson { path, file })
+}
+
+fn write_ndjson_line(file: &mut File, event: &ExportedEvent<'_>) -> Result<()> {
+    let mut line = serde_json::to_vec(event)?;
+    line.push(b'\n');
+    file.write_all(&line)?;
+    Ok(())
+}
+
+/// Sends an entry using the journald native protocol (newline-separated
+/// `FIELD=value` pairs). Values are flattened to one line and capped in size.
+fn send_journal_entry(socket: &UnixDatagram, event: &ExportedEvent<'_>) -> Result<()> {
+    let message = match event.message {
+        Some(message) => format!("[{}] {message}", event.code),
+        None => format!("[{}]", event.code),
+    };
+    let fields = [
+        ("MESSAGE", message),
+        ("PRIORITY", event.severity.priority().to_string()),
+        ("SYSLOG_IDENTIFIER", SYSLOG_IDENTIFIER.to_string()),
+        ("ARCTIC_EVENT_TYPE", event.event_type.to_string()),
+        ("ARCTIC_EVENT_CODE", event.code.clone()),
+        ("ARCTIC_EVENT_JSON", serde_json::to_string(event.payload)?),
+    ];
+    let mut entry = String::new();
+    for (key, value) in fields {
+        let mut value = value.replace('\n', " ");
+        if value.len() > MAX_JOURNAL_FIELD_BYTES {
+            let mut cut = MAX_JOURNAL_FIELD_BYTES;
+            while !value.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            value.truncate(cut);
+        }
+        entry.push_str(key);
+        entry.push('=');
+        entry.push_str(&value);
+        entry.push('\n');
+    }
+    socket
+        .send(entry.as_bytes())
+        .context("failed to send entry to journald")?;
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! Fault injection for QA. Nothing here does anything unless one of the
+//! `ARCTIC_FAULT_*` variables is set:
+//!
+//! - `ARCTIC_FAULT_BANDWIDTH_KBPS`: caps the combined download rate.
+//! - `ARCTIC_FAULT_HTTP_ERROR_RATE`: probability (0-1) that a request fails with a 5xx.
+//! - `ARCTIC_FAULT_TIMEOUT_RATE`: probability (0-1) that a request stalls and times out.
+//! - `ARCTIC_FAULT_FAIL_STEPS`: comma-separated installer steps to fail, or `*` for all.
+
+use chacha20poly1305::aead::{rand_core::RngCore, OsRng};
+use std::{
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+/// How long an injected timeout stalls before failing, so cancel and progress UI see a
+/// real hang.
+const INJECTED_TIMEOUT_STALL: Duration = Duration::from_secs(5);
+const INJECTED_STATUSES: [u16; 3] = [500, 502, 503];
+
+#[derive(Clone, Debug, Default)]
+pub struct FaultSettings {
+    pub bandwidth_bytes_per_sec: Option<u64>,
+    pub http_error_rate: f64,
+    pub timeout_rate: f64,
+    pub fail_steps: Vec<String>,
+}
+
+impl FaultSettings {
+    fn from_env() -> Self {
+        let rate = |var: &str| {
+            std::env::var(var)
+                .ok()
+                .and_then(|value| value.trim().parse::<f64>().ok())
+                .filter(|value| value.is_finite())
+                .map(|value| value.clamp(0.0, 1.0))
+                .unwrap_or(0.0)
+        };
+        Self {
+            bandwidth_bytes_per_sec: std::env::var("ARCTIC_FAULT_BANDWIDTH_KBPS")
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .filter(|kbps| *kbps > 0)
+                .map(|kbps| kbps * 1024),
+            http_error_rate: rate("ARCTIC_FAULT_HTTP_ERROR_RATE"),
+            timeout_rate: rate("ARCTIC_FAULT_TIMEOUT_RATE"),
+            fail_steps: std::env::var("ARCTIC_FAULT_FAIL_STEPS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|step| step.trim().to_string())
+                .filter(|step| !step.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.bandwidth_bytes_per_sec.is_some()
+            || self.http_error_rate > 0.0
+            || self.timeout_rate > 0.0
+            || !self.fail_steps.is_empty()
+    }
+
+    pub fn fails_step(&self, step: &str) -> bool {
+        self.fail_steps
+            .iter()
+            .any(|candidate| candidate == "*" || candidate == step)
+    }
+}
+
+static SETTINGS: OnceLock<FaultSettings> = OnceLock::new();
+/// When the shared bandwidth budget is next free.
+static NEXT_SLOT: OnceLock<Mutex<Instant>> = OnceLock::new();
+
+pub fn settings() -> &'static FaultSettings {
+    SETTINGS.get_or_init(FaultSettings::from_env)
+}
+
+#[derive(Clone, Copy, Debug, Error)]
+pub enum InjectedFault {
+    #[error("injected fault: server returned status {0}")]
+    ServerError(u16),
+    #[error("injected fault: request timed out")]
+    Timeout,
+}
+
+impl InjectedFault {
+    /// How long the caller should hang before reporting the fault.
+    pub fn stall(&self) -> Duration {
+        match self {
+            InjectedFault::ServerError(_) => Duration::ZERO,
+            InjectedFault::Timeout => INJECTED_TIMEOUT_STALL,
+        }
+    }
+}
+
+fn roll(probability: f64) -> bool {
+    probability > 0.0 && (OsRng.next_u32() as f64 / u32::MAX as f64) < probability
+}
+
+/// Decides whether the request about to be sent should fail.
+pub fn roll_request_fault() -> Option<InjectedFault> {
+    let settings = settings();
+    if roll(settings.timeout_rate) {
+        return Some(InjectedFault::Timeout);
+    }
+    if roll(settings.http_error_rate) {
+        let status = INJECTED_STATUSES[OsRng.next_u32() as usize % INJECTED_STATUSES.len()];
+        return Some(InjectedFault::ServerError(status));
+    }
+    None
+}
+
+/// Async form of [`roll_request_fault`] that also waits out an injected stall.
+pub async fn before_request() -> Result<(), InjectedFault> {
+    match roll_request_fault() {
+        Some(fault) => {
+            tokio::time::sleep(fault.stall()).await;
+            Err(fault)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Delays the caller so all streams together stay under the configured bandwidth cap.
+pub async fn throttle(bytes: usize) {
+    let Some(rate) = settings().bandwidth_bytes_per_sec else {
+        return;
+    };
+    let cost = Duration::from_secs_f64(bytes as f64 / rate as f64);
+    let ready_at = {
+        let slot = NEXT_SLOT.get_or_init(|| Mutex::new(Instant::now()));
+        let Ok(mut next) = slot.lock() else {
+            return;
+        };
+        let start = (*next).max(Instant::now());
+        *next = start + cost;
+        *next
+    };
+    tokio::time::sleep_until(ready_at.into()).await;
+}
@@ -0,0 +1,165 @@
+use crate::config::HookSettings;
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::{
+    io::Read,
+    os::unix::process::CommandExt,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 300;
+const MAX_CAPTURED_OUTPUT: usize = 64 * 1024;
+// How long to wait for the output readers once the hook is gone; a background process
+// that escaped the group can keep the pipes open indefinitely.
+const READER_JOIN_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    PreDownload,
+    PostDownload,
+    PostInstall,
+}
+
+impl HookEvent {
+    pub const fn identifier(self) -> &'static str {
+        match self {
+            HookEvent::PreDownload => "pre_download",
+            HookEvent::PostDownload => "post_download",
+            HookEvent::PostInstall => "post_install",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HookOutcome {
+    pub event: HookEvent,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl HookOutcome {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+}
+
+/// Runs the hook configured for `event` through `sh -c`, passing `env` plus
+/// `ARCTIC_HOOK_EVENT`. Returns `Ok(None)` when no hook is configured. The hook is
+/// killed once the configured timeout (default 5 minutes) elapses.
+pub fn run_hook(
+    settings: &HookSettings,
+    event: HookEvent,
+    env: &[(&str, String)],
+) -> Result<Option<HookOutcome>> {
+    let Some(command) = settings.command_for(event) else {
+        return Ok(None);
+    };
+    let timeout = Duration::from_secs(settings.timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS));
+
+    info!("Running {} hook: {command}", event.identifier());
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("ARCTIC_HOOK_EVENT", event.identifier())
+        .envs(env.iter().map(|(key, value)| (*key, value.as_str())))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .with_context(|| format!("failed to start {} hook", event.identifier()))?;
+
+    let stdout = child.stdout.take().map(capture_output);
+    let stderr = child.stderr.take().map(capture_output);
+
+    let started = Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if started.elapsed() >= timeout {
+            timed_out = true;
+            // Kill the whole group so commands started by the script die with it.
+            let _ = Command::new("kill")
+                .arg("-KILL")
+                .arg(format!("-{}", child.id()))
+                .status();
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+
+    let join_deadline = Instant::now() + READER_JOIN_GRACE;
+    let outcome = HookOutcome {
+        event,
+        exit_code: status.and_then(|status| status.code()),
+        timed_out,
+        stdout: stdout
+            .and_then(|h| join_capture(h, join_deadline))
+            .unwrap_or_default(),
+        stderr: stderr
+            .and_then(|h| join_capture(h, join_deadline))
+            .unwrap_or_default(),
+    };
+    for line in outcome.stdout.lines() {
+        info!("[{} hook] {line}", event.identifier());
+    }
+    for line in outcome.stderr.lines() {
+        warn!("[{} hook] {line}", event.identifier());
+    }
+    if outcome.timed_out {
+        warn!(
+            "{} hook timed out after {}s and was killed.",
+            event.identifier(),
+            timeout.as_secs()
+        );
+    } else if !outcome.success() {
+        warn!(
+            "{} hook exited with status {:?}.",
+            event.identifier(),
+            outcome.exit_code
+        );
+    }
+    Ok(Some(outcome))
+}
+
+/// Reads a hook's output stream to the end, keeping only the last
+/// `MAX_CAPTURED_OUTPUT` bytes so a chatty hook cannot grow the buffer without bound.
+fn capture_output<R: Read + Send + 'static>(mut reader: R) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 8192];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => buf.extend_from_slice(&chunk[..read]),
+            }
+            if buf.len() > 2 * MAX_CAPTURED_OUTPUT {
+                buf.drain(..buf.len() - MAX_CAPTURED_OUTPUT);
+            }
+        }
+        if buf.len() > MAX_CAPTURED_OUTPUT {
+            buf.drain(..buf.len() - MAX_CAPTURED_OUTPUT);
+        }
+        String::from_utf8_lossy(&buf).to_string()
+    })
+}
+
+/// Joins an output reader, giving up at `deadline`. An abandoned reader exits on its
+/// own once whatever still holds the pipe closes it.
+fn join_capture(handle: thread::JoinHandle<String>, deadline: Instant) -> Option<String> {
+    while !handle.is_finished() {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    handle.join().ok()
+}
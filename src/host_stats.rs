@@ -0,0 +1,278 @@
+use anyhow::{Context, Result};
+use log::warn;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub const HOST_STATS_FILE: &str = "host-stats.json";
+
+/// Consecutive failures after which a host is considered unreliable.
+const FLAG_CONSECUTIVE_FAILURES: u32 = 3;
+/// Failure ratio (with at least `FLAG_MIN_ATTEMPTS` attempts) that flags a host.
+const FLAG_FAILURE_RATE: f64 = 0.5;
+const FLAG_MIN_ATTEMPTS: u64 = 4;
+/// Transfers smaller than this are too short to judge throughput.
+const THROUGHPUT_SAMPLE_MIN_BYTES: u64 = 16 * 1024 * 1024;
+const THROUGHPUT_MIN_SAMPLES: u32 = 2;
+const SLOW_HOST_BYTES_PER_SEC: u64 = 1024 * 1024;
+/// Minimum gap between writes of the statistics file while transfers keep reporting;
+/// whatever is left over is written by [`HostStatsTracker::flush`].
+const PERSIST_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct HostStats {
+    pub host: String,
+    pub attempts: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    #[serde(default)]
+    pub sampled_bytes: u64,
+    #[serde(default)]
+    pub sampled_millis: u64,
+    #[serde(default)]
+    pub samples: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_failure_at: Option<u64>,
+}
+
+impl HostStats {
+    fn new(host: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn failure_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.attempts as f64
+        }
+    }
+
+    /// Average throughput across transfers large enough to be meaningful.
+    pub fn bytes_per_sec(&self) -> Option<u64> {
+        if self.samples == 0 || self.sampled_millis == 0 {
+            return None;
+        }
+        Some(self.sampled_bytes.saturating_mul(1000) / self.sampled_millis)
+    }
+
+    /// Why this host is currently considered a problem, if it is.
+    pub fn problem(&self) -> Option<String> {
+        if self.consecutive_failures >= FLAG_CONSECUTIVE_FAILURES {
+            return Some(format!(
+                "{} consecutive failures",
+                self.consecutive_failures
+            ));
+        }
+        if self.attempts >= FLAG_MIN_ATTEMPTS && self.failure_rate() >= FLAG_FAILURE_RATE {
+            return Some(format!(
+                "{:.0}% of {} downloads failed",
+                self.failure_rate() * 100.0,
+                self.attempts
+            ));
+        }
+        match self.bytes_per_sec() {
+            Some(rate)
+                if self.samples >= THROUGHPUT_MIN_SAMPLES && rate < SLOW_HOST_BYTES_PER_SEC =>
+            {
+                Some(format!(
+                    "slow: {:.1} MB/s average",
+                    rate as f64 / 1_000_000.0
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Per-host download counters, persisted to `state/host-stats.json`.
+#[derive(Debug)]
+pub struct HostStatsTracker {
+    path: PathBuf,
+    hosts: Mutex<HashMap<String, HostStats>>,
+    persist_state: Mutex<PersistState>,
+}
+
+#[derive(Debug, Default)]
+struct PersistState {
+    last_write: Option<Instant>,
+    dirty: bool,
+}
+
+impl HostStatsTracker {
+    pub fn load(path: PathBuf) -> Self {
+        let hosts = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Vec<HostStats>>(&bytes).ok())
+            .map(|entries| {
+                entries
+                    .into_iter()
+                    .map(|entry| (entry.host.clone(), entry))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            hosts: Mutex::new(hosts),
+            persist_state: Mutex::new(PersistState::default()),
+        }
+    }
+
+    pub fn record_success(&self, url: &str, bytes: u64, elapsed: Duration) {
+        self.update(url, |stats| {
+            stats.attempts += 1;
+            stats.consecutive_failures = 0;
+            stats.last_success_at = Some(unix_now());
+            if bytes >= THROUGHPUT_SAMPLE_MIN_BYTES {
+                stats.sampled_bytes = stats.sampled_bytes.saturating_add(bytes);
+                stats.sampled_millis = stats
+                    .sampled_millis
+                    .saturating_add(elapsed.as_millis().max(1) as u64);
+                stats.samples += 1;
+            }
+        });
+    }
+
+    pub fn record_failure(&self, url: &str, error: &str) {
+        self.update(url, |stats| {
+            stats.attempts += 1;
+            stats.failures += 1;
+            stats.consecutive_failures += 1;
+            stats.last_error = Some(error.to_string());
+            stats.last_failure_at = Some(unix_now());
+        });
+    }
+
+    pub fn snapshot(&self) -> Vec<HostStats> {
+        let mut entries: Vec<HostStats> = self
+            .hosts
+            .lock()
+            .map(|hosts| hosts.values().cloned().collect())
+            .unwrap_or_default();
+        entries.sort_by(|a, b| a.host.cmp(&b.host));
+        entries
+    }
+
+    pub fn problem_for(&self, host: &str) -> Option<String> {
+        self.hosts
+            .lock()
+            .ok()
+            .and_then(|hosts| hosts.get(host).and_then(HostStats::problem))
+    }
+
+    /// Drops blacklisted hosts from `urls` and moves flagged hosts behind healthy
+    /// ones, keeping the original order otherwise.
+    pub fn order_candidates(&self, urls: Vec<String>, blacklist: &[String]) -> Vec<String> {
+        let mut ranked: Vec<(bool, String)> = urls
+            .into_iter()
+            .filter_map(|url| {
+                let host = url_host(&url);
+                if host
+                    .as_deref()
+                    .is_some_and(|host| is_blacklisted(host, blacklist))
+                {
+                    return None;
+                }
+                let flagged = host.is_some_and(|host| self.problem_for(&host).is_some());
+                Some((flagged, url))
+            })
+            .collect();
+        ranked.sort_by_key(|(flagged, _)| *flagged);
+        ranked.into_iter().map(|(_, url)| url).collect()
+    }
+
+    /// Writes the statistics file if any update has not reached it yet.
+    pub fn flush(&self) {
+        self.write_out(true);
+    }
+
+    fn update(&self, url: &str, apply: impl FnOnce(&mut HostStats)) {
+        let Some(host) = url_host(url) else {
+            return;
+        };
+        {
+            let Ok(mut hosts) = self.hosts.lock() else {
+                return;
+            };
+            apply(
+                hosts
+                    .entry(host.clone())
+                    .or_insert_with(|| HostStats::new(&host)),
+            );
+        }
+        if let Ok(mut state) = self.persist_state.lock() {
+            state.dirty = true;
+        }
+        self.write_out(false);
+    }
+
+    /// Persists pending updates, unless `force` is off and the file was written less
+    /// than [`PERSIST_INTERVAL`] ago.
+    fn write_out(&self, force: bool) {
+        let Ok(mut state) = self.persist_state.lock() else {
+            return;
+        };
+        let due = match state.last_write {
+            Some(at) => at.elapsed() >= PERSIST_INTERVAL,
+            None => true,
+        };
+        if !state.dirty || !(force || due) {
+            return;
+        }
+        let entries = self.snapshot();
+        if let Err(err) = self.persist(&entries) {
+            warn!("Unable to save host statistics: {err:#}");
+        }
+        state.dirty = false;
+        state.last_write = Some(Instant::now());
+    }
+
+    fn persist(&self, entries: &[HostStats]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_vec_pretty(entries)?;
+        fs::write(&self.path, json)
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+/// Lower-cased host name of `url`, if it parses.
+pub fn url_host(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+}
+
+/// Whether `host` matches a blacklist entry exactly or is a subdomain of one.
+pub fn is_blacklisted(host: &str, blacklist: &[String]) -> bool {
+    blacklist.iter().any(|entry| {
+        let entry = entry.trim().to_ascii_lowercase();
+        !entry.is_empty()
+            && (host == entry
+                || host
+                    .strip_suffix(entry.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.')))
+    })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
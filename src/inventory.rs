@@ -0,0 +1,215 @@
+use crate::model::{AssetClass, ModelCatalog};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const MODEL_FILE_EXTENSIONS: &[&str] = &[
+    "safetensors",
+    "sft",
+    "ckpt",
+    "pt",
+    "pth",
+    "bin",
+    "gguf",
+    "onnx",
+];
+
+#[derive(Clone, Debug)]
+pub struct InventoryEntry {
+    pub path: PathBuf,
+    pub category: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+}
+
+/// Walks the `models/` folder of every root and returns the model files found.
+/// Partial downloads (`*.part.N`) are ignored.
+pub fn scan_model_inventory(roots: &[PathBuf]) -> Vec<InventoryEntry> {
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for root in roots {
+        let models_root = root.join("models");
+        if !models_root.is_dir() {
+            continue;
+        }
+        let mut stack = vec![models_root.clone()];
+        while let Some(dir) = stack.pop() {
+            let Ok(read_dir) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if file_type.is_dir() {
+                    stack.push(path);
+                    continue;
+                }
+                if !is_model_file(&path) {
+                    continue;
+                }
+                let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+                if !seen.insert(canonical) {
+                    continue;
+                }
+                let category = path
+                    .strip_prefix(&models_root)
+                    .ok()
+                    .and_then(|rel| rel.components().next())
+                    .filter(|_| path.parent() != Some(models_root.as_path()))
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let file_name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let size_bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                entries.push(InventoryEntry {
+                    path,
+                    category,
+                    file_name,
+                    size_bytes,
+                });
+            }
+        }
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    entries
+}
+
+/// Lowercased file names of every artifact, LoRA and standalone asset (upscaler,
+/// ControlNet, embedding, CLIP vision) known to the catalog.
+pub fn catalog_file_names(catalog: &ModelCatalog) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for model in &catalog.models {
+        for variant in &model.variants {
+            for artifact in &variant.artifacts {
+                names.insert(artifact.file_name().to_ascii_lowercase());
+            }
+        }
+        for group in &model.always {
+            for artifact in &group.artifacts {
+                names.insert(artifact.file_name().to_ascii_lowercase());
+            }
+        }
+    }
+    for lora in &catalog.loras {
+        names.insert(lora.derived_file_name().to_ascii_lowercase());
+    }
+    for class in AssetClass::all() {
+        for asset in catalog.assets(*class) {
+            names.insert(asset.derived_file_name().to_ascii_lowercase());
+        }
+    }
+    names
+}
+
+/// Lowercased model file names referenced by saved workflows under `user/*/workflows`.
+pub fn workflow_file_references(comfy_root: &Path) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let Ok(users) = fs::read_dir(comfy_root.join("user")) else {
+        return names;
+    };
+    for user in users.flatten() {
+        let mut stack = vec![user.path().join("workflows")];
+        while let Some(dir) = stack.pop() {
+            let Ok(read_dir) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    stack.push(path);
+                    continue;
+                }
+                let is_json = path
+                    .extension()
+                    .map(|ext| ext.eq_ignore_ascii_case("json"))
+                    .unwrap_or(false);
+                if !is_json {
+                    continue;
+                }
+                let Ok(raw) = fs::read_to_string(&path) else {
+                    continue;
+                };
+                if let Ok(value) = serde_json::from_str::<Value>(&raw) {
+                    collect_model_references(&value, &mut names);
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Model files under the given roots that neither the catalog nor a saved workflow refers to.
+pub fn find_orphaned_models(
+    comfy_root: &Path,
+    model_roots: &[PathBuf],
+    catalog: &ModelCatalog,
+) -> Vec<InventoryEntry> {
+    let mut referenced = catalog_file_names(catalog);
+    referenced.extend(workflow_file_references(comfy_root));
+    scan_model_inventory(model_roots)
+        .into_iter()
+        .filter(|entry| !referenced.contains(&entry.file_name.to_ascii_lowercase()))
+        .collect()
+}
+
+/// Deletes a model file and prunes directories left empty, never climbing above `stop_at`.
+/// Returns the number of bytes freed.
+pub fn remove_model_file(path: &Path, stop_at: &Path) -> Result<u64> {
+    let size = fs::metadata(path)
+        .with_context(|| format!("failed to read metadata for {:?}", path))?
+        .len();
+    fs::remove_file(path).with_context(|| format!("failed to remove {:?}", path))?;
+
+    let mut current = path.parent();
+    while let Some(dir) = current {
+        if dir == stop_at || !dir.starts_with(stop_at) {
+            break;
+        }
+        if fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+    Ok(size)
+}
+
+fn is_model_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            MODEL_FILE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+        .unwrap_or(false)
+}
+
+fn collect_model_references(value: &Value, names: &mut HashSet<String>) {
+    match value {
+        Value::String(text) => {
+            let base = text.rsplit(['/', '\\']).next().unwrap_or(text).trim();
+            if is_model_file(Path::new(base)) {
+                names.insert(base.to_ascii_lowercase());
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_model_references(item, names);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values() {
+                collect_model_references(item, names);
+            }
+        }
+        _ => {}
+    }
+}
@@ -1,10 +1,21 @@
+pub mod agent;
+pub mod api_access;
 pub mod app;
 pub mod catalog;
+pub mod compat;
 pub mod config;
 pub mod download;
 pub mod env_flags;
+pub mod events;
+pub mod faults;
+pub mod hooks;
+pub mod host_stats;
+pub mod inventory;
 pub mod model;
+pub mod perf;
 pub mod preview;
 pub mod ram;
+pub mod removable;
+pub mod secrets;
 pub mod updater;
 pub mod vram;
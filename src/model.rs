@@ -1,5 +1,8 @@
 use crate::{ram::RamTier, vram::VramTier};
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ModelCatalog {
@@ -9,9 +12,26 @@ pub struct ModelCatalog {
     pub loras: Vec<LoraDefinition>,
     #[serde(default)]
     pub workflows: Vec<WorkflowDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub upscalers: Vec<AssetDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub controlnets: Vec<AssetDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embeddings: Vec<AssetDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clip_vision: Vec<AssetDefinition>,
 }
 
 impl ModelCatalog {
+    /// Flags the given LoRA ids as favorites and moves them to the front of the list,
+    /// keeping catalog order within each group.
+    pub fn mark_favorite_loras(&mut self, favorites: &[String]) {
+        for lora in &mut self.loras {
+            lora.favorite = favorites.iter().any(|id| id == &lora.id);
+        }
+        self.loras.sort_by_key(|lora| !lora.favorite);
+    }
+
     pub fn find_model(&self, id: &str) -> Option<&MasterModel> {
         self.models.iter().find(|model| model.id == id)
     }
@@ -56,6 +76,172 @@ impl ModelCatalog {
     pub fn find_workflow(&self, id: &str) -> Option<WorkflowDefinition> {
         self.workflows.iter().find(|workflow| workflow.id == id).cloned()
     }
+
+    pub fn assets(&self, class: AssetClass) -> &[AssetDefinition] {
+        match class {
+            AssetClass::Upscaler => &self.upscalers,
+            AssetClass::Controlnet => &self.controlnets,
+            AssetClass::Embedding => &self.embeddings,
+            AssetClass::ClipVision => &self.clip_vision,
+        }
+    }
+
+    fn assets_mut(&mut self, class: AssetClass) -> &mut Vec<AssetDefinition> {
+        match class {
+            AssetClass::Upscaler => &mut self.upscalers,
+            AssetClass::Controlnet => &mut self.controlnets,
+            AssetClass::Embedding => &mut self.embeddings,
+            AssetClass::ClipVision => &mut self.clip_vision,
+        }
+    }
+
+    pub fn find_asset(&self, class: AssetClass, id: &str) -> Option<AssetDefinition> {
+        self.assets(class)
+            .iter()
+            .find(|asset| asset.id == id)
+            .cloned()
+    }
+
+    /// Merges named catalogs ordered from highest to lowest priority. The first source to
+    /// define an id of a given kind keeps it; definitions from lower-priority sources
+    /// are dropped and reported as conflicts.
+    pub fn merge_layers(
+        layers: Vec<(String, ModelCatalog)>,
+    ) -> (ModelCatalog, Vec<CatalogConflict>) {
+        let mut merged = ModelCatalog {
+            catalog_version: 0,
+            models: Vec::new(),
+            loras: Vec::new(),
+            workflows: Vec::new(),
+            upscalers: Vec::new(),
+            controlnets: Vec::new(),
+            embeddings: Vec::new(),
+            clip_vision: Vec::new(),
+        };
+        let mut owners = HashMap::new();
+        let mut conflicts = Vec::new();
+        for (source, mut catalog) in layers {
+            merged.catalog_version = merged.catalog_version.max(catalog.catalog_version);
+            let mut layer = MergeLayer {
+                source: &source,
+                owners: &mut owners,
+                conflicts: &mut conflicts,
+            };
+            for class in AssetClass::all() {
+                let incoming = std::mem::take(catalog.assets_mut(*class));
+                layer.merge(
+                    class.identifier(),
+                    merged.assets_mut(*class),
+                    incoming,
+                    |a| &a.id,
+                );
+            }
+            layer.merge("model", &mut merged.models, catalog.models, |m| &m.id);
+            layer.merge("lora", &mut merged.loras, catalog.loras, |l| &l.id);
+            layer.merge("workflow", &mut merged.workflows, catalog.workflows, |w| {
+                &w.id
+            });
+        }
+        (merged, conflicts)
+    }
+}
+
+/// An entry defined by more than one catalog source; `kept_source` wins by priority.
+#[derive(Clone, Debug, Serialize)]
+pub struct CatalogConflict {
+    pub kind: String,
+    pub id: String,
+    pub kept_source: String,
+    pub dropped_source: String,
+}
+
+struct MergeLayer<'a> {
+    source: &'a str,
+    owners: &'a mut HashMap<(&'static str, String), String>,
+    conflicts: &'a mut Vec<CatalogConflict>,
+}
+
+impl MergeLayer<'_> {
+    fn merge<T>(
+        &mut self,
+        kind: &'static str,
+        target: &mut Vec<T>,
+        incoming: Vec<T>,
+        id: impl Fn(&T) -> &String,
+    ) {
+        for entry in incoming {
+            let key = (kind, id(&entry).clone());
+            match self.owners.get(&key) {
+                Some(owner) => self.conflicts.push(CatalogConflict {
+                    kind: kind.to_string(),
+                    id: key.1,
+                    kept_source: owner.clone(),
+                    dropped_source: self.source.to_string(),
+                }),
+                None => {
+                    self.owners.insert(key, self.source.to_string());
+                    target.push(entry);
+                }
+            }
+        }
+    }
+}
+
+/// Broad kind of model a family produces; drives tier filtering and folder routing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelFamilyType {
+    #[default]
+    Image,
+    Video,
+    Audio,
+    #[serde(rename = "3d")]
+    ThreeD,
+}
+
+impl ModelFamilyType {
+    pub fn all() -> &'static [ModelFamilyType] {
+        use ModelFamilyType::*;
+        &[Image, Video, Audio, ThreeD]
+    }
+
+    pub const fn identifier(self) -> &'static str {
+        match self {
+            ModelFamilyType::Image => "image",
+            ModelFamilyType::Video => "video",
+            ModelFamilyType::Audio => "audio",
+            ModelFamilyType::ThreeD => "3d",
+        }
+    }
+
+    /// Image and video loaders scan subfolders, so their files are grouped per model.
+    /// Audio and 3D loaders read fixed folders such as `models/audio_encoders` or
+    /// `models/UltraShape`, so their files go straight into the category folder.
+    pub const fn uses_model_subfolders(self) -> bool {
+        matches!(self, ModelFamilyType::Image | ModelFamilyType::Video)
+    }
+
+    /// Audio and 3D variants are small enough that any variant built for a weaker GPU
+    /// also fits a stronger one, rather than requiring an exact tier match.
+    pub const fn accepts_weaker_variants(self) -> bool {
+        matches!(self, ModelFamilyType::Audio | ModelFamilyType::ThreeD)
+    }
+
+    /// RAM tier minimums used when the catalog entry does not set its own.
+    pub const fn default_ram_thresholds(self) -> RamTierThresholds {
+        match self {
+            ModelFamilyType::Audio | ModelFamilyType::ThreeD => RamTierThresholds {
+                tier_a_min_gb: Some(32.0),
+                tier_b_min_gb: Some(16.0),
+                tier_c_min_gb: None,
+            },
+            ModelFamilyType::Image | ModelFamilyType::Video => RamTierThresholds {
+                tier_a_min_gb: None,
+                tier_b_min_gb: None,
+                tier_c_min_gb: None,
+            },
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -63,11 +249,38 @@ pub struct MasterModel {
     pub id: String,
     pub display_name: String,
     pub family: String,
+    #[serde(default)]
+    pub family_type: ModelFamilyType,
     pub variants: Vec<ModelVariant>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub always: Vec<AlwaysGroup>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ram_tier_thresholds: Option<RamTierThresholds>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommended_settings: Option<RecommendedSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+}
+
+/// Generation settings the model author suggests, shown in the model detail panel.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RecommendedSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampler: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduler: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cfg: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
 }
 
 impl MasterModel {
@@ -84,9 +297,12 @@ impl MasterModel {
     }
 
     pub fn variants_for_tier(&self, tier: VramTier) -> Vec<ModelVariant> {
+        let weaker_ok = self.family_type.accepts_weaker_variants();
         self.variants
             .iter()
-            .filter(|variant| variant.tier == tier)
+            .filter(|variant| {
+                variant.tier == tier || (weaker_ok && variant.tier.strength() < tier.strength())
+            })
             .cloned()
             .collect()
     }
@@ -122,12 +338,84 @@ impl MasterModel {
     }
 
     pub fn resolved_ram_thresholds(&self) -> ResolvedRamTierThresholds {
-        ResolvedRamTierThresholds::new(self.ram_tier_thresholds.as_ref())
+        let defaults = self.family_type.default_ram_thresholds();
+        let thresholds = match &self.ram_tier_thresholds {
+            Some(catalog) => RamTierThresholds {
+                tier_a_min_gb: catalog.tier_a_min_gb.or(defaults.tier_a_min_gb),
+                tier_b_min_gb: catalog.tier_b_min_gb.or(defaults.tier_b_min_gb),
+                tier_c_min_gb: catalog.tier_c_min_gb.or(defaults.tier_c_min_gb),
+            },
+            None => defaults,
+        };
+        ResolvedRamTierThresholds::new(Some(&thresholds))
+    }
+
+    /// Folder an artifact of this model is stored in, relative to the ComfyUI root.
+    pub fn artifact_subdir(&self, artifact: &ModelArtifact) -> String {
+        let category = artifact.target_category.comfyui_subdir();
+        if self.family_type.uses_model_subfolders() {
+            format!("{category}/{}", self.id)
+        } else {
+            category
+        }
     }
 
     pub fn ram_tier_range_label(&self, tier: RamTier) -> String {
         self.resolved_ram_thresholds().range_label(tier)
     }
+
+    /// Files of sibling variants that `variant` makes redundant: same folder and model
+    /// size, but a lower quantization than the file replacing it. Shared files (the
+    /// `always` groups or anything `variant` itself installs) are never returned.
+    pub fn superseded_by<'a>(
+        &'a self,
+        variant: &ModelVariant,
+    ) -> Vec<(&'a ModelVariant, &'a ModelArtifact)> {
+        let same_file = |a: &ModelArtifact, b: &ModelArtifact| {
+            a.target_category == b.target_category && a.file_name() == b.file_name()
+        };
+        let shared = |artifact: &ModelArtifact| {
+            variant.artifacts.iter().any(|own| same_file(own, artifact))
+                || self
+                    .always
+                    .iter()
+                    .flat_map(|group| group.artifacts.iter())
+                    .any(|always| same_file(always, artifact))
+        };
+
+        let mut superseded: Vec<(&ModelVariant, &ModelArtifact)> = Vec::new();
+        for sibling in &self.variants {
+            if sibling.same_slot(variant) {
+                continue;
+            }
+            if let (Some(ours), Some(theirs)) = (&variant.model_size, &sibling.model_size) {
+                if !ours.eq_ignore_ascii_case(theirs) {
+                    continue;
+                }
+            }
+            for artifact in &sibling.artifacts {
+                if shared(artifact) || superseded.iter().any(|(_, prev)| same_file(prev, artifact))
+                {
+                    continue;
+                }
+                let Some(rank) = quantization_from_file_name(artifact.file_name())
+                    .and_then(|q| quantization_rank(&q))
+                else {
+                    continue;
+                };
+                let replaced = variant.artifacts.iter().any(|own| {
+                    own.target_category == artifact.target_category
+                        && quantization_from_file_name(own.file_name())
+                            .and_then(|q| quantization_rank(&q))
+                            .is_some_and(|own_rank| own_rank > rank)
+                });
+                if replaced {
+                    superseded.push((sibling, artifact));
+                }
+            }
+        }
+        superseded
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -140,6 +428,152 @@ impl ResolvedModel {
     pub fn artifacts_for_download(&self, ram_tier: Option<RamTier>) -> Vec<ModelArtifact> {
         self.master.artifacts_for_variant(&self.variant, ram_tier)
     }
+
+    /// Every artifact this variant can install, regardless of RAM tier, with the
+    /// ones `artifacts_for_download(ram_tier)` would pick marked as recommended.
+    pub fn artifact_options(&self, ram_tier: Option<RamTier>) -> Vec<ArtifactOption> {
+        let groups = self
+            .master
+            .always
+            .iter()
+            .flat_map(|group| {
+                let label = group.label.clone().unwrap_or_else(|| group.id.clone());
+                group
+                    .artifacts
+                    .iter()
+                    .map(move |artifact| (label.clone(), artifact))
+            })
+            .chain(
+                self.variant
+                    .artifacts
+                    .iter()
+                    .map(|artifact| (self.variant.selection_label(), artifact)),
+            );
+
+        let picked = self.artifacts_for_download(ram_tier);
+        let mut options: Vec<ArtifactOption> = Vec::new();
+        for (group, artifact) in groups {
+            if options.iter().any(|option| option.path == artifact.path) {
+                continue;
+            }
+            options.push(ArtifactOption {
+                path: artifact.path.clone(),
+                file_name: artifact.file_name().to_string(),
+                group,
+                category: artifact.target_category.slug().to_string(),
+                quantization: quantization_from_file_name(artifact.file_name()),
+                min_ram_tier: artifact.min_ram_tier,
+                size_bytes: artifact.size_bytes,
+                recommended: picked.iter().any(|picked| picked.path == artifact.path),
+            });
+        }
+        options
+    }
+
+    /// Artifacts matching an explicit list of catalog paths, bypassing RAM tier
+    /// selection. Unknown paths are an error so a stale selection is not silently
+    /// narrowed.
+    pub fn artifacts_for_selection(&self, paths: &[String]) -> Result<Vec<ModelArtifact>> {
+        let available: Vec<&ModelArtifact> = self
+            .master
+            .always
+            .iter()
+            .flat_map(|group| group.artifacts.iter())
+            .chain(self.variant.artifacts.iter())
+            .collect();
+        let mut selected: Vec<ModelArtifact> = Vec::new();
+        for path in paths {
+            let artifact = available
+                .iter()
+                .find(|artifact| &artifact.path == path)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "artifact {path} is not part of {} / {}",
+                        self.master.id,
+                        self.variant.id
+                    )
+                })?;
+            if !selected.iter().any(|picked| picked.path == artifact.path) {
+                selected.push((*artifact).clone());
+            }
+        }
+        Ok(selected)
+    }
+}
+
+/// One installable file of a variant, as offered for manual quant selection.
+#[derive(Clone, Debug, Serialize)]
+pub struct ArtifactOption {
+    pub path: String,
+    pub file_name: String,
+    pub group: String,
+    pub category: String,
+    pub quantization: Option<String>,
+    pub min_ram_tier: Option<RamTier>,
+    pub size_bytes: Option<u64>,
+    pub recommended: bool,
+}
+
+/// Precision or GGUF quant named in a weight file, e.g. `Q5_K_M` or `fp8_e4m3fn`.
+pub fn quantization_from_file_name(file_name: &str) -> Option<String> {
+    let stem = file_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(file_name);
+    let bytes = stem.as_bytes();
+    for (index, window) in bytes.windows(2).enumerate() {
+        let at_boundary = index == 0 || matches!(bytes[index - 1], b'-' | b'_' | b'.');
+        if at_boundary && matches!(window[0], b'Q' | b'q') && window[1].is_ascii_digit() {
+            let quant = stem[index..].split(['-', '.']).next().unwrap_or_default();
+            return Some(quant.to_ascii_uppercase());
+        }
+    }
+    const PRECISIONS: [&str; 7] = ["nvfp4", "fp4", "fp8", "bf16", "fp16", "fp32", "int8"];
+    let lower = stem.to_ascii_lowercase();
+    let tokens: Vec<&str> = lower.split(['-', '_', '.']).collect();
+    tokens.iter().enumerate().find_map(|(index, token)| {
+        PRECISIONS.contains(token).then(|| {
+            // Keep the fp8 format suffix (e4m3fn / e5m2) when present.
+            match tokens.get(index + 1) {
+                Some(next) if next.starts_with("e4m3") || next.starts_with("e5m2") => {
+                    format!("{token}_{next}")
+                }
+                _ => token.to_string(),
+            }
+        })
+    })
+}
+
+/// Orders quantizations by how much of the original weights they keep, so a Q8 file
+/// outranks a Q4 one and fp16 outranks fp8. Unknown labels have no rank.
+pub fn quantization_rank(quant: &str) -> Option<u32> {
+    let lower = quant.trim().to_ascii_lowercase();
+    if let Some(rest) = lower.strip_prefix('q') {
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        let bits: u32 = digits.parse().ok()?;
+        // K-quant sizes and the legacy _0/_1 variants sit between whole bit widths.
+        let suffix = &rest[digits.len()..];
+        let bump = if suffix.contains("_k_l") {
+            4
+        } else if suffix.contains("_k_m") {
+            3
+        } else if suffix.contains("_k") {
+            2
+        } else if suffix.starts_with("_1") {
+            1
+        } else {
+            0
+        };
+        return Some(bits * 10 + bump);
+    }
+    let base = lower.split('_').next().unwrap_or_default();
+    match base {
+        "nvfp4" | "fp4" => Some(45),
+        "fp8" | "int8" => Some(85),
+        "bf16" | "fp16" => Some(160),
+        "fp32" => Some(320),
+        _ => None,
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -173,6 +607,36 @@ impl RamTierThresholds {
     pub fn is_empty(&self) -> bool {
         self.tier_a_min_gb.is_none() && self.tier_b_min_gb.is_none() && self.tier_c_min_gb.is_none()
     }
+
+    /// Rejects negative values and thresholds that would make a weaker tier require
+    /// more RAM than a stronger one once combined with the defaults.
+    pub fn validate(&self) -> Result<()> {
+        for tier in RamTier::all() {
+            if let Some(value) = self.min_for(*tier) {
+                if !value.is_finite() || value < 0.0 {
+                    return Err(anyhow!(
+                        "{} minimum must be a positive number of GB",
+                        tier.label()
+                    ));
+                }
+            }
+        }
+        let resolved = ResolvedRamTierThresholds::new(Some(self));
+        for tier in RamTier::all() {
+            if let Some(stronger) = tier.next_stronger() {
+                if resolved.min(*tier) >= resolved.min(stronger) {
+                    return Err(anyhow!(
+                        "{} minimum ({} GB) must be below the {} minimum ({} GB)",
+                        tier.label(),
+                        format_gb(resolved.min(*tier)),
+                        stronger.label(),
+                        format_gb(resolved.min(stronger))
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -198,10 +662,52 @@ impl ResolvedRamTierThresholds {
         Self { mins }
     }
 
+    /// Applies user overrides from settings on top of catalog or default thresholds.
+    /// Overrides are only validated against the defaults, so a model's own thresholds
+    /// can end up out of order around them; those are clamped to the overridden values
+    /// (the user's numbers win), which at worst leaves a tier no machine falls into.
+    pub fn with_user_overrides(mut self, overrides: Option<&RamTierThresholds>) -> Self {
+        let Some(overrides) = overrides else {
+            return self;
+        };
+        let mut overridden = [false; 3];
+        for tier in RamTier::all() {
+            if let Some(value) = overrides.min_for(*tier) {
+                self.mins[tier.index()] = value;
+                overridden[tier.index()] = true;
+            }
+        }
+        // Index 0 is the strongest tier; minimums must not rise towards weaker tiers.
+        for stronger in (0..self.mins.len() - 1).rev() {
+            let weaker = stronger + 1;
+            if self.mins[weaker] > self.mins[stronger]
+                && overridden[weaker]
+                && !overridden[stronger]
+            {
+                self.mins[stronger] = self.mins[weaker];
+            }
+        }
+        for stronger in 0..self.mins.len() - 1 {
+            let weaker = stronger + 1;
+            if self.mins[weaker] > self.mins[stronger] {
+                self.mins[weaker] = self.mins[stronger];
+            }
+        }
+        self
+    }
+
     pub fn min(&self, tier: RamTier) -> f64 {
         self.mins[tier.index()]
     }
 
+    pub fn tier_for(&self, total_gb: f64) -> RamTier {
+        RamTier::all()
+            .iter()
+            .copied()
+            .find(|tier| total_gb >= self.min(*tier))
+            .unwrap_or(RamTier::TierC)
+    }
+
     pub fn range_label(&self, tier: RamTier) -> String {
         let min = self.min(tier);
         if let Some(next) = tier.next_stronger() {
@@ -237,6 +743,13 @@ pub struct LoraDefinition {
     pub note: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub file_name: Option<String>,
+    /// Set on catalog snapshots handed to the UI; never read from catalog files.
+    #[serde(
+        default,
+        skip_deserializing,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub favorite: bool,
 }
 
 impl LoraDefinition {
@@ -247,7 +760,7 @@ impl LoraDefinition {
 
         let url = self.download_url.trim();
         let last_segment = url
-            .rsplit(|c| c == '/' || c == '\\')
+            .rsplit(['/', '\\'])
             .next()
             .unwrap_or("lora.safetensors");
         let cleaned = last_segment.split('?').next().unwrap_or(last_segment);
@@ -287,6 +800,107 @@ pub struct WorkflowDefinition {
     pub youtube_url: Option<String>,
 }
 
+/// Asset classes that are downloaded as single files straight into a ComfyUI model folder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetClass {
+    Upscaler,
+    Controlnet,
+    Embedding,
+    ClipVision,
+}
+
+impl AssetClass {
+    pub fn all() -> &'static [AssetClass] {
+        use AssetClass::*;
+        &[Upscaler, Controlnet, Embedding, ClipVision]
+    }
+
+    pub const fn identifier(self) -> &'static str {
+        match self {
+            AssetClass::Upscaler => "upscaler",
+            AssetClass::Controlnet => "controlnet",
+            AssetClass::Embedding => "embedding",
+            AssetClass::ClipVision => "clip_vision",
+        }
+    }
+
+    pub fn from_identifier(value: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|class| class.identifier() == value.trim())
+    }
+
+    /// Key of the class's array in catalog JSON.
+    pub const fn catalog_key(self) -> &'static str {
+        match self {
+            AssetClass::Upscaler => "upscalers",
+            AssetClass::Controlnet => "controlnets",
+            AssetClass::Embedding => "embeddings",
+            AssetClass::ClipVision => "clip_vision",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            AssetClass::Upscaler => "Upscalers",
+            AssetClass::Controlnet => "ControlNets",
+            AssetClass::Embedding => "Embeddings",
+            AssetClass::ClipVision => "CLIP Vision",
+        }
+    }
+
+    pub const fn comfyui_subdir(self) -> &'static str {
+        match self {
+            AssetClass::Upscaler => "models/upscale_models",
+            AssetClass::Controlnet => "models/controlnet",
+            AssetClass::Embedding => "models/embeddings",
+            AssetClass::ClipVision => "models/clip_vision",
+        }
+    }
+}
+
+/// A single-file asset (upscaler, ControlNet, embedding or CLIP vision model).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AssetDefinition {
+    pub id: String,
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    pub download_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_url: Option<String>,
+}
+
+impl AssetDefinition {
+    pub fn derived_file_name(&self) -> String {
+        if let Some(file) = &self.file_name {
+            return file.clone();
+        }
+        let last_segment = self
+            .download_url
+            .trim()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default();
+        let cleaned = last_segment.split('?').next().unwrap_or(last_segment);
+        if cleaned.is_empty() {
+            format!("{}.safetensors", self.id)
+        } else {
+            cleaned.to_string()
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ModelVariant {
     pub id: String,
@@ -297,10 +911,69 @@ pub struct ModelVariant {
     pub quantization: Option<String>,
     #[serde(default)]
     pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notices: Vec<VariantNotice>,
     pub artifacts: Vec<ModelArtifact>,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeLevel {
+    #[default]
+    Note,
+    Warning,
+}
+
+impl NoticeLevel {
+    pub fn all() -> &'static [NoticeLevel] {
+        &[NoticeLevel::Note, NoticeLevel::Warning]
+    }
+
+    pub const fn identifier(self) -> &'static str {
+        match self {
+            NoticeLevel::Note => "note",
+            NoticeLevel::Warning => "warning",
+        }
+    }
+}
+
+/// Installation caveat shown before a variant is downloaded (minimum ComfyUI version,
+/// custom nodes the example workflow needs, ...). Warnings must be acknowledged.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VariantNotice {
+    #[serde(default)]
+    pub level: NoticeLevel,
+    pub message: String,
+}
+
 impl ModelVariant {
+    /// Identifies the exact set of warnings a user acknowledged, so a catalog update that
+    /// adds or rewords one asks again. `None` when the variant has no warnings.
+    pub fn warnings_fingerprint(&self, model_id: &str) -> Option<String> {
+        let mut hasher = Sha256::new();
+        let mut any = false;
+        for notice in self
+            .notices
+            .iter()
+            .filter(|notice| notice.level == NoticeLevel::Warning)
+        {
+            hasher.update(notice.message.trim().as_bytes());
+            hasher.update([0]);
+            any = true;
+        }
+        if !any {
+            return None;
+        }
+        let digest = hasher.finalize();
+        let short: String = digest[..6].iter().map(|b| format!("{b:02x}")).collect();
+        Some(format!("{model_id}/{}#{short}", self.id))
+    }
+
+    /// Variant ids are only unique within a VRAM tier.
+    fn same_slot(&self, other: &ModelVariant) -> bool {
+        self.id == other.id && self.tier == other.tier
+    }
+
     pub fn selection_label(&self) -> String {
         let mut parts = Vec::new();
         if let Some(size) = &self.model_size {
@@ -506,3 +1179,676 @@ impl<'de> Deserialize<'de> for TargetCategory {
         Ok(TargetCategory::from_slug(&value))
     }
 }
+
+/// An entry added to or removed from the catalog. `parent` is the model id for variants.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogChangeEntry {
+    pub id: String,
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+/// A download location that moved between two catalog revisions.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogUrlChange {
+    pub kind: String,
+    pub id: String,
+    pub old_url: String,
+    pub new_url: String,
+}
+
+/// Differences between two catalog revisions, as shown in the "what's new" view.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CatalogChanges {
+    /// Unix timestamp (seconds) of the refresh that produced this diff.
+    #[serde(default)]
+    pub detected_at: u64,
+    #[serde(default)]
+    pub added_models: Vec<CatalogChangeEntry>,
+    #[serde(default)]
+    pub removed_models: Vec<CatalogChangeEntry>,
+    #[serde(default)]
+    pub added_variants: Vec<CatalogChangeEntry>,
+    #[serde(default)]
+    pub removed_variants: Vec<CatalogChangeEntry>,
+    #[serde(default)]
+    pub added_loras: Vec<CatalogChangeEntry>,
+    #[serde(default)]
+    pub removed_loras: Vec<CatalogChangeEntry>,
+    #[serde(default)]
+    pub added_workflows: Vec<CatalogChangeEntry>,
+    #[serde(default)]
+    pub removed_workflows: Vec<CatalogChangeEntry>,
+    #[serde(default)]
+    pub changed_urls: Vec<CatalogUrlChange>,
+}
+
+impl CatalogChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added_models.is_empty()
+            && self.removed_models.is_empty()
+            && self.added_variants.is_empty()
+            && self.removed_variants.is_empty()
+            && self.added_loras.is_empty()
+            && self.removed_loras.is_empty()
+            && self.added_workflows.is_empty()
+            && self.removed_workflows.is_empty()
+            && self.changed_urls.is_empty()
+    }
+}
+
+/// Compares two catalog revisions. Variants of newly added or removed models are folded
+/// into the model entry rather than listed again.
+pub fn diff_catalogs(old: &ModelCatalog, new: &ModelCatalog) -> CatalogChanges {
+    let mut changes = CatalogChanges::default();
+    let entry = |id: &str, display_name: &str, parent: Option<&str>| CatalogChangeEntry {
+        id: id.to_string(),
+        display_name: display_name.to_string(),
+        parent: parent.map(str::to_string),
+    };
+
+    for model in &new.models {
+        let Some(previous) = old.find_model(&model.id) else {
+            changes
+                .added_models
+                .push(entry(&model.id, &model.display_name, None));
+            continue;
+        };
+        for variant in &model.variants {
+            let Some(previous_variant) = previous.variants.iter().find(|v| v.same_slot(variant))
+            else {
+                changes.added_variants.push(entry(
+                    &variant.id,
+                    &variant.selection_label(),
+                    Some(&model.id),
+                ));
+                continue;
+            };
+            for artifact in &variant.artifacts {
+                let moved = previous_variant
+                    .artifacts
+                    .iter()
+                    .find(|a| a.file_name() == artifact.file_name())
+                    .map(artifact_source)
+                    .filter(|old_url| *old_url != artifact_source(artifact));
+                if let Some(old_url) = moved {
+                    changes.changed_urls.push(CatalogUrlChange {
+                        kind: "artifact".to_string(),
+                        id: format!("{}/{}/{}", model.id, variant.id, artifact.file_name()),
+                        old_url,
+                        new_url: artifact_source(artifact),
+                    });
+                }
+            }
+        }
+        for variant in &previous.variants {
+            if !model.variants.iter().any(|v| v.same_slot(variant)) {
+                changes.removed_variants.push(entry(
+                    &variant.id,
+                    &variant.selection_label(),
+                    Some(&model.id),
+                ));
+            }
+        }
+    }
+    for model in &old.models {
+        if new.find_model(&model.id).is_none() {
+            changes
+                .removed_models
+                .push(entry(&model.id, &model.display_name, None));
+        }
+    }
+
+    for lora in &new.loras {
+        match old.loras.iter().find(|l| l.id == lora.id) {
+            None => changes
+                .added_loras
+                .push(entry(&lora.id, &lora.display_name, None)),
+            Some(previous) if previous.download_url != lora.download_url => {
+                changes.changed_urls.push(CatalogUrlChange {
+                    kind: "lora".to_string(),
+                    id: lora.id.clone(),
+                    old_url: previous.download_url.clone(),
+                    new_url: lora.download_url.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for lora in &old.loras {
+        if !new.loras.iter().any(|l| l.id == lora.id) {
+            changes
+                .removed_loras
+                .push(entry(&lora.id, &lora.display_name, None));
+        }
+    }
+
+    for workflow in &new.workflows {
+        match old.workflows.iter().find(|w| w.id == workflow.id) {
+            None => changes
+                .added_workflows
+                .push(entry(&workflow.id, &workflow.display_name, None)),
+            Some(previous) if previous.workflow_json_url != workflow.workflow_json_url => {
+                changes.changed_urls.push(CatalogUrlChange {
+                    kind: "workflow".to_string(),
+                    id: workflow.id.clone(),
+                    old_url: previous.workflow_json_url.clone(),
+                    new_url: workflow.workflow_json_url.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for workflow in &old.workflows {
+        if !new.workflows.iter().any(|w| w.id == workflow.id) {
+            changes
+                .removed_workflows
+                .push(entry(&workflow.id, &workflow.display_name, None));
+        }
+    }
+
+    changes
+}
+
+fn artifact_source(artifact: &ModelArtifact) -> String {
+    match artifact.direct_url.as_deref().filter(|url| !url.is_empty()) {
+        Some(url) => url.to_string(),
+        None => format!("{}/{}", artifact.repo, artifact.path),
+    }
+}
+
+/// A single problem found while validating catalog JSON.
+#[derive(Clone, Debug, Serialize)]
+pub struct CatalogIssue {
+    pub line: Option<usize>,
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for CatalogIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}: {}", self.field, self.message),
+            None => write!(f, "{}: {}", self.field, self.message),
+        }
+    }
+}
+
+/// Checks raw catalog JSON for problems serde alone would not explain well: syntax errors,
+/// unknown tier identifiers, missing download URLs and duplicate IDs. Issues carry the
+/// JSON field path and, where it can be located, the line in the source text.
+pub fn validate_catalog_json(raw: &str) -> Vec<CatalogIssue> {
+    let value: serde_json::Value = match serde_json::from_str(raw) {
+        Ok(value) => value,
+        Err(err) => {
+            return vec![CatalogIssue {
+                line: Some(err.line()),
+                field: "(document)".to_string(),
+                message: format!("invalid JSON: {err}"),
+            }]
+        }
+    };
+
+    let mut validator = CatalogValidator {
+        raw,
+        entry: 0,
+        issues: Vec::new(),
+    };
+    validator.check_root(&value);
+
+    if validator.issues.is_empty() {
+        if let Err(err) = serde_json::from_str::<ModelCatalog>(raw) {
+            validator.issues.push(CatalogIssue {
+                line: Some(err.line()),
+                field: "(document)".to_string(),
+                message: err.to_string(),
+            });
+        }
+    }
+    validator.issues
+}
+
+struct CatalogValidator<'a> {
+    raw: &'a str,
+    /// Offset of the entry being checked; anchors are looked up from here on.
+    entry: usize,
+    issues: Vec<CatalogIssue>,
+}
+
+impl CatalogValidator<'_> {
+    /// Points anchor lookups at the value `path` leads to from `from`, returning the
+    /// previous entry offset so nested entries can restore it.
+    fn enter(&mut self, from: usize, path: &[JsonStep]) -> usize {
+        let previous = self.entry;
+        self.entry = value_offset(self.raw, from, path).unwrap_or(from);
+        previous
+    }
+
+    fn push(&mut self, field: String, anchor: Option<&str>, message: impl Into<String>) {
+        let line = anchor.and_then(|needle| line_of(self.raw, self.entry, needle));
+        self.issues.push(CatalogIssue {
+            line,
+            field,
+            message: message.into(),
+        });
+    }
+
+    fn check_root(&mut self, value: &serde_json::Value) {
+        let Some(root) = value.as_object() else {
+            self.push(
+                "(document)".to_string(),
+                None,
+                "catalog must be a JSON object",
+            );
+            return;
+        };
+        if !root.get("catalog_version").is_some_and(|v| v.is_u64()) {
+            self.push(
+                "catalog_version".to_string(),
+                Some("\"catalog_version\""),
+                "missing or not a non-negative integer",
+            );
+        }
+        match root.get("models").and_then(|v| v.as_array()) {
+            Some(models) => {
+                let mut ids = Vec::new();
+                for (index, model) in models.iter().enumerate() {
+                    self.enter(0, &[JsonStep::Key("models"), JsonStep::Index(index)]);
+                    self.check_model(&format!("models[{index}]"), model, &mut ids);
+                }
+            }
+            None => self.push("models".to_string(), None, "missing `models` array"),
+        }
+        if let Some(loras) = root.get("loras").and_then(|v| v.as_array()) {
+            let mut ids = Vec::new();
+            for (index, lora) in loras.iter().enumerate() {
+                self.enter(0, &[JsonStep::Key("loras"), JsonStep::Index(index)]);
+                let field = format!("loras[{index}]");
+                let id = self.check_id(&field, lora, &mut ids);
+                self.check_url(&field, id.as_deref(), lora, "download_url");
+            }
+        }
+        if let Some(workflows) = root.get("workflows").and_then(|v| v.as_array()) {
+            let mut ids = Vec::new();
+            for (index, workflow) in workflows.iter().enumerate() {
+                self.enter(0, &[JsonStep::Key("workflows"), JsonStep::Index(index)]);
+                let field = format!("workflows[{index}]");
+                let id = self.check_id(&field, workflow, &mut ids);
+                self.check_url(&field, id.as_deref(), workflow, "workflow_json_url");
+            }
+        }
+        for class in AssetClass::all() {
+            let key = class.catalog_key();
+            let Some(assets) = root.get(key).and_then(|v| v.as_array()) else {
+                continue;
+            };
+            let mut ids = Vec::new();
+            for (index, asset) in assets.iter().enumerate() {
+                self.enter(0, &[JsonStep::Key(key), JsonStep::Index(index)]);
+                let field = format!("{key}[{index}]");
+                let id = self.check_id(&field, asset, &mut ids);
+                self.check_url(&field, id.as_deref(), asset, "download_url");
+            }
+        }
+    }
+
+    fn check_model(&mut self, field: &str, model: &serde_json::Value, ids: &mut Vec<String>) {
+        let id = self.check_id(field, model, ids);
+        let anchor = id.as_deref().map(id_anchor);
+        if let Some(thresholds) = model.get("ram_tier_thresholds") {
+            if !thresholds.is_null() && !thresholds.is_object() {
+                self.push(
+                    format!("{field}.ram_tier_thresholds"),
+                    anchor.as_deref(),
+                    "must be an object",
+                );
+            }
+        }
+        if let Some(settings) = model.get("recommended_settings") {
+            if !settings.is_null() && !settings.is_object() {
+                self.push(
+                    format!("{field}.recommended_settings"),
+                    anchor.as_deref(),
+                    "must be an object",
+                );
+            }
+        }
+        if let Some(kind) = model.get("family_type").filter(|v| !v.is_null()) {
+            let known = kind.as_str().is_some_and(|kind| {
+                ModelFamilyType::all()
+                    .iter()
+                    .any(|t| t.identifier() == kind)
+            });
+            if !known {
+                self.push(
+                    format!("{field}.family_type"),
+                    anchor.as_deref(),
+                    format!(
+                        "unknown family type {kind} (expected one of {})",
+                        ModelFamilyType::all()
+                            .iter()
+                            .map(|t| t.identifier())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                );
+            }
+        }
+        if model.get("source_url").is_some_and(|url| !url.is_null()) {
+            self.check_url(field, id.as_deref(), model, "source_url");
+        }
+
+        let Some(variants) = model.get("variants").and_then(|v| v.as_array()) else {
+            self.push(
+                format!("{field}.variants"),
+                anchor.as_deref(),
+                "missing `variants` array",
+            );
+            return;
+        };
+        let mut variant_ids = Vec::new();
+        let model_entry = self.entry;
+        for (index, variant) in variants.iter().enumerate() {
+            self.enter(
+                model_entry,
+                &[JsonStep::Key("variants"), JsonStep::Index(index)],
+            );
+            let variant_field = format!("{field}.variants[{index}]");
+            let tier = variant.get("tier").and_then(|v| v.as_str());
+            let variant_id = self.check_scoped_id(&variant_field, variant, tier, &mut variant_ids);
+            let variant_anchor = variant_id.as_deref().map(id_anchor);
+            match tier {
+                Some(tier) if VramTier::from_identifier(tier).is_some() => {}
+                Some(tier) => self.push(
+                    format!("{variant_field}.tier"),
+                    variant_anchor.as_deref(),
+                    format!(
+                        "unknown VRAM tier `{tier}` (expected one of {})",
+                        VramTier::all()
+                            .iter()
+                            .map(|tier| tier.identifier())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                ),
+                None => self.push(
+                    format!("{variant_field}.tier"),
+                    variant_anchor.as_deref(),
+                    "missing VRAM tier",
+                ),
+            }
+            self.check_notices(&variant_field, variant_anchor.as_deref(), variant);
+            self.check_artifacts(&variant_field, variant_anchor.as_deref(), variant);
+        }
+        self.entry = model_entry;
+
+        if let Some(groups) = model.get("always").and_then(|v| v.as_array()) {
+            for (index, group) in groups.iter().enumerate() {
+                self.check_artifacts(
+                    &format!("{field}.always[{index}]"),
+                    anchor.as_deref(),
+                    group,
+                );
+            }
+        }
+    }
+
+    fn check_notices(&mut self, field: &str, anchor: Option<&str>, variant: &serde_json::Value) {
+        let Some(notices) = variant.get("notices").filter(|v| !v.is_null()) else {
+            return;
+        };
+        let Some(notices) = notices.as_array() else {
+            self.push(format!("{field}.notices"), anchor, "must be an array");
+            return;
+        };
+        for (index, notice) in notices.iter().enumerate() {
+            let notice_field = format!("{field}.notices[{index}]");
+            let message = notice.get("message").and_then(|v| v.as_str());
+            if message.map_or(true, |message| message.trim().is_empty()) {
+                self.push(format!("{notice_field}.message"), anchor, "missing message");
+            }
+            if let Some(level) = notice.get("level").filter(|v| !v.is_null()) {
+                let known = level.as_str().is_some_and(|level| {
+                    NoticeLevel::all()
+                        .iter()
+                        .any(|known| known.identifier() == level)
+                });
+                if !known {
+                    self.push(
+                        format!("{notice_field}.level"),
+                        anchor,
+                        format!("unknown notice level {level} (expected note or warning)"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_artifacts(&mut self, field: &str, anchor: Option<&str>, parent: &serde_json::Value) {
+        let Some(artifacts) = parent.get("artifacts").and_then(|v| v.as_array()) else {
+            self.push(
+                format!("{field}.artifacts"),
+                anchor,
+                "missing `artifacts` array",
+            );
+            return;
+        };
+        for (index, artifact) in artifacts.iter().enumerate() {
+            let artifact_field = format!("{field}.artifacts[{index}]");
+            let path = artifact.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            let path_anchor = (!path.is_empty()).then(|| format!("\"{path}\""));
+            let artifact_anchor = path_anchor.as_deref().or(anchor);
+            let repo = artifact.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+            let direct = artifact
+                .get("direct_url")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            if path.trim().is_empty() {
+                self.push(
+                    format!("{artifact_field}.path"),
+                    artifact_anchor,
+                    "missing artifact path",
+                );
+            }
+            if direct.trim().is_empty() && repo.trim().is_empty() {
+                self.push(
+                    artifact_field.clone(),
+                    artifact_anchor,
+                    "missing download source (`repo` or `direct_url`)",
+                );
+            }
+            if artifact
+                .get("target_category")
+                .and_then(|v| v.as_str())
+                .map_or(true, |v| v.trim().is_empty())
+            {
+                self.push(
+                    format!("{artifact_field}.target_category"),
+                    artifact_anchor,
+                    "missing target category",
+                );
+            }
+            if let Some(tier) = artifact.get("min_ram_tier").filter(|v| !v.is_null()) {
+                let known = tier.as_str().and_then(RamTier::from_identifier).is_some();
+                if !known {
+                    self.push(
+                        format!("{artifact_field}.min_ram_tier"),
+                        artifact_anchor,
+                        format!(
+                            "unknown RAM tier {tier} (expected one of {})",
+                            RamTier::all()
+                                .iter()
+                                .map(|tier| tier.identifier())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_id(
+        &mut self,
+        field: &str,
+        value: &serde_json::Value,
+        seen: &mut Vec<String>,
+    ) -> Option<String> {
+        self.check_scoped_id(field, value, None, seen)
+    }
+
+    /// Variants may reuse an id across tiers, so their uniqueness is scoped by tier.
+    fn check_scoped_id(
+        &mut self,
+        field: &str,
+        value: &serde_json::Value,
+        scope: Option<&str>,
+        seen: &mut Vec<String>,
+    ) -> Option<String> {
+        match value.get("id").and_then(|v| v.as_str()).map(str::trim) {
+            Some(id) if !id.is_empty() => {
+                let key = match scope {
+                    Some(scope) => format!("{id}@{scope}"),
+                    None => id.to_string(),
+                };
+                if seen.contains(&key) {
+                    let message = match scope {
+                        Some(scope) => format!("duplicate id `{id}` for tier `{scope}`"),
+                        None => format!("duplicate id `{id}`"),
+                    };
+                    self.push(format!("{field}.id"), Some(&id_anchor(id)), message);
+                } else {
+                    seen.push(key);
+                }
+                Some(id.to_string())
+            }
+            _ => {
+                self.push(format!("{field}.id"), None, "missing id");
+                None
+            }
+        }
+    }
+
+    fn check_url(&mut self, field: &str, id: Option<&str>, value: &serde_json::Value, key: &str) {
+        let url = value.get(key).and_then(|v| v.as_str()).unwrap_or("").trim();
+        let anchor = id.map(id_anchor);
+        if url.is_empty() {
+            self.push(format!("{field}.{key}"), anchor.as_deref(), "missing URL");
+        } else if !url.starts_with("https://") && !url.starts_with("http://") {
+            self.push(
+                format!("{field}.{key}"),
+                anchor.as_deref(),
+                format!("`{url}` is not an http(s) URL"),
+            );
+        }
+    }
+}
+
+fn id_anchor(id: &str) -> String {
+    format!("\"{id}\"")
+}
+
+/// 1-based line of the first occurrence of `needle` at or after `from`, the offset of
+/// the entry being reported, so a repeated id points at the entry that repeats it.
+fn line_of(raw: &str, from: usize, needle: &str) -> Option<usize> {
+    raw.get(from..)?
+        .find(needle)
+        .map(|offset| raw[..from + offset].matches('\n').count() + 1)
+}
+
+enum JsonStep<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Byte offset of the value `path` leads to, starting at the value at `from`. `raw`
+/// must be valid JSON; `None` when the path does not exist.
+fn value_offset(raw: &str, from: usize, path: &[JsonStep]) -> Option<usize> {
+    let bytes = raw.as_bytes();
+    let mut pos = skip_json_whitespace(bytes, from);
+    for step in path {
+        match (step, *bytes.get(pos)?) {
+            (JsonStep::Key(key), b'{') => loop {
+                pos = skip_json_whitespace(bytes, pos + 1);
+                if bytes.get(pos)? == &b'}' {
+                    return None;
+                }
+                let key_end = skip_json_value(bytes, pos)?;
+                let name: String = serde_json::from_str(&raw[pos..key_end]).ok()?;
+                // Past the `:` that follows the key.
+                pos = skip_json_whitespace(bytes, skip_json_whitespace(bytes, key_end) + 1);
+                if name == *key {
+                    break;
+                }
+                pos = skip_json_whitespace(bytes, skip_json_value(bytes, pos)?);
+                if bytes.get(pos)? != &b',' {
+                    return None;
+                }
+            },
+            (JsonStep::Index(index), b'[') => {
+                pos = skip_json_whitespace(bytes, pos + 1);
+                for _ in 0..*index {
+                    pos = skip_json_whitespace(bytes, skip_json_value(bytes, pos)?);
+                    if bytes.get(pos)? != &b',' {
+                        return None;
+                    }
+                    pos = skip_json_whitespace(bytes, pos + 1);
+                }
+                if bytes.get(pos)? == &b']' {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(pos)
+}
+
+fn skip_json_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while bytes.get(pos).is_some_and(u8::is_ascii_whitespace) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Offset just past the JSON value that starts at `pos`.
+fn skip_json_value(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let start = pos;
+    loop {
+        let Some(&byte) = bytes.get(pos) else {
+            return (depth == 0 && !in_string).then_some(pos);
+        };
+        if in_string {
+            match byte {
+                b'\\' => pos += 1,
+                b'"' => {
+                    in_string = false;
+                    if depth == 0 {
+                        return Some(pos + 1);
+                    }
+                }
+                _ => {}
+            }
+            pos += 1;
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' if depth == 0 => return Some(pos),
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos + 1);
+                }
+            }
+            b',' if depth == 0 => return Some(pos),
+            byte if depth == 0 && pos > start && byte.is_ascii_whitespace() => return Some(pos),
+            _ => {}
+        }
+        pos += 1;
+    }
+}
@@ -0,0 +1,122 @@
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+/// One startup phase, timed once per process.
+#[derive(Clone, Debug, Serialize)]
+pub struct StartupTiming {
+    pub phase: String,
+    /// When the phase began, relative to the first profiler call (the top of `main`).
+    pub started_at_ms: u64,
+    pub duration_ms: u64,
+}
+
+/// Aggregated latency of one Tauri command since launch.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CommandTiming {
+    pub command: String,
+    pub calls: u64,
+    pub total_ms: u64,
+    pub mean_ms: u64,
+    pub max_ms: u64,
+    pub last_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PerfProfile {
+    pub uptime_ms: u64,
+    pub startup: Vec<StartupTiming>,
+    /// Slowest first, by total time spent.
+    pub commands: Vec<CommandTiming>,
+}
+
+#[derive(Default)]
+struct Profiler {
+    startup: Vec<StartupTiming>,
+    commands: BTreeMap<String, CommandTiming>,
+}
+
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+static PROFILER: OnceLock<Mutex<Profiler>> = OnceLock::new();
+
+fn epoch() -> Instant {
+    *EPOCH.get_or_init(Instant::now)
+}
+
+fn profiler() -> &'static Mutex<Profiler> {
+    PROFILER.get_or_init(|| Mutex::new(Profiler::default()))
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().min(u64::MAX as u128) as u64
+}
+
+/// Pins the profiler's zero point; call first thing in `main`.
+pub fn start() {
+    epoch();
+}
+
+/// Records a startup phase that began at `started`. Later records of the same phase
+/// are ignored, so "first probe" style timings can be reported from any call site.
+pub fn record_startup(phase: &str, started: Instant) {
+    let duration_ms = millis(started.elapsed());
+    let started_at_ms = millis(started.saturating_duration_since(epoch()));
+    let Ok(mut profiler) = profiler().lock() else {
+        return;
+    };
+    if profiler.startup.iter().any(|timing| timing.phase == phase) {
+        return;
+    }
+    profiler.startup.push(StartupTiming {
+        phase: phase.to_string(),
+        started_at_ms,
+        duration_ms,
+    });
+}
+
+pub fn time_startup<T>(phase: &str, op: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = op();
+    record_startup(phase, started);
+    result
+}
+
+pub fn record_command(command: &str, elapsed: Duration) {
+    let elapsed_ms = millis(elapsed);
+    let Ok(mut profiler) = profiler().lock() else {
+        return;
+    };
+    let timing = profiler
+        .commands
+        .entry(command.to_string())
+        .or_insert_with(|| CommandTiming {
+            command: command.to_string(),
+            ..CommandTiming::default()
+        });
+    timing.calls += 1;
+    timing.total_ms = timing.total_ms.saturating_add(elapsed_ms);
+    timing.mean_ms = timing.total_ms / timing.calls;
+    timing.max_ms = timing.max_ms.max(elapsed_ms);
+    timing.last_ms = elapsed_ms;
+}
+
+pub fn snapshot() -> PerfProfile {
+    let uptime_ms = millis(epoch().elapsed());
+    let Ok(profiler) = profiler().lock() else {
+        return PerfProfile {
+            uptime_ms,
+            startup: Vec::new(),
+            commands: Vec::new(),
+        };
+    };
+    let mut commands: Vec<CommandTiming> = profiler.commands.values().cloned().collect();
+    commands.sort_by_key(|timing| std::cmp::Reverse(timing.total_ms));
+    PerfProfile {
+        uptime_ms,
+        startup: profiler.startup.clone(),
+        commands,
+    }
+}
@@ -0,0 +1,144 @@
+use std::{
+    fs, io,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+};
+
+/// One entry from `/proc/self/mountinfo`.
+#[derive(Clone, Debug)]
+pub struct MountEntry {
+    pub mount_point: PathBuf,
+    pub source: String,
+    pub fs_type: String,
+}
+
+impl MountEntry {
+    /// USB sticks report `removable=1`; USB hard drives and card readers often do not,
+    /// so anything on a USB bus or auto-mounted under `/media` counts as well.
+    pub fn is_removable(&self) -> bool {
+        let mount = self.mount_point.to_string_lossy();
+        if mount.starts_with("/media/") || mount.starts_with("/run/media/") {
+            return true;
+        }
+        block_device_is_removable(&self.source)
+    }
+}
+
+fn unescape_mount_field(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let digits: String = chars.clone().take(3).collect();
+            if digits.len() == 3 {
+                if let Ok(value) = u8::from_str_radix(&digits, 8) {
+                    out.push(value as char);
+                    chars.nth(2);
+                    continue;
+                }
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn parse_mountinfo(contents: &str) -> Vec<MountEntry> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (left, right) = line.split_once(" - ")?;
+            let mount_point = left.split_whitespace().nth(4)?;
+            let mut right = right.split_whitespace();
+            let fs_type = right.next()?;
+            let source = right.next().unwrap_or_default();
+            Some(MountEntry {
+                mount_point: PathBuf::from(unescape_mount_field(mount_point)),
+                source: unescape_mount_field(source),
+                fs_type: fs_type.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn mounts() -> Vec<MountEntry> {
+    fs::read_to_string("/proc/self/mountinfo")
+        .map(|contents| parse_mountinfo(&contents))
+        .unwrap_or_default()
+}
+
+fn block_device_is_removable(source: &str) -> bool {
+    if !source.starts_with("/dev/") {
+        return false;
+    }
+    let Ok(device) = fs::canonicalize(source) else {
+        return false;
+    };
+    let Some(name) = device.file_name() else {
+        return false;
+    };
+    let Ok(sys_path) = fs::canonicalize(Path::new("/sys/class/block").join(name)) else {
+        return false;
+    };
+    if sys_path.to_string_lossy().contains("/usb") {
+        return true;
+    }
+    // Partitions carry no flag of their own; the parent disk does.
+    let removable = [Some(sys_path.as_path()), sys_path.parent()]
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| fs::read_to_string(dir.join("removable")).ok())
+        .any(|flag| flag.trim() == "1");
+    removable
+}
+
+/// The mount that holds `path`, resolved through the nearest existing ancestor so a
+/// destination folder that has not been created yet still maps to its drive.
+pub fn mount_for(path: &Path) -> Option<MountEntry> {
+    let existing = path.ancestors().find(|candidate| candidate.exists())?;
+    let resolved = fs::canonicalize(existing).ok()?;
+    mounts()
+        .into_iter()
+        .filter(|entry| resolved.starts_with(&entry.mount_point))
+        // Later entries shadow earlier ones on the same mount point.
+        .fold(None, |best: Option<MountEntry>, entry| match best {
+            Some(current)
+                if current.mount_point.as_os_str().len() > entry.mount_point.as_os_str().len() =>
+            {
+                Some(current)
+            }
+            _ => Some(entry),
+        })
+}
+
+/// Mount point of the removable drive holding `path`, if it lives on one.
+pub fn removable_mount(path: &Path) -> Option<PathBuf> {
+    mount_for(path)
+        .filter(MountEntry::is_removable)
+        .map(|entry| entry.mount_point)
+}
+
+pub fn is_mounted(mount_point: &Path) -> bool {
+    mounts()
+        .iter()
+        .any(|entry| entry.mount_point == mount_point)
+}
+
+/// Flushes a finished file and the directory entry that names it.
+pub fn sync_file_and_parent(path: &Path) -> io::Result<()> {
+    fs::File::open(path)?.sync_all()?;
+    if let Some(parent) = path.parent() {
+        fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Writes back everything still cached for the filesystem mounted at `mount_point`.
+pub fn sync_filesystem(mount_point: &Path) -> io::Result<()> {
+    let dir = fs::File::open(mount_point)?;
+    // SAFETY: the descriptor is valid for the lifetime of `dir`.
+    if unsafe { libc::syncfs(dir.as_raw_fd()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
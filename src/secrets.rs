@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Context, Result};
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+
+const KDF_ARGON2ID: &str = "argon2id";
+const CIPHER_XCHACHA20: &str = "xchacha20poly1305";
+const SALT_LEN: usize = 16;
+
+/// Secret settings values kept out of `settings.json` while encryption is enabled.
+/// Hook commands are included because they are where webhook URLs, which carry their
+/// secret in the path, end up.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SecretValues {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub civitai_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_agent_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_download_hook: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_download_hook: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_install_hook: Option<String>,
+}
+
+impl SecretValues {
+    pub fn is_empty(&self) -> bool {
+        self.civitai_token.is_none()
+            && self.remote_agent_token.is_none()
+            && self.pre_download_hook.is_none()
+            && self.post_download_hook.is_none()
+            && self.post_install_hook.is_none()
+    }
+}
+
+/// Passphrase-sealed secrets as stored on disk. Binary fields are hex encoded.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EncryptedSecrets {
+    pub kdf: String,
+    pub cipher: String,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Key derived from the user's passphrase, held in memory while settings are unlocked.
+#[derive(Clone)]
+pub struct SecretsKey {
+    key: [u8; 32],
+    salt: Vec<u8>,
+}
+
+impl std::fmt::Debug for SecretsKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretsKey(..)")
+    }
+}
+
+impl SecretsKey {
+    /// Derives a key with a fresh random salt, for first-time encryption.
+    pub fn generate(passphrase: &str) -> Result<Self> {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, salt)
+    }
+
+    /// Derives the key matching an existing encrypted blob.
+    pub fn for_blob(passphrase: &str, blob: &EncryptedSecrets) -> Result<Self> {
+        if blob.kdf != KDF_ARGON2ID || blob.cipher != CIPHER_XCHACHA20 {
+            return Err(anyhow!(
+                "unsupported settings encryption ({} / {})",
+                blob.kdf,
+                blob.cipher
+            ));
+        }
+        let salt = decode_hex(&blob.salt).context("invalid salt in encrypted settings")?;
+        Self::derive(passphrase, salt)
+    }
+
+    fn derive(passphrase: &str, salt: Vec<u8>) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(anyhow!("passphrase must not be empty"));
+        }
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|err| anyhow!("failed to derive settings key: {err}"))?;
+        Ok(Self { key, salt })
+    }
+
+    pub fn seal(&self, values: &SecretValues) -> Result<EncryptedSecrets> {
+        let cipher = XChaCha20Poly1305::new((&self.key).into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plaintext = serde_json::to_vec(values)?;
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| anyhow!("failed to encrypt settings secrets"))?;
+        Ok(EncryptedSecrets {
+            kdf: KDF_ARGON2ID.to_string(),
+            cipher: CIPHER_XCHACHA20.to_string(),
+            salt: encode_hex(&self.salt),
+            nonce: encode_hex(&nonce),
+            ciphertext: encode_hex(&ciphertext),
+        })
+    }
+
+    pub fn open(&self, blob: &EncryptedSecrets) -> Result<SecretValues> {
+        let nonce = decode_hex(&blob.nonce).context("invalid nonce in encrypted settings")?;
+        if nonce.len() != 24 {
+            return Err(anyhow!("invalid nonce length in encrypted settings"));
+        }
+        let ciphertext =
+            decode_hex(&blob.ciphertext).context("invalid ciphertext in encrypted settings")?;
+        let cipher = XChaCha20Poly1305::new((&self.key).into());
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow!("incorrect passphrase or corrupted settings"))?;
+        serde_json::from_slice(&plaintext).context("failed to parse decrypted settings secrets")
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    let value = value.trim();
+    if !value.is_ascii() {
+        return Err(anyhow!("non-ASCII character in hex string"));
+    }
+    if value.len() % 2 != 0 {
+        return Err(anyhow!("odd-length hex string"));
+    }
+    value
+        .as_bytes()
+        .chunks(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| anyhow!("invalid hex at offset {}", index * 2))
+        })
+        .collect()
+}
@@ -297,7 +297,7 @@ async fn fetch_manifest(client: &Client, url: &str) -> Result<UpdateManifest> {
 fn installer_file_name(url: &str) -> Option<String> {
     reqwest::Url::parse(url)
         .ok()
-        .and_then(|parsed| parsed.path_segments()?.last().map(str::to_string))
+        .and_then(|parsed| parsed.path_segments()?.next_back().map(str::to_string))
         .filter(|name| !name.trim().is_empty())
 }
 
@@ -462,6 +462,8 @@ fn detect_linux_distro_family() -> String {
     } else if haystack.contains("fedora") || haystack.contains("rhel") || haystack.contains("centos")
     {
         "fedora".to_string()
+    } else if haystack.contains("suse") {
+        "suse".to_string()
     } else {
         "unknown".to_string()
     }
@@ -496,7 +498,7 @@ fn select_linux_release_asset(manifest: &LinuxReleaseManifest) -> Option<&LinuxR
             .iter()
             .find(|asset| asset.name.to_ascii_lowercase().ends_with(".deb"))
             .copied(),
-        "fedora" => candidates
+        "fedora" | "suse" => candidates
             .iter()
             .find(|asset| asset.name.to_ascii_lowercase().ends_with(".rpm"))
             .copied(),
@@ -97,6 +97,14 @@ impl VramTier {
         }
     }
 
+    pub fn from_vram_gb(vram_gb: f64) -> Self {
+        VramTier::all()
+            .iter()
+            .copied()
+            .find(|tier| vram_gb >= tier.min_vram_gb())
+            .unwrap_or(VramTier::TierC)
+    }
+
     pub fn next_stronger(self) -> Option<VramTier> {
         match self {
             VramTier::TierS => None,